@@ -0,0 +1,27 @@
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use katana_cli::file::NodeArgsConfig;
+
+#[derive(Args)]
+pub struct ConfigArgs {
+    #[command(subcommand)]
+    commands: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    #[command(about = "Prints a configuration file with the default value of every option")]
+    PrintDefaults,
+}
+
+impl ConfigArgs {
+    pub(crate) fn execute(self) -> Result<()> {
+        match self.commands {
+            Commands::PrintDefaults => {
+                print!("{}", NodeArgsConfig::defaults().to_toml()?);
+            }
+        }
+
+        Ok(())
+    }
+}
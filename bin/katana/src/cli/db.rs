@@ -6,8 +6,11 @@ use comfy_table::modifiers::UTF8_ROUND_CORNERS;
 use comfy_table::presets::UTF8_FULL;
 use comfy_table::Table;
 use katana_db::abstraction::Database;
+use katana_db::backup::{backup_db, restore_db};
 use katana_db::mdbx::{DbEnv, DbEnvKind};
+use katana_db::migration::migrate_db;
 use katana_db::tables::NUM_TABLES;
+use katana_db::version::CURRENT_DB_VERSION;
 
 /// Create a human-readable byte unit string (eg. 16.00 KiB)
 macro_rules! byte_unit {
@@ -36,6 +39,25 @@ pub struct DbArgs {
 enum Commands {
     #[command(about = "Retrieves database statistics")]
     Stats,
+
+    #[command(about = "Migrates a database created by an older release to the current version")]
+    Migrate,
+
+    #[command(about = "Backs up the database, which can be in use by a running node")]
+    Backup {
+        #[arg(help = "Path to the backup directory")]
+        output: String,
+
+        #[arg(long)]
+        #[arg(help = "Update an existing backup with the changes made since it was taken")]
+        incremental: bool,
+    },
+
+    #[command(about = "Restores a backup to the database, which must not be in use")]
+    Restore {
+        #[arg(help = "Path to the backup directory")]
+        backup: String,
+    },
 }
 
 impl DbArgs {
@@ -121,6 +143,46 @@ impl DbArgs {
 
                 println!("{table}");
             }
+
+            Commands::Migrate => {
+                let path = path::absolute(shellexpand::full(&self.path)?.into_owned())?;
+                let version = migrate_db(&path)
+                    .with_context(|| format!("Migrating database at path {}", path.display()))?;
+
+                if version == CURRENT_DB_VERSION {
+                    println!("Database is already at the current version {CURRENT_DB_VERSION}.");
+                } else {
+                    println!("Migrated database from version {version} to {CURRENT_DB_VERSION}.");
+                }
+            }
+
+            Commands::Backup { output, incremental } => {
+                let path = path::absolute(shellexpand::full(&self.path)?.into_owned())?;
+                let output = path::absolute(shellexpand::full(&output)?.into_owned())?;
+                let stats = backup_db(&path, &output, incremental)
+                    .with_context(|| format!("Backing up database to path {}", output.display()))?;
+
+                println!(
+                    "Backed up database to {}: {} entries written, {} entries removed.",
+                    output.display(),
+                    stats.written,
+                    stats.deleted
+                );
+            }
+
+            Commands::Restore { backup } => {
+                let path = path::absolute(shellexpand::full(&self.path)?.into_owned())?;
+                let backup = path::absolute(shellexpand::full(&backup)?.into_owned())?;
+                let stats = restore_db(&backup, &path)
+                    .with_context(|| format!("Restoring backup at path {}", backup.display()))?;
+
+                println!(
+                    "Restored database from {}: {} entries written, {} entries removed.",
+                    backup.display(),
+                    stats.written,
+                    stats.deleted
+                );
+            }
         }
 
         Ok(())
@@ -139,7 +201,7 @@ fn open_db_ro(path: &str) -> Result<DbEnv> {
 }
 
 /// Create a table with the default UTF-8 full border and rounded corners.
-fn table() -> Table {
+pub(crate) fn table() -> Table {
     let mut table = Table::new();
     table.load_preset(UTF8_FULL).apply_modifier(UTF8_ROUND_CORNERS);
     table
@@ -0,0 +1,410 @@
+use std::fmt::Display;
+use std::fs;
+use std::io::{self, BufRead, IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{bail, ensure, Context, Result};
+use clap::{Args, Parser, ValueEnum};
+use katana_cli::file::NodeArgsConfig;
+use katana_cli::{
+    DevOptions, EnvironmentOptions, GasPriceOracleOptions, NodeArgs, StarknetOptions,
+};
+use katana_core::constants::{
+    DEFAULT_ETH_L1_GAS_PRICE, DEFAULT_SEQUENCER_ADDRESS, DEFAULT_STRK_L1_GAS_PRICE,
+};
+use katana_core::service::messaging::MessagingConfig;
+use katana_primitives::block::GasPrices;
+use katana_primitives::chain::ChainId;
+use katana_primitives::genesis::json::GenesisJson;
+use katana_primitives::Felt;
+use rand::Rng;
+use url::Url;
+
+/// The chain ID prompted by default, as a Cairo short string.
+const DEFAULT_CHAIN_ID: &str = "KATANA";
+
+const GENESIS_FILE: &str = "genesis.json";
+const CONFIG_FILE: &str = "config.toml";
+
+/// The number of accounts prefunded in the genesis block by default.
+const DEFAULT_ACCOUNTS: u16 = 10;
+/// The interval, in seconds, at which messages are exchanged with the settlement layer by default.
+const DEFAULT_MESSAGING_INTERVAL: u64 = 5;
+
+#[derive(Debug, Args)]
+pub struct InitArgs {
+    #[arg(long = "output-dir", value_name = "DIR")]
+    #[arg(help = "Directory the chain files are written to.")]
+    #[arg(default_value = ".")]
+    output_dir: PathBuf,
+
+    #[arg(long = "chain-id", value_name = "ID")]
+    #[arg(help = "The chain ID, as a hex string (`0x` prefix) or a Cairo short string.")]
+    #[arg(value_parser = ChainId::parse)]
+    chain_id: Option<ChainId>,
+
+    #[arg(long = "fee-token", value_name = "TOKEN")]
+    #[arg(help = "The token the L1 gas price is set for, the other one keeps its default price.")]
+    fee_token: Option<FeeToken>,
+
+    #[arg(long = "gas-price", value_name = "PRICE")]
+    #[arg(help = "The L1 gas price in the fee token, in wei for ETH or in fri for STRK.")]
+    gas_price: Option<u128>,
+
+    #[arg(long, value_name = "NUM")]
+    #[arg(help = "Number of accounts prefunded in the genesis block.")]
+    accounts: Option<u16>,
+
+    #[arg(long, value_name = "LAYER")]
+    #[arg(help = "The layer the chain exchanges messages with.")]
+    settlement: Option<SettlementLayer>,
+
+    #[arg(long = "settlement.rpc-url", value_name = "URL")]
+    #[arg(help = "The RPC URL of the settlement layer.")]
+    settlement_rpc_url: Option<Url>,
+
+    #[arg(long = "settlement.contract", value_name = "ADDRESS")]
+    #[arg(help = "The address of the messaging contract on the settlement layer.")]
+    settlement_contract: Option<String>,
+
+    #[arg(long = "settlement.sender", value_name = "ADDRESS")]
+    #[arg(help = "The account settling the messages on the settlement layer.")]
+    settlement_sender: Option<String>,
+
+    #[arg(long = "settlement.private-key", value_name = "KEY")]
+    #[arg(help = "The private key of the settlement account.")]
+    settlement_private_key: Option<String>,
+
+    #[arg(short, long)]
+    #[arg(help = "Don't prompt for the values that aren't given, using their defaults.")]
+    yes: bool,
+
+    #[arg(long)]
+    #[arg(help = "Start a node from the generated files.")]
+    start: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum FeeToken {
+    Eth,
+    Strk,
+}
+
+impl FromStr for FeeToken {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        <Self as ValueEnum>::from_str(s, true)
+    }
+}
+
+impl Display for FeeToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FeeToken::Eth => f.write_str("eth"),
+            FeeToken::Strk => f.write_str("strk"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SettlementLayer {
+    None,
+    Ethereum,
+    Starknet,
+}
+
+impl FromStr for SettlementLayer {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        <Self as ValueEnum>::from_str(s, true)
+    }
+}
+
+impl Display for SettlementLayer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SettlementLayer::None => f.write_str("none"),
+            SettlementLayer::Ethereum => f.write_str("ethereum"),
+            SettlementLayer::Starknet => f.write_str("starknet"),
+        }
+    }
+}
+
+impl InitArgs {
+    pub(crate) fn execute(mut self) -> Result<()> {
+        let prompter = Prompter { interactive: !self.yes && io::stdin().is_terminal() };
+
+        let chain_id = match self.chain_id {
+            Some(id) => id,
+            None => {
+                let name = prompter.ask("Chain ID", DEFAULT_CHAIN_ID.to_string(), |s| {
+                    ChainId::parse(s).map(|_| s.to_string()).map_err(|e| e.to_string())
+                })?;
+                ChainId::parse(&name)?
+            }
+        };
+        let fee_token = prompter.value("Fee token (eth, strk)", self.fee_token, FeeToken::Strk)?;
+        let default_price = match fee_token {
+            FeeToken::Eth => DEFAULT_ETH_L1_GAS_PRICE,
+            FeeToken::Strk => DEFAULT_STRK_L1_GAS_PRICE,
+        };
+        let gas_price = prompter.value("L1 gas price", self.gas_price, default_price)?;
+        let accounts = prompter.value("Prefunded accounts", self.accounts, DEFAULT_ACCOUNTS)?;
+
+        let settlement = prompter.value(
+            "Settlement layer (none, ethereum, starknet)",
+            self.settlement,
+            SettlementLayer::None,
+        )?;
+        let messaging = match settlement {
+            SettlementLayer::None => None,
+            layer => Some(self.messaging_config(layer, &prompter)?),
+        };
+
+        let (genesis_path, config_path) = write_chain_files(
+            &self.output_dir,
+            chain_id,
+            fee_token,
+            gas_price,
+            accounts,
+            messaging,
+        )?;
+
+        // the files are checked the same way as when a node is started from them
+        let args = node_args(&genesis_path, &config_path)?;
+        if let Err(error) = args.clone().with_config_file().and_then(|args| args.config()) {
+            fs::remove_file(&genesis_path)?;
+            fs::remove_file(&config_path)?;
+            return Err(error.context("Invalid chain configuration"));
+        }
+
+        println!("Chain files written to {}.", self.output_dir.display());
+        println!(
+            "Start the chain with `katana --config {} --genesis {}`.",
+            config_path.display(),
+            genesis_path.display()
+        );
+
+        if self.start {
+            args.execute()?;
+        }
+
+        Ok(())
+    }
+
+    fn messaging_config(
+        &mut self,
+        layer: SettlementLayer,
+        prompter: &Prompter,
+    ) -> Result<MessagingConfig> {
+        if layer == SettlementLayer::Starknet && !cfg!(feature = "starknet-messaging") {
+            bail!("Messaging with Starknet requires katana to be built with `starknet-messaging`");
+        }
+
+        let default_url = match layer {
+            SettlementLayer::Starknet => "http://localhost:5050",
+            _ => "http://localhost:8545",
+        };
+        let rpc_url = match self.settlement_rpc_url.take() {
+            Some(url) => url,
+            None => prompter.ask("Settlement RPC URL", Url::parse(default_url)?, |s| {
+                Url::parse(s).map_err(|e| e.to_string())
+            })?,
+        };
+
+        let contract_address =
+            prompter.required("Messaging contract address", self.settlement_contract.take())?;
+        let sender_address =
+            prompter.required("Settlement account", self.settlement_sender.take())?;
+        let private_key =
+            prompter.required("Settlement private key", self.settlement_private_key.take())?;
+
+        for (name, value) in [
+            ("messaging contract address", &contract_address),
+            ("settlement account", &sender_address),
+            ("settlement private key", &private_key),
+        ] {
+            Felt::from_hex(value).with_context(|| format!("Invalid {name} `{value}`"))?;
+        }
+
+        Ok(MessagingConfig {
+            chain: layer.to_string(),
+            rpc_url: rpc_url.to_string(),
+            contract_address,
+            sender_address,
+            private_key,
+            interval: DEFAULT_MESSAGING_INTERVAL,
+            from_block: 0,
+        })
+    }
+}
+
+/// Writes the genesis and the node configuration of the chain to `dir`, returning their paths.
+fn write_chain_files(
+    dir: &Path,
+    chain_id: ChainId,
+    fee_token: FeeToken,
+    gas_price: u128,
+    accounts: u16,
+    messaging: Option<MessagingConfig>,
+) -> Result<(PathBuf, PathBuf)> {
+    let genesis_path = dir.join(GENESIS_FILE);
+    let config_path = dir.join(CONFIG_FILE);
+
+    for path in [&genesis_path, &config_path] {
+        ensure!(!path.exists(), "File {} already exists", path.display());
+    }
+
+    let mut gas_prices =
+        GasPrices { eth: DEFAULT_ETH_L1_GAS_PRICE, strk: DEFAULT_STRK_L1_GAS_PRICE };
+    let mut gpo = GasPriceOracleOptions::default();
+    match fee_token {
+        FeeToken::Eth => {
+            gas_prices.eth = gas_price;
+            gpo.l1_eth_gas_price = gas_price;
+        }
+        FeeToken::Strk => {
+            gas_prices.strk = gas_price;
+            gpo.l1_strk_gas_price = gas_price;
+        }
+    }
+
+    let genesis = GenesisJson {
+        timestamp: SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs(),
+        sequencer_address: *DEFAULT_SEQUENCER_ADDRESS,
+        gas_prices,
+        ..Default::default()
+    };
+
+    // the accounts are derived from a random seed, so that every chain has its own keys
+    let development = DevOptions {
+        seed: format!("{:016x}", rand::thread_rng().gen::<u64>()),
+        total_accounts: accounts,
+        ..Default::default()
+    };
+    let config = NodeArgsConfig {
+        starknet: Some(StarknetOptions {
+            environment: EnvironmentOptions { chain_id: Some(chain_id), ..Default::default() },
+            ..Default::default()
+        }),
+        gpo: Some(gpo),
+        development: Some(development),
+        messaging,
+        ..Default::default()
+    };
+
+    fs::create_dir_all(dir)?;
+    fs::write(&genesis_path, serde_json::to_string_pretty(&genesis)?)?;
+    fs::write(&config_path, config.to_toml()?)?;
+
+    Ok((genesis_path, config_path))
+}
+
+fn node_args(genesis: &Path, config: &Path) -> Result<NodeArgs> {
+    let genesis = genesis.to_str().context("Non UTF-8 genesis path")?;
+    let config = config.to_str().context("Non UTF-8 config path")?;
+    Ok(NodeArgs::try_parse_from(["katana", "--config", config, "--genesis", genesis])?)
+}
+
+/// Asks the values that weren't given on the command line, if the input is a terminal.
+struct Prompter {
+    interactive: bool,
+}
+
+impl Prompter {
+    /// Returns the given value, or asks for it, falling back to `default`.
+    fn value<T>(&self, question: &str, value: Option<T>, default: T) -> Result<T>
+    where
+        T: FromStr + Display,
+        T::Err: Display,
+    {
+        match value {
+            Some(value) => Ok(value),
+            None => self.ask(question, default, |s| s.parse().map_err(|e: T::Err| e.to_string())),
+        }
+    }
+
+    /// Returns the given value, or asks for it. There is no default, so the value can't be left
+    /// out in non-interactive mode.
+    fn required(&self, question: &str, value: Option<String>) -> Result<String> {
+        if let Some(value) = value {
+            return Ok(value);
+        }
+
+        ensure!(self.interactive, "Missing value for `{question}`");
+
+        loop {
+            let answer = read_answer(&format!("{question}: "))?;
+            if !answer.is_empty() {
+                return Ok(answer);
+            }
+        }
+    }
+
+    /// Asks for a value until a valid one is entered. An empty answer selects the `default`,
+    /// which is also used without asking in non-interactive mode.
+    fn ask<T: Display>(
+        &self,
+        question: &str,
+        default: T,
+        parse: impl Fn(&str) -> Result<T, String>,
+    ) -> Result<T> {
+        if !self.interactive {
+            return Ok(default);
+        }
+
+        loop {
+            let answer = read_answer(&format!("{question} [{default}]: "))?;
+            if answer.is_empty() {
+                return Ok(default);
+            }
+
+            match parse(&answer) {
+                Ok(value) => return Ok(value),
+                Err(error) => eprintln!("Invalid value: {error}"),
+            }
+        }
+    }
+}
+
+fn read_answer(prompt: &str) -> Result<String> {
+    print!("{prompt}");
+    io::stdout().flush()?;
+
+    let mut answer = String::new();
+    if io::stdin().lock().read_line(&mut answer)? == 0 {
+        bail!("Unexpected end of input");
+    }
+
+    Ok(answer.trim().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_files_start_a_node() {
+        let dir = tempfile::tempdir().unwrap();
+        let chain_id = ChainId::parse("MY_GAME").unwrap();
+
+        let (genesis, config) =
+            write_chain_files(dir.path(), chain_id, FeeToken::Eth, 42, 3, None).unwrap();
+
+        let args = node_args(&genesis, &config).unwrap().with_config_file().unwrap();
+        let config = args.config().unwrap();
+
+        assert_eq!(config.chain.id, chain_id);
+        assert_eq!(config.chain.genesis.gas_prices.eth, 42);
+        assert_eq!(config.chain.genesis.gas_prices.strk, DEFAULT_STRK_L1_GAS_PRICE);
+        assert_eq!(config.dev.fixed_gas_prices.unwrap().gas_price.eth, 42);
+        assert!(config.messaging.is_none());
+
+        // the generated files are never overwritten
+        assert!(write_chain_files(dir.path(), chain_id, FeeToken::Eth, 42, 3, None).is_err());
+    }
+}
@@ -1,4 +1,7 @@
+mod config;
 mod db;
+mod init;
+mod stress;
 
 use anyhow::Result;
 use clap::{Args, CommandFactory, Parser, Subcommand};
@@ -21,11 +24,14 @@ impl Cli {
         if let Some(cmd) = self.commands {
             return match cmd {
                 Commands::Completions(args) => args.execute(),
+                Commands::Config(args) => args.execute(),
                 Commands::Db(args) => args.execute(),
+                Commands::Init(args) => args.execute(),
+                Commands::Stress(args) => args.execute(),
             };
         }
 
-        self.node.with_config_file()?.execute()
+        self.node.execute()
     }
 }
 
@@ -34,8 +40,17 @@ enum Commands {
     #[command(about = "Generate shell completion file for specified shell")]
     Completions(CompletionsArgs),
 
+    #[command(about = "Configuration file utilities")]
+    Config(config::ConfigArgs),
+
     #[command(about = "Database utilities")]
     Db(db::DbArgs),
+
+    #[command(about = "Generate the genesis and configuration files of a new chain")]
+    Init(init::InitArgs),
+
+    #[command(about = "Generate a transaction workload against a node and report its performance")]
+    Stress(stress::StressArgs),
 }
 
 #[derive(Debug, Args)]
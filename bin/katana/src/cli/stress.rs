@@ -0,0 +1,463 @@
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use clap::Args;
+use jsonrpsee::http_client::HttpClientBuilder;
+use katana_primitives::genesis::constant::DEFAULT_ETH_FEE_TOKEN_ADDRESS;
+use katana_rpc_api::dev::DevApiClient;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use starknet::accounts::{Account, ConnectedAccount, ExecutionEncoding, SingleOwnerAccount};
+use starknet::core::types::{Call, ExecutionResult, Felt};
+use starknet::core::utils::get_selector_from_name;
+use starknet::providers::jsonrpc::HttpTransport;
+use starknet::providers::{JsonRpcClient, Provider};
+use starknet::signers::{LocalWallet, SigningKey};
+use tokio::task::JoinHandle;
+use tokio::time::{interval, sleep, Instant, MissedTickBehavior};
+use url::Url;
+
+use super::db::table;
+
+type OwnerAccount = SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet>;
+
+/// How long to wait for a transaction to be included before considering it lost.
+const RECEIPT_TIMEOUT: Duration = Duration::from_secs(60);
+/// Interval between two receipt polls of the same transaction.
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Args)]
+pub struct StressArgs {
+    #[arg(long = "rpc-url", value_name = "URL")]
+    #[arg(help = "The URL of the Katana node to stress.")]
+    #[arg(default_value = "http://localhost:5050")]
+    rpc_url: Url,
+
+    #[arg(long, value_name = "NUM")]
+    #[arg(help = "Number of predeployed accounts sending transactions concurrently.")]
+    #[arg(default_value_t = 10)]
+    accounts: usize,
+
+    #[arg(long, value_name = "NUM")]
+    #[arg(help = "Target number of transactions sent per second, across all accounts.")]
+    #[arg(default_value_t = 10)]
+    tps: u64,
+
+    #[arg(long, value_name = "SECONDS")]
+    #[arg(help = "Duration of the workload in seconds.")]
+    #[arg(default_value_t = 30)]
+    duration: u64,
+
+    #[arg(long = "call", value_name = "CALL")]
+    #[arg(help = "A call of the workload, as <ADDRESS>:<ENTRYPOINT>[:<CALLDATA>][@<WEIGHT>], \
+                  with comma separated calldata. Can be repeated to build a weighted call mix, \
+                  e.g. of the systems of a deployed world. Defaults to fee token transfers.")]
+    calls: Vec<CallSpec>,
+
+    #[arg(long = "max-fee", value_name = "FEE")]
+    #[arg(help = "Max fee of the transactions. Fees are not estimated to not skew the latency.")]
+    #[arg(default_value = "0x2386f26fc10000")]
+    #[arg(value_parser = parse_felt)]
+    max_fee: Felt,
+
+    #[arg(long)]
+    #[arg(help = "Wait for the transactions to be included to measure the inclusion latency.")]
+    wait: bool,
+}
+
+impl StressArgs {
+    pub(crate) fn execute(self) -> Result<()> {
+        ensure!(self.accounts > 0, "At least one account is required");
+        ensure!(self.tps > 0, "Target TPS must be greater than zero");
+
+        tokio::runtime::Builder::new_multi_thread().enable_all().build()?.block_on(self.run())
+    }
+
+    async fn run(self) -> Result<()> {
+        let accounts = self.accounts().await?;
+        let mix = Arc::new(CallMix::new(if self.calls.is_empty() {
+            vec![CallSpec::fee_token_transfer()]
+        } else {
+            self.calls.clone()
+        })?);
+
+        // Every account sends its share of the target rate sequentially, so that nonces are
+        // always submitted in order.
+        let period = Duration::from_secs_f64(self.accounts as f64 / self.tps as f64);
+        let started = Instant::now();
+        let deadline = started + Duration::from_secs(self.duration);
+
+        let workers = accounts
+            .into_iter()
+            .map(|account| {
+                let mix = mix.clone();
+                tokio::spawn(worker(account, mix, period, deadline, self.max_fee, self.wait))
+            })
+            .collect::<Vec<_>>();
+
+        let mut samples = Vec::new();
+        for worker in workers {
+            samples.extend(worker.await??);
+        }
+        let elapsed = started.elapsed();
+
+        let mut outcomes = Vec::with_capacity(samples.len());
+        for sample in samples {
+            outcomes.push(sample.outcome().await?);
+        }
+
+        Report::new(&outcomes, elapsed).print(&self);
+        Ok(())
+    }
+
+    /// Connects to the first predeployed accounts of the node, which must expose the dev RPC.
+    async fn accounts(&self) -> Result<Vec<OwnerAccount>> {
+        let client = HttpClientBuilder::default().build(self.rpc_url.as_str())?;
+        let predeployed = client
+            .predeployed_accounts()
+            .await
+            .context("Fetching predeployed accounts, is the dev RPC enabled?")?;
+
+        let keys = predeployed
+            .into_iter()
+            .filter_map(|account| account.private_key.map(|key| (account.address, key)))
+            .take(self.accounts)
+            .collect::<Vec<_>>();
+
+        ensure!(
+            keys.len() == self.accounts,
+            "Requested {} accounts but the node only has {} predeployed accounts with a known \
+             private key",
+            self.accounts,
+            keys.len()
+        );
+
+        let chain_id = self.provider().chain_id().await?;
+
+        Ok(keys
+            .into_iter()
+            .map(|(address, key)| {
+                let signer = LocalWallet::from(SigningKey::from_secret_scalar(key));
+                SingleOwnerAccount::new(
+                    self.provider(),
+                    signer,
+                    address.into(),
+                    chain_id,
+                    ExecutionEncoding::New,
+                )
+            })
+            .collect())
+    }
+
+    fn provider(&self) -> JsonRpcClient<HttpTransport> {
+        JsonRpcClient::new(HttpTransport::new(self.rpc_url.clone()))
+    }
+}
+
+async fn worker(
+    account: OwnerAccount,
+    mix: Arc<CallMix>,
+    period: Duration,
+    deadline: Instant,
+    max_fee: Felt,
+    wait: bool,
+) -> Result<Vec<Sample>> {
+    let mut nonce = account.get_nonce().await?;
+    let mut rng = StdRng::from_entropy();
+    let mut samples = Vec::new();
+
+    let mut ticker = interval(period);
+    // Do not burst to catch up when the node is slower than the target rate.
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+        if Instant::now() >= deadline {
+            break;
+        }
+
+        let call = mix.sample(&mut rng).clone();
+        let sent_at = Instant::now();
+        let result = account.execute_v1(vec![call]).nonce(nonce).max_fee(max_fee).send().await;
+        let submission = sent_at.elapsed();
+
+        let sample = match result {
+            Ok(tx) => {
+                // Only accepted transactions consume their nonce.
+                nonce += Felt::ONE;
+                let inclusion = wait.then(|| {
+                    let provider = account.provider().clone();
+                    tokio::spawn(wait_for_inclusion(provider, tx.transaction_hash, sent_at))
+                });
+                Sample { submission, result: SampleResult::Accepted(inclusion) }
+            }
+            Err(e) => Sample { submission, result: SampleResult::Rejected(e.to_string()) },
+        };
+
+        samples.push(sample);
+    }
+
+    Ok(samples)
+}
+
+/// Polls the receipt of a transaction until it is included, returning the latency from its
+/// submission and the revert reason, if any.
+async fn wait_for_inclusion(
+    provider: JsonRpcClient<HttpTransport>,
+    transaction_hash: Felt,
+    sent_at: Instant,
+) -> Result<Duration, String> {
+    loop {
+        if let Ok(receipt) = provider.get_transaction_receipt(transaction_hash).await {
+            return match receipt.receipt.execution_result() {
+                ExecutionResult::Succeeded => Ok(sent_at.elapsed()),
+                ExecutionResult::Reverted { reason } => Err(format!("Reverted: {reason}")),
+            };
+        }
+
+        if sent_at.elapsed() >= RECEIPT_TIMEOUT {
+            return Err("Not included before timeout".to_string());
+        }
+
+        sleep(RECEIPT_POLL_INTERVAL).await;
+    }
+}
+
+#[derive(Debug)]
+enum SampleResult {
+    Accepted(Option<JoinHandle<Result<Duration, String>>>),
+    Rejected(String),
+}
+
+#[derive(Debug)]
+struct Sample {
+    submission: Duration,
+    result: SampleResult,
+}
+
+impl Sample {
+    async fn outcome(self) -> Result<Outcome> {
+        let (inclusion, error) = match self.result {
+            SampleResult::Accepted(None) => (None, None),
+            SampleResult::Accepted(Some(handle)) => match handle.await? {
+                Ok(latency) => (Some(latency), None),
+                Err(e) => (None, Some(e)),
+            },
+            SampleResult::Rejected(e) => (None, Some(e)),
+        };
+
+        Ok(Outcome { submission: self.submission, inclusion, error })
+    }
+}
+
+#[derive(Debug)]
+struct Outcome {
+    submission: Duration,
+    inclusion: Option<Duration>,
+    error: Option<String>,
+}
+
+#[derive(Debug)]
+struct Report {
+    sent: usize,
+    succeeded: usize,
+    elapsed: Duration,
+    submission: Vec<Duration>,
+    inclusion: Vec<Duration>,
+    errors: Vec<(String, usize)>,
+}
+
+impl Report {
+    fn new(outcomes: &[Outcome], elapsed: Duration) -> Self {
+        let mut submission = outcomes.iter().map(|o| o.submission).collect::<Vec<_>>();
+        let mut inclusion = outcomes.iter().filter_map(|o| o.inclusion).collect::<Vec<_>>();
+        submission.sort();
+        inclusion.sort();
+
+        let mut errors = HashMap::<String, usize>::new();
+        for error in outcomes.iter().filter_map(|o| o.error.as_ref()) {
+            *errors.entry(error.clone()).or_default() += 1;
+        }
+        let mut errors = errors.into_iter().collect::<Vec<_>>();
+        errors.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+        let succeeded = outcomes.iter().filter(|o| o.error.is_none()).count();
+
+        Self { sent: outcomes.len(), succeeded, elapsed, submission, inclusion, errors }
+    }
+
+    fn print(&self, args: &StressArgs) {
+        let achieved_tps = self.succeeded as f64 / self.elapsed.as_secs_f64();
+
+        let mut summary = table();
+        summary.set_header(vec!["Accounts", "Target TPS", "Duration", "Sent", "Succeeded", "TPS"]);
+        summary.add_row(vec![
+            args.accounts.to_string(),
+            args.tps.to_string(),
+            format!("{:.2?}", self.elapsed),
+            self.sent.to_string(),
+            self.succeeded.to_string(),
+            format!("{achieved_tps:.2}"),
+        ]);
+        println!("{summary}");
+
+        let mut latency = table();
+        latency.set_header(vec!["Latency", "p50", "p90", "p99", "Max"]);
+        latency.add_row(latency_row("Submission", &self.submission));
+        if args.wait {
+            latency.add_row(latency_row("Inclusion", &self.inclusion));
+        }
+        println!("{latency}");
+
+        if !self.errors.is_empty() {
+            let mut errors = table();
+            errors.set_header(vec!["Error", "Count"]);
+            errors
+                .add_rows(self.errors.iter().map(|(e, count)| vec![e.clone(), count.to_string()]));
+            println!("{errors}");
+        }
+    }
+}
+
+fn latency_row(name: &str, sorted: &[Duration]) -> Vec<String> {
+    let mut row = vec![name.to_string()];
+    row.extend([50.0, 90.0, 99.0, 100.0].into_iter().map(|p| match percentile(sorted, p) {
+        Some(latency) => format!("{latency:.2?}"),
+        None => "-".to_string(),
+    }));
+    row
+}
+
+/// Returns the nearest-rank percentile of sorted values.
+fn percentile(sorted: &[Duration], p: f64) -> Option<Duration> {
+    if sorted.is_empty() {
+        return None;
+    }
+
+    let rank = (p / 100.0 * sorted.len() as f64).ceil() as usize;
+    Some(sorted[rank.clamp(1, sorted.len()) - 1])
+}
+
+/// A call of the workload and its relative weight in the call mix.
+#[derive(Debug, Clone)]
+struct CallSpec {
+    call: Call,
+    weight: u32,
+}
+
+impl CallSpec {
+    /// Transfers 1 wei of the default fee token, which exists on any Katana instance.
+    fn fee_token_transfer() -> Self {
+        let call = Call {
+            to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+            selector: get_selector_from_name("transfer").expect("valid selector"),
+            calldata: vec![Felt::ONE, Felt::ONE, Felt::ZERO],
+        };
+        Self { call, weight: 1 }
+    }
+}
+
+impl FromStr for CallSpec {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let (call, weight) = match s.rsplit_once('@') {
+            Some((call, weight)) => {
+                (call, weight.parse::<u32>().with_context(|| format!("Invalid weight: {weight}"))?)
+            }
+            None => (s, 1),
+        };
+
+        let mut parts = call.splitn(3, ':');
+        let (Some(to), Some(entrypoint)) = (parts.next(), parts.next()) else {
+            bail!("Invalid call {s}, expected <ADDRESS>:<ENTRYPOINT>[:<CALLDATA>][@<WEIGHT>]");
+        };
+
+        let calldata = match parts.next() {
+            Some(calldata) if !calldata.is_empty() => {
+                calldata.split(',').map(parse_felt).collect::<Result<Vec<_>>>()?
+            }
+            _ => Vec::new(),
+        };
+
+        let call = Call {
+            to: parse_felt(to)?,
+            selector: get_selector_from_name(entrypoint)
+                .with_context(|| format!("Invalid entrypoint: {entrypoint}"))?,
+            calldata,
+        };
+
+        Ok(Self { call, weight })
+    }
+}
+
+/// Weighted call mix the workload calls are sampled from.
+#[derive(Debug)]
+struct CallMix {
+    calls: Vec<CallSpec>,
+    total_weight: u32,
+}
+
+impl CallMix {
+    fn new(calls: Vec<CallSpec>) -> Result<Self> {
+        let total_weight = calls.iter().map(|c| c.weight).sum::<u32>();
+        ensure!(total_weight > 0, "The call mix must have a positive total weight");
+        Ok(Self { calls, total_weight })
+    }
+
+    fn sample<R: Rng>(&self, rng: &mut R) -> &Call {
+        let mut target = rng.gen_range(0..self.total_weight);
+        for spec in &self.calls {
+            if target < spec.weight {
+                return &spec.call;
+            }
+            target -= spec.weight;
+        }
+
+        unreachable!("target is lower than the total weight")
+    }
+}
+
+fn parse_felt(s: &str) -> Result<Felt> {
+    Felt::from_str(s.trim()).map_err(|_| anyhow!("Invalid felt: {s}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_call_spec() {
+        let spec = CallSpec::from_str("0x1234:spawn:0x1,2@3").unwrap();
+        assert_eq!(spec.weight, 3);
+        assert_eq!(spec.call.to, Felt::from(0x1234));
+        assert_eq!(spec.call.selector, get_selector_from_name("spawn").unwrap());
+        assert_eq!(spec.call.calldata, vec![Felt::ONE, Felt::TWO]);
+
+        let spec = CallSpec::from_str("0x1234:move").unwrap();
+        assert_eq!(spec.weight, 1);
+        assert!(spec.call.calldata.is_empty());
+
+        assert!(CallSpec::from_str("0x1234").is_err());
+        assert!(CallSpec::from_str("0x1234:move@heavy").is_err());
+    }
+
+    #[test]
+    fn call_mix_rejects_zero_weight() {
+        let spec = CallSpec { weight: 0, ..CallSpec::fee_token_transfer() };
+        assert!(CallMix::new(vec![spec]).is_err());
+    }
+
+    #[test]
+    fn nearest_rank_percentile() {
+        let latencies = (1..=100).map(Duration::from_millis).collect::<Vec<_>>();
+        assert_eq!(percentile(&latencies, 50.0), Some(Duration::from_millis(50)));
+        assert_eq!(percentile(&latencies, 99.0), Some(Duration::from_millis(99)));
+        assert_eq!(percentile(&latencies, 100.0), Some(Duration::from_millis(100)));
+        assert_eq!(percentile(&latencies[..1], 0.0), Some(Duration::from_millis(1)));
+        assert_eq!(percentile(&[], 50.0), None);
+    }
+}
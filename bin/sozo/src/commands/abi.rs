@@ -0,0 +1,62 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use scarb::core::Config;
+use sozo_ops::abi;
+use sozo_scarbext::WorkspaceExt;
+use tracing::trace;
+
+#[derive(Debug, Args)]
+pub struct AbiArgs {
+    #[command(subcommand)]
+    command: AbiCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AbiCommand {
+    #[command(about = "Export the ABI of the world contract, built from the local artifacts, \
+                       as JSON")]
+    Export {
+        #[arg(long)]
+        #[arg(help = "Export the events of the world with their selectors, along with the \
+                      schemas of the models and events, to configure external indexers")]
+        events: bool,
+
+        #[arg(short, long)]
+        #[arg(help = "File to write the JSON document into, instead of the standard output")]
+        output: Option<PathBuf>,
+    },
+}
+
+impl AbiArgs {
+    pub fn run(self, config: &Config) -> Result<()> {
+        trace!(args = ?self);
+
+        let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
+
+        match self.command {
+            AbiCommand::Export { events, output } => {
+                let world_local = ws.load_world_local()?;
+
+                let json = if events {
+                    serde_json::to_string_pretty(&abi::world_events(&world_local)?)?
+                } else {
+                    serde_json::to_string_pretty(&world_local.class.abi)?
+                };
+
+                match output {
+                    Some(path) => {
+                        fs::write(&path, json)
+                            .with_context(|| format!("Failed to write `{}`.", path.display()))?;
+                        println!("ABI written to `{}`.", path.display());
+                    }
+                    None => println!("{json}"),
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
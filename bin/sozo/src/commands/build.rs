@@ -1,21 +1,28 @@
 use std::cmp::Reverse;
+use std::fs;
 
-use anyhow::Result;
+use anyhow::{Context, Result};
 use clap::{Args, Parser};
 use colored::{ColoredString, Colorize};
 use dojo_bindgen::{BuiltinPlugins, PluginManager};
 use dojo_world::local::{ResourceLocal, WorldLocal};
 use dojo_world::ResourceType;
-use scarb::core::{Config, Package, TargetKind};
+use scarb::core::{Config, Package, TargetKind, Workspace};
 use scarb::ops::CompileOpts;
 use scarb_ui::args::{FeaturesSpec, PackagesFilter};
+use sozo_ops::class_size::{self, ClassSize, MAX_CASM_FELTS, MAX_SIERRA_SIZE_BYTES};
+use sozo_scarbext::toolchain;
 use sozo_scarbext::WorkspaceExt;
+use starknet::core::types::contract::SierraClass;
 use tabled::settings::Style;
 use tabled::{Table, Tabled};
 use tracing::debug;
 
 use crate::commands::check_package_dojo_version;
 
+/// Name of the size report written into the target directory when a class exceeds the limits.
+const SIZE_REPORT_JSON: &str = "size_report.json";
+
 #[derive(Debug, Clone, Args)]
 pub struct BuildArgs {
     #[arg(long)]
@@ -94,18 +101,36 @@ impl BuildArgs {
             check_package_dojo_version(&ws, p)?;
         }
 
-        debug!(?packages);
+        // Packages pinned to a Cairo version other than the embedded one are compiled by their
+        // own toolchain.
+        let mut native = vec![];
+        let mut pinned = vec![];
 
-        scarb::ops::compile(
-            packages.iter().map(|p| p.id).collect(),
-            CompileOpts {
-                include_target_names: vec![],
-                include_target_kinds: vec![],
-                exclude_target_kinds: vec![TargetKind::TEST],
-                features: self.features.try_into()?,
-            },
-            &ws,
-        )?;
+        for p in packages {
+            match toolchain::pinned_toolchain(&p)? {
+                Some(t) => pinned.push((p, t)),
+                None => native.push(p),
+            }
+        }
+
+        debug!(?native, ?pinned);
+
+        if !native.is_empty() {
+            scarb::ops::compile(
+                native.iter().map(|p| p.id).collect(),
+                CompileOpts {
+                    include_target_names: vec![],
+                    include_target_kinds: vec![],
+                    exclude_target_kinds: vec![TargetKind::TEST],
+                    features: self.features.try_into()?,
+                },
+                &ws,
+            )?;
+        }
+
+        for (p, t) in &pinned {
+            toolchain::compile_with_toolchain(&ws, p, t)?;
+        }
 
         let mut builtin_plugins = vec![];
 
@@ -138,20 +163,33 @@ impl BuildArgs {
         // directly during the compilation to get the data we need from it.
         config.tokio_handle().block_on(bindgen.generate(None)).expect("Error generating bindings");
 
-        if self.stats != StatOptions::default() {
-            let world = WorldLocal::from_directory(
-                ws.target_dir_profile().to_string(),
-                ws.load_profile_config().unwrap(),
-            )?;
+        let world = WorldLocal::from_directory(
+            ws.target_dir_profile().to_string(),
+            ws.load_profile_config().unwrap(),
+        );
+
+        // The world is only required to display the statistics, the size check being a hint.
+        let world = match world {
+            Ok(world) => world,
+            Err(e) if self.stats == StatOptions::default() => {
+                debug!(error = ?e, "Skipping the class size check.");
+                return Ok(());
+            }
+            Err(e) => return Err(e),
+        };
 
-            let world_stat = world.to_stat_item();
-            let mut stats = vec![world_stat];
+        let mut classes = vec![(world.to_stat_item(), &world.class)];
 
-            for r in world.resources.values() {
-                if r.resource_type() != ResourceType::Namespace {
-                    stats.push(r.to_stat_item());
-                }
+        for r in world.resources.values() {
+            if r.resource_type() != ResourceType::Namespace {
+                classes.push((r.to_stat_item(), &r.common().class));
             }
+        }
+
+        check_class_sizes(&ws, &classes)?;
+
+        if self.stats != StatOptions::default() {
+            let mut stats = classes.into_iter().map(|(s, _)| s).collect::<Vec<_>>();
 
             if self.stats.sort_by_tag {
                 stats.sort_by_key(|s| s.tag.clone());
@@ -194,6 +232,78 @@ casm = true
     }
 }
 
+/// Reports the largest functions and dependencies of the classes exceeding the limits of the
+/// public networks, and writes them into the size report of the target directory.
+fn check_class_sizes(ws: &Workspace<'_>, classes: &[(StatItem, &SierraClass)]) -> Result<()> {
+    let mut reports = vec![];
+
+    for (stat, class) in classes {
+        if class_size::exceeds_limits(stat.sierra_file_size, stat.casm_bytecode_size) {
+            reports.push(class_size::class_size(
+                &stat.tag,
+                class,
+                stat.sierra_file_size,
+                stat.casm_bytecode_size,
+            )?);
+        }
+    }
+
+    if reports.is_empty() {
+        return Ok(());
+    }
+
+    for report in &reports {
+        print_class_size(report);
+    }
+
+    let path = ws.target_dir_profile().path_unchecked().join(SIZE_REPORT_JSON);
+    fs::write(&path, serde_json::to_string_pretty(&reports)?)
+        .with_context(|| format!("Failed to write `{path}`."))?;
+
+    println!(
+        "{}",
+        format!(
+            "Consider moving the largest systems into other contracts. The size report is \
+             written to `{path}`."
+        )
+        .bright_yellow()
+    );
+
+    Ok(())
+}
+
+fn print_class_size(report: &ClassSize) {
+    println!(
+        "{}",
+        format!(
+            "\n`{}` exceeds the class size limits: {} Sierra bytes (max {}), {} Casm felts (max \
+             {}).",
+            report.tag,
+            report.sierra_file_size,
+            MAX_SIERRA_SIZE_BYTES,
+            report.casm_felts,
+            MAX_CASM_FELTS
+        )
+        .bright_red()
+    );
+
+    println!("Largest functions, in Sierra statements:");
+    for f in &report.functions {
+        println!("  {:>5.1}% {:>8} {}", report.share(f.statements), f.statements, f.name);
+    }
+
+    println!("Largest dependencies, in Sierra statements:");
+    for d in &report.dependencies {
+        println!(
+            "  {:>5.1}% {:>8} {} ({} functions)",
+            report.share(d.statements),
+            d.statements,
+            d.name,
+            d.functions
+        );
+    }
+}
+
 impl Default for BuildArgs {
     fn default() -> Self {
         // use the clap defaults
@@ -232,9 +342,6 @@ struct StatItemPrint {
 
 impl From<&StatItem> for StatItemPrint {
     fn from(item: &StatItem) -> Self {
-        const MAX_SIERRA_SIZE_BYTES: usize = 4_089_446;
-        const MAX_CASM_FELTS: usize = 81_290;
-
         let tag = if item.tag == "world" {
             "World".to_string().bright_magenta()
         } else {
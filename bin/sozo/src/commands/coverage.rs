@@ -0,0 +1,249 @@
+//! Coverage of the Cairo tests, collected from the Sierra statements executed by each test.
+//!
+//! The tests are run a second time with the profiler of the Cairo runner, which records how many
+//! steps are spent in each Sierra statement. The statements are mapped to their source location
+//! with the code locations emitted by the test compiler, and attributed to the contract defining
+//! the function they belong to. One lcov report is written per contract.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+
+use anyhow::{Context, Result};
+use cairo_lang_compiler::db::RootDatabase;
+use cairo_lang_defs::ids::TopLevelLanguageElementId;
+use cairo_lang_filesystem::ids::CrateId;
+use cairo_lang_runner::profiling::ProfilingInfoCollectionConfig;
+use cairo_lang_runner::{SierraCasmRunner, StarknetState};
+use cairo_lang_sierra::program::StatementIdx;
+use cairo_lang_starknet::contract::find_contracts;
+use cairo_lang_test_plugin::TestCompilation;
+use camino::Utf8Path;
+
+/// A Sierra statement with its source location and the number of steps executed in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StatementCoverage {
+    /// Full path of the function the statement belongs to.
+    pub function: String,
+    pub file: String,
+    /// Line of the statement, 1-based as expected by lcov.
+    pub line: usize,
+    pub hits: usize,
+}
+
+/// Runs the tests of the compilation with the profiler, and returns the coverage of every
+/// statement of the program that has a source location.
+///
+/// Gas is disabled for the coverage run, only the executed statements are of interest here.
+pub fn collect(
+    db: &RootDatabase,
+    compiled: &TestCompilation,
+    filter: &str,
+) -> Result<Vec<StatementCoverage>> {
+    let program = &compiled.sierra_program.program;
+
+    let Some(locations) = &compiled.metadata.statements_locations else {
+        anyhow::bail!("Tests must be compiled with the statements code locations for coverage.");
+    };
+    let locations = locations.extract_statements_source_code_locations(db);
+
+    let runner = SierraCasmRunner::new(
+        program.clone(),
+        None,
+        compiled.metadata.contracts_info.clone(),
+        Some(ProfilingInfoCollectionConfig::default()),
+    )
+    .context("Failed to setup the runner for coverage.")?;
+
+    let mut hits: HashMap<usize, usize> = HashMap::new();
+
+    for (name, test) in &compiled.metadata.named_tests {
+        if test.ignored || !name.contains(filter) {
+            continue;
+        }
+
+        let function = runner.find_function(name)?;
+        let result = runner
+            .run_function_with_starknet_context(function, &[], None, StarknetState::default())
+            .with_context(|| format!("Failed to run test `{name}` for coverage."))?;
+
+        if let Some(profiling_info) = result.profiling_info {
+            for (idx, weight) in profiling_info.sierra_statement_weights.iter_sorted() {
+                *hits.entry(idx.0).or_default() += weight;
+            }
+        }
+    }
+
+    // Functions sorted by entry point, a statement belongs to the last function starting
+    // before it.
+    let mut functions = program
+        .funcs
+        .iter()
+        .map(|f| (f.entry_point.0, f.id.debug_name.clone().unwrap_or_default().to_string()))
+        .collect::<Vec<_>>();
+    functions.sort();
+
+    let mut statements = vec![];
+
+    for idx in 0..program.statements.len() {
+        let f = functions.partition_point(|(entry, _)| *entry <= idx);
+        let Some((_, function)) = f.checked_sub(1).map(|f| &functions[f]) else {
+            continue;
+        };
+
+        let Some(location) = locations
+            .statements_to_code_location_map
+            .get(&StatementIdx(idx))
+            .and_then(|l| l.first())
+        else {
+            continue;
+        };
+
+        let (file, span) = location;
+        statements.push(StatementCoverage {
+            function: function.clone(),
+            file: file.0.clone(),
+            line: span.start.line + 1,
+            hits: hits.get(&idx).copied().unwrap_or_default(),
+        });
+    }
+
+    Ok(statements)
+}
+
+/// Returns the full paths of the Starknet contracts defined in the given crates.
+pub fn contracts(db: &RootDatabase, crate_ids: &[CrateId]) -> Vec<String> {
+    find_contracts(db, crate_ids).iter().map(|c| c.submodule_id.full_path(db)).collect()
+}
+
+/// Builds the lcov report of each contract, keyed by the contract path.
+///
+/// Only the statements located in the files under `root` are reported, to exclude the
+/// dependencies of the package.
+pub fn lcov_reports(
+    statements: &[StatementCoverage],
+    contracts: &[String],
+    root: &Utf8Path,
+) -> BTreeMap<String, String> {
+    let mut reports = BTreeMap::new();
+
+    for contract in contracts {
+        let prefix = format!("{contract}::");
+
+        // file -> line -> hits, and file -> function -> (first line, hits).
+        let mut lines: BTreeMap<&str, BTreeMap<usize, usize>> = BTreeMap::new();
+        let mut functions: BTreeMap<&str, BTreeMap<&str, (usize, usize)>> = BTreeMap::new();
+
+        for s in statements.iter().filter(|s| s.function.starts_with(&prefix)) {
+            if !Utf8Path::new(&s.file).starts_with(root) {
+                continue;
+            }
+
+            *lines.entry(&s.file).or_default().entry(s.line).or_default() += s.hits;
+
+            let f = functions.entry(&s.file).or_default().entry(&s.function).or_insert((s.line, 0));
+            f.0 = f.0.min(s.line);
+            f.1 += s.hits;
+        }
+
+        if lines.is_empty() {
+            continue;
+        }
+
+        let mut report = String::new();
+
+        for (file, lines) in &lines {
+            report.push_str(&format!("TN:{contract}\nSF:{file}\n"));
+
+            let file_functions = functions.get(file).cloned().unwrap_or_default();
+            for (name, (line, _)) in &file_functions {
+                report.push_str(&format!("FN:{line},{name}\n"));
+            }
+            for (name, (_, hits)) in &file_functions {
+                report.push_str(&format!("FNDA:{hits},{name}\n"));
+            }
+            report.push_str(&format!("FNF:{}\n", file_functions.len()));
+            report.push_str(&format!(
+                "FNH:{}\n",
+                file_functions.values().filter(|(_, hits)| *hits > 0).count()
+            ));
+
+            for (line, hits) in lines {
+                report.push_str(&format!("DA:{line},{hits}\n"));
+            }
+            report.push_str(&format!("LF:{}\n", lines.len()));
+            report.push_str(&format!("LH:{}\n", lines.values().filter(|h| **h > 0).count()));
+            report.push_str("end_of_record\n");
+        }
+
+        reports.insert(contract.clone(), report);
+    }
+
+    reports
+}
+
+/// Writes the lcov report of each contract into `output_dir`, returning the written paths.
+pub fn write_lcov_reports(
+    reports: &BTreeMap<String, String>,
+    output_dir: &Utf8Path,
+) -> Result<Vec<String>> {
+    fs::create_dir_all(output_dir)
+        .with_context(|| format!("Failed to create directory `{output_dir}`."))?;
+
+    let mut paths = vec![];
+
+    for (contract, report) in reports {
+        let path = output_dir.join(format!("{}.lcov", contract.replace("::", "-")));
+        fs::write(&path, report).with_context(|| format!("Failed to write `{path}`."))?;
+        paths.push(path.to_string());
+    }
+
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn statement(function: &str, file: &str, line: usize, hits: usize) -> StatementCoverage {
+        StatementCoverage { function: function.to_string(), file: file.to_string(), line, hits }
+    }
+
+    #[test]
+    fn lcov_per_contract() {
+        let statements = vec![
+            statement("game::actions::actions::spawn", "/game/src/actions.cairo", 10, 3),
+            statement("game::actions::actions::spawn", "/game/src/actions.cairo", 11, 2),
+            statement("game::actions::actions::move", "/game/src/actions.cairo", 20, 0),
+            statement("game::actions::actions::move", "/game/src/actions.cairo", 20, 0),
+            statement("game::actions::actions::spawn", "/dojo/src/world.cairo", 5, 7),
+            statement("game::tests::test_spawn", "/game/src/tests.cairo", 3, 1),
+        ];
+
+        let reports = lcov_reports(
+            &statements,
+            &["game::actions::actions".to_string(), "game::other::other".to_string()],
+            Utf8Path::new("/game"),
+        );
+
+        assert_eq!(reports.len(), 1);
+        assert_eq!(
+            reports["game::actions::actions"],
+            concat!(
+                "TN:game::actions::actions\n",
+                "SF:/game/src/actions.cairo\n",
+                "FN:20,game::actions::actions::move\n",
+                "FN:10,game::actions::actions::spawn\n",
+                "FNDA:0,game::actions::actions::move\n",
+                "FNDA:5,game::actions::actions::spawn\n",
+                "FNF:2\n",
+                "FNH:1\n",
+                "DA:10,3\n",
+                "DA:11,2\n",
+                "DA:20,0\n",
+                "LF:3\n",
+                "LH:2\n",
+                "end_of_record\n",
+            )
+        );
+    }
+}
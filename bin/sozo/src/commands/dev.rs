@@ -11,7 +11,7 @@ use scarb_ui::args::{FeaturesSpec, PackagesFilter};
 use tracing::{error, info, trace};
 
 use super::build::BuildArgs;
-use super::migrate::MigrateArgs;
+use super::migrate::{MigrateArgs, DEFAULT_FINALITY_TIMEOUT};
 use super::options::account::AccountOptions;
 use super::options::starknet::StarknetOptions;
 use super::options::transaction::TransactionOptions;
@@ -87,6 +87,10 @@ impl DevArgs {
             starknet: self.starknet,
             account: self.account,
             transaction: self.transaction,
+            watch_finality: false,
+            finality_timeout: DEFAULT_FINALITY_TIMEOUT,
+            print_addresses: false,
+            command: None,
         };
 
         let _ = migrate_args.clone().run(config);
@@ -0,0 +1,116 @@
+use std::process;
+
+use anyhow::{anyhow, Result};
+use clap::{Args, ValueEnum};
+use colored::Colorize;
+use scarb::core::Config;
+use sozo_ops::diff::{self, Compatibility, DiffReport};
+use sozo_scarbext::WorkspaceExt;
+use tracing::trace;
+
+use super::options::starknet::StarknetOptions;
+use super::options::world::WorldOptions;
+use crate::utils;
+
+/// Exit code when the local world has changes which can be migrated without breaking the
+/// deployed world.
+const EXIT_UPGRADABLE: i32 = 2;
+/// Exit code when the local world has changes breaking the deployed world.
+const EXIT_BREAKING: i32 = 3;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum DiffTarget {
+    /// The world deployed on the chain.
+    Rpc,
+    /// The world recorded in the manifest of the last migration.
+    Manifest,
+}
+
+#[derive(Debug, Args)]
+#[command(long_about = "Compare the built world with the deployed world. Exits with 0 when \
+                        nothing changes, 2 when the changes can be migrated without breaking \
+                        the deployed world, and 3 when they break it, e.g. a model member \
+                        being removed or an entrypoint changing its signature.")]
+pub struct DiffArgs {
+    #[arg(long, value_enum, default_value_t = DiffTarget::Rpc)]
+    #[arg(help = "What the built world is compared against.")]
+    pub against: DiffTarget,
+
+    #[command(flatten)]
+    pub world: WorldOptions,
+
+    #[command(flatten)]
+    pub starknet: StarknetOptions,
+}
+
+impl DiffArgs {
+    pub fn run(self, config: &Config) -> Result<()> {
+        trace!(args = ?self);
+
+        let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
+        let DiffArgs { against, world, starknet } = self;
+
+        let report = match against {
+            DiffTarget::Rpc => config.tokio_handle().block_on(async {
+                let (world_diff, provider, _) =
+                    utils::get_world_diff_and_provider(starknet, world, &ws).await?;
+                diff::diff_against_chain(&world_diff, &provider).await
+            })?,
+            DiffTarget::Manifest => {
+                let world_local = ws.load_world_local()?;
+                let manifest = ws.read_manifest_profile()?.ok_or_else(|| {
+                    anyhow!(
+                        "No manifest found for the current profile, the world must be migrated \
+                         first."
+                    )
+                })?;
+                diff::diff_against_manifest(&world_local, &manifest)
+            }
+        };
+
+        print_report(&report);
+
+        match report.compatibility() {
+            Compatibility::Unchanged => Ok(()),
+            Compatibility::Upgradable => process::exit(EXIT_UPGRADABLE),
+            Compatibility::Breaking => process::exit(EXIT_BREAKING),
+        }
+    }
+}
+
+fn print_report(report: &DiffReport) {
+    let world = Compatibility::from(report.world.compatibility());
+    if world != Compatibility::Unchanged {
+        println!("{} world", label(world));
+        println!("    {}", report.world.migration_path());
+    }
+
+    for resource in &report.resources {
+        if resource.compatibility == Compatibility::Unchanged {
+            continue;
+        }
+
+        println!("{} {}", label(resource.compatibility), resource.tag);
+        for reason in &resource.reasons {
+            println!("    {reason}");
+        }
+    }
+
+    match report.compatibility() {
+        Compatibility::Unchanged => println!("{}", "The world is up to date.".green()),
+        Compatibility::Upgradable => {
+            println!("{}", "The changes can be migrated without breaking the world.".blue())
+        }
+        Compatibility::Breaking => {
+            println!("{}", "The changes break the deployed world.".yellow())
+        }
+    }
+}
+
+fn label(compatibility: Compatibility) -> String {
+    match compatibility {
+        Compatibility::Unchanged => "unchanged ".green().to_string(),
+        Compatibility::Upgradable => "upgradable".blue().to_string(),
+        Compatibility::Breaking => "breaking  ".yellow().to_string(),
+    }
+}
@@ -0,0 +1,363 @@
+use anyhow::{anyhow, Result};
+use clap::Args;
+use colored::Colorize;
+use dojo_world::config::Environment;
+use katana_rpc_api::starknet::RPC_SPEC_VERSION;
+use scarb::core::{Config, Workspace};
+use sozo_ops::diff::{self, Compatibility};
+use sozo_scarbext::WorkspaceExt;
+use starknet::core::types::{BlockId, BlockTag, StarknetError};
+use starknet::core::utils as snutils;
+use starknet::providers::jsonrpc::HttpTransport;
+use starknet::providers::{JsonRpcClient, Provider, ProviderError};
+use tracing::trace;
+
+use super::check_package_dojo_version;
+use super::options::account::AccountOptions;
+use super::options::starknet::StarknetOptions;
+use super::options::world::WorldOptions;
+use crate::utils;
+
+#[derive(Debug, Args)]
+#[command(long_about = "Diagnose the common problems of the environment: incompatible versions \
+                        of the toolchain, an unreachable chain, an account that isn't deployed \
+                        or a manifest out of sync with the chain. Each problem found is printed \
+                        with a way to fix it.")]
+pub struct DoctorArgs {
+    #[command(flatten)]
+    world: WorldOptions,
+
+    #[command(flatten)]
+    starknet: StarknetOptions,
+
+    #[command(flatten)]
+    account: AccountOptions,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Status {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    message: String,
+    /// How to fix the problem, if any.
+    fix: Option<String>,
+}
+
+impl Check {
+    fn ok(name: &'static str, message: impl Into<String>) -> Self {
+        Self { name, status: Status::Ok, message: message.into(), fix: None }
+    }
+
+    fn warning(name: &'static str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { name, status: Status::Warning, message: message.into(), fix: Some(fix.into()) }
+    }
+
+    fn error(name: &'static str, message: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self { name, status: Status::Error, message: message.into(), fix: Some(fix.into()) }
+    }
+
+    fn print(&self) {
+        let label = match self.status {
+            Status::Ok => "ok     ".green(),
+            Status::Warning => "warning".yellow(),
+            Status::Error => "error  ".red(),
+        };
+
+        println!("{label} {}: {}", self.name, self.message);
+        if let Some(fix) = &self.fix {
+            println!("        fix: {fix}");
+        }
+    }
+}
+
+impl DoctorArgs {
+    pub fn run(self, config: &Config) -> Result<()> {
+        trace!(args = ?self);
+
+        let mut checks = vec![Check::ok(
+            "versions",
+            format!(
+                "sozo {}, scarb {}, cairo {}",
+                env!("CARGO_PKG_VERSION"),
+                scarb::version::get().version,
+                scarb::version::get().cairo.version
+            ),
+        )];
+
+        let manifest_path = config.manifest_path().to_path_buf();
+        checks.push(match utils::verify_cairo_version_compatibility(&manifest_path) {
+            Ok(()) => Check::ok("cairo", "the Cairo version of the package is supported"),
+            Err(e) => Check::error(
+                "cairo",
+                e.to_string(),
+                "Change the `cairo-version` of the package in Scarb.toml to the Cairo version \
+                 above.",
+            ),
+        });
+
+        let ws = match scarb::ops::read_workspace(config.manifest_path(), config) {
+            Ok(ws) => ws,
+            Err(e) => {
+                checks.push(Check::error(
+                    "workspace",
+                    format!("{e:#}"),
+                    "Run sozo from a Dojo project, or pass its Scarb.toml with --manifest-path.",
+                ));
+                return report(&checks);
+            }
+        };
+
+        checks.extend(check_dojo_dependency(&ws));
+
+        let env = ws.load_profile_config().ok().and_then(|c| c.env);
+        let env = env.as_ref();
+
+        let provider = match self.starknet.provider(env) {
+            Ok((provider, url)) => {
+                let (check, chain_id) =
+                    config.tokio_handle().block_on(check_chain(&provider, &url));
+                checks.push(check);
+                chain_id.map(|chain_id| (provider, chain_id))
+            }
+            Err(e) => {
+                checks.push(Check::error(
+                    "chain",
+                    e.to_string(),
+                    "Set `rpc_url` in the [env] of the profile config, or pass --rpc-url.",
+                ));
+                None
+            }
+        };
+
+        if let Some((provider, chain_id)) = &provider {
+            let check = config.tokio_handle().block_on(check_account(
+                &self.account,
+                env,
+                provider,
+                chain_id,
+            ));
+            checks.push(check);
+        }
+
+        let provider = provider.as_ref().map(|(provider, _)| provider);
+        let manifest = check_manifest(&ws, &self.world, env, provider);
+        checks.extend(config.tokio_handle().block_on(manifest));
+
+        report(&checks)
+    }
+}
+
+/// Prints the checks, failing if any of them is an error.
+fn report(checks: &[Check]) -> Result<()> {
+    checks.iter().for_each(Check::print);
+
+    let errors = checks.iter().filter(|c| c.status == Status::Error).count();
+    let warnings = checks.iter().filter(|c| c.status == Status::Warning).count();
+
+    println!();
+    if errors > 0 {
+        return Err(anyhow!("Found {errors} error(s) and {warnings} warning(s)."));
+    }
+
+    if warnings > 0 {
+        println!("{}", format!("No errors found, {warnings} warning(s).").yellow());
+    } else {
+        println!("{}", "No problems found.".green());
+    }
+
+    Ok(())
+}
+
+fn check_dojo_dependency(ws: &Workspace<'_>) -> Vec<Check> {
+    let errors = ws
+        .members()
+        .filter_map(|package| check_package_dojo_version(ws, &package).err())
+        .map(|e| {
+            Check::error(
+                "dojo",
+                e.to_string(),
+                format!(
+                    "Use the tag v{} of the dojo dependency, or install the sozo release matching \
+                     the tag.",
+                    env!("CARGO_PKG_VERSION")
+                ),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    if errors.is_empty() {
+        vec![Check::ok("dojo", "the dojo dependency matches the sozo version")]
+    } else {
+        errors
+    }
+}
+
+/// Checks that the chain is reachable and serves a supported RPC version, returning its chain id.
+async fn check_chain(
+    provider: &JsonRpcClient<HttpTransport>,
+    url: &str,
+) -> (Check, Option<String>) {
+    let spec_version = match provider.spec_version().await {
+        Ok(version) => version,
+        Err(e) => {
+            let check = Check::error(
+                "chain",
+                format!("{url} is unreachable: {e}"),
+                "Start katana, or point sozo to a running node with `rpc_url` in the [env] of \
+                 the profile config or --rpc-url.",
+            );
+            return (check, None);
+        }
+    };
+
+    if !utils::is_compatible_version(&spec_version, RPC_SPEC_VERSION).unwrap_or(false) {
+        let check = Check::error(
+            "chain",
+            format!("{url} serves the RPC version {spec_version}, expected {RPC_SPEC_VERSION}"),
+            "Use a node release serving the expected RPC version, or the sozo release matching \
+             the node.",
+        );
+        return (check, None);
+    }
+
+    let chain_id = match provider.chain_id().await {
+        Ok(id) => snutils::parse_cairo_short_string(&id).unwrap_or_else(|_| format!("{id:#x}")),
+        Err(e) => {
+            let check = Check::error(
+                "chain",
+                format!("failed to fetch the chain id from {url}: {e}"),
+                "Check that the RPC URL points to a Starknet node.",
+            );
+            return (check, None);
+        }
+    };
+
+    let check = Check::ok("chain", format!("{url} is reachable, chain id {chain_id}"));
+    (check, Some(chain_id))
+}
+
+async fn check_account(
+    account: &AccountOptions,
+    env: Option<&Environment>,
+    provider: &JsonRpcClient<HttpTransport>,
+    chain_id: &str,
+) -> Check {
+    let address = match account.account_address(env) {
+        Ok(address) => address,
+        Err(_) => {
+            return Check::warning(
+                "account",
+                "no account configured",
+                "Set `account_address` in the [env] of the profile config, or pass \
+                 --account-address.",
+            );
+        }
+    };
+
+    match dojo_utils::is_deployed(address, provider).await {
+        Ok(true) => Check::ok("account", format!("{address:#066x} is deployed")),
+        Ok(false) => Check::error(
+            "account",
+            format!("{address:#066x} isn't deployed on chain {chain_id}"),
+            "Deploy the account, or use one of the prefunded accounts printed by katana at \
+             startup.",
+        ),
+        Err(e) => Check::error(
+            "account",
+            format!("failed to fetch the class of {address:#066x}: {e}"),
+            "Check that the node is healthy.",
+        ),
+    }
+}
+
+async fn check_manifest(
+    ws: &Workspace<'_>,
+    world: &WorldOptions,
+    env: Option<&Environment>,
+    provider: Option<&JsonRpcClient<HttpTransport>>,
+) -> Vec<Check> {
+    let manifest = match ws.read_manifest_profile() {
+        Ok(Some(manifest)) => manifest,
+        Ok(None) => {
+            return vec![Check::warning(
+                "manifest",
+                "the world hasn't been migrated with the current profile",
+                "Run `sozo migrate`.",
+            )];
+        }
+        Err(e) => {
+            return vec![Check::error(
+                "manifest",
+                format!("the manifest can't be read: {e}"),
+                "Remove the manifest of the current profile and run `sozo migrate`.",
+            )];
+        }
+    };
+
+    let mut checks = Vec::new();
+    let address = manifest.world.address;
+
+    if let Ok(Some(configured)) = world.address(env) {
+        if configured != address {
+            checks.push(Check::warning(
+                "manifest",
+                format!(
+                    "the configured world address {configured:#066x} differs from the world \
+                     address {address:#066x} of the manifest"
+                ),
+                "Update or remove `world_address` in the [env] of the profile config.",
+            ));
+        }
+    }
+
+    if let Some(provider) = provider {
+        let class_hash = provider.get_class_hash_at(BlockId::Tag(BlockTag::Pending), address).await;
+        checks.push(match class_hash {
+            Ok(class_hash) if class_hash == manifest.world.class_hash => {
+                Check::ok("manifest", format!("the world {address:#066x} is deployed"))
+            }
+            Ok(class_hash) => Check::warning(
+                "manifest",
+                format!(
+                    "the world {address:#066x} has the class {class_hash:#066x} on chain, but \
+                     {:#066x} in the manifest",
+                    manifest.world.class_hash
+                ),
+                "Run `sozo migrate` to update the manifest.",
+            ),
+            Err(ProviderError::StarknetError(StarknetError::ContractNotFound)) => Check::error(
+                "manifest",
+                format!("the world {address:#066x} of the manifest isn't deployed on this chain"),
+                "The chain may have been restarted: run `sozo migrate` to deploy the world again.",
+            ),
+            Err(e) => Check::error(
+                "manifest",
+                format!("failed to fetch the class of the world {address:#066x}: {e}"),
+                "Check that the node is healthy.",
+            ),
+        });
+    }
+
+    checks.push(match ws.load_world_local() {
+        Ok(local) => match diff::diff_against_manifest(&local, &manifest).compatibility() {
+            Compatibility::Unchanged => {
+                Check::ok("build", "the built world matches the last migration")
+            }
+            _ => Check::warning(
+                "build",
+                "the built world differs from the last migration",
+                "Run `sozo migrate`, or `sozo diff --against manifest` to see the changes.",
+            ),
+        },
+        Err(_) => Check::warning("build", "the world isn't built", "Run `sozo build`."),
+    });
+
+    checks
+}
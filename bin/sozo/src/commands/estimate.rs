@@ -0,0 +1,156 @@
+use anyhow::{bail, Result};
+use clap::Args;
+use dojo_world::config::calldata_decoder;
+use scarb::core::Config;
+use sozo_ops::resource_descriptor::ResourceDescriptor;
+use sozo_scarbext::WorkspaceExt;
+use starknet::accounts::Account;
+use starknet::core::types::{Call, ExecutionResources, SimulatedTransaction, TransactionTrace};
+use starknet::core::utils as snutils;
+use tracing::trace;
+
+use super::options::account::AccountOptions;
+use super::options::starknet::StarknetOptions;
+use super::options::transaction::FeeToken;
+use super::options::world::WorldOptions;
+use crate::utils;
+
+#[derive(Debug, Args)]
+#[command(about = "Estimate the fee of executing a system with the given calldata.")]
+pub struct EstimateArgs {
+    #[arg(
+        help = "The address or the tag (ex: dojo_examples:actions) of the contract to be executed."
+    )]
+    pub tag_or_address: ResourceDescriptor,
+
+    #[arg(help = "The name of the entrypoint to be executed.")]
+    pub entrypoint: String,
+
+    #[arg(short, long)]
+    #[arg(help = "The calldata to be passed to the system. Comma separated values e.g., \
+                  0x12345,128,u256:9999999999. Sozo supports some prefixes that you can use to \
+                  automatically parse some types. The supported prefixes are:
+                  - u256: A 256-bit unsigned integer.
+                  - sstr: A cairo short string.
+                  - str: A cairo string (ByteArray).
+                  - int: A signed integer.
+                  - no prefix: A cairo felt or any type that fit into one felt.")]
+    pub calldata: Option<String>,
+
+    #[arg(long)]
+    #[arg(help = "If true, sozo will compute the diff of the world from the chain to translate \
+                  tags to addresses.")]
+    pub diff: bool,
+
+    #[arg(long)]
+    #[arg(help = "Fee token used to estimate the fee.")]
+    #[arg(default_value_t = FeeToken::Strk)]
+    pub fee: FeeToken,
+
+    #[command(flatten)]
+    pub starknet: StarknetOptions,
+
+    #[command(flatten)]
+    pub account: AccountOptions,
+
+    #[command(flatten)]
+    pub world: WorldOptions,
+}
+
+impl EstimateArgs {
+    pub fn run(self, config: &Config) -> Result<()> {
+        trace!(args = ?self);
+
+        let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
+
+        let profile_config = ws.load_profile_config()?;
+
+        let descriptor = self.tag_or_address.ensure_namespace(&profile_config.namespace.default);
+
+        config.tokio_handle().block_on(async {
+            let (contract_address, contracts) = utils::resolve_contract_address(
+                &descriptor,
+                self.account.clone(),
+                self.starknet.clone(),
+                self.world,
+                &ws,
+                self.diff,
+            )
+            .await?;
+
+            trace!(
+                contract=?descriptor,
+                entrypoint=self.entrypoint,
+                calldata=?self.calldata,
+                "Executing Estimate command."
+            );
+
+            let calldata = if let Some(cd) = self.calldata {
+                calldata_decoder::decode_calldata(&cd)?
+            } else {
+                vec![]
+            };
+
+            let call = Call {
+                calldata,
+                to: contract_address,
+                selector: snutils::get_selector_from_name(&self.entrypoint)?,
+            };
+
+            let (provider, _) = self.starknet.provider(profile_config.env.as_ref())?;
+
+            let account = self
+                .account
+                .account(provider, profile_config.env.as_ref(), &self.starknet, &contracts)
+                .await?;
+
+            // The transaction is simulated instead of only estimated to get the resources used by
+            // its execution.
+            let simulated = match self.fee {
+                FeeToken::Strk => account.execute_v3(vec![call]).simulate(false, false).await?,
+                FeeToken::Eth => account.execute_v1(vec![call]).simulate(false, false).await?,
+            };
+
+            print_estimate(&simulated)
+        })
+    }
+}
+
+fn print_estimate(simulated: &SimulatedTransaction) -> Result<()> {
+    let TransactionTrace::Invoke(trace) = &simulated.transaction_trace else {
+        bail!("Unexpected transaction trace type for an invoke transaction.");
+    };
+
+    let fee = &simulated.fee_estimation;
+    println!("Overall fee: {} {:?}", fee.overall_fee, fee.unit);
+    println!("L1 gas: {} (price: {})", fee.gas_consumed, fee.gas_price);
+    println!("L1 data gas: {} (price: {})", fee.data_gas_consumed, fee.data_gas_price);
+
+    let ExecutionResources { computation_resources: resources, data_resources } =
+        &trace.execution_resources;
+
+    println!("\nComputation resources:");
+    println!("  steps: {}", resources.steps);
+
+    let optional_resources = [
+        ("memory holes", resources.memory_holes),
+        ("range check", resources.range_check_builtin_applications),
+        ("pedersen", resources.pedersen_builtin_applications),
+        ("poseidon", resources.poseidon_builtin_applications),
+        ("ec op", resources.ec_op_builtin_applications),
+        ("ecdsa", resources.ecdsa_builtin_applications),
+        ("bitwise", resources.bitwise_builtin_applications),
+        ("keccak", resources.keccak_builtin_applications),
+        ("segment arena", resources.segment_arena_builtin),
+    ];
+
+    for (name, value) in optional_resources.into_iter().filter_map(|(n, v)| v.map(|v| (n, v))) {
+        println!("  {name}: {value}");
+    }
+
+    println!("\nData availability:");
+    println!("  L1 gas: {}", data_resources.data_availability.l1_gas);
+    println!("  L1 data gas: {}", data_resources.data_availability.l1_data_gas);
+
+    Ok(())
+}
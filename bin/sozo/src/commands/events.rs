@@ -1,16 +1,28 @@
 use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
-use anyhow::Result;
-use clap::Args;
+use anyhow::{anyhow, bail, Context, Result};
+use clap::{Args, ValueEnum};
 use colored::Colorize;
+use dojo_types::primitive::Primitive;
+use dojo_types::schema::{Struct, Ty};
 use dojo_world::contracts::abigen::world::{self, Event as WorldEvent};
+use dojo_world::contracts::model::ModelReader;
+use dojo_world::contracts::WorldContractReader;
 use dojo_world::diff::WorldDiff;
+use parquet::column::writer::ColumnWriter;
+use parquet::data_type::ByteArray;
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
 use scarb::core::Config;
 use sozo_ops::model;
 use sozo_scarbext::WorkspaceExt;
-use starknet::core::types::{BlockId, BlockTag, EventFilter, Felt};
-use starknet::core::utils::starknet_keccak;
+use starknet::core::types::{BlockId, BlockTag, EmittedEvent, EventFilter, Felt};
+use starknet::core::utils::{get_selector_from_name, starknet_keccak};
 use starknet::providers::Provider;
 
 use super::options::starknet::StarknetOptions;
@@ -44,6 +56,17 @@ pub struct EventsArgs {
     #[arg(help = "Print values as raw json")]
     pub json: bool,
 
+    #[arg(long)]
+    #[arg(help = "Directory where the decoded events are exported, one file per event kind. \
+                  The records and dojo events are decoded against their schemas, into one \
+                  file per model or event. When exporting, all the pages matching the filter \
+                  are fetched.")]
+    pub export: Option<PathBuf>,
+
+    #[arg(long, value_enum, requires = "export", default_value_t = ExportFormat::Csv)]
+    #[arg(help = "Format of the exported files")]
+    pub export_format: ExportFormat,
+
     #[command(flatten)]
     pub world: WorldOptions,
 
@@ -82,6 +105,19 @@ impl EventsArgs {
                 keys,
             };
 
+            if let Some(export_dir) = self.export {
+                return export_events(
+                    &provider,
+                    event_filter,
+                    self.continuation_token,
+                    self.chunk_size,
+                    &world_diff,
+                    &export_dir,
+                    self.export_format,
+                )
+                .await;
+            }
+
             let res =
                 provider.get_events(event_filter, self.continuation_token, self.chunk_size).await?;
 
@@ -373,3 +409,847 @@ async fn match_event<P: Provider + Send + Sync>(
 
     Ok(())
 }
+
+/// The format of the exported event files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// Comma separated values, with a header line.
+    Csv,
+    /// Apache Parquet, with typed columns.
+    Parquet,
+}
+
+impl ExportFormat {
+    /// Returns the name of the file of an event kind.
+    fn file_name(&self, kind: &str) -> String {
+        match self {
+            ExportFormat::Csv => format!("{kind}.csv"),
+            ExportFormat::Parquet => format!("{kind}.parquet"),
+        }
+    }
+}
+
+/// Columns shared by all the exported event kinds.
+const COMMON_COLUMNS: [&str; 2] = ["block_number", "transaction_hash"];
+
+/// Fetches all the events matching the filter and writes them into one file per event kind.
+async fn export_events<P: Provider + Send + Sync>(
+    provider: P,
+    event_filter: EventFilter,
+    mut continuation_token: Option<String>,
+    chunk_size: u64,
+    world_diff: &WorldDiff,
+    export_dir: &Path,
+    format: ExportFormat,
+) -> Result<()> {
+    fs::create_dir_all(export_dir)
+        .with_context(|| format!("Failed to create export directory {}", export_dir.display()))?;
+
+    let tags: HashMap<Felt, String> =
+        world_diff.resources.iter().map(|(s, r)| (*s, r.tag())).collect();
+
+    let world_reader = WorldContractReader::new(world_diff.world_info.address, &provider);
+    // the schemas of the models and events, `None` if it couldn't be fetched
+    let mut schemas: HashMap<Felt, Option<Ty>> = HashMap::new();
+
+    let mut exporter = Exporter::new(export_dir, format);
+
+    loop {
+        let res = provider.get_events(event_filter.clone(), continuation_token, chunk_size).await?;
+
+        for event in &res.events {
+            match world::Event::try_from(event) {
+                Ok(ev) => {
+                    if let Some(selector) = schema_selector(&ev) {
+                        if !schemas.contains_key(&selector) {
+                            let schema = fetch_schema(&world_reader, tags.get(&selector)).await;
+                            if schema.is_none() {
+                                tracing::warn!(
+                                    selector = format!("{:#066x}", selector),
+                                    "Failed to fetch the schema, the values are exported as felts."
+                                );
+                            }
+                            schemas.insert(selector, schema);
+                        }
+                    }
+
+                    if let Some((kind, columns, values)) = decoded_record(&ev, &tags, &schemas) {
+                        let row = common_values(event).into_iter().chain(values).collect();
+                        exporter.write(&kind, &columns, row)?;
+                    } else if let Some((kind, columns, values)) = event_record(&ev, &tags)? {
+                        let row = common_values(event).into_iter().chain(values).collect();
+                        exporter.write(kind, columns, row)?;
+                    }
+                }
+                Err(e) => {
+                    tracing::error!(
+                        ?e,
+                        "Failed to parse remote world event which is supposed to be valid."
+                    );
+                }
+            }
+        }
+
+        continuation_token = res.continuation_token;
+        if continuation_token.is_none() {
+            break;
+        }
+    }
+
+    for (file, count) in exporter.finish()? {
+        println!("{count} event(s) exported to {}", export_dir.join(file).display());
+    }
+
+    Ok(())
+}
+
+fn common_values(event: &EmittedEvent) -> Vec<Value> {
+    vec![Value::Int(event.block_number), Value::Text(format!("{:#066x}", event.transaction_hash))]
+}
+
+/// A value of an exported column.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Value {
+    /// An integer, missing for the events of the pending block.
+    Int(Option<u64>),
+    /// A signed integer member, up to 64 bits.
+    Signed(i64),
+    /// An unsigned integer member, up to 64 bits.
+    Unsigned(u64),
+    Bool(bool),
+    Text(String),
+}
+
+impl Value {
+    fn to_csv(&self) -> String {
+        match self {
+            Value::Int(value) => value.map(|v| v.to_string()).unwrap_or_default(),
+            Value::Signed(value) => value.to_string(),
+            Value::Unsigned(value) => value.to_string(),
+            Value::Bool(value) => value.to_string(),
+            Value::Text(value) => csv_escape(value),
+        }
+    }
+
+    /// The type of the Parquet column of the value, in the message type syntax.
+    fn parquet_type(&self) -> &'static str {
+        match self {
+            Value::Int(_) => "OPTIONAL INT64",
+            Value::Signed(_) | Value::Unsigned(_) => "REQUIRED INT64",
+            Value::Bool(_) => "REQUIRED BOOLEAN",
+            Value::Text(_) => "REQUIRED BINARY",
+        }
+    }
+
+    /// The logical type annotation of the Parquet column of the value, if any.
+    fn parquet_annotation(&self) -> Option<&'static str> {
+        match self {
+            Value::Unsigned(_) => Some("UINT_64"),
+            Value::Text(_) => Some("UTF8"),
+            _ => None,
+        }
+    }
+}
+
+/// A decoded event ready to be exported: the name of its kind, the columns specific to this kind
+/// and the values of those columns.
+type EventRecord = (&'static str, &'static [&'static str], Vec<Value>);
+
+/// Flattens a world event into typed columns, which depend on the event kind.
+///
+/// Felts are exported as hex strings, and lists of felts as `;` separated hex strings.
+fn event_record(event: &WorldEvent, tags: &HashMap<Felt, String>) -> Result<Option<EventRecord>> {
+    let felt = |f: &Felt| Value::Text(format!("{:#066x}", f));
+    let felts = |fs: &[Felt]| {
+        Value::Text(fs.iter().map(|f| format!("{:#066x}", f)).collect::<Vec<String>>().join(";"))
+    };
+    let tag = |s: &Felt| Value::Text(tags.get(s).cloned().unwrap_or_default());
+    let text = |s: String| Value::Text(s);
+
+    let record: EventRecord = match event {
+        WorldEvent::WorldSpawned(e) => (
+            "world_spawned",
+            &["creator", "class_hash"],
+            vec![felt(&e.creator.0), felt(&e.class_hash.0)],
+        ),
+        WorldEvent::WorldUpgraded(e) => {
+            ("world_upgraded", &["class_hash"], vec![felt(&e.class_hash.0)])
+        }
+        WorldEvent::NamespaceRegistered(e) => {
+            ("namespace_registered", &["namespace"], vec![text(e.namespace.to_string()?)])
+        }
+        WorldEvent::ModelRegistered(e) => (
+            "model_registered",
+            &["namespace", "name", "class_hash", "address"],
+            vec![
+                text(e.namespace.to_string()?),
+                text(e.name.to_string()?),
+                felt(&e.class_hash.0),
+                felt(&e.address.0),
+            ],
+        ),
+        WorldEvent::EventRegistered(e) => (
+            "event_registered",
+            &["namespace", "name", "class_hash", "address"],
+            vec![
+                text(e.namespace.to_string()?),
+                text(e.name.to_string()?),
+                felt(&e.class_hash.0),
+                felt(&e.address.0),
+            ],
+        ),
+        WorldEvent::ContractRegistered(e) => (
+            "contract_registered",
+            &["namespace", "name", "class_hash", "address", "salt"],
+            vec![
+                text(e.namespace.to_string()?),
+                text(e.name.to_string()?),
+                felt(&e.class_hash.0),
+                felt(&e.address.0),
+                felt(&e.salt),
+            ],
+        ),
+        WorldEvent::ModelUpgraded(e) => (
+            "model_upgraded",
+            &["tag", "selector", "class_hash", "address", "prev_address"],
+            vec![
+                tag(&e.selector),
+                felt(&e.selector),
+                felt(&e.class_hash.0),
+                felt(&e.address.0),
+                felt(&e.prev_address.0),
+            ],
+        ),
+        WorldEvent::EventUpgraded(e) => (
+            "event_upgraded",
+            &["tag", "selector", "class_hash", "address", "prev_address"],
+            vec![
+                tag(&e.selector),
+                felt(&e.selector),
+                felt(&e.class_hash.0),
+                felt(&e.address.0),
+                felt(&e.prev_address.0),
+            ],
+        ),
+        WorldEvent::ContractUpgraded(e) => (
+            "contract_upgraded",
+            &["tag", "selector", "class_hash"],
+            vec![tag(&e.selector), felt(&e.selector), felt(&e.class_hash.0)],
+        ),
+        WorldEvent::ContractInitialized(e) => (
+            "contract_initialized",
+            &["tag", "selector", "init_calldata"],
+            vec![tag(&e.selector), felt(&e.selector), felts(&e.init_calldata)],
+        ),
+        WorldEvent::WriterUpdated(e) => (
+            "writer_updated",
+            &["resource_tag", "resource", "contract", "value"],
+            vec![tag(&e.resource), felt(&e.resource), felt(&e.contract.0), Value::Bool(e.value)],
+        ),
+        WorldEvent::OwnerUpdated(e) => (
+            "owner_updated",
+            &["resource_tag", "resource", "contract", "value"],
+            vec![tag(&e.resource), felt(&e.resource), felt(&e.contract.0), Value::Bool(e.value)],
+        ),
+        WorldEvent::StoreSetRecord(e) => (
+            "store_set_record",
+            &["tag", "selector", "entity_id", "keys", "values"],
+            vec![
+                tag(&e.selector),
+                felt(&e.selector),
+                felt(&e.entity_id),
+                felts(&e.keys),
+                felts(&e.values),
+            ],
+        ),
+        WorldEvent::StoreUpdateRecord(e) => (
+            "store_update_record",
+            &["tag", "selector", "entity_id", "values"],
+            vec![tag(&e.selector), felt(&e.selector), felt(&e.entity_id), felts(&e.values)],
+        ),
+        WorldEvent::StoreUpdateMember(e) => (
+            "store_update_member",
+            &["tag", "selector", "entity_id", "member_selector", "values"],
+            vec![
+                tag(&e.selector),
+                felt(&e.selector),
+                felt(&e.entity_id),
+                felt(&e.member_selector),
+                felts(&e.values),
+            ],
+        ),
+        WorldEvent::StoreDelRecord(e) => (
+            "store_del_record",
+            &["tag", "selector", "entity_id"],
+            vec![tag(&e.selector), felt(&e.selector), felt(&e.entity_id)],
+        ),
+        WorldEvent::EventEmitted(e) => (
+            "event_emitted",
+            &["tag", "selector", "system_address", "keys", "values"],
+            vec![
+                tag(&e.selector),
+                felt(&e.selector),
+                felt(&e.system_address.0),
+                felts(&e.keys),
+                felts(&e.values),
+            ],
+        ),
+        _ => return Ok(None),
+    };
+
+    Ok(Some(record))
+}
+
+/// Returns the selector of the model or event whose schema decodes the data of the event.
+fn schema_selector(event: &WorldEvent) -> Option<Felt> {
+    match event {
+        WorldEvent::StoreSetRecord(e) => Some(e.selector),
+        WorldEvent::StoreUpdateRecord(e) => Some(e.selector),
+        WorldEvent::StoreUpdateMember(e) => Some(e.selector),
+        WorldEvent::EventEmitted(e) => Some(e.selector),
+        _ => None,
+    }
+}
+
+/// Fetches the schema of the model or event with the given tag.
+async fn fetch_schema<P: Provider + Send + Sync>(
+    world_reader: &WorldContractReader<P>,
+    tag: Option<&String>,
+) -> Option<Ty> {
+    let model = world_reader.model_reader_with_tag(tag?).await.ok()?;
+    model.schema().await.ok()
+}
+
+/// A record decoded against its schema: the name of its kind, its columns and their values.
+type DecodedRecord = (String, Vec<String>, Vec<Value>);
+
+/// Decodes the members of a record or a dojo event against the schema of its model or event,
+/// exported into one file per model or event, and per member for the updates of a member.
+///
+/// Returns `None` for the other world events, and when the data doesn't match the schema, e.g. for
+/// the records written before an upgrade of the model. Those are exported as felts instead.
+fn decoded_record(
+    event: &WorldEvent,
+    tags: &HashMap<Felt, String>,
+    schemas: &HashMap<Felt, Option<Ty>>,
+) -> Option<DecodedRecord> {
+    let schema_of = |selector: &Felt| schemas.get(selector)?.clone();
+    let felt = |f: &Felt| Value::Text(format!("{:#066x}", f));
+
+    let (kind, mut columns, mut values, mut schema, mut felts) = match event {
+        WorldEvent::StoreSetRecord(e) => (
+            format!("store_set_record.{}", tags.get(&e.selector)?),
+            vec!["entity_id".to_string()],
+            vec![felt(&e.entity_id)],
+            schema_of(&e.selector)?,
+            [e.keys.clone(), e.values.clone()].concat(),
+        ),
+        WorldEvent::StoreUpdateRecord(e) => {
+            let mut schema = schema_of(&e.selector)?;
+            // the keys are not part of the update
+            if let Ty::Struct(s) = &mut schema {
+                s.children.retain(|m| !m.key);
+            }
+
+            (
+                format!("store_update_record.{}", tags.get(&e.selector)?),
+                vec!["entity_id".to_string()],
+                vec![felt(&e.entity_id)],
+                schema,
+                e.values.clone(),
+            )
+        }
+        WorldEvent::StoreUpdateMember(e) => {
+            let schema = schema_of(&e.selector)?;
+            let model = schema.as_struct()?;
+            let member = model
+                .children
+                .iter()
+                .find(|m| get_selector_from_name(&m.name).is_ok_and(|s| s == e.member_selector))?;
+
+            (
+                format!("store_update_member.{}.{}", tags.get(&e.selector)?, member.name),
+                vec!["entity_id".to_string()],
+                vec![felt(&e.entity_id)],
+                Ty::Struct(Struct { name: model.name.clone(), children: vec![member.clone()] }),
+                e.values.clone(),
+            )
+        }
+        WorldEvent::EventEmitted(e) => (
+            format!("event_emitted.{}", tags.get(&e.selector)?),
+            vec!["system_address".to_string()],
+            vec![felt(&e.system_address.0)],
+            schema_of(&e.selector)?,
+            [e.keys.clone(), e.values.clone()].concat(),
+        ),
+        _ => return None,
+    };
+
+    schema.deserialize(&mut felts).ok()?;
+    if !felts.is_empty() {
+        return None;
+    }
+
+    for member in &schema.as_struct()?.children {
+        flatten(member.name.clone(), &member.ty, &mut columns, &mut values).ok()?;
+    }
+
+    Some((kind, columns, values))
+}
+
+/// Flattens a decoded value into columns named after its path, e.g. `position.x`.
+///
+/// Integers up to 64 bits and booleans get numeric and boolean columns. Wider integers are
+/// exported as decimal strings and felts as hex strings. An enum is exported as the name of its
+/// variant, followed by the felts of its data if any of its variants has some, and arrays as the
+/// felts of their items.
+fn flatten(
+    name: String,
+    ty: &Ty,
+    columns: &mut Vec<String>,
+    values: &mut Vec<Value>,
+) -> Result<()> {
+    let felts = |fs: Vec<Felt>| {
+        Value::Text(fs.iter().map(|f| format!("{:#066x}", f)).collect::<Vec<String>>().join(";"))
+    };
+
+    match ty {
+        Ty::Primitive(primitive) => {
+            columns.push(name);
+            values.push(primitive_value(primitive));
+        }
+        Ty::Struct(s) => {
+            for member in &s.children {
+                flatten(format!("{name}.{}", member.name), &member.ty, columns, values)?;
+            }
+        }
+        Ty::Tuple(items) => {
+            for (i, item) in items.iter().enumerate() {
+                flatten(format!("{name}.{i}"), item, columns, values)?;
+            }
+        }
+        Ty::Enum(e) => {
+            let option = e.option()?;
+            columns.push(name.clone());
+            values.push(Value::Text(option.name.clone()));
+
+            if e.options.iter().any(|o| o.ty != Ty::Tuple(vec![])) {
+                columns.push(format!("{name}.data"));
+                values.push(felts(option.ty.serialize()?));
+            }
+        }
+        Ty::Array(items) => {
+            let mut data = Vec::new();
+            for item in items {
+                data.extend(item.serialize()?);
+            }
+            columns.push(name);
+            values.push(felts(data));
+        }
+        Ty::ByteArray(bytes) => {
+            columns.push(name);
+            values.push(Value::Text(bytes.clone()));
+        }
+    }
+
+    Ok(())
+}
+
+fn primitive_value(primitive: &Primitive) -> Value {
+    match primitive {
+        Primitive::Bool(v) => Value::Bool(v.unwrap_or_default()),
+        Primitive::I8(v) => Value::Signed(v.unwrap_or_default().into()),
+        Primitive::I16(v) => Value::Signed(v.unwrap_or_default().into()),
+        Primitive::I32(v) => Value::Signed(v.unwrap_or_default().into()),
+        Primitive::I64(v) => Value::Signed(v.unwrap_or_default()),
+        Primitive::U8(v) => Value::Unsigned(v.unwrap_or_default().into()),
+        Primitive::U16(v) => Value::Unsigned(v.unwrap_or_default().into()),
+        Primitive::U32(v) => Value::Unsigned(v.unwrap_or_default().into()),
+        Primitive::USize(v) => Value::Unsigned(v.unwrap_or_default().into()),
+        Primitive::U64(v) => Value::Unsigned(v.unwrap_or_default()),
+        Primitive::I128(v) => Value::Text(v.unwrap_or_default().to_string()),
+        Primitive::U128(v) => Value::Text(v.unwrap_or_default().to_string()),
+        Primitive::U256(v) => Value::Text(format!("0x{:064x}", v.unwrap_or_default())),
+        Primitive::Felt252(v) | Primitive::ClassHash(v) | Primitive::ContractAddress(v) => {
+            Value::Text(format!("{:#066x}", v.unwrap_or_default()))
+        }
+    }
+}
+
+/// Lazily creates one file per event kind, in the export format.
+struct Exporter<'a> {
+    dir: &'a Path,
+    format: ExportFormat,
+    files: HashMap<String, (ExportFile, usize)>,
+}
+
+/// An export file of an event kind.
+enum ExportFile {
+    Csv(BufWriter<File>),
+    Parquet(ParquetFile),
+}
+
+impl<'a> Exporter<'a> {
+    fn new(dir: &'a Path, format: ExportFormat) -> Self {
+        Self { dir, format, files: HashMap::new() }
+    }
+
+    /// Writes a row of values, the common columns first.
+    fn write(&mut self, kind: &str, columns: &[impl AsRef<str>], row: Vec<Value>) -> Result<()> {
+        let columns = COMMON_COLUMNS
+            .iter()
+            .copied()
+            .chain(columns.iter().map(AsRef::as_ref))
+            .collect::<Vec<_>>();
+
+        if !self.files.contains_key(kind) {
+            let path = self.dir.join(self.format.file_name(kind));
+            let file = File::create(&path)
+                .with_context(|| format!("Failed to create export file {}", path.display()))?;
+
+            let file = match self.format {
+                ExportFormat::Csv => {
+                    let mut file = BufWriter::new(file);
+                    writeln!(file, "{}", columns.join(","))?;
+                    ExportFile::Csv(file)
+                }
+                ExportFormat::Parquet => {
+                    ExportFile::Parquet(ParquetFile::new(file, kind, &columns, &row)?)
+                }
+            };
+
+            self.files.insert(kind.to_string(), (file, 0));
+        }
+
+        let (file, count) = self.files.get_mut(kind).expect("file must exist");
+        match file {
+            ExportFile::Csv(file) => {
+                let line = row.iter().map(Value::to_csv).collect::<Vec<String>>().join(",");
+                writeln!(file, "{line}")?;
+            }
+            ExportFile::Parquet(file) => file.write(row)?,
+        }
+        *count += 1;
+
+        Ok(())
+    }
+
+    /// Flushes all the files and returns the number of events written per file.
+    fn finish(self) -> Result<Vec<(String, usize)>> {
+        let mut counts = Vec::with_capacity(self.files.len());
+
+        for (kind, (file, count)) in self.files {
+            match file {
+                ExportFile::Csv(mut file) => file.flush()?,
+                ExportFile::Parquet(file) => file.close()?,
+            }
+            counts.push((self.format.file_name(&kind), count));
+        }
+
+        counts.sort();
+        Ok(counts)
+    }
+}
+
+/// Number of rows buffered before being written as a Parquet row group.
+const PARQUET_ROW_GROUP_SIZE: usize = 10_000;
+
+/// A Parquet file whose schema is derived from the types of the values of its first row.
+struct ParquetFile {
+    writer: SerializedFileWriter<File>,
+    rows: Vec<Vec<Value>>,
+}
+
+impl ParquetFile {
+    fn new(file: File, kind: &str, columns: &[&str], row: &[Value]) -> Result<Self> {
+        let fields = columns
+            .iter()
+            .zip(row)
+            .map(|(name, value)| match value.parquet_annotation() {
+                Some(annotation) => format!("{} {name} ({annotation});", value.parquet_type()),
+                None => format!("{} {name};", value.parquet_type()),
+            })
+            .collect::<Vec<_>>()
+            .join(" ");
+        let schema = Arc::new(parse_message_type(&format!("message {kind} {{ {fields} }}"))?);
+        let props = Arc::new(WriterProperties::builder().build());
+
+        Ok(Self { writer: SerializedFileWriter::new(file, schema, props)?, rows: Vec::new() })
+    }
+
+    fn write(&mut self, row: Vec<Value>) -> Result<()> {
+        self.rows.push(row);
+        if self.rows.len() >= PARQUET_ROW_GROUP_SIZE {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Writes the buffered rows as a row group.
+    fn flush(&mut self) -> Result<()> {
+        if self.rows.is_empty() {
+            return Ok(());
+        }
+
+        let mut row_group = self.writer.next_row_group()?;
+        let mut index = 0;
+
+        while let Some(mut column) = row_group.next_column()? {
+            let values = self.rows.iter().map(|row| &row[index]);
+
+            match column.untyped() {
+                ColumnWriter::Int64ColumnWriter(writer) => {
+                    // only the optional columns have definition levels
+                    let mut levels = Vec::new();
+                    let mut data = Vec::with_capacity(self.rows.len());
+                    for value in values {
+                        match value {
+                            Value::Int(value) => {
+                                levels.push(value.is_some() as i16);
+                                data.extend(value.map(|v| v as i64));
+                            }
+                            Value::Signed(value) => data.push(*value),
+                            // the bits are read back as unsigned through the column annotation
+                            Value::Unsigned(value) => data.push(*value as i64),
+                            _ => bail!("Expected an integer value."),
+                        }
+                    }
+                    let levels = (!levels.is_empty()).then_some(levels.as_slice());
+                    writer.write_batch(&data, levels, None)?;
+                }
+                ColumnWriter::BoolColumnWriter(writer) => {
+                    let data = values
+                        .map(|value| match value {
+                            Value::Bool(value) => Ok(*value),
+                            _ => Err(anyhow!("Expected a boolean value.")),
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    writer.write_batch(&data, None, None)?;
+                }
+                ColumnWriter::ByteArrayColumnWriter(writer) => {
+                    let data = values
+                        .map(|value| match value {
+                            Value::Text(value) => Ok(ByteArray::from(value.as_str())),
+                            _ => Err(anyhow!("Expected a text value.")),
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    writer.write_batch(&data, None, None)?;
+                }
+                _ => unreachable!("only the columns of the exported values are created"),
+            }
+
+            column.close()?;
+            index += 1;
+        }
+
+        row_group.close()?;
+        self.rows.clear();
+        Ok(())
+    }
+
+    fn close(mut self) -> Result<()> {
+        self.flush()?;
+        self.writer.close()?;
+        Ok(())
+    }
+}
+
+/// Quotes a CSV field if it contains a separator, a quote or a line break.
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_types::schema::{Enum, EnumOption, Member};
+    use parquet::file::reader::{FileReader, SerializedFileReader};
+    use parquet::record::Field;
+
+    use super::*;
+
+    fn store_del_record() -> (HashMap<Felt, String>, WorldEvent) {
+        let tags = HashMap::from([(Felt::ONE, "ns-Position".to_string())]);
+        let event = WorldEvent::StoreDelRecord(world::StoreDelRecord {
+            selector: Felt::ONE,
+            entity_id: Felt::TWO,
+        });
+        (tags, event)
+    }
+
+    fn export(dir: &Path, format: ExportFormat, blocks: &[Option<u64>]) {
+        let (tags, event) = store_del_record();
+        let mut exporter = Exporter::new(dir, format);
+
+        for block in blocks {
+            let (kind, columns, values) = event_record(&event, &tags).unwrap().unwrap();
+            let common = vec![Value::Int(*block), Value::Text(format!("{:#066x}", Felt::THREE))];
+            exporter.write(kind, columns, common.into_iter().chain(values).collect()).unwrap();
+        }
+
+        let file = format.file_name("store_del_record");
+        assert_eq!(exporter.finish().unwrap(), vec![(file, blocks.len())]);
+    }
+
+    #[test]
+    fn csv_fields_are_escaped() {
+        assert_eq!(csv_escape("dojo_examples-Position"), "dojo_examples-Position");
+        assert_eq!(csv_escape("a,b"), "\"a,b\"");
+        assert_eq!(csv_escape("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_escape("line\nbreak"), "\"line\nbreak\"");
+    }
+
+    #[test]
+    fn csv_export_writes_header_once_per_kind() {
+        let dir = tempfile::tempdir().unwrap();
+        export(dir.path(), ExportFormat::Csv, &[Some(0), None]);
+
+        let content = fs::read_to_string(dir.path().join("store_del_record.csv")).unwrap();
+        let lines: Vec<&str> = content.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[0], "block_number,transaction_hash,tag,selector,entity_id");
+        assert!(lines[1].starts_with("0,"));
+        // the block number of a pending event is left empty
+        assert!(lines[2].starts_with(","));
+        assert!(lines[2].ends_with(&format!(
+            "ns-Position,{:#066x},{:#066x}",
+            Felt::ONE,
+            Felt::TWO
+        )));
+    }
+
+    #[test]
+    fn parquet_export_writes_typed_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        export(dir.path(), ExportFormat::Parquet, &[Some(7), None]);
+
+        let file = File::open(dir.path().join("store_del_record.parquet")).unwrap();
+        let reader = SerializedFileReader::new(file).unwrap();
+        let rows = reader.get_row_iter(None).unwrap().map(|r| r.unwrap()).collect::<Vec<_>>();
+        assert_eq!(rows.len(), 2);
+
+        let columns = rows[0].get_column_iter().collect::<Vec<_>>();
+        assert_eq!(columns[0], (&"block_number".to_string(), &Field::Long(7)));
+        assert_eq!(columns[2], (&"tag".to_string(), &Field::Str("ns-Position".to_string())));
+        assert_eq!(
+            columns[4],
+            (&"entity_id".to_string(), &Field::Str(format!("{:#066x}", Felt::TWO)))
+        );
+        assert_eq!(rows[1].get_column_iter().next().unwrap().1, &Field::Null);
+    }
+
+    fn position_schema() -> Ty {
+        let member = |name: &str, key: bool, ty: Ty| Member { name: name.to_string(), key, ty };
+        Ty::Struct(Struct {
+            name: "Position".to_string(),
+            children: vec![
+                member("player", true, Ty::Primitive(Primitive::ContractAddress(None))),
+                member(
+                    "vec",
+                    false,
+                    Ty::Struct(Struct {
+                        name: "Vec2".to_string(),
+                        children: vec![
+                            member("x", false, Ty::Primitive(Primitive::U32(None))),
+                            member("y", false, Ty::Primitive(Primitive::I8(None))),
+                        ],
+                    }),
+                ),
+                member("moving", false, Ty::Primitive(Primitive::Bool(None))),
+                member(
+                    "direction",
+                    false,
+                    Ty::Enum(Enum {
+                        name: "Direction".to_string(),
+                        option: None,
+                        options: vec![
+                            EnumOption { name: "Left".to_string(), ty: Ty::Tuple(vec![]) },
+                            EnumOption { name: "Right".to_string(), ty: Ty::Tuple(vec![]) },
+                        ],
+                    }),
+                ),
+            ],
+        })
+    }
+
+    fn store_set_record() -> (HashMap<Felt, String>, HashMap<Felt, Option<Ty>>, WorldEvent) {
+        let tags = HashMap::from([(Felt::ONE, "ns-Position".to_string())]);
+        let schemas = HashMap::from([(Felt::ONE, Some(position_schema()))]);
+        let event = WorldEvent::StoreSetRecord(world::StoreSetRecord {
+            selector: Felt::ONE,
+            entity_id: Felt::TWO,
+            keys: vec![Felt::THREE],
+            // x = 7, y = -1, moving, Right
+            values: vec![Felt::from(7), Felt::from(-1i8), Felt::ONE, Felt::ONE],
+        });
+        (tags, schemas, event)
+    }
+
+    #[test]
+    fn records_are_decoded_against_their_schema() {
+        let (tags, schemas, event) = store_set_record();
+
+        let (kind, columns, values) = decoded_record(&event, &tags, &schemas).unwrap();
+        assert_eq!(kind, "store_set_record.ns-Position");
+        assert_eq!(columns, ["entity_id", "player", "vec.x", "vec.y", "moving", "direction"]);
+        assert_eq!(
+            values,
+            vec![
+                Value::Text(format!("{:#066x}", Felt::TWO)),
+                Value::Text(format!("{:#066x}", Felt::THREE)),
+                Value::Unsigned(7),
+                Value::Signed(-1),
+                Value::Bool(true),
+                Value::Text("Right".to_string()),
+            ]
+        );
+
+        // the data doesn't match the schema, the record is exported as felts
+        let WorldEvent::StoreSetRecord(mut record) = event else { unreachable!() };
+        record.values.push(Felt::ONE);
+        assert!(decoded_record(&WorldEvent::StoreSetRecord(record), &tags, &schemas).is_none());
+
+        // no schema
+        let (tags, _, event) = store_set_record();
+        let schemas = HashMap::from([(Felt::ONE, None)]);
+        assert!(decoded_record(&event, &tags, &schemas).is_none());
+    }
+
+    #[test]
+    fn member_updates_are_decoded_against_their_member() {
+        let (tags, schemas, _) = store_set_record();
+        let event = WorldEvent::StoreUpdateMember(world::StoreUpdateMember {
+            selector: Felt::ONE,
+            entity_id: Felt::TWO,
+            member_selector: get_selector_from_name("vec").unwrap(),
+            values: vec![Felt::from(1), Felt::from(2)],
+        });
+
+        let (kind, columns, values) = decoded_record(&event, &tags, &schemas).unwrap();
+        assert_eq!(kind, "store_update_member.ns-Position.vec");
+        assert_eq!(columns, ["entity_id", "vec.x", "vec.y"]);
+        assert_eq!(values[1..], [Value::Unsigned(1), Value::Signed(2)]);
+    }
+
+    #[test]
+    fn parquet_export_writes_member_columns() {
+        let dir = tempfile::tempdir().unwrap();
+        let (tags, schemas, event) = store_set_record();
+
+        let mut exporter = Exporter::new(dir.path(), ExportFormat::Parquet);
+        let (kind, columns, values) = decoded_record(&event, &tags, &schemas).unwrap();
+        let common = vec![Value::Int(Some(7)), Value::Text(format!("{:#066x}", Felt::THREE))];
+        exporter.write(&kind, &columns, common.into_iter().chain(values).collect()).unwrap();
+        exporter.finish().unwrap();
+
+        let file = File::open(dir.path().join("store_set_record.ns-Position.parquet")).unwrap();
+        let reader = SerializedFileReader::new(file).unwrap();
+        let row = reader.get_row_iter(None).unwrap().next().unwrap().unwrap();
+
+        let columns = row.get_column_iter().map(|(_, f)| f.clone()).collect::<Vec<_>>();
+        assert_eq!(columns[4..], [Field::ULong(7), Field::Long(-1), Field::Bool(true)]);
+    }
+}
@@ -1,8 +1,12 @@
-use anyhow::{anyhow, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, bail, Context, Result};
 use clap::Args;
 use dojo_utils::{Invoker, TxnConfig};
-use dojo_world::config::calldata_decoder;
-use scarb::core::Config;
+use dojo_world::config::{calldata_decoder, ProfileConfig};
+use scarb::core::{Config, Workspace};
+use serde::Deserialize;
 use sozo_ops::resource_descriptor::ResourceDescriptor;
 use sozo_scarbext::WorkspaceExt;
 use sozo_walnut::WalnutDebugger;
@@ -22,10 +26,12 @@ pub struct ExecuteArgs {
     #[arg(
         help = "The address or the tag (ex: dojo_examples:actions) of the contract to be executed."
     )]
-    pub tag_or_address: ResourceDescriptor,
+    #[arg(required_unless_present = "batch")]
+    pub tag_or_address: Option<ResourceDescriptor>,
 
     #[arg(help = "The name of the entrypoint to be executed.")]
-    pub entrypoint: String,
+    #[arg(required_unless_present = "batch")]
+    pub entrypoint: Option<String>,
 
     #[arg(short, long)]
     #[arg(help = "The calldata to be passed to the system. Comma separated values e.g., \
@@ -43,6 +49,19 @@ pub struct ExecuteArgs {
                   tags to addresses.")]
     pub diff: bool,
 
+    #[arg(long, value_name = "PATH")]
+    #[arg(conflicts_with_all = ["tag_or_address", "entrypoint", "calldata"])]
+    #[arg(help = "Path of a JSON or CSV file of calls to execute, possibly to different \
+                  contracts. JSON files contain an array of objects with `contract`, \
+                  `entrypoint` and optional `calldata` fields. CSV files contain one call per \
+                  line, with the contract, the entrypoint and then the calldata values as \
+                  columns.")]
+    pub batch: Option<PathBuf>,
+
+    #[arg(long, default_value_t = 50)]
+    #[arg(help = "Maximum number of calls sent in a single multicall when using `--batch`.")]
+    pub max_calls: usize,
+
     #[command(flatten)]
     pub starknet: StarknetOptions,
 
@@ -64,50 +83,44 @@ impl ExecuteArgs {
 
         let profile_config = ws.load_profile_config()?;
 
-        let descriptor = self.tag_or_address.ensure_namespace(&profile_config.namespace.default);
-
         #[cfg(feature = "walnut")]
         let _walnut_debugger = WalnutDebugger::new_from_flag(
             self.transaction.walnut,
             self.starknet.url(profile_config.env.as_ref())?,
         );
 
-        let txn_config: TxnConfig = self.transaction.try_into()?;
+        let txn_config: TxnConfig = self.transaction.clone().try_into()?;
 
-        config.tokio_handle().block_on(async {
-            let (contract_address, contracts) = match &descriptor {
-                ResourceDescriptor::Address(address) => (Some(*address), Default::default()),
-                ResourceDescriptor::Tag(tag) => {
-                    let contracts = utils::contracts_from_manifest_or_diff(
-                        self.account.clone(),
-                        self.starknet.clone(),
-                        self.world,
-                        &ws,
-                        self.diff,
-                    )
-                    .await?;
-
-                    (contracts.get(tag).map(|c| c.address), contracts)
-                }
-                ResourceDescriptor::Name(_) => {
-                    unimplemented!("Expected to be a resolved tag with default namespace.")
-                }
-            };
+        if let Some(batch) = &self.batch {
+            let calls = BatchCall::from_file(batch)?;
+            return config.tokio_handle().block_on(self.run_batch(
+                calls,
+                &ws,
+                &profile_config,
+                txn_config,
+            ));
+        }
+
+        let descriptor = self
+            .tag_or_address
+            .expect("required by clap when `--batch` is not set")
+            .ensure_namespace(&profile_config.namespace.default);
+        let entrypoint = self.entrypoint.expect("required by clap when `--batch` is not set");
 
-            let contract_address = contract_address.ok_or_else(|| {
-                let mut message = format!("Contract {descriptor} not found in the manifest.");
-                if self.diff {
-                    message.push_str(
-                        " Run the command again with `--diff` to force the fetch of data from the \
-                         chain.",
-                    );
-                }
-                anyhow!(message)
-            })?;
+        config.tokio_handle().block_on(async {
+            let (contract_address, contracts) = utils::resolve_contract_address(
+                &descriptor,
+                self.account.clone(),
+                self.starknet.clone(),
+                self.world,
+                &ws,
+                self.diff,
+            )
+            .await?;
 
             trace!(
                 contract=?descriptor,
-                entrypoint=self.entrypoint,
+                entrypoint,
                 calldata=?self.calldata,
                 "Executing Execute command."
             );
@@ -121,7 +134,7 @@ impl ExecuteArgs {
             let call = Call {
                 calldata,
                 to: contract_address,
-                selector: snutils::get_selector_from_name(&self.entrypoint)?,
+                selector: snutils::get_selector_from_name(&entrypoint)?,
             };
 
             let (provider, _) = self.starknet.provider(profile_config.env.as_ref())?;
@@ -139,4 +152,175 @@ impl ExecuteArgs {
             Ok(())
         })
     }
+
+    /// Executes the calls of a batch file, chunked into multicalls of at most `max_calls` calls.
+    async fn run_batch(
+        self,
+        calls: Vec<BatchCall>,
+        ws: &Workspace<'_>,
+        profile_config: &ProfileConfig,
+        txn_config: TxnConfig,
+    ) -> Result<()> {
+        let default_ns = &profile_config.namespace.default;
+        let calls = calls
+            .into_iter()
+            .map(|c| {
+                Ok((ResourceDescriptor::from_string(&c.contract)?.ensure_namespace(default_ns), c))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        // the address book is only needed to resolve tags
+        let contracts = if calls.iter().any(|(d, _)| matches!(d, ResourceDescriptor::Tag(_))) {
+            utils::contracts_from_manifest_or_diff(
+                self.account.clone(),
+                self.starknet.clone(),
+                self.world,
+                ws,
+                self.diff,
+            )
+            .await?
+        } else {
+            Default::default()
+        };
+
+        let calls = calls
+            .into_iter()
+            .map(|(descriptor, call)| {
+                let to = match &descriptor {
+                    ResourceDescriptor::Address(address) => *address,
+                    ResourceDescriptor::Tag(tag) => {
+                        contracts.get(tag).map(|c| c.address).ok_or_else(|| {
+                            anyhow!("Contract {descriptor} not found in the manifest.")
+                        })?
+                    }
+                    ResourceDescriptor::Name(_) => {
+                        unimplemented!("Expected to be a resolved tag with default namespace.")
+                    }
+                };
+
+                let calldata = match &call.calldata {
+                    Some(cd) => calldata_decoder::decode_calldata(cd)?,
+                    None => vec![],
+                };
+
+                Ok(Call {
+                    to,
+                    calldata,
+                    selector: snutils::get_selector_from_name(&call.entrypoint)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let (provider, _) = self.starknet.provider(profile_config.env.as_ref())?;
+
+        let account = self
+            .account
+            .account(provider, profile_config.env.as_ref(), &self.starknet, &contracts)
+            .await?;
+
+        let chunks = calls.chunks(self.max_calls.max(1)).collect::<Vec<_>>();
+        for (i, chunk) in chunks.iter().enumerate() {
+            trace!(chunk = i, calls = chunk.len(), "Executing batch chunk.");
+
+            let mut invoker = Invoker::new(&account, txn_config);
+            invoker.extend_calls(chunk.to_vec());
+            let tx_result = invoker.multicall().await?;
+
+            println!("Chunk {}/{} ({} calls): {}", i + 1, chunks.len(), chunk.len(), tx_result);
+        }
+
+        Ok(())
+    }
+}
+
+/// A call read from a batch file.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct BatchCall {
+    /// The address or the tag of the contract to call.
+    pub contract: String,
+    /// The name of the entrypoint to call.
+    pub entrypoint: String,
+    /// The calldata, using the same format as the `--calldata` argument.
+    #[serde(default)]
+    pub calldata: Option<String>,
+}
+
+impl BatchCall {
+    /// Reads the calls of a batch file, parsed as CSV unless its extension is `json`.
+    pub fn from_file(path: &Path) -> Result<Vec<Self>> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read batch file {}", path.display()))?;
+
+        if path.extension().is_some_and(|ext| ext == "json") {
+            Ok(serde_json::from_str(&content)?)
+        } else {
+            Self::from_csv(&content)
+        }
+    }
+
+    /// Parses CSV lines of the form `contract,entrypoint[,calldata...]`. Empty lines and lines
+    /// starting with `#` are ignored.
+    fn from_csv(content: &str) -> Result<Vec<Self>> {
+        content
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+            .map(|(i, line)| {
+                let mut columns = line.splitn(3, ',').map(str::trim);
+
+                let contract = columns.next().unwrap_or_default().to_string();
+                let entrypoint = match columns.next() {
+                    Some(entrypoint) if !entrypoint.is_empty() => entrypoint.to_string(),
+                    _ => bail!("Missing entrypoint at line {} of the batch file.", i + 1),
+                };
+                let calldata = columns.next().filter(|cd| !cd.is_empty()).map(str::to_string);
+
+                Ok(Self { contract, entrypoint, calldata })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn batch_calls_from_csv() {
+        let content =
+            "# contract,entrypoint,calldata\n\nns-actions,spawn\n0x1234,transfer,0x1,u256:2\n";
+
+        let calls = BatchCall::from_csv(content).unwrap();
+        assert_eq!(
+            calls,
+            vec![
+                BatchCall {
+                    contract: "ns-actions".to_string(),
+                    entrypoint: "spawn".to_string(),
+                    calldata: None,
+                },
+                BatchCall {
+                    contract: "0x1234".to_string(),
+                    entrypoint: "transfer".to_string(),
+                    calldata: Some("0x1,u256:2".to_string()),
+                },
+            ]
+        );
+
+        assert!(BatchCall::from_csv("ns-actions").is_err());
+    }
+
+    #[test]
+    fn batch_calls_from_json() {
+        let content = r#"[
+            {"contract": "ns-actions", "entrypoint": "spawn"},
+            {"contract": "0x1234", "entrypoint": "transfer", "calldata": "0x1,u256:2"}
+        ]"#;
+
+        let calls: Vec<BatchCall> = serde_json::from_str(content).unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].calldata, None);
+        assert_eq!(calls[1].calldata, Some("0x1,u256:2".to_string()));
+    }
 }
@@ -0,0 +1,165 @@
+use std::path::PathBuf;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, bail, Context, Result};
+use clap::Args;
+use katana_node_bindings::Katana;
+use scarb::core::Config;
+use sozo_ops::fuzz::{self, FuzzConfig};
+use sozo_ops::resource_descriptor::ResourceDescriptor;
+use sozo_scarbext::WorkspaceExt;
+use starknet::accounts::{ExecutionEncoding, SingleOwnerAccount};
+use starknet::providers::jsonrpc::HttpTransport;
+use starknet::providers::{JsonRpcClient, Provider};
+use starknet::signers::LocalWallet;
+use tracing::trace;
+
+use super::options::starknet::StarknetOptions;
+
+#[derive(Debug, Args)]
+#[command(about = "Fuzz the systems of a contract on an ephemeral fork of the migrated world.")]
+pub struct FuzzArgs {
+    #[arg(help = "The tag (ex: dojo_examples-actions) or the address of the contract to fuzz.")]
+    pub tag_or_address: ResourceDescriptor,
+
+    #[arg(long)]
+    #[arg(value_delimiter = ',')]
+    #[arg(help = "Comma separated names of the systems to fuzz. All the systems of the contract \
+                  are fuzzed by default.")]
+    pub systems: Vec<String>,
+
+    #[arg(long, default_value_t = 100)]
+    #[arg(help = "The number of cases run for each system.")]
+    pub runs: u64,
+
+    #[arg(long)]
+    #[arg(help = "The seed of the session, random by default. A failing case is replayed by \
+                  running a single case with its reported seed.")]
+    pub seed: Option<u64>,
+
+    #[arg(long)]
+    #[arg(help = "The block to fork the chain at, the latest block by default.")]
+    pub fork_block: Option<u64>,
+
+    #[arg(long, value_name = "PATH")]
+    #[arg(help = "Path of the katana binary used to fork the chain, looked up in the PATH by \
+                  default.")]
+    pub katana: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub starknet: StarknetOptions,
+}
+
+impl FuzzArgs {
+    pub fn run(self, config: &Config) -> Result<()> {
+        trace!(args = ?self);
+
+        let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
+        let profile_config = ws.load_profile_config()?;
+
+        let descriptor = self.tag_or_address.ensure_namespace(&profile_config.namespace.default);
+        let manifest = ws
+            .read_manifest_profile()?
+            .ok_or_else(|| anyhow!("No manifest found, the world must be migrated first."))?;
+
+        let contract = match &descriptor {
+            ResourceDescriptor::Address(address) => {
+                manifest.contracts.iter().find(|c| c.address == *address)
+            }
+            ResourceDescriptor::Tag(tag) => manifest.contracts.iter().find(|c| &c.tag == tag),
+            ResourceDescriptor::Name(name) => {
+                bail!("Contract `{name}` must be given as a tag or an address.")
+            }
+        }
+        .ok_or_else(|| anyhow!("Contract {descriptor} not found in the manifest."))?;
+
+        let systems = fuzz::systems(&contract.abi)
+            .into_iter()
+            .filter(|s| self.systems.is_empty() || self.systems.contains(&s.name))
+            .collect::<Vec<_>>();
+
+        if let Some(unknown) = self.systems.iter().find(|s| !systems.iter().any(|f| &f.name == *s))
+        {
+            bail!("System `{unknown}` not found in contract {}.", contract.tag);
+        }
+
+        let seed = match self.seed {
+            Some(seed) => seed,
+            None => SystemTime::now().duration_since(UNIX_EPOCH)?.as_nanos() as u64,
+        };
+        let fuzz_config = FuzzConfig { runs: self.runs, seed };
+
+        let url = self.starknet.url(profile_config.env.as_ref())?;
+
+        config.tokio_handle().block_on(async {
+            let fork_block = match self.fork_block {
+                Some(block) => block,
+                None => JsonRpcClient::new(HttpTransport::new(url.clone())).block_number().await?,
+            };
+
+            println!(
+                "Fuzzing {} on a fork of {url} at block {fork_block} with seed {seed}.",
+                contract.tag
+            );
+
+            // The fork is killed when the instance is dropped.
+            let mut katana = self.katana.clone().map_or_else(Katana::new, Katana::at);
+            katana = katana.fork_provider(url.as_str()).fork_block_number(fork_block).fee(false);
+            let instance = katana.try_spawn().context("Failed to spawn the katana fork")?;
+
+            let dev_account = instance
+                .accounts()
+                .first()
+                .ok_or_else(|| anyhow!("The katana fork has no predeployed account."))?;
+            let signer = LocalWallet::from_signing_key(
+                dev_account.private_key.clone().expect("dev accounts have a private key"),
+            );
+            let account = SingleOwnerAccount::new(
+                JsonRpcClient::new(HttpTransport::new(instance.endpoint_url())),
+                signer,
+                dev_account.address,
+                instance.chain_id(),
+                ExecutionEncoding::New,
+            );
+
+            let reports = fuzz::fuzz_systems(
+                &account,
+                contract.address,
+                &contract.abi,
+                &systems,
+                &fuzz_config,
+            )
+            .await?;
+
+            let mut failing_cases = 0;
+
+            for report in &reports {
+                let failures = report.failures.iter().map(|f| f.count).sum::<u64>();
+                failing_cases += failures;
+                println!("{}: {} runs, {} failures", report.system, report.runs, failures);
+
+                for failure in &report.failures {
+                    let calldata = failure
+                        .calldata
+                        .iter()
+                        .map(|f| format!("{:#x}", f))
+                        .collect::<Vec<_>>()
+                        .join(",");
+
+                    println!("  [{}x] {}", failure.count, failure.reason);
+                    println!("    calldata: {calldata}");
+                    println!(
+                        "    replay: sozo fuzz {} --systems {} --runs 1 --seed {} --fork-block {}",
+                        contract.tag, report.system, failure.seed, fork_block
+                    );
+                }
+            }
+
+            if failing_cases > 0 {
+                bail!("{failing_cases} failing cases found.");
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -2,10 +2,13 @@ use anyhow::Result;
 use clap::Args;
 use colored::*;
 use dojo_types::naming;
+use dojo_world::contracts::WorldContractReader;
 use dojo_world::diff::{ResourceDiff, WorldDiff, WorldStatus};
 use dojo_world::ResourceType;
 use scarb::core::Config;
 use serde::Serialize;
+use starknet::core::types::Felt;
+use starknet::providers::Provider;
 use tabled::settings::object::Cell;
 use tabled::settings::{Color, Style};
 use tabled::{Table, Tabled};
@@ -21,6 +24,11 @@ pub struct InspectArgs {
                   displayed.")]
     resource: Option<String>,
 
+    #[arg(long)]
+    #[arg(help = "Fetch and display the metadata URIs published onchain for the world and its \
+                  deployed resources.")]
+    metadata: bool,
+
     #[command(flatten)]
     world: WorldOptions,
 
@@ -33,18 +41,22 @@ impl InspectArgs {
         trace!(args = ?self);
         let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
 
-        let InspectArgs { world, starknet, resource } = self;
+        let InspectArgs { world, starknet, resource, metadata } = self;
 
         config.tokio_handle().block_on(async {
-            let (world_diff, _, _) =
+            let (world_diff, provider, _) =
                 utils::get_world_diff_and_provider(starknet.clone(), world, &ws).await?;
 
-            if let Some(resource) = resource {
-                inspect_resource(&resource, &world_diff);
+            if let Some(resource) = &resource {
+                inspect_resource(resource, &world_diff);
             } else {
                 inspect_world(&world_diff);
             }
 
+            if metadata {
+                inspect_metadata(&world_diff, &provider, resource.as_deref()).await?;
+            }
+
             Ok(())
         })
     }
@@ -165,13 +177,31 @@ struct GranteeDisplay {
     source: GranteeSource,
 }
 
-/// Inspects a resource.
-fn inspect_resource(resource_name_or_tag: &str, world_diff: &WorldDiff) {
-    let selector = if naming::is_valid_tag(resource_name_or_tag) {
+#[derive(Debug, Tabled)]
+struct MetadataInspect {
+    #[tabled(rename = "Metadata")]
+    tag: String,
+    #[tabled(rename = "Dojo Selector")]
+    selector: String,
+    #[tabled(rename = "URI")]
+    uri: String,
+}
+
+/// The selector of the world in the resources of the world contract.
+const WORLD_SELECTOR: Felt = Felt::ZERO;
+
+/// Computes the selector of a resource from its tag, or from its name for namespaces.
+fn resource_selector(resource_name_or_tag: &str) -> Felt {
+    if naming::is_valid_tag(resource_name_or_tag) {
         naming::compute_selector_from_tag(resource_name_or_tag)
     } else {
         naming::compute_bytearray_hash(resource_name_or_tag)
-    };
+    }
+}
+
+/// Inspects a resource.
+fn inspect_resource(resource_name_or_tag: &str, world_diff: &WorldDiff) {
+    let selector = resource_selector(resource_name_or_tag);
     let resource_diff = world_diff.resources.get(&selector);
 
     if resource_diff.is_none() {
@@ -301,6 +331,63 @@ fn inspect_world(world_diff: &WorldDiff) {
     print_table(&events_disp, Some(Color::FG_BRIGHT_BLACK), None);
 }
 
+/// Inspects the metadata URIs published onchain for the world and its deployed resources, or for
+/// the given resource only.
+async fn inspect_metadata<P>(
+    world_diff: &WorldDiff,
+    provider: P,
+    resource_name_or_tag: Option<&str>,
+) -> Result<()>
+where
+    P: Provider + Sync,
+{
+    if let WorldStatus::NotDeployed = world_diff.world_info.status {
+        println!("World not deployed, no metadata published yet.");
+        return Ok(());
+    }
+
+    let mut resources = vec![];
+
+    if let Some(resource_name_or_tag) = resource_name_or_tag {
+        match world_diff.resources.get(&resource_selector(resource_name_or_tag)) {
+            Some(ResourceDiff::Created(_)) => {
+                println!("Resource not deployed, no metadata published yet.");
+                return Ok(());
+            }
+            // Already reported by the resource inspection.
+            None => return Ok(()),
+            Some(resource) => resources.push((resource.tag(), resource.dojo_selector())),
+        }
+    } else {
+        for resource in world_diff.resources.values() {
+            if !matches!(resource, ResourceDiff::Created(_)) {
+                resources.push((resource.tag(), resource.dojo_selector()));
+            }
+        }
+
+        resources.sort();
+        resources.insert(0, ("World".to_string(), WORLD_SELECTOR));
+    }
+
+    let world = WorldContractReader::new(world_diff.world_info.address, provider);
+    let mut metadata_disp = vec![];
+
+    for (tag, selector) in resources {
+        let metadata = world.metadata(&selector).call().await?;
+        let uri = metadata.metadata_uri.to_string()?;
+
+        metadata_disp.push(MetadataInspect {
+            tag,
+            selector: format!("{:#066x}", selector),
+            uri: if uri.is_empty() { "Not set".to_string() } else { uri },
+        });
+    }
+
+    print_table(&metadata_disp, Some(Color::FG_BRIGHT_BLACK), Some("\n> Metadata"));
+
+    Ok(())
+}
+
 /// Displays the resource diff with the address and class hash.
 fn resource_diff_display(world_diff: &WorldDiff, resource: &ResourceDiff) -> ResourceInspect {
     let n_local_writers_only = world_diff.get_writers(resource.dojo_selector()).only_local().len();
@@ -0,0 +1,188 @@
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use clap::Args;
+use dojo_utils::{Invoker, TxnConfig};
+use dojo_world::config::calldata_decoder;
+use scarb::core::Config;
+use sozo_ops::resource_descriptor::ResourceDescriptor;
+use sozo_scarbext::WorkspaceExt;
+use starknet::core::types::Call;
+use starknet::core::utils as snutils;
+use tokio::time::{self, MissedTickBehavior};
+use tracing::trace;
+
+use super::options::account::AccountOptions;
+use super::options::starknet::StarknetOptions;
+use super::options::transaction::TransactionOptions;
+use super::options::world::WorldOptions;
+use crate::utils;
+
+#[derive(Debug, Args)]
+#[command(about = "Repeatedly execute a system on an interval, retrying failed transactions.")]
+pub struct KeeperArgs {
+    #[arg(help = "The address or the tag (ex: dojo_examples:actions) of the contract to be \
+                  executed.")]
+    pub tag_or_address: ResourceDescriptor,
+
+    #[arg(help = "The name of the entrypoint to be executed.")]
+    pub entrypoint: String,
+
+    #[arg(short, long)]
+    #[arg(help = "The calldata to be passed to the system, in the same format as for `sozo \
+                  execute`.")]
+    pub calldata: Option<String>,
+
+    #[arg(long, value_name = "SECONDS", default_value_t = 60)]
+    #[arg(help = "The interval between two executions of the system.")]
+    pub interval: u64,
+
+    #[arg(long, default_value_t = 3)]
+    #[arg(help = "Number of times a failed execution is retried before waiting for the next \
+                  interval.")]
+    pub retries: u32,
+
+    #[arg(long, value_name = "SECONDS", default_value_t = 5)]
+    #[arg(help = "The delay before retrying a failed execution.")]
+    pub retry_delay: u64,
+
+    #[arg(long, value_name = "FACTOR", default_value_t = 1.2)]
+    #[arg(help = "The factor the fees are multiplied by on each retry.")]
+    #[arg(long_help = "The factor the fees are multiplied by on each retry. The max fee, gas \
+                       and gas price set with the transaction options are scaled, or else the \
+                       multiplier of the estimated fee for ETH transactions. The fees stay \
+                       capped by `--max-invoke-fee`.")]
+    pub fee_bump: f64,
+
+    #[arg(long, value_name = "COUNT")]
+    #[arg(help = "Stop after the given number of executions, instead of running until \
+                  interrupted.")]
+    pub runs: Option<u64>,
+
+    #[arg(long)]
+    #[arg(help = "If true, sozo will compute the diff of the world from the chain to translate \
+                  tags to addresses.")]
+    pub diff: bool,
+
+    #[command(flatten)]
+    pub starknet: StarknetOptions,
+
+    #[command(flatten)]
+    pub account: AccountOptions,
+
+    #[command(flatten)]
+    pub world: WorldOptions,
+
+    #[command(flatten)]
+    pub transaction: TransactionOptions,
+}
+
+impl KeeperArgs {
+    pub fn run(self, config: &Config) -> Result<()> {
+        trace!(args = ?self);
+
+        if self.interval == 0 {
+            bail!("The interval must be at least one second.");
+        }
+
+        if self.fee_bump < 1.0 {
+            bail!("The fee bump factor must be at least 1.");
+        }
+
+        let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
+        let profile_config = ws.load_profile_config()?;
+
+        let descriptor =
+            self.tag_or_address.clone().ensure_namespace(&profile_config.namespace.default);
+
+        // an execution is only considered done once the transaction is accepted, so that the
+        // failed ones are retried.
+        let mut txn_config: TxnConfig = self.transaction.clone().try_into()?;
+        txn_config.wait = true;
+
+        config.tokio_handle().block_on(async {
+            let (contract_address, contracts) = utils::resolve_contract_address(
+                &descriptor,
+                self.account.clone(),
+                self.starknet.clone(),
+                self.world.clone(),
+                &ws,
+                self.diff,
+            )
+            .await?;
+
+            let calldata = match &self.calldata {
+                Some(cd) => calldata_decoder::decode_calldata(cd)?,
+                None => vec![],
+            };
+
+            let call = Call {
+                calldata,
+                to: contract_address,
+                selector: snutils::get_selector_from_name(&self.entrypoint)?,
+            };
+
+            let (provider, _) = self.starknet.provider(profile_config.env.as_ref())?;
+            let account = self
+                .account
+                .account(provider, profile_config.env.as_ref(), &self.starknet, &contracts)
+                .await?;
+
+            println!("Executing {}::{} every {}s.", descriptor, self.entrypoint, self.interval);
+
+            let mut ticker = time::interval(Duration::from_secs(self.interval));
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            let mut run = 0;
+            while self.runs.map_or(true, |runs| run < runs) {
+                ticker.tick().await;
+                run += 1;
+
+                for attempt in 0..=self.retries {
+                    let txn_config = txn_config.with_fee_bump(self.fee_bump.powi(attempt as i32));
+                    let invoker = Invoker::new(&account, txn_config);
+
+                    match invoker.invoke(call.clone()).await {
+                        Ok(result) => {
+                            println!("Run #{run}: {result}");
+                            break;
+                        }
+                        Err(e) if attempt < self.retries => {
+                            println!("Run #{run}: attempt {} failed: {e}", attempt + 1);
+                            time::sleep(Duration::from_secs(self.retry_delay)).await;
+                        }
+                        Err(e) => {
+                            println!("Run #{run}: failed after {} attempts: {e}", attempt + 1);
+                        }
+                    }
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::Parser;
+
+    use super::*;
+
+    #[derive(Parser)]
+    struct Cli {
+        #[command(flatten)]
+        args: KeeperArgs,
+    }
+
+    #[test]
+    fn keeper_args_defaults() {
+        let cli = Cli::parse_from(["sozo", "ns-actions", "tick", "--interval", "10"]);
+
+        assert_eq!(cli.args.entrypoint, "tick");
+        assert_eq!(cli.args.interval, 10);
+        assert_eq!(cli.args.retries, 3);
+        assert_eq!(cli.args.fee_bump, 1.2);
+        assert_eq!(cli.args.runs, None);
+    }
+}
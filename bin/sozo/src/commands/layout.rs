@@ -0,0 +1,79 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use clap::{Args, Subcommand};
+use scarb::core::Config;
+use sozo_ops::layout;
+use sozo_scarbext::WorkspaceExt;
+use tracing::trace;
+
+/// Name of the JSON layout file written into the output directory.
+const LAYOUT_JSON: &str = "storage_layout.json";
+/// Name of the report written into the output directory.
+const LAYOUT_REPORT: &str = "storage_layout.md";
+
+#[derive(Debug, Args)]
+pub struct LayoutArgs {
+    #[command(subcommand)]
+    command: LayoutCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum LayoutCommand {
+    #[command(about = "Export the storage layout of the models of the world, built from the \
+                       local artifacts, as JSON and as a human-readable report")]
+    Export {
+        #[arg(short, long, default_value = "layout")]
+        #[arg(help = "Directory to write the layout files into")]
+        output_dir: PathBuf,
+
+        #[arg(long)]
+        #[arg(value_delimiter = ',')]
+        #[arg(help = "Comma separated tags of the models, and names of the types, deriving \
+                      `IntrospectPacked` e.g., ns-Position,Vec2. They can't be detected from \
+                      the artifacts")]
+        packed: Vec<String>,
+    },
+}
+
+impl LayoutArgs {
+    pub fn run(self, config: &Config) -> Result<()> {
+        trace!(args = ?self);
+
+        let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
+
+        match self.command {
+            LayoutCommand::Export { output_dir, packed } => {
+                let world_local = ws.load_world_local()?;
+                let packed = packed.into_iter().collect::<HashSet<_>>();
+
+                let world_layout = layout::world_layout(&world_local, &packed)?;
+
+                fs::create_dir_all(&output_dir).with_context(|| {
+                    format!("Failed to create directory `{}`.", output_dir.display())
+                })?;
+
+                let json_path = output_dir.join(LAYOUT_JSON);
+                fs::write(&json_path, serde_json::to_string_pretty(&world_layout)?)
+                    .with_context(|| format!("Failed to write `{}`.", json_path.display()))?;
+
+                let report_path = output_dir.join(LAYOUT_REPORT);
+                fs::write(&report_path, world_layout.report())
+                    .with_context(|| format!("Failed to write `{}`.", report_path.display()))?;
+
+                for model in &world_layout.models {
+                    println!("{}: {} storage entries", model.tag, model.entries.len());
+                }
+                println!(
+                    "Storage layout written to `{}` and `{}`.",
+                    json_path.display(),
+                    report_path.display()
+                );
+
+                Ok(())
+            }
+        }
+    }
+}
@@ -1,14 +1,28 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
 use anyhow::{Context, Result};
-use clap::Args;
+use clap::{Args, Subcommand};
 use colored::Colorize;
-use dojo_utils::{self, TxnConfig};
+use dojo_types::naming;
+use dojo_utils::{self, TransactionWaiter, TxnConfig};
+use dojo_world::config::ProfileConfig;
 use dojo_world::contracts::WorldContract;
+use dojo_world::diff::Manifest;
+use dojo_world::utils::compute_dojo_contract_address;
 use scarb::core::{Config, Workspace};
-use sozo_ops::migrate::{Migration, MigrationResult};
+use sozo_ops::migrate::journal::{
+    MigrationProgress, MigrationState, MigrationStep, MIGRATION_JOURNAL_JSON,
+};
+use sozo_ops::migrate::{Migration, MigrationJournal, MigrationResult};
 use sozo_ops::migration_ui::MigrationUi;
+use sozo_ops::upgrade_check::{self, WorldCompatibility};
 use sozo_scarbext::WorkspaceExt;
+use starknet::accounts::ConnectedAccount;
+use starknet::core::types::{
+    Felt, StarknetError, TransactionExecutionStatus, TransactionFinalityStatus, TransactionStatus,
+};
 use starknet::core::utils::parse_cairo_short_string;
-use starknet::providers::Provider;
+use starknet::providers::{Provider, ProviderError};
 use tabled::settings::Style;
 use tabled::{Table, Tabled};
 use tracing::trace;
@@ -20,7 +34,11 @@ use super::options::world::WorldOptions;
 use crate::utils;
 
 #[derive(Debug, Clone, Args)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct MigrateArgs {
+    #[command(subcommand)]
+    pub command: Option<MigrateCommand>,
+
     #[command(flatten)]
     pub transaction: TransactionOptions,
 
@@ -32,18 +50,61 @@ pub struct MigrateArgs {
 
     #[command(flatten)]
     pub account: AccountOptions,
+
+    /// Wait for the transactions of the migration to be accepted on L1 before exiting.
+    #[arg(long)]
+    pub watch_finality: bool,
+
+    /// The maximum time to wait for the transactions to be accepted on L1, in seconds.
+    #[arg(long, value_name = "SECONDS", default_value_t = DEFAULT_FINALITY_TIMEOUT)]
+    #[arg(requires = "watch_finality")]
+    pub finality_timeout: u64,
+
+    /// Print the addresses of the world and of its contracts, without migrating.
+    ///
+    /// The contracts deployed by the last migration keep the address recorded in its manifest,
+    /// the address of the others is computed from the seed and their class hash.
+    #[arg(long)]
+    #[arg(conflicts_with = "watch_finality")]
+    pub print_addresses: bool,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum MigrateCommand {
+    #[command(about = "Show the progress of the running or last migration, from its journal")]
+    Status(MigrateStatusArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct MigrateStatusArgs {
+    #[command(flatten)]
+    pub starknet: StarknetOptions,
 }
 
+/// The default maximum time to wait for the transactions to be accepted on L1, in seconds.
+pub const DEFAULT_FINALITY_TIMEOUT: u64 = 3600;
+
+/// The interval between two checks of the finality status of a transaction, in milliseconds.
+const FINALITY_POLL_INTERVAL_MS: u64 = 10_000;
+
 impl MigrateArgs {
     /// Runs the migration.
     pub fn run(self, config: &Config) -> Result<()> {
         trace!(args = ?self);
 
+        if let Some(MigrateCommand::Status(args)) = self.command {
+            return args.run(config);
+        }
+
         let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
         ws.profile_check()?;
         ws.ensure_profile_artifacts()?;
 
-        let MigrateArgs { world, starknet, account, .. } = self;
+        if self.print_addresses {
+            return print_addresses(&ws, &self.world);
+        }
+
+        let MigrateArgs { world, starknet, account, watch_finality, finality_timeout, .. } = self;
 
         config.tokio_handle().block_on(async {
             print_banner(&ws, &starknet).await?;
@@ -61,18 +122,37 @@ impl MigrateArgs {
 
             let world_address = world_diff.world_info.address;
 
+            // An upgrade removing entrypoints of the deployed world breaks their callers.
+            let check = upgrade_check::check_world_upgrade(&world_diff, account.provider()).await?;
+            if check.compatibility() == WorldCompatibility::Breaking {
+                spinner.stop_and_persist_boxed("⚠️ ", check.migration_path());
+                spinner.restart("Migrating...");
+            }
+
+            let profile_config = ws.load_profile_config()?;
+
             let mut txn_config: TxnConfig = self.transaction.try_into()?;
             txn_config.wait = true;
 
+            // The fee ceilings of the command line take precedence over the profile ones.
+            if let Some(migration) = &profile_config.migration {
+                txn_config.max_declare_fee =
+                    txn_config.max_declare_fee.or(migration.max_declare_fee);
+                txn_config.max_invoke_fee = txn_config.max_invoke_fee.or(migration.max_invoke_fee);
+            }
+
+            let journal_path =
+                ws.target_dir_profile().path_unchecked().join(MIGRATION_JOURNAL_JSON);
             let migration = Migration::new(
                 world_diff,
                 WorldContract::new(world_address, &account),
                 txn_config,
-                ws.load_profile_config()?,
+                profile_config,
                 rpc_url,
-            );
+            )
+            .with_journal(MigrationJournal::new(journal_path, world_address));
 
-            let MigrationResult { manifest, has_changes } =
+            let MigrationResult { manifest, has_changes, transactions } =
                 migration.migrate(&mut spinner).await.context("Migration failed.")?;
 
             spinner.update_text("Writing manifest...");
@@ -88,11 +168,201 @@ impl MigrateArgs {
 
             spinner.stop_and_persist_boxed(symbol, end_text);
 
+            if watch_finality && !transactions.is_empty() {
+                spinner.restart("Waiting for L1 finality...");
+
+                let timeout = Duration::from_secs(finality_timeout);
+                wait_for_l1_finality(account.provider(), &transactions, timeout, &mut spinner)
+                    .await?;
+
+                let end_text = format!("{} transactions accepted on L1", transactions.len());
+                spinner.stop_and_persist_boxed("🔒", end_text);
+            }
+
             Ok(())
         })
     }
 }
 
+/// Waits for all the `transactions` to be accepted on L1, reporting the progress on the spinner.
+///
+/// Fails if a transaction is reverted, or if they are not all accepted within `timeout`.
+async fn wait_for_l1_finality<P>(
+    provider: &P,
+    transactions: &[Felt],
+    timeout: Duration,
+    spinner: &mut MigrationUi,
+) -> Result<()>
+where
+    P: Provider + Send,
+{
+    let deadline = Instant::now() + timeout;
+
+    for (accepted, hash) in transactions.iter().enumerate() {
+        spinner.update_text_boxed(format!(
+            "Waiting for L1 finality ({}/{} transactions accepted)...",
+            accepted,
+            transactions.len()
+        ));
+
+        TransactionWaiter::new(*hash, provider)
+            .with_tx_status(TransactionFinalityStatus::AcceptedOnL1)
+            .with_interval(FINALITY_POLL_INTERVAL_MS)
+            .with_timeout(deadline.saturating_duration_since(Instant::now()))
+            .await
+            .with_context(|| format!("Transaction {:#066x} was not accepted on L1.", hash))?;
+    }
+
+    Ok(())
+}
+
+impl MigrateStatusArgs {
+    /// Prints the progress of the migration of the current profile, from its journal.
+    pub fn run(self, config: &Config) -> Result<()> {
+        trace!(args = ?self);
+
+        let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
+        let path = ws.target_dir_profile().path_unchecked().join(MIGRATION_JOURNAL_JSON);
+
+        let Some(progress) =
+            MigrationProgress::read(&path).with_context(|| format!("Failed to read `{path}`."))?
+        else {
+            println!("No migration journal found for the current profile.");
+            return Ok(());
+        };
+
+        // The status of the transactions is only shown if the chain is reachable.
+        let env = ws.load_profile_config().ok().and_then(|c| c.env);
+        let provider = self.starknet.provider(env.as_ref()).ok().map(|(provider, _)| provider);
+
+        config.tokio_handle().block_on(print_progress(&progress, provider.as_ref()));
+        Ok(())
+    }
+}
+
+/// Prints the steps of a migration and the status of their transactions.
+async fn print_progress<P>(progress: &MigrationProgress, provider: Option<&P>)
+where
+    P: Provider + Sync,
+{
+    let state = match progress.state {
+        MigrationState::Running => {
+            format!("running, or interrupted (pid {})", progress.pid).yellow()
+        }
+        MigrationState::Completed => "completed".green(),
+        MigrationState::Failed => "failed".red(),
+    };
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    println!("World       : {:#066x}", progress.world_address);
+    println!("State       : {state}");
+    println!("Started     : {}s ago", now.saturating_sub(progress.started_at));
+    println!("Last update : {}s ago", now.saturating_sub(progress.updated_at));
+    println!();
+
+    for MigrationStep { description, transactions, done } in &progress.steps {
+        let symbol = if *done { "✓".green() } else { "…".yellow() };
+        println!("{symbol} {description}");
+
+        for hash in transactions {
+            let status = match provider {
+                Some(provider) => transaction_status(provider, *hash).await,
+                None => "unknown".to_string(),
+            };
+            println!("    {hash:#066x} {status}");
+        }
+    }
+}
+
+async fn transaction_status<P>(provider: &P, hash: Felt) -> String
+where
+    P: Provider + Sync,
+{
+    match provider.get_transaction_status(hash).await {
+        Ok(TransactionStatus::Received) => "received".yellow().to_string(),
+        Ok(TransactionStatus::Rejected) => "rejected".red().to_string(),
+        Ok(TransactionStatus::AcceptedOnL2(TransactionExecutionStatus::Reverted))
+        | Ok(TransactionStatus::AcceptedOnL1(TransactionExecutionStatus::Reverted)) => {
+            "reverted".red().to_string()
+        }
+        Ok(TransactionStatus::AcceptedOnL2(_)) => "accepted on L2".green().to_string(),
+        Ok(TransactionStatus::AcceptedOnL1(_)) => "accepted on L1".green().to_string(),
+        Err(ProviderError::StarknetError(StarknetError::TransactionHashNotFound)) => {
+            "not found".red().to_string()
+        }
+        Err(_) => "unknown".to_string(),
+    }
+}
+
+#[derive(Debug, Tabled)]
+struct AddressPreview {
+    #[tabled(rename = "Resource")]
+    tag: String,
+    #[tabled(rename = "Contract Address")]
+    address: String,
+}
+
+/// Prints the deterministic addresses of the world and of its contracts.
+///
+/// The addresses are computed from the local world only, so they can be known before the world
+/// is deployed. Contracts already deployed keep the address they were deployed at, even if their
+/// class has changed since.
+fn print_addresses(ws: &Workspace<'_>, world: &WorldOptions) -> Result<()> {
+    let profile_config = ws.load_profile_config()?;
+    let world_local = ws.load_world_local()?;
+    let world_address = utils::get_world_address(&profile_config, world, &world_local)?;
+
+    // the manifest of another world doesn't tell where the contracts of this one are
+    let manifest = ws.read_manifest_profile()?.filter(|m| m.world.address == world_address);
+
+    let contracts = world_local.resources.iter().filter_map(|(selector, resource)| {
+        let contract = resource.as_contract()?;
+        Some((*selector, resource.tag(), contract.common.class_hash))
+    });
+    let contracts =
+        contract_addresses(contracts, &profile_config, world_address, manifest.as_ref());
+
+    let world =
+        AddressPreview { tag: "world".to_string(), address: format!("{:#066x}", world_address) };
+    let rows = std::iter::once(world).chain(contracts);
+
+    println!("{}", Table::new(rows).with(Style::psql()));
+
+    Ok(())
+}
+
+/// Returns the addresses of the contracts migrated to the world, given their selector, tag and
+/// local class hash, ordered by tag.
+///
+/// The address of a contract depends on the class it was first deployed with, so the contracts
+/// already deployed take the address recorded in the manifest of the last migration.
+fn contract_addresses(
+    contracts: impl IntoIterator<Item = (Felt, String, Felt)>,
+    profile_config: &ProfileConfig,
+    world_address: Felt,
+    manifest: Option<&Manifest>,
+) -> Vec<AddressPreview> {
+    let deployed = manifest.map(|m| m.contracts.as_slice()).unwrap_or_default();
+
+    let mut contracts = contracts
+        .into_iter()
+        .filter(|(_, tag, _)| {
+            !profile_config.is_skipped(tag)
+                && !profile_config.is_ignored(&naming::get_namespace_from_tag(tag), tag)
+        })
+        .map(|(selector, tag, class_hash)| {
+            let address = match deployed.iter().find(|c| c.tag == tag) {
+                Some(contract) => contract.address,
+                None => compute_dojo_contract_address(selector, class_hash, world_address),
+            };
+            AddressPreview { tag, address: format!("{:#066x}", address) }
+        })
+        .collect::<Vec<_>>();
+
+    contracts.sort_by(|a, b| a.tag.cmp(&b.tag));
+    contracts
+}
+
 #[derive(Debug, Tabled)]
 pub struct Banner {
     pub profile: String,
@@ -121,3 +391,54 @@ async fn print_banner(ws: &Workspace<'_>, starknet: &StarknetOptions) -> Result<
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use dojo_world::config::migration_config::MigrationConfig;
+    use dojo_world::diff::DojoContract;
+
+    use super::*;
+
+    #[test]
+    fn contract_addresses_of_deployed_and_skipped_contracts() {
+        let world_address = Felt::from(0x1234);
+        let contracts = [
+            (Felt::ONE, "ns-actions".to_string(), Felt::from(10)),
+            (Felt::TWO, "ns-others".to_string(), Felt::from(20)),
+            (Felt::THREE, "ns-skipped".to_string(), Felt::from(30)),
+        ];
+
+        let profile_config = ProfileConfig {
+            migration: Some(MigrationConfig {
+                skip_contracts: Some(vec!["ns-skipped".to_string()]),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        // `ns-actions` was upgraded since it was deployed at this address
+        let manifest = Manifest {
+            contracts: vec![DojoContract {
+                tag: "ns-actions".to_string(),
+                address: Felt::from(0xabcd),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+
+        let addresses =
+            contract_addresses(contracts, &profile_config, world_address, Some(&manifest));
+        let addresses =
+            addresses.iter().map(|c| (c.tag.as_str(), c.address.as_str())).collect::<Vec<_>>();
+
+        let actions = format!("{:#066x}", Felt::from(0xabcd));
+        let others = format!(
+            "{:#066x}",
+            compute_dojo_contract_address(Felt::TWO, Felt::from(20), world_address)
+        );
+        assert_eq!(
+            addresses,
+            vec![("ns-actions", actions.as_str()), ("ns-others", others.as_str())]
+        );
+    }
+}
@@ -1,5 +1,6 @@
 use core::fmt;
 
+use abi::AbiArgs;
 use anyhow::Result;
 use auth::AuthArgs;
 use clap::Subcommand;
@@ -7,35 +8,55 @@ use events::EventsArgs;
 use scarb::core::{Config, Package, Workspace};
 use tracing::info_span;
 
+pub(crate) mod abi;
 pub(crate) mod auth;
 pub(crate) mod build;
 pub(crate) mod call;
 pub(crate) mod clean;
+pub(crate) mod coverage;
 pub(crate) mod dev;
+pub(crate) mod diff;
+pub(crate) mod doctor;
+pub(crate) mod estimate;
 pub(crate) mod events;
 pub(crate) mod execute;
+pub(crate) mod fuzz;
 pub(crate) mod hash;
 pub(crate) mod init;
 pub(crate) mod inspect;
+pub(crate) mod keeper;
+pub(crate) mod layout;
 pub(crate) mod migrate;
 pub(crate) mod model;
 pub(crate) mod options;
+pub(crate) mod state;
 pub(crate) mod test;
+pub(crate) mod upgrade_check;
 
 use build::BuildArgs;
 use call::CallArgs;
 use clean::CleanArgs;
 use dev::DevArgs;
+use diff::DiffArgs;
+use doctor::DoctorArgs;
+use estimate::EstimateArgs;
 use execute::ExecuteArgs;
+use fuzz::FuzzArgs;
 use hash::HashArgs;
 use init::InitArgs;
 use inspect::InspectArgs;
+use keeper::KeeperArgs;
+use layout::LayoutArgs;
 use migrate::MigrateArgs;
 use model::ModelArgs;
+use state::StateArgs;
 use test::TestArgs;
+use upgrade_check::UpgradeCheckArgs;
 
 #[derive(Debug, Subcommand)]
 pub enum Commands {
+    #[command(about = "Export the ABI of the world and the schemas of its events")]
+    Abi(Box<AbiArgs>),
     #[command(about = "Grant or revoke a contract permission to write to a resource")]
     Auth(Box<AuthArgs>),
     #[command(about = "Build the world, generating the necessary artifacts for deployment")]
@@ -47,8 +68,16 @@ pub enum Commands {
     Migrate(Box<MigrateArgs>),
     #[command(about = "Execute a system with the given calldata.")]
     Execute(Box<ExecuteArgs>),
+    #[command(about = "Estimate the fee of executing a system with the given calldata.")]
+    Estimate(Box<EstimateArgs>),
+    #[command(about = "Repeatedly execute a system on an interval, retrying failed transactions.")]
+    Keeper(Box<KeeperArgs>),
+    #[command(about = "Fuzz the systems of a contract on an ephemeral fork of the migrated world")]
+    Fuzz(Box<FuzzArgs>),
     #[command(about = "Inspect the world")]
     Inspect(Box<InspectArgs>),
+    #[command(about = "Export the storage layout of the world")]
+    Layout(Box<LayoutArgs>),
     #[command(about = "Clean the build directory")]
     Clean(Box<CleanArgs>),
     #[command(about = "Call a contract")]
@@ -63,17 +92,32 @@ pub enum Commands {
     Model(Box<ModelArgs>),
     #[command(about = "Inspect events emitted by the world")]
     Events(Box<EventsArgs>),
+    #[command(about = "Dump or load the entities of a world")]
+    State(Box<StateArgs>),
+    #[command(about = "Check the compatibility of the local world class with the deployed world")]
+    UpgradeCheck(Box<UpgradeCheckArgs>),
+    #[command(about = "Compare the built world with the deployed world, with exit codes for CI")]
+    Diff(Box<DiffArgs>),
+    #[command(about = "Diagnose common problems of the toolchain, the chain and the manifest")]
+    Doctor(Box<DoctorArgs>),
 }
 
 impl fmt::Display for Commands {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            Commands::Abi(_) => write!(f, "Abi"),
             Commands::Auth(_) => write!(f, "Auth"),
             Commands::Build(_) => write!(f, "Build"),
             Commands::Clean(_) => write!(f, "Clean"),
             Commands::Dev(_) => write!(f, "Dev"),
+            Commands::Diff(_) => write!(f, "Diff"),
+            Commands::Doctor(_) => write!(f, "Doctor"),
             Commands::Execute(_) => write!(f, "Execute"),
+            Commands::Estimate(_) => write!(f, "Estimate"),
+            Commands::Fuzz(_) => write!(f, "Fuzz"),
             Commands::Inspect(_) => write!(f, "Inspect"),
+            Commands::Keeper(_) => write!(f, "Keeper"),
+            Commands::Layout(_) => write!(f, "Layout"),
             Commands::Migrate(_) => write!(f, "Migrate"),
             Commands::Call(_) => write!(f, "Call"),
             Commands::Test(_) => write!(f, "Test"),
@@ -81,6 +125,8 @@ impl fmt::Display for Commands {
             Commands::Init(_) => write!(f, "Init"),
             Commands::Model(_) => write!(f, "Model"),
             Commands::Events(_) => write!(f, "Events"),
+            Commands::State(_) => write!(f, "State"),
+            Commands::UpgradeCheck(_) => write!(f, "UpgradeCheck"),
         }
     }
 }
@@ -94,12 +140,17 @@ pub fn run(command: Commands, config: &Config) -> Result<()> {
     // useful to write tests for each command.
 
     match command {
+        Commands::Abi(args) => args.run(config),
         Commands::Auth(args) => args.run(config),
         Commands::Build(args) => args.run(config),
         Commands::Dev(args) => args.run(config),
         Commands::Migrate(args) => args.run(config),
         Commands::Execute(args) => args.run(config),
+        Commands::Estimate(args) => args.run(config),
+        Commands::Fuzz(args) => args.run(config),
         Commands::Inspect(args) => args.run(config),
+        Commands::Keeper(args) => args.run(config),
+        Commands::Layout(args) => args.run(config),
         Commands::Clean(args) => args.run(config),
         Commands::Call(args) => args.run(config),
         Commands::Test(args) => args.run(config),
@@ -107,6 +158,10 @@ pub fn run(command: Commands, config: &Config) -> Result<()> {
         Commands::Init(args) => args.run(config),
         Commands::Model(args) => args.run(config),
         Commands::Events(args) => args.run(config),
+        Commands::State(args) => args.run(config),
+        Commands::UpgradeCheck(args) => args.run(config),
+        Commands::Diff(args) => args.run(config),
+        Commands::Doctor(args) => args.run(config),
     }
 }
 
@@ -1,9 +1,10 @@
 use std::fmt::{Display, Formatter};
+use std::time::Duration;
 
 use anyhow::{bail, Result};
 use clap::builder::PossibleValue;
 use clap::{Args, ValueEnum};
-use dojo_utils::{EthFeeConfig, FeeConfig, StrkFeeConfig, TxnAction, TxnConfig};
+use dojo_utils::{EthFeeConfig, FeeBumpConfig, FeeConfig, StrkFeeConfig, TxnAction, TxnConfig};
 use starknet::core::types::Felt;
 
 #[derive(Debug, Clone, Args, Default)]
@@ -44,6 +45,46 @@ pub struct TransactionOptions {
     #[arg(global = true)]
     pub gas_price: Option<u128>,
 
+    #[arg(long, value_name = "FEE")]
+    #[arg(help = "Maximum fee a declare transaction can cost, in the smallest unit of the fee \
+                  token.")]
+    #[arg(long_help = "Maximum fee a declare transaction can cost, in the smallest unit of the \
+                       fee token (Wei or Fri). The transaction is not sent if its estimated fee \
+                       exceeds this value, and its max fee is capped to it otherwise.")]
+    #[arg(global = true)]
+    pub max_declare_fee: Option<Felt>,
+
+    #[arg(long, value_name = "FEE")]
+    #[arg(help = "Maximum fee an invoke transaction can cost, in the smallest unit of the fee \
+                  token.")]
+    #[arg(long_help = "Maximum fee an invoke transaction can cost, in the smallest unit of the \
+                       fee token (Wei or Fri). The transaction is not sent if its estimated fee \
+                       exceeds this value, and its max fee is capped to it otherwise.")]
+    #[arg(global = true)]
+    pub max_invoke_fee: Option<Felt>,
+
+    #[arg(long, value_name = "SECONDS")]
+    #[arg(help = "Replace a transaction with bumped fees if it's not accepted after this \
+                  duration.")]
+    #[arg(long_help = "Replace a transaction with bumped fees if it's not accepted after this \
+                       duration, eg because it's stuck in the mempool. Only used when waiting \
+                       for the transactions. The fees are never bumped above the max declare or \
+                       invoke fee.")]
+    #[arg(global = true)]
+    pub replace_after: Option<u64>,
+
+    #[arg(long, value_name = "FACTOR")]
+    #[arg(help = "The factor the fees of a transaction are multiplied by on each replacement.")]
+    #[arg(requires = "replace_after")]
+    #[arg(global = true)]
+    pub replacement_fee_bump: Option<f64>,
+
+    #[arg(long, value_name = "COUNT")]
+    #[arg(help = "The maximum number of times a transaction is replaced.")]
+    #[arg(requires = "replace_after")]
+    #[arg(global = true)]
+    pub max_replacements: Option<u32>,
+
     #[arg(long)]
     #[arg(help = "Wait until the transaction is accepted by the sequencer, returning the status \
                   and hash.")]
@@ -119,6 +160,21 @@ impl TryFrom<TransactionOptions> for TxnConfig {
             }
         };
 
+        let fee_bump = value.replace_after.map(|seconds| {
+            let default = FeeBumpConfig::default();
+            FeeBumpConfig {
+                deadline: Duration::from_secs(seconds),
+                factor: value.replacement_fee_bump.unwrap_or(default.factor),
+                max_replacements: value.max_replacements.unwrap_or(default.max_replacements),
+            }
+        });
+
+        if let Some(FeeBumpConfig { factor, .. }) = fee_bump {
+            if factor <= 1.0 {
+                bail!("The replacement fee bump must be greater than 1, got {factor}.");
+            }
+        }
+
         Ok(Self {
             wait: value.wait || value.walnut,
             receipt: value.receipt,
@@ -132,6 +188,9 @@ impl TryFrom<TransactionOptions> for TxnConfig {
                 }),
             },
             walnut: value.walnut,
+            max_declare_fee: value.max_declare_fee,
+            max_invoke_fee: value.max_invoke_fee,
+            fee_bump,
         })
     }
 }
@@ -181,6 +240,11 @@ mod tests {
             gas_price: Some(100),
             max_fee_raw: None,
             fee_estimate_multiplier: None,
+            max_declare_fee: Some(Felt::from(10000)),
+            max_invoke_fee: None,
+            replace_after: Some(30),
+            replacement_fee_bump: None,
+            max_replacements: Some(5),
             walnut: false,
         };
 
@@ -189,6 +253,16 @@ mod tests {
         assert!(config.wait);
         assert!(config.receipt);
         assert!(!config.walnut);
+        assert_eq!(config.max_declare_fee, Some(Felt::from(10000)));
+        assert_eq!(config.max_invoke_fee, None);
+        assert_eq!(
+            config.fee_bump,
+            Some(FeeBumpConfig {
+                deadline: Duration::from_secs(30),
+                factor: 1.5,
+                max_replacements: 5
+            })
+        );
 
         match config.fee_config {
             FeeConfig::Strk(strk_config) => {
@@ -211,6 +285,11 @@ mod tests {
             gas_price: None,
             max_fee_raw: Some(Felt::from(1000)),
             fee_estimate_multiplier: Some(1.5),
+            max_declare_fee: None,
+            max_invoke_fee: None,
+            replace_after: None,
+            replacement_fee_bump: None,
+            max_replacements: None,
             walnut: true,
         };
 
@@ -219,6 +298,7 @@ mod tests {
         assert!(config.wait);
         assert!(config.receipt);
         assert!(config.walnut);
+        assert_eq!(config.fee_bump, None);
 
         match config.fee_config {
             FeeConfig::Eth(eth_config) => {
@@ -256,6 +336,17 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_invalid_replacement_fee_bump() {
+        let opts = TransactionOptions {
+            replace_after: Some(30),
+            replacement_fee_bump: Some(0.9),
+            ..Default::default()
+        };
+        let result: Result<TxnConfig, _> = opts.try_into();
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_fee_token_display() {
         assert_eq!(FeeToken::Eth.to_string(), "ETH");
@@ -0,0 +1,165 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Args, Subcommand};
+use dojo_utils::TxnConfig;
+use scarb::core::Config;
+use sozo_ops::resource_descriptor::ResourceDescriptor;
+use sozo_ops::state::{self, Remapping, StateDump};
+use sozo_scarbext::WorkspaceExt;
+use starknet::core::types::{BlockId, BlockTag};
+use tracing::trace;
+
+use super::options::account::AccountOptions;
+use super::options::starknet::StarknetOptions;
+use super::options::transaction::TransactionOptions;
+use super::options::world::WorldOptions;
+use crate::utils;
+
+#[derive(Debug, Args)]
+pub struct StateArgs {
+    #[command(subcommand)]
+    command: StateCommand,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum StateCommand {
+    #[command(about = "Dump all the entities of the given models into a file")]
+    Dump {
+        #[arg(long)]
+        #[arg(value_delimiter = ',')]
+        #[arg(help = "Comma separated tags or names of the models to dump e.g., \
+                      ns-Position,ns-Health")]
+        models: Vec<ResourceDescriptor>,
+
+        #[arg(short, long)]
+        #[arg(help = "Path of the file to write the state dump into")]
+        output: PathBuf,
+
+        #[arg(short, long)]
+        #[arg(help = "Block number at which to read the entities (pending block by default)")]
+        block: Option<u64>,
+
+        #[command(flatten)]
+        world: WorldOptions,
+
+        #[command(flatten)]
+        starknet: StarknetOptions,
+    },
+
+    #[command(about = "Write the entities of a state dump into a world")]
+    Load {
+        #[arg(short, long)]
+        #[arg(help = "Path of the state dump to load")]
+        input: PathBuf,
+
+        #[arg(long)]
+        #[arg(help = "Path of a JSON remapping file translating keys (and optionally values) \
+                      before the entities are written")]
+        remap: Option<PathBuf>,
+
+        #[arg(long, default_value_t = 50)]
+        #[arg(help = "Number of entities written per transaction")]
+        chunk_size: usize,
+
+        #[command(flatten)]
+        world: WorldOptions,
+
+        #[command(flatten)]
+        starknet: StarknetOptions,
+
+        #[command(flatten)]
+        account: AccountOptions,
+
+        #[command(flatten)]
+        transaction: TransactionOptions,
+    },
+}
+
+impl StateArgs {
+    pub fn run(self, config: &Config) -> Result<()> {
+        trace!(args = ?self);
+
+        let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
+        let profile_config = ws.load_profile_config()?;
+        let default_ns = profile_config.namespace.default.clone();
+
+        config.tokio_handle().block_on(async {
+            match self.command {
+                StateCommand::Dump { models, output, block, world, starknet } => {
+                    if models.is_empty() {
+                        anyhow::bail!("At least one model must be provided with `--models`.");
+                    }
+
+                    let tags = models
+                        .into_iter()
+                        .map(|m| m.ensure_namespace(&default_ns).to_string())
+                        .collect::<Vec<_>>();
+                    let block_id =
+                        block.map(BlockId::Number).unwrap_or(BlockId::Tag(BlockTag::Pending));
+                    let from_block = profile_config
+                        .env
+                        .as_ref()
+                        .and_then(|e| e.world_block)
+                        .map(BlockId::Number);
+
+                    let (world_diff, provider, _) =
+                        utils::get_world_diff_and_provider(starknet, world, &ws).await?;
+
+                    let dump = state::dump_state(
+                        &tags,
+                        world_diff.world_info.address,
+                        &provider,
+                        from_block,
+                        block_id,
+                    )
+                    .await?;
+
+                    dump.to_file(&output)?;
+
+                    for model in &dump.models {
+                        println!("{}: {} entities", model.tag, model.entities.len());
+                    }
+
+                    Ok(())
+                }
+                StateCommand::Load {
+                    input,
+                    remap,
+                    chunk_size,
+                    world,
+                    starknet,
+                    account,
+                    transaction,
+                } => {
+                    let mut dump = StateDump::from_file(&input)?;
+
+                    if let Some(remap) = remap {
+                        Remapping::from_file(&remap)?.apply(&mut dump);
+                    }
+
+                    let txn_config: TxnConfig = transaction.try_into()?;
+
+                    let (world_diff, account, _) =
+                        utils::get_world_diff_and_account(account, starknet, world, &ws, &mut None)
+                            .await?;
+
+                    let results = state::load_state(
+                        &dump,
+                        world_diff.world_info.address,
+                        &account,
+                        txn_config,
+                        chunk_size,
+                    )
+                    .await?;
+
+                    for result in results {
+                        println!("{}", result);
+                    }
+
+                    Ok(())
+                }
+            }
+        })
+    }
+}
@@ -15,6 +15,7 @@ use cairo_lang_starknet::starknet_plugin_suite;
 use cairo_lang_test_plugin::{test_plugin_suite, TestsCompilationConfig};
 use cairo_lang_test_runner::{CompiledTestRunner, RunProfilerConfig, TestCompiler, TestRunConfig};
 use cairo_lang_utils::ordered_hash_map::OrderedHashMap;
+use camino::Utf8PathBuf;
 use clap::Args;
 use dojo_lang::dojo_plugin_suite;
 use itertools::Itertools;
@@ -31,6 +32,7 @@ use tracing::trace;
 pub const WORLD_QUALIFIED_PATH: &str = "dojo::world::world_contract::world";
 
 use super::check_package_dojo_version;
+use super::coverage;
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
@@ -76,6 +78,13 @@ pub struct TestArgs {
     /// Should we print the resource usage.
     #[arg(long, default_value_t = false)]
     print_resource_usage: bool,
+    /// Should we collect the coverage of the contracts and write it as lcov reports.
+    #[arg(long, default_value_t = false)]
+    coverage: bool,
+    /// The directory to write the lcov reports into, one per contract.
+    #[arg(long, default_value = "coverage")]
+    #[arg(requires = "coverage")]
+    coverage_dir: Utf8PathBuf,
     /// Specify the features to activate.
     #[command(flatten)]
     features: FeaturesSpec,
@@ -169,21 +178,40 @@ impl TestArgs {
             let compiler = TestCompiler {
                 db: db.snapshot(),
                 main_crate_ids,
-                test_crate_ids,
+                test_crate_ids: test_crate_ids.clone(),
                 allow_warnings: true,
                 config: TestsCompilationConfig {
                     starknet: true,
                     add_statements_functions: false,
-                    add_statements_code_locations: false,
+                    add_statements_code_locations: self.coverage,
                 },
             };
 
             let compiled = compiler.build()?;
+
+            let coverage_reports = if self.coverage {
+                let statements = coverage::collect(&db, &compiled, &self.filter)?;
+                let contracts = coverage::contracts(&db, &test_crate_ids);
+                Some(coverage::lcov_reports(
+                    &statements,
+                    &contracts,
+                    unit.main_component().package.root(),
+                ))
+            } else {
+                None
+            };
+
             let runner = CompiledTestRunner { compiled, config };
 
             // Database is required here for the profiler to work.
             runner.run(Some(&db))?;
 
+            if let Some(reports) = coverage_reports {
+                for path in coverage::write_lcov_reports(&reports, &self.coverage_dir)? {
+                    println!("coverage written to {path}");
+                }
+            }
+
             println!();
         }
 
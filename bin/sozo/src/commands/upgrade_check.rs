@@ -0,0 +1,57 @@
+use anyhow::Result;
+use clap::Args;
+use colored::Colorize;
+use scarb::core::Config;
+use sozo_ops::upgrade_check::{self, WorldCompatibility};
+use tracing::trace;
+
+use super::options::starknet::StarknetOptions;
+use super::options::world::WorldOptions;
+use crate::utils;
+
+#[derive(Debug, Args)]
+pub struct UpgradeCheckArgs {
+    #[command(flatten)]
+    world: WorldOptions,
+
+    #[command(flatten)]
+    starknet: StarknetOptions,
+}
+
+impl UpgradeCheckArgs {
+    pub fn run(self, config: &Config) -> Result<()> {
+        trace!(args = ?self);
+
+        let ws = scarb::ops::read_workspace(config.manifest_path(), config)?;
+        let UpgradeCheckArgs { world, starknet } = self;
+
+        config.tokio_handle().block_on(async {
+            let (world_diff, provider, _) =
+                utils::get_world_diff_and_provider(starknet, world, &ws).await?;
+
+            let check = upgrade_check::check_world_upgrade(&world_diff, &provider).await?;
+
+            println!("sozo version        : {}", env!("CARGO_PKG_VERSION"));
+            println!("Local world class   : {:#066x}", check.local_class_hash);
+            match check.remote_class_hash {
+                Some(hash) => println!("Deployed world class: {:#066x}", hash),
+                None => println!("Deployed world class: none"),
+            }
+            println!();
+
+            let path = check.migration_path();
+            match check.compatibility() {
+                WorldCompatibility::Breaking => {
+                    println!("{}", path.yellow());
+                    anyhow::bail!("The local world is incompatible with the deployed world.");
+                }
+                WorldCompatibility::Upgradable => println!("{}", path.blue()),
+                WorldCompatibility::NotDeployed | WorldCompatibility::Synced => {
+                    println!("{}", path.green())
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
@@ -6,6 +6,7 @@ use std::process::exit;
 use anyhow::Result;
 use args::SozoArgs;
 use clap::Parser;
+use commands::Commands;
 use scarb::compiler::plugin::CairoPluginRepository;
 use scarb::compiler::CompilerRepository;
 use scarb::core::Config;
@@ -32,7 +33,10 @@ fn cli_main(args: SozoArgs) -> Result<()> {
 
     let manifest_path = scarb::ops::find_manifest_path(args.manifest_path.as_deref())?;
 
-    utils::verify_cairo_version_compatibility(&manifest_path)?;
+    // `sozo doctor` reports an incompatible Cairo version instead of failing on it.
+    if !matches!(args.command, Commands::Doctor(_)) {
+        utils::verify_cairo_version_compatibility(&manifest_path)?;
+    }
 
     let config = Config::builder(manifest_path.clone())
         .log_filter_directive(env::var_os("SCARB_LOG"))
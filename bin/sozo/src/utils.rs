@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::fs;
 use std::io::{self, Write};
 use std::str::FromStr;
 
@@ -13,6 +14,7 @@ use katana_rpc_api::starknet::RPC_SPEC_VERSION;
 use scarb::core::{TomlManifest, Workspace};
 use semver::Version;
 use sozo_ops::migration_ui::MigrationUi;
+use sozo_ops::resource_descriptor::ResourceDescriptor;
 use sozo_scarbext::WorkspaceExt;
 use starknet::accounts::{Account, ConnectedAccount};
 use starknet::core::types::Felt;
@@ -68,13 +70,25 @@ pub fn verify_cairo_version_compatibility(manifest_path: &Utf8PathBuf) -> Result
 
     let Some(cairo_version) = package.cairo_version else { return Ok(()) };
 
+    // A package pinned to another Cairo version is compiled by its own toolchain.
+    let raw_manifest = fs::read_to_string(manifest_path)?.parse::<toml::Table>()?;
+    if raw_manifest
+        .get("tool")
+        .and_then(|t| t.get("dojo"))
+        .and_then(|d| d.get("toolchain"))
+        .is_some()
+    {
+        return Ok(());
+    }
+
     // only when cairo version is found in manifest file confirm that it matches
     let version_req = cairo_version.as_defined().unwrap();
     let version = Version::from_str(scarb_cairo_version.version).unwrap();
     if !version_req.matches(&version) {
         anyhow::bail!(
             "Cairo version {} found in {} is not supported by dojo (expecting {}). Please change \
-             the Cairo version in your manifest or update dojo.",
+             the Cairo version in your manifest, update dojo, or set the sozo binary to compile \
+             the package with under `toolchain` in [tool.dojo].",
             version_req,
             manifest_path,
             version,
@@ -192,7 +206,7 @@ pub async fn get_world_diff_and_account(
 ///
 /// * `Result<bool>` - Returns `true` if the provided version is compatible with the expected
 ///   version, `false` otherwise.
-fn is_compatible_version(provided_version: &str, expected_version: &str) -> Result<bool> {
+pub fn is_compatible_version(provided_version: &str, expected_version: &str) -> Result<bool> {
     use semver::{Version, VersionReq};
 
     let provided_ver = Version::parse(provided_version)
@@ -237,6 +251,44 @@ pub async fn contracts_from_manifest_or_diff(
     Ok(contracts)
 }
 
+/// Resolves the address of the contract targeted by `descriptor`.
+///
+/// Tags are resolved using the contracts of the local manifest, or of the world diff if
+/// `force_diff` is set, which are returned alongside the address.
+pub async fn resolve_contract_address(
+    descriptor: &ResourceDescriptor,
+    account: AccountOptions,
+    starknet: StarknetOptions,
+    world: WorldOptions,
+    ws: &Workspace<'_>,
+    force_diff: bool,
+) -> Result<(Felt, HashMap<String, ContractInfo>)> {
+    let (contract_address, contracts) = match descriptor {
+        ResourceDescriptor::Address(address) => (Some(*address), Default::default()),
+        ResourceDescriptor::Tag(tag) => {
+            let contracts =
+                contracts_from_manifest_or_diff(account, starknet, world, ws, force_diff).await?;
+
+            (contracts.get(tag).map(|c| c.address), contracts)
+        }
+        ResourceDescriptor::Name(_) => {
+            unimplemented!("Expected to be a resolved tag with default namespace.")
+        }
+    };
+
+    let contract_address = contract_address.ok_or_else(|| {
+        let mut message = format!("Contract {descriptor} not found in the manifest.");
+        if force_diff {
+            message.push_str(
+                " Run the command again with `--diff` to force the fetch of data from the chain.",
+            );
+        }
+        anyhow!(message)
+    })?;
+
+    Ok((contract_address, contracts))
+}
+
 /// Prompts the user to confirm an operation.
 pub fn prompt_confirm(prompt: &str) -> Result<bool> {
     print!("{} [y/N]", prompt);
@@ -39,6 +39,7 @@ use torii_core::simple_broker::SimpleBroker;
 use torii_core::sql::cache::ModelCache;
 use torii_core::sql::Sql;
 use torii_core::types::{Contract, ContractType, Model};
+use torii_core::webhook::{WebhookConfig, WebhookSink};
 use torii_server::proxy::Proxy;
 use tracing::{error, info};
 use tracing_subscriber::{fmt, EnvFilter};
@@ -95,6 +96,8 @@ async fn main() -> anyhow::Result<()> {
     options = options.auto_vacuum(SqliteAutoVacuum::None);
     options = options.journal_mode(SqliteJournalMode::Wal);
     options = options.synchronous(SqliteSynchronous::Normal);
+    // The executors of the worlds hold the write lock until they commit the blocks they index.
+    options = options.busy_timeout(Duration::from_secs(60));
 
     let pool = SqlitePoolOptions::new().min_connections(1).connect_with(options).await?;
 
@@ -120,8 +123,21 @@ async fn main() -> anyhow::Result<()> {
     let executor_handle = tokio::spawn(async move { executor.run().await });
 
     let model_cache = Arc::new(ModelCache::new(pool.clone()));
-    let db = Sql::new(pool.clone(), sender.clone(), &args.indexing.contracts, model_cache.clone())
-        .await?;
+    // The cursors of the additional worlds are stored along the ones of the main world contracts.
+    let world_contracts = args
+        .indexing
+        .worlds
+        .iter()
+        .map(|world| Contract { address: world.address, r#type: ContractType::WORLD })
+        .collect::<Vec<_>>();
+    let db = Sql::new(
+        pool.clone(),
+        sender.clone(),
+        &[args.indexing.contracts.clone(), world_contracts].concat(),
+        model_cache.clone(),
+    )
+    .await?
+    .with_aggregations(args.indexing.aggregations.clone());
 
     let processors = Processors {
         transaction: vec![Box::new(StoreTransactionProcessor)],
@@ -138,29 +154,69 @@ async fn main() -> anyhow::Result<()> {
         flags.insert(IndexingFlags::RAW_EVENTS);
     }
 
+    let engine_config = EngineConfig {
+        max_concurrent_tasks: args.indexing.max_concurrent_tasks,
+        start_block: 0,
+        blocks_chunk_size: args.indexing.blocks_chunk_size,
+        events_chunk_size: args.indexing.events_chunk_size,
+        index_pending: args.indexing.pending,
+        polling_interval: Duration::from_millis(args.indexing.polling_interval),
+        flags,
+        event_processor_config: EventProcessorConfig {
+            historical_events: args.events.historical.into_iter().collect(),
+            namespaces: args.indexing.namespaces.into_iter().collect(),
+            excluded_models: args.indexing.excluded_models.into_iter().collect(),
+        },
+    };
+
     let mut engine: Engine<Arc<JsonRpcClient<HttpTransport>>> = Engine::new(
         world,
         db.clone(),
         provider.clone(),
         processors,
-        EngineConfig {
-            max_concurrent_tasks: args.indexing.max_concurrent_tasks,
-            start_block: 0,
-            blocks_chunk_size: args.indexing.blocks_chunk_size,
-            events_chunk_size: args.indexing.events_chunk_size,
-            index_pending: args.indexing.pending,
-            polling_interval: Duration::from_millis(args.indexing.polling_interval),
-            flags,
-            event_processor_config: EventProcessorConfig {
-                historical_events: args.events.historical.into_iter().collect(),
-                namespaces: args.indexing.namespaces.into_iter().collect(),
-            },
-        },
+        engine_config.clone(),
         shutdown_tx.clone(),
         Some(block_tx),
         &args.indexing.contracts,
     );
 
+    // Each additional world is indexed by its own engine, in models and entities isolated from the
+    // ones of the other worlds. Each engine has its own executor, so that the blocks of the worlds
+    // are committed independently.
+    let mut world_engines = Vec::new();
+    let mut world_executors = Vec::new();
+    for world in &args.indexing.worlds {
+        let provider: Arc<_> = match &world.rpc {
+            Some(rpc) => JsonRpcClient::new(HttpTransport::new(Url::parse(rpc)?)).into(),
+            None => provider.clone(),
+        };
+
+        info!(target: LOG_TARGET, world = %format!("{:#x}", world.address), "Indexing world.");
+
+        let (mut executor, sender) = Executor::new(
+            pool.clone(),
+            shutdown_tx.clone(),
+            provider.clone(),
+            args.indexing.max_concurrent_tasks,
+        )
+        .await?;
+        world_executors.push(tokio::spawn(async move { executor.run().await }));
+
+        world_engines.push(Engine::new(
+            WorldContractReader::new(world.address, provider.clone()),
+            db.clone().with_executor(sender).with_world(world.address),
+            provider,
+            Processors {
+                transaction: vec![Box::new(StoreTransactionProcessor)],
+                ..Processors::default()
+            },
+            engine_config.clone(),
+            shutdown_tx.clone(),
+            None,
+            &[Contract { address: world.address, r#type: ContractType::WORLD }],
+        ));
+    }
+
     let shutdown_rx = shutdown_tx.subscribe();
     let (grpc_addr, grpc_server) = torii_grpc::server::new(
         shutdown_rx,
@@ -182,16 +238,29 @@ async fn main() -> anyhow::Result<()> {
     let (artifacts_addr, artifacts_server) =
         torii_server::artifacts::new(shutdown_tx.subscribe(), &absolute_path, pool.clone()).await?;
 
-    let mut libp2p_relay_server = torii_relay::server::Relay::new(
+    let libp2p_relay_server = if args.relay.enabled {
+        Some(
+            torii_relay::server::Relay::new(
+                db.clone(),
+                provider.clone(),
+                args.relay.port,
+                args.relay.webrtc_port,
+                args.relay.websocket_port,
+                args.relay.local_key_path,
+                args.relay.cert_path,
+            )
+            .expect("Failed to start libp2p relay server"),
+        )
+    } else {
+        None
+    };
+
+    let (messages_addr, messages_server) = torii_relay::server::http::new(
+        shutdown_tx.subscribe(),
         db,
         provider.clone(),
-        args.relay.port,
-        args.relay.webrtc_port,
-        args.relay.websocket_port,
-        args.relay.local_key_path,
-        args.relay.cert_path,
-    )
-    .expect("Failed to start libp2p relay server");
+        libp2p_relay_server.as_ref().map(|relay| relay.publisher()),
+    );
 
     let addr = SocketAddr::new(args.server.http_addr, args.server.http_port);
 
@@ -201,6 +270,7 @@ async fn main() -> anyhow::Result<()> {
         Some(grpc_addr),
         None,
         Some(artifacts_addr),
+        Some(messages_addr),
     ));
 
     let graphql_server = spawn_rebuilding_graphql_server(
@@ -211,6 +281,7 @@ async fn main() -> anyhow::Result<()> {
     );
 
     let gql_endpoint = format!("{addr}/graphql");
+    let messages_endpoint = format!("{addr}/messages");
     let encoded: String =
         form_urlencoded::byte_serialize(gql_endpoint.replace("0.0.0.0", "localhost").as_bytes())
             .collect();
@@ -219,6 +290,7 @@ async fn main() -> anyhow::Result<()> {
     info!(target: LOG_TARGET, endpoint = %gql_endpoint, "Serving Graphql playground.");
     info!(target: LOG_TARGET, url = %explorer_url, "Serving World Explorer.");
     info!(target: LOG_TARGET, path = %artifacts_path, "Serving ERC artifacts at path");
+    info!(target: LOG_TARGET, endpoint = %messages_endpoint, "Accepting offchain messages.");
 
     if args.explorer {
         if let Err(e) = webbrowser::open(&explorer_url) {
@@ -234,22 +306,69 @@ async fn main() -> anyhow::Result<()> {
         tokio::spawn(server.start(addr));
     }
 
+    if let Some(url) = args.webhook.url {
+        info!(target: LOG_TARGET, %url, "Delivering entity updates to webhook.");
+        let sink = WebhookSink::new(
+            WebhookConfig {
+                url: url.to_string(),
+                secret: args.webhook.secret,
+                models: args.webhook.models.into_iter().collect(),
+                batch_size: args.webhook.batch_size,
+                flush_interval: Duration::from_millis(args.webhook.flush_interval),
+                max_retries: args.webhook.max_retries,
+            },
+            shutdown_tx.clone(),
+        );
+        tokio::spawn(async move {
+            if let Err(e) = sink.run().await {
+                error!(target: LOG_TARGET, error = %e, "Running webhook sink.");
+            }
+        });
+    }
+
     let engine_handle = tokio::spawn(async move { engine.start().await });
+    let world_engines_handle = tokio::spawn(async move {
+        if world_engines.is_empty() {
+            std::future::pending::<()>().await;
+        }
+        futures::future::try_join_all(world_engines.iter_mut().map(|engine| engine.start()))
+            .await
+            .map(|_| ())
+    });
+    let world_executors_handle = tokio::spawn(async move {
+        if world_executors.is_empty() {
+            std::future::pending::<()>().await;
+        }
+        futures::future::try_join_all(
+            world_executors.into_iter().map(|handle| async move { handle.await? }),
+        )
+        .await
+        .map(|_| ())
+    });
     let proxy_server_handle =
         tokio::spawn(async move { proxy_server.start(shutdown_tx.subscribe()).await });
     let graphql_server_handle = tokio::spawn(graphql_server);
     let grpc_server_handle = tokio::spawn(grpc_server);
-    let libp2p_relay_server_handle = tokio::spawn(async move { libp2p_relay_server.run().await });
+    let libp2p_relay_server_handle = tokio::spawn(async move {
+        match libp2p_relay_server {
+            Some(mut relay) => relay.run().await,
+            None => std::future::pending().await,
+        }
+    });
     let artifacts_server_handle = tokio::spawn(artifacts_server);
+    let messages_server_handle = tokio::spawn(messages_server);
 
     tokio::select! {
         res = engine_handle => res??,
+        res = world_engines_handle => res??,
         res = executor_handle => res??,
+        res = world_executors_handle => res??,
         res = proxy_server_handle => res??,
         res = graphql_server_handle => res?,
         res = grpc_server_handle => res??,
         res = libp2p_relay_server_handle => res?,
         res = artifacts_server_handle => res?,
+        res = messages_server_handle => res?,
         _ = dojo_utils::signal::wait_signals() => {},
     };
 
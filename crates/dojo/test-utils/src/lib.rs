@@ -4,3 +4,4 @@ pub mod compiler;
 pub mod migration;
 pub mod rpc;
 pub mod sequencer;
+pub mod snapshot;
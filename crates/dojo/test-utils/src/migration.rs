@@ -6,11 +6,20 @@ pub const TYPES_TEST_DB_DIR: &str = "/tmp/types-test-db";
 /// Copies the spawn and move test database to a temporary directory and returns the path to the
 /// temporary directory. Must be used if the test is going to modify the database.
 pub fn copy_spawn_and_move_db() -> Utf8PathBuf {
-    crate::compiler::copy_tmp_dir(&Utf8PathBuf::from(SPAWN_AND_MOVE_TEST_DB_DIR))
+    copy_db(SPAWN_AND_MOVE_TEST_DB_DIR)
 }
 
 /// Copies the types test database to a temporary directory and returns the path to the temporary
 /// directory. Must be used if the test is going to modify the database.
 pub fn copy_types_test_db() -> Utf8PathBuf {
-    crate::compiler::copy_tmp_dir(&Utf8PathBuf::from(TYPES_TEST_DB_DIR))
+    copy_db(TYPES_TEST_DB_DIR)
+}
+
+/// Copies a test database and migrates the copy to the current database version, as the test
+/// databases may have been generated by an older release of katana.
+fn copy_db(dir: &str) -> Utf8PathBuf {
+    let path = crate::compiler::copy_tmp_dir(&Utf8PathBuf::from(dir));
+    katana_db::migration::migrate_db(&path)
+        .unwrap_or_else(|e| panic!("Failed to migrate the test database {dir}: {e:#}"));
+    path
 }
@@ -1,16 +1,19 @@
 use std::collections::HashSet;
+use std::net::SocketAddr;
 use std::sync::Arc;
 
 use jsonrpsee::core::Error;
 use katana_core::backend::Backend;
 use katana_core::constants::DEFAULT_SEQUENCER_ADDRESS;
+use katana_core::service::invariant::Invariant;
 use katana_executor::implementation::blockifier::BlockifierFactory;
 use katana_node::config::dev::DevConfig;
 use katana_node::config::rpc::{ApiKind, RpcConfig, DEFAULT_RPC_ADDR, DEFAULT_RPC_MAX_CONNECTIONS};
 pub use katana_node::config::*;
-use katana_node::LaunchedNode;
+use katana_node::{LaunchedNode, Node};
 use katana_primitives::chain::ChainId;
 use katana_primitives::chain_spec::ChainSpec;
+use katana_primitives::genesis::snapshot::GenesisSnapshot;
 use starknet::accounts::{ExecutionEncoding, SingleOwnerAccount};
 use starknet::core::chain_id;
 use starknet::core::types::{BlockId, BlockTag, Felt};
@@ -19,6 +22,8 @@ use starknet::providers::JsonRpcClient;
 use starknet::signers::{LocalWallet, SigningKey};
 use url::Url;
 
+use crate::snapshot;
+
 #[derive(Debug)]
 pub struct TestAccount {
     pub private_key: Felt,
@@ -35,12 +40,17 @@ pub struct TestSequencer {
 
 impl TestSequencer {
     pub async fn start(config: Config) -> Self {
-        let handle = katana_node::build(config)
-            .await
-            .expect("Failed to build node components")
-            .launch()
-            .await
-            .expect("Failed to launch node");
+        Self::start_with_invariants(config, Vec::new()).await
+    }
+
+    /// Starts a sequencer checking the given invariants after every block.
+    pub async fn start_with_invariants(
+        config: Config,
+        invariants: Vec<Arc<dyn Invariant>>,
+    ) -> Self {
+        let mut node = katana_node::build(config).await.expect("Failed to build node components");
+        node.invariants = invariants;
+        let handle = node.launch().await.expect("Failed to launch node");
 
         let url = Url::parse(&format!("http://{}", handle.rpc.addr)).expect("Failed to parse URL");
 
@@ -53,6 +63,19 @@ impl TestSequencer {
         TestSequencer { handle, account, url }
     }
 
+    /// Starts a sequencer with the state of the snapshot allocated in its genesis, eg a migrated
+    /// world, so no transaction has to be executed to reach it.
+    pub async fn start_from_snapshot(mut config: Config, snapshot: GenesisSnapshot) -> Self {
+        snapshot.apply(&mut config.chain.genesis);
+        Self::start(config).await
+    }
+
+    /// Exports the current state of the sequencer as a genesis snapshot.
+    pub fn genesis_snapshot(&self) -> anyhow::Result<GenesisSnapshot> {
+        let backend = self.backend();
+        snapshot::genesis_snapshot(backend.blockchain.provider(), &backend.chain_spec.genesis)
+    }
+
     pub fn account(&self) -> SingleOwnerAccount<JsonRpcClient<HttpTransport>, LocalWallet> {
         let mut account = SingleOwnerAccount::new(
             JsonRpcClient::new(HttpTransport::new(self.url.clone())),
@@ -75,6 +98,10 @@ impl TestSequencer {
         &self.handle.node.backend
     }
 
+    pub fn node(&self) -> &Node {
+        &self.handle.node
+    }
+
     pub fn account_at_index(
         &self,
         index: usize,
@@ -107,13 +134,18 @@ impl TestSequencer {
         self.handle.rpc.handle.stop()
     }
 
+    /// Returns the address of the gRPC server, if it has been started.
+    pub fn grpc_addr(&self) -> Option<SocketAddr> {
+        self.handle.grpc
+    }
+
     pub fn url(&self) -> Url {
         self.url.clone()
     }
 }
 
 pub fn get_default_test_config(sequencing: SequencingConfig) -> Config {
-    let dev = DevConfig { fee: false, account_validation: true, fixed_gas_prices: None };
+    let dev = DevConfig { fee: false, ..Default::default() };
     let mut chain = ChainSpec { id: ChainId::SEPOLIA, ..Default::default() };
     chain.genesis.sequencer_address = *DEFAULT_SEQUENCER_ADDRESS;
 
@@ -123,6 +155,7 @@ pub fn get_default_test_config(sequencing: SequencingConfig) -> Config {
         addr: DEFAULT_RPC_ADDR,
         max_connections: DEFAULT_RPC_MAX_CONNECTIONS,
         apis: HashSet::from([ApiKind::Starknet, ApiKind::Dev, ApiKind::Saya, ApiKind::Torii]),
+        ..Default::default()
     };
 
     Config { sequencing, rpc, dev, chain, ..Default::default() }
@@ -0,0 +1,73 @@
+//! Genesis snapshots of migrated worlds, to start the test sequencers directly from the state of
+//! a migration instead of executing it in every test.
+
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use katana_primitives::block::BlockHashOrNumber;
+use katana_primitives::genesis::snapshot::GenesisSnapshot;
+use katana_primitives::genesis::Genesis;
+use katana_primitives::state::StateUpdatesWithDeclaredClasses;
+use katana_provider::providers::db::DbProvider;
+use katana_provider::traits::block::BlockNumberProvider;
+use katana_provider::traits::contract::ContractClassProvider;
+use katana_provider::traits::state::StateFactoryProvider;
+use katana_provider::traits::state_update::StateUpdateProvider;
+
+/// Builds the genesis snapshot of the latest state of a chain started from `genesis`, by folding
+/// the state updates of all its blocks.
+pub fn genesis_snapshot<P>(provider: &P, genesis: &Genesis) -> Result<GenesisSnapshot>
+where
+    P: BlockNumberProvider + StateUpdateProvider + StateFactoryProvider,
+{
+    let latest = provider.latest_number()?;
+    let state = provider.latest()?;
+
+    let mut states = StateUpdatesWithDeclaredClasses::default();
+
+    for number in 0..=latest {
+        let update = provider
+            .state_update(BlockHashOrNumber::Num(number))?
+            .with_context(|| format!("Missing state update of block {number}."))?;
+
+        let updates = &mut states.state_updates;
+        updates.nonce_updates.extend(update.nonce_updates);
+        updates.deployed_contracts.extend(update.deployed_contracts);
+        updates.replaced_classes.extend(update.replaced_classes);
+        updates.declared_classes.extend(update.declared_classes);
+        updates.deprecated_declared_classes.extend(update.deprecated_declared_classes);
+
+        for (address, storage) in update.storage_updates {
+            updates.storage_updates.entry(address).or_default().extend(storage);
+        }
+    }
+
+    let class_hashes = states
+        .state_updates
+        .declared_classes
+        .keys()
+        .chain(&states.state_updates.deprecated_declared_classes)
+        .copied()
+        .collect::<Vec<_>>();
+
+    for hash in class_hashes {
+        let class =
+            state.class(hash)?.with_context(|| format!("Missing class {hash:#x} in state."))?;
+        states.declared_compiled_classes.insert(hash, class);
+
+        if let Some(sierra) = state.sierra_class(hash)? {
+            states.declared_sierra_classes.insert(hash, sierra);
+        }
+    }
+
+    Ok(GenesisSnapshot::new(genesis, states))
+}
+
+/// Builds the genesis snapshot of the katana database at `db_dir`, from a chain started with the
+/// default genesis.
+///
+/// The database must not be in use by a running node.
+pub fn genesis_snapshot_from_db<P: AsRef<Path>>(db_dir: P) -> Result<GenesisSnapshot> {
+    let db = katana_db::open_db(db_dir)?;
+    genesis_snapshot(&DbProvider::new(db), &Genesis::default())
+}
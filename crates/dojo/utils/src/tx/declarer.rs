@@ -15,9 +15,8 @@ use starknet::core::types::{
 };
 use starknet::providers::{Provider, ProviderError};
 
-use crate::{
-    FeeConfig, TransactionError, TransactionExt, TransactionResult, TransactionWaiter, TxnConfig,
-};
+use crate::tx::send_and_wait;
+use crate::{FeeConfig, TransactionError, TransactionExt, TransactionResult, TxnConfig};
 
 #[derive(Debug, Clone)]
 pub struct LabeledClass {
@@ -111,38 +110,60 @@ where
             "Declaring class."
         );
 
-        let DeclareTransactionResult { transaction_hash, class_hash } = match txn_config.fee_config
-        {
-            FeeConfig::Strk(_) => {
-                account
-                    .declare_v3(Arc::new(labeled_class.class), casm_class_hash)
-                    .send_with_cfg(txn_config)
-                    .await?
-            }
-            FeeConfig::Eth(_) => {
-                account
-                    .declare_v2(Arc::new(labeled_class.class), casm_class_hash)
-                    .send_with_cfg(txn_config)
-                    .await?
-            }
-        };
-
-        tracing::trace!(
-            label = labeled_class.label,
-            transaction_hash = format!("{:#066x}", transaction_hash),
-            class_hash = format!("{:#066x}", class_hash),
-            casm_class_hash = format!("{:#066x}", labeled_class.casm_class_hash),
-            "Declared class."
-        );
-
-        if txn_config.wait {
-            let receipt = TransactionWaiter::new(transaction_hash, &account.provider()).await?;
-
-            if txn_config.receipt {
-                return Ok(TransactionResult::HashReceipt(transaction_hash, Box::new(receipt)));
+        let class = Arc::new(labeled_class.class);
+
+        let ceiling = txn_config.max_declare_fee;
+        let txn_config = txn_config
+            .resolve(ceiling, || async {
+                let estimate = match txn_config.fee_config {
+                    FeeConfig::Strk(_) => {
+                        account.declare_v3(class.clone(), casm_class_hash).estimate_fee().await?
+                    }
+                    FeeConfig::Eth(_) => {
+                        account.declare_v2(class.clone(), casm_class_hash).estimate_fee().await?
+                    }
+                };
+                Ok::<_, TransactionError<A::SignError>>(estimate)
+            })
+            .await?;
+
+        let result = send_and_wait(account, &txn_config, ceiling, |txn_config, nonce| {
+            let class = class.clone();
+            async move {
+                let DeclareTransactionResult { transaction_hash, .. } = match txn_config.fee_config
+                {
+                    FeeConfig::Strk(_) => {
+                        let declaration = account.declare_v3(class, casm_class_hash);
+                        let declaration = match nonce {
+                            Some(nonce) => declaration.nonce(nonce),
+                            None => declaration,
+                        };
+                        declaration.send_with_cfg(&txn_config).await?
+                    }
+                    FeeConfig::Eth(_) => {
+                        let declaration = account.declare_v2(class, casm_class_hash);
+                        let declaration = match nonce {
+                            Some(nonce) => declaration.nonce(nonce),
+                            None => declaration,
+                        };
+                        declaration.send_with_cfg(&txn_config).await?
+                    }
+                };
+                Ok::<_, TransactionError<A::SignError>>(transaction_hash)
             }
+        })
+        .await?;
+
+        if let Some(transaction_hash) = result.transaction_hash() {
+            tracing::trace!(
+                label = labeled_class.label,
+                transaction_hash = format!("{:#066x}", transaction_hash),
+                class_hash = format!("{:#066x}", class_hash),
+                casm_class_hash = format!("{:#066x}", casm_class_hash),
+                "Declared class."
+            );
         }
 
-        Ok(TransactionResult::Hash(transaction_hash))
+        Ok(result)
     }
 }
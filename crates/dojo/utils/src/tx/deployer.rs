@@ -1,17 +1,14 @@
 //! The deployer is in charge of deploying contracts to starknet.
 
 use starknet::accounts::ConnectedAccount;
-use starknet::core::types::{
-    BlockId, BlockTag, Call, Felt, InvokeTransactionResult, StarknetError,
-};
+use starknet::core::types::{BlockId, BlockTag, Call, Felt, StarknetError};
 use starknet::core::utils::get_contract_address;
 use starknet::macros::{felt, selector};
 use starknet::providers::{Provider, ProviderError};
 use tracing::trace;
 
-use crate::{
-    FeeConfig, TransactionError, TransactionExt, TransactionResult, TransactionWaiter, TxnConfig,
-};
+use crate::tx::execute_with_cfg;
+use crate::{FeeConfig, TransactionError, TransactionResult, TxnConfig};
 
 const UDC_DEPLOY_SELECTOR: Felt = selector!("deployContract");
 const UDC_ADDRESS: Felt =
@@ -60,33 +57,22 @@ where
 
         let call = Call { calldata: udc_calldata, selector: UDC_DEPLOY_SELECTOR, to: UDC_ADDRESS };
 
-        let InvokeTransactionResult { transaction_hash } = match self.txn_config.fee_config {
-            FeeConfig::Strk(_) => {
-                trace!("Deploying with STRK.");
-                self.account.execute_v3(vec![call]).send_with_cfg(&self.txn_config).await?
-            }
-            FeeConfig::Eth(_) => {
-                trace!("Deploying with ETH.");
-                self.account.execute_v1(vec![call]).send_with_cfg(&self.txn_config).await?
-            }
-        };
-
-        trace!(
-            transaction_hash = format!("{:#066x}", transaction_hash),
-            contract_address = format!("{:#066x}", contract_address),
-            "Deployed contract via UDC."
-        );
+        match self.txn_config.fee_config {
+            FeeConfig::Strk(_) => trace!("Deploying with STRK."),
+            FeeConfig::Eth(_) => trace!("Deploying with ETH."),
+        }
 
-        if self.txn_config.wait {
-            let receipt =
-                TransactionWaiter::new(transaction_hash, &self.account.provider()).await?;
+        let result = execute_with_cfg(&self.account, vec![call], &self.txn_config).await?;
 
-            if self.txn_config.receipt {
-                return Ok(TransactionResult::HashReceipt(transaction_hash, Box::new(receipt)));
-            }
+        if let Some(transaction_hash) = result.transaction_hash() {
+            trace!(
+                transaction_hash = format!("{:#066x}", transaction_hash),
+                contract_address = format!("{:#066x}", contract_address),
+                "Deployed contract via UDC."
+            );
         }
 
-        Ok(TransactionResult::Hash(transaction_hash))
+        Ok(result)
     }
 }
 
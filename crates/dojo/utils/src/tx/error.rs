@@ -1,6 +1,6 @@
 use starknet::accounts::AccountError;
 use starknet::core::types::contract::{CompressProgramError, ComputeClassHashError};
-use starknet::core::types::StarknetError;
+use starknet::core::types::{Felt, StarknetError};
 use starknet::providers::ProviderError;
 use thiserror::Error;
 
@@ -27,6 +27,8 @@ where
     ClassCompression(#[from] CompressProgramError),
     #[error("Fee calculation overflow")]
     FeeOutOfRange,
+    #[error("Transaction fee of {fee} exceeds the maximum fee of {ceiling}")]
+    FeeCeilingExceeded { fee: Felt, ceiling: Felt },
 }
 
 impl<S> From<AccountError<S>> for TransactionError<S>
@@ -5,8 +5,8 @@ use starknet::core::types::Call;
 use tracing::trace;
 
 use super::TransactionResult;
-use crate::tx::FeeConfig;
-use crate::{TransactionError, TransactionExt, TransactionWaiter, TxnConfig};
+use crate::tx::{execute_with_cfg, FeeConfig};
+use crate::{TransactionError, TxnConfig};
 
 #[derive(Debug)]
 pub struct Invoker<A>
@@ -55,29 +55,14 @@ where
     ) -> Result<TransactionResult, TransactionError<A::SignError>> {
         trace!(?call, "Invoke contract.");
 
-        let tx = match self.txn_config.fee_config {
-            FeeConfig::Strk(config) => {
-                trace!(?config, "Invoking with STRK.");
-                self.account.execute_v3(vec![call]).send_with_cfg(&self.txn_config).await?
-            }
-            FeeConfig::Eth(config) => {
-                trace!(?config, "Invoking with ETH.");
-                self.account.execute_v1(vec![call]).send_with_cfg(&self.txn_config).await?
-            }
-        };
-
-        trace!(transaction_hash = format!("{:#066x}", tx.transaction_hash), "Invoke contract.");
+        self.trace_fee_config();
+        let result = execute_with_cfg(&self.account, vec![call], &self.txn_config).await?;
 
-        if self.txn_config.wait {
-            let receipt =
-                TransactionWaiter::new(tx.transaction_hash, &self.account.provider()).await?;
-
-            if self.txn_config.receipt {
-                return Ok(TransactionResult::HashReceipt(tx.transaction_hash, Box::new(receipt)));
-            }
+        if let Some(hash) = result.transaction_hash() {
+            trace!(transaction_hash = format!("{:#066x}", hash), "Invoke contract.");
         }
 
-        Ok(TransactionResult::Hash(tx.transaction_hash))
+        Ok(result)
     }
 
     /// Invokes all the calls in one single transaction.
@@ -88,32 +73,21 @@ where
 
         trace!(?self.calls, "Invoke contract multicall.");
 
-        let tx = match self.txn_config.fee_config {
-            FeeConfig::Strk(config) => {
-                trace!(?config, "Invoking with STRK.");
-                self.account.execute_v3(self.calls.clone()).send_with_cfg(&self.txn_config).await?
-            }
-            FeeConfig::Eth(config) => {
-                trace!(?config, "Invoking with ETH.");
-                self.account.execute_v1(self.calls.clone()).send_with_cfg(&self.txn_config).await?
-            }
-        };
+        self.trace_fee_config();
+        let result = execute_with_cfg(&self.account, self.calls.clone(), &self.txn_config).await?;
 
-        trace!(
-            transaction_hash = format!("{:#066x}", tx.transaction_hash),
-            "Invoke contract multicall."
-        );
+        if let Some(hash) = result.transaction_hash() {
+            trace!(transaction_hash = format!("{:#066x}", hash), "Invoke contract multicall.");
+        }
 
-        if self.txn_config.wait {
-            let receipt =
-                TransactionWaiter::new(tx.transaction_hash, &self.account.provider()).await?;
+        Ok(result)
+    }
 
-            if self.txn_config.receipt {
-                return Ok(TransactionResult::HashReceipt(tx.transaction_hash, Box::new(receipt)));
-            }
+    fn trace_fee_config(&self) {
+        match self.txn_config.fee_config {
+            FeeConfig::Strk(config) => trace!(?config, "Invoking with STRK."),
+            FeeConfig::Eth(config) => trace!(?config, "Invoking with ETH."),
         }
-
-        Ok(TransactionResult::Hash(tx.transaction_hash))
     }
 
     /// Invokes all the calls individually, usually used for debugging if a multicall failed.
@@ -5,6 +5,8 @@ pub mod invoker;
 pub mod waiter;
 
 use std::fmt;
+use std::future::Future;
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
 use colored_json::ToColoredJson;
@@ -15,12 +17,15 @@ use starknet::accounts::{
     SingleOwnerAccount,
 };
 use starknet::core::types::{
-    BlockId, BlockTag, DeclareTransactionResult, DeployAccountTransactionResult, Felt,
-    InvokeTransactionResult, TransactionReceiptWithBlockInfo,
+    BlockId, BlockTag, Call, DeclareTransactionResult, DeployAccountTransactionResult, FeeEstimate,
+    Felt, InvokeTransactionResult, TransactionReceiptWithBlockInfo, TransactionStatus,
 };
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::{AnyProvider, JsonRpcClient, Provider};
 use starknet::signers::{LocalWallet, SigningKey};
+use tracing::warn;
+
+use crate::{TransactionError, TransactionWaiter, TransactionWaitingError};
 
 #[derive(Debug, Copy, Clone, Default)]
 pub struct StrkFeeConfig {
@@ -64,6 +69,34 @@ pub struct TxnConfig {
     pub walnut: bool,
     /// The fee configuration to use for the transaction.
     pub fee_config: FeeConfig,
+    /// The maximum fee a declare transaction can cost, in the smallest unit of the fee token.
+    pub max_declare_fee: Option<Felt>,
+    /// The maximum fee an invoke transaction can cost, in the smallest unit of the fee token.
+    pub max_invoke_fee: Option<Felt>,
+    /// How to replace a transaction that isn't accepted in time, only used when waiting for the
+    /// transactions. Transactions are never replaced if `None`.
+    pub fee_bump: Option<FeeBumpConfig>,
+}
+
+/// The replacement of the transactions that aren't accepted in time, eg stuck at `RECEIVED`.
+///
+/// A transaction not accepted before the deadline is sent again with the same nonce and its fees
+/// multiplied by `factor`, then by `factor` squared on the next replacement, and so on. The fees
+/// are never bumped above the max declare or invoke fee.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct FeeBumpConfig {
+    /// The time to wait for a transaction to be accepted before replacing it.
+    pub deadline: Duration,
+    /// The factor the fees are multiplied by on each replacement.
+    pub factor: f64,
+    /// The maximum number of times a transaction is replaced.
+    pub max_replacements: u32,
+}
+
+impl Default for FeeBumpConfig {
+    fn default() -> Self {
+        Self { deadline: Duration::from_secs(60), factor: 1.5, max_replacements: 3 }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -85,6 +118,16 @@ pub enum TransactionResult {
     HashReceipt(Felt, Box<TransactionReceiptWithBlockInfo>),
 }
 
+impl TransactionResult {
+    /// Returns the hash of the transaction, if one was sent.
+    pub fn transaction_hash(&self) -> Option<Felt> {
+        match self {
+            TransactionResult::Noop => None,
+            TransactionResult::Hash(hash) | TransactionResult::HashReceipt(hash, _) => Some(*hash),
+        }
+    }
+}
+
 impl fmt::Display for TransactionResult {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -107,6 +150,318 @@ impl TxnConfig {
     pub fn init_wait() -> Self {
         Self { wait: true, ..Default::default() }
     }
+
+    /// Returns the configuration to send a transaction with, resolving the fee configuration
+    /// against the transaction fee `estimate` so that the transaction can't be charged more than
+    /// `ceiling`.
+    ///
+    /// Fails if the estimated fee, or the max fee explicitly configured, exceeds the ceiling.
+    pub fn bounded_by<S>(
+        &self,
+        ceiling: Felt,
+        estimate: &FeeEstimate,
+    ) -> Result<Self, TransactionError<S>>
+    where
+        S: std::error::Error,
+    {
+        let exceeded =
+            |fee: u128| TransactionError::FeeCeilingExceeded { fee: fee.into(), ceiling };
+        let to_u128 =
+            |value: Felt| u128::try_from(value).map_err(|_| TransactionError::FeeOutOfRange);
+
+        let max = to_u128(ceiling)?;
+        let overall_fee = to_u128(estimate.overall_fee)?;
+        if overall_fee > max {
+            return Err(exceeded(overall_fee));
+        }
+
+        let fee_config = match self.fee_config {
+            FeeConfig::Eth(c) => {
+                let max_fee = match c.max_fee_raw {
+                    Some(max_fee) => to_u128(max_fee)?,
+                    None => {
+                        let multiplier = c.fee_estimate_multiplier.unwrap_or(ETH_FEE_MULTIPLIER);
+                        ((overall_fee as f64 * multiplier) as u128).min(max)
+                    }
+                };
+
+                if max_fee > max {
+                    return Err(exceeded(max_fee));
+                }
+
+                FeeConfig::Eth(EthFeeConfig {
+                    fee_estimate_multiplier: None,
+                    max_fee_raw: Some(max_fee.into()),
+                })
+            }
+            FeeConfig::Strk(c) => {
+                let gas_price = match c.gas_price {
+                    Some(gas_price) => gas_price,
+                    None => (to_u128(estimate.gas_price)? as f64 * STRK_GAS_MULTIPLIER) as u128,
+                }
+                .max(1);
+
+                // the gas needed to pay for the estimated fee at the chosen gas price
+                let needed = overall_fee.div_ceil(gas_price);
+                let gas = match c.gas {
+                    Some(gas) => gas as u128,
+                    None => ((needed as f64 * STRK_GAS_MULTIPLIER) as u128).min(max / gas_price),
+                };
+
+                if gas < needed {
+                    return Err(exceeded(needed.saturating_mul(gas_price)));
+                }
+
+                let max_fee = gas.saturating_mul(gas_price);
+                if max_fee > max {
+                    return Err(exceeded(max_fee));
+                }
+
+                let gas = u64::try_from(gas).map_err(|_| TransactionError::FeeOutOfRange)?;
+                FeeConfig::Strk(StrkFeeConfig { gas: Some(gas), gas_price: Some(gas_price) })
+            }
+        };
+
+        Ok(Self { fee_config, ..*self })
+    }
+
+    /// Returns the configuration with its fees scaled by `factor`, eg to resend a transaction
+    /// that failed to be included.
+    ///
+    /// The explicit max fee, gas and gas price are scaled, and the default multiplier of the
+    /// estimated ETH fee if no max fee is set. The STRK fees are estimated with fixed
+    /// multipliers, so they're left to the estimation if not explicitly set.
+    pub fn with_fee_bump(&self, factor: f64) -> Self {
+        let scale = |value: u128| (value as f64 * factor) as u128;
+
+        let fee_config = match self.fee_config {
+            FeeConfig::Eth(c) => match c.max_fee_raw.map(u128::try_from) {
+                Some(Ok(max_fee)) => {
+                    FeeConfig::Eth(EthFeeConfig { max_fee_raw: Some(scale(max_fee).into()), ..c })
+                }
+                Some(Err(_)) => self.fee_config,
+                None => FeeConfig::Eth(EthFeeConfig {
+                    fee_estimate_multiplier: Some(
+                        c.fee_estimate_multiplier.unwrap_or(ETH_FEE_MULTIPLIER) * factor,
+                    ),
+                    ..c
+                }),
+            },
+            FeeConfig::Strk(c) => FeeConfig::Strk(StrkFeeConfig {
+                gas: c.gas.map(|gas| scale(gas.into()).min(u64::MAX.into()) as u64),
+                gas_price: c.gas_price.map(scale),
+            }),
+        };
+
+        Self { fee_config, ..*self }
+    }
+
+    /// Returns the max fee of the transaction if it's explicitly set, as a max fee or as a gas
+    /// amount and price.
+    pub fn max_fee(&self) -> Option<Felt> {
+        match self.fee_config {
+            FeeConfig::Eth(c) => c.max_fee_raw,
+            FeeConfig::Strk(StrkFeeConfig { gas: Some(gas), gas_price: Some(gas_price) }) => {
+                Some((gas as u128).saturating_mul(gas_price).into())
+            }
+            FeeConfig::Strk(_) => None,
+        }
+    }
+
+    /// Returns the configuration to send a transaction with, bounded by `ceiling` if any.
+    ///
+    /// The fees are resolved against the fee estimated by `estimate` if there is a ceiling, or if
+    /// the transaction may be replaced, so that its fees can be bumped.
+    pub(crate) async fn resolve<S, E, F>(
+        &self,
+        ceiling: Option<Felt>,
+        estimate: E,
+    ) -> Result<Self, TransactionError<S>>
+    where
+        S: std::error::Error,
+        E: FnOnce() -> F,
+        F: Future<Output = Result<FeeEstimate, TransactionError<S>>>,
+    {
+        let replaceable = self.wait && self.fee_bump.is_some();
+        match ceiling {
+            Some(ceiling) => self.bounded_by(ceiling, &estimate().await?),
+            None if replaceable => self.bounded_by(u128::MAX.into(), &estimate().await?),
+            None => Ok(*self),
+        }
+    }
+
+    /// Returns the result of a transaction that has been waited for.
+    fn waited(&self, hash: Felt, receipt: TransactionReceiptWithBlockInfo) -> TransactionResult {
+        if self.receipt {
+            TransactionResult::HashReceipt(hash, Box::new(receipt))
+        } else {
+            TransactionResult::Hash(hash)
+        }
+    }
+}
+
+/// Sends a transaction with `send`, given the configuration and the nonce to send it with, and
+/// waits for it if the configuration says so.
+///
+/// If a fee bump is configured, a transaction that isn't accepted before its deadline is replaced
+/// by the one sent with the same nonce and bumped fees, up to the maximum number of replacements.
+/// The fees are not bumped above `ceiling`. Whichever of the transactions sent is accepted is
+/// returned.
+pub(crate) async fn send_and_wait<A, S, F>(
+    account: &A,
+    txn_config: &TxnConfig,
+    ceiling: Option<Felt>,
+    send: S,
+) -> Result<TransactionResult, TransactionError<A::SignError>>
+where
+    A: ConnectedAccount + Sync,
+    S: Fn(TxnConfig, Option<Felt>) -> F,
+    F: Future<Output = Result<Felt, TransactionError<A::SignError>>>,
+{
+    let fee_bump = txn_config.fee_bump.filter(|_| txn_config.wait);
+
+    // the replacements must use the same nonce
+    let nonce = match fee_bump {
+        Some(_) => Some(account.get_nonce().await?),
+        None => None,
+    };
+
+    let mut hash = send(*txn_config, nonce).await?;
+    if !txn_config.wait {
+        return Ok(TransactionResult::Hash(hash));
+    }
+
+    let provider = account.provider();
+    let mut sent = vec![hash];
+
+    if let Some(fee_bump) = fee_bump {
+        for replacement in 1..=fee_bump.max_replacements {
+            match TransactionWaiter::new(hash, &provider).with_timeout(fee_bump.deadline).await {
+                Ok(receipt) => return Ok(txn_config.waited(hash, receipt)),
+                Err(
+                    TransactionWaitingError::Timeout | TransactionWaitingError::TransactionRejected,
+                ) => {}
+                Err(e) => return Err(e.into()),
+            }
+
+            // an earlier transaction may have been accepted in the meantime
+            if let Some(hash) = find_accepted(&provider, &sent).await {
+                let receipt = TransactionWaiter::new(hash, &provider).await?;
+                return Ok(txn_config.waited(hash, receipt));
+            }
+
+            let config = txn_config.with_fee_bump(fee_bump.factor.powi(replacement as i32));
+            if let (Some(ceiling), Some(max_fee)) = (ceiling, config.max_fee()) {
+                if max_fee > ceiling {
+                    warn!(
+                        transaction_hash = format!("{:#066x}", hash),
+                        max_fee = format!("{:#x}", max_fee),
+                        ceiling = format!("{:#x}", ceiling),
+                        "Transaction not accepted in time, but the bumped fee exceeds the maximum."
+                    );
+                    break;
+                }
+            }
+
+            warn!(
+                transaction_hash = format!("{:#066x}", hash),
+                replacement, "Transaction not accepted in time, replacing it with bumped fees."
+            );
+
+            match send(config, nonce).await {
+                Ok(replaced_by) => {
+                    hash = replaced_by;
+                    sent.push(hash);
+                }
+                // eg the nonce has been used by one of the transactions sent in the meantime
+                Err(error) => {
+                    warn!(%error, "Failed to replace the transaction.");
+                    break;
+                }
+            }
+        }
+    }
+
+    match TransactionWaiter::new(hash, &provider).await {
+        Ok(receipt) => Ok(txn_config.waited(hash, receipt)),
+        Err(e) => match find_accepted(&provider, &sent).await {
+            Some(hash) => {
+                let receipt = TransactionWaiter::new(hash, &provider).await?;
+                Ok(txn_config.waited(hash, receipt))
+            }
+            None => Err(e.into()),
+        },
+    }
+}
+
+/// Returns the first of the transactions `sent` that has been accepted, if any.
+async fn find_accepted<P>(provider: &P, sent: &[Felt]) -> Option<Felt>
+where
+    P: Provider + Sync,
+{
+    for hash in sent {
+        if let Ok(TransactionStatus::AcceptedOnL2(_) | TransactionStatus::AcceptedOnL1(_)) =
+            provider.get_transaction_status(hash).await
+        {
+            return Some(*hash);
+        }
+    }
+
+    None
+}
+
+/// The default multiplier applied to the estimated fee of ETH transactions.
+const ETH_FEE_MULTIPLIER: f64 = 1.1;
+/// The default multiplier applied to the estimated gas and gas price of STRK transactions.
+const STRK_GAS_MULTIPLIER: f64 = 1.5;
+
+/// Sends the `calls` in a single invoke transaction, paid with the token of the fee
+/// configuration and bounded by its max invoke fee, if any, and waits for it if the configuration
+/// says so.
+pub(crate) async fn execute_with_cfg<A>(
+    account: &A,
+    calls: Vec<Call>,
+    txn_config: &TxnConfig,
+) -> Result<TransactionResult, TransactionError<A::SignError>>
+where
+    A: ConnectedAccount + Sync,
+{
+    let ceiling = txn_config.max_invoke_fee;
+    let txn_config = txn_config
+        .resolve(ceiling, || async {
+            let estimate = match txn_config.fee_config {
+                FeeConfig::Strk(_) => account.execute_v3(calls.clone()).estimate_fee().await?,
+                FeeConfig::Eth(_) => account.execute_v1(calls.clone()).estimate_fee().await?,
+            };
+            Ok::<_, TransactionError<A::SignError>>(estimate)
+        })
+        .await?;
+
+    send_and_wait(account, &txn_config, ceiling, |txn_config, nonce| {
+        let calls = calls.clone();
+        async move {
+            let InvokeTransactionResult { transaction_hash } = match txn_config.fee_config {
+                FeeConfig::Strk(_) => {
+                    let execution = account.execute_v3(calls);
+                    let execution = match nonce {
+                        Some(nonce) => execution.nonce(nonce),
+                        None => execution,
+                    };
+                    execution.send_with_cfg(&txn_config).await?
+                }
+                FeeConfig::Eth(_) => {
+                    let execution = account.execute_v1(calls);
+                    let execution = match nonce {
+                        Some(nonce) => execution.nonce(nonce),
+                        None => execution,
+                    };
+                    execution.send_with_cfg(&txn_config).await?
+                }
+            };
+            Ok::<_, TransactionError<A::SignError>>(transaction_hash)
+        }
+    })
+    .await
 }
 
 /// Helper trait to abstract away setting `TxnConfig` configurations before sending a transaction
@@ -362,3 +717,110 @@ pub async fn get_predeployed_accounts<A: ConnectedAccount>(
 
     Ok(declarers)
 }
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use starknet::core::types::PriceUnit;
+
+    use super::*;
+
+    type Error = TransactionError<std::convert::Infallible>;
+
+    fn estimate(overall_fee: u128, gas_price: u128) -> FeeEstimate {
+        FeeEstimate {
+            gas_consumed: (overall_fee / gas_price).into(),
+            gas_price: gas_price.into(),
+            data_gas_consumed: Felt::ZERO,
+            data_gas_price: Felt::ZERO,
+            overall_fee: overall_fee.into(),
+            unit: PriceUnit::Fri,
+        }
+    }
+
+    #[test]
+    fn bounded_eth_max_fee() {
+        let config =
+            TxnConfig { fee_config: FeeConfig::Eth(Default::default()), ..Default::default() };
+
+        let bounded: Result<_, Error> = config.bounded_by(Felt::from(5000), &estimate(1000, 1));
+        let FeeConfig::Eth(c) = bounded.unwrap().fee_config else { panic!("Expected ETH") };
+        assert_eq!(c.max_fee_raw, Some(Felt::from(1100)));
+
+        // the estimated fee with the default multiplier is capped to the ceiling
+        let bounded: Result<_, Error> = config.bounded_by(Felt::from(1050), &estimate(1000, 1));
+        let FeeConfig::Eth(c) = bounded.unwrap().fee_config else { panic!("Expected ETH") };
+        assert_eq!(c.max_fee_raw, Some(Felt::from(1050)));
+    }
+
+    #[test]
+    fn bounded_strk_gas() {
+        let config = TxnConfig::default();
+
+        // 67 units of gas are needed at the gas price of 15
+        let bounded: Result<_, Error> = config.bounded_by(Felt::from(2000), &estimate(1000, 10));
+        let FeeConfig::Strk(c) = bounded.unwrap().fee_config else { panic!("Expected STRK") };
+        assert_eq!(c.gas_price, Some(15));
+        assert_eq!(c.gas, Some(100));
+
+        let bounded: Result<_, Error> = config.bounded_by(Felt::from(1200), &estimate(1000, 10));
+        let FeeConfig::Strk(c) = bounded.unwrap().fee_config else { panic!("Expected STRK") };
+        assert_eq!(c.gas_price, Some(15));
+        assert_eq!(c.gas, Some(80));
+    }
+
+    #[test]
+    fn fee_above_ceiling_is_rejected() {
+        let config = TxnConfig::default();
+        let result: Result<_, Error> = config.bounded_by(Felt::from(999), &estimate(1000, 10));
+        assert_matches!(result, Err(TransactionError::FeeCeilingExceeded { .. }));
+
+        let fee_config = EthFeeConfig { max_fee_raw: Some(Felt::from(3000)), ..Default::default() };
+        let config = TxnConfig { fee_config: FeeConfig::Eth(fee_config), ..Default::default() };
+        let result: Result<_, Error> = config.bounded_by(Felt::from(2000), &estimate(1000, 1));
+        assert_matches!(result, Err(TransactionError::FeeCeilingExceeded { .. }));
+    }
+
+    #[test]
+    fn fee_bump() {
+        let config =
+            TxnConfig { fee_config: FeeConfig::Eth(Default::default()), ..Default::default() };
+        let FeeConfig::Eth(c) = config.with_fee_bump(2.0).fee_config else {
+            panic!("Expected ETH")
+        };
+        assert_eq!(c.fee_estimate_multiplier, Some(2.2));
+
+        let fee_config = EthFeeConfig { max_fee_raw: Some(Felt::from(1000)), ..Default::default() };
+        let config = TxnConfig { fee_config: FeeConfig::Eth(fee_config), ..Default::default() };
+        let FeeConfig::Eth(c) = config.with_fee_bump(1.5).fee_config else {
+            panic!("Expected ETH")
+        };
+        assert_eq!(c.max_fee_raw, Some(Felt::from(1500)));
+
+        let fee_config = StrkFeeConfig { gas: None, gas_price: Some(10) };
+        let config = TxnConfig { fee_config: FeeConfig::Strk(fee_config), ..Default::default() };
+        let FeeConfig::Strk(c) = config.with_fee_bump(1.5).fee_config else {
+            panic!("Expected STRK")
+        };
+        assert_eq!(c.gas, None);
+        assert_eq!(c.gas_price, Some(15));
+    }
+
+    #[test]
+    fn max_fee() {
+        let fee_config = EthFeeConfig { max_fee_raw: Some(Felt::from(1000)), ..Default::default() };
+        let config = TxnConfig { fee_config: FeeConfig::Eth(fee_config), ..Default::default() };
+        assert_eq!(config.max_fee(), Some(Felt::from(1000)));
+
+        let config = config.with_fee_bump(1.5f64.powi(2));
+        assert_eq!(config.max_fee(), Some(Felt::from(2250)));
+
+        let fee_config = StrkFeeConfig { gas: Some(100), gas_price: Some(10) };
+        let config = TxnConfig { fee_config: FeeConfig::Strk(fee_config), ..Default::default() };
+        assert_eq!(config.max_fee(), Some(Felt::from(1000)));
+
+        let fee_config = StrkFeeConfig { gas: None, gas_price: Some(10) };
+        let config = TxnConfig { fee_config: FeeConfig::Strk(fee_config), ..Default::default() };
+        assert_eq!(config.max_fee(), None);
+    }
+}
@@ -1,4 +1,5 @@
 use serde::Deserialize;
+use starknet::core::types::Felt;
 
 #[derive(Debug, Clone, Deserialize, Default)]
 pub struct MigrationConfig {
@@ -10,4 +11,16 @@ pub struct MigrationConfig {
     /// Determine the contract initialization order.
     /// Expecting tags.
     pub order_inits: Option<Vec<String>>,
+    /// Resources that are never diffed nor migrated, eg. resources managed by another team.
+    /// Expecting tags or namespaces, a namespace ignoring all its resources.
+    pub ignore: Option<Vec<String>>,
+    /// Maximum fee of a declare transaction, in the smallest unit of the fee token.
+    /// Overridden by the `--max-declare-fee` option.
+    pub max_declare_fee: Option<Felt>,
+    /// Maximum fee of an invoke transaction, in the smallest unit of the fee token.
+    /// Overridden by the `--max-invoke-fee` option.
+    pub max_invoke_fee: Option<Felt>,
+    /// Account receiving the ownership of the world and of all the resources once migrated, the
+    /// ownership of the migrating account being revoked.
+    pub final_owner: Option<Felt>,
 }
@@ -2,7 +2,7 @@ use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::path::Path;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
 use serde::Deserialize;
 use toml;
 
@@ -14,6 +14,10 @@ use super::world_config::WorldConfig;
 /// Profile configuration that is used to configure the world and its environment.
 ///
 /// This [`ProfileConfig`] is expected to be loaded from a TOML file.
+///
+/// The RPC URL, the account and world addresses of the environment and the init call arguments
+/// may reference environment variables with `${VAR}`, so the same file can be used for several
+/// deployments.
 #[derive(Debug, Clone, Default, Deserialize)]
 pub struct ProfileConfig {
     pub world: WorldConfig,
@@ -41,13 +45,47 @@ impl ProfileConfig {
         }
     }
 
-    /// Loads the profile configuration from a TOML file.
+    /// Loads the profile configuration from a TOML file, interpolating the environment variables
+    /// it references.
     pub fn from_toml<P: AsRef<Path>>(toml_path: P) -> Result<Self> {
         let content = fs::read_to_string(&toml_path)?;
-        let config: ProfileConfig = toml::from_str(&content)?;
+        let mut config: ProfileConfig = toml::from_str(&content)?;
+        config.interpolate_env_vars(|name| std::env::var(name).ok())?;
         Ok(config)
     }
 
+    /// Replaces the `${VAR}` references of the interpolated fields with the value returned by
+    /// `lookup`, failing if a variable isn't defined.
+    pub fn interpolate_env_vars<F>(&mut self, lookup: F) -> Result<()>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        if let Some(env) = &mut self.env {
+            let fields = [
+                ("env.rpc_url", &mut env.rpc_url),
+                ("env.account_address", &mut env.account_address),
+                ("env.world_address", &mut env.world_address),
+            ];
+
+            for (field, value) in fields {
+                if let Some(value) = value {
+                    *value = interpolate(value, field, &lookup)?;
+                }
+            }
+        }
+
+        if let Some(init_call_args) = &mut self.init_call_args {
+            for (tag, args) in init_call_args.iter_mut() {
+                let field = format!("init_call_args.{tag}");
+                for arg in args.iter_mut() {
+                    *arg = interpolate(arg, &field, &lookup)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
     /// Returns the local writers for a given tag.
     pub fn get_local_writers(&self, tag: &str) -> HashSet<String> {
         if let Some(writers) = &self.writers {
@@ -66,6 +104,32 @@ impl ProfileConfig {
         }
     }
 
+    /// Ignores the resources listed in a `.dojoignore` file, in addition to the ones of the
+    /// migration config. The file lists one tag or namespace per line, empty lines and lines
+    /// starting with `#` are skipped.
+    pub fn extend_ignore_from_file<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let content = fs::read_to_string(path)?;
+        let entries = content
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(String::from);
+
+        let migration = self.migration.get_or_insert_with(Default::default);
+        migration.ignore.get_or_insert_with(Vec::new).extend(entries);
+
+        Ok(())
+    }
+
+    /// Returns true if the resource with the given namespace and tag must be left out of the
+    /// world diff.
+    pub fn is_ignored(&self, namespace: &str, tag: &str) -> bool {
+        self.migration
+            .as_ref()
+            .and_then(|migration| migration.ignore.as_ref())
+            .is_some_and(|ignore| ignore.iter().any(|i| i == namespace || i == tag))
+    }
+
     /// Returns true if the tag has to be skipped during migration.
     pub fn is_skipped(&self, tag: &str) -> bool {
         if let Some(migration) = &self.migration {
@@ -80,10 +144,43 @@ impl ProfileConfig {
     }
 }
 
+/// Replaces the `${VAR}` references of `value`, which is the content of `field`.
+fn interpolate<F>(value: &str, field: &str, lookup: &F) -> Result<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut interpolated = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        interpolated.push_str(&rest[..start]);
+
+        let Some(len) = rest[start + 2..].find('}') else {
+            bail!("Unterminated environment variable reference in `{field}`: `{value}`.");
+        };
+
+        let name = &rest[start + 2..start + 2 + len];
+        if name.is_empty() {
+            bail!("Empty environment variable reference in `{field}`: `{value}`.");
+        }
+
+        match lookup(name) {
+            Some(var) => interpolated.push_str(&var),
+            None => bail!("Environment variable `{name}` referenced in `{field}` is not set."),
+        }
+
+        rest = &rest[start + 3 + len..];
+    }
+
+    interpolated.push_str(rest);
+    Ok(interpolated)
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
 
+    use starknet::core::types::Felt;
     use url::Url;
 
     use super::*;
@@ -148,6 +245,9 @@ mod tests {
 
         [migration]
         skip_contracts = [ "module::my-contract" ]
+        ignore = [ "ns3", "ns1-other" ]
+        max_declare_fee = "0x1000"
+        final_owner = "0x1234"
 
         [writers]
         "ns1" = ["ns1-actions"]
@@ -161,8 +261,16 @@ mod tests {
 
         let config = toml::from_str::<ProfileConfig>(content).unwrap();
 
+        assert!(config.is_ignored("ns3", "ns3-m1"));
+        assert!(config.is_ignored("ns1", "ns1-other"));
+        assert!(!config.is_ignored("ns1", "ns1-actions"));
+
         let migration = config.migration.unwrap();
         assert_eq!(migration.skip_contracts.unwrap(), vec!["module::my-contract".to_string()]);
+        assert_eq!(migration.ignore.unwrap(), vec!["ns3".to_string(), "ns1-other".to_string()]);
+        assert_eq!(migration.max_declare_fee, Some(Felt::from(0x1000)));
+        assert_eq!(migration.max_invoke_fee, None);
+        assert_eq!(migration.final_owner, Some(Felt::from(0x1234)));
 
         let env = config.env.unwrap();
         assert_eq!(env.rpc_url, Some("https://example.com/rpc".to_string()));
@@ -212,4 +320,61 @@ mod tests {
             )]))
         );
     }
+
+    #[test]
+    fn test_profile_config_env_interpolation() {
+        let content = r#"
+        [world]
+        name = "test"
+        seed = "abcd"
+
+        [namespace]
+        default = "test"
+
+        [env]
+        rpc_url = "https://${RPC_HOST}:${RPC_PORT}/rpc"
+        account_address = "${ACCOUNT}"
+        private_key = "${NOT_INTERPOLATED}"
+
+        [init_call_args]
+        "ns1-actions" = [ "0x1", "${INIT_ARG}" ]
+        "#;
+
+        let vars = HashMap::from([
+            ("RPC_HOST", "example.com"),
+            ("RPC_PORT", "5050"),
+            ("ACCOUNT", "0x123"),
+            ("INIT_ARG", "0x2"),
+        ]);
+
+        let mut config = toml::from_str::<ProfileConfig>(content).unwrap();
+        config.interpolate_env_vars(|name| vars.get(name).map(|v| v.to_string())).unwrap();
+
+        let env = config.env.unwrap();
+        assert_eq!(env.rpc_url, Some("https://example.com:5050/rpc".to_string()));
+        assert_eq!(env.account_address, Some("0x123".to_string()));
+        assert_eq!(env.private_key, Some("${NOT_INTERPOLATED}".to_string()));
+        assert_eq!(
+            config.init_call_args,
+            Some(HashMap::from([(
+                "ns1-actions".to_string(),
+                vec!["0x1".to_string(), "0x2".to_string()]
+            )]))
+        );
+    }
+
+    #[test]
+    fn test_profile_config_env_interpolation_errors() {
+        let lookup = |name: &str| (name == "SET").then(|| "value".to_string());
+
+        let error = interpolate("${MISSING}", "env.rpc_url", &lookup).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Environment variable `MISSING` referenced in `env.rpc_url` is not set."
+        );
+
+        assert!(interpolate("${SET", "env.rpc_url", &lookup).is_err());
+        assert!(interpolate("${}", "env.rpc_url", &lookup).is_err());
+        assert_eq!(interpolate("$SET-${SET}", "env.rpc_url", &lookup).unwrap(), "$SET-value");
+    }
 }
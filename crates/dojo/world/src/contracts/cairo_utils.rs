@@ -7,3 +7,60 @@ pub fn str_to_felt(string: &str) -> Result<Felt> {
         anyhow!(format!("Failed to convert string `{}` to cairo short string: {}", string, e))
     })
 }
+
+/// Returns the type of the elements of an array or a span, from its ABI type name.
+pub fn array_inner_type(ty: &str) -> Option<&str> {
+    ty.strip_prefix("core::array::Array::<")
+        .or_else(|| ty.strip_prefix("core::array::Span::<"))
+        .and_then(|s| s.strip_suffix('>'))
+}
+
+/// Returns the types of the elements of a tuple, from its ABI type name.
+pub fn tuple_inner_types(ty: &str) -> Option<Vec<&str>> {
+    let inner = ty.strip_prefix('(')?.strip_suffix(')')?;
+
+    let mut types = vec![];
+    let mut depth = 0;
+    let mut start = 0;
+
+    for (i, c) in inner.char_indices() {
+        match c {
+            '<' | '(' => depth += 1,
+            '>' | ')' => depth -= 1,
+            ',' if depth == 0 => {
+                types.push(inner[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+
+    let last = inner[start..].trim();
+    if !last.is_empty() {
+        types.push(last);
+    }
+
+    Some(types)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn array_types() {
+        assert_eq!(array_inner_type("core::array::Array::<core::felt252>"), Some("core::felt252"));
+        assert_eq!(array_inner_type("core::array::Span::<(u8, u8)>"), Some("(u8, u8)"));
+        assert_eq!(array_inner_type("core::felt252"), None);
+    }
+
+    #[test]
+    fn tuple_types() {
+        assert_eq!(
+            tuple_inner_types("(core::felt252, core::array::Array::<(u8, u8)>)"),
+            Some(vec!["core::felt252", "core::array::Array::<(u8, u8)>"])
+        );
+        assert_eq!(tuple_inner_types("()"), Some(vec![]));
+        assert_eq!(tuple_inner_types("core::felt252"), None);
+    }
+}
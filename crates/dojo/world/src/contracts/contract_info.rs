@@ -124,12 +124,13 @@ mod tests {
     use starknet::macros::felt;
 
     use super::*;
-    use crate::diff::{DojoContract, DojoModel, WorldContract};
+    use crate::diff::{DojoContract, DojoModel, WorldContract, MANIFEST_VERSION};
     use crate::local::{CommonLocalInfo, ContractLocal, WorldLocal};
 
     #[test]
     fn test_manifest_to_contracts_info() {
         let manifest = Manifest {
+            version: MANIFEST_VERSION,
             world: WorldContract {
                 address: felt!("0x5678"),
                 class_hash: felt!("0x1111"),
@@ -127,14 +127,19 @@ where
         })
     }
 
-    pub async fn entity_storage(&self, keys: &[Felt]) -> Result<Vec<Felt>, ModelError> {
+    /// Returns the model layout as expected by the world contract entrypoints.
+    pub async fn world_layout(&self) -> Result<Layout, ModelError> {
         // As the dojo::model::Layout type has been pasted
         // in both `model` and `world` ABI by abigen, the compiler sees both types
         // as different even if they are strictly identical.
         // Here is a trick reading the model layout as raw FieldElement
         // and deserialize it to a world::Layout.
         let raw_layout = self.model_reader.layout().raw_call().await?;
-        let layout = Layout::cairo_deserialize(raw_layout.as_slice(), 0)?;
+        Ok(Layout::cairo_deserialize(raw_layout.as_slice(), 0)?)
+    }
+
+    pub async fn entity_storage(&self, keys: &[Felt]) -> Result<Vec<Felt>, ModelError> {
+        let layout = self.world_layout().await?;
 
         Ok(self
             .world_reader
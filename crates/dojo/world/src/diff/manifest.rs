@@ -1,6 +1,12 @@
 //! Manifest data to store the diff result in files.
+//!
+//! Manifests are versioned, and manifests written by older versions are upgraded when read, one
+//! version at a time, so that the deployment records survive the changes of the format.
 
+use anyhow::{anyhow, bail, Result};
+use dojo_types::naming;
 use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use serde_with::serde_as;
 use starknet::core::serde::unsigned_field_element::UfeHex;
 use starknet::core::types::contract::AbiEntry;
@@ -11,9 +17,17 @@ use crate::local::ResourceLocal;
 use crate::remote::ResourceRemote;
 use crate::ResourceType;
 
+/// The current version of the manifest format.
+///
+/// Manifests without a version were written before the format was versioned, and are upgraded
+/// from version 0.
+pub const MANIFEST_VERSION: u32 = 1;
+
 #[serde_as]
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct Manifest {
+    /// Version of the manifest format.
+    pub version: u32,
     pub world: WorldContract,
     pub contracts: Vec<DojoContract>,
     pub models: Vec<DojoModel>,
@@ -138,7 +152,13 @@ impl Manifest {
         models.sort_by_key(|m| m.tag.clone());
         events.sort_by_key(|e| e.tag.clone());
 
-        Self { world, contracts, models, events }
+        Self { version: MANIFEST_VERSION, world, contracts, models, events }
+    }
+
+    /// Parses a manifest from its JSON representation, upgrading it first if it has been written
+    /// by an older version.
+    pub fn from_json(value: Value) -> Result<Self> {
+        Ok(serde_json::from_value(upgrade(value)?)?)
     }
 
     pub fn get_contract_address(&self, tag: &str) -> Option<Felt> {
@@ -238,3 +258,232 @@ fn resource_diff_to_dojo_event(resource: &ResourceDiff) -> DojoEvent {
         _ => unreachable!(),
     }
 }
+
+/// Upgrades a manifest to the current version, one version at a time.
+fn upgrade(mut value: Value) -> Result<Value> {
+    let mut version = match value.get("version") {
+        None => 0,
+        Some(v) => v.as_u64().ok_or_else(|| anyhow!("Invalid manifest version `{v}`."))?,
+    };
+
+    if version > MANIFEST_VERSION as u64 {
+        bail!(
+            "Manifest version {version} is more recent than the supported version \
+             {MANIFEST_VERSION}, sozo must be upgraded to read it."
+        );
+    }
+
+    while version < MANIFEST_VERSION as u64 {
+        value = match version {
+            0 => upgrade_v0(value)?,
+            _ => unreachable!("every version older than the current one has an upgrade"),
+        };
+        version += 1;
+    }
+
+    Ok(value)
+}
+
+/// Upgrades an unversioned manifest to version 1.
+///
+/// Unversioned manifests are either in the format of version 1, possibly without the fields added
+/// over time (events, entrypoints, selectors), or in the legacy format with a `kind` for each
+/// resource and the ABIs stored in separate files.
+fn upgrade_v0(value: Value) -> Result<Value> {
+    let world = value.get("world").ok_or_else(|| anyhow!("Manifest has no world."))?;
+
+    let world = json!({
+        "class_hash": field(world, "class_hash")?,
+        // The world of a legacy manifest may not be deployed yet.
+        "address": world.get("address").filter(|a| !a.is_null()).cloned().unwrap_or(json!("0x0")),
+        "seed": world.get("seed").cloned().unwrap_or(json!("")),
+        "name": world.get("name").cloned().unwrap_or(json!("")),
+        "entrypoints": world.get("entrypoints").cloned().unwrap_or(json!([])),
+        "abi": abi(world),
+    });
+
+    let contracts = resources(&value, "contracts")
+        .map(|c| {
+            let tag = tag(c)?;
+            Ok(json!({
+                "address": field(c, "address")?,
+                "class_hash": field(c, "class_hash")?,
+                "abi": abi(c),
+                "init_calldata": c.get("init_calldata").cloned().unwrap_or(json!([])),
+                "selector": selector(c, &tag),
+                "tag": tag,
+                "systems": systems(c),
+            }))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let [models, events] = ["models", "events"].map(|kind| {
+        resources(&value, kind)
+            .map(|r| {
+                let tag = tag(r)?;
+                Ok(json!({
+                    "members": r.get("members").cloned().unwrap_or(json!([])),
+                    "class_hash": field(r, "class_hash")?,
+                    "selector": selector(r, &tag),
+                    "tag": tag,
+                }))
+            })
+            .collect::<Result<Vec<_>>>()
+    });
+    let (models, events) = (models?, events?);
+
+    Ok(json!({
+        "version": 1,
+        "world": world,
+        "contracts": contracts,
+        "models": models,
+        "events": events,
+    }))
+}
+
+/// Returns the resources of the given kind of an unversioned manifest.
+fn resources<'a>(manifest: &'a Value, kind: &str) -> impl Iterator<Item = &'a Value> {
+    manifest.get(kind).and_then(Value::as_array).into_iter().flatten()
+}
+
+fn field(resource: &Value, name: &str) -> Result<Value> {
+    resource.get(name).cloned().ok_or_else(|| anyhow!("Manifest resource has no `{name}`."))
+}
+
+fn tag(resource: &Value) -> Result<String> {
+    resource.get("tag").and_then(Value::as_str).map(str::to_string).ok_or_else(|| {
+        anyhow!("Manifest resource has no tag, manifests older than dojo 1.0 can't be upgraded.")
+    })
+}
+
+/// Returns the selector of a resource, computed from its tag when missing.
+fn selector(resource: &Value, tag: &str) -> Value {
+    resource
+        .get("selector")
+        .cloned()
+        .unwrap_or_else(|| json!(format!("{:#x}", naming::compute_selector_from_tag(tag))))
+}
+
+/// Returns the ABI of a resource, legacy manifests only having the path of the ABI file.
+fn abi(resource: &Value) -> Value {
+    resource.get("abi").filter(|abi| abi.is_array()).cloned().unwrap_or(json!([]))
+}
+
+/// Returns the names of the systems of a contract, legacy manifests describing each system with
+/// an object.
+fn systems(contract: &Value) -> Value {
+    let systems = contract
+        .get("systems")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|s| s.as_str().or_else(|| s.get("name").and_then(Value::as_str)))
+        .collect::<Vec<_>>();
+
+    json!(systems)
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::macros::felt;
+
+    use super::*;
+
+    const WORLD: &str = r#""world": {
+        "class_hash": "0x1",
+        "address": "0x2",
+        "seed": "dojo",
+        "name": "Dojo",
+        "entrypoints": ["uuid"],
+        "abi": []
+    }"#;
+
+    #[test]
+    fn unversioned_manifest_is_upgraded() {
+        let manifest = serde_json::from_str(&format!(
+            r#"{{
+                {WORLD},
+                "contracts": [{{
+                    "address": "0x3",
+                    "class_hash": "0x4",
+                    "abi": [],
+                    "tag": "ns-actions",
+                    "selector": "0x5",
+                    "systems": ["spawn"]
+                }}],
+                "models": [{{
+                    "members": [],
+                    "class_hash": "0x6",
+                    "tag": "ns-Position"
+                }}]
+            }}"#
+        ))
+        .unwrap();
+
+        let manifest = Manifest::from_json(manifest).unwrap();
+
+        assert_eq!(manifest.version, MANIFEST_VERSION);
+        assert_eq!(manifest.world.entrypoints, vec!["uuid".to_string()]);
+        assert_eq!(manifest.get_contract_address("ns-actions"), Some(felt!("0x3")));
+        assert_eq!(manifest.contracts[0].selector, felt!("0x5"));
+        assert_eq!(manifest.models[0].selector, naming::compute_selector_from_tag("ns-Position"));
+        assert!(manifest.events.is_empty());
+    }
+
+    #[test]
+    fn legacy_manifest_is_upgraded() {
+        let manifest = json!({
+            "world": {
+                "kind": "WorldContract",
+                "class_hash": "0x1",
+                "address": null,
+                "abi": "manifests/dev/deployment/abis/dojo-world.json",
+                "seed": "dojo"
+            },
+            "base": { "kind": "Class", "class_hash": "0x7" },
+            "contracts": [{
+                "kind": "DojoContract",
+                "address": "0x3",
+                "class_hash": "0x4",
+                "abi": "manifests/dev/deployment/abis/contracts/actions.json",
+                "init_calldata": ["0x1"],
+                "tag": "ns-actions",
+                "systems": [{ "name": "spawn" }, { "name": "move" }]
+            }],
+            "models": [{
+                "kind": "DojoModel",
+                "members": [{ "name": "player", "type": "ContractAddress", "key": true }],
+                "class_hash": "0x6",
+                "tag": "ns-Position"
+            }]
+        });
+
+        let manifest = Manifest::from_json(manifest).unwrap();
+
+        assert_eq!(manifest.world.address, Felt::ZERO);
+        assert_eq!(manifest.contracts[0].systems, vec!["spawn".to_string(), "move".to_string()]);
+        assert_eq!(manifest.contracts[0].init_calldata, vec!["0x1".to_string()]);
+        assert!(manifest.contracts[0].abi.is_empty());
+        assert_eq!(manifest.contracts[0].selector, naming::compute_selector_from_tag("ns-actions"));
+        assert!(manifest.models[0].members[0].key);
+    }
+
+    #[test]
+    fn current_manifest_roundtrip() {
+        let manifest = Manifest { version: MANIFEST_VERSION, ..Default::default() };
+        let value = serde_json::to_value(&manifest).unwrap();
+
+        assert_eq!(Manifest::from_json(value).unwrap().version, MANIFEST_VERSION);
+    }
+
+    #[test]
+    fn newer_manifest_is_rejected() {
+        let manifest = serde_json::from_str(&format!(
+            r#"{{ "version": {}, {WORLD}, "contracts": [], "models": [], "events": [] }}"#,
+            MANIFEST_VERSION + 1
+        ))
+        .unwrap();
+
+        assert!(Manifest::from_json(manifest).is_err());
+    }
+}
@@ -31,6 +31,8 @@ pub struct WorldStatusInfo {
     pub address: Felt,
     /// The class hash of the world.
     pub class_hash: Felt,
+    /// The class hash of the deployed world, if any.
+    pub remote_class_hash: Option<Felt>,
     /// The casm class hash of the world.
     pub casm_class_hash: Felt,
     /// The sierra class of the world.
@@ -78,6 +80,7 @@ impl WorldDiff {
             world_info: WorldStatusInfo {
                 address: local.deterministic_world_address()?,
                 class_hash: local.class_hash,
+                remote_class_hash: None,
                 casm_class_hash: local.casm_class_hash,
                 class: local.class,
                 status: WorldStatus::NotDeployed,
@@ -91,6 +94,11 @@ impl WorldDiff {
         };
 
         for (selector, resource) in local.resources {
+            if diff.profile_config.is_ignored(&resource.namespace(), &resource.tag()) {
+                tracing::trace!(tag = resource.tag(), "Ignoring resource.");
+                continue;
+            }
+
             // Namespaces are enumerated to be easily retrieved later.
             if let ResourceLocal::Namespace(_) = &resource {
                 diff.namespaces.push(selector);
@@ -118,6 +126,7 @@ impl WorldDiff {
                 // As the remote world was found, its address is always used.
                 address: remote.address,
                 class_hash: local.class_hash,
+                remote_class_hash: Some(remote.current_class_hash()),
                 casm_class_hash: local.casm_class_hash,
                 class: local.class,
                 entrypoints: local.entrypoints,
@@ -131,6 +140,12 @@ impl WorldDiff {
         };
 
         for (local_selector, local_resource) in local.resources {
+            // Ignored resources are left out, whatever their remote state.
+            if diff.profile_config.is_ignored(&local_resource.namespace(), &local_resource.tag()) {
+                tracing::trace!(tag = local_resource.tag(), "Ignoring resource.");
+                continue;
+            }
+
             // Namespaces are enumerated to be easily retrieved later.
             if let ResourceLocal::Namespace(_) = &local_resource {
                 diff.namespaces.push(local_selector);
@@ -359,6 +374,7 @@ mod tests {
     use starknet::core::types::Felt;
 
     use super::*;
+    use crate::config::migration_config::MigrationConfig;
     use crate::config::NamespaceConfig;
     use crate::local::{CommonLocalInfo, ContractLocal, NamespaceLocal, ResourceLocal, WorldLocal};
     use crate::remote::{CommonRemoteInfo, ContractRemote, NamespaceRemote};
@@ -487,4 +503,47 @@ mod tests {
             ResourceDiff::Synced(_, _)
         ));
     }
+
+    #[test]
+    fn test_world_diff_ignore() {
+        let ns = "ns".to_string();
+        let namespace_config = NamespaceConfig::new(&ns);
+        let mut profile_config = ProfileConfig::new("test", "seed", namespace_config.clone());
+        profile_config.migration = Some(MigrationConfig {
+            ignore: Some(vec!["ns-ignored".to_string(), "other".to_string()]),
+            ..Default::default()
+        });
+
+        let mut local = WorldLocal::new(profile_config);
+
+        let contract = |namespace: &str, name: &str| {
+            ResourceLocal::Contract(ContractLocal {
+                common: CommonLocalInfo {
+                    name: name.to_string(),
+                    namespace: namespace.to_string(),
+                    class: empty_sierra_class(),
+                    casm_class: None,
+                    class_hash: Felt::ONE,
+                    casm_class_hash: Felt::ZERO,
+                },
+                systems: vec![],
+            })
+        };
+
+        let kept = contract(&ns, "kept");
+        local.add_resource(kept.clone());
+        local.add_resource(contract(&ns, "ignored"));
+        local.add_resource(ResourceLocal::Namespace(NamespaceLocal { name: "other".to_string() }));
+        local.add_resource(contract("other", "c"));
+
+        let mut remote = WorldRemote::default();
+        remote.class_hashes.push(Felt::ONE);
+
+        for diff in [WorldDiff::from_local(local.clone()).unwrap(), WorldDiff::new(local, remote)] {
+            // the default namespace and the kept contract
+            assert_eq!(diff.resources.len(), 2);
+            assert!(diff.resources.contains_key(&kept.dojo_selector()));
+            assert_eq!(diff.namespaces, vec![naming::compute_bytearray_hash("ns")]);
+        }
+    }
 }
@@ -8,6 +8,7 @@ pub mod contracts;
 pub mod diff;
 pub mod local;
 pub mod remote;
+pub mod storage;
 pub mod uri;
 pub mod utils;
 
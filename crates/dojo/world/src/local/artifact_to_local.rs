@@ -126,7 +126,7 @@ impl WorldLocal {
                                             class_hash,
                                             casm_class_hash,
                                         },
-                                        members: vec![],
+                                        members: Member::from_abi(&name, &abi),
                                     });
 
                                     resources.push(resource);
@@ -152,7 +152,7 @@ impl WorldLocal {
                                             class_hash,
                                             casm_class_hash,
                                         },
-                                        members: vec![],
+                                        members: Member::from_abi(&name, &abi),
                                     });
 
                                     resources.push(resource);
@@ -374,4 +374,54 @@ mod tests {
         let systems = systems_from_abi(&abi.abi);
         assert_eq!(systems, vec!["system_1", "system_2", "system_3", "system_4", "upgrade"]);
     }
+
+    #[test]
+    fn test_members_from_abi() {
+        let abi: Vec<AbiEntry> = serde_json::from_value(serde_json::json!([
+            {
+                "type": "struct",
+                "name": "ns::models::Position",
+                "members": [
+                    { "name": "player", "type": "core::felt252" },
+                    { "name": "x", "type": "core::integer::u32" }
+                ]
+            },
+            {
+                "type": "struct",
+                "name": "ns::models::PositionValue",
+                "members": [{ "name": "x", "type": "core::integer::u32" }]
+            }
+        ]))
+        .unwrap();
+
+        let members = Member::from_abi("Position", &abi);
+        let members =
+            members.iter().map(|m| (m.name.as_str(), m.ty.as_str(), m.key)).collect::<Vec<_>>();
+        assert_eq!(
+            members,
+            vec![("player", "core::felt252", true), ("x", "core::integer::u32", false)]
+        );
+
+        assert!(Member::from_abi("Moves", &abi).is_empty());
+    }
+
+    #[test]
+    fn test_members_from_abi_with_keys() {
+        let abi: Vec<AbiEntry> = serde_json::from_value(serde_json::json!([{
+            "type": "struct",
+            "name": "ns::models::Position",
+            "members": [
+                { "name": "player", "type": "core::felt252" },
+                { "name": "x", "type": "core::integer::u32" }
+            ]
+        }]))
+        .unwrap();
+
+        let keys = |members: Vec<Member>| {
+            members.into_iter().filter(|m| m.key).map(|m| m.name).collect::<Vec<_>>()
+        };
+
+        assert!(keys(Member::from_abi("Position", &abi)).is_empty());
+        assert_eq!(keys(Member::from_abi_with_keys("Position", &abi, &["player"])), vec!["player"]);
+    }
 }
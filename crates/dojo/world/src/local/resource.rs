@@ -71,6 +71,46 @@ pub struct Member {
     pub key: bool,
 }
 
+impl Member {
+    /// Returns the members of the model or event struct `name`, as declared in the ABI of its
+    /// class.
+    ///
+    /// The keys are the members missing from the `<name>Value` struct generated for the values of
+    /// the resource. Returns no member if the struct isn't found in the ABI.
+    pub fn from_abi(name: &str, abi: &[AbiEntry]) -> Vec<Member> {
+        Self::from_abi_with_keys(name, abi, &[])
+    }
+
+    /// Same as [`Member::from_abi`], but the keys are the members named in `keys` if the ABI
+    /// doesn't declare the `<name>Value` struct.
+    pub fn from_abi_with_keys(name: &str, abi: &[AbiEntry], keys: &[&str]) -> Vec<Member> {
+        let find_struct = |suffix: String| {
+            abi.iter().find_map(|entry| match entry {
+                AbiEntry::Struct(s) if s.name.ends_with(&suffix) => Some(s),
+                _ => None,
+            })
+        };
+
+        let Some(resource) = find_struct(format!("::{name}")) else {
+            return vec![];
+        };
+        let values = find_struct(format!("::{name}Value"));
+
+        resource
+            .members
+            .iter()
+            .map(|m| Member {
+                name: m.name.clone(),
+                ty: m.r#type.clone(),
+                key: match values {
+                    Some(values) => !values.members.iter().any(|v| v.name == m.name),
+                    None => keys.contains(&m.name.as_str()),
+                },
+            })
+            .collect()
+    }
+}
+
 impl ResourceLocal {
     /// Returns the name of the resource.
     pub fn name(&self) -> String {
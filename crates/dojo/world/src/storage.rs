@@ -0,0 +1,95 @@
+//! Storage addresses of the records of a world.
+//!
+//! The world stores the values of a record at addresses derived from the model selector, the
+//! entity id and the path of each value in the model, following `dojo::storage::layout`.
+
+use starknet::core::types::Felt;
+use starknet::core::utils::normalize_address;
+use starknet::macros::short_string;
+use starknet_crypto::poseidon_hash_many;
+
+/// The domain of the world storage keys.
+pub const DOJO_STORAGE: Felt = short_string!("dojo_storage");
+/// The domain of the base addresses of the storage chunks.
+pub const DOJO_STORAGE_CHUNK: Felt = short_string!("DojoStorageChunk");
+/// Maximum number of bits packed into a single storage slot.
+pub const PACKING_MAX_BITS: u8 = 251;
+/// Number of slots of a storage chunk. The values spanning more slots are split into chunks.
+pub const CHUNK_SIZE: u64 = 256;
+
+/// Computes the base address of the value stored at `key` for the given model.
+pub fn base_address(model_selector: Felt, key: Felt) -> Felt {
+    normalize_address(poseidon_hash_many(&[DOJO_STORAGE, model_selector, key]))
+}
+
+/// Computes the base address of the chunk of a value stored past its first 256 slots.
+pub fn chunk_base_address(base: Felt, chunk: u64) -> Felt {
+    normalize_address(poseidon_hash_many(&[base, Felt::from(chunk), DOJO_STORAGE_CHUNK]))
+}
+
+/// Computes the key of a nested value, as done by `dojo::utils::combine_key`.
+pub fn combine_key(parent: Felt, child: Felt) -> Felt {
+    poseidon_hash_many(&[parent, child])
+}
+
+/// Returns the addresses of the `count` slots of the value stored at `key` for the given model.
+pub fn slot_addresses(model_selector: Felt, key: Felt, count: u64) -> impl Iterator<Item = Felt> {
+    let base = base_address(model_selector, key);
+
+    (0..count.div_ceil(CHUNK_SIZE)).flat_map(move |chunk| {
+        let chunk_base = if chunk == 0 { base } else { chunk_base_address(base, chunk) };
+        let len = (count - chunk * CHUNK_SIZE).min(CHUNK_SIZE);
+        (0..len).map(move |i| chunk_base + Felt::from(i))
+    })
+}
+
+/// Returns the number of slots the values of a fixed layout are packed into.
+pub fn packed_size(bits: &[u8]) -> u64 {
+    let mut size = 1;
+    let mut partial = 0;
+
+    for bits in bits.iter().map(|b| *b as usize) {
+        partial += bits;
+        if partial > PACKING_MAX_BITS as usize {
+            size += 1;
+            partial = bits;
+        }
+    }
+
+    size
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::core::utils::cairo_short_string_to_felt;
+    use starknet::macros::felt;
+
+    use super::*;
+
+    #[test]
+    fn storage_domains() {
+        assert_eq!(DOJO_STORAGE, cairo_short_string_to_felt("dojo_storage").unwrap());
+        assert_eq!(DOJO_STORAGE_CHUNK, cairo_short_string_to_felt("DojoStorageChunk").unwrap());
+    }
+
+    #[test]
+    fn slots_past_the_first_chunk_are_in_other_chunks() {
+        let (model, key) = (felt!("0x1234"), felt!("0xe"));
+        let addresses = slot_addresses(model, key, 300).collect::<Vec<_>>();
+
+        let base = base_address(model, key);
+        let second_chunk = chunk_base_address(base, 1);
+        assert_eq!(addresses.len(), 300);
+        assert_eq!(addresses[255], base + Felt::from(255));
+        assert_eq!(addresses[256], second_chunk);
+        assert_eq!(addresses[299], second_chunk + Felt::from(43));
+    }
+
+    #[test]
+    fn values_are_packed_up_to_251_bits_per_slot() {
+        assert_eq!(packed_size(&[]), 1);
+        assert_eq!(packed_size(&[128, 123]), 1);
+        assert_eq!(packed_size(&[128, 124]), 2);
+        assert_eq!(packed_size(&[251; 3]), 3);
+    }
+}
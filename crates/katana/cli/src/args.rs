@@ -1,69 +1,130 @@
 //! Katana node CLI options and configuration.
 
 use std::collections::HashSet;
+use std::fs::File;
+use std::io;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use alloy_primitives::U256;
-use anyhow::{Context, Result};
+use anyhow::{bail, Context, Result};
 use clap::Parser;
+use katana_core::backend::gas_oracle::{GasPricing, GasPricingMode};
 use katana_core::constants::DEFAULT_SEQUENCER_ADDRESS;
 use katana_core::service::messaging::MessagingConfig;
-use katana_node::config::db::DbConfig;
+use katana_core::service::scheduler::SchedulerConfig;
+use katana_node::config::db::{DbConfig, PruneConfig};
 use katana_node::config::dev::{DevConfig, FixedL1GasPriceConfig};
 use katana_node::config::execution::ExecutionConfig;
 use katana_node::config::fork::ForkingConfig;
+use katana_node::config::grpc::GrpcConfig;
 use katana_node::config::metrics::MetricsConfig;
 use katana_node::config::rpc::{ApiKind, RpcConfig};
 use katana_node::config::{Config, SequencingConfig};
+use katana_pool::{NonceValidation, PoolConfig, DEFAULT_POOL_MAX_SIZE};
+use katana_primitives::block::HeaderExtension;
+use katana_primitives::chain::ChainId;
 use katana_primitives::chain_spec::{self, ChainSpec};
+use katana_primitives::da::DataAvailabilityMode;
+use katana_primitives::env::TxLimits;
 use katana_primitives::genesis::allocation::DevAllocationsGenerator;
+#[cfg(feature = "slot")]
+use katana_primitives::genesis::constant::CONTROLLER_CLASS_HASH;
 use katana_primitives::genesis::constant::DEFAULT_PREFUNDED_ACCOUNT_BALANCE;
+use katana_primitives::genesis::snapshot::GenesisSnapshot;
 use serde::{Deserialize, Serialize};
-use tracing::{info, Subscriber};
+use tracing::{info, warn, Subscriber};
 use tracing_log::LogTracer;
+use tracing_subscriber::fmt::writer::{BoxMakeWriter, MakeWriterExt};
 use tracing_subscriber::{fmt, EnvFilter};
 
 use crate::file::NodeArgsConfig;
 use crate::options::*;
 use crate::utils;
-use crate::utils::{parse_seed, LogFormat};
+use crate::utils::{parse_seed, LogFormat, LogLevel};
 
 pub(crate) const LOG_TARGET: &str = "katana::cli";
 
+/// Replaces the log filter of the global subscriber.
+type LogFilterReload = Box<dyn Fn(&str) -> Result<()> + Send + Sync>;
+
 #[derive(Parser, Debug, Serialize, Deserialize, Default, Clone)]
 #[command(next_help_heading = "Node options")]
 pub struct NodeArgs {
-    /// Don't print anything on startup.
-    #[arg(long)]
+    /// Don't print anything, neither on startup nor the logs.
+    ///
+    /// The logs are still written to the `--log.file` if any.
+    #[arg(long, env = "KATANA_SILENT")]
     pub silent: bool,
 
     /// Disable auto and interval mining, and mine on demand instead via an endpoint.
-    #[arg(long)]
+    #[arg(long, env = "KATANA_NO_MINING")]
     #[arg(conflicts_with = "block_time")]
     pub no_mining: bool,
 
     /// Block time in milliseconds for interval mining.
-    #[arg(short, long)]
+    #[arg(short, long, env = "KATANA_BLOCK_TIME")]
     #[arg(value_name = "MILLISECONDS")]
     pub block_time: Option<u64>,
 
+    /// Path of an executable checking invariants after every mined block.
+    ///
+    /// The executable is given the block number as argument, and the RPC URL of the node in the
+    /// `KATANA_RPC_URL` environment variable. When it exits with a non-zero status, block
+    /// production is halted and the state is dumped into `--invariant-dump-dir`.
+    #[arg(long, env = "KATANA_INVARIANT_SCRIPT")]
+    #[arg(value_name = "PATH")]
+    pub invariant_script: Option<PathBuf>,
+
+    /// Directory where the state is dumped when an invariant is violated.
+    #[arg(long, env = "KATANA_INVARIANT_DUMP_DIR")]
+    #[arg(value_name = "PATH")]
+    #[arg(requires = "invariant_script")]
+    pub invariant_dump_dir: Option<PathBuf>,
+
+    /// Number of threads executing the transactions of a block.
+    ///
+    /// With more than one, independent transactions are executed in parallel, and the ones
+    /// conflicting with a transaction before them are executed again. Sequential if not set.
+    #[arg(long, env = "KATANA_EXECUTION_WORKERS")]
+    #[arg(value_name = "COUNT")]
+    pub execution_workers: Option<usize>,
+
+    /// Calls submitted in transactions on a schedule, only available in dev mode.
+    ///
+    /// Can only be set in the `[scheduler]` table of the configuration file.
+    #[arg(skip)]
+    pub scheduler: Option<SchedulerConfig>,
+
     /// Directory path of the database to initialize from.
     ///
     /// The path must either be an empty directory or a directory which already contains a
     /// previously initialized Katana database.
-    #[arg(long)]
+    #[arg(long, env = "KATANA_DB_DIR")]
     #[arg(value_name = "PATH")]
     pub db_dir: Option<PathBuf>,
 
+    /// Run several isolated chains in this process, one for each name of the comma separated
+    /// list, e.g. `shard1,shard2`.
+    ///
+    /// The chain ID of a chain is its name, and its database is the sub-directory of `--db-dir`
+    /// named after it, if set. The RPC endpoint of a chain is at `/chain/<name>` on the RPC
+    /// address. Subscriptions aren't routed, they must be made to the address of the chain's own
+    /// RPC server, printed at startup.
+    #[arg(long, env = "KATANA_CHAINS")]
+    #[arg(value_name = "NAMES", value_delimiter = ',')]
+    #[arg(conflicts_with_all = ["chain_id", "fork_provider"])]
+    pub chains: Vec<String>,
+
     /// Configuration file
-    #[arg(long)]
+    #[arg(long, env = "KATANA_CONFIG")]
     config: Option<PathBuf>,
 
     /// Configure the messaging with an other chain.
     ///
     /// Configure the messaging to allow Katana listening/sending messages on a
     /// settlement chain that can be Ethereum or an other Starknet sequencer.
-    #[arg(long)]
+    #[arg(long, env = "KATANA_MESSAGING")]
     #[arg(value_name = "PATH")]
     #[arg(value_parser = katana_core::service::messaging::MessagingConfig::parse)]
     pub messaging: Option<MessagingConfig>,
@@ -79,6 +140,10 @@ pub struct NodeArgs {
     #[command(flatten)]
     pub server: ServerOptions,
 
+    #[cfg(feature = "grpc")]
+    #[command(flatten)]
+    pub grpc: GrpcOptions,
+
     #[command(flatten)]
     pub starknet: StarknetOptions,
 
@@ -88,6 +153,12 @@ pub struct NodeArgs {
     #[command(flatten)]
     pub forking: ForkingOptions,
 
+    #[command(flatten)]
+    pub pruning: PruningOptions,
+
+    #[command(flatten)]
+    pub txpool: TxPoolOptions,
+
     #[command(flatten)]
     pub development: DevOptions,
 
@@ -97,16 +168,29 @@ pub struct NodeArgs {
 }
 
 impl NodeArgs {
+    /// Starts the node, with the options of the configuration file merged into the command line
+    /// ones.
     pub fn execute(&self) -> Result<()> {
-        self.init_logging()?;
+        let args = self.clone().with_config_file()?;
+        let reload_log_filter = args.init_logging()?;
         tokio::runtime::Builder::new_multi_thread()
             .enable_all()
             .build()
             .context("failed to build tokio runtime")?
-            .block_on(self.start_node())
+            .block_on(args.start_node(self, reload_log_filter))
     }
 
-    async fn start_node(&self) -> Result<()> {
+    /// `cli_args` are the arguments before being merged with the configuration file, which is read
+    /// again whenever the configuration of the node is reloaded.
+    async fn start_node(
+        &self,
+        cli_args: &NodeArgs,
+        reload_log_filter: LogFilterReload,
+    ) -> Result<()> {
+        if !self.chains.is_empty() {
+            return self.start_chains(cli_args, reload_log_filter).await;
+        }
+
         // Build the node
         let config = self.config()?;
         let node = katana_node::build(config).await.context("failed to build node")?;
@@ -117,6 +201,22 @@ impl NodeArgs {
 
         // Launch the node
         let handle = node.launch().await.context("failed to launch node")?;
+        Self::register_config_reloader(&handle.node, cli_args.clone(), reload_log_filter);
+
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+
+            let reloader = handle.node.backend.config_reloader.clone();
+            let mut hangup = signal(SignalKind::hangup())?;
+            tokio::spawn(async move {
+                while hangup.recv().await.is_some() {
+                    if let Err(error) = reloader.reload() {
+                        warn!(target: LOG_TARGET, %error, "Failed to reload configuration.");
+                    }
+                }
+            });
+        }
 
         // Wait until an OS signal (ie SIGINT, SIGTERM) is received or the node is shutdown.
         tokio::select! {
@@ -133,53 +233,252 @@ impl NodeArgs {
         Ok(())
     }
 
-    fn init_logging(&self) -> Result<()> {
-        const DEFAULT_LOG_FILTER: &str = "info,tasks=debug,executor=trace,forking::backend=trace,\
-                                          blockifier=off,jsonrpsee_server=off,hyper=off,\
-                                          messaging=debug,node=error";
+    /// Starts a node for each of the `--chains`, and the router forwarding the requests to them.
+    async fn start_chains(
+        &self,
+        cli_args: &NodeArgs,
+        reload_log_filter: LogFilterReload,
+    ) -> Result<()> {
+        let config = self.config()?;
+        let addr = config.rpc.socket_addr();
+        let chains = self.chain_configs(config)?;
 
-        let filter = if self.development.dev {
-            &format!("{DEFAULT_LOG_FILTER},server=debug")
-        } else {
-            DEFAULT_LOG_FILTER
-        };
+        if !self.silent {
+            utils::print_intro(self, &chains[0].1.chain);
+        }
+
+        let handle = katana_node::multi::launch_chains(chains, addr)
+            .await
+            .context("failed to launch chains")?;
 
+        let reload_log_filter = Arc::new(reload_log_filter);
+        for (_, chain) in &handle.chains {
+            let reload_log_filter = reload_log_filter.clone();
+            let reload_log_filter: LogFilterReload =
+                Box::new(move |filter: &str| reload_log_filter(filter));
+            Self::register_config_reloader(&chain.node, cli_args.clone(), reload_log_filter);
+        }
+
+        #[cfg(unix)]
+        {
+            use tokio::signal::unix::{signal, SignalKind};
+
+            let reloaders = handle
+                .chains
+                .iter()
+                .map(|(_, chain)| chain.node.backend.config_reloader.clone())
+                .collect::<Vec<_>>();
+            let mut hangup = signal(SignalKind::hangup())?;
+            tokio::spawn(async move {
+                while hangup.recv().await.is_some() {
+                    for reloader in &reloaders {
+                        if let Err(error) = reloader.reload() {
+                            warn!(target: LOG_TARGET, %error, "Failed to reload configuration.");
+                        }
+                    }
+                }
+            });
+        }
+
+        tokio::select! {
+            _ = dojo_utils::signal::wait_signals() => {
+                handle.stop().await?;
+            },
+
+            _ = handle.stopped() => { }
+        }
+
+        info!("Shutting down.");
+
+        Ok(())
+    }
+
+    /// Returns the configuration of each of the `--chains`, derived from the node `config`.
+    fn chain_configs(&self, config: Config) -> Result<Vec<(String, Config)>> {
+        if config.metrics.is_some() || config.grpc.is_some() {
+            bail!("the metrics and gRPC servers are not supported with multiple chains");
+        }
+
+        self.chains
+            .iter()
+            .map(|name| {
+                if name.is_empty() || name.contains('/') {
+                    bail!("invalid chain name '{name}'");
+                }
+
+                let mut config = config.clone();
+                config.chain.id =
+                    ChainId::parse(name).with_context(|| format!("invalid chain name '{name}'"))?;
+                config.db.dir = config.db.dir.map(|dir| dir.join(name));
+                Ok((name.clone(), config))
+            })
+            .collect()
+    }
+
+    /// Registers the hook reloading the configuration of the node, on `SIGHUP` or through the
+    /// `dev_reloadConfig` endpoint.
+    fn register_config_reloader(
+        node: &katana_node::Node,
+        cli_args: NodeArgs,
+        reload_log_filter: LogFilterReload,
+    ) {
+        node.set_config_source(move || {
+            let args = cli_args.clone().with_config_file()?;
+            let config = args.config()?;
+            reload_log_filter(&args.log_filter())?;
+            Ok(config)
+        });
+    }
+
+    /// Installs the global subscriber, returning a function to change its log filter.
+    fn init_logging(&self) -> Result<LogFilterReload> {
         LogTracer::init()?;
 
         // If the user has set the `RUST_LOG` environment variable, then we prioritize it.
         // Otherwise, we use the default log filter.
         // TODO: change env var to `KATANA_LOG`.
-        let filter = EnvFilter::try_from_default_env().or(EnvFilter::try_new(filter))?;
-        let builder = fmt::Subscriber::builder().with_env_filter(filter);
+        let from_env = EnvFilter::try_from_default_env().ok();
+        let filter_set_by_env = from_env.is_some();
+        let filter = match from_env {
+            Some(filter) => filter,
+            None => EnvFilter::try_new(self.log_filter())?,
+        };
 
-        let subscriber: Box<dyn Subscriber + Send + Sync> = match self.logging.log_format {
-            LogFormat::Full => Box::new(builder.finish()),
-            LogFormat::Json => Box::new(builder.json().finish()),
+        let file = match &self.logging.log_file {
+            Some(path) => Some(Arc::new(
+                File::options()
+                    .create(true)
+                    .append(true)
+                    .open(path)
+                    .with_context(|| format!("failed to open log file {}", path.display()))?,
+            )),
+            None => None,
         };
 
-        Ok(tracing::subscriber::set_global_default(subscriber)?)
+        // In silent mode, nothing is written to the standard output.
+        let ansi = file.is_none();
+        let writer = match (self.silent, file) {
+            (false, None) => BoxMakeWriter::new(io::stdout),
+            (false, Some(file)) => BoxMakeWriter::new(io::stdout.and(file)),
+            (true, Some(file)) => BoxMakeWriter::new(file),
+            (true, None) => BoxMakeWriter::new(io::sink),
+        };
+
+        let builder =
+            fmt::Subscriber::builder().with_env_filter(filter).with_writer(writer).with_ansi(ansi);
+
+        let subscriber: Box<dyn Subscriber + Send + Sync>;
+        let reload: LogFilterReload;
+        match self.logging.log_format {
+            LogFormat::Full => {
+                let builder = builder.with_filter_reloading();
+                let handle = builder.reload_handle();
+                reload =
+                    Box::new(move |filter: &str| Ok(handle.reload(EnvFilter::try_new(filter)?)?));
+                subscriber = Box::new(builder.finish());
+            }
+            LogFormat::Json => {
+                let builder = builder.json().with_filter_reloading();
+                let handle = builder.reload_handle();
+                reload =
+                    Box::new(move |filter: &str| Ok(handle.reload(EnvFilter::try_new(filter)?)?));
+                subscriber = Box::new(builder.finish());
+            }
+        }
+
+        tracing::subscriber::set_global_default(subscriber)?;
+
+        // The filter set with `RUST_LOG` is kept as is.
+        if filter_set_by_env {
+            Ok(Box::new(|_: &str| Ok(())))
+        } else {
+            Ok(reload)
+        }
+    }
+
+    /// Returns the log filter directives, with the level of the modules set on the command line
+    /// overriding the default ones.
+    fn log_filter(&self) -> String {
+        const DEFAULT_LOG_FILTER: &str = "info,tasks=debug,executor=trace,forking::backend=trace,\
+                                          blockifier=off,jsonrpsee_server=off,hyper=off,\
+                                          messaging=debug,node=error";
+
+        let mut directives = DEFAULT_LOG_FILTER.split(',').map(String::from).collect::<Vec<_>>();
+        if self.development.dev {
+            directives.push("server=debug".to_string());
+        }
+
+        let modules: [(&[&str], Option<LogLevel>); 4] = [
+            (&["executor", "katana::executor", "katana_executor"], self.logging.log_executor),
+            (&["rpc", "server", "katana_rpc"], self.logging.log_rpc),
+            (&["pool", "katana_pool"], self.logging.log_pool),
+            (
+                &["forking::backend", "katana::core::backend", "katana_provider", "katana_db"],
+                self.logging.log_storage,
+            ),
+        ];
+
+        for (targets, level) in modules {
+            let Some(level) = level else { continue };
+            for target in targets {
+                directives.retain(|d| d.split('=').next() != Some(*target));
+                directives.push(format!("{target}={level}"));
+            }
+        }
+
+        directives.join(",")
     }
 
     pub fn config(&self) -> Result<katana_node::config::Config> {
+        if self.scheduler.is_some() && !self.development.dev {
+            bail!("the transaction scheduler is only available in dev mode (`--dev`)");
+        }
+
+        // the messages of a block are sent from its receipts, which may be pruned before
+        if self.pruning.prune_history.is_some() && self.messaging.is_some() {
+            bail!("the history pruning (`--prune.history`) can't be used with messaging");
+        }
+
         let db = self.db_config();
         let rpc = self.rpc_config();
-        let dev = self.dev_config();
+        let dev = self.dev_config()?;
         let chain = self.chain_spec()?;
         let metrics = self.metrics_config();
+        let grpc = self.grpc_config();
         let forking = self.forking_config()?;
         let execution = self.execution_config();
         let sequencing = self.sequencer_config();
         let messaging = self.messaging.clone();
-
-        Ok(Config { metrics, db, dev, rpc, chain, execution, sequencing, messaging, forking })
+        let pool = self.pool_config();
+
+        Ok(Config {
+            metrics,
+            grpc,
+            db,
+            dev,
+            rpc,
+            chain,
+            execution,
+            sequencing,
+            messaging,
+            forking,
+            pool,
+        })
     }
 
     fn sequencer_config(&self) -> SequencingConfig {
-        SequencingConfig { block_time: self.block_time, no_mining: self.no_mining }
+        SequencingConfig {
+            block_time: self.block_time,
+            no_mining: self.no_mining,
+            invariant_script: self.invariant_script.clone(),
+            invariant_dump_dir: self.invariant_dump_dir.clone(),
+            scheduler: self.scheduler.clone(),
+        }
     }
 
     fn rpc_config(&self) -> RpcConfig {
-        let mut apis = HashSet::from([ApiKind::Starknet, ApiKind::Torii, ApiKind::Saya]);
+        let mut apis =
+            HashSet::from([ApiKind::Starknet, ApiKind::Katana, ApiKind::Torii, ApiKind::Saya]);
         // only enable `katana` API in dev mode
         if self.development.dev {
             apis.insert(ApiKind::Dev);
@@ -192,7 +491,15 @@ impl NodeArgs {
                 port: self.server.http_port,
                 addr: self.server.http_addr,
                 max_connections: self.server.max_connections,
+                max_batch_size: self.server.max_batch_size,
+                request_timeout: std::time::Duration::from_secs(self.server.request_timeout),
                 cors_origins: self.server.http_cors_origins.clone(),
+                allowed_methods: self.server.allowed_methods.clone(),
+                disabled_methods: self.server.disabled_methods.clone(),
+                max_requests_per_second: self.server.rate_limit,
+                trust_forwarded_for: self.server.trust_forwarded_for,
+                worker_threads: self.server.worker_threads,
+                max_queued_tasks: self.server.max_queued_tasks,
             }
         }
 
@@ -209,6 +516,16 @@ impl NodeArgs {
             chain_spec.id = id;
         }
 
+        let env = &self.starknet.environment;
+        if env.header_da_mode.is_some() || env.header_extra_data.is_some() {
+            chain_spec.header_extension = Some(HeaderExtension {
+                da_mode: env.header_da_mode.unwrap_or(DataAvailabilityMode::L1),
+                extra_data: env.header_extra_data.clone().map(|b| b.to_vec()).unwrap_or_default(),
+            });
+        }
+
+        chain_spec.disabled_syscalls = env.disabled_syscalls.iter().copied().collect();
+
         if let Some(genesis) = self.starknet.genesis.clone() {
             chain_spec.genesis = genesis;
         } else {
@@ -216,10 +533,31 @@ impl NodeArgs {
         }
 
         // generate dev accounts
-        let accounts = DevAllocationsGenerator::new(self.development.total_accounts)
+        let generator = DevAllocationsGenerator::new(self.development.total_accounts)
             .with_seed(parse_seed(&self.development.seed))
-            .with_balance(U256::from(DEFAULT_PREFUNDED_ACCOUNT_BALANCE))
-            .generate();
+            .with_balance(U256::from(DEFAULT_PREFUNDED_ACCOUNT_BALANCE));
+
+        #[cfg(feature = "slot")]
+        let generator = if self.slot.dev_controllers {
+            if !chain_spec.genesis.classes.contains_key(&CONTROLLER_CLASS_HASH) {
+                bail!("the genesis doesn't declare the Controller account class");
+            }
+            generator.with_class(CONTROLLER_CLASS_HASH)
+        } else {
+            generator
+        };
+
+        #[allow(unused_mut)]
+        let mut accounts = generator.generate();
+
+        #[cfg(feature = "slot")]
+        if self.slot.dev_controllers {
+            for account in accounts.values_mut() {
+                let owner =
+                    katana_slot_controller::get_starknet_owner_storage(account.private_key)?;
+                account.storage.get_or_insert_with(Default::default).extend(owner);
+            }
+        }
 
         chain_spec.genesis.extend_allocations(accounts.into_iter().map(|(k, v)| (k, v.into())));
 
@@ -228,10 +566,16 @@ impl NodeArgs {
             katana_slot_controller::add_controller_account(&mut chain_spec.genesis)?;
         }
 
+        if let Some(path) = &self.starknet.genesis_snapshot {
+            GenesisSnapshot::load(path)
+                .with_context(|| format!("Failed to load genesis snapshot `{}`.", path.display()))?
+                .apply(&mut chain_spec.genesis);
+        }
+
         Ok(chain_spec)
     }
 
-    fn dev_config(&self) -> DevConfig {
+    fn dev_config(&self) -> Result<DevConfig> {
         let mut fixed_gas_prices = None;
 
         if self.gpo.l1_eth_gas_price > 0 {
@@ -254,17 +598,46 @@ impl NodeArgs {
             prices.data_gas_price.strk = self.gpo.l1_strk_data_gas_price;
         }
 
-        DevConfig {
+        let gas_pricing = match self.gpo.pricing {
+            GasPricingMode::Fixed => GasPricing::Fixed,
+            GasPricingMode::Dynamic => {
+                if self.gpo.target_gas == 0 {
+                    bail!("the target gas of the dynamic gas pricing must be greater than zero");
+                }
+                GasPricing::Dynamic { target_gas: self.gpo.target_gas }
+            }
+            GasPricingMode::Sampled => {
+                let Some(url) = self.gpo.l1_rpc_url.clone() else {
+                    bail!("the sampled gas pricing requires an L1 node (`--gpo.l1-rpc-url`)");
+                };
+                if self.gpo.sampling_interval == 0 {
+                    bail!("the gas price sampling interval must be at least one second");
+                }
+                let interval = std::time::Duration::from_secs(self.gpo.sampling_interval);
+                GasPricing::Sampled { url, interval }
+            }
+        };
+
+        Ok(DevConfig {
             fixed_gas_prices,
+            gas_pricing,
             fee: !self.development.no_fee,
             account_validation: !self.development.no_account_validation,
-        }
+        })
     }
 
     fn execution_config(&self) -> ExecutionConfig {
+        let env = &self.starknet.environment;
         ExecutionConfig {
-            invocation_max_steps: self.starknet.environment.invoke_max_steps,
-            validation_max_steps: self.starknet.environment.validate_max_steps,
+            invocation_max_steps: env.invoke_max_steps,
+            validation_max_steps: env.validate_max_steps,
+            tx_limits: TxLimits {
+                max_calldata_length: env.max_calldata_length,
+                max_signature_length: env.max_signature_length,
+                max_bytecode_size: env.max_bytecode_size,
+            },
+            gas_accounting: env.gas_accounting,
+            workers: self.execution_workers.unwrap_or(1),
             ..Default::default()
         }
     }
@@ -279,7 +652,20 @@ impl NodeArgs {
     }
 
     fn db_config(&self) -> DbConfig {
-        DbConfig { dir: self.db_dir.clone() }
+        let prune = PruneConfig { history: self.pruning.prune_history };
+        DbConfig { dir: self.db_dir.clone(), prune }
+    }
+
+    fn pool_config(&self) -> PoolConfig {
+        let nonce_validation = if self.txpool.txpool_queue_nonce_gaps {
+            NonceValidation::AllowGaps
+        } else {
+            self.txpool.txpool_nonce_validation
+        };
+
+        let max_size =
+            self.txpool.txpool_max_size.map_or(DEFAULT_POOL_MAX_SIZE, |size| size as usize);
+        PoolConfig { max_size, nonce_validation }
     }
 
     fn metrics_config(&self) -> Option<MetricsConfig> {
@@ -294,6 +680,18 @@ impl NodeArgs {
         None
     }
 
+    fn grpc_config(&self) -> Option<GrpcConfig> {
+        #[cfg(feature = "grpc")]
+        if self.grpc.grpc {
+            Some(GrpcConfig { addr: self.grpc.grpc_addr, port: self.grpc.grpc_port })
+        } else {
+            None
+        }
+
+        #[cfg(not(feature = "grpc"))]
+        None
+    }
+
     /// Parse the node config from the command line arguments and the config file,
     /// and merge them together prioritizing the command line arguments.
     pub fn with_config_file(mut self) -> Result<Self> {
@@ -307,6 +705,10 @@ impl NodeArgs {
         // Currently, the merge is made at the top level of the commands.
         // We may add recursive merging in the future.
 
+        if !self.silent {
+            self.silent = config.silent.unwrap_or_default();
+        }
+
         if !self.no_mining {
             self.no_mining = config.no_mining.unwrap_or_default();
         }
@@ -315,10 +717,30 @@ impl NodeArgs {
             self.block_time = config.block_time;
         }
 
+        if self.invariant_script.is_none() {
+            self.invariant_script = config.invariant_script;
+        }
+
+        if self.invariant_dump_dir.is_none() {
+            self.invariant_dump_dir = config.invariant_dump_dir;
+        }
+
+        if self.execution_workers.is_none() {
+            self.execution_workers = config.execution_workers;
+        }
+
+        if self.scheduler.is_none() {
+            self.scheduler = config.scheduler;
+        }
+
         if self.db_dir.is_none() {
             self.db_dir = config.db_dir;
         }
 
+        if self.messaging.is_none() {
+            self.messaging = config.messaging;
+        }
+
         if self.logging == LoggingOptions::default() {
             if let Some(logging) = config.logging {
                 self.logging = logging;
@@ -340,6 +762,13 @@ impl NodeArgs {
             }
         }
 
+        #[cfg(feature = "grpc")]
+        if self.grpc == GrpcOptions::default() {
+            if let Some(grpc) = config.grpc {
+                self.grpc = grpc;
+            }
+        }
+
         self.starknet.merge(config.starknet.as_ref());
         self.development.merge(config.development.as_ref());
 
@@ -355,6 +784,25 @@ impl NodeArgs {
             }
         }
 
+        if self.pruning == PruningOptions::default() {
+            if let Some(pruning) = config.pruning {
+                self.pruning = pruning;
+            }
+        }
+
+        if self.txpool == TxPoolOptions::default() {
+            if let Some(txpool) = config.txpool {
+                self.txpool = txpool;
+            }
+        }
+
+        #[cfg(feature = "slot")]
+        if self.slot == SlotOptions::default() {
+            if let Some(slot) = config.slot {
+                self.slot = slot;
+            }
+        }
+
         Ok(self)
     }
 }
@@ -364,14 +812,19 @@ mod test {
     use std::str::FromStr;
 
     use assert_matches::assert_matches;
+    use katana_core::backend::gas_oracle::DEFAULT_SAMPLING_INTERVAL;
     use katana_core::constants::{
         DEFAULT_ETH_L1_DATA_GAS_PRICE, DEFAULT_ETH_L1_GAS_PRICE, DEFAULT_STRK_L1_DATA_GAS_PRICE,
         DEFAULT_STRK_L1_GAS_PRICE,
     };
+    use katana_core::service::scheduler::Interval;
     use katana_node::config::execution::{
         DEFAULT_INVOCATION_MAX_STEPS, DEFAULT_VALIDATION_MAX_STEPS,
     };
+    #[cfg(feature = "server")]
+    use katana_node::config::rpc::DEFAULT_RPC_MAX_QUEUED_TASKS;
     use katana_primitives::chain::ChainId;
+    use katana_primitives::env::Syscall;
     use katana_primitives::{address, felt, ContractAddress, Felt};
 
     use super::*;
@@ -418,6 +871,63 @@ mod test {
         assert_eq!(config.chain.genesis.sequencer_address, *DEFAULT_SEQUENCER_ADDRESS);
     }
 
+    #[test]
+    fn prune_history() {
+        let config = NodeArgs::parse_from(["katana"]).config().unwrap();
+        assert_eq!(config.db.prune.history, None);
+
+        let config = NodeArgs::parse_from(["katana", "--prune.history", "64"]).config().unwrap();
+        assert_eq!(config.db.prune.history, Some(64));
+
+        // at least the latest block must be kept
+        assert!(NodeArgs::try_parse_from(["katana", "--prune.history", "0"]).is_err());
+
+        let result = NodeArgs::try_parse_from([
+            "katana",
+            "--prune.history",
+            "64",
+            "--fork.provider",
+            "http://localhost:5050",
+        ]);
+        assert!(result.is_err());
+
+        let result = NodeArgs::try_parse_from([
+            "katana",
+            "--prune.history",
+            "64",
+            "--messaging",
+            "../contracts/messaging/anvil.messaging.json",
+        ]);
+        assert_eq!(result.unwrap_err().kind(), clap::error::ErrorKind::ArgumentConflict);
+
+        // the messaging may also come from the config file
+        let mut args = NodeArgs::parse_from(["katana", "--prune.history", "64"]);
+        args.messaging = Some(MessagingConfig::default());
+        assert!(args.config().is_err());
+    }
+
+    #[test]
+    fn txpool_options() {
+        let config = NodeArgs::parse_from(["katana"]).config().unwrap();
+        assert_eq!(config.pool.max_size, DEFAULT_POOL_MAX_SIZE);
+        assert_eq!(config.pool.nonce_validation, NonceValidation::Strict);
+
+        let args = ["katana", "--txpool.max-size", "100", "--txpool.queue-nonce-gaps"];
+        let config = NodeArgs::parse_from(args).config().unwrap();
+        assert_eq!(config.pool.max_size, 100);
+        assert_eq!(config.pool.nonce_validation, NonceValidation::AllowGaps);
+
+        let args = ["katana", "--txpool.nonce-validation", "disabled"];
+        let config = NodeArgs::parse_from(args).config().unwrap();
+        assert_eq!(config.pool.nonce_validation, NonceValidation::Disabled);
+
+        let args = ["katana", "--txpool.nonce-validation", "strict", "--txpool.queue-nonce-gaps"];
+        assert!(NodeArgs::try_parse_from(args).is_err());
+        assert!(NodeArgs::try_parse_from(["katana", "--txpool.nonce-validation", "x"]).is_err());
+
+        assert!(NodeArgs::try_parse_from(["katana", "--txpool.max-size", "0"]).is_err());
+    }
+
     #[test]
     fn custom_fixed_gas_prices() {
         let config = NodeArgs::parse_from(["katana"]).config().unwrap();
@@ -500,6 +1010,90 @@ mod test {
         })
     }
 
+    #[test]
+    fn disabled_syscalls() {
+        let config = NodeArgs::parse_from(["katana"]).config().unwrap();
+        assert!(config.chain.disabled_syscalls.is_empty());
+
+        let config =
+            NodeArgs::parse_from(["katana", "--disable-syscalls", "get_block_hash,secp256k1"])
+                .config()
+                .unwrap();
+        assert_eq!(
+            config.chain.disabled_syscalls.into_iter().collect::<Vec<_>>(),
+            vec![Syscall::GetBlockHash, Syscall::Secp256k1]
+        );
+
+        assert!(NodeArgs::try_parse_from(["katana", "--disable-syscalls", "foo"]).is_err());
+    }
+
+    #[test]
+    fn multiple_chains() {
+        let args = NodeArgs::parse_from(["katana", "--chains", "shard1,shard2", "--db-dir", "db"]);
+        let chains = args.chain_configs(args.config().unwrap()).unwrap();
+
+        let names = chains.iter().map(|(name, _)| name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names, vec!["shard1", "shard2"]);
+        assert_eq!(chains[0].1.chain.id, ChainId::parse("shard1").unwrap());
+        assert_eq!(chains[1].1.chain.id, ChainId::parse("shard2").unwrap());
+        assert_eq!(chains[0].1.db.dir, Some(PathBuf::from("db/shard1")));
+        assert_eq!(chains[1].1.db.dir, Some(PathBuf::from("db/shard2")));
+
+        let args = NodeArgs::parse_from(["katana", "--chains", "shard/1"]);
+        assert!(args.chain_configs(args.config().unwrap()).is_err());
+
+        // the chain id of a chain is its name
+        let args = ["katana", "--chains", "shard1", "--chain-id", "SN_SEPOLIA"];
+        assert!(NodeArgs::try_parse_from(args).is_err());
+    }
+
+    #[test]
+    fn tx_limits() {
+        let config = NodeArgs::parse_from(["katana"]).config().unwrap();
+        assert_eq!(config.execution.tx_limits, TxLimits::default());
+
+        let config = NodeArgs::parse_from([
+            "katana",
+            "--max-calldata-length",
+            "4000",
+            "--max-signature-length",
+            "4000",
+            "--max-bytecode-size",
+            "81920",
+        ])
+        .config()
+        .unwrap();
+
+        let expected = TxLimits {
+            max_calldata_length: Some(4000),
+            max_signature_length: Some(4000),
+            max_bytecode_size: Some(81920),
+        };
+        assert_eq!(config.execution.tx_limits, expected);
+    }
+
+    #[test]
+    fn invariant_script() {
+        let config = NodeArgs::parse_from(["katana"]).config().unwrap();
+        assert!(config.sequencing.invariant_script.is_none());
+        assert!(config.sequencing.invariant_dump_dir.is_none());
+
+        let config = NodeArgs::parse_from([
+            "katana",
+            "--invariant-script",
+            "./check.sh",
+            "--invariant-dump-dir",
+            "./dumps",
+        ])
+        .config()
+        .unwrap();
+        assert_eq!(config.sequencing.invariant_script, Some(PathBuf::from("./check.sh")));
+        assert_eq!(config.sequencing.invariant_dump_dir, Some(PathBuf::from("./dumps")));
+
+        // The dump directory is only meaningful along with a script.
+        assert!(NodeArgs::try_parse_from(["katana", "--invariant-dump-dir", "./dumps"]).is_err());
+    }
+
     #[test]
     fn genesis_with_fixed_gas_prices() {
         let config = NodeArgs::parse_from([
@@ -533,6 +1127,39 @@ mod test {
         })
     }
 
+    #[test]
+    fn gas_pricing_modes() {
+        let config = NodeArgs::parse_from(["katana"]).config().unwrap();
+        assert_eq!(config.dev.gas_pricing, GasPricing::Fixed);
+
+        let config =
+            NodeArgs::parse_from(["katana", "--gpo.pricing", "dynamic", "--gpo.target-gas", "500"])
+                .config()
+                .unwrap();
+        assert_eq!(config.dev.gas_pricing, GasPricing::Dynamic { target_gas: 500 });
+
+        let config = NodeArgs::parse_from([
+            "katana",
+            "--gpo.pricing",
+            "sampled",
+            "--gpo.l1-rpc-url",
+            "http://localhost:8545",
+        ])
+        .config()
+        .unwrap();
+        assert_eq!(
+            config.dev.gas_pricing,
+            GasPricing::Sampled {
+                url: "http://localhost:8545".parse().unwrap(),
+                interval: DEFAULT_SAMPLING_INTERVAL,
+            }
+        );
+
+        // sampling requires an L1 node
+        assert!(NodeArgs::try_parse_from(["katana", "--gpo.pricing", "sampled"]).is_err());
+        assert!(NodeArgs::try_parse_from(["katana", "--gpo.pricing", "eip1559"]).is_err());
+    }
+
     #[test]
     fn config_from_file_and_cli() {
         // CLI args must take precedence over the config file.
@@ -591,4 +1218,149 @@ chain_id.Named = "Mainnet"
         assert_eq!(config.chain.genesis.gas_prices.strk, 8888);
         assert_eq!(config.chain.id, ChainId::Id(Felt::from_str("0x123").unwrap()));
     }
+
+    #[test]
+    #[cfg(feature = "slot")]
+    fn dev_controller_accounts() {
+        use katana_primitives::genesis::constant::{
+            CONTROLLER_CLASS_HASH, DEFAULT_ACCOUNT_CLASS_HASH,
+        };
+
+        let config = NodeArgs::parse_from(["katana"]).config().unwrap();
+        for (_, account) in config.chain.genesis.accounts() {
+            assert_eq!(account.class_hash(), DEFAULT_ACCOUNT_CLASS_HASH);
+        }
+
+        let config = NodeArgs::parse_from(["katana", "--slot.dev-controllers"]).config().unwrap();
+        let mut accounts = config.chain.genesis.accounts().peekable();
+        assert!(accounts.peek().is_some());
+
+        // owned by the Starknet signer of their dev key
+        for (_, account) in accounts {
+            assert_eq!(account.class_hash(), CONTROLLER_CLASS_HASH);
+
+            let private_key = account.private_key().unwrap();
+            let owner = katana_slot_controller::get_starknet_owner_storage(private_key).unwrap();
+            let storage = account.storage().unwrap();
+            assert!(owner.iter().all(|(key, value)| storage.get(key) == Some(value)));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "server")]
+    fn rpc_method_filters_and_rate_limit() {
+        let args = NodeArgs::parse_from([
+            "katana",
+            "--rpc.allowed-methods",
+            "starknet_*,katana_getBlocks",
+            "--rpc.disabled-methods",
+            "starknet_add*",
+            "--rpc.rate-limit",
+            "50",
+            "--rpc.trust-forwarded-for",
+            "--rpc.worker-threads",
+            "4",
+        ]);
+        let config = args.config().unwrap();
+
+        assert_eq!(config.rpc.max_requests_per_second, Some(50));
+        assert!(config.rpc.trust_forwarded_for);
+        assert_eq!(config.rpc.worker_threads, Some(4));
+        assert_eq!(config.rpc.max_queued_tasks, DEFAULT_RPC_MAX_QUEUED_TASKS);
+        assert!(config.rpc.is_method_enabled("katana_getBlocks"));
+        assert!(!config.rpc.is_method_enabled("starknet_addDeclareTransaction"));
+        assert!(!config.rpc.is_method_enabled("torii_getTransactions"));
+    }
+
+    #[test]
+    fn scheduler_from_config_file() {
+        let content = r#"
+[[scheduler.jobs]]
+contract = "0x1234"
+entrypoint = "tick"
+blocks = 5
+"#;
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("katana-scheduler-config.toml");
+        std::fs::write(&path, content).unwrap();
+        let path = path.to_string_lossy().to_string();
+
+        let args = NodeArgs::parse_from(["katana", "--config", &path]).with_config_file().unwrap();
+        assert!(args.config().is_err(), "the scheduler is only available in dev mode");
+
+        let args = NodeArgs::parse_from(["katana", "--dev", "--config", &path])
+            .with_config_file()
+            .unwrap();
+        let scheduler = args.config().unwrap().sequencing.scheduler.unwrap();
+        assert_eq!(scheduler.jobs.len(), 1);
+        assert_eq!(scheduler.jobs[0].entrypoint, "tick");
+        assert_eq!(scheduler.jobs[0].every, Interval::Blocks(5));
+    }
+
+    #[test]
+    fn all_options_have_env_var() {
+        use clap::CommandFactory;
+
+        for arg in NodeArgs::command().get_arguments() {
+            let env = arg.get_env().and_then(|env| env.to_str());
+            assert_matches!(env, Some(env) if env.starts_with("KATANA_"), "{}", arg.get_id());
+        }
+    }
+
+    #[test]
+    fn default_config_file() {
+        let content = NodeArgsConfig::defaults().to_toml().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("katana-default-config.toml");
+        std::fs::write(&path, &content).unwrap();
+
+        let config = NodeArgsConfig::read(&path).unwrap();
+        assert_eq!(config.starknet, Some(StarknetOptions::default()));
+        assert_eq!(config.development, Some(DevOptions::default()));
+
+        let path = path.to_string_lossy().to_string();
+        let args = NodeArgs::parse_from(["katana", "--config", &path]).with_config_file().unwrap();
+        let default = NodeArgs::parse_from(["katana"]);
+        assert_eq!(args.starknet, default.starknet);
+        assert_eq!(args.development, default.development);
+        assert_eq!(args.logging, default.logging);
+    }
+
+    #[test]
+    fn log_levels_per_module() {
+        let args = NodeArgs::parse_from(["katana"]);
+        assert!(args.log_filter().contains("executor=trace"));
+        assert!(!args.log_filter().contains("pool="));
+
+        let args = NodeArgs::parse_from([
+            "katana",
+            "--log.executor",
+            "off",
+            "--log.rpc",
+            "debug",
+            "--log.pool",
+            "warn",
+            "--log.storage",
+            "error",
+            "--log.file",
+            "katana.log",
+        ]);
+
+        let filter = args.log_filter();
+        let directives = filter.split(',').collect::<Vec<_>>();
+
+        assert!(directives.contains(&"executor=off"));
+        assert!(!directives.contains(&"executor=trace"));
+        assert!(directives.contains(&"katana::executor=off"));
+        assert!(directives.contains(&"rpc=debug"));
+        assert!(directives.contains(&"server=debug"));
+        assert!(directives.contains(&"pool=warn"));
+        assert!(directives.contains(&"forking::backend=error"));
+        assert!(!directives.contains(&"forking::backend=trace"));
+        assert!(directives.contains(&"katana_provider=error"));
+        assert_eq!(args.logging.log_file, Some(PathBuf::from("katana.log")));
+
+        // The filter must be accepted by the subscriber.
+        EnvFilter::try_new(filter).unwrap();
+    }
 }
@@ -2,6 +2,7 @@ use std::path::{Path, PathBuf};
 
 use anyhow::Result;
 use katana_core::service::messaging::MessagingConfig;
+use katana_core::service::scheduler::SchedulerConfig;
 use serde::{Deserialize, Serialize};
 
 use crate::options::*;
@@ -10,20 +11,31 @@ use crate::NodeArgs;
 /// Node arguments configuration file.
 #[derive(Debug, Serialize, Deserialize, Default)]
 pub struct NodeArgsConfig {
+    pub silent: Option<bool>,
     pub no_mining: Option<bool>,
     pub block_time: Option<u64>,
+    pub invariant_script: Option<PathBuf>,
+    pub invariant_dump_dir: Option<PathBuf>,
+    pub execution_workers: Option<usize>,
+    pub scheduler: Option<SchedulerConfig>,
     pub db_dir: Option<PathBuf>,
     pub messaging: Option<MessagingConfig>,
     pub logging: Option<LoggingOptions>,
     pub starknet: Option<StarknetOptions>,
     pub gpo: Option<GasPriceOracleOptions>,
     pub forking: Option<ForkingOptions>,
+    pub pruning: Option<PruningOptions>,
+    pub txpool: Option<TxPoolOptions>,
     #[serde(rename = "dev")]
     pub development: Option<DevOptions>,
     #[cfg(feature = "server")]
     pub server: Option<ServerOptions>,
     #[cfg(feature = "server")]
     pub metrics: Option<MetricsOptions>,
+    #[cfg(feature = "grpc")]
+    pub grpc: Option<GrpcOptions>,
+    #[cfg(feature = "slot")]
+    pub slot: Option<SlotOptions>,
 }
 
 impl NodeArgsConfig {
@@ -31,6 +43,36 @@ impl NodeArgsConfig {
         let file = std::fs::read_to_string(path)?;
         Ok(toml::from_str(&file)?)
     }
+
+    /// Configuration with every option set to its default value, so that all the available
+    /// options are listed when serialized.
+    pub fn defaults() -> Self {
+        NodeArgsConfig {
+            silent: Some(false),
+            no_mining: Some(false),
+            logging: Some(LoggingOptions::default()),
+            starknet: Some(StarknetOptions::default()),
+            gpo: Some(GasPriceOracleOptions::default()),
+            forking: Some(ForkingOptions::default()),
+            pruning: Some(PruningOptions::default()),
+            txpool: Some(TxPoolOptions::default()),
+            development: Some(DevOptions::default()),
+            #[cfg(feature = "server")]
+            server: Some(ServerOptions::default()),
+            #[cfg(feature = "server")]
+            metrics: Some(MetricsOptions::default()),
+            #[cfg(feature = "grpc")]
+            grpc: Some(GrpcOptions::default()),
+            #[cfg(feature = "slot")]
+            slot: Some(SlotOptions::default()),
+            ..Default::default()
+        }
+    }
+
+    /// Serializes the configuration in the TOML format of the configuration file.
+    pub fn to_toml(&self) -> Result<String> {
+        Ok(toml::to_string_pretty(self)?)
+    }
 }
 
 impl TryFrom<NodeArgs> for NodeArgsConfig {
@@ -41,8 +83,13 @@ impl TryFrom<NodeArgs> for NodeArgsConfig {
         let args = args.with_config_file()?;
 
         let mut node_config = NodeArgsConfig {
+            silent: if args.silent { Some(true) } else { None },
             no_mining: if args.no_mining { Some(true) } else { None },
             block_time: args.block_time,
+            invariant_script: args.invariant_script,
+            invariant_dump_dir: args.invariant_dump_dir,
+            execution_workers: args.execution_workers,
+            scheduler: args.scheduler,
             db_dir: args.db_dir,
             messaging: args.messaging,
             ..Default::default()
@@ -58,6 +105,10 @@ impl TryFrom<NodeArgs> for NodeArgsConfig {
             if args.gpo == GasPriceOracleOptions::default() { None } else { Some(args.gpo) };
         node_config.forking =
             if args.forking == ForkingOptions::default() { None } else { Some(args.forking) };
+        node_config.pruning =
+            if args.pruning == PruningOptions::default() { None } else { Some(args.pruning) };
+        node_config.txpool =
+            if args.txpool == TxPoolOptions::default() { None } else { Some(args.txpool) };
         node_config.development =
             if args.development == DevOptions::default() { None } else { Some(args.development) };
 
@@ -69,6 +120,18 @@ impl TryFrom<NodeArgs> for NodeArgsConfig {
                 if args.metrics == MetricsOptions::default() { None } else { Some(args.metrics) };
         }
 
+        #[cfg(feature = "grpc")]
+        {
+            node_config.grpc =
+                if args.grpc == GrpcOptions::default() { None } else { Some(args.grpc) };
+        }
+
+        #[cfg(feature = "slot")]
+        {
+            node_config.slot =
+                if args.slot == SlotOptions::default() { None } else { Some(args.slot) };
+        }
+
         Ok(node_config)
     }
 }
@@ -5,22 +5,40 @@
 //! If a configuration file is provided, the values are merged with the clap args, however, the clap
 //! args keep the precedence.
 //!
+//! Every option can also be set with its `KATANA_*` environment variable, eg `KATANA_HTTP_PORT`
+//! for `--http.port`. A value from the environment takes precedence over the configuration file,
+//! but not over the command line.
+//!
 //! Currently, the merge is made at the top level of the commands.
 
 use std::net::IpAddr;
+use std::path::PathBuf;
 
+use alloy_primitives::Bytes;
 use clap::Args;
+use katana_core::backend::gas_oracle::{
+    GasPricingMode, DEFAULT_SAMPLING_INTERVAL, DEFAULT_TARGET_GAS,
+};
+use katana_executor::GasAccounting;
 use katana_node::config::execution::{DEFAULT_INVOCATION_MAX_STEPS, DEFAULT_VALIDATION_MAX_STEPS};
+#[cfg(feature = "grpc")]
+use katana_node::config::grpc::{DEFAULT_GRPC_ADDR, DEFAULT_GRPC_PORT};
 use katana_node::config::metrics::{DEFAULT_METRICS_ADDR, DEFAULT_METRICS_PORT};
 #[cfg(feature = "server")]
-use katana_node::config::rpc::{DEFAULT_RPC_ADDR, DEFAULT_RPC_MAX_CONNECTIONS, DEFAULT_RPC_PORT};
+use katana_node::config::rpc::{
+    DEFAULT_RPC_ADDR, DEFAULT_RPC_MAX_CONNECTIONS, DEFAULT_RPC_MAX_QUEUED_TASKS, DEFAULT_RPC_PORT,
+    DEFAULT_RPC_REQUEST_TIMEOUT,
+};
+use katana_pool::NonceValidation;
 use katana_primitives::block::BlockHashOrNumber;
 use katana_primitives::chain::ChainId;
+use katana_primitives::da::DataAvailabilityMode;
+use katana_primitives::env::Syscall;
 use katana_primitives::genesis::Genesis;
 use serde::{Deserialize, Serialize};
 use url::Url;
 
-use crate::utils::{parse_block_hash_or_number, parse_genesis, LogFormat};
+use crate::utils::{parse_block_hash_or_number, parse_da_mode, parse_genesis, LogFormat, LogLevel};
 
 const DEFAULT_DEV_SEED: &str = "0";
 const DEFAULT_DEV_ACCOUNTS: u16 = 10;
@@ -33,20 +51,20 @@ pub struct MetricsOptions {
     ///
     /// For now, metrics will still be collected even if this flag is not set. This only
     /// controls whether the metrics server is started or not.
-    #[arg(long)]
+    #[arg(long, env = "KATANA_METRICS")]
     #[serde(default)]
     pub metrics: bool,
 
     /// The metrics will be served at the given address.
     #[arg(requires = "metrics")]
-    #[arg(long = "metrics.addr", value_name = "ADDRESS")]
+    #[arg(long = "metrics.addr", value_name = "ADDRESS", env = "KATANA_METRICS_ADDR")]
     #[arg(default_value_t = DEFAULT_METRICS_ADDR)]
     #[serde(default = "default_metrics_addr")]
     pub metrics_addr: IpAddr,
 
     /// The metrics will be served at the given port.
     #[arg(requires = "metrics")]
-    #[arg(long = "metrics.port", value_name = "PORT")]
+    #[arg(long = "metrics.port", value_name = "PORT", env = "KATANA_METRICS_PORT")]
     #[arg(default_value_t = DEFAULT_METRICS_PORT)]
     #[serde(default = "default_metrics_port")]
     pub metrics_port: u16,
@@ -62,33 +80,135 @@ impl Default for MetricsOptions {
     }
 }
 
+#[cfg(feature = "grpc")]
+#[derive(Debug, Args, Clone, Serialize, Deserialize, PartialEq)]
+#[command(next_help_heading = "gRPC options")]
+pub struct GrpcOptions {
+    /// Enable the gRPC server.
+    ///
+    /// The server serves the read methods of the Starknet API, and streams the new blocks and
+    /// events of the chain. Calls and fee estimations are only served by the JSON-RPC API.
+    #[arg(long, env = "KATANA_GRPC")]
+    #[serde(default)]
+    pub grpc: bool,
+
+    /// The gRPC server will listen on the given address.
+    #[arg(requires = "grpc")]
+    #[arg(long = "grpc.addr", value_name = "ADDRESS", env = "KATANA_GRPC_ADDR")]
+    #[arg(default_value_t = DEFAULT_GRPC_ADDR)]
+    #[serde(default = "default_grpc_addr")]
+    pub grpc_addr: IpAddr,
+
+    /// The gRPC server will listen on the given port.
+    #[arg(requires = "grpc")]
+    #[arg(long = "grpc.port", value_name = "PORT", env = "KATANA_GRPC_PORT")]
+    #[arg(default_value_t = DEFAULT_GRPC_PORT)]
+    #[serde(default = "default_grpc_port")]
+    pub grpc_port: u16,
+}
+
+#[cfg(feature = "grpc")]
+impl Default for GrpcOptions {
+    fn default() -> Self {
+        GrpcOptions { grpc: false, grpc_addr: DEFAULT_GRPC_ADDR, grpc_port: DEFAULT_GRPC_PORT }
+    }
+}
+
 #[cfg(feature = "server")]
 #[derive(Debug, Args, Clone, Serialize, Deserialize, PartialEq)]
 #[command(next_help_heading = "Server options")]
 pub struct ServerOptions {
     /// HTTP-RPC server listening interface.
-    #[arg(long = "http.addr", value_name = "ADDRESS")]
+    #[arg(long = "http.addr", value_name = "ADDRESS", env = "KATANA_HTTP_ADDR")]
     #[arg(default_value_t = DEFAULT_RPC_ADDR)]
     #[serde(default = "default_http_addr")]
     pub http_addr: IpAddr,
 
     /// HTTP-RPC server listening port.
-    #[arg(long = "http.port", value_name = "PORT")]
+    #[arg(long = "http.port", value_name = "PORT", env = "KATANA_HTTP_PORT")]
     #[arg(default_value_t = DEFAULT_RPC_PORT)]
     #[serde(default = "default_http_port")]
     pub http_port: u16,
 
     /// Comma separated list of domains from which to accept cross origin requests.
-    #[arg(long = "http.cors_origins")]
+    #[arg(long = "http.cors_origins", env = "KATANA_HTTP_CORS_ORIGINS")]
     #[arg(value_delimiter = ',')]
     #[serde(default)]
     pub http_cors_origins: Option<Vec<String>>,
 
     /// Maximum number of concurrent connections allowed.
-    #[arg(long = "rpc.max-connections", value_name = "COUNT")]
+    #[arg(long = "rpc.max-connections", value_name = "COUNT", env = "KATANA_RPC_MAX_CONNECTIONS")]
     #[arg(default_value_t = DEFAULT_RPC_MAX_CONNECTIONS)]
     #[serde(default = "default_max_connections")]
     pub max_connections: u32,
+
+    /// Maximum number of calls allowed in a batch request.
+    ///
+    /// Batches are unlimited if not set.
+    #[arg(long = "rpc.max-batch-size", value_name = "COUNT", env = "KATANA_RPC_MAX_BATCH_SIZE")]
+    #[serde(default)]
+    pub max_batch_size: Option<u32>,
+
+    /// Timeout of a request, in seconds.
+    ///
+    /// A batch request is timed out as a whole.
+    #[arg(long = "rpc.request-timeout", value_name = "SECONDS")]
+    #[arg(env = "KATANA_RPC_REQUEST_TIMEOUT")]
+    #[arg(default_value_t = DEFAULT_RPC_REQUEST_TIMEOUT)]
+    #[serde(default = "default_request_timeout")]
+    pub request_timeout: u64,
+
+    /// Comma separated list of the RPC methods to expose, out of those of the enabled APIs.
+    ///
+    /// A method ending with `*` matches all the methods starting with what precedes it, eg
+    /// `starknet_*`. All the methods are exposed if not set.
+    #[arg(long = "rpc.allowed-methods", value_name = "METHODS")]
+    #[arg(env = "KATANA_RPC_ALLOWED_METHODS", value_delimiter = ',')]
+    #[serde(default)]
+    pub allowed_methods: Option<Vec<String>>,
+
+    /// Comma separated list of the RPC methods not to expose, eg `dev_*`.
+    ///
+    /// Takes precedence over `--rpc.allowed-methods`.
+    #[arg(long = "rpc.disabled-methods", value_name = "METHODS")]
+    #[arg(env = "KATANA_RPC_DISABLED_METHODS", value_delimiter = ',')]
+    #[serde(default)]
+    pub disabled_methods: Vec<String>,
+
+    /// Maximum number of requests per second from a single client.
+    ///
+    /// The requests are counted per connection, not per IP address: a client opening several
+    /// connections gets the limit on each of them. With `--rpc.trust-forwarded-for`, they are
+    /// counted per address of the `X-Forwarded-For` header instead. Unlimited if not set.
+    #[arg(long = "rpc.rate-limit", value_name = "REQUESTS", env = "KATANA_RPC_RATE_LIMIT")]
+    #[serde(default)]
+    pub rate_limit: Option<u32>,
+
+    /// Identify the clients of the rate limit by the address the reverse proxy in front of the
+    /// node appends to the `X-Forwarded-For` header.
+    ///
+    /// Only set it if the node can't be reached without going through the proxy, since clients
+    /// can send the header with any address.
+    #[arg(long = "rpc.trust-forwarded-for", env = "KATANA_RPC_TRUST_FORWARDED_FOR")]
+    #[arg(requires = "rate_limit")]
+    #[serde(default)]
+    pub trust_forwarded_for: bool,
+
+    /// Number of threads running the calls, fee estimations and simulations.
+    ///
+    /// Defaults to the number of CPUs.
+    #[arg(long = "rpc.worker-threads", value_name = "COUNT", env = "KATANA_RPC_WORKER_THREADS")]
+    #[serde(default)]
+    pub worker_threads: Option<usize>,
+
+    /// Maximum number of calls, fee estimations and simulations waiting for a worker thread.
+    ///
+    /// The requests exceeding it are rejected with a "server is busy" error.
+    #[arg(long = "rpc.max-queued-tasks", value_name = "COUNT")]
+    #[arg(env = "KATANA_RPC_MAX_QUEUED_TASKS")]
+    #[arg(default_value_t = DEFAULT_RPC_MAX_QUEUED_TASKS)]
+    #[serde(default = "default_max_queued_tasks")]
+    pub max_queued_tasks: usize,
 }
 
 #[cfg(feature = "server")]
@@ -98,7 +218,15 @@ impl Default for ServerOptions {
             http_addr: DEFAULT_RPC_ADDR,
             http_port: DEFAULT_RPC_PORT,
             max_connections: DEFAULT_RPC_MAX_CONNECTIONS,
+            max_batch_size: None,
+            request_timeout: DEFAULT_RPC_REQUEST_TIMEOUT,
             http_cors_origins: None,
+            allowed_methods: None,
+            disabled_methods: Vec::new(),
+            rate_limit: None,
+            trust_forwarded_for: false,
+            worker_threads: None,
+            max_queued_tasks: DEFAULT_RPC_MAX_QUEUED_TASKS,
         }
     }
 }
@@ -110,10 +238,22 @@ pub struct StarknetOptions {
     #[serde(rename = "env")]
     pub environment: EnvironmentOptions,
 
-    #[arg(long)]
+    /// Path to a genesis configuration file, in JSON or TOML (`.toml` extension).
+    ///
+    /// The file can declare classes, deploy contracts at fixed addresses with their storage, and
+    /// fund accounts in the genesis block.
+    #[arg(long, env = "KATANA_GENESIS")]
     #[arg(value_parser = parse_genesis)]
     #[arg(conflicts_with_all(["seed", "total_accounts"]))]
     pub genesis: Option<Genesis>,
+
+    /// Path to a genesis snapshot exported from another chain, eg after migrating a world.
+    ///
+    /// The classes and contracts of the snapshot are allocated in the genesis block, so the chain
+    /// starts directly in that state.
+    #[arg(long = "genesis.snapshot", value_name = "PATH", env = "KATANA_GENESIS_SNAPSHOT")]
+    #[arg(conflicts_with = "fork_provider")]
+    pub genesis_snapshot: Option<PathBuf>,
 }
 
 impl StarknetOptions {
@@ -124,6 +264,10 @@ impl StarknetOptions {
             if self.genesis.is_none() {
                 self.genesis = other.genesis.clone();
             }
+
+            if self.genesis_snapshot.is_none() {
+                self.genesis_snapshot = other.genesis_snapshot.clone();
+            }
         }
     }
 }
@@ -136,22 +280,70 @@ pub struct EnvironmentOptions {
     /// The chain ID. If a raw hex string (`0x` prefix) is provided, then it'd
     /// used as the actual chain ID. Otherwise, it's represented as the raw
     /// ASCII values. It must be a valid Cairo short string.
-    #[arg(long)]
+    #[arg(long, env = "KATANA_CHAIN_ID")]
     #[arg(value_parser = ChainId::parse)]
     #[serde(default)]
     pub chain_id: Option<ChainId>,
 
     /// The maximum number of steps available for the account validation logic.
-    #[arg(long)]
+    #[arg(long, env = "KATANA_VALIDATE_MAX_STEPS")]
     #[arg(default_value_t = DEFAULT_VALIDATION_MAX_STEPS)]
     #[serde(default = "default_validate_max_steps")]
     pub validate_max_steps: u32,
 
     /// The maximum number of steps available for the account execution logic.
-    #[arg(long)]
+    #[arg(long, env = "KATANA_INVOKE_MAX_STEPS")]
     #[arg(default_value_t = DEFAULT_INVOCATION_MAX_STEPS)]
     #[serde(default = "default_invoke_max_steps")]
     pub invoke_max_steps: u32,
+
+    /// The maximum length of the calldata of a transaction. Unlimited if not set.
+    #[arg(long, value_name = "LENGTH", env = "KATANA_MAX_CALLDATA_LENGTH")]
+    #[serde(default)]
+    pub max_calldata_length: Option<usize>,
+
+    /// The maximum length of the signature of a transaction. Unlimited if not set.
+    #[arg(long, value_name = "LENGTH", env = "KATANA_MAX_SIGNATURE_LENGTH")]
+    #[serde(default)]
+    pub max_signature_length: Option<usize>,
+
+    /// The maximum size, in felts, of the compiled bytecode of a declared class. Unlimited if
+    /// not set.
+    #[arg(long, value_name = "SIZE", env = "KATANA_MAX_BYTECODE_SIZE")]
+    #[serde(default)]
+    pub max_bytecode_size: Option<usize>,
+
+    /// How the computation of transactions is accounted for in their fee.
+    ///
+    /// `sierra-gas` reports the fees in receipts and estimates as if the computation was paid
+    /// with the Sierra gas consumed instead of the Cairo steps.
+    #[arg(long, value_name = "MODE", env = "KATANA_GAS_ACCOUNTING")]
+    #[arg(default_value_t = GasAccounting::CairoSteps)]
+    #[serde(default)]
+    pub gas_accounting: GasAccounting,
+
+    /// The data availability mode advertised in the header extension of every block.
+    ///
+    /// Setting this or `--header.extra-data` attaches a header extension to the produced
+    /// blocks, available through the `katana_getBlockHeaderExtension` method.
+    #[arg(long = "header.da-mode", value_name = "MODE", env = "KATANA_HEADER_DA_MODE")]
+    #[arg(value_parser = parse_da_mode)]
+    #[serde(default)]
+    pub header_da_mode: Option<DataAvailabilityMode>,
+
+    /// Operator-defined hex encoded bytes included in the header extension of every block.
+    #[arg(long = "header.extra-data", value_name = "HEX", env = "KATANA_HEADER_EXTRA_DATA")]
+    #[serde(default)]
+    pub header_extra_data: Option<Bytes>,
+
+    /// Comma separated list of syscalls that contracts are not allowed to use, e.g.
+    /// `get_block_hash,secp256k1,replace_class`.
+    ///
+    /// Declaring a class whose Sierra program uses any of them fails.
+    #[arg(long = "disable-syscalls", value_name = "SYSCALLS", env = "KATANA_DISABLE_SYSCALLS")]
+    #[arg(value_delimiter = ',')]
+    #[serde(default)]
+    pub disabled_syscalls: Vec<Syscall>,
 }
 
 impl Default for EnvironmentOptions {
@@ -159,7 +351,14 @@ impl Default for EnvironmentOptions {
         EnvironmentOptions {
             validate_max_steps: DEFAULT_VALIDATION_MAX_STEPS,
             invoke_max_steps: DEFAULT_INVOCATION_MAX_STEPS,
+            max_calldata_length: None,
+            max_signature_length: None,
+            max_bytecode_size: None,
+            gas_accounting: GasAccounting::CairoSteps,
             chain_id: None,
+            header_da_mode: None,
+            header_extra_data: None,
+            disabled_syscalls: Vec::new(),
         }
     }
 }
@@ -178,6 +377,34 @@ impl EnvironmentOptions {
             if self.invoke_max_steps == DEFAULT_INVOCATION_MAX_STEPS {
                 self.invoke_max_steps = other.invoke_max_steps;
             }
+
+            if self.max_calldata_length.is_none() {
+                self.max_calldata_length = other.max_calldata_length;
+            }
+
+            if self.max_signature_length.is_none() {
+                self.max_signature_length = other.max_signature_length;
+            }
+
+            if self.max_bytecode_size.is_none() {
+                self.max_bytecode_size = other.max_bytecode_size;
+            }
+
+            if self.gas_accounting == GasAccounting::CairoSteps {
+                self.gas_accounting = other.gas_accounting;
+            }
+
+            if self.header_da_mode.is_none() {
+                self.header_da_mode = other.header_da_mode;
+            }
+
+            if self.header_extra_data.is_none() {
+                self.header_extra_data = other.header_extra_data.clone();
+            }
+
+            if self.disabled_syscalls.is_empty() {
+                self.disabled_syscalls = other.disabled_syscalls.clone();
+            }
         }
     }
 }
@@ -187,26 +414,26 @@ impl EnvironmentOptions {
 #[serde(rename = "dev")]
 pub struct DevOptions {
     /// Enable development mode.
-    #[arg(long)]
+    #[arg(long, env = "KATANA_DEV")]
     #[serde(default)]
     pub dev: bool,
 
     /// Specify the seed for randomness of accounts to be predeployed.
     #[arg(requires = "dev")]
-    #[arg(long = "dev.seed", default_value = DEFAULT_DEV_SEED)]
+    #[arg(long = "dev.seed", default_value = DEFAULT_DEV_SEED, env = "KATANA_DEV_SEED")]
     #[serde(default = "default_seed")]
     pub seed: String,
 
     /// Number of pre-funded accounts to generate.
     #[arg(requires = "dev")]
-    #[arg(long = "dev.accounts", value_name = "NUM")]
+    #[arg(long = "dev.accounts", value_name = "NUM", env = "KATANA_DEV_ACCOUNTS")]
     #[arg(default_value_t = DEFAULT_DEV_ACCOUNTS)]
     #[serde(default = "default_accounts")]
     pub total_accounts: u16,
 
     /// Disable charging fee when executing transactions.
     #[arg(requires = "dev")]
-    #[arg(long = "dev.no-fee")]
+    #[arg(long = "dev.no-fee", env = "KATANA_DEV_NO_FEE")]
     #[serde(default)]
     pub no_fee: bool,
 
@@ -214,7 +441,7 @@ pub struct DevOptions {
     ///
     /// Skipping the transaction sender's account validation function.
     #[arg(requires = "dev")]
-    #[arg(long = "dev.no-account-validation")]
+    #[arg(long = "dev.no-account-validation", env = "KATANA_DEV_NO_ACCOUNT_VALIDATION")]
     #[serde(default)]
     pub no_account_validation: bool,
 }
@@ -265,29 +492,104 @@ pub struct ForkingOptions {
     /// This will operate Katana in forked mode. Continuing from the tip of the forked network, or
     /// at a specific block if `fork.block` is provided.
     #[arg(long = "fork.provider", value_name = "URL", conflicts_with = "genesis")]
+    #[arg(env = "KATANA_FORK_PROVIDER")]
     pub fork_provider: Option<Url>,
 
     /// Fork the network at a specific block id, can either be a hash (0x-prefixed) or a block
     /// number.
     #[arg(long = "fork.block", value_name = "BLOCK", requires = "fork_provider")]
+    #[arg(env = "KATANA_FORK_BLOCK")]
     #[arg(value_parser = parse_block_hash_or_number)]
     pub fork_block: Option<BlockHashOrNumber>,
 }
 
+#[derive(Debug, Args, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[command(next_help_heading = "Pruning options")]
+pub struct PruningOptions {
+    /// Keep the state history, receipts and traces of only the given number of most recent
+    /// blocks.
+    ///
+    /// The history of the older blocks is pruned from the database as new blocks are mined, so
+    /// their state can no longer be queried. Their headers and transactions are kept.
+    ///
+    /// Can't be used with messaging, which needs the receipts of every block to send their
+    /// messages to the settlement chain.
+    #[arg(long = "prune.history", value_name = "BLOCKS")]
+    #[arg(conflicts_with_all = ["fork_provider", "messaging"])]
+    #[arg(env = "KATANA_PRUNE_HISTORY")]
+    #[arg(value_parser = clap::value_parser!(u64).range(1..))]
+    pub prune_history: Option<u64>,
+}
+
+#[derive(Debug, Args, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[command(next_help_heading = "Transaction pool options")]
+pub struct TxPoolOptions {
+    /// The maximum number of transactions held in the pool. Defaults to 10000.
+    ///
+    /// When the pool is full, the oldest queued transaction is evicted to make room for a new
+    /// one. New transactions are rejected if there is no queued transaction to evict.
+    #[arg(long = "txpool.max-size", value_name = "COUNT", env = "KATANA_TXPOOL_MAX_SIZE")]
+    #[arg(value_parser = clap::value_parser!(u64).range(1..))]
+    pub txpool_max_size: Option<u64>,
+
+    /// Queue the transactions whose nonce is ahead of their sender's next nonce, instead of
+    /// rejecting them. Same as `--txpool.nonce-validation allow-gaps`.
+    ///
+    /// Queued transactions become pending once the transactions filling the nonce gap are added.
+    #[arg(long = "txpool.queue-nonce-gaps", env = "KATANA_TXPOOL_QUEUE_NONCE_GAPS")]
+    #[arg(conflicts_with = "txpool_nonce_validation")]
+    #[serde(default)]
+    pub txpool_queue_nonce_gaps: bool,
+
+    /// How the nonces of the incoming transactions are validated.
+    ///
+    /// `strict` rejects the transactions whose nonce is ahead of their sender's next nonce.
+    /// `allow-gaps` queues them until the transactions filling the gap are added. `disabled`
+    /// accepts the transactions in any nonce order, as long as their nonce isn't below their
+    /// sender's current nonce, for load testing: transactions executed out of order may fail.
+    #[arg(long = "txpool.nonce-validation", value_name = "MODE")]
+    #[arg(env = "KATANA_TXPOOL_NONCE_VALIDATION")]
+    #[arg(default_value_t = NonceValidation::Strict)]
+    #[serde(default)]
+    pub txpool_nonce_validation: NonceValidation,
+}
+
 #[derive(Debug, Args, Clone, Serialize, Deserialize, Default, PartialEq)]
 #[command(next_help_heading = "Logging options")]
 pub struct LoggingOptions {
     /// Log format to use
-    #[arg(long = "log.format", value_name = "FORMAT")]
+    #[arg(long = "log.format", value_name = "FORMAT", env = "KATANA_LOG_FORMAT")]
     #[arg(default_value_t = LogFormat::Full)]
     pub log_format: LogFormat,
+
+    /// Write the logs to this file, in addition to the standard output.
+    ///
+    /// With `--silent`, the logs are only written to this file.
+    #[arg(long = "log.file", value_name = "PATH", env = "KATANA_LOG_FILE")]
+    pub log_file: Option<PathBuf>,
+
+    /// Log level of the transactions execution.
+    #[arg(long = "log.executor", value_name = "LEVEL", env = "KATANA_LOG_EXECUTOR")]
+    pub log_executor: Option<LogLevel>,
+
+    /// Log level of the RPC server.
+    #[arg(long = "log.rpc", value_name = "LEVEL", env = "KATANA_LOG_RPC")]
+    pub log_rpc: Option<LogLevel>,
+
+    /// Log level of the transaction pool.
+    #[arg(long = "log.pool", value_name = "LEVEL", env = "KATANA_LOG_POOL")]
+    pub log_pool: Option<LogLevel>,
+
+    /// Log level of the storage, including the forked state.
+    #[arg(long = "log.storage", value_name = "LEVEL", env = "KATANA_LOG_STORAGE")]
+    pub log_storage: Option<LogLevel>,
 }
 
-#[derive(Debug, Args, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[derive(Debug, Args, Clone, Serialize, Deserialize, PartialEq)]
 #[command(next_help_heading = "Gas Price Oracle Options")]
 pub struct GasPriceOracleOptions {
     /// The L1 ETH gas price. (denominated in wei)
-    #[arg(long = "gpo.l1-eth-gas-price", value_name = "WEI")]
+    #[arg(long = "gpo.l1-eth-gas-price", value_name = "WEI", env = "KATANA_GPO_L1_ETH_GAS_PRICE")]
     #[arg(default_value_t = 0)]
     #[serde(serialize_with = "cainome_cairo_serde::serialize_as_hex")]
     #[serde(deserialize_with = "cainome_cairo_serde::deserialize_from_hex")]
@@ -295,6 +597,7 @@ pub struct GasPriceOracleOptions {
 
     /// The L1 STRK gas price. (denominated in fri)
     #[arg(long = "gpo.l1-strk-gas-price", value_name = "FRI")]
+    #[arg(env = "KATANA_GPO_L1_STRK_GAS_PRICE")]
     #[arg(default_value_t = 0)]
     #[serde(serialize_with = "cainome_cairo_serde::serialize_as_hex")]
     #[serde(deserialize_with = "cainome_cairo_serde::deserialize_from_hex")]
@@ -302,6 +605,7 @@ pub struct GasPriceOracleOptions {
 
     /// The L1 ETH data gas price. (denominated in wei)
     #[arg(long = "gpo.l1-eth-data-gas-price", value_name = "WEI")]
+    #[arg(env = "KATANA_GPO_L1_ETH_DATA_GAS_PRICE")]
     #[arg(default_value_t = 0)]
     #[serde(serialize_with = "cainome_cairo_serde::serialize_as_hex")]
     #[serde(deserialize_with = "cainome_cairo_serde::deserialize_from_hex")]
@@ -309,10 +613,59 @@ pub struct GasPriceOracleOptions {
 
     /// The L1 STRK data gas price. (denominated in fri)
     #[arg(long = "gpo.l1-strk-data-gas-price", value_name = "FRI")]
+    #[arg(env = "KATANA_GPO_L1_STRK_DATA_GAS_PRICE")]
     #[arg(default_value_t = 0)]
     #[serde(serialize_with = "cainome_cairo_serde::serialize_as_hex")]
     #[serde(deserialize_with = "cainome_cairo_serde::deserialize_from_hex")]
     pub l1_strk_data_gas_price: u128,
+
+    /// How the L1 gas prices change from block to block.
+    ///
+    /// `fixed` uses the prices above for every block. `dynamic` raises the prices after blocks
+    /// consuming more than `--gpo.target-gas` and lowers them after blocks consuming less, like the
+    /// base fee of EIP-1559, without going below the prices above. `sampled` uses the base fees of
+    /// the L1 node at `--gpo.l1-rpc-url`, the STRK prices following them at the ratio of the prices
+    /// above.
+    #[arg(long = "gpo.pricing", value_name = "MODE", env = "KATANA_GPO_PRICING")]
+    #[arg(default_value_t = GasPricingMode::Fixed)]
+    #[serde(default)]
+    pub pricing: GasPricingMode,
+
+    /// The L1 gas consumed by a block that leaves the dynamic prices unchanged.
+    #[arg(long = "gpo.target-gas", value_name = "GAS", env = "KATANA_GPO_TARGET_GAS")]
+    #[arg(default_value_t = DEFAULT_TARGET_GAS)]
+    #[serde(default = "default_target_gas")]
+    #[serde(serialize_with = "cainome_cairo_serde::serialize_as_hex")]
+    #[serde(deserialize_with = "cainome_cairo_serde::deserialize_from_hex")]
+    pub target_gas: u128,
+
+    /// The URL of the L1 node the sampled prices are read from.
+    #[arg(long = "gpo.l1-rpc-url", value_name = "URL", env = "KATANA_GPO_L1_RPC_URL")]
+    #[arg(required_if_eq("pricing", "sampled"))]
+    #[serde(default)]
+    pub l1_rpc_url: Option<Url>,
+
+    /// The interval at which the prices are sampled from L1.
+    #[arg(long = "gpo.sampling-interval", value_name = "SECONDS")]
+    #[arg(env = "KATANA_GPO_SAMPLING_INTERVAL")]
+    #[arg(default_value_t = DEFAULT_SAMPLING_INTERVAL.as_secs())]
+    #[serde(default = "default_sampling_interval")]
+    pub sampling_interval: u64,
+}
+
+impl Default for GasPriceOracleOptions {
+    fn default() -> Self {
+        GasPriceOracleOptions {
+            l1_eth_gas_price: 0,
+            l1_strk_gas_price: 0,
+            l1_eth_data_gas_price: 0,
+            l1_strk_data_gas_price: 0,
+            pricing: GasPricingMode::Fixed,
+            target_gas: DEFAULT_TARGET_GAS,
+            l1_rpc_url: None,
+            sampling_interval: DEFAULT_SAMPLING_INTERVAL.as_secs(),
+        }
+    }
 }
 
 #[cfg(feature = "slot")]
@@ -320,8 +673,17 @@ pub struct GasPriceOracleOptions {
 #[command(next_help_heading = "Slot options")]
 pub struct SlotOptions {
     #[arg(hide = true)]
-    #[arg(long = "slot.controller")]
+    #[arg(long = "slot.controller", env = "KATANA_SLOT_CONTROLLER")]
     pub controller: bool,
+
+    /// Predeploy the dev accounts as Cartridge Controller accounts.
+    ///
+    /// The Controller account class natively supports session keys and policies. Each account is
+    /// owned by the Starknet signer of its dev private key, so its transactions and sessions must
+    /// be signed the way the Controller expects, eg. with the Controller SDK.
+    #[arg(long = "slot.dev-controllers", env = "KATANA_SLOT_DEV_CONTROLLERS")]
+    #[serde(default)]
+    pub dev_controllers: bool,
 }
 
 // ** Default functions to setup serde of the configuration file **
@@ -333,6 +695,14 @@ fn default_accounts() -> u16 {
     DEFAULT_DEV_ACCOUNTS
 }
 
+fn default_target_gas() -> u128 {
+    DEFAULT_TARGET_GAS
+}
+
+fn default_sampling_interval() -> u64 {
+    DEFAULT_SAMPLING_INTERVAL.as_secs()
+}
+
 fn default_validate_max_steps() -> u32 {
     DEFAULT_VALIDATION_MAX_STEPS
 }
@@ -356,6 +726,16 @@ fn default_max_connections() -> u32 {
     DEFAULT_RPC_MAX_CONNECTIONS
 }
 
+#[cfg(feature = "server")]
+fn default_request_timeout() -> u64 {
+    DEFAULT_RPC_REQUEST_TIMEOUT
+}
+
+#[cfg(feature = "server")]
+fn default_max_queued_tasks() -> usize {
+    DEFAULT_RPC_MAX_QUEUED_TASKS
+}
+
 #[cfg(feature = "server")]
 fn default_metrics_addr() -> IpAddr {
     DEFAULT_METRICS_ADDR
@@ -365,3 +745,13 @@ fn default_metrics_addr() -> IpAddr {
 fn default_metrics_port() -> u16 {
     DEFAULT_METRICS_PORT
 }
+
+#[cfg(feature = "grpc")]
+fn default_grpc_addr() -> IpAddr {
+    DEFAULT_GRPC_ADDR
+}
+
+#[cfg(feature = "grpc")]
+fn default_grpc_port() -> u16 {
+    DEFAULT_GRPC_PORT
+}
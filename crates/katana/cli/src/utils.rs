@@ -1,7 +1,7 @@
 use std::fmt::Display;
 use std::path::PathBuf;
 
-use anyhow::{Context, Result};
+use anyhow::{anyhow, Context, Result};
 use clap::builder::PossibleValue;
 use clap::ValueEnum;
 use console::Style;
@@ -9,6 +9,7 @@ use katana_primitives::block::{BlockHash, BlockHashOrNumber, BlockNumber};
 use katana_primitives::chain_spec::ChainSpec;
 use katana_primitives::class::ClassHash;
 use katana_primitives::contract::ContractAddress;
+use katana_primitives::da::DataAvailabilityMode;
 use katana_primitives::genesis::allocation::GenesisAccountAlloc;
 use katana_primitives::genesis::constant::{
     DEFAULT_LEGACY_ERC20_CLASS_HASH, DEFAULT_LEGACY_UDC_CLASS_HASH, DEFAULT_UDC_ADDRESS,
@@ -40,6 +41,15 @@ pub fn parse_genesis(value: &str) -> Result<Genesis> {
     Ok(genesis)
 }
 
+/// Used as clap value parser for [DataAvailabilityMode].
+pub fn parse_da_mode(value: &str) -> Result<DataAvailabilityMode> {
+    match value.to_lowercase().as_str() {
+        "l1" => Ok(DataAvailabilityMode::L1),
+        "l2" => Ok(DataAvailabilityMode::L2),
+        _ => Err(anyhow!("invalid data availability mode `{value}`, expected `l1` or `l2`")),
+    }
+}
+
 /// If the value starts with `0x`, it is parsed as a [`BlockHash`], otherwise as a [`BlockNumber`].
 pub fn parse_block_hash_or_number(value: &str) -> Result<BlockHashOrNumber> {
     if value.starts_with("0x") {
@@ -79,6 +89,41 @@ impl Display for LogFormat {
     }
 }
 
+/// Verbosity of the logs of a module.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl ValueEnum for LogLevel {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Off, Self::Error, Self::Warn, Self::Info, Self::Debug, Self::Trace]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(PossibleValue::new(self.to_string()))
+    }
+}
+
+impl Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Off => write!(f, "off"),
+            Self::Error => write!(f, "error"),
+            Self::Warn => write!(f, "warn"),
+            Self::Info => write!(f, "info"),
+            Self::Debug => write!(f, "debug"),
+            Self::Trace => write!(f, "trace"),
+        }
+    }
+}
+
 pub fn print_intro(args: &NodeArgs, chain: &ChainSpec) {
     let mut accounts = chain.genesis.accounts().peekable();
     let account_class_hash = accounts.peek().map(|e| e.1.class_hash());
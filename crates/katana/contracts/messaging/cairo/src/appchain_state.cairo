@@ -0,0 +1,89 @@
+//! The state of an appchain settled on starknet.
+//!
+//! This contract, deployed on starknet, records the state updates
+//! posted by the sequencer of the appchain (katana in that case) for
+//! every block it produces, in the same transaction as the messages
+//! of the block are registered on `appchain_messaging`.
+//!
+//! There is no proof involved, the state updates are trusted as long as
+//! they are posted by the appchain account. It's only meant to prototype
+//! the settlement of an appchain locally.
+
+/// Trait for Appchain state. For now, only one appchain is whitelisted.
+#[starknet::interface]
+trait IAppchainState<T> {
+    /// Posts the state update of an appchain block.
+    /// Blocks already posted are ignored, the other ones must be posted in order.
+    fn update_state(ref self: T, block_number: u64, block_hash: felt252, state_root: felt252);
+
+    /// Returns the number of blocks posted, and the hash and state root of the last one.
+    fn appchain_state(self: @T) -> (u64, felt252, felt252);
+}
+
+#[starknet::contract]
+mod appchain_state {
+    use starknet::ContractAddress;
+
+    use super::IAppchainState;
+
+    #[storage]
+    struct Storage {
+        // The account on Starknet used by the appchain sequencer to post the state updates.
+        appchain_account: ContractAddress,
+        // The number of blocks posted, which is the number of the next block to post.
+        block_count: u64,
+        // The hash of the last block posted.
+        block_hash: felt252,
+        // The state root of the last block posted.
+        state_root: felt252,
+    }
+
+    #[event]
+    #[derive(Drop, starknet::Event)]
+    enum Event {
+        StateUpdated: StateUpdated,
+    }
+
+    #[derive(Drop, starknet::Event)]
+    struct StateUpdated {
+        #[key]
+        block_number: u64,
+        block_hash: felt252,
+        state_root: felt252,
+    }
+
+    #[constructor]
+    fn constructor(ref self: ContractState, appchain_account: ContractAddress) {
+        self.appchain_account.write(appchain_account);
+    }
+
+    #[abi(embed_v0)]
+    impl AppchainStateImpl of IAppchainState<ContractState> {
+        fn update_state(
+            ref self: ContractState, block_number: u64, block_hash: felt252, state_root: felt252
+        ) {
+            assert(
+                self.appchain_account.read() == starknet::get_caller_address(),
+                'Unauthorized state update',
+            );
+
+            // The sequencer posts the blocks again from the genesis when it restarts.
+            let block_count = self.block_count.read();
+            if block_number < block_count {
+                return;
+            }
+
+            assert(block_number == block_count, 'INVALID_BLOCK_NUMBER');
+
+            self.block_count.write(block_count + 1);
+            self.block_hash.write(block_hash);
+            self.state_root.write(state_root);
+
+            self.emit(StateUpdated { block_number, block_hash, state_root });
+        }
+
+        fn appchain_state(self: @ContractState) -> (u64, felt252, felt252) {
+            (self.block_count.read(), self.block_hash.read(), self.state_root.read())
+        }
+    }
+}
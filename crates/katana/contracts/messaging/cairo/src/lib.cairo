@@ -1,4 +1,5 @@
 mod appchain_messaging;
+mod appchain_state;
 mod contract_msg_l1;
 mod contract_msg_starknet;
 mod contract_1;
@@ -14,6 +14,7 @@ use slot::account_sdk::signers::{HashSigner, Signer, SignerTrait};
 use slot::account_sdk::OriginProvider;
 use slot::credential::Credentials;
 use starknet::core::utils::get_storage_var_address;
+use starknet::signers::SigningKey;
 use tracing::trace;
 
 mod webauthn;
@@ -21,6 +22,9 @@ mod webauthn;
 const WEBAUTHN_RP_ID: &str = "cartridge.gg";
 const WEBAUTHN_ORIGIN: &str = "https://x.cartridge.gg";
 
+// the storage variable name in the Controller contract for storing owners' credentials
+const MULTIPLE_OWNERS_COMPONENT_SUB_STORAGE: &str = "owners";
+
 pub fn add_controller_account(genesis: &mut Genesis) -> Result<()> {
     // bouncer that checks if there is an authenticated slot user
     let credentials = Credentials::load()?;
@@ -67,6 +71,19 @@ fn add_controller_account_inner(
     Ok(())
 }
 
+/// Get the contract storage of a Controller account owned by the Starknet signer of
+/// `private_key`.
+///
+/// Used for the dev accounts predeployed as Controller accounts, whose key is known, so that
+/// clients can authorize sessions with it like for any Controller.
+pub fn get_starknet_owner_storage(private_key: Felt) -> Result<BTreeMap<StorageKey, StorageValue>> {
+    let signer = Signer::Starknet(SigningKey::from_secret_scalar(private_key));
+    let guid = signer.signer().guid();
+
+    let storage = get_storage_var_address(MULTIPLE_OWNERS_COMPONENT_SUB_STORAGE, &[guid])?;
+    Ok(BTreeMap::from([(storage, Felt::ONE)]))
+}
+
 pub mod json {
     use anyhow::Result;
     use katana_primitives::genesis::json::{
@@ -183,8 +200,6 @@ fn get_contract_storage(
         WebauthnSigner::new(WEBAUTHN_RP_ID.to_string(), credential_id, public_key, SlotBackend);
     let guid = Signer::Webauthn(webauthn_signer).signer().guid();
 
-    // the storage variable name in the Controller contract for storing owners' credentials
-    const MULTIPLE_OWNERS_COMPONENT_SUB_STORAGE: &str = "owners";
     let storage = get_storage_var_address(MULTIPLE_OWNERS_COMPONENT_SUB_STORAGE, &[guid])?;
 
     // 1 for boolean True in Cairo. Refer to the provided link above.
@@ -198,7 +213,7 @@ mod tests {
 
     use assert_matches::assert_matches;
     use slot::account::{Controller, ControllerSigner, SignerType, WebAuthnCredential};
-    use starknet::macros::felt;
+    use starknet::macros::{felt, short_string};
 
     use super::*;
 
@@ -255,6 +270,19 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_get_starknet_owner_storage() {
+        let private_key = felt!("0x1234");
+        let public_key = SigningKey::from_secret_scalar(private_key).verifying_key().scalar();
+
+        // the guid of a Starknet signer is the hash of its type and of its public key
+        let guid = starknet_crypto::poseidon_hash(short_string!("Starknet Signer"), public_key);
+        let key = get_storage_var_address(MULTIPLE_OWNERS_COMPONENT_SUB_STORAGE, &[guid]).unwrap();
+
+        let storage = get_starknet_owner_storage(private_key).unwrap();
+        assert_eq!(storage, BTreeMap::from([(key, Felt::ONE)]));
+    }
+
     #[test]
     fn test_get_contract_storage() {
         let credential_id = webauthn::credential::from_base64(WEBAUTHN_CREDENTIAL_ID).unwrap();
@@ -0,0 +1,223 @@
+//! Dumps of the blocks mined by a node, to restore its state on another node started from the
+//! same genesis.
+
+use std::collections::BTreeMap;
+
+use katana_executor::ExecutorFactory;
+use katana_primitives::block::{
+    BlockHash, BlockHashOrNumber, BlockNumber, FinalityStatus, Header, SealedBlock,
+    SealedBlockWithStatus,
+};
+use katana_primitives::class::{ClassHash, CompiledClass, FlattenedSierraClass};
+use katana_primitives::receipt::Receipt;
+use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
+use katana_primitives::trace::TxExecInfo;
+use katana_primitives::transaction::TxWithHash;
+use katana_provider::error::ProviderError;
+use katana_provider::traits::block::{
+    BlockHashProvider, BlockNumberProvider, BlockUnwinder, BlockWriter, HeaderProvider,
+};
+use katana_provider::traits::contract::ContractClassProvider;
+use katana_provider::traits::state::StateFactoryProvider;
+use katana_provider::traits::state_update::StateUpdateProvider;
+use katana_provider::traits::transaction::{
+    ReceiptProvider, TransactionProvider, TransactionTraceProvider,
+};
+use katana_provider::traits::trie::{ClassTrieWriter, ContractTrieWriter};
+use serde::{Deserialize, Serialize};
+use starknet::macros::short_string;
+use starknet_types_core::hash::{self, StarkHash};
+use tracing::info;
+
+use super::{Backend, LOG_TARGET};
+
+#[derive(Debug, thiserror::Error)]
+pub enum StateDumpError {
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+
+    #[error("missing {0} of block {1}")]
+    MissingBlockData(&'static str, BlockNumber),
+
+    #[error("the dump was made from a chain with a different genesis block {0:#x}")]
+    GenesisMismatch(BlockHash),
+
+    #[error("the chain already has blocks on top of its genesis")]
+    ChainNotEmpty,
+
+    #[error("the dumped block {0} is not a child of the previous block")]
+    InvalidParent(BlockNumber),
+
+    #[error("the state root of the dumped block {0} doesn't match its state updates")]
+    StateRootMismatch(BlockNumber),
+}
+
+/// The blocks mined on top of the genesis of a chain, along with their execution output.
+///
+/// Replaying the blocks of a dump on a chain started from the same genesis restores the state of
+/// the dumped chain, without having to execute its transactions again.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StateDump {
+    /// The hash of the genesis block the dumped blocks are built on.
+    pub genesis_hash: BlockHash,
+    /// The dumped blocks, in ascending order.
+    pub blocks: Vec<DumpedBlock>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DumpedBlock {
+    pub hash: BlockHash,
+    pub header: Header,
+    pub body: Vec<TxWithHash>,
+    pub receipts: Vec<Receipt>,
+    pub executions: Vec<TxExecInfo>,
+    pub state_updates: StateUpdates,
+    /// The definitions of the classes declared in the block.
+    pub classes: BTreeMap<ClassHash, CompiledClass>,
+    /// The sierra definitions of the classes declared in the block, legacy classes excluded.
+    pub sierra_classes: BTreeMap<ClassHash, FlattenedSierraClass>,
+}
+
+impl<EF: ExecutorFactory> Backend<EF> {
+    /// Dumps all the blocks mined on top of the genesis block.
+    pub fn dump_state(&self) -> Result<StateDump, StateDumpError> {
+        let provider = self.blockchain.provider();
+        let latest = provider.latest_number()?;
+        let state = provider.latest()?;
+
+        let genesis_hash =
+            provider.block_hash_by_num(0)?.ok_or(StateDumpError::MissingBlockData("hash", 0))?;
+
+        let mut blocks = Vec::with_capacity(latest as usize);
+
+        for number in 1..=latest {
+            let id = BlockHashOrNumber::Num(number);
+            let missing = |data| StateDumpError::MissingBlockData(data, number);
+
+            let hash = provider.block_hash_by_num(number)?.ok_or_else(|| missing("hash"))?;
+            let header = provider.header(id)?.ok_or_else(|| missing("header"))?;
+            let body =
+                provider.transactions_by_block(id)?.ok_or_else(|| missing("transactions"))?;
+            let receipts = provider.receipts_by_block(id)?.ok_or_else(|| missing("receipts"))?;
+            let executions = provider
+                .transaction_executions_by_block(id)?
+                .ok_or_else(|| missing("executions"))?;
+            let state_updates =
+                provider.state_update(id)?.ok_or_else(|| missing("state update"))?;
+
+            let mut classes = BTreeMap::new();
+            let mut sierra_classes = BTreeMap::new();
+
+            let declared = state_updates.declared_classes.keys();
+            for hash in declared.chain(&state_updates.deprecated_declared_classes) {
+                let class = state.class(*hash)?.ok_or_else(|| missing("declared class"))?;
+                classes.insert(*hash, class);
+
+                if let Some(sierra) = state.sierra_class(*hash)? {
+                    sierra_classes.insert(*hash, sierra);
+                }
+            }
+
+            blocks.push(DumpedBlock {
+                hash,
+                header,
+                body,
+                receipts,
+                executions,
+                state_updates,
+                classes,
+                sierra_classes,
+            });
+        }
+
+        Ok(StateDump { genesis_hash, blocks })
+    }
+
+    /// Loads the blocks of a dump on top of the genesis block.
+    ///
+    /// The chain must have the same genesis as the dumped one, and no other block. The state roots
+    /// of the loaded blocks are recomputed, so a dump which doesn't match its state updates is
+    /// rejected, leaving the chain without any of its blocks.
+    pub fn load_state(&self, dump: StateDump) -> Result<(), StateDumpError> {
+        let provider = self.blockchain.provider();
+
+        if provider.latest_number()? != 0 {
+            return Err(StateDumpError::ChainNotEmpty);
+        }
+
+        let genesis_hash =
+            provider.block_hash_by_num(0)?.ok_or(StateDumpError::MissingBlockData("hash", 0))?;
+        if genesis_hash != dump.genesis_hash {
+            return Err(StateDumpError::GenesisMismatch(dump.genesis_hash));
+        }
+
+        let mut parent_hash = genesis_hash;
+        for block in &dump.blocks {
+            if block.header.parent_hash != parent_hash {
+                return Err(StateDumpError::InvalidParent(block.header.number));
+            }
+            parent_hash = block.hash;
+        }
+
+        let block_count = dump.blocks.len();
+
+        // the roots can only be checked once the previous blocks are inserted, so the blocks
+        // loaded before a failure are unwound, with their trie changes, to leave the chain as it
+        // was
+        if let Err(error) = self.insert_dumped_blocks(dump.blocks) {
+            provider.unwind_to(0)?;
+            return Err(error);
+        }
+
+        info!(target: LOG_TARGET, %block_count, "State loaded.");
+        Ok(())
+    }
+
+    fn insert_dumped_blocks(&self, blocks: Vec<DumpedBlock>) -> Result<(), StateDumpError> {
+        let provider = self.blockchain.provider();
+
+        for block in blocks {
+            let number = block.header.number;
+            let expected_root = block.header.state_root;
+            let state_updates = block.state_updates.clone();
+
+            // the block is inserted before its tries are updated, so that a failure past this
+            // point is rolled back by unwinding the block along with its trie changes
+            let sealed = SealedBlock { hash: block.hash, header: block.header, body: block.body };
+            let sealed =
+                SealedBlockWithStatus { block: sealed, status: FinalityStatus::AcceptedOnL2 };
+            let states = StateUpdatesWithDeclaredClasses {
+                state_updates: block.state_updates,
+                declared_sierra_classes: block.sierra_classes,
+                declared_compiled_classes: block.classes,
+            };
+
+            provider.insert_block_with_header_extension(
+                sealed,
+                states,
+                block.receipts,
+                block.executions,
+                self.chain_spec.header_extension.clone(),
+            )?;
+
+            let class_trie_root = ClassTrieWriter::insert_updates(
+                &provider,
+                number,
+                &state_updates.declared_classes,
+            )?;
+            let contract_trie_root =
+                ContractTrieWriter::insert_updates(&provider, number, &state_updates)?;
+            let state_root = hash::Poseidon::hash_array(&[
+                short_string!("STARKNET_STATE_V0"),
+                contract_trie_root,
+                class_trie_root,
+            ]);
+
+            if state_root != expected_root {
+                return Err(StateDumpError::StateRootMismatch(number));
+            }
+        }
+
+        Ok(())
+    }
+}
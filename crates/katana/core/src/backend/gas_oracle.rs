@@ -1,25 +1,298 @@
+//! The L1 gas prices the blocks are opened with.
+//!
+//! The prices are either the configured ones, sampled from an L1 node, or moved from block to
+//! block depending on how full the blocks are, the same way the base fee of EIP-1559 is.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use alloy_network::Ethereum;
+use alloy_provider::{Provider, ReqwestProvider};
+use alloy_rpc_types_eth::BlockNumberOrTag;
+use futures::future::BoxFuture;
 use katana_primitives::block::GasPrices;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use tracing::{trace, warn};
+use url::Url;
+
+const LOG_TARGET: &str = "gas_oracle";
+
+/// The default L1 gas consumed by a block targeted by the dynamic pricing.
+pub const DEFAULT_TARGET_GAS: u128 = 1_000_000;
+
+/// The default interval at which the prices are sampled from L1.
+pub const DEFAULT_SAMPLING_INTERVAL: Duration = Duration::from_secs(12);
+
+/// The maximum change of the dynamic prices from one block to the next one is 1/8 of the prices.
+const DYNAMIC_CHANGE_DENOMINATOR: u128 = 8;
+
+/// How the gas prices change from block to block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GasPricing {
+    /// The configured prices are used for every block.
+    Fixed,
+    /// The prices rise after a block consuming more than `target_gas` L1 gas and decrease after a
+    /// block consuming less, by up to 12.5% for a block consuming twice the target or none. They
+    /// never go below the configured prices.
+    Dynamic { target_gas: u128 },
+    /// The ETH prices are the base fee and the blob base fee of the L1 node at `url`, sampled
+    /// every `interval`. The STRK prices follow them at the ratio of the configured prices.
+    Sampled { url: Url, interval: Duration },
+}
+
+/// The kind of [`GasPricing`], as selected on the command line.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GasPricingMode {
+    #[default]
+    Fixed,
+    Dynamic,
+    Sampled,
+}
+
+impl fmt::Display for GasPricingMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GasPricingMode::Fixed => f.write_str("fixed"),
+            GasPricingMode::Dynamic => f.write_str("dynamic"),
+            GasPricingMode::Sampled => f.write_str("sampled"),
+        }
+    }
+}
+
+impl FromStr for GasPricingMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "fixed" => Ok(GasPricingMode::Fixed),
+            "dynamic" => Ok(GasPricingMode::Dynamic),
+            "sampled" => Ok(GasPricingMode::Sampled),
+            _ => Err(format!(
+                "invalid gas pricing mode `{s}`, expected `fixed`, `dynamic` or `sampled`"
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Prices {
+    gas: GasPrices,
+    data_gas: GasPrices,
+}
+
+#[derive(Debug)]
+struct OracleState {
+    /// The prices set from the configuration.
+    configured: Prices,
+    /// The prices the next blocks are opened with.
+    current: Prices,
+}
 
-// TODO: implement a proper gas oracle function - sample the l1 gas and data gas prices
-// currently this just return the hardcoded value set from the cli or if not set, the default value.
 #[derive(Debug)]
 pub struct L1GasOracle {
-    gas_prices: GasPrices,
-    data_gas_prices: GasPrices,
+    pricing: GasPricing,
+    state: Arc<RwLock<OracleState>>,
 }
 
 impl L1GasOracle {
+    pub fn new(gas_prices: GasPrices, data_gas_prices: GasPrices, pricing: GasPricing) -> Self {
+        let prices = Prices { gas: gas_prices, data_gas: data_gas_prices };
+        let state = OracleState { configured: prices.clone(), current: prices };
+        Self { pricing, state: Arc::new(RwLock::new(state)) }
+    }
+
     pub fn fixed(gas_prices: GasPrices, data_gas_prices: GasPrices) -> Self {
-        Self { gas_prices, data_gas_prices }
+        Self::new(gas_prices, data_gas_prices, GasPricing::Fixed)
+    }
+
+    /// Returns how the prices change from block to block.
+    pub fn pricing(&self) -> &GasPricing {
+        &self.pricing
+    }
+
+    /// Replaces the configured prices, which apply from the next opened block.
+    ///
+    /// The dynamic prices start over from the new prices, and the sampled ones are converted to
+    /// STRK with their ratio from the next sample.
+    pub fn set_fixed(&self, gas_prices: GasPrices, data_gas_prices: GasPrices) {
+        let prices = Prices { gas: gas_prices, data_gas: data_gas_prices };
+        let mut state = self.state.write();
+        state.configured = prices.clone();
+        state.current = prices;
     }
 
     /// Returns the current gas prices.
     pub fn current_gas_prices(&self) -> GasPrices {
-        self.gas_prices.clone()
+        self.state.read().current.gas.clone()
     }
 
     /// Returns the current data gas prices.
     pub fn current_data_gas_prices(&self) -> GasPrices {
-        self.data_gas_prices.clone()
+        self.state.read().current.data_gas.clone()
+    }
+
+    /// Updates the dynamic prices after a block consuming `gas_consumed` L1 gas has been mined.
+    pub fn on_block_mined(&self, gas_consumed: u128) {
+        let GasPricing::Dynamic { target_gas } = self.pricing else { return };
+
+        let mut state = self.state.write();
+        let OracleState { configured, current } = &mut *state;
+
+        let adjust =
+            |price: u128, floor: u128| adjust_dynamic_price(price, floor, gas_consumed, target_gas);
+
+        current.gas.eth = adjust(current.gas.eth, configured.gas.eth);
+        current.gas.strk = adjust(current.gas.strk, configured.gas.strk);
+        current.data_gas.eth = adjust(current.data_gas.eth, configured.data_gas.eth);
+        current.data_gas.strk = adjust(current.data_gas.strk, configured.data_gas.strk);
+
+        trace!(target: LOG_TARGET, %gas_consumed, gas_prices = ?current.gas, "Adjusted gas prices.");
+    }
+
+    /// Returns the task sampling the prices from L1, if the prices are sampled.
+    pub fn sampler(&self) -> Option<BoxFuture<'static, ()>> {
+        let GasPricing::Sampled { url, interval } = &self.pricing else { return None };
+
+        let provider = ReqwestProvider::<Ethereum>::new_http(url.clone());
+        let state = Arc::clone(&self.state);
+        let interval = *interval;
+
+        Some(Box::pin(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                match provider.get_fee_history(1, BlockNumberOrTag::Latest, &[]).await {
+                    Ok(history) => {
+                        let gas_price = history.base_fee_per_gas.last().copied();
+                        let data_gas_price = history.base_fee_per_blob_gas.last().copied();
+                        apply_sample(&mut state.write(), gas_price, data_gas_price);
+                    }
+                    Err(error) => {
+                        warn!(target: LOG_TARGET, %error, "Failed to sample L1 gas prices.");
+                    }
+                }
+            }
+        }))
+    }
+}
+
+/// Moves `price` towards the fullness of the last block, without going below `floor`.
+fn adjust_dynamic_price(price: u128, floor: u128, gas_consumed: u128, target_gas: u128) -> u128 {
+    if target_gas == 0 {
+        return price;
+    }
+
+    let gas_consumed = gas_consumed.min(target_gas.saturating_mul(2));
+    let price = if gas_consumed >= target_gas {
+        let delta = price.saturating_mul(gas_consumed - target_gas)
+            / target_gas
+            / DYNAMIC_CHANGE_DENOMINATOR;
+        // a full block always raises the price, even if it's too low to be raised by 1/8.
+        let delta = if gas_consumed > target_gas { delta.max(1) } else { delta };
+        price.saturating_add(delta)
+    } else {
+        let delta = price.saturating_mul(target_gas - gas_consumed)
+            / target_gas
+            / DYNAMIC_CHANGE_DENOMINATOR;
+        price - delta
+    };
+
+    price.max(floor)
+}
+
+/// Sets the current ETH prices to the sampled ones, and the STRK prices to the sampled ones
+/// converted with the ratio of the configured prices. A missing sample keeps the current prices.
+fn apply_sample(state: &mut OracleState, gas_price: Option<u128>, data_gas_price: Option<u128>) {
+    let to_strk = |eth: u128, configured: &GasPrices| {
+        if configured.eth == 0 {
+            configured.strk
+        } else {
+            eth.saturating_mul(configured.strk) / configured.eth
+        }
+    };
+
+    if let Some(eth) = gas_price {
+        let strk = to_strk(eth, &state.configured.gas);
+        state.current.gas = GasPrices { eth, strk };
+    }
+
+    if let Some(eth) = data_gas_price {
+        let strk = to_strk(eth, &state.configured.data_gas);
+        state.current.data_gas = GasPrices { eth, strk };
+    }
+
+    trace!(target: LOG_TARGET, gas_prices = ?state.current.gas, "Sampled L1 gas prices.");
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::block::GasPrices;
+
+    use super::{adjust_dynamic_price, apply_sample, GasPricing, L1GasOracle, OracleState, Prices};
+
+    #[test]
+    fn dynamic_prices_follow_block_fullness() {
+        let prices = GasPrices { eth: 800, strk: 1600 };
+        let oracle = L1GasOracle::new(
+            prices.clone(),
+            prices.clone(),
+            GasPricing::Dynamic { target_gas: 100 },
+        );
+
+        // a block consuming twice the target raises the prices by 1/8
+        oracle.on_block_mined(200);
+        assert_eq!(oracle.current_gas_prices(), GasPrices { eth: 900, strk: 1800 });
+
+        // the change is capped at 1/8
+        oracle.on_block_mined(1_000);
+        assert_eq!(oracle.current_gas_prices(), GasPrices { eth: 1012, strk: 2025 });
+
+        // a block at the target keeps the prices
+        oracle.on_block_mined(100);
+        assert_eq!(oracle.current_gas_prices(), GasPrices { eth: 1012, strk: 2025 });
+
+        // empty blocks lower the prices down to the configured ones
+        for _ in 0..10 {
+            oracle.on_block_mined(0);
+        }
+        assert_eq!(oracle.current_gas_prices(), prices);
+        assert_eq!(oracle.current_data_gas_prices(), prices);
+    }
+
+    #[test]
+    fn fixed_prices_ignore_block_fullness() {
+        let prices = GasPrices { eth: 800, strk: 1600 };
+        let oracle = L1GasOracle::fixed(prices.clone(), prices.clone());
+
+        oracle.on_block_mined(u128::MAX);
+        assert_eq!(oracle.current_gas_prices(), prices);
+    }
+
+    #[test]
+    fn small_prices_rise_with_full_blocks() {
+        assert_eq!(adjust_dynamic_price(1, 1, 150, 100), 2);
+        assert_eq!(adjust_dynamic_price(1, 1, 50, 100), 1);
+        assert_eq!(adjust_dynamic_price(5, 1, 100, 0), 5);
+    }
+
+    #[test]
+    fn sampled_strk_prices_keep_configured_ratio() {
+        let configured = Prices {
+            gas: GasPrices { eth: 10, strk: 30 },
+            data_gas: GasPrices { eth: 0, strk: 7 },
+        };
+        let mut state = OracleState { configured: configured.clone(), current: configured };
+
+        apply_sample(&mut state, Some(20), Some(4));
+        assert_eq!(state.current.gas, GasPrices { eth: 20, strk: 60 });
+        // without a configured ETH price there is no ratio to convert with
+        assert_eq!(state.current.data_gas, GasPrices { eth: 4, strk: 7 });
+
+        apply_sample(&mut state, None, None);
+        assert_eq!(state.current.gas, GasPrices { eth: 20, strk: 60 });
     }
 }
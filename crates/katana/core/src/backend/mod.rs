@@ -1,32 +1,41 @@
+use std::collections::HashMap;
+use std::iter;
 use std::sync::Arc;
 
+use alloy_primitives::B256;
+use futures::channel::mpsc::{channel, Receiver, Sender};
 use gas_oracle::L1GasOracle;
 use katana_executor::{ExecutionOutput, ExecutionResult, ExecutorFactory};
 use katana_primitives::block::{
-    FinalityStatus, Header, PartialHeader, SealedBlock, SealedBlockWithStatus,
+    BlockNumber, FinalityStatus, Header, PartialHeader, SealedBlock, SealedBlockWithStatus,
 };
 use katana_primitives::chain_spec::ChainSpec;
 use katana_primitives::da::L1DataAvailabilityMode;
 use katana_primitives::env::BlockEnv;
 use katana_primitives::receipt::{Event, ReceiptWithTxHash};
-use katana_primitives::state::{compute_state_diff_hash, StateUpdates};
+use katana_primitives::state::{compute_state_diff_hash, StateUpdates, StorageDiff};
 use katana_primitives::transaction::{TxHash, TxWithHash};
 use katana_primitives::Felt;
 use katana_provider::traits::block::{BlockHashProvider, BlockWriter};
+use katana_provider::traits::state::{StateFactoryProvider, StateProvider};
 use katana_provider::traits::trie::{ClassTrieWriter, ContractTrieWriter};
 use katana_trie::compute_merkle_root;
 use parking_lot::RwLock;
 use starknet::macros::short_string;
 use starknet_types_core::hash::{self, StarkHash};
-use tracing::info;
+use tracing::{info, warn};
 
 pub mod contract;
+pub mod dump;
 pub mod gas_oracle;
 pub mod storage;
+pub mod verify;
 
 use self::storage::Blockchain;
 use crate::env::BlockContextGenerator;
+use crate::reload::ConfigReloader;
 use crate::service::block_producer::{BlockProductionError, MinedBlockOutcome};
+use crate::service::hooks::BlockBuildingHook;
 use crate::utils::get_current_timestamp;
 
 pub(crate) const LOG_TARGET: &str = "katana::core::backend";
@@ -42,6 +51,29 @@ pub struct Backend<EF: ExecutorFactory> {
     pub executor_factory: Arc<EF>,
 
     pub gas_oracle: L1GasOracle,
+
+    /// Listeners notified of the storage changes of every mined block.
+    pub storage_diff_listeners: RwLock<Vec<Sender<BlockStorageDiffs>>>,
+
+    /// Listeners notified of the number of every mined block.
+    pub block_listeners: RwLock<Vec<Sender<BlockNumber>>>,
+
+    /// Reloads the settings that can be changed while the node is running.
+    pub config_reloader: ConfigReloader,
+
+    /// The L1 handler transactions injected by the messaging service, by the hash of the
+    /// settlement chain transaction which sent their messages.
+    pub l1_messages: RwLock<HashMap<B256, Vec<TxHash>>>,
+
+    /// Hooks run around the execution of every transaction included in a block.
+    pub block_building_hooks: RwLock<Vec<Arc<dyn BlockBuildingHook>>>,
+}
+
+/// The storage changes made by a mined block.
+#[derive(Debug, Clone)]
+pub struct BlockStorageDiffs {
+    pub block_number: BlockNumber,
+    pub diffs: Vec<StorageDiff>,
 }
 
 impl<EF: ExecutorFactory> Backend<EF> {
@@ -65,7 +97,15 @@ impl<EF: ExecutorFactory> Backend<EF> {
             }
         }
 
+        // the previous values have to be read before the new state is committed
+        let storage_diffs = if self.storage_diff_listeners.read().is_empty() {
+            None
+        } else {
+            Some(self.storage_diffs(&execution_output.states.state_updates)?)
+        };
+
         let tx_count = txs.len() as u32;
+        let gas_consumed = receipts.iter().map(|r| r.receipt.fee().gas_consumed).sum::<u128>();
         let tx_hashes = txs.iter().map(|tx| tx.hash).collect::<Vec<TxHash>>();
 
         // create a new block and compute its commitment
@@ -82,15 +122,84 @@ impl<EF: ExecutorFactory> Backend<EF> {
         // TODO: maybe should change the arguments for insert_block_with_states_and_receipts to
         // accept ReceiptWithTxHash instead to avoid this conversion.
         let receipts = receipts.into_iter().map(|r| r.receipt).collect::<Vec<_>>();
-        self.blockchain.provider().insert_block_with_states_and_receipts(
+        self.blockchain.provider().insert_block_with_header_extension(
             block,
             execution_output.states,
             receipts,
             traces,
+            self.chain_spec.header_extension.clone(),
         )?;
 
+        if let Some(diffs) = storage_diffs {
+            let diffs = BlockStorageDiffs { block_number, diffs };
+            notify_listeners(&self.storage_diff_listeners, diffs, "storage diffs");
+        }
+
+        // the prices of the next block depend on how full this one is
+        self.gas_oracle.on_block_mined(gas_consumed);
+
+        notify_listeners(&self.block_listeners, block_number, "mined block");
+
         info!(target: LOG_TARGET, %block_number, %tx_count, "Block mined.");
-        Ok(MinedBlockOutcome { block_number, txs: tx_hashes, stats: execution_output.stats })
+        Ok(MinedBlockOutcome {
+            block_number,
+            txs: tx_hashes,
+            skipped_txs: Vec::new(),
+            stats: execution_output.stats,
+        })
+    }
+
+    /// Registers a listener that receives the storage changes of every block mined from now on.
+    pub fn add_storage_diff_listener(&self) -> Receiver<BlockStorageDiffs> {
+        const STORAGE_DIFF_LISTENER_BUFFER_SIZE: usize = 256;
+        let (tx, rx) = channel(STORAGE_DIFF_LISTENER_BUFFER_SIZE);
+        self.storage_diff_listeners.write().push(tx);
+        rx
+    }
+
+    /// Registers a listener that receives the number of every block mined from now on, once the
+    /// block is committed to the storage.
+    pub fn add_block_listener(&self) -> Receiver<BlockNumber> {
+        const BLOCK_LISTENER_BUFFER_SIZE: usize = 256;
+        let (tx, rx) = channel(BLOCK_LISTENER_BUFFER_SIZE);
+        self.block_listeners.write().push(tx);
+        rx
+    }
+
+    /// Registers a hook run around the execution of every transaction included in the blocks
+    /// built from now on.
+    pub fn add_block_building_hook(&self, hook: Arc<dyn BlockBuildingHook>) {
+        self.block_building_hooks.write().push(hook);
+    }
+
+    /// Returns the hooks run around the execution of the transactions of a block.
+    pub fn block_building_hooks(&self) -> Vec<Arc<dyn BlockBuildingHook>> {
+        self.block_building_hooks.read().clone()
+    }
+
+    /// Pairs every storage update with the value the slot had in the latest committed state.
+    fn storage_diffs(
+        &self,
+        state_updates: &StateUpdates,
+    ) -> Result<Vec<StorageDiff>, BlockProductionError> {
+        let state = self.blockchain.provider().latest()?;
+        let mut diffs = Vec::new();
+
+        for (contract_address, entries) in &state_updates.storage_updates {
+            for (key, new_value) in entries {
+                let old_value = state.storage(*contract_address, *key)?.unwrap_or_default();
+                if old_value != *new_value {
+                    diffs.push(StorageDiff {
+                        contract_address: *contract_address,
+                        key: *key,
+                        old_value,
+                        new_value: *new_value,
+                    });
+                }
+            }
+        }
+
+        Ok(diffs)
     }
 
     pub fn update_block_env(&self, block_env: &mut BlockEnv) {
@@ -157,6 +266,21 @@ impl<EF: ExecutorFactory> Backend<EF> {
     }
 }
 
+/// Sends the notification to all listeners, dropping the ones that have been closed.
+fn notify_listeners<T: Clone>(listeners: &RwLock<Vec<Sender<T>>>, item: T, kind: &str) {
+    listeners.write().retain_mut(|listener| match listener.try_send(item.clone()) {
+        Ok(()) => true,
+        Err(e) if e.is_full() => {
+            warn!(
+                target: LOG_TARGET,
+                "Unable to send {kind} notification because channel is full."
+            );
+            true
+        }
+        Err(_) => false,
+    });
+}
+
 #[derive(Debug, Clone)]
 pub struct UncommittedBlock<'a, P>
 where
@@ -221,13 +345,11 @@ where
     }
 
     fn compute_transaction_commitment(&self) -> Felt {
-        let tx_hashes = self.transactions.iter().map(|t| t.hash).collect::<Vec<TxHash>>();
-        compute_merkle_root::<hash::Poseidon>(&tx_hashes).unwrap()
+        compute_transactions_commitment(&self.transactions)
     }
 
     fn compute_receipt_commitment(&self) -> Felt {
-        let receipt_hashes = self.receipts.iter().map(|r| r.compute_hash()).collect::<Vec<Felt>>();
-        compute_merkle_root::<hash::Poseidon>(&receipt_hashes).unwrap()
+        compute_receipts_commitment(self.receipts)
     }
 
     fn compute_state_diff_commitment(&self) -> Felt {
@@ -235,25 +357,7 @@ where
     }
 
     fn compute_event_commitment(&self) -> Felt {
-        // h(emitter_address, tx_hash, h(keys), h(data))
-        fn event_hash(tx: TxHash, event: &Event) -> Felt {
-            let keys_hash = hash::Poseidon::hash_array(&event.keys);
-            let data_hash = hash::Poseidon::hash_array(&event.data);
-            hash::Poseidon::hash_array(&[tx, event.from_address.into(), keys_hash, data_hash])
-        }
-
-        // the iterator will yield all events from all the receipts, each one paired with the
-        // transaction hash that emitted it: (tx hash, event).
-        let events = self.receipts.iter().flat_map(|r| r.events().iter().map(|e| (r.tx_hash, e)));
-
-        let mut hashes = Vec::new();
-        for (tx, event) in events {
-            let event_hash = event_hash(tx, event);
-            hashes.push(event_hash);
-        }
-
-        // compute events commitment
-        compute_merkle_root::<hash::Poseidon>(&hashes).unwrap()
+        compute_events_commitment(self.receipts)
     }
 
     // state_commitment = hPos("STARKNET_STATE_V0", contract_trie_root, class_trie_root)
@@ -279,3 +383,111 @@ where
         ])
     }
 }
+
+/// Computes the leaf of a transaction in the transactions tree of a block.
+///
+/// The leaf is h(tx_hash, signature...), with a single zero in place of an empty signature.
+pub fn transaction_commitment_leaf(tx: &TxWithHash) -> Felt {
+    let signature = tx.transaction.signature();
+    let signature = if signature.is_empty() { &[Felt::ZERO][..] } else { signature };
+    let elements = iter::once(tx.hash).chain(signature.iter().copied()).collect::<Vec<_>>();
+    hash::Poseidon::hash_array(&elements)
+}
+
+/// Computes the commitment of the transactions of a block.
+pub fn compute_transactions_commitment(transactions: &[TxWithHash]) -> Felt {
+    let leaves = transactions.iter().map(transaction_commitment_leaf).collect::<Vec<Felt>>();
+    compute_merkle_root::<hash::Poseidon>(&leaves).unwrap()
+}
+
+/// Computes the commitment of the receipts of a block.
+pub fn compute_receipts_commitment(receipts: &[ReceiptWithTxHash]) -> Felt {
+    let receipt_hashes = receipts.iter().map(|r| r.compute_hash()).collect::<Vec<Felt>>();
+    compute_merkle_root::<hash::Poseidon>(&receipt_hashes).unwrap()
+}
+
+/// Computes the commitment of the events emitted by the transactions of a block.
+pub fn compute_events_commitment(receipts: &[ReceiptWithTxHash]) -> Felt {
+    // h(emitter_address, tx_hash, len(keys), keys..., len(data), data...)
+    fn event_hash(tx: TxHash, event: &Event) -> Felt {
+        let elements = [event.from_address.into(), tx, Felt::from(event.keys.len())]
+            .into_iter()
+            .chain(event.keys.iter().copied())
+            .chain(iter::once(Felt::from(event.data.len())))
+            .chain(event.data.iter().copied())
+            .collect::<Vec<Felt>>();
+        hash::Poseidon::hash_array(&elements)
+    }
+
+    // the iterator will yield all events from all the receipts, each one paired with the
+    // transaction hash that emitted it: (tx hash, event).
+    let events = receipts.iter().flat_map(|r| r.events().iter().map(|e| (r.tx_hash, e)));
+
+    let mut hashes = Vec::new();
+    for (tx, event) in events {
+        let event_hash = event_hash(tx, event);
+        hashes.push(event_hash);
+    }
+
+    // compute events commitment
+    compute_merkle_root::<hash::Poseidon>(&hashes).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::fee::{PriceUnit, TxFeeInfo};
+    use katana_primitives::receipt::{InvokeTxReceipt, Receipt};
+    use katana_primitives::transaction::{InvokeTx, InvokeTxV1, L1HandlerTx, Tx};
+    use katana_primitives::{address, felt};
+
+    use super::*;
+
+    #[test]
+    fn transaction_leaves_include_the_signature() {
+        let signed =
+            InvokeTxV1 { signature: vec![felt!("0x1"), felt!("0x2")], ..Default::default() };
+        let transactions = [
+            TxWithHash { hash: felt!("0xa"), transaction: Tx::Invoke(InvokeTx::V1(signed)) },
+            TxWithHash { hash: felt!("0xb"), transaction: Tx::L1Handler(L1HandlerTx::default()) },
+        ];
+
+        // an empty signature is hashed as a single zero
+        let leaves = [
+            hash::Poseidon::hash_array(&[felt!("0xa"), felt!("0x1"), felt!("0x2")]),
+            hash::Poseidon::hash_array(&[felt!("0xb"), Felt::ZERO]),
+        ];
+        let expected = compute_merkle_root::<hash::Poseidon>(&leaves).unwrap();
+
+        assert_eq!(compute_transactions_commitment(&transactions), expected);
+    }
+
+    #[test]
+    fn event_leaves_are_prefixed_with_lengths() {
+        let event = Event {
+            from_address: address!("0x1"),
+            keys: vec![felt!("0x2"), felt!("0x3")],
+            data: vec![felt!("0x4")],
+        };
+        let receipt = Receipt::Invoke(InvokeTxReceipt {
+            revert_error: None,
+            events: vec![event],
+            messages_sent: Vec::new(),
+            execution_resources: Default::default(),
+            fee: TxFeeInfo { gas_consumed: 0, gas_price: 0, overall_fee: 0, unit: PriceUnit::Wei },
+        });
+        let receipts = [ReceiptWithTxHash::new(felt!("0xa"), receipt)];
+
+        let leaf = hash::Poseidon::hash_array(&[
+            felt!("0x1"),
+            felt!("0xa"),
+            felt!("0x2"),
+            felt!("0x2"),
+            felt!("0x3"),
+            felt!("0x1"),
+            felt!("0x4"),
+        ]);
+        let expected = compute_merkle_root::<hash::Poseidon>(&[leaf]).unwrap();
+
+        assert_eq!(compute_events_commitment(&receipts), expected);
+    }
+}
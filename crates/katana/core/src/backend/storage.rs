@@ -11,14 +11,19 @@ use katana_primitives::state::StateUpdatesWithDeclaredClasses;
 use katana_primitives::version::ProtocolVersion;
 use katana_provider::providers::db::DbProvider;
 use katana_provider::providers::fork::ForkedProvider;
-use katana_provider::traits::block::{BlockProvider, BlockWriter};
+use katana_provider::traits::block::{
+    BlockProvider, BlockUnwinder, BlockWriter, HeaderExtensionProvider, HeaderExtensionWriter,
+};
+use katana_provider::traits::checkpoint::CheckpointProvider;
 use katana_provider::traits::contract::ContractClassWriter;
 use katana_provider::traits::env::BlockEnvProvider;
-use katana_provider::traits::state::{StateFactoryProvider, StateRootProvider, StateWriter};
+use katana_provider::traits::state::{
+    ContractStorageProvider, StateFactoryProvider, StateRootProvider, StateWriter,
+};
 use katana_provider::traits::state_update::StateUpdateProvider;
 use katana_provider::traits::transaction::{
-    ReceiptProvider, TransactionProvider, TransactionStatusProvider, TransactionTraceProvider,
-    TransactionsProviderExt,
+    EventsBloomProvider, ReceiptProvider, TransactionProvider, TransactionStatusProvider,
+    TransactionTraceProvider, TransactionsProviderExt,
 };
 use katana_provider::traits::trie::{ClassTrieWriter, ContractTrieWriter};
 use katana_provider::BlockchainProvider;
@@ -33,19 +38,25 @@ use url::Url;
 pub trait Database:
     BlockProvider
     + BlockWriter
+    + HeaderExtensionProvider
+    + HeaderExtensionWriter
     + TransactionProvider
     + TransactionStatusProvider
     + TransactionTraceProvider
     + TransactionsProviderExt
     + ReceiptProvider
+    + EventsBloomProvider
     + StateUpdateProvider
     + StateRootProvider
     + StateWriter
+    + ContractStorageProvider
     + ContractClassWriter
     + StateFactoryProvider
     + BlockEnvProvider
     + ClassTrieWriter
     + ContractTrieWriter
+    + CheckpointProvider
+    + BlockUnwinder
     + 'static
     + Send
     + Sync
@@ -56,19 +67,25 @@ pub trait Database:
 impl<T> Database for T where
     T: BlockProvider
         + BlockWriter
+        + HeaderExtensionProvider
+        + HeaderExtensionWriter
         + TransactionProvider
         + TransactionStatusProvider
         + TransactionTraceProvider
         + TransactionsProviderExt
         + ReceiptProvider
+        + EventsBloomProvider
         + StateUpdateProvider
         + StateRootProvider
         + StateWriter
+        + ContractStorageProvider
         + ContractClassWriter
         + StateFactoryProvider
         + BlockEnvProvider
         + ClassTrieWriter
         + ContractTrieWriter
+        + CheckpointProvider
+        + BlockUnwinder
         + 'static
         + Send
         + Sync
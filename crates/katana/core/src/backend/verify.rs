@@ -0,0 +1,108 @@
+//! Verification of the hashes of the committed blocks.
+
+use katana_executor::ExecutorFactory;
+use katana_primitives::block::{BlockHash, BlockHashOrNumber, BlockNumber, Header};
+use katana_primitives::receipt::ReceiptWithTxHash;
+use katana_primitives::state::compute_state_diff_hash;
+use katana_primitives::transaction::TxHash;
+use katana_provider::error::ProviderError;
+use katana_provider::traits::block::{BlockHashProvider, HeaderProvider};
+use katana_provider::traits::state_update::StateUpdateProvider;
+use katana_provider::traits::transaction::{ReceiptProvider, TransactionProvider};
+use katana_provider::ProviderResult;
+
+use super::{
+    compute_events_commitment, compute_receipts_commitment, compute_transactions_commitment,
+    Backend,
+};
+
+/// The hash a block was committed with, along with the one recomputed from its content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockHashVerification {
+    pub block_number: BlockNumber,
+    /// The hash the block was committed with.
+    pub block_hash: BlockHash,
+    /// The hash of the header rebuilt from the transactions, receipts and state updates of the
+    /// block.
+    pub computed_hash: BlockHash,
+    /// The header fields whose committed value doesn't match the content of the block.
+    pub mismatches: Vec<&'static str>,
+}
+
+impl BlockHashVerification {
+    /// Returns `true` if the committed hash matches the content of the block.
+    pub fn is_valid(&self) -> bool {
+        self.block_hash == self.computed_hash && self.mismatches.is_empty()
+    }
+}
+
+impl<EF: ExecutorFactory> Backend<EF> {
+    /// Recomputes the hash of a block mined by the node from its content.
+    ///
+    /// The counts and commitments of the header are recomputed from the transactions, receipts
+    /// and state updates of the block. The state root is taken from the header, as the tries
+    /// are only kept at their latest state.
+    pub fn verify_block_hash(
+        &self,
+        block_id: BlockHashOrNumber,
+    ) -> ProviderResult<Option<BlockHashVerification>> {
+        let provider = self.blockchain.provider();
+
+        let Some(header) = provider.header(block_id)? else { return Ok(None) };
+        let number = header.number;
+
+        let block_hash =
+            provider.block_hash_by_num(number)?.ok_or(ProviderError::MissingBlockHash(number))?;
+        let txs = provider
+            .transactions_by_block(id(number))?
+            .ok_or(ProviderError::MissingBlockTxs(number))?;
+        let receipts = provider.receipts_by_block(id(number))?.unwrap_or_default();
+        let state_updates = provider.state_update(id(number))?.unwrap_or_default();
+
+        let tx_hashes = txs.iter().map(|tx| tx.hash).collect::<Vec<TxHash>>();
+        let receipts = tx_hashes
+            .iter()
+            .zip(receipts)
+            .map(|(hash, receipt)| ReceiptWithTxHash::new(*hash, receipt))
+            .collect::<Vec<_>>();
+
+        let computed = Header {
+            transaction_count: txs.len() as u32,
+            events_count: receipts.iter().map(|r| r.events().len() as u32).sum(),
+            state_diff_length: state_updates.len() as u32,
+            transactions_commitment: compute_transactions_commitment(&txs),
+            events_commitment: compute_events_commitment(&receipts),
+            receipts_commitment: compute_receipts_commitment(&receipts),
+            state_diff_commitment: compute_state_diff_hash(state_updates),
+            ..header.clone()
+        };
+
+        let fields = [
+            ("transaction_count", header.transaction_count == computed.transaction_count),
+            ("events_count", header.events_count == computed.events_count),
+            ("state_diff_length", header.state_diff_length == computed.state_diff_length),
+            (
+                "transactions_commitment",
+                header.transactions_commitment == computed.transactions_commitment,
+            ),
+            ("events_commitment", header.events_commitment == computed.events_commitment),
+            ("receipts_commitment", header.receipts_commitment == computed.receipts_commitment),
+            (
+                "state_diff_commitment",
+                header.state_diff_commitment == computed.state_diff_commitment,
+            ),
+        ];
+        let mismatches = fields.into_iter().filter(|(_, eq)| !eq).map(|(name, _)| name).collect();
+
+        Ok(Some(BlockHashVerification {
+            block_number: number,
+            block_hash,
+            computed_hash: computed.compute_hash(),
+            mismatches,
+        }))
+    }
+}
+
+fn id(number: BlockNumber) -> BlockHashOrNumber {
+    BlockHashOrNumber::Num(number)
+}
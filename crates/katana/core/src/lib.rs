@@ -3,5 +3,6 @@
 pub mod backend;
 pub mod constants;
 pub mod env;
+pub mod reload;
 pub mod service;
 pub mod utils;
@@ -0,0 +1,42 @@
+//! Reloading of the configuration of a running node.
+
+use std::fmt;
+use std::sync::Arc;
+
+use anyhow::bail;
+use parking_lot::RwLock;
+
+type ReloadFn = dyn Fn() -> anyhow::Result<()> + Send + Sync;
+
+/// Hook reloading the settings of a running node that can be changed without restarting it.
+///
+/// The node doesn't know where its configuration comes from, so the hook is registered by the
+/// owner of the configuration source, eg. the CLI reading the configuration file again.
+#[derive(Clone, Default)]
+pub struct ConfigReloader(Arc<RwLock<Option<Arc<ReloadFn>>>>);
+
+impl ConfigReloader {
+    /// Registers the hook called on every reload, replacing the previous one if any.
+    pub fn set<F>(&self, hook: F)
+    where
+        F: Fn() -> anyhow::Result<()> + Send + Sync + 'static,
+    {
+        *self.0.write() = Some(Arc::new(hook));
+    }
+
+    /// Reloads the configuration. Fails if no hook has been registered.
+    pub fn reload(&self) -> anyhow::Result<()> {
+        // the lock is released before calling the hook, which may take a while
+        let hook = self.0.read().clone();
+        match hook {
+            Some(hook) => hook(),
+            None => bail!("the configuration of this node can't be reloaded"),
+        }
+    }
+}
+
+impl fmt::Debug for ConfigReloader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfigReloader").field("registered", &self.0.read().is_some()).finish()
+    }
+}
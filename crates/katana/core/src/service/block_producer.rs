@@ -2,17 +2,20 @@ use std::collections::VecDeque;
 use std::future::Future;
 use std::pin::Pin;
 use std::sync::Arc;
-use std::task::{Context, Poll};
+use std::task::{Context, Poll, Waker};
 use std::time::Duration;
 
 use futures::channel::mpsc::{channel, Receiver, Sender};
 use futures::stream::{Stream, StreamExt};
 use futures::FutureExt;
-use katana_executor::{BlockExecutor, ExecutionResult, ExecutionStats, ExecutorFactory};
+use katana_executor::{
+    BlockExecutor, ExecutionOutput, ExecutionResult, ExecutionStats, ExecutorFactory,
+};
 use katana_pool::validation::stateful::TxValidator;
 use katana_primitives::block::{BlockHashOrNumber, ExecutableBlock, PartialHeader};
 use katana_primitives::da::L1DataAvailabilityMode;
 use katana_primitives::receipt::Receipt;
+use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
 use katana_primitives::trace::TxExecInfo;
 use katana_primitives::transaction::{ExecutableTxWithHash, TxHash, TxWithHash};
 use katana_provider::error::ProviderError;
@@ -26,6 +29,7 @@ use tokio::time::{interval_at, Instant, Interval};
 use tracing::{error, info, trace, warn};
 
 use crate::backend::Backend;
+use crate::service::hooks::{self, BlockBuildingHook};
 
 pub(crate) const LOG_TARGET: &str = "miner";
 
@@ -45,6 +49,8 @@ pub enum BlockProductionError {
 pub struct MinedBlockOutcome {
     pub block_number: u64,
     pub txs: Vec<TxHash>,
+    /// The transactions skipped by the block building hooks, dropped without being executed.
+    pub skipped_txs: Vec<TxHash>,
     pub stats: ExecutionStats,
 }
 
@@ -60,7 +66,7 @@ type ServiceFuture<T> = Pin<Box<dyn Future<Output = BlockingTaskResult<T>> + Sen
 type BlockProductionResult = Result<MinedBlockOutcome, BlockProductionError>;
 type BlockProductionFuture = ServiceFuture<Result<MinedBlockOutcome, BlockProductionError>>;
 
-type TxExecutionResult = Result<Vec<TxWithOutcome>, BlockProductionError>;
+type TxExecutionResult = Result<(Vec<TxWithOutcome>, Vec<TxHash>), BlockProductionError>;
 type TxExecutionFuture = ServiceFuture<TxExecutionResult>;
 
 type BlockProductionWithTxnsFuture =
@@ -119,6 +125,100 @@ impl<EF: ExecutorFactory> BlockProducer<EF> {
         matches!(*self.producer.read(), BlockProducerMode::Interval(_))
     }
 
+    /// Changes the block time of _interval_ mining, in milliseconds. Returns `false` without
+    /// changing anything if the block producer is not running in _interval_ mode with a block time.
+    pub fn set_block_time(&self, block_time: u64) -> bool {
+        match &mut *self.producer.write() {
+            BlockProducerMode::Instant(_) => false,
+            BlockProducerMode::Interval(producer) => producer.set_interval(block_time),
+        }
+    }
+
+    /// Opens the pending block again with an updated block context, so that changes to the next
+    /// block timestamp or the gas prices apply to it. Returns `false` if the pending block already
+    /// has transactions, in which case the changes apply from the next block.
+    ///
+    /// Does nothing in _instant_ mode, where the block context is created when a block is mined.
+    pub fn reopen_pending_block(&self) -> Result<bool, BlockProductionError> {
+        match &mut *self.producer.write() {
+            BlockProducerMode::Instant(_) => Ok(true),
+            BlockProducerMode::Interval(producer) => producer.reopen_empty_block(),
+        }
+    }
+
+    /// Opens the pending block again on top of the latest block, and updates the state the pool
+    /// validates transactions against, after the chain has been reverted to an earlier block.
+    /// Returns `false` if a block is being mined or the pending block already has transactions, in
+    /// which case nothing is changed.
+    pub fn reset_to_latest_block(&self) -> Result<bool, BlockProductionError> {
+        match &mut *self.producer.write() {
+            BlockProducerMode::Instant(producer) => producer.update_validator().map(|_| true),
+            BlockProducerMode::Interval(producer) => producer.reopen_empty_block(),
+        }
+    }
+
+    /// Mines a block with the given transactions right away, on top of the latest block. The
+    /// transactions failing validation are left out of the block.
+    ///
+    /// In _interval_ mode, the pending block is opened again on top of the mined block, so it must
+    /// not have transactions yet.
+    pub fn mine_transactions(
+        &self,
+        transactions: Vec<ExecutableTxWithHash>,
+    ) -> Result<MinedBlockOutcome, BlockProductionError> {
+        let transactions = VecDeque::from([transactions]);
+        match &mut *self.producer.write() {
+            BlockProducerMode::Instant(producer) => {
+                let (outcome, _) = InstantBlockProducer::do_mine(
+                    producer.validator.clone(),
+                    producer.permit.clone(),
+                    producer.backend.clone(),
+                    transactions,
+                )?;
+                Ok(outcome)
+            }
+            BlockProducerMode::Interval(producer) => {
+                let (outcome, _) = InstantBlockProducer::do_mine(
+                    producer.validator.clone(),
+                    producer.permit.clone(),
+                    producer.backend.clone(),
+                    transactions,
+                )?;
+                producer.reopen_empty_block()?;
+                Ok(outcome)
+            }
+        }
+    }
+
+    /// Mines a block without transactions which applies `state_updates`, so that they are
+    /// committed like the ones of executed transactions.
+    ///
+    /// In _interval_ mode, the pending block is opened again on top of the mined block, so it must
+    /// not have transactions yet.
+    pub fn mine_state_updates(
+        &self,
+        state_updates: StateUpdates,
+    ) -> Result<MinedBlockOutcome, BlockProductionError> {
+        match &mut *self.producer.write() {
+            BlockProducerMode::Instant(producer) => InstantBlockProducer::do_mine_state_updates(
+                producer.validator.clone(),
+                producer.permit.clone(),
+                producer.backend.clone(),
+                state_updates,
+            ),
+            BlockProducerMode::Interval(producer) => {
+                let outcome = InstantBlockProducer::do_mine_state_updates(
+                    producer.validator.clone(),
+                    producer.permit.clone(),
+                    producer.backend.clone(),
+                    state_updates,
+                )?;
+                producer.reopen_empty_block()?;
+                Ok(outcome)
+            }
+        }
+    }
+
     /// Returns `true` if the block producer is running in _instant_ mode. Otherwise, `fales`.
     pub fn is_instant_mining(&self) -> bool {
         matches!(*self.producer.read(), BlockProducerMode::Instant(_))
@@ -178,10 +278,20 @@ impl PendingExecutor {
     }
 }
 
+/// Creates the interval ticking every `time` milliseconds, starting after the first period.
+fn block_interval(time: u64) -> Interval {
+    let duration = Duration::from_millis(time);
+    let mut interval = interval_at(Instant::now() + duration, duration);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    interval
+}
+
 #[allow(missing_debug_implementations)]
 pub struct IntervalBlockProducer<EF: ExecutorFactory> {
     /// The interval at which new blocks are mined.
     interval: Option<Interval>,
+    /// Waker of the task polling the producer, woken when the interval is changed.
+    waker: Option<Waker>,
     backend: Arc<Backend<EF>>,
     /// Single active future that mines a new block
     ongoing_mining: Option<BlockProductionFuture>,
@@ -190,6 +300,8 @@ pub struct IntervalBlockProducer<EF: ExecutorFactory> {
     executor: PendingExecutor,
     blocking_task_spawner: BlockingTaskPool,
     ongoing_execution: Option<TxExecutionFuture>,
+    /// The transactions skipped by the hooks since the last mined block.
+    skipped_txs: Vec<TxHash>,
     /// Listeners notified when a new executed tx is added.
     tx_execution_listeners: RwLock<Vec<Sender<Vec<TxWithOutcome>>>>,
 
@@ -203,12 +315,7 @@ pub struct IntervalBlockProducer<EF: ExecutorFactory> {
 
 impl<EF: ExecutorFactory> IntervalBlockProducer<EF> {
     pub fn new(backend: Arc<Backend<EF>>, interval: Option<u64>) -> Self {
-        let interval = interval.map(|time| {
-            let duration = Duration::from_millis(time);
-            let mut interval = interval_at(Instant::now() + duration, duration);
-            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
-            interval
-        });
+        let interval = interval.map(block_interval);
 
         let provider = backend.blockchain.provider();
 
@@ -233,8 +340,10 @@ impl<EF: ExecutorFactory> IntervalBlockProducer<EF> {
             permit,
             backend,
             interval,
+            waker: None,
             ongoing_mining: None,
             ongoing_execution: None,
+            skipped_txs: Vec::new(),
             queued: VecDeque::default(),
             executor: PendingExecutor::new(executor),
             tx_execution_listeners: RwLock::new(vec![]),
@@ -253,6 +362,46 @@ impl<EF: ExecutorFactory> IntervalBlockProducer<EF> {
         self.executor.clone()
     }
 
+    /// Changes the interval at which blocks are mined, restarting it from now. Returns `false`
+    /// without changing anything if the producer doesn't mine at a fixed interval.
+    pub fn set_interval(&mut self, time: u64) -> bool {
+        match &mut self.interval {
+            Some(interval) => {
+                *interval = block_interval(time);
+                // the new interval must be polled to be registered with the timer
+                if let Some(waker) = self.waker.take() {
+                    waker.wake();
+                }
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replaces the executor of the pending block with one created from the current block
+    /// context. Returns `false` without changing anything if the pending block isn't empty or
+    /// transactions are being executed or mined.
+    pub fn reopen_empty_block(&mut self) -> Result<bool, BlockProductionError> {
+        let busy = self.ongoing_mining.is_some()
+            || self.ongoing_execution.is_some()
+            || !self.queued.is_empty()
+            || !self.executor.read().transactions().is_empty();
+
+        if busy {
+            return Ok(false);
+        }
+
+        self.executor = self.create_new_executor_for_next_block()?;
+
+        let provider = self.backend.blockchain.provider();
+        let state = self.executor.0.read().state();
+        let num = provider.latest_number()?;
+        let block_env = provider.block_env_at(num.into())?.unwrap();
+        self.validator.update(state, block_env);
+
+        Ok(true)
+    }
+
     /// Force mine a new block. It will only able to mine if there is no ongoing mining process.
     pub fn force_mine(&mut self) {
         match Self::do_mine(self.permit.clone(), self.executor.clone(), self.backend.clone()) {
@@ -301,20 +450,21 @@ impl<EF: ExecutorFactory> IntervalBlockProducer<EF> {
 
     fn execute_transactions(
         executor: PendingExecutor,
+        hooks: Vec<Arc<dyn BlockBuildingHook>>,
         transactions: Vec<ExecutableTxWithHash>,
     ) -> TxExecutionResult {
         let executor = &mut executor.write();
 
-        let new_txs_count = transactions.len();
-        executor.execute_transactions(transactions)?;
+        // the hooks may skip transactions or add some, so the count is taken from the executor
+        let prev_txs_count = executor.transactions().len();
+        let skipped = hooks::execute_transactions(&mut ***executor, &hooks, transactions)?;
 
         let txs = executor.transactions();
-        let total_txs = txs.len();
 
         // Take only the results of the newly executed transactions
         let results = txs
             .iter()
-            .skip(total_txs - new_txs_count)
+            .skip(prev_txs_count)
             .filter_map(|(tx, res)| match res {
                 ExecutionResult::Failed { .. } => None,
                 ExecutionResult::Success { receipt, trace, .. } => Some(TxWithOutcome {
@@ -325,7 +475,7 @@ impl<EF: ExecutorFactory> IntervalBlockProducer<EF> {
             })
             .collect::<Vec<TxWithOutcome>>();
 
-        Ok(results)
+        Ok((results, skipped))
     }
 
     fn create_new_executor_for_next_block(&self) -> Result<PendingExecutor, BlockProductionError> {
@@ -382,6 +532,7 @@ impl<EF: ExecutorFactory> Stream for IntervalBlockProducer<EF> {
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let pin = self.get_mut();
+        pin.waker = Some(cx.waker().clone());
 
         if let Some(interval) = &mut pin.interval {
             // mine block if the interval is over
@@ -402,13 +553,14 @@ impl<EF: ExecutorFactory> Stream for IntervalBlockProducer<EF> {
                 && pin.ongoing_mining.is_none()
             {
                 let executor = pin.executor.clone();
+                let hooks = pin.backend.block_building_hooks();
 
                 let transactions: Vec<ExecutableTxWithHash> =
                     std::mem::take(&mut pin.queued).into_iter().flatten().collect();
 
                 let fut = pin
                     .blocking_task_spawner
-                    .spawn(|| Self::execute_transactions(executor, transactions));
+                    .spawn(|| Self::execute_transactions(executor, hooks, transactions));
 
                 pin.ongoing_execution = Some(Box::pin(fut));
             }
@@ -417,7 +569,8 @@ impl<EF: ExecutorFactory> Stream for IntervalBlockProducer<EF> {
             if let Some(mut execution) = pin.ongoing_execution.take() {
                 if let Poll::Ready(executor) = execution.poll_unpin(cx) {
                     match executor {
-                        Ok(Ok(txs)) => {
+                        Ok(Ok((txs, skipped))) => {
+                            pin.skipped_txs.extend(skipped);
                             pin.notify_listener(txs);
                             continue;
                         }
@@ -465,6 +618,9 @@ impl<EF: ExecutorFactory> Stream for IntervalBlockProducer<EF> {
                             Err(e) => return Poll::Ready(Some(Err(e))),
                         }
 
+                        let skipped = std::mem::take(&mut pin.skipped_txs);
+                        let outcome = outcome
+                            .map(|outcome| MinedBlockOutcome { skipped_txs: skipped, ..outcome });
                         return Poll::Ready(Some(outcome));
                     }
 
@@ -533,6 +689,16 @@ impl<EF: ExecutorFactory> InstantBlockProducer<EF> {
         }
     }
 
+    /// Updates the state the pool validates transactions against to the latest state.
+    fn update_validator(&self) -> Result<(), BlockProductionError> {
+        let provider = self.backend.blockchain.provider();
+        let state = provider.latest()?;
+        let latest_num = provider.latest_number()?;
+        let block_env = provider.block_env_at(latest_num.into())?.expect("latest");
+        self.validator.update(state, block_env);
+        Ok(())
+    }
+
     pub fn force_mine(&mut self) {
         if self.block_mining.is_none() {
             let txs = std::mem::take(&mut self.queued);
@@ -572,8 +738,9 @@ impl<EF: ExecutorFactory> InstantBlockProducer<EF> {
 
         let mut executor = backend.executor_factory.with_state(latest_state);
 
+        // the block is opened empty, its transactions are executed with the hooks
         let block = ExecutableBlock {
-            body: transactions,
+            body: Vec::new(),
             header: PartialHeader {
                 parent_hash,
                 number: block_env.number,
@@ -587,6 +754,11 @@ impl<EF: ExecutorFactory> InstantBlockProducer<EF> {
         };
 
         executor.execute_block(block)?;
+        let skipped = hooks::execute_transactions(
+            &mut *executor,
+            &backend.block_building_hooks(),
+            transactions,
+        )?;
 
         let execution_output = executor.take_execution_output()?;
         let txs_outcomes = execution_output
@@ -602,20 +774,49 @@ impl<EF: ExecutorFactory> InstantBlockProducer<EF> {
             .collect::<Vec<_>>();
 
         let outcome = backend.do_mine_block(&block_env, execution_output)?;
+        let outcome = MinedBlockOutcome { skipped_txs: skipped, ..outcome };
+        Self::update_validator_state(&validator, &backend)?;
+
+        trace!(target: LOG_TARGET, block_number = %outcome.block_number, "Created new block.");
 
-        // update pool validator state here ---------
+        Ok((outcome, txs_outcomes))
+    }
+
+    fn do_mine_state_updates(
+        validator: TxValidator,
+        permit: Arc<Mutex<()>>,
+        backend: Arc<Backend<EF>>,
+        state_updates: StateUpdates,
+    ) -> Result<MinedBlockOutcome, BlockProductionError> {
+        let _permit = permit.lock();
 
         let provider = backend.blockchain.provider();
-        let state = provider.latest()?;
         let latest_num = provider.latest_number()?;
-        let block_env = provider.block_env_at(latest_num.into())?.expect("latest");
-        validator.update(state, block_env);
+        let mut block_env = provider.block_env_at(BlockHashOrNumber::Num(latest_num))?.unwrap();
+        backend.update_block_env(&mut block_env);
+
+        let states = StateUpdatesWithDeclaredClasses { state_updates, ..Default::default() };
+        let execution_output = ExecutionOutput { states, ..Default::default() };
 
-        // -------------------------------------------
+        let outcome = backend.do_mine_block(&block_env, execution_output)?;
+        Self::update_validator_state(&validator, &backend)?;
 
         trace!(target: LOG_TARGET, block_number = %outcome.block_number, "Created new block.");
 
-        Ok((outcome, txs_outcomes))
+        Ok(outcome)
+    }
+
+    /// Updates the pool validator to the state of the latest block.
+    fn update_validator_state(
+        validator: &TxValidator,
+        backend: &Backend<EF>,
+    ) -> Result<(), BlockProductionError> {
+        let provider = backend.blockchain.provider();
+        let state = provider.latest()?;
+        let latest_num = provider.latest_number()?;
+        let block_env = provider.block_env_at(latest_num.into())?.expect("latest");
+        validator.update(state, block_env);
+        Ok(())
     }
 
     pub fn add_listener(&self) -> Receiver<Vec<TxWithOutcome>> {
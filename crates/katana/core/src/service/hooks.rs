@@ -0,0 +1,82 @@
+//! Hooks run around the execution of every transaction included in a block.
+//!
+//! They let embedders of the node customize block building, eg to rate limit the senders of
+//! transactions or to inject transactions of their own, without changing the block producer.
+
+use std::sync::Arc;
+
+use katana_executor::{BlockExecutor, ExecutionResult, ExecutorResult};
+use katana_primitives::env::BlockEnv;
+use katana_primitives::transaction::{ExecutableTxWithHash, TxHash};
+
+/// What to do with a transaction about to be executed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxAction {
+    /// Execute the transaction and include it in the block.
+    Execute,
+    /// Leave the transaction out of the block. It is dropped from the pool without being
+    /// executed.
+    Skip,
+}
+
+/// Custom logic run before and after every transaction executed in a block.
+pub trait BlockBuildingHook: Send + Sync {
+    /// Called before executing `tx` in the block with the environment `block_env`.
+    ///
+    /// The transaction is skipped if any of the hooks returns [`TxAction::Skip`].
+    fn before_transaction(&self, block_env: &BlockEnv, tx: &ExecutableTxWithHash) -> TxAction {
+        let _ = (block_env, tx);
+        TxAction::Execute
+    }
+
+    /// Called after executing `tx`, with the result of its execution. Returns the transactions to
+    /// execute right after it, in the same block.
+    ///
+    /// The returned transactions aren't passed to the hooks.
+    fn after_transaction(
+        &self,
+        block_env: &BlockEnv,
+        tx: &ExecutableTxWithHash,
+        result: &ExecutionResult,
+    ) -> Vec<ExecutableTxWithHash> {
+        let _ = (block_env, tx, result);
+        Vec::new()
+    }
+}
+
+/// Executes the transactions with the executor, running the hooks around each of them. Returns
+/// the hashes of the transactions skipped by the hooks.
+pub(crate) fn execute_transactions(
+    executor: &mut dyn BlockExecutor<'_>,
+    hooks: &[Arc<dyn BlockBuildingHook>],
+    transactions: Vec<ExecutableTxWithHash>,
+) -> ExecutorResult<Vec<TxHash>> {
+    if hooks.is_empty() {
+        executor.execute_transactions(transactions)?;
+        return Ok(Vec::new());
+    }
+
+    let block_env = executor.block_env();
+    let mut skipped = Vec::new();
+
+    for tx in transactions {
+        if hooks.iter().any(|hook| hook.before_transaction(&block_env, &tx) == TxAction::Skip) {
+            skipped.push(tx.hash);
+            continue;
+        }
+
+        executor.execute_transactions(vec![tx.clone()])?;
+
+        let Some((_, result)) = executor.transactions().last() else { continue };
+        let injected = hooks
+            .iter()
+            .flat_map(|hook| hook.after_transaction(&block_env, &tx, result))
+            .collect::<Vec<_>>();
+
+        if !injected.is_empty() {
+            executor.execute_transactions(injected)?;
+        }
+    }
+
+    Ok(skipped)
+}
@@ -0,0 +1,202 @@
+//! Invariants checked after every mined block.
+//!
+//! When an invariant is violated, block production is halted and the state of the chain is
+//! dumped, so that the violation can be investigated through the RPC and the dump.
+
+use std::fs::{self, File};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::Result;
+use futures::future::BoxFuture;
+use katana_executor::ExecutorFactory;
+use katana_primitives::block::BlockNumber;
+use katana_primitives::state::StateUpdates;
+use katana_provider::traits::state_update::StateUpdateProvider;
+use serde_json::json;
+use tokio::process::Command;
+
+use crate::backend::Backend;
+
+/// A property of the chain state that must hold after every block.
+pub trait Invariant: Send + Sync {
+    /// The name of the invariant, used to report violations.
+    fn name(&self) -> &str;
+
+    /// Checks the invariant once the block `block_number` has been mined, returning the reason of
+    /// the violation if it doesn't hold.
+    fn check(&self, block_number: BlockNumber) -> BoxFuture<'static, Result<(), String>>;
+}
+
+/// An invariant evaluated by an external executable.
+///
+/// The executable is given the block number as argument, and the RPC URL of the node in the
+/// `KATANA_RPC_URL` environment variable, from which it can read the state. A non-zero exit status
+/// is a violation, described by the output of the executable.
+#[derive(Debug, Clone)]
+pub struct InvariantScript {
+    name: String,
+    path: PathBuf,
+    rpc_url: String,
+}
+
+impl InvariantScript {
+    pub fn new(path: impl Into<PathBuf>, rpc_url: impl Into<String>) -> Self {
+        let path = path.into();
+        Self { name: path.display().to_string(), path, rpc_url: rpc_url.into() }
+    }
+}
+
+impl Invariant for InvariantScript {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn check(&self, block_number: BlockNumber) -> BoxFuture<'static, Result<(), String>> {
+        let mut command = Command::new(&self.path);
+        command
+            .arg(block_number.to_string())
+            .env("KATANA_RPC_URL", &self.rpc_url)
+            .kill_on_drop(true);
+
+        Box::pin(async move {
+            let output =
+                command.output().await.map_err(|e| format!("failed to run the script: {e}"))?;
+
+            if output.status.success() {
+                return Ok(());
+            }
+
+            let stdout = String::from_utf8_lossy(&output.stdout);
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let reason = format!("{}\n{}", stdout.trim(), stderr.trim());
+
+            match reason.trim() {
+                "" => Err(format!("script exited with {}", output.status)),
+                reason => Err(reason.to_string()),
+            }
+        })
+    }
+}
+
+/// A violated invariant.
+#[derive(Debug, Clone)]
+pub struct InvariantViolation {
+    pub invariant: String,
+    pub block_number: BlockNumber,
+    pub reason: String,
+    /// The path of the state dump, or the reason it couldn't be written.
+    pub dump: Result<PathBuf, String>,
+}
+
+/// Checks a set of invariants after every block, dumping the state on violation.
+#[allow(missing_debug_implementations)]
+pub struct InvariantChecker<EF: ExecutorFactory> {
+    backend: Arc<Backend<EF>>,
+    invariants: Vec<Arc<dyn Invariant>>,
+    dump_dir: PathBuf,
+}
+
+impl<EF: ExecutorFactory> Clone for InvariantChecker<EF> {
+    fn clone(&self) -> Self {
+        Self {
+            backend: self.backend.clone(),
+            invariants: self.invariants.clone(),
+            dump_dir: self.dump_dir.clone(),
+        }
+    }
+}
+
+impl<EF: ExecutorFactory> InvariantChecker<EF> {
+    pub fn new(
+        backend: Arc<Backend<EF>>,
+        invariants: Vec<Arc<dyn Invariant>>,
+        dump_dir: impl Into<PathBuf>,
+    ) -> Self {
+        Self { backend, invariants, dump_dir: dump_dir.into() }
+    }
+
+    /// Checks all the invariants in order, stopping at the first violation.
+    pub(crate) fn check(
+        &self,
+        block_number: BlockNumber,
+    ) -> BoxFuture<'static, Result<(), InvariantViolation>> {
+        let this = self.clone();
+
+        Box::pin(async move {
+            for invariant in &this.invariants {
+                if let Err(reason) = invariant.check(block_number).await {
+                    let dump = this
+                        .dump_state(block_number, invariant.name(), &reason)
+                        .map_err(|e| e.to_string());
+
+                    return Err(InvariantViolation {
+                        invariant: invariant.name().to_string(),
+                        block_number,
+                        reason,
+                        dump,
+                    });
+                }
+            }
+
+            Ok(())
+        })
+    }
+
+    /// Writes the violation and the state accumulated up to `block_number` into the dump
+    /// directory.
+    fn dump_state(
+        &self,
+        block_number: BlockNumber,
+        invariant: &str,
+        reason: &str,
+    ) -> Result<PathBuf> {
+        let provider = self.backend.blockchain.provider();
+        let mut state = StateUpdates::default();
+
+        for number in 0..=block_number {
+            let Some(update) = provider.state_update(number.into())? else {
+                continue;
+            };
+
+            state.nonce_updates.extend(update.nonce_updates);
+            state.deployed_contracts.extend(update.deployed_contracts);
+            state.declared_classes.extend(update.declared_classes);
+            state.deprecated_declared_classes.extend(update.deprecated_declared_classes);
+            state.replaced_classes.extend(update.replaced_classes);
+            for (address, storage) in update.storage_updates {
+                state.storage_updates.entry(address).or_default().extend(storage);
+            }
+        }
+
+        fs::create_dir_all(&self.dump_dir)?;
+        let path = self.dump_dir.join(format!("invariant-violation-{block_number}.json"));
+
+        let dump = json!({
+            "block_number": block_number,
+            "invariant": invariant,
+            "reason": reason,
+            "state": state,
+        });
+        serde_json::to_writer_pretty(File::create(&path)?, &dump)?;
+
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn script_exit_status() {
+        let holds = InvariantScript::new("true", "http://localhost:5050");
+        assert_eq!(holds.check(1).await, Ok(()));
+
+        let violated = InvariantScript::new("false", "http://localhost:5050");
+        assert!(violated.check(1).await.unwrap_err().contains("exit status: 1"));
+
+        let missing = InvariantScript::new("/nonexistent/invariant", "http://localhost:5050");
+        assert!(missing.check(1).await.unwrap_err().starts_with("failed to run the script"));
+    }
+}
@@ -28,6 +28,11 @@
 //! settlement chain configuration in `starknet.rs` and `ethereum.rs`. The `service.rs` file aims at
 //! running the common logic.
 //!
+//! Katana can also act as an appchain settling on Starknet, or on another Katana instance for local
+//! prototyping. When the `state_contract_address` of the configuration is set, the state update
+//! of every block (its number, hash and state root) is posted to this contract on the settlement
+//! chain, in the same transaction as the messages sent by the block.
+//!
 //! To start Katana with the messaging enabled, the option `--messaging` must be used with a
 //! configuration file following the `MessagingConfig` format. An example of this file can be found
 //! in the messaging contracts.
@@ -43,6 +48,7 @@ use std::pin::Pin;
 use std::task::{Context, Poll};
 
 use ::starknet::providers::ProviderError as StarknetProviderError;
+use alloy_primitives::B256;
 use alloy_transport::TransportError;
 use anyhow::Result;
 use async_trait::async_trait;
@@ -77,6 +83,8 @@ pub enum Error {
     SendError,
     #[error(transparent)]
     Provider(ProviderError),
+    #[error("Failed to read the local block: {0}")]
+    LocalProvider(#[from] katana_provider::error::ProviderError),
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -112,6 +120,11 @@ pub struct MessagingConfig {
     pub interval: u64,
     /// The block on settlement chain from where Katana will start fetching messages.
     pub from_block: u64,
+    /// The address of the contract on the settlement chain the state update of every block is
+    /// posted to, along with the messages sent by the block. Only supported when settling on
+    /// Starknet, state updates are not posted if not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state_contract_address: Option<String>,
 }
 
 impl MessagingConfig {
@@ -137,8 +150,9 @@ pub trait Messenger {
     type MessageTransaction;
 
     /// Gathers messages emitted on the settlement chain and convert them to their
-    /// corresponding transaction type on Starknet, and the latest block on the settlement until
-    /// which the messages were collected.
+    /// corresponding transaction type on Starknet, along with the hash of the settlement chain
+    /// transaction which sent each of them, and the latest block on the settlement until which the
+    /// messages were collected.
     ///
     /// # Arguments
     ///
@@ -151,7 +165,7 @@ pub trait Messenger {
         from_block: u64,
         max_blocks: u64,
         chain_id: ChainId,
-    ) -> MessengerResult<(u64, Vec<Self::MessageTransaction>)>;
+    ) -> MessengerResult<(u64, Vec<(B256, Self::MessageTransaction)>)>;
 
     /// Computes the hash of the given messages and sends them to the settlement chain.
     ///
@@ -177,6 +191,11 @@ pub enum MessengerMode {
 impl MessengerMode {
     pub async fn from_config(config: MessagingConfig) -> MessengerResult<Self> {
         match config.chain.as_str() {
+            CONFIG_CHAIN_ETHEREUM if config.state_contract_address.is_some() => {
+                error!(target: LOG_TARGET, "State updates can only be posted to Starknet.");
+                Err(Error::UnsupportedChain)
+            }
+
             CONFIG_CHAIN_ETHEREUM => match EthereumMessaging::new(config).await {
                 Ok(m_eth) => {
                     info!(target: LOG_TARGET, "Messaging enabled [Ethereum].");
@@ -10,10 +10,14 @@ use katana_primitives::block::BlockHashOrNumber;
 use katana_primitives::receipt::MessageToL1;
 use katana_primitives::transaction::{ExecutableTxWithHash, L1HandlerTx, TxHash};
 use katana_provider::traits::block::BlockNumberProvider;
+#[cfg(feature = "starknet-messaging")]
+use katana_provider::traits::block::{BlockHashProvider, HeaderProvider};
 use katana_provider::traits::transaction::ReceiptProvider;
 use tokio::time::{interval_at, Instant, Interval};
 use tracing::{error, info};
 
+#[cfg(feature = "starknet-messaging")]
+use super::starknet::BlockStateUpdate;
 use super::{MessagingConfig, Messenger, MessengerMode, MessengerResult, LOG_TARGET};
 use crate::backend::Backend;
 use crate::service::TxPool;
@@ -88,9 +92,10 @@ impl<EF: ExecutorFactory> MessagingService<EF> {
                     inner.gather_messages(from_block, max_block, backend.chain_spec.id).await?;
                 let txs_count = txs.len();
 
-                txs.into_iter().for_each(|tx| {
+                txs.into_iter().for_each(|(l1_tx_hash, tx)| {
                     let hash = tx.calculate_hash();
                     trace_l1_handler_tx_exec(hash, &tx);
+                    backend.l1_messages.write().entry(l1_tx_hash).or_default().push(hash);
 
                     // ignore result because L1Handler tx will always be valid
                     let _ =
@@ -106,9 +111,10 @@ impl<EF: ExecutorFactory> MessagingService<EF> {
                     inner.gather_messages(from_block, max_block, backend.chain_spec.id).await?;
                 let txs_count = txs.len();
 
-                txs.into_iter().for_each(|tx| {
+                txs.into_iter().for_each(|(l1_tx_hash, tx)| {
                     let hash = tx.calculate_hash();
                     trace_l1_handler_tx_exec(hash, &tx);
+                    backend.l1_messages.write().entry(l1_tx_hash).or_default().push(hash);
 
                     // ignore result because L1Handler tx will always be valid
                     let tx = ExecutableTxWithHash { hash, transaction: tx.into() };
@@ -125,15 +131,44 @@ impl<EF: ExecutorFactory> MessagingService<EF> {
         backend: Arc<Backend<EF>>,
         messenger: Arc<MessengerMode>,
     ) -> MessengerResult<Option<(u64, usize)>> {
+        // The receipts of a pruned block are no longer available, which fails the sending of the
+        // block instead of skipping its messages.
         let Some(messages) = ReceiptProvider::receipts_by_block(
             backend.blockchain.provider(),
             BlockHashOrNumber::Num(block_num),
-        )
-        .unwrap()
+        )?
         .map(|r| r.iter().flat_map(|r| r.messages_sent().to_vec()).collect::<Vec<MessageToL1>>()) else {
             return Ok(None);
         };
 
+        // The state update is posted for every block, even if it has no message to send.
+        #[cfg(feature = "starknet-messaging")]
+        if let MessengerMode::Starknet(inner) = messenger.as_ref() {
+            if inner.settles_state() {
+                let provider = backend.blockchain.provider();
+                let (Some(header), Some(block_hash)) = (
+                    HeaderProvider::header_by_number(provider, block_num)?,
+                    BlockHashProvider::block_hash_by_num(provider, block_num)?,
+                ) else {
+                    return Ok(None);
+                };
+
+                let state_update = BlockStateUpdate {
+                    block_number: block_num,
+                    block_hash,
+                    state_root: header.state_root,
+                };
+
+                let hashes = inner
+                    .settle_block(&messages, state_update)
+                    .await
+                    .map(|hashes| hashes.iter().map(|h| format!("{h:#x}")).collect::<Vec<_>>())?;
+                trace_msg_to_l1_sent(&messages, &hashes);
+                trace_state_update_sent(&state_update);
+                return Ok(Some((block_num, hashes.len())));
+            }
+        }
+
         if messages.is_empty() {
             Ok(Some((block_num, 0)))
         } else {
@@ -311,6 +346,18 @@ fn trace_msg_to_l1_sent(messages: &[MessageToL1], hashes: &[String]) {
     }
 }
 
+#[cfg(feature = "starknet-messaging")]
+fn trace_state_update_sent(state_update: &BlockStateUpdate) {
+    #[rustfmt::skip]
+    info!(
+        target: LOG_TARGET,
+        block_number = %state_update.block_number,
+        block_hash = %format!("{:#x}", state_update.block_hash),
+        state_root = %format!("{:#x}", state_update.state_root),
+        "State update posted to settlement layer.",
+    );
+}
+
 fn trace_l1_handler_tx_exec(hash: TxHash, tx: &L1HandlerTx) {
     let calldata_str: Vec<_> = tx.calldata.iter().map(|f| format!("{f:#x}")).collect();
 
@@ -1,5 +1,6 @@
 use std::sync::Arc;
 
+use alloy_primitives::B256;
 use anyhow::Result;
 use async_trait::async_trait;
 use katana_primitives::chain::ChainId;
@@ -28,6 +29,14 @@ const EXE_MAGIC: Felt = felt!("0x455845");
 
 pub const HASH_EXEC: Felt = felt!("0xee");
 
+/// The state update of a block, as posted to the state contract on the settlement chain.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockStateUpdate {
+    pub block_number: u64,
+    pub block_hash: Felt,
+    pub state_root: Felt,
+}
+
 #[derive(Debug)]
 pub struct StarknetMessaging {
     chain_id: Felt,
@@ -35,6 +44,7 @@ pub struct StarknetMessaging {
     wallet: LocalWallet,
     sender_account_address: Felt,
     messaging_contract_address: Felt,
+    state_contract_address: Option<Felt>,
 }
 
 impl StarknetMessaging {
@@ -50,6 +60,8 @@ impl StarknetMessaging {
         let chain_id = provider.chain_id().await?;
         let sender_account_address = Felt::from_hex(&config.sender_address)?;
         let messaging_contract_address = Felt::from_hex(&config.contract_address)?;
+        let state_contract_address =
+            config.state_contract_address.as_deref().map(Felt::from_hex).transpose()?;
 
         Ok(StarknetMessaging {
             wallet,
@@ -57,9 +69,15 @@ impl StarknetMessaging {
             chain_id,
             sender_account_address,
             messaging_contract_address,
+            state_contract_address,
         })
     }
 
+    /// Returns true if the state updates of the blocks are posted to the settlement chain.
+    pub fn settles_state(&self) -> bool {
+        self.state_contract_address.is_some()
+    }
+
     pub async fn fetch_events(
         &self,
         from_block: BlockId,
@@ -127,29 +145,71 @@ impl StarknetMessaging {
     }
 
     /// Sends messages hashes to settlement layer by sending a transaction.
-    async fn send_hashes(&self, mut hashes: Vec<Felt>) -> MessengerResult<Felt> {
+    async fn send_hashes(&self, hashes: Vec<Felt>) -> MessengerResult<Felt> {
+        let Some(call) = self.register_hashes_call(hashes) else {
+            return Ok(Felt::ZERO);
+        };
+
+        match self.send_invoke_tx(vec![call]).await {
+            Ok(tx_hash) => {
+                trace!(target: LOG_TARGET, tx_hash = %format!("{:#064x}", tx_hash), "Hashes sending transaction.");
+                Ok(tx_hash)
+            }
+            Err(e) => {
+                error!(target: LOG_TARGET, error = %e, "Settling hashes on Starknet.");
+                Err(Error::SendError)
+            }
+        }
+    }
+
+    /// Returns the call registering the messages hashes on the messaging contract, if there is any
+    /// hash to register.
+    fn register_hashes_call(&self, mut hashes: Vec<Felt>) -> Option<Call> {
         hashes.retain(|&x| x != HASH_EXEC);
 
         if hashes.is_empty() {
-            return Ok(Felt::ZERO);
+            return None;
         }
 
         let mut calldata = hashes;
         calldata.insert(0, calldata.len().into());
 
-        let call = Call {
+        Some(Call {
             selector: selector!("add_messages_hashes_from_appchain"),
             to: self.messaging_contract_address,
             calldata,
+        })
+    }
+
+    /// Sends the messages of a block along with its state update, in a single transaction so
+    /// that the state update is never posted without the messages of the block.
+    ///
+    /// Returns the hashes of the messages, as [`Messenger::send_messages`] does.
+    pub async fn settle_block(
+        &self,
+        messages: &[MessageToL1],
+        state_update: BlockStateUpdate,
+    ) -> MessengerResult<Vec<Felt>> {
+        let Some(state_contract_address) = self.state_contract_address else {
+            return self.send_messages(messages).await;
         };
 
-        match self.send_invoke_tx(vec![call]).await {
+        let (hashes, mut calls) = parse_messages(messages)?;
+        calls.extend(self.register_hashes_call(hashes.clone()));
+        calls.push(state_update_call(state_contract_address, state_update));
+
+        match self.send_invoke_tx(calls).await {
             Ok(tx_hash) => {
-                trace!(target: LOG_TARGET, tx_hash = %format!("{:#064x}", tx_hash), "Hashes sending transaction.");
-                Ok(tx_hash)
+                trace!(
+                    target: LOG_TARGET,
+                    tx_hash = %format!("{:#064x}", tx_hash),
+                    block = %state_update.block_number,
+                    "State update transaction."
+                );
+                Ok(hashes)
             }
             Err(e) => {
-                error!(target: LOG_TARGET, error = %e, "Settling hashes on Starknet.");
+                error!(target: LOG_TARGET, error = %e, "Settling block on Starknet.");
                 Err(Error::SendError)
             }
         }
@@ -166,7 +226,7 @@ impl Messenger for StarknetMessaging {
         from_block: u64,
         max_blocks: u64,
         chain_id: ChainId,
-    ) -> MessengerResult<(u64, Vec<Self::MessageTransaction>)> {
+    ) -> MessengerResult<(u64, Vec<(B256, Self::MessageTransaction)>)> {
         let chain_latest_block: u64 = match self.provider.block_number().await {
             Ok(n) => n,
             Err(_) => {
@@ -191,7 +251,7 @@ impl Messenger for StarknetMessaging {
             chain_latest_block
         };
 
-        let mut l1_handler_txs: Vec<L1HandlerTx> = vec![];
+        let mut l1_handler_txs: Vec<(B256, L1HandlerTx)> = vec![];
 
         self.fetch_events(BlockId::Number(from_block), BlockId::Number(to_block))
             .await
@@ -206,7 +266,7 @@ impl Messenger for StarknetMessaging {
                 );
 
                 if let Ok(tx) = l1_handler_tx_from_event(e, chain_id) {
-                    l1_handler_txs.push(tx)
+                    l1_handler_txs.push((B256::from(e.transaction_hash.to_bytes_be()), tx))
                 }
             });
 
@@ -306,6 +366,19 @@ fn parse_messages(messages: &[MessageToL1]) -> MessengerResult<(Vec<Felt>, Vec<C
     Ok((hashes, calls))
 }
 
+/// Returns the call posting the state update of a block to the state contract.
+fn state_update_call(state_contract_address: Felt, state_update: BlockStateUpdate) -> Call {
+    Call {
+        selector: selector!("update_state"),
+        to: state_contract_address,
+        calldata: vec![
+            state_update.block_number.into(),
+            state_update.block_hash,
+            state_update.state_root,
+        ],
+    }
+}
+
 fn l1_handler_tx_from_event(event: &EmittedEvent, chain_id: ChainId) -> Result<L1HandlerTx> {
     if event.keys[0] != selector!("MessageSentToAppchain") {
         debug!(
@@ -426,6 +499,25 @@ mod tests {
         parse_messages(&messages).unwrap();
     }
 
+    #[test]
+    fn state_update_call_calldata() {
+        let state_contract_address = selector!("state_contract_address");
+        let state_update = BlockStateUpdate {
+            block_number: 5,
+            block_hash: selector!("block_hash"),
+            state_root: selector!("state_root"),
+        };
+
+        let call = state_update_call(state_contract_address, state_update);
+
+        assert_eq!(call.to, state_contract_address);
+        assert_eq!(call.selector, selector!("update_state"));
+        assert_eq!(
+            call.calldata,
+            vec![Felt::from(5), selector!("block_hash"), selector!("state_root")]
+        );
+    }
+
     #[test]
     fn l1_handler_tx_from_event_parse_ok() {
         let from_address = selector!("from_address");
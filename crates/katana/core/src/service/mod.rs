@@ -3,7 +3,9 @@ use std::pin::Pin;
 use std::task::{Context, Poll};
 
 use block_producer::BlockProductionError;
+use futures::future::BoxFuture;
 use futures::stream::StreamExt;
+use futures::FutureExt;
 use katana_executor::ExecutorFactory;
 use katana_pool::ordering::PoolOrd;
 use katana_pool::pending::PendingTransactions;
@@ -12,11 +14,15 @@ use katana_primitives::transaction::ExecutableTxWithHash;
 use tracing::{error, info};
 
 use self::block_producer::BlockProducer;
+use self::invariant::{InvariantChecker, InvariantViolation};
 use self::metrics::BlockProducerMetrics;
 
 pub mod block_producer;
+pub mod hooks;
+pub mod invariant;
 pub mod messaging;
 mod metrics;
+pub mod scheduler;
 
 pub(crate) const LOG_TARGET: &str = "node";
 
@@ -40,6 +46,12 @@ where
     pub(crate) pool: TxPool,
     /// Metrics for recording the service operations
     metrics: BlockProducerMetrics,
+    /// The invariants checked after every block, if any
+    invariants: Option<InvariantChecker<EF>>,
+    /// The check of the invariants of the last mined block, if still in progress
+    invariant_check: Option<BoxFuture<'static, Result<(), InvariantViolation>>>,
+    /// Whether block production has been halted by an invariant violation
+    halted: bool,
 }
 
 impl<EF, O> BlockProductionTask<EF, O>
@@ -52,7 +64,22 @@ where
         miner: TransactionMiner<O>,
         block_producer: BlockProducer<EF>,
     ) -> Self {
-        Self { block_producer, miner, pool, metrics: BlockProducerMetrics::default() }
+        Self {
+            block_producer,
+            miner,
+            pool,
+            metrics: BlockProducerMetrics::default(),
+            invariants: None,
+            invariant_check: None,
+            halted: false,
+        }
+    }
+
+    /// Checks the invariants of `checker` after every block. Block production is halted on the
+    /// first violation, leaving the node running to inspect the state.
+    pub fn with_invariants(mut self, checker: InvariantChecker<EF>) -> Self {
+        self.invariants = Some(checker);
+        self
     }
 }
 
@@ -68,7 +95,36 @@ where
 
         // this drives block production and feeds new sets of ready transactions to the block
         // producer
-        loop {
+        'production: loop {
+            // no block is mined until the invariants of the previous one have been checked
+            if let Some(check) = this.invariant_check.as_mut() {
+                match check.poll_unpin(cx) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(Ok(())) => this.invariant_check = None,
+                    Poll::Ready(Err(violation)) => {
+                        this.invariant_check = None;
+                        this.halted = true;
+
+                        let dump = match &violation.dump {
+                            Ok(path) => path.display().to_string(),
+                            Err(e) => format!("failed to dump state: {e}"),
+                        };
+                        error!(
+                            target: LOG_TARGET,
+                            invariant = %violation.invariant,
+                            block_number = %violation.block_number,
+                            reason = %violation.reason,
+                            %dump,
+                            "Invariant violated, halting block production."
+                        );
+                    }
+                }
+            }
+
+            if this.halted {
+                return Poll::Pending;
+            }
+
             while let Poll::Ready(Some(res)) = this.block_producer.poll_next(cx) {
                 match res {
                     Ok(outcome) => {
@@ -79,8 +135,14 @@ where
                         this.metrics.l1_gas_processed_total.increment(gas_used as u64);
                         this.metrics.cairo_steps_processed_total.increment(steps_used as u64);
 
-                        // remove mined transactions from the pool
+                        // remove mined and skipped transactions from the pool
                         this.pool.remove_transactions(&outcome.txs);
+                        this.pool.remove_transactions(&outcome.skipped_txs);
+
+                        if let Some(invariants) = &this.invariants {
+                            this.invariant_check = Some(invariants.check(outcome.block_number));
+                            continue 'production;
+                        }
                     }
 
                     Err(error) => {
@@ -0,0 +1,229 @@
+//! Transactions submitted on a schedule, eg to call the system advancing the tick of a game world,
+//! so that autonomous worlds keep running without an external keeper.
+//!
+//! The transactions are invoke transactions signed by one of the development accounts of the
+//! genesis, and submitted to the pool like the ones received through the RPC.
+
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use futures::channel::mpsc::Receiver;
+use futures::StreamExt;
+use katana_pool::{TransactionPool, TxPool};
+use katana_primitives::block::BlockNumber;
+use katana_primitives::chain::ChainId;
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::genesis::Genesis;
+use katana_primitives::transaction::{ExecutableTx, ExecutableTxWithHash, InvokeTx, InvokeTxV1};
+use katana_primitives::Felt;
+use serde::{Deserialize, Serialize};
+use starknet::core::utils::get_selector_from_name;
+use starknet::signers::SigningKey;
+use tokio::time::{self, Instant, MissedTickBehavior};
+use tracing::{error, info};
+
+pub(crate) const LOG_TARGET: &str = "scheduler";
+
+/// The transactions to submit on a schedule.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchedulerConfig {
+    /// The development account sending the transactions. Defaults to the first account of the
+    /// genesis.
+    ///
+    /// The nonce of the account is managed by the scheduler, so it shouldn't be used to send
+    /// other transactions.
+    #[serde(default)]
+    pub account: Option<ContractAddress>,
+    /// The scheduled calls.
+    #[serde(default)]
+    pub jobs: Vec<ScheduledCall>,
+}
+
+/// A call submitted in its own transaction at a fixed interval.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledCall {
+    /// The contract called.
+    pub contract: ContractAddress,
+    /// The name of the function called.
+    pub entrypoint: String,
+    #[serde(default)]
+    pub calldata: Vec<Felt>,
+    #[serde(flatten)]
+    pub every: Interval,
+    /// The max fee of the transactions. The transactions are free of fee if zero.
+    #[serde(default)]
+    pub max_fee: u128,
+}
+
+/// The interval between two submissions of a scheduled call.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Interval {
+    /// Every given number of mined blocks.
+    ///
+    /// With instant mining, the transaction mines a block of its own, so a call scheduled every
+    /// block keeps the node mining.
+    Blocks(u64),
+    /// Every given number of seconds.
+    Seconds(u64),
+}
+
+/// Submits the scheduled calls to the pool.
+#[allow(missing_debug_implementations)]
+pub struct Scheduler {
+    pool: TxPool,
+    chain_id: ChainId,
+    account: ContractAddress,
+    signing_key: SigningKey,
+    /// The jobs, along with the selector of their entrypoint.
+    jobs: Vec<(ScheduledCall, Felt)>,
+}
+
+impl Scheduler {
+    /// Creates a scheduler sending the calls of `config` from a development account of `genesis`.
+    pub fn new(
+        pool: TxPool,
+        chain_id: ChainId,
+        genesis: &Genesis,
+        config: SchedulerConfig,
+    ) -> Result<Self> {
+        let (account, alloc) = match config.account {
+            Some(address) => genesis.accounts().find(|(addr, _)| **addr == address),
+            None => genesis.accounts().find(|(_, alloc)| alloc.private_key().is_some()),
+        }
+        .ok_or_else(|| anyhow!("scheduler account is not a genesis account"))?;
+
+        let private_key = alloc
+            .private_key()
+            .ok_or_else(|| anyhow!("scheduler account {account} is not a development account"))?;
+
+        let jobs = config
+            .jobs
+            .into_iter()
+            .map(|job| {
+                if matches!(job.every, Interval::Blocks(0) | Interval::Seconds(0)) {
+                    return Err(anyhow!("interval of scheduled call `{}` is zero", job.entrypoint));
+                }
+
+                let selector = get_selector_from_name(&job.entrypoint)?;
+                Ok((job, selector))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            pool,
+            chain_id,
+            account: *account,
+            signing_key: SigningKey::from_secret_scalar(private_key),
+            jobs,
+        })
+    }
+
+    /// Submits the calls when they are due, counting the mined blocks with `blocks`.
+    pub async fn run(self, mut blocks: Receiver<BlockNumber>) {
+        let mut ticker = time::interval(Duration::from_secs(1));
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
+        let start = Instant::now();
+        let mut next_runs = self
+            .jobs
+            .iter()
+            .map(|(job, _)| match job.every {
+                Interval::Seconds(secs) => Some(start + Duration::from_secs(secs)),
+                Interval::Blocks(_) => None,
+            })
+            .collect::<Vec<_>>();
+
+        loop {
+            tokio::select! {
+                block = blocks.next() => {
+                    let Some(block) = block else { break };
+
+                    for (job, selector) in &self.jobs {
+                        if matches!(job.every, Interval::Blocks(n) if block % n == 0) {
+                            self.submit(job, *selector);
+                        }
+                    }
+                }
+
+                now = ticker.tick() => {
+                    for ((job, selector), next_run) in self.jobs.iter().zip(&mut next_runs) {
+                        let (Some(at), Interval::Seconds(secs)) = (next_run.as_mut(), job.every)
+                        else {
+                            continue;
+                        };
+
+                        if now >= *at {
+                            *at += Duration::from_secs(secs);
+                            self.submit(job, *selector);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn submit(&self, job: &ScheduledCall, selector: Felt) {
+        match self.send_call(job, selector) {
+            Ok(hash) => {
+                info!(target: LOG_TARGET, entrypoint = %job.entrypoint, hash = format!("{hash:#x}"), "Scheduled call submitted.");
+            }
+            Err(error) => {
+                error!(target: LOG_TARGET, entrypoint = %job.entrypoint, %error, "Submitting scheduled call.");
+            }
+        }
+    }
+
+    fn send_call(&self, job: &ScheduledCall, selector: Felt) -> Result<Felt> {
+        // the nonce following the transactions of the account already in the pool
+        let nonce = self.pool.validator().pool_nonce(self.account)?.unwrap_or_default();
+
+        let mut calldata = vec![Felt::ONE, job.contract.into(), selector];
+        calldata.push(job.calldata.len().into());
+        calldata.extend_from_slice(&job.calldata);
+
+        let mut tx = InvokeTxV1 {
+            chain_id: self.chain_id,
+            sender_address: self.account,
+            nonce,
+            calldata,
+            signature: Vec::new(),
+            max_fee: job.max_fee,
+        };
+
+        let hash = InvokeTx::V1(tx.clone()).calculate_hash(false);
+        let signature = self.signing_key.sign(&hash)?;
+        tx.signature = vec![signature.r, signature.s];
+
+        let tx = ExecutableTxWithHash { hash, transaction: ExecutableTx::Invoke(InvokeTx::V1(tx)) };
+        Ok(self.pool.add_transaction(tx)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Interval, SchedulerConfig};
+
+    #[test]
+    fn parse_config() {
+        let config: SchedulerConfig = serde_json::from_value(serde_json::json!({
+            "jobs": [
+                { "contract": "0x1234", "entrypoint": "tick", "blocks": 10 },
+                {
+                    "contract": "0x1234",
+                    "entrypoint": "spawn",
+                    "calldata": ["0x1", "0x2"],
+                    "seconds": 5,
+                    "max_fee": 1000
+                }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(config.account, None);
+        assert_eq!(config.jobs[0].every, Interval::Blocks(10));
+        assert_eq!(config.jobs[0].max_fee, 0);
+        assert_eq!(config.jobs[1].every, Interval::Seconds(5));
+        assert_eq!(config.jobs[1].calldata.len(), 2);
+    }
+}
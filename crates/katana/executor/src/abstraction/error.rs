@@ -1,10 +1,14 @@
 use katana_primitives::class::ClassHash;
 use katana_primitives::contract::{ContractAddress, Nonce};
+use katana_primitives::env::Syscall;
 use katana_primitives::Felt;
 
 /// Errors that can be returned by the executor.
 #[derive(Debug, thiserror::Error)]
-pub enum ExecutorError {}
+pub enum ExecutorError {
+    #[error("Failed to update the execution state: {0}")]
+    StateUpdate(String),
+}
 
 /// Errors that can occur during the transaction execution.
 #[derive(Debug, Clone, thiserror::Error)]
@@ -60,6 +64,12 @@ pub enum ExecutionError {
     #[error("Fee transfer error: {0}")]
     FeeTransferError(String),
 
+    #[error(
+        "Insufficient balance to settle the Sierra gas fee: remaining fee {remaining_fee} \
+         exceeds account balance u256({balance_low}, {balance_high})"
+    )]
+    FeeSettlementInsufficientBalance { remaining_fee: u128, balance_low: Felt, balance_high: Felt },
+
     #[error("Entry point execution error: {reason}")]
     ExecutionFailed { reason: String },
 
@@ -69,6 +79,26 @@ pub enum ExecutionError {
     #[error("Transaction reverted: {revert_error}")]
     TransactionReverted { revert_error: String },
 
+    #[error(
+        "Class with hash {class_hash:#x} uses the `{syscall}` syscall which is disabled on this \
+         chain"
+    )]
+    DisabledSyscall { class_hash: ClassHash, syscall: Syscall },
+
+    #[error("Calldata length ({length}) exceeds the maximum ({max})")]
+    CalldataTooLong { length: usize, max: usize },
+
+    #[error("Signature length ({length}) exceeds the maximum ({max})")]
+    SignatureTooLong { length: usize, max: usize },
+
+    #[error(
+        "Bytecode size ({size}) of class with hash {class_hash:#x} exceeds the maximum ({max})"
+    )]
+    BytecodeTooLarge { class_hash: ClassHash, size: usize, max: usize },
+
+    #[error("Sierra gas consumed ({gas_consumed}) exceeds the max L2 gas amount ({max_amount})")]
+    L2GasBoundExceeded { max_amount: u64, gas_consumed: u128 },
+
     #[error("{0}")]
     Other(String),
 }
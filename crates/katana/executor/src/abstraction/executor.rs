@@ -1,6 +1,7 @@
 use katana_primitives::block::ExecutableBlock;
 use katana_primitives::env::{BlockEnv, CfgEnv};
 use katana_primitives::fee::TxFeeInfo;
+use katana_primitives::trace::CallInfo;
 use katana_primitives::transaction::{ExecutableTxWithHash, TxWithHash};
 use katana_primitives::Felt;
 use katana_provider::traits::state::StateProvider;
@@ -65,6 +66,9 @@ pub trait ExecutorExt {
     ) -> Vec<ResultAndStates>;
 
     /// Get the fee estimation for the given transactions.
+    ///
+    /// The transactions are executed in order, each one on top of the state changes of the
+    /// previous ones, without modifying the state of the executor.
     fn estimate_fee(
         &self,
         transactions: Vec<ExecutableTxWithHash>,
@@ -73,4 +77,8 @@ pub trait ExecutorExt {
 
     /// Perform a contract entry point call and return the output.
     fn call(&self, call: EntryPointCall) -> Result<Vec<Felt>, ExecutionError>;
+
+    /// Perform a contract entry point call and return its execution trace, including the inner
+    /// calls and the resources used by each of them.
+    fn trace_call(&self, call: EntryPointCall) -> Result<CallInfo, ExecutionError>;
 }
@@ -1,5 +1,11 @@
 mod error;
 mod executor;
+mod session;
+
+use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::{Arc, RwLock};
 
 pub use error::*;
 pub use executor::*;
@@ -8,11 +14,13 @@ use katana_primitives::contract::{ContractAddress, Nonce, StorageKey, StorageVal
 use katana_primitives::receipt::Receipt;
 use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
 use katana_primitives::trace::TxExecInfo;
-use katana_primitives::transaction::TxWithHash;
+use katana_primitives::transaction::{ExecutableTxWithHash, TxWithHash};
 use katana_primitives::Felt;
 use katana_provider::traits::contract::ContractClassProvider;
 use katana_provider::traits::state::StateProvider;
 use katana_provider::ProviderResult;
+use serde::{Deserialize, Serialize};
+pub use session::*;
 
 pub type ExecutorResult<T> = Result<T, error::ExecutorError>;
 
@@ -28,11 +36,24 @@ pub struct ExecutionFlags {
     fee: bool,
     /// Determine whether to perform transaction's sender nonce check.
     nonce_check: bool,
+    /// Determine how the computation of a transaction is accounted for in its fee.
+    gas_accounting: GasAccounting,
+    /// The accounts whose validation logic is skipped even if the account validation is enabled.
+    impersonated_accounts: ImpersonatedAccounts,
+    /// The session keys whose transactions are executed without the account validation logic.
+    sessions: Sessions,
 }
 
 impl Default for ExecutionFlags {
     fn default() -> Self {
-        Self { account_validation: true, fee: true, nonce_check: true }
+        Self {
+            account_validation: true,
+            fee: true,
+            nonce_check: true,
+            gas_accounting: GasAccounting::default(),
+            impersonated_accounts: ImpersonatedAccounts::default(),
+            sessions: Sessions::default(),
+        }
     }
 }
 
@@ -59,6 +80,24 @@ impl ExecutionFlags {
         self
     }
 
+    /// Set how the transaction computation is accounted for in the fee.
+    pub fn with_gas_accounting(mut self, mode: GasAccounting) -> Self {
+        self.gas_accounting = mode;
+        self
+    }
+
+    /// Set the accounts whose validation logic is skipped.
+    pub fn with_impersonated_accounts(mut self, accounts: ImpersonatedAccounts) -> Self {
+        self.impersonated_accounts = accounts;
+        self
+    }
+
+    /// Set the sessions whose transactions skip the account validation logic.
+    pub fn with_sessions(mut self, sessions: Sessions) -> Self {
+        self.sessions = sessions;
+        self
+    }
+
     /// Returns whether the account validation is enabled.
     pub fn account_validation(&self) -> bool {
         self.account_validation
@@ -73,6 +112,91 @@ impl ExecutionFlags {
     pub fn nonce_check(&self) -> bool {
         self.nonce_check
     }
+
+    /// Returns how the transaction computation is accounted for in the fee.
+    pub fn gas_accounting(&self) -> GasAccounting {
+        self.gas_accounting
+    }
+
+    /// Returns the accounts whose validation logic is skipped.
+    pub fn impersonated_accounts(&self) -> &ImpersonatedAccounts {
+        &self.impersonated_accounts
+    }
+
+    /// Returns the sessions whose transactions skip the account validation logic.
+    pub fn sessions(&self) -> &Sessions {
+        &self.sessions
+    }
+
+    /// Returns whether the account validation is enabled for `tx`, executed at `timestamp`. It's
+    /// not if its sender is impersonated, or if it's signed with a valid session of its sender.
+    pub fn account_validation_of(&self, tx: &ExecutableTxWithHash, timestamp: u64) -> bool {
+        self.account_validation
+            && !self.impersonated_accounts.contains(tx.sender_address())
+            && !self.sessions.authorizes(tx, timestamp)
+    }
+}
+
+/// A set of impersonated accounts, whose transactions are executed without running their
+/// validation logic, so that they can be sent without a valid signature.
+///
+/// The set is shared by all its clones, so the accounts impersonated through one of them apply to
+/// every executor created with the same [`ExecutionFlags`].
+#[derive(Debug, Clone, Default)]
+pub struct ImpersonatedAccounts(Arc<RwLock<HashSet<ContractAddress>>>);
+
+impl ImpersonatedAccounts {
+    /// Starts impersonating `address`. Returns `false` if it was already impersonated.
+    pub fn insert(&self, address: ContractAddress) -> bool {
+        self.0.write().expect("poisoned lock").insert(address)
+    }
+
+    /// Stops impersonating `address`. Returns `false` if it wasn't impersonated.
+    pub fn remove(&self, address: ContractAddress) -> bool {
+        self.0.write().expect("poisoned lock").remove(&address)
+    }
+
+    /// Returns whether `address` is impersonated.
+    pub fn contains(&self, address: ContractAddress) -> bool {
+        self.0.read().expect("poisoned lock").contains(&address)
+    }
+}
+
+/// The unit in which the computation of a transaction is measured when computing its fee.
+///
+/// Starknet is transitioning from charging for Cairo steps to charging for the Sierra gas
+/// consumed by the contracts, this allows comparing the cost of transactions under both regimes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum GasAccounting {
+    /// The computation is charged based on the Cairo VM resources (steps and builtins).
+    #[default]
+    CairoSteps,
+    /// The computation is charged based on the Sierra gas consumed by the contract calls.
+    SierraGas,
+}
+
+impl fmt::Display for GasAccounting {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GasAccounting::CairoSteps => f.write_str("cairo-steps"),
+            GasAccounting::SierraGas => f.write_str("sierra-gas"),
+        }
+    }
+}
+
+impl FromStr for GasAccounting {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "cairo-steps" => Ok(GasAccounting::CairoSteps),
+            "sierra-gas" => Ok(GasAccounting::SierraGas),
+            _ => Err(format!(
+                "invalid gas accounting mode `{s}`, expected `cairo-steps` or `sierra-gas`"
+            )),
+        }
+    }
 }
 
 /// Stats about the transactions execution.
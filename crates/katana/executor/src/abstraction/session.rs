@@ -0,0 +1,171 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::transaction::{ExecutableTx, ExecutableTxWithHash, InvokeTx};
+use katana_primitives::Felt;
+use serde::{Deserialize, Serialize};
+
+/// A key allowed to sign some of the transactions of an account on its behalf.
+///
+/// The transactions signed with a session key are executed without running the validation logic
+/// of the account, like the ones of an impersonated account. This lets clients exercise
+/// session-based flows against the dev accounts of a local node, whatever their account class.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    /// The public key of the session key.
+    pub public_key: Felt,
+    /// The timestamp from which the session can't be used anymore.
+    pub expires_at: u64,
+    /// The calls the transactions signed with the session are allowed to make.
+    pub policies: Vec<SessionPolicy>,
+}
+
+/// A call allowed by a session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionPolicy {
+    pub contract_address: ContractAddress,
+    pub selector: Felt,
+}
+
+impl Session {
+    /// Returns whether the session authorizes an invoke transaction of hash `hash`, `signature`
+    /// and `calldata` at `timestamp`.
+    ///
+    /// The calldata must be a list of calls, as encoded by Cairo 1 accounts:
+    /// `[calls len, (to, selector, calldata len, calldata...)...]`.
+    fn authorizes(
+        &self,
+        hash: Felt,
+        signature: &[Felt],
+        calldata: &[Felt],
+        timestamp: u64,
+    ) -> bool {
+        if timestamp >= self.expires_at {
+            return false;
+        }
+
+        let [r, s] = signature else { return false };
+        if !starknet_crypto::verify(&self.public_key, &hash, r, s).unwrap_or(false) {
+            return false;
+        }
+
+        match calls_of(calldata) {
+            Some(calls) => calls.iter().all(|call| self.policies.contains(call)),
+            None => false,
+        }
+    }
+}
+
+/// The sessions of the accounts of a node.
+///
+/// The sessions are shared by all the clones, so the sessions minted through one of them apply to
+/// every executor created with the same [`ExecutionFlags`](crate::ExecutionFlags).
+#[derive(Debug, Clone, Default)]
+pub struct Sessions(Arc<RwLock<HashMap<ContractAddress, Vec<Session>>>>);
+
+impl Sessions {
+    /// Adds a session to the sessions of `account`.
+    pub fn insert(&self, account: ContractAddress, session: Session) {
+        self.0.write().expect("poisoned lock").entry(account).or_default().push(session);
+    }
+
+    /// Revokes all the sessions of `account`. Returns the number of revoked sessions.
+    pub fn revoke(&self, account: ContractAddress) -> usize {
+        self.0.write().expect("poisoned lock").remove(&account).map_or(0, |s| s.len())
+    }
+
+    /// Returns whether `tx` is an invoke transaction signed with one of the sessions of its
+    /// sender, which allows all of its calls and hasn't expired at `timestamp`.
+    pub fn authorizes(&self, tx: &ExecutableTxWithHash, timestamp: u64) -> bool {
+        let (sender, signature, calldata) = match &tx.transaction {
+            ExecutableTx::Invoke(InvokeTx::V1(tx)) => {
+                (tx.sender_address, &tx.signature, &tx.calldata)
+            }
+            ExecutableTx::Invoke(InvokeTx::V3(tx)) => {
+                (tx.sender_address, &tx.signature, &tx.calldata)
+            }
+            _ => return false,
+        };
+
+        let sessions = self.0.read().expect("poisoned lock");
+        sessions.get(&sender).is_some_and(|sessions| {
+            sessions.iter().any(|s| s.authorizes(tx.hash, signature, calldata, timestamp))
+        })
+    }
+}
+
+/// Decodes the calls of the calldata of an invoke transaction, as `(to, selector)` pairs.
+fn calls_of(calldata: &[Felt]) -> Option<Vec<SessionPolicy>> {
+    let (len, mut rest) = calldata.split_first()?;
+    let len = usize::try_from(*len).ok()?;
+
+    let mut calls = Vec::with_capacity(len.min(rest.len()));
+    for _ in 0..len {
+        let [to, selector, args_len, args @ ..] = rest else { return None };
+        let args_len = usize::try_from(*args_len).ok()?;
+        rest = args.get(args_len..)?;
+        calls.push(SessionPolicy { contract_address: (*to).into(), selector: *selector });
+    }
+
+    rest.is_empty().then_some(calls)
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::transaction::InvokeTxV1;
+    use katana_primitives::{address, felt};
+    use starknet_crypto::{get_public_key, rfc6979_generate_k, sign};
+
+    use super::*;
+
+    fn transfer_policy() -> SessionPolicy {
+        SessionPolicy { contract_address: address!("0x49d"), selector: felt!("0x83afd3") }
+    }
+
+    fn invoke(private_key: Felt, calldata: Vec<Felt>) -> ExecutableTxWithHash {
+        let tx = InvokeTxV1 { sender_address: address!("0x1"), calldata, ..Default::default() };
+        let mut tx = ExecutableTxWithHash::new(ExecutableTx::Invoke(InvokeTx::V1(tx)));
+
+        let k = rfc6979_generate_k(&tx.hash, &private_key, None);
+        let signature = sign(&private_key, &tx.hash, &k).unwrap();
+        if let ExecutableTx::Invoke(InvokeTx::V1(ref mut inner)) = tx.transaction {
+            inner.signature = vec![signature.r, signature.s];
+        }
+
+        tx
+    }
+
+    #[test]
+    fn sessions_authorize_signed_calls_within_their_policies() {
+        let key = felt!("0x1234");
+        let sessions = Sessions::default();
+        sessions.insert(
+            address!("0x1"),
+            Session {
+                public_key: get_public_key(&key),
+                expires_at: 100,
+                policies: vec![transfer_policy()],
+            },
+        );
+
+        let transfer = vec![Felt::ONE, felt!("0x49d"), felt!("0x83afd3"), Felt::ONE, Felt::TWO];
+        let tx = invoke(key, transfer.clone());
+        assert!(sessions.authorizes(&tx, 99));
+        // the session has expired
+        assert!(!sessions.authorizes(&tx, 100));
+
+        // signed with another key
+        assert!(!sessions.authorizes(&invoke(felt!("0x5678"), transfer.clone()), 0));
+
+        // a call outside of the policies
+        let approve = vec![Felt::ONE, felt!("0x49d"), felt!("0x219209e"), Felt::ZERO];
+        assert!(!sessions.authorizes(&invoke(key, approve), 0));
+
+        // calldata that isn't a list of calls
+        assert!(!sessions.authorizes(&invoke(key, transfer[..4].to_vec()), 0));
+
+        assert_eq!(sessions.revoke(address!("0x1")), 1);
+        assert!(!sessions.authorizes(&tx, 0));
+    }
+}
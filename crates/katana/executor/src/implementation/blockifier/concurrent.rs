@@ -0,0 +1,348 @@
+//! Optimistic concurrent execution of the transactions of a block.
+//!
+//! The transactions are first executed in parallel, each on top of the state at the start of the
+//! batch, while recording the state entries they read. They are then committed in order. A
+//! transaction which read an entry written by one committed before it executed on a stale state,
+//! so it's executed again on top of the committed transactions before being committed.
+//!
+//! Every transaction paying a fee updates the balance of the sequencer, which would make all of
+//! them conflict. The balance read by the fee transfer is thus left out of the conflict detection,
+//! and the fee collected by each transaction is added to the committed balance instead. The
+//! transactions sent by the sequencer, or whose validation or execution access the balance, still
+//! conflict on it like on any other entry.
+
+use std::cell::RefCell;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use blockifier::abi::abi_utils::get_fee_token_var_address;
+use blockifier::abi::sierra_types::next_storage_key;
+use blockifier::context::BlockContext;
+use blockifier::state::cached_state;
+use blockifier::state::state_api::{State, StateReader, StateResult};
+use katana_cairo::starknet_api::core::{ClassHash, CompiledClassHash, ContractAddress, Nonce};
+use katana_cairo::starknet_api::state::StorageKey;
+use katana_primitives::env::{Syscall, TxLimits};
+use katana_primitives::trace::CallInfo;
+use katana_primitives::transaction::{ExecutableTxWithHash, TxWithHash};
+use katana_primitives::Felt;
+
+use super::state::{CachedState, StateDb};
+use super::utils;
+use crate::{ExecutionFlags, ExecutionResult, ExecutorError, ExecutorResult};
+
+/// An entry of the state read or written by a transaction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Entry {
+    Storage(ContractAddress, StorageKey),
+    Nonce(ContractAddress),
+    ClassHash(ContractAddress),
+    CompiledClassHash(ClassHash),
+}
+
+/// The execution of a transaction on top of the state at the start of the batch.
+struct Speculation {
+    result: ExecutionResult,
+    /// The entries read by the transaction, with the value they had.
+    reads: HashMap<Entry, Felt>,
+    /// The entries written by the transaction, with their new value.
+    writes: HashMap<Entry, Felt>,
+}
+
+/// Executes the transactions with up to `workers` threads, and commits their state updates to
+/// `state`.
+///
+/// The results are the same as executing the transactions one after the other. Declare
+/// transactions must be executed sequentially, as the classes they declare aren't committed.
+pub(super) fn execute<S: StateDb>(
+    state: &CachedState<S>,
+    block_context: &BlockContext,
+    flags: &ExecutionFlags,
+    tx_limits: &TxLimits,
+    disabled_syscalls: &BTreeSet<Syscall>,
+    workers: usize,
+    transactions: Vec<ExecutableTxWithHash>,
+) -> ExecutorResult<Vec<(TxWithHash, ExecutionResult)>> {
+    let run = |tx: &ExecutableTxWithHash| {
+        speculate(state, block_context, flags, tx_limits, disabled_syscalls, tx.clone())
+    };
+
+    let next = AtomicUsize::new(0);
+    let workers = workers.min(transactions.len());
+    let mut speculations = std::thread::scope(|scope| {
+        let handles = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut speculations = Vec::new();
+                    loop {
+                        let idx = next.fetch_add(1, Ordering::Relaxed);
+                        let Some(tx) = transactions.get(idx) else { break speculations };
+                        speculations.push((idx, run(tx)));
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("execution worker panicked"))
+            .collect::<Vec<_>>()
+    });
+    speculations.sort_unstable_by_key(|(idx, _)| *idx);
+
+    let sequencer_balance = SequencerBalance::new(block_context);
+    let mut written = HashSet::new();
+    let mut results = Vec::with_capacity(transactions.len());
+
+    for ((_, speculation), tx) in speculations.into_iter().zip(transactions) {
+        let sent_by_sequencer =
+            tx.sender_address() == utils::to_address(block_context.block_info().sequencer_address);
+        // the balance is only read to collect the fee, which is added to the committed balance
+        let fee_only = !sent_by_sequencer && !sequencer_balance.is_accessed_by(&speculation.result);
+        let is_stale = |entry: &Entry| {
+            written.contains(entry) && !(fee_only && sequencer_balance.contains(entry))
+        };
+
+        let speculation = if speculation.reads.keys().any(is_stale) {
+            // the state now includes the updates of all the transactions before it
+            run(&tx)
+        } else {
+            speculation
+        };
+
+        commit(state, &speculation, &sequencer_balance)?;
+        written.extend(speculation.writes.keys().copied());
+        results.push((TxWithHash::from(&tx), speculation.result));
+    }
+
+    Ok(results)
+}
+
+fn speculate<S: StateDb>(
+    state: &CachedState<S>,
+    block_context: &BlockContext,
+    flags: &ExecutionFlags,
+    tx_limits: &TxLimits,
+    disabled_syscalls: &BTreeSet<Syscall>,
+    tx: ExecutableTxWithHash,
+) -> Speculation {
+    let reader = RecordingReader { inner: state, reads: Default::default() };
+    let mut cached = cached_state::CachedState::new(&reader);
+
+    let result = match utils::check_tx(&tx, tx_limits, disabled_syscalls) {
+        Ok(()) => utils::transact(&mut cached, block_context, flags, tx),
+        Err(error) => ExecutionResult::new_failed(error),
+    };
+
+    let diff = cached.to_state_diff().unwrap();
+    let writes = diff
+        .storage
+        .into_iter()
+        .map(|((a, k), v)| (Entry::Storage(a, k), v))
+        .chain(diff.nonces.into_iter().map(|(a, n)| (Entry::Nonce(a), n.0)))
+        .chain(diff.class_hashes.into_iter().map(|(a, h)| (Entry::ClassHash(a), h.0)))
+        .chain(
+            diff.compiled_class_hashes.into_iter().map(|(h, c)| (Entry::CompiledClassHash(h), c.0)),
+        )
+        .collect();
+
+    drop(cached);
+    Speculation { result, reads: reader.reads.into_inner(), writes }
+}
+
+/// Writes the state updates of a transaction.
+fn commit<S: StateDb>(
+    state: &CachedState<S>,
+    speculation: &Speculation,
+    sequencer_balance: &SequencerBalance,
+) -> ExecutorResult<()> {
+    let mut state = state.0.lock();
+    let state = &mut state.inner;
+
+    for (entry, value) in &speculation.writes {
+        let res = match *entry {
+            _ if sequencer_balance.contains(entry) => continue,
+            Entry::Storage(address, key) => state.set_storage_at(address, key, *value),
+            // a transaction bumps the nonce of its sender by one
+            Entry::Nonce(address) => state.increment_nonce(address),
+            Entry::ClassHash(address) => state.set_class_hash_at(address, ClassHash(*value)),
+            Entry::CompiledClassHash(hash) => {
+                state.set_compiled_class_hash(hash, CompiledClassHash(*value))
+            }
+        };
+
+        res.map_err(|e| ExecutorError::StateUpdate(e.to_string()))?;
+    }
+
+    for token in sequencer_balance.tokens {
+        sequencer_balance.commit(state, token, speculation)?;
+    }
+
+    Ok(())
+}
+
+/// The storage entries of the balance of the sequencer in the fee tokens.
+struct SequencerBalance {
+    tokens: [ContractAddress; 2],
+    /// The entry of the low part of the `u256` balance.
+    low: StorageKey,
+    /// The entry of the high part of the `u256` balance.
+    high: StorageKey,
+}
+
+impl SequencerBalance {
+    fn new(block_context: &BlockContext) -> Self {
+        let sequencer = block_context.block_info().sequencer_address;
+        let low = get_fee_token_var_address(sequencer);
+        let high = next_storage_key(&low).expect("valid storage key");
+
+        let tokens = &block_context.chain_info().fee_token_addresses;
+        Self { tokens: [tokens.eth_fee_token_address, tokens.strk_fee_token_address], low, high }
+    }
+
+    fn contains(&self, entry: &Entry) -> bool {
+        matches!(*entry, Entry::Storage(token, key)
+            if self.tokens.contains(&token) && (key == self.low || key == self.high))
+    }
+
+    /// Returns whether the balance may have been accessed by the transaction other than by its
+    /// fee transfer.
+    fn is_accessed_by(&self, result: &ExecutionResult) -> bool {
+        match result {
+            ExecutionResult::Success { trace, .. } if trace.revert_error.is_none() => {
+                [&trace.validate_call_info, &trace.execute_call_info]
+                    .into_iter()
+                    .flatten()
+                    .any(|call| self.is_accessed_in(call))
+            }
+            // the state accessed by a reverted or failed execution isn't traced
+            _ => true,
+        }
+    }
+
+    fn is_accessed_in(&self, call: &CallInfo) -> bool {
+        let is_token = self.tokens.iter().any(|t| utils::to_address(*t) == call.contract_address);
+        let accessed = |key: &StorageKey| call.accessed_storage_keys.contains(key.0.key());
+
+        (is_token && (accessed(&self.low) || accessed(&self.high)))
+            || call.inner_calls.iter().any(|call| self.is_accessed_in(call))
+    }
+
+    /// Adds the amount the transaction added to the balance in `token` to the committed one.
+    ///
+    /// The balance read by the transaction may be stale if only its fee transfer read it.
+    fn commit<S: State>(
+        &self,
+        state: &mut S,
+        token: ContractAddress,
+        speculation: &Speculation,
+    ) -> ExecutorResult<()> {
+        let (low, high) = (Entry::Storage(token, self.low), Entry::Storage(token, self.high));
+        if !speculation.writes.contains_key(&low) && !speculation.writes.contains_key(&high) {
+            return Ok(());
+        }
+
+        let read = |entry| speculation.reads.get(&entry).copied().unwrap_or_default();
+        let read = (read(low), read(high));
+        let written = (
+            speculation.writes.get(&low).copied().unwrap_or(read.0),
+            speculation.writes.get(&high).copied().unwrap_or(read.1),
+        );
+
+        let map_err =
+            |e: blockifier::state::errors::StateError| ExecutorError::StateUpdate(e.to_string());
+        let current = (
+            state.get_storage_at(token, self.low).map_err(map_err)?,
+            state.get_storage_at(token, self.high).map_err(map_err)?,
+        );
+
+        let (new_low, new_high) = add_sub_u256(
+            to_u256(current).ok_or_else(invalid_balance)?,
+            to_u256(written).ok_or_else(invalid_balance)?,
+            to_u256(read).ok_or_else(invalid_balance)?,
+        );
+
+        state.set_storage_at(token, self.low, new_low.into()).map_err(map_err)?;
+        state.set_storage_at(token, self.high, new_high.into()).map_err(map_err)
+    }
+}
+
+fn invalid_balance() -> ExecutorError {
+    ExecutorError::StateUpdate("sequencer balance isn't a valid u256".to_string())
+}
+
+/// Returns the low and high parts of a `u256` stored in two felts.
+fn to_u256((low, high): (Felt, Felt)) -> Option<(u128, u128)> {
+    Some((u128::try_from(low).ok()?, u128::try_from(high).ok()?))
+}
+
+/// Returns `a + b - c` of `u256` split in their low and high parts, carrying between the parts.
+fn add_sub_u256(a: (u128, u128), b: (u128, u128), c: (u128, u128)) -> (u128, u128) {
+    let (low, carry) = a.0.overflowing_add(b.0);
+    let high = a.1.wrapping_add(b.1).wrapping_add(carry.into());
+    let (low, borrow) = low.overflowing_sub(c.0);
+    let high = high.wrapping_sub(c.1).wrapping_sub(borrow.into());
+    (low, high)
+}
+
+/// A state reader recording the first value read of every entry.
+struct RecordingReader<'s, S: StateDb> {
+    inner: &'s CachedState<S>,
+    reads: RefCell<HashMap<Entry, Felt>>,
+}
+
+impl<S: StateDb> RecordingReader<'_, S> {
+    fn record(&self, entry: Entry, value: Felt) {
+        self.reads.borrow_mut().entry(entry).or_insert(value);
+    }
+}
+
+impl<S: StateDb> StateReader for &RecordingReader<'_, S> {
+    fn get_storage_at(&self, address: ContractAddress, key: StorageKey) -> StateResult<Felt> {
+        let value = self.inner.get_storage_at(address, key)?;
+        self.record(Entry::Storage(address, key), value);
+        Ok(value)
+    }
+
+    fn get_nonce_at(&self, address: ContractAddress) -> StateResult<Nonce> {
+        let nonce = self.inner.get_nonce_at(address)?;
+        self.record(Entry::Nonce(address), nonce.0);
+        Ok(nonce)
+    }
+
+    fn get_class_hash_at(&self, address: ContractAddress) -> StateResult<ClassHash> {
+        let hash = self.inner.get_class_hash_at(address)?;
+        self.record(Entry::ClassHash(address), hash.0);
+        Ok(hash)
+    }
+
+    fn get_compiled_contract_class(
+        &self,
+        class_hash: ClassHash,
+    ) -> StateResult<blockifier::execution::contract_class::ContractClass> {
+        let class = self.inner.get_compiled_contract_class(class_hash)?;
+        // only the declaration of the class matters, legacy classes have no compiled class hash
+        self.record(Entry::CompiledClassHash(class_hash), Felt::ZERO);
+        Ok(class)
+    }
+
+    fn get_compiled_class_hash(&self, class_hash: ClassHash) -> StateResult<CompiledClassHash> {
+        let hash = self.inner.get_compiled_class_hash(class_hash)?;
+        self.record(Entry::CompiledClassHash(class_hash), hash.0);
+        Ok(hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::add_sub_u256;
+
+    #[test]
+    fn add_sub_u256_carries() {
+        // the fee makes the low part of the balance overflow
+        assert_eq!(add_sub_u256((u128::MAX - 1, 0), (12, 0), (10, 0)), (0, 1));
+        // the read balance is above the committed one
+        assert_eq!(add_sub_u256((5, 1), (20, 0), (u128::MAX, 0)), (26, 0));
+        assert_eq!(add_sub_u256((7, 3), (7, 3), (7, 3)), (7, 3));
+    }
+}
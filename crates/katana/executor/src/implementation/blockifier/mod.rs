@@ -1,10 +1,12 @@
 // Re-export the blockifier crate.
 pub use blockifier;
 
+mod concurrent;
 mod error;
 mod state;
 pub mod utils;
 
+use std::collections::BTreeSet;
 use std::num::NonZeroU128;
 
 use blockifier::blockifier::block::{BlockInfo, GasPrices};
@@ -13,9 +15,10 @@ use blockifier::state::cached_state::{self, MutRefState};
 use blockifier::state::state_api::StateReader;
 use katana_cairo::starknet_api::block::{BlockNumber, BlockTimestamp};
 use katana_primitives::block::{ExecutableBlock, GasPrices as KatanaGasPrices, PartialHeader};
-use katana_primitives::env::{BlockEnv, CfgEnv};
+use katana_primitives::env::{BlockEnv, CfgEnv, Syscall, TxLimits};
 use katana_primitives::fee::TxFeeInfo;
-use katana_primitives::transaction::{ExecutableTx, ExecutableTxWithHash, TxWithHash};
+use katana_primitives::trace::CallInfo;
+use katana_primitives::transaction::{ExecutableTx, ExecutableTxWithHash, TxHash, TxWithHash};
 use katana_primitives::Felt;
 use katana_provider::traits::state::StateProvider;
 use tracing::info;
@@ -33,12 +36,22 @@ pub(crate) const LOG_TARGET: &str = "katana::executor::blockifier";
 pub struct BlockifierFactory {
     cfg: CfgEnv,
     flags: ExecutionFlags,
+    workers: usize,
 }
 
 impl BlockifierFactory {
     /// Create a new factory with the given configuration and simulation flags.
     pub fn new(cfg: CfgEnv, flags: ExecutionFlags) -> Self {
-        Self { cfg, flags }
+        Self { cfg, flags, workers: 1 }
+    }
+
+    /// Set the number of threads the executors execute the transactions of a block with.
+    ///
+    /// With more than one, the transactions are executed concurrently and re-executed when they
+    /// conflict with the ones before them. See [`StarknetVMProcessor::with_workers`].
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers;
+        self
     }
 }
 
@@ -60,7 +73,8 @@ impl ExecutorFactory for BlockifierFactory {
     {
         let cfg_env = self.cfg.clone();
         let flags = self.flags.clone();
-        Box::new(StarknetVMProcessor::new(Box::new(state), block_env, cfg_env, flags))
+        let executor = StarknetVMProcessor::new(Box::new(state), block_env, cfg_env, flags);
+        Box::new(executor.with_workers(self.workers))
     }
 
     fn cfg(&self) -> &CfgEnv {
@@ -80,6 +94,9 @@ pub struct StarknetVMProcessor<'a> {
     transactions: Vec<(TxWithHash, ExecutionResult)>,
     simulation_flags: ExecutionFlags,
     stats: ExecutionStats,
+    tx_limits: TxLimits,
+    disabled_syscalls: BTreeSet<Syscall>,
+    workers: usize,
 }
 
 impl<'a> StarknetVMProcessor<'a> {
@@ -92,7 +109,29 @@ impl<'a> StarknetVMProcessor<'a> {
         let transactions = Vec::new();
         let block_context = utils::block_context_from_envs(&block_env, &cfg_env);
         let state = state::CachedState::new(StateProviderDb::new(state));
-        Self { block_context, state, transactions, simulation_flags, stats: Default::default() }
+        let tx_limits = cfg_env.tx_limits;
+        let disabled_syscalls = cfg_env.disabled_syscalls;
+        Self {
+            block_context,
+            state,
+            transactions,
+            simulation_flags,
+            stats: Default::default(),
+            tx_limits,
+            disabled_syscalls,
+            workers: 1,
+        }
+    }
+
+    /// Set the number of threads the transactions are executed with.
+    ///
+    /// With more than one, the transactions are executed optimistically in parallel, and the ones
+    /// that read a state entry written by a transaction before them are executed again. The
+    /// results are the same as with a sequential execution. Declare transactions are always
+    /// executed sequentially.
+    pub fn with_workers(mut self, workers: usize) -> Self {
+        self.workers = workers;
+        self
     }
 
     fn fill_block_env_from_header(&mut self, header: &PartialHeader) {
@@ -132,6 +171,68 @@ impl<'a> StarknetVMProcessor<'a> {
             BlockContext::new(block_info, chain_info, versioned_constants, Default::default());
     }
 
+    fn execute_sequentially(
+        &mut self,
+        transactions: Vec<ExecutableTxWithHash>,
+    ) -> ExecutorResult<()> {
+        let block_context = &self.block_context;
+        let flags = &self.simulation_flags;
+        let mut state = self.state.0.lock();
+
+        for exec_tx in transactions {
+            // Collect class artifacts if its a declare tx
+            let class_decl_artifacts = if let ExecutableTx::Declare(tx) = exec_tx.as_ref() {
+                let class_hash = tx.class_hash();
+                Some((class_hash, tx.compiled_class.clone(), tx.sierra_class.clone()))
+            } else {
+                None
+            };
+
+            let tx = TxWithHash::from(&exec_tx);
+            let res = match utils::check_tx(&exec_tx, &self.tx_limits, &self.disabled_syscalls) {
+                Ok(()) => utils::transact(&mut state.inner, block_context, flags, exec_tx),
+                Err(error) => ExecutionResult::new_failed(error),
+            };
+
+            if let (ExecutionResult::Success { .. }, Some((class_hash, compiled, sierra))) =
+                (&res, class_decl_artifacts)
+            {
+                state.declared_classes.insert(class_hash, (compiled, sierra));
+            }
+
+            record_execution(&mut self.stats, tx.hash, &res);
+            self.transactions.push((tx, res));
+        }
+
+        Ok(())
+    }
+
+    fn execute_concurrently(
+        &mut self,
+        transactions: Vec<ExecutableTxWithHash>,
+    ) -> ExecutorResult<()> {
+        if transactions.is_empty() {
+            return Ok(());
+        }
+
+        let results = concurrent::execute(
+            &self.state,
+            &self.block_context,
+            &self.simulation_flags,
+            &self.tx_limits,
+            &self.disabled_syscalls,
+            self.workers,
+            transactions,
+        )?;
+
+        for (tx, res) in results {
+            record_execution(&mut self.stats, tx.hash, &res);
+            self.transactions.push((tx, res));
+        }
+
+        Ok(())
+    }
+
     fn simulate_with<F, T>(
         &self,
         transactions: Vec<ExecutableTxWithHash>,
@@ -148,7 +249,10 @@ impl<'a> StarknetVMProcessor<'a> {
         let mut results = Vec::with_capacity(transactions.len());
         for exec_tx in transactions {
             let tx = TxWithHash::from(&exec_tx);
-            let res = utils::transact(&mut state, block_context, flags, exec_tx);
+            let res = match utils::check_tx(&exec_tx, &self.tx_limits, &self.disabled_syscalls) {
+                Ok(()) => utils::transact(&mut state, block_context, flags, exec_tx),
+                Err(error) => ExecutionResult::new_failed(error),
+            };
             results.push(op(&mut state, (tx, res)));
         }
 
@@ -167,49 +271,21 @@ impl<'a> BlockExecutor<'a> for StarknetVMProcessor<'a> {
         &mut self,
         transactions: Vec<ExecutableTxWithHash>,
     ) -> ExecutorResult<()> {
-        let block_context = &self.block_context;
-        let flags = &self.simulation_flags;
-        let mut state = self.state.0.lock();
+        if self.workers <= 1 {
+            return self.execute_sequentially(transactions);
+        }
 
-        for exec_tx in transactions {
-            // Collect class artifacts if its a declare tx
-            let class_decl_artifacts = if let ExecutableTx::Declare(tx) = exec_tx.as_ref() {
-                let class_hash = tx.class_hash();
-                Some((class_hash, tx.compiled_class.clone(), tx.sierra_class.clone()))
+        let mut batch = Vec::new();
+        for tx in transactions {
+            if let ExecutableTx::Declare(_) = tx.as_ref() {
+                self.execute_concurrently(std::mem::take(&mut batch))?;
+                self.execute_sequentially(vec![tx])?;
             } else {
-                None
-            };
-
-            let tx = TxWithHash::from(&exec_tx);
-            let hash = tx.hash;
-            let res = utils::transact(&mut state.inner, block_context, flags, exec_tx);
-
-            match &res {
-                ExecutionResult::Success { receipt, trace } => {
-                    self.stats.l1_gas_used += receipt.fee().gas_consumed;
-                    self.stats.cairo_steps_used +=
-                        receipt.resources_used().vm_resources.n_steps as u128;
-
-                    if let Some(reason) = receipt.revert_reason() {
-                        info!(target: LOG_TARGET, hash = format!("{hash:#x}"), %reason, "Transaction reverted.");
-                    }
-
-                    if let Some((class_hash, compiled, sierra)) = class_decl_artifacts {
-                        state.declared_classes.insert(class_hash, (compiled, sierra));
-                    }
-
-                    crate::utils::log_resources(&trace.actual_resources);
-                }
-
-                ExecutionResult::Failed { error } => {
-                    info!(target: LOG_TARGET, hash = format!("{hash:#x}"), %error, "Executing transaction.");
-                }
-            };
-
-            self.transactions.push((tx, res));
+                batch.push(tx);
+            }
         }
 
-        Ok(())
+        self.execute_concurrently(batch)
     }
 
     fn take_execution_output(&mut self) -> ExecutorResult<ExecutionOutput> {
@@ -247,6 +323,26 @@ impl<'a> BlockExecutor<'a> for StarknetVMProcessor<'a> {
     }
 }
 
+/// Accounts the resources used by an executed transaction in the stats, and logs its outcome.
+fn record_execution(stats: &mut ExecutionStats, hash: TxHash, res: &ExecutionResult) {
+    match res {
+        ExecutionResult::Success { receipt, trace } => {
+            stats.l1_gas_used += receipt.fee().gas_consumed;
+            stats.cairo_steps_used += receipt.resources_used().vm_resources.n_steps as u128;
+
+            if let Some(reason) = receipt.revert_reason() {
+                info!(target: LOG_TARGET, hash = format!("{hash:#x}"), %reason, "Transaction reverted.");
+            }
+
+            crate::utils::log_resources(&trace.actual_resources);
+        }
+
+        ExecutionResult::Failed { error } => {
+            info!(target: LOG_TARGET, hash = format!("{hash:#x}"), %error, "Executing transaction.");
+        }
+    }
+}
+
 impl ExecutorExt for StarknetVMProcessor<'_> {
     fn simulate(
         &self,
@@ -289,4 +385,12 @@ impl ExecutorExt for StarknetVMProcessor<'_> {
         let retdata = utils::call(call, state, block_context, 1_000_000_000)?;
         Ok(retdata)
     }
+
+    fn trace_call(&self, call: EntryPointCall) -> Result<CallInfo, ExecutionError> {
+        let block_context = &self.block_context;
+        let mut state = self.state.0.lock();
+        let state = MutRefState::new(&mut state.inner);
+        let trace = utils::trace_call(call, state, block_context, 1_000_000_000)?;
+        Ok(trace)
+    }
 }
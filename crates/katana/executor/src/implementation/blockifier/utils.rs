@@ -1,4 +1,4 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::num::NonZeroU128;
 use std::sync::Arc;
 
@@ -15,7 +15,7 @@ use blockifier::execution::contract_class::{
 use blockifier::execution::entry_point::{CallEntryPoint, CallType, EntryPointExecutionContext};
 use blockifier::fee::fee_utils::get_fee_by_gas_vector;
 use blockifier::state::cached_state;
-use blockifier::state::state_api::StateReader;
+use blockifier::state::state_api::{State, StateReader};
 use blockifier::transaction::account_transaction::AccountTransaction;
 use blockifier::transaction::objects::{
     DeprecatedTransactionInfo, FeeType, HasRelatedFeeType, TransactionExecutionInfo,
@@ -44,19 +44,20 @@ use katana_cairo::starknet_api::transaction::{
     ResourceBoundsMapping, Tip, TransactionHash, TransactionSignature, TransactionVersion,
 };
 use katana_primitives::chain::NamedChainId;
-use katana_primitives::env::{BlockEnv, CfgEnv};
+use katana_primitives::env::{BlockEnv, CfgEnv, Syscall, TxLimits};
 use katana_primitives::fee::{PriceUnit, TxFeeInfo};
 use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
-use katana_primitives::trace::{L1Gas, TxExecInfo, TxResources};
+use katana_primitives::trace::{L1Gas, StateDiffSize, TxExecInfo, TxResources};
 use katana_primitives::transaction::{
     DeclareTx, DeployAccountTx, ExecutableTx, ExecutableTxWithHash, InvokeTx, TxType,
 };
 use katana_primitives::{class, event, message, trace, Felt};
 use katana_provider::traits::contract::ContractClassProvider;
 use starknet::core::utils::parse_cairo_short_string;
+use starknet::macros::selector;
 
 use super::state::{CachedState, StateDb};
-use crate::abstraction::{EntryPointCall, ExecutionFlags};
+use crate::abstraction::{EntryPointCall, ExecutionFlags, GasAccounting};
 use crate::utils::build_receipt;
 use crate::{ExecutionError, ExecutionResult};
 
@@ -70,9 +71,9 @@ pub fn transact<S: StateReader>(
         state: &mut cached_state::CachedState<S>,
         block_context: &BlockContext,
         simulation_flags: &ExecutionFlags,
+        validate: bool,
         tx: Transaction,
     ) -> Result<(TransactionExecutionInfo, TxFeeInfo), ExecutionError> {
-        let validate = simulation_flags.account_validation();
         let charge_fee = simulation_flags.fee();
         // Blockifier doesn't provide a way to fully skip nonce check during the tx validation
         // stage. The `nonce_check` flag in `tx.execute()` only 'relaxes' the check for
@@ -122,10 +123,45 @@ pub fn transact<S: StateReader>(
         Ok((info, fee_info))
     }
 
-    match transact_inner(state, block_context, simulation_flags, to_executor_tx(tx.clone())) {
-        Ok((info, fee)) => {
+    let sender = tx.sender_address();
+    // the L2 gas can only be checked once the transaction is executed, so its changes are kept
+    // apart until then
+    let max_l2_gas = tx.resource_bounds().map(|bounds| bounds.l2_gas.max_amount);
+    let mut tx_state = cached_state::TransactionalState::create_transactional(state);
+
+    let timestamp = block_context.block_info().block_timestamp.0;
+    let validate = simulation_flags.account_validation_of(&tx, timestamp);
+
+    let exec_tx = to_executor_tx(tx.clone());
+    match transact_inner(&mut tx_state, block_context, simulation_flags, validate, exec_tx) {
+        Ok((info, mut fee)) => {
+            // the fee transferred during the execution, if any, is the one computed by blockifier
+            let charged_fee = info.fee_transfer_call_info.as_ref().map(|_| fee.overall_fee);
             // get the trace and receipt from the execution info
-            let trace = to_exec_info(info, tx.r#type());
+            let mut trace = to_exec_info(info, tx.r#type());
+
+            if let Err(error) = check_l2_gas_bound(max_l2_gas, &trace) {
+                tx_state.abort();
+                return ExecutionResult::new_failed(error);
+            }
+
+            if simulation_flags.gas_accounting() == GasAccounting::SierraGas {
+                apply_sierra_gas_accounting(&mut fee, &trace);
+
+                if let Some(charged_fee) = charged_fee {
+                    match settle_fee(&mut tx_state, block_context, sender, &fee, charged_fee) {
+                        Ok(Some(settlement)) => attach_fee_settlement(&mut trace, settlement),
+                        Ok(None) => {}
+                        Err(error) => {
+                            tx_state.abort();
+                            return ExecutionResult::new_failed(error);
+                        }
+                    }
+                }
+            }
+
+            tx_state.commit();
+
             let receipt = build_receipt(tx.tx_ref(), fee, &trace);
             ExecutionResult::new_success(receipt, trace)
         }
@@ -134,6 +170,142 @@ pub fn transact<S: StateReader>(
     }
 }
 
+/// The Sierra gas cost of a single Cairo step.
+const SIERRA_GAS_PER_STEP: u128 = 100;
+/// The number of Cairo steps paid by a single unit of L1 gas.
+const STEPS_PER_L1_GAS: u128 = 400;
+
+/// Replaces the Cairo steps component of the transaction fee with the Sierra gas consumed by its
+/// calls, priced at the same rate (one step being worth [`SIERRA_GAS_PER_STEP`] units of gas).
+///
+/// Legacy classes, which don't track Sierra gas, are considered free of computation.
+fn apply_sierra_gas_accounting(fee: &mut TxFeeInfo, trace: &TxExecInfo) {
+    let sierra_gas = trace.sierra_gas_consumed();
+    let steps = trace.actual_resources.vm_resources.n_steps as u128;
+    let steps_l1_gas = steps.div_ceil(STEPS_PER_L1_GAS);
+    let sierra_l1_gas = sierra_gas.div_ceil(SIERRA_GAS_PER_STEP * STEPS_PER_L1_GAS);
+
+    fee.gas_consumed = fee.gas_consumed.saturating_sub(steps_l1_gas) + sierra_l1_gas;
+    fee.overall_fee = fee.overall_fee.saturating_sub(steps_l1_gas * fee.gas_price)
+        + sierra_l1_gas * fee.gas_price;
+}
+
+/// The gas given to the fee token call settling the Sierra gas fee of a transaction.
+const FEE_SETTLEMENT_INITIAL_GAS: u64 = 1_000_000_000;
+
+/// Settles the difference between the fee transferred to the sequencer during the execution of a
+/// transaction, which is computed from the Cairo steps, and the fee of its receipt, so that the
+/// sender is charged exactly the latter.
+///
+/// The difference is transferred through the `transfer` entry point of the fee token, which emits
+/// the `Transfer` event of the settlement. Returns the call, if any transfer was needed.
+fn settle_fee<S: State>(
+    state: &mut S,
+    block_context: &BlockContext,
+    sender: katana_primitives::contract::ContractAddress,
+    fee: &TxFeeInfo,
+    charged_fee: u128,
+) -> Result<Option<trace::CallInfo>, ExecutionError> {
+    let fee_type = match fee.unit {
+        PriceUnit::Wei => FeeType::Eth,
+        PriceUnit::Fri => FeeType::Strk,
+    };
+    let token = block_context.chain_info().fee_token_address(&fee_type);
+    let sender = to_blk_address(sender);
+    let sequencer = block_context.block_info().sequencer_address;
+
+    let (from, to, amount) = if fee.overall_fee > charged_fee {
+        (sender, sequencer, fee.overall_fee - charged_fee)
+    } else {
+        (sequencer, sender, charged_fee - fee.overall_fee)
+    };
+
+    if amount == 0 || from == to {
+        return Ok(None);
+    }
+
+    // checked upfront, as the token would only revert the transfer with its own message
+    let (balance_low, balance_high) = state.get_fee_token_balance(from, token)?;
+    if balance_high == Felt::ZERO && balance_low < Felt::from(amount) {
+        return Err(ExecutionError::FeeSettlementInsufficientBalance {
+            remaining_fee: amount,
+            balance_low,
+            balance_high,
+        });
+    }
+
+    let call = CallEntryPoint {
+        entry_point_type: EntryPointType::External,
+        entry_point_selector: core::EntryPointSelector(selector!("transfer")),
+        calldata: Calldata(Arc::new(vec![*to.0.key(), amount.into(), Felt::ZERO])),
+        storage_address: token,
+        caller_address: from,
+        call_type: CallType::Call,
+        initial_gas: FEE_SETTLEMENT_INITIAL_GAS,
+        ..Default::default()
+    };
+
+    let mut context = EntryPointExecutionContext::new(
+        Arc::new(TransactionContext {
+            block_context: block_context.clone(),
+            tx_info: TransactionInfo::Deprecated(DeprecatedTransactionInfo::default()),
+        }),
+        ExecutionMode::Execute,
+        true,
+    )
+    .expect("shouldn't fail");
+
+    let call = call
+        .execute(state, &mut ExecutionResources::default(), &mut context)
+        .map_err(|e| ExecutionError::FeeTransferError(e.to_string()))?;
+
+    if call.execution.failed {
+        let retdata = call.execution.retdata.0.iter();
+        let reason = retdata
+            .map(|felt| parse_cairo_short_string(felt).unwrap_or_else(|_| format!("{felt:#x}")))
+            .collect::<Vec<_>>()
+            .join(", ");
+        return Err(ExecutionError::FeeTransferError(reason));
+    }
+
+    Ok(Some(to_call_info(call)))
+}
+
+/// Adds the call settling the Sierra gas fee to the inner calls of the fee transfer of the
+/// transaction, so that its `Transfer` event is part of the receipt.
+///
+/// The call is executed in its own context, so its events are ordered after the ones of the fee
+/// transfer.
+fn attach_fee_settlement(trace: &mut TxExecInfo, mut settlement: trace::CallInfo) {
+    fn last_event_order(call: &trace::CallInfo) -> Option<u64> {
+        let inner = call.inner_calls.iter().filter_map(last_event_order);
+        call.events.iter().map(|e| e.order).chain(inner).max()
+    }
+
+    fn shift_event_orders(call: &mut trace::CallInfo, offset: u64) {
+        call.events.iter_mut().for_each(|e| e.order += offset);
+        call.inner_calls.iter_mut().for_each(|call| shift_event_orders(call, offset));
+    }
+
+    let Some(fee_transfer) = trace.fee_transfer_call_info.as_mut() else { return };
+    shift_event_orders(&mut settlement, last_event_order(fee_transfer).map_or(0, |o| o + 1));
+    fee_transfer.inner_calls.push(settlement);
+}
+
+/// Checks that the Sierra gas consumed by a transaction is within its L2 gas bound.
+///
+/// A bound of zero isn't enforced, as it's what the clients set while the L2 gas isn't priced.
+fn check_l2_gas_bound(max_amount: Option<u64>, trace: &TxExecInfo) -> Result<(), ExecutionError> {
+    let Some(max_amount) = max_amount.filter(|max| *max > 0) else { return Ok(()) };
+
+    let gas_consumed = trace.sierra_gas_consumed();
+    if gas_consumed > max_amount as u128 {
+        return Err(ExecutionError::L2GasBoundExceeded { max_amount, gas_consumed });
+    }
+
+    Ok(())
+}
+
 /// Perform a function call on a contract and retrieve the return values.
 pub fn call<S: StateReader>(
     request: EntryPointCall,
@@ -141,6 +313,27 @@ pub fn call<S: StateReader>(
     block_context: &BlockContext,
     initial_gas: u128,
 ) -> Result<Vec<Felt>, ExecutionError> {
+    let res = execute_call(request, state, block_context, initial_gas)?;
+    Ok(res.execution.retdata.0)
+}
+
+/// Perform a function call on a contract and retrieve its execution trace.
+pub fn trace_call<S: StateReader>(
+    request: EntryPointCall,
+    state: S,
+    block_context: &BlockContext,
+    initial_gas: u128,
+) -> Result<trace::CallInfo, ExecutionError> {
+    let res = execute_call(request, state, block_context, initial_gas)?;
+    Ok(to_call_info(res))
+}
+
+fn execute_call<S: StateReader>(
+    request: EntryPointCall,
+    state: S,
+    block_context: &BlockContext,
+    initial_gas: u128,
+) -> Result<CallInfo, ExecutionError> {
     let mut state = cached_state::CachedState::new(state);
 
     let call = CallEntryPoint {
@@ -176,7 +369,91 @@ pub fn call<S: StateReader>(
         .expect("shouldn't fail"),
     )?;
 
-    Ok(res.execution.retdata.0)
+    Ok(res)
+}
+
+/// Rejects declare transactions whose class uses any of the `disabled` syscalls.
+///
+/// The check is done on the libfuncs declared in the Sierra program of the class, so legacy
+/// (Cairo 0) classes are not covered.
+pub fn check_disabled_syscalls(
+    tx: &ExecutableTxWithHash,
+    disabled: &BTreeSet<Syscall>,
+) -> Result<(), ExecutionError> {
+    if disabled.is_empty() {
+        return Ok(());
+    }
+
+    let ExecutableTx::Declare(tx) = tx.as_ref() else { return Ok(()) };
+    let class::CompiledClass::Class(class) = &tx.compiled_class else { return Ok(()) };
+
+    for libfunc in &class.sierra.program.libfunc_declarations {
+        let name = libfunc.long_id.generic_id.0.as_str();
+        if let Some(syscall) = disabled.iter().find(|s| s.matches_libfunc(name)) {
+            let class_hash = tx.class_hash();
+            return Err(ExecutionError::DisabledSyscall { class_hash, syscall: *syscall });
+        }
+    }
+
+    Ok(())
+}
+
+/// Rejects transactions exceeding any of the size `limits`.
+///
+/// The bytecode size is only checked for Sierra classes, as the size of the compiled class.
+pub fn check_tx_limits(tx: &ExecutableTxWithHash, limits: &TxLimits) -> Result<(), ExecutionError> {
+    let (calldata, signature) = match tx.as_ref() {
+        ExecutableTx::Invoke(InvokeTx::V1(tx)) => (Some(&tx.calldata), &tx.signature),
+        ExecutableTx::Invoke(InvokeTx::V3(tx)) => (Some(&tx.calldata), &tx.signature),
+        ExecutableTx::DeployAccount(DeployAccountTx::V1(tx)) => {
+            (Some(&tx.constructor_calldata), &tx.signature)
+        }
+        ExecutableTx::DeployAccount(DeployAccountTx::V3(tx)) => {
+            (Some(&tx.constructor_calldata), &tx.signature)
+        }
+        ExecutableTx::Declare(tx) => match &tx.transaction {
+            DeclareTx::V1(tx) => (None, &tx.signature),
+            DeclareTx::V2(tx) => (None, &tx.signature),
+            DeclareTx::V3(tx) => (None, &tx.signature),
+        },
+        // L1 handler transactions are sent by the L1 and not signed
+        ExecutableTx::L1Handler(_) => return Ok(()),
+    };
+
+    if let (Some(calldata), Some(max)) = (calldata, limits.max_calldata_length) {
+        if calldata.len() > max {
+            return Err(ExecutionError::CalldataTooLong { length: calldata.len(), max });
+        }
+    }
+
+    if let Some(max) = limits.max_signature_length {
+        if signature.len() > max {
+            return Err(ExecutionError::SignatureTooLong { length: signature.len(), max });
+        }
+    }
+
+    if let (ExecutableTx::Declare(tx), Some(max)) = (tx.as_ref(), limits.max_bytecode_size) {
+        if let class::CompiledClass::Class(class) = &tx.compiled_class {
+            let size = class.casm.bytecode.len();
+            if size > max {
+                let class_hash = tx.class_hash();
+                return Err(ExecutionError::BytecodeTooLarge { class_hash, size, max });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Runs the checks done before executing a transaction: the size limits and the disabled
+/// syscalls.
+pub fn check_tx(
+    tx: &ExecutableTxWithHash,
+    limits: &TxLimits,
+    disabled_syscalls: &BTreeSet<Syscall>,
+) -> Result<(), ExecutionError> {
+    check_tx_limits(tx, limits)?;
+    check_disabled_syscalls(tx, disabled_syscalls)
 }
 
 pub fn to_executor_tx(tx: ExecutableTxWithHash) -> Transaction {
@@ -563,6 +840,15 @@ fn starknet_api_ethaddr_to_felt(value: katana_cairo::starknet_api::core::EthAddr
 }
 
 pub fn to_exec_info(exec_info: TransactionExecutionInfo, r#type: TxType) -> TxExecInfo {
+    let state_changes =
+        &exec_info.transaction_receipt.resources.starknet_resources.state_changes_for_fee;
+    let state_diff_size = StateDiffSize {
+        n_storage_updates: state_changes.n_storage_updates,
+        n_class_hash_updates: state_changes.n_class_hash_updates,
+        n_compiled_class_hash_updates: state_changes.n_compiled_class_hash_updates,
+        n_modified_contracts: state_changes.n_modified_contracts,
+    };
+
     TxExecInfo {
         r#type,
         validate_call_info: exec_info.validate_call_info.map(to_call_info),
@@ -583,6 +869,7 @@ pub fn to_exec_info(exec_info: TransactionExecutionInfo, r#type: TxType) -> TxEx
                 l1_gas: exec_info.transaction_receipt.gas.l1_data_gas,
                 l1_data_gas: exec_info.transaction_receipt.gas.l1_data_gas,
             },
+            state_diff_size,
         },
     }
 }
@@ -680,6 +967,129 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn sierra_gas_accounting() {
+        let mut fee =
+            TxFeeInfo { gas_consumed: 30, gas_price: 2, overall_fee: 60, unit: PriceUnit::Wei };
+
+        let mut trace = TxExecInfo::default();
+        // 4000 steps are worth 10 units of L1 gas
+        trace.actual_resources.vm_resources.n_steps = 4000;
+        // 800k units of Sierra gas are worth 20 units of L1 gas
+        trace.execute_call_info =
+            Some(trace::CallInfo { gas_consumed: 800_000, ..Default::default() });
+
+        apply_sierra_gas_accounting(&mut fee, &trace);
+
+        assert_eq!(fee.gas_consumed, 40);
+        assert_eq!(fee.overall_fee, 80);
+    }
+
+    #[test]
+    fn fee_settlement_requires_the_remaining_fee() {
+        use blockifier::abi::abi_utils::get_fee_token_var_address;
+        use blockifier::test_utils::dict_state_reader::DictStateReader;
+        use katana_primitives::address;
+
+        let block_env = BlockEnv { sequencer_address: address!("0x3"), ..Default::default() };
+        let mut cfg_env = CfgEnv::default();
+        cfg_env.fee_token_addresses.eth = address!("0x1");
+        let block_context = block_context_from_envs(&block_env, &cfg_env);
+
+        let sender = address!("0x2");
+        let mut state = cached_state::CachedState::new(DictStateReader::default());
+        let key = get_fee_token_var_address(to_blk_address(sender));
+        state.set_storage_at(to_blk_address(address!("0x1")), key, Felt::TWO).unwrap();
+
+        let fee = TxFeeInfo { gas_consumed: 7, gas_price: 1, overall_fee: 7, unit: PriceUnit::Wei };
+
+        // nothing to settle
+        assert!(matches!(settle_fee(&mut state, &block_context, sender, &fee, 7), Ok(None)));
+
+        let Err(ExecutionError::FeeSettlementInsufficientBalance {
+            remaining_fee,
+            balance_low,
+            balance_high,
+        }) = settle_fee(&mut state, &block_context, sender, &fee, 2)
+        else {
+            panic!("the remaining fee exceeds the balance of the sender");
+        };
+        assert_eq!(remaining_fee, 5);
+        assert_eq!((balance_low, balance_high), (Felt::TWO, Felt::ZERO));
+    }
+
+    #[test]
+    fn fee_settlement_events_follow_the_fee_transfer_ones() {
+        let event = |order| event::OrderedEvent { order, keys: vec![], data: vec![] };
+
+        let mut trace = TxExecInfo::default();
+        trace.fee_transfer_call_info = Some(trace::CallInfo {
+            events: vec![event(0)],
+            inner_calls: vec![trace::CallInfo { events: vec![event(1)], ..Default::default() }],
+            ..Default::default()
+        });
+
+        let settlement = trace::CallInfo {
+            events: vec![event(0)],
+            inner_calls: vec![trace::CallInfo { events: vec![event(1)], ..Default::default() }],
+            ..Default::default()
+        };
+        attach_fee_settlement(&mut trace, settlement);
+
+        let fee_transfer = trace.fee_transfer_call_info.as_ref().unwrap();
+        let settlement = &fee_transfer.inner_calls[1];
+        assert_eq!(settlement.events[0].order, 2);
+        assert_eq!(settlement.inner_calls[0].events[0].order, 3);
+    }
+
+    #[test]
+    fn l2_gas_bound() {
+        let mut trace = TxExecInfo::default();
+        trace.validate_call_info =
+            Some(trace::CallInfo { gas_consumed: 300, ..Default::default() });
+        trace.execute_call_info = Some(trace::CallInfo { gas_consumed: 700, ..Default::default() });
+
+        assert!(check_l2_gas_bound(None, &trace).is_ok());
+        // a zero bound isn't enforced
+        assert!(check_l2_gas_bound(Some(0), &trace).is_ok());
+        assert!(check_l2_gas_bound(Some(1000), &trace).is_ok());
+        assert!(matches!(
+            check_l2_gas_bound(Some(999), &trace),
+            Err(ExecutionError::L2GasBoundExceeded { max_amount: 999, gas_consumed: 1000 })
+        ));
+    }
+
+    #[test]
+    fn tx_limits() {
+        let tx = InvokeTx::V1(katana_primitives::transaction::InvokeTxV1 {
+            calldata: vec![Felt::ONE; 10],
+            signature: vec![Felt::ONE; 2],
+            ..Default::default()
+        });
+        let tx = ExecutableTxWithHash::new(tx.into());
+
+        assert!(check_tx_limits(&tx, &TxLimits::default()).is_ok());
+
+        let limits = TxLimits {
+            max_calldata_length: Some(10),
+            max_signature_length: Some(2),
+            max_bytecode_size: Some(0),
+        };
+        assert!(check_tx_limits(&tx, &limits).is_ok());
+
+        let limits = TxLimits { max_calldata_length: Some(9), ..Default::default() };
+        assert!(matches!(
+            check_tx_limits(&tx, &limits),
+            Err(ExecutionError::CalldataTooLong { length: 10, max: 9 })
+        ));
+
+        let limits = TxLimits { max_signature_length: Some(1), ..Default::default() };
+        assert!(matches!(
+            check_tx_limits(&tx, &limits),
+            Err(ExecutionError::SignatureTooLong { length: 2, max: 1 })
+        ));
+    }
+
     #[test]
     fn convert_chain_id() {
         let katana_mainnet = katana_primitives::chain::ChainId::MAINNET;
@@ -3,6 +3,7 @@ use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash, Flat
 use katana_primitives::contract::{ContractAddress, Nonce, StorageKey, StorageValue};
 use katana_primitives::env::{BlockEnv, CfgEnv};
 use katana_primitives::fee::TxFeeInfo;
+use katana_primitives::trace::CallInfo;
 use katana_primitives::transaction::{ExecutableTxWithHash, TxWithHash};
 use katana_primitives::Felt;
 use katana_provider::traits::contract::ContractClassProvider;
@@ -90,6 +91,11 @@ impl ExecutorExt for NoopExecutor {
         let _ = call;
         Ok(vec![])
     }
+
+    fn trace_call(&self, call: EntryPointCall) -> Result<CallInfo, ExecutionError> {
+        let _ = call;
+        Ok(CallInfo::default())
+    }
 }
 
 impl<'a> BlockExecutor<'a> for NoopExecutor {
@@ -103,36 +103,46 @@ pub fn l2_to_l1_messages_from_exec_info(info: &TxExecInfo) -> Vec<MessageToL1> {
     messages
 }
 
+/// Returns the events emitted by the call and its inner calls, in the order they were emitted.
 fn get_events_recur(info: &CallInfo) -> Vec<Event> {
-    let mut events: Vec<Event> = vec![];
+    let mut events = vec![];
+    collect_events_recur(info, &mut events);
+    events.sort_by_key(|(order, _)| *order);
+    events.into_iter().map(|(_, event)| event).collect()
+}
 
-    events.extend(info.events.iter().map(|e| Event {
-        from_address: info.contract_address,
-        data: e.data.clone(),
-        keys: e.keys.clone(),
+fn collect_events_recur(info: &CallInfo, events: &mut Vec<(u64, Event)>) {
+    events.extend(info.events.iter().map(|e| {
+        let event = Event {
+            from_address: info.contract_address,
+            data: e.data.clone(),
+            keys: e.keys.clone(),
+        };
+        (e.order, event)
     }));
 
-    info.inner_calls.iter().for_each(|call| {
-        events.extend(get_events_recur(call));
-    });
-
-    events
+    info.inner_calls.iter().for_each(|call| collect_events_recur(call, events));
 }
 
+/// Returns the messages sent by the call and its inner calls, in the order they were sent.
 fn get_l2_to_l1_messages_recur(info: &CallInfo) -> Vec<MessageToL1> {
     let mut messages = vec![];
+    collect_l2_to_l1_messages_recur(info, &mut messages);
+    messages.sort_by_key(|(order, _)| *order);
+    messages.into_iter().map(|(_, message)| message).collect()
+}
 
-    messages.extend(info.l2_to_l1_messages.iter().map(|m| MessageToL1 {
-        from_address: m.from_address,
-        to_address: m.to_address,
-        payload: m.payload.clone(),
+fn collect_l2_to_l1_messages_recur(info: &CallInfo, messages: &mut Vec<(u64, MessageToL1)>) {
+    messages.extend(info.l2_to_l1_messages.iter().map(|m| {
+        let message = MessageToL1 {
+            from_address: m.from_address,
+            to_address: m.to_address,
+            payload: m.payload.clone(),
+        };
+        (m.order, message)
     }));
 
-    info.inner_calls.iter().for_each(|call| {
-        messages.extend(get_l2_to_l1_messages_recur(call));
-    });
-
-    messages
+    info.inner_calls.iter().for_each(|call| collect_l2_to_l1_messages_recur(call, messages));
 }
 
 #[cfg(test)]
@@ -151,7 +161,7 @@ mod tests {
                 OrderedEvent { order: 4, data: vec![2u8.into()], keys: vec![20u8.into()] },
             ],
             l2_to_l1_messages: vec![OrderedL2ToL1Message {
-                order: 0,
+                order: 2,
                 from_address: felt!("0x111").into(),
                 to_address: felt!("0x200"),
                 payload: vec![1u8.into()],
@@ -238,4 +248,21 @@ mod tests {
 
         similar_asserts::assert_eq!(events, expected_messages)
     }
+
+    #[test]
+    fn events_and_messages_in_execution_order() {
+        let mut info = call_info();
+        // emitted by the outer call after the inner call returned
+        info.events[0].order = 5;
+        info.l2_to_l1_messages[0].order = 3;
+
+        let events = super::get_events_recur(&info);
+        let keys = events.iter().map(|e| e.keys[0]).collect::<Vec<_>>();
+        assert_eq!(keys, vec![10u8.into(), 20u8.into(), 2u8.into()]);
+
+        let messages = super::get_l2_to_l1_messages_recur(&info);
+        let to = messages.iter().map(|m| m.to_address).collect::<Vec<_>>();
+        assert_eq!(to, vec![felt!("0x201"), felt!("0x200"), felt!("0x200")]);
+        assert_eq!(messages[1].from_address, info.inner_calls[0].contract_address);
+    }
 }
@@ -56,7 +56,11 @@ fn test_executor_with_valid_blocks_impl<EF: ExecutorFactory>(
 
     // ensure that all transactions succeeded, if not panic with the error message and tx index
     let has_failed = transactions.iter().enumerate().find_map(|(i, (_, res))| {
-        if let ExecutionResult::Failed { error } = res { Some((i, error)) } else { None }
+        if let ExecutionResult::Failed { error } = res {
+            Some((i, error))
+        } else {
+            None
+        }
     });
 
     if let Some((pos, error)) = has_failed {
@@ -322,10 +326,102 @@ fn test_executor_with_valid_blocks_impl<EF: ExecutorFactory>(
 #[cfg(feature = "blockifier")]
 mod blockifier {
     use fixtures::blockifier::factory;
+    use fixtures::{cfg, chain, flags};
     use katana_executor::implementation::blockifier::BlockifierFactory;
+    use katana_executor::ExecutionFlags;
+    use katana_primitives::block::{
+        Block, FinalityStatus, GasPrices, PartialHeader, SealedBlockWithStatus,
+    };
+    use katana_primitives::chain::ChainId;
+    use katana_primitives::chain_spec::ChainSpec;
+    use katana_primitives::da::L1DataAvailabilityMode;
+    use katana_primitives::env::CfgEnv;
+    use katana_primitives::transaction::{
+        ExecutableTx, ExecutableTxWithHash, InvokeTx, InvokeTxV1,
+    };
+    use katana_primitives::version::CURRENT_STARKNET_VERSION;
+    use katana_provider::providers::db::DbProvider;
+    use katana_provider::traits::block::BlockWriter;
+    use katana_provider::traits::state::StateFactoryProvider;
+    use starknet::macros::selector;
 
     use super::*;
 
+    const SEQUENCER: ContractAddress = ContractAddress(Felt::ONE);
+
+    /// Returns the genesis state, with a sequencer balance whose low part overflows once it
+    /// collects a fee.
+    fn state_with_sequencer_balance(chain: &ChainSpec) -> Box<dyn StateProvider> {
+        let mut states = chain.state_updates();
+        let balance = get_storage_var_address("ERC20_balances", &[SEQUENCER.into()]).unwrap();
+        states
+            .state_updates
+            .storage_updates
+            .entry(DEFAULT_ETH_FEE_TOKEN_ADDRESS)
+            .or_default()
+            .insert(balance, Felt::from(u128::MAX - 1000));
+
+        let provider = DbProvider::new_ephemeral();
+        let block = SealedBlockWithStatus {
+            status: FinalityStatus::AcceptedOnL2,
+            block: Block::default().seal_with_hash(123u64.into()),
+        };
+        provider.insert_block_with_states_and_receipts(block, states, vec![], vec![]).unwrap();
+        provider.latest().unwrap()
+    }
+
+    fn transfer(sender: ContractAddress, nonce: u64, recipient: Felt) -> ExecutableTxWithHash {
+        ExecutableTxWithHash::new(ExecutableTx::Invoke(InvokeTx::V1(InvokeTxV1 {
+            chain_id: ChainId::parse("KATANA").unwrap(),
+            sender_address: sender,
+            calldata: vec![
+                Felt::ONE,
+                DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+                selector!("transfer"),
+                felt!("0x3"),
+                recipient,
+                felt!("0x99"),
+                Felt::ZERO,
+            ],
+            max_fee: 4367000000000000,
+            signature: vec![],
+            nonce: nonce.into(),
+        })))
+    }
+
+    fn execute(factory: BlockifierFactory, chain: &ChainSpec) -> ExecutionOutput {
+        let gas_prices = GasPrices { eth: 100 * u128::pow(10, 9), strk: 100 * u128::pow(10, 9) };
+        let accounts = chain.genesis.accounts().map(|(a, _)| *a).collect::<Vec<_>>();
+        let (a, b, c) = (accounts[0], accounts[1], accounts[2]);
+
+        let block = ExecutableBlock {
+            header: PartialHeader {
+                protocol_version: CURRENT_STARKNET_VERSION,
+                number: 1,
+                timestamp: 100,
+                sequencer_address: SEQUENCER,
+                parent_hash: 123u64.into(),
+                l1_gas_prices: gas_prices.clone(),
+                l1_data_gas_prices: gas_prices,
+                l1_da_mode: L1DataAvailabilityMode::Calldata,
+            },
+            body: vec![
+                transfer(a, 0, felt!("0xabc")),
+                // conflicts on the nonce and balance of `a`
+                transfer(a, 1, felt!("0xabc")),
+                // conflicts on the balance of `a`
+                transfer(b, 0, a.into()),
+                // accesses the balance of the sequencer during its execution
+                transfer(c, 0, SEQUENCER.into()),
+                transfer(b, 1, felt!("0xdef")),
+            ],
+        };
+
+        let mut executor = factory.with_state(state_with_sequencer_balance(chain));
+        executor.execute_block(block).unwrap();
+        executor.take_execution_output().unwrap()
+    }
+
     #[rstest::rstest]
     fn test_executor_with_valid_blocks(
         factory: BlockifierFactory,
@@ -334,4 +430,46 @@ mod blockifier {
     ) {
         test_executor_with_valid_blocks_impl(factory, state, blocks)
     }
+
+    #[rstest::rstest]
+    fn test_concurrent_executor_with_valid_blocks(
+        factory: BlockifierFactory,
+        #[from(state_provider)] state: Box<dyn StateProvider>,
+        #[from(valid_blocks)] blocks: [ExecutableBlock; 3],
+    ) {
+        test_executor_with_valid_blocks_impl(factory.with_workers(4), state, blocks)
+    }
+
+    #[rstest::rstest]
+    fn test_concurrent_executor_with_conflicting_txs(
+        chain: &ChainSpec,
+        cfg: CfgEnv,
+        #[with(true)] flags: ExecutionFlags,
+    ) {
+        let factory = BlockifierFactory::new(cfg.clone(), flags.clone());
+        let sequential = execute(factory, chain);
+
+        let factory = BlockifierFactory::new(cfg, flags).with_workers(4);
+        let concurrent = execute(factory, chain);
+
+        let receipts = |output: &ExecutionOutput| {
+            output.transactions.iter().map(|(_, res)| res.receipt().cloned()).collect::<Vec<_>>()
+        };
+
+        let receipts_of_sequential = receipts(&sequential);
+        assert!(receipts_of_sequential
+            .iter()
+            .all(|r| r.as_ref().is_some_and(|r| !r.is_reverted())));
+        similar_asserts::assert_eq!(receipts(&concurrent), receipts_of_sequential);
+        similar_asserts::assert_eq!(
+            concurrent.states.state_updates,
+            sequential.states.state_updates
+        );
+
+        // the fees carried over to the high part of the sequencer balance
+        let balance = get_storage_var_address("ERC20_balances", &[SEQUENCER.into()]).unwrap();
+        let high = balance + Felt::ONE;
+        let storage = &concurrent.states.state_updates.storage_updates;
+        assert_eq!(storage[&DEFAULT_ETH_FEE_TOKEN_ADDRESS].get(&high), Some(&Felt::ONE));
+    }
 }
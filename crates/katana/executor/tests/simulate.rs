@@ -1,9 +1,9 @@
 mod fixtures;
 
 use fixtures::transaction::executable_tx;
-use fixtures::{executor_factory, state_provider};
+use fixtures::{executor_factory, state_provider, valid_blocks};
 use katana_executor::{ExecutionFlags, ExecutionOutput, ExecutorFactory};
-use katana_primitives::block::GasPrices;
+use katana_primitives::block::{ExecutableBlock, GasPrices};
 use katana_primitives::env::BlockEnv;
 use katana_primitives::fee::PriceUnit;
 use katana_primitives::transaction::ExecutableTxWithHash;
@@ -95,4 +95,29 @@ mod blockifier {
     ) {
         test_simulate_tx_impl(executor_factory, block_env, state_provider, tx, flags);
     }
+
+    #[rstest::rstest]
+    fn test_estimate_fee_of_dependent_txs(
+        factory: BlockifierFactory,
+        block_env: BlockEnv,
+        #[from(state_provider)] state: Box<dyn StateProvider>,
+        #[from(state_provider)] other_state: Box<dyn StateProvider>,
+        #[from(valid_blocks)] blocks: [ExecutableBlock; 3],
+    ) {
+        // the transactions of the fixture aren't signed
+        let flags = ExecutionFlags::new().with_account_validation(false).with_nonce_check(false);
+
+        // the account deployed by the second transaction pays for its deployment with the funds
+        // sent by the first one
+        let fund_tx = blocks[0].body[0].clone();
+        let deploy_account_tx = blocks[1].body[0].clone();
+
+        let executor = factory.with_state_and_block_env(state, block_env.clone());
+        let fees = executor.estimate_fee(vec![fund_tx, deploy_account_tx.clone()], flags.clone());
+        assert!(fees.iter().all(|fee| fee.is_ok()), "all txs should be estimated: {fees:?}");
+
+        let executor = factory.with_state_and_block_env(other_state, block_env);
+        let fees = executor.estimate_fee(vec![deploy_account_tx], flags);
+        assert!(fees[0].is_err(), "the account can't pay for its deployment without the funds");
+    }
 }
@@ -1 +1,21 @@
 //! gRPC implementations.
+//!
+//! The Starknet service mirrors the read methods of the Starknet JSON-RPC API, along with
+//! streaming variants to follow the blocks and the events of the chain, for the clients which
+//! are bottlenecked by the JSON serialization.
+
+#[cfg(feature = "server")]
+pub mod server;
+
+pub mod proto {
+    pub mod starknet {
+        tonic::include_proto!("starknet");
+
+        #[cfg(feature = "server")]
+        pub(crate) const FILE_DESCRIPTOR_SET: &[u8] =
+            tonic::include_file_descriptor_set!("starknet_descriptor");
+    }
+    pub mod types {
+        tonic::include_proto!("types");
+    }
+}
@@ -0,0 +1,646 @@
+//! Conversions between the types of the Starknet JSON-RPC API and their protobuf counterparts.
+
+use katana_primitives::block::{BlockIdOrTag, BlockTag};
+use katana_primitives::Felt;
+use starknet::core::types::{
+    BlockStatus, CompressedLegacyContractClass, DataAvailabilityMode, DeclareTransaction,
+    DeployAccountTransaction, ExecutionResources, ExecutionResult, FlattenedSierraClass,
+    InvokeTransaction, L1DataAvailabilityMode, LegacyContractEntryPoint, PriceUnit, ReceiptBlock,
+    ResourceBounds, ResourceBoundsMapping, ResourcePrice, SierraEntryPoint, StateDiff, Transaction,
+    TransactionFinalityStatus, TransactionReceipt, TransactionWithReceipt,
+};
+use tonic::Status;
+
+use crate::proto::types as proto;
+use crate::proto::types::transaction::Transaction as ProtoTx;
+
+impl From<Felt> for proto::Felt {
+    fn from(value: Felt) -> Self {
+        Self { value: value.to_bytes_be().to_vec() }
+    }
+}
+
+/// Parses a required field holding a felt.
+pub fn felt(value: Option<proto::Felt>, field: &str) -> Result<Felt, Status> {
+    let value = value.ok_or_else(|| Status::invalid_argument(format!("missing `{field}`")))?;
+    felt_from_bytes(&value.value, field)
+}
+
+/// Parses a felt from its big-endian representation.
+pub fn felt_from_bytes(bytes: &[u8], field: &str) -> Result<Felt, Status> {
+    if bytes.len() > 32 {
+        return Err(Status::invalid_argument(format!("`{field}` is longer than 32 bytes")));
+    }
+    Ok(Felt::from_bytes_be_slice(bytes))
+}
+
+/// Parses a required block id.
+pub fn block_id(value: Option<proto::BlockId>) -> Result<BlockIdOrTag, Status> {
+    use proto::block_id::Identifier;
+
+    let missing = || Status::invalid_argument("missing `block_id`");
+    match value.and_then(|v| v.identifier).ok_or_else(missing)? {
+        Identifier::Number(number) => Ok(BlockIdOrTag::Number(number)),
+        Identifier::Hash(hash) => Ok(BlockIdOrTag::Hash(felt_from_bytes(&hash.value, "hash")?)),
+        Identifier::Tag(tag) => match tag.as_str() {
+            "latest" => Ok(BlockIdOrTag::Tag(BlockTag::Latest)),
+            "pending" => Ok(BlockIdOrTag::Tag(BlockTag::Pending)),
+            tag => Err(Status::invalid_argument(format!("unknown block tag `{tag}`"))),
+        },
+    }
+}
+
+/// Parses the keys of an event filter, `None` if any key is accepted.
+pub fn keys_filter(keys: Vec<proto::KeysFilter>) -> Result<Option<Vec<Vec<Felt>>>, Status> {
+    if keys.is_empty() {
+        return Ok(None);
+    }
+
+    let keys = keys.into_iter().map(|k| {
+        k.keys.iter().map(|key| felt_from_bytes(&key.value, "keys")).collect::<Result<_, _>>()
+    });
+    Ok(Some(keys.collect::<Result<_, _>>()?))
+}
+
+pub fn felts(values: Vec<Felt>) -> Vec<proto::Felt> {
+    values.into_iter().map(proto::Felt::from).collect()
+}
+
+fn some(value: Felt) -> Option<proto::Felt> {
+    Some(value.into())
+}
+
+fn version(version: u8) -> String {
+    format!("{version:#x}")
+}
+
+pub fn block_status(status: BlockStatus) -> String {
+    match status {
+        BlockStatus::Pending => "PENDING",
+        BlockStatus::AcceptedOnL2 => "ACCEPTED_ON_L2",
+        BlockStatus::AcceptedOnL1 => "ACCEPTED_ON_L1",
+        BlockStatus::Rejected => "REJECTED",
+    }
+    .to_string()
+}
+
+fn l1_da_mode(mode: L1DataAvailabilityMode) -> String {
+    match mode {
+        L1DataAvailabilityMode::Blob => "BLOB",
+        L1DataAvailabilityMode::Calldata => "CALLDATA",
+    }
+    .to_string()
+}
+
+fn da_mode(mode: DataAvailabilityMode) -> String {
+    match mode {
+        DataAvailabilityMode::L1 => "L1",
+        DataAvailabilityMode::L2 => "L2",
+    }
+    .to_string()
+}
+
+pub fn finality_status(status: TransactionFinalityStatus) -> String {
+    match status {
+        TransactionFinalityStatus::AcceptedOnL2 => "ACCEPTED_ON_L2",
+        TransactionFinalityStatus::AcceptedOnL1 => "ACCEPTED_ON_L1",
+    }
+    .to_string()
+}
+
+fn resource_price(price: ResourcePrice) -> Option<proto::ResourcePrice> {
+    Some(proto::ResourcePrice {
+        price_in_wei: some(price.price_in_wei),
+        price_in_fri: some(price.price_in_fri),
+    })
+}
+
+fn resource_bounds(bounds: ResourceBoundsMapping) -> Option<proto::ResourceBoundsMapping> {
+    let convert = |b: ResourceBounds| proto::ResourceBounds {
+        max_amount: some(b.max_amount.into()),
+        max_price_per_unit: some(b.max_price_per_unit.into()),
+    };
+
+    Some(proto::ResourceBoundsMapping {
+        l1_gas: Some(convert(bounds.l1_gas)),
+        l2_gas: Some(convert(bounds.l2_gas)),
+    })
+}
+
+/// The header of a block, the hash, the number and the root being unknown for the pending block.
+#[allow(clippy::too_many_arguments)]
+pub fn header(
+    block_hash: Option<Felt>,
+    parent_hash: Felt,
+    block_number: u64,
+    new_root: Option<Felt>,
+    timestamp: u64,
+    sequencer_address: Felt,
+    l1_gas_price: ResourcePrice,
+    l1_data_gas_price: ResourcePrice,
+    l1_da_mode: L1DataAvailabilityMode,
+    starknet_version: String,
+) -> Option<proto::BlockHeader> {
+    Some(proto::BlockHeader {
+        block_hash: block_hash.map(Into::into),
+        parent_hash: some(parent_hash),
+        block_number,
+        new_root: new_root.map(Into::into),
+        timestamp,
+        sequencer_address: some(sequencer_address),
+        l1_gas_price: resource_price(l1_gas_price),
+        l1_data_gas_price: resource_price(l1_data_gas_price),
+        l1_da_mode: self::l1_da_mode(l1_da_mode),
+        starknet_version,
+    })
+}
+
+/// Converts a block with its receipts.
+pub fn block_with_receipts(
+    block: starknet::core::types::BlockWithReceipts,
+) -> proto::BlockWithReceipts {
+    proto::BlockWithReceipts {
+        status: block_status(block.status),
+        header: header(
+            Some(block.block_hash),
+            block.parent_hash,
+            block.block_number,
+            Some(block.new_root),
+            block.timestamp,
+            block.sequencer_address,
+            block.l1_gas_price,
+            block.l1_data_gas_price,
+            block.l1_da_mode,
+            block.starknet_version,
+        ),
+        transactions: transactions_with_receipts(block.transactions),
+    }
+}
+
+pub fn transactions_with_receipts(
+    transactions: Vec<TransactionWithReceipt>,
+) -> Vec<proto::TransactionWithReceipt> {
+    transactions
+        .into_iter()
+        .map(|t| proto::TransactionWithReceipt {
+            transaction: Some(t.transaction.into()),
+            receipt: Some(receipt(t.receipt, None)),
+        })
+        .collect()
+}
+
+impl From<Transaction> for proto::Transaction {
+    fn from(tx: Transaction) -> Self {
+        let transaction_hash = some(*tx.transaction_hash());
+
+        let transaction = match tx {
+            Transaction::Invoke(InvokeTransaction::V0(tx)) => {
+                ProtoTx::InvokeV0(proto::InvokeTxnV0 {
+                    max_fee: some(tx.max_fee),
+                    version: version(0),
+                    signature: felts(tx.signature),
+                    r#type: "INVOKE".to_string(),
+                    contract_address: some(tx.contract_address),
+                    entry_point_selector: some(tx.entry_point_selector),
+                    calldata: felts(tx.calldata),
+                })
+            }
+            Transaction::Invoke(InvokeTransaction::V1(tx)) => {
+                ProtoTx::InvokeV1(proto::InvokeTxnV1 {
+                    max_fee: some(tx.max_fee),
+                    version: version(1),
+                    signature: felts(tx.signature),
+                    nonce: some(tx.nonce),
+                    r#type: "INVOKE".to_string(),
+                    sender_address: some(tx.sender_address),
+                    calldata: felts(tx.calldata),
+                })
+            }
+            Transaction::Invoke(InvokeTransaction::V3(tx)) => {
+                ProtoTx::InvokeV3(proto::InvokeTxnV3 {
+                    r#type: "INVOKE".to_string(),
+                    sender_address: some(tx.sender_address),
+                    calldata: felts(tx.calldata),
+                    version: version(3),
+                    signature: felts(tx.signature),
+                    nonce: some(tx.nonce),
+                    resource_bounds: resource_bounds(tx.resource_bounds),
+                    tip: some(tx.tip.into()),
+                    paymaster_data: felts(tx.paymaster_data),
+                    account_deployment_data: felts(tx.account_deployment_data),
+                    nonce_data_availability_mode: da_mode(tx.nonce_data_availability_mode),
+                    fee_data_availability_mode: da_mode(tx.fee_data_availability_mode),
+                })
+            }
+            Transaction::L1Handler(tx) => ProtoTx::L1Handler(proto::L1HandlerTxn {
+                version: format!("{:#x}", tx.version),
+                r#type: "L1_HANDLER".to_string(),
+                nonce: tx.nonce,
+                contract_address: some(tx.contract_address),
+                entry_point_selector: some(tx.entry_point_selector),
+                calldata: felts(tx.calldata),
+            }),
+            Transaction::Declare(DeclareTransaction::V0(tx)) => {
+                ProtoTx::DeclareV0(proto::DeclareTxnV0 {
+                    max_fee: some(tx.max_fee),
+                    version: version(0),
+                    signature: felts(tx.signature),
+                    r#type: "DECLARE".to_string(),
+                    class_hash: some(tx.class_hash),
+                    sender_address: some(tx.sender_address),
+                })
+            }
+            Transaction::Declare(DeclareTransaction::V1(tx)) => {
+                ProtoTx::DeclareV1(proto::DeclareTxnV1 {
+                    max_fee: some(tx.max_fee),
+                    version: version(1),
+                    signature: felts(tx.signature),
+                    nonce: some(tx.nonce),
+                    r#type: "DECLARE".to_string(),
+                    class_hash: some(tx.class_hash),
+                    sender_address: some(tx.sender_address),
+                })
+            }
+            Transaction::Declare(DeclareTransaction::V2(tx)) => {
+                ProtoTx::DeclareV2(proto::DeclareTxnV2 {
+                    r#type: "DECLARE".to_string(),
+                    sender_address: some(tx.sender_address),
+                    compiled_class_hash: some(tx.compiled_class_hash),
+                    max_fee: some(tx.max_fee),
+                    version: version(2),
+                    signature: felts(tx.signature),
+                    nonce: some(tx.nonce),
+                    class: Vec::new(),
+                    class_hash: some(tx.class_hash),
+                })
+            }
+            Transaction::Declare(DeclareTransaction::V3(tx)) => {
+                ProtoTx::DeclareV3(proto::DeclareTxnV3 {
+                    r#type: "DECLARE".to_string(),
+                    sender_address: some(tx.sender_address),
+                    compiled_class_hash: some(tx.compiled_class_hash),
+                    version: version(3),
+                    signature: felts(tx.signature),
+                    nonce: some(tx.nonce),
+                    class_hash: some(tx.class_hash),
+                    resource_bounds: resource_bounds(tx.resource_bounds),
+                    tip: some(tx.tip.into()),
+                    paymaster_data: felts(tx.paymaster_data),
+                    account_deployment_data: felts(tx.account_deployment_data),
+                    nonce_data_availability_mode: da_mode(tx.nonce_data_availability_mode),
+                    fee_data_availability_mode: da_mode(tx.fee_data_availability_mode),
+                })
+            }
+            Transaction::Deploy(tx) => ProtoTx::Deploy(proto::DeployTxn {
+                version: format!("{:#x}", tx.version),
+                r#type: "DEPLOY".to_string(),
+                class_hash: some(tx.class_hash),
+                contract_address_salt: some(tx.contract_address_salt),
+                constructor_calldata: felts(tx.constructor_calldata),
+            }),
+            Transaction::DeployAccount(DeployAccountTransaction::V1(tx)) => {
+                ProtoTx::DeployAccount(proto::DeployAccountTxn {
+                    max_fee: some(tx.max_fee),
+                    version: version(1),
+                    signature: felts(tx.signature),
+                    nonce: some(tx.nonce),
+                    r#type: "DEPLOY_ACCOUNT".to_string(),
+                    class_hash: some(tx.class_hash),
+                    contract_address_salt: some(tx.contract_address_salt),
+                    constructor_calldata: felts(tx.constructor_calldata),
+                })
+            }
+            Transaction::DeployAccount(DeployAccountTransaction::V3(tx)) => {
+                ProtoTx::DeployAccountV3(proto::DeployAccountTxnV3 {
+                    r#type: "DEPLOY_ACCOUNT".to_string(),
+                    version: version(3),
+                    signature: felts(tx.signature),
+                    nonce: some(tx.nonce),
+                    contract_address_salt: some(tx.contract_address_salt),
+                    constructor_calldata: felts(tx.constructor_calldata),
+                    class_hash: some(tx.class_hash),
+                    resource_bounds: resource_bounds(tx.resource_bounds),
+                    tip: some(tx.tip.into()),
+                    paymaster_data: felts(tx.paymaster_data),
+                    nonce_data_availability_mode: da_mode(tx.nonce_data_availability_mode),
+                    fee_data_availability_mode: da_mode(tx.fee_data_availability_mode),
+                })
+            }
+        };
+
+        Self { transaction: Some(transaction), transaction_hash }
+    }
+}
+
+/// Converts a receipt, along with the block of its transaction if known.
+pub fn receipt(
+    receipt: TransactionReceipt,
+    block: Option<ReceiptBlock>,
+) -> proto::TransactionReceipt {
+    macro_rules! common {
+        ($r:ident) => {
+            (
+                $r.transaction_hash,
+                $r.actual_fee,
+                $r.finality_status,
+                $r.messages_sent,
+                $r.events,
+                $r.execution_resources,
+                $r.execution_result,
+            )
+        };
+    }
+
+    let (r#type, contract_address, message_hash, common) = match receipt {
+        TransactionReceipt::Invoke(r) => ("INVOKE", None, Vec::new(), common!(r)),
+        TransactionReceipt::L1Handler(r) => {
+            ("L1_HANDLER", None, r.message_hash.as_bytes().to_vec(), common!(r))
+        }
+        TransactionReceipt::Declare(r) => ("DECLARE", None, Vec::new(), common!(r)),
+        TransactionReceipt::Deploy(r) => {
+            ("DEPLOY", Some(r.contract_address), Vec::new(), common!(r))
+        }
+        TransactionReceipt::DeployAccount(r) => {
+            ("DEPLOY_ACCOUNT", Some(r.contract_address), Vec::new(), common!(r))
+        }
+    };
+
+    let (transaction_hash, fee, finality, messages, events, resources, result) = common;
+
+    let (execution_status, revert_reason) = match result {
+        ExecutionResult::Succeeded => ("SUCCEEDED", String::new()),
+        ExecutionResult::Reverted { reason } => ("REVERTED", reason),
+    };
+
+    let (block_hash, block_number) = match block {
+        Some(ReceiptBlock::Block { block_hash, block_number }) => {
+            (some(block_hash), Some(block_number))
+        }
+        _ => (None, None),
+    };
+
+    proto::TransactionReceipt {
+        r#type: r#type.to_string(),
+        transaction_hash: some(transaction_hash),
+        actual_fee: Some(proto::FeePayment {
+            amount: some(fee.amount),
+            unit: match fee.unit {
+                PriceUnit::Wei => "WEI",
+                PriceUnit::Fri => "FRI",
+            }
+            .to_string(),
+        }),
+        finality_status: finality_status(finality),
+        messages_sent: messages
+            .into_iter()
+            .map(|m| proto::MessageToL1 {
+                from_address: some(m.from_address),
+                to_address: some(m.to_address),
+                payload: felts(m.payload),
+            })
+            .collect(),
+        events: events
+            .into_iter()
+            .map(|e| proto::Event {
+                from_address: some(e.from_address),
+                keys: felts(e.keys),
+                data: felts(e.data),
+            })
+            .collect(),
+        execution_resources: Some(execution_resources(resources)),
+        execution_status: execution_status.to_string(),
+        revert_reason,
+        block_hash,
+        block_number,
+        contract_address: contract_address.map(Into::into),
+        message_hash,
+    }
+}
+
+fn execution_resources(resources: ExecutionResources) -> proto::ExecutionResources {
+    let c = resources.computation_resources;
+    let da = resources.data_resources.data_availability;
+
+    proto::ExecutionResources {
+        steps: c.steps,
+        memory_holes: c.memory_holes.unwrap_or_default(),
+        range_check_builtin_applications: c.range_check_builtin_applications.unwrap_or_default(),
+        pedersen_builtin_applications: c.pedersen_builtin_applications.unwrap_or_default(),
+        poseidon_builtin_applications: c.poseidon_builtin_applications.unwrap_or_default(),
+        ec_op_builtin_applications: c.ec_op_builtin_applications.unwrap_or_default(),
+        ecdsa_builtin_applications: c.ecdsa_builtin_applications.unwrap_or_default(),
+        bitwise_builtin_applications: c.bitwise_builtin_applications.unwrap_or_default(),
+        keccak_builtin_applications: c.keccak_builtin_applications.unwrap_or_default(),
+        segment_arena_builtin: c.segment_arena_builtin.unwrap_or_default(),
+        data_availability: Some(proto::DataAvailability {
+            l1_gas: da.l1_gas,
+            l1_data_gas: da.l1_data_gas,
+        }),
+    }
+}
+
+pub fn state_diff(diff: StateDiff) -> Option<proto::StateDiff> {
+    Some(proto::StateDiff {
+        storage_diffs: diff
+            .storage_diffs
+            .into_iter()
+            .map(|d| proto::StorageDiff {
+                address: some(d.address),
+                storage_entries: d
+                    .storage_entries
+                    .into_iter()
+                    .map(|e| proto::StorageEntry { key: some(e.key), value: some(e.value) })
+                    .collect(),
+            })
+            .collect(),
+        deprecated_declared_classes: felts(diff.deprecated_declared_classes),
+        declared_classes: diff
+            .declared_classes
+            .into_iter()
+            .map(|c| proto::DeclaredClass {
+                class_hash: some(c.class_hash),
+                compiled_class_hash: some(c.compiled_class_hash),
+            })
+            .collect(),
+        deployed_contracts: diff
+            .deployed_contracts
+            .into_iter()
+            .map(|c| proto::DeployedContract {
+                address: some(c.address),
+                class_hash: some(c.class_hash),
+            })
+            .collect(),
+        replaced_classes: diff
+            .replaced_classes
+            .into_iter()
+            .map(|c| proto::ReplacedClass {
+                contract_address: some(c.contract_address),
+                class_hash: some(c.class_hash),
+            })
+            .collect(),
+        nonces: diff
+            .nonces
+            .into_iter()
+            .map(|n| proto::Nonce {
+                contract_address: some(n.contract_address),
+                nonce: some(n.nonce),
+            })
+            .collect(),
+    })
+}
+
+pub fn emitted_event(event: starknet::core::types::EmittedEvent) -> proto::EmittedEvent {
+    proto::EmittedEvent {
+        event: Some(proto::Event {
+            from_address: some(event.from_address),
+            keys: felts(event.keys),
+            data: felts(event.data),
+        }),
+        block_hash: event.block_hash.map(Into::into),
+        block_number: event.block_number.unwrap_or_default(),
+        transaction_hash: some(event.transaction_hash),
+    }
+}
+
+/// Converts a Sierra class.
+pub fn sierra_class(class: FlattenedSierraClass) -> proto::ContractClass {
+    let entry_points = |entry_points: Vec<SierraEntryPoint>| {
+        entry_points
+            .into_iter()
+            .map(|e| proto::SierraEntryPoint {
+                selector: some(e.selector),
+                function_idx: e.function_idx,
+            })
+            .collect()
+    };
+
+    let entry_points_by_type = class.entry_points_by_type;
+    proto::ContractClass {
+        sierra_program: felts(class.sierra_program),
+        contract_class_version: class.contract_class_version,
+        entry_points_by_type: Some(proto::EntryPointsByType {
+            constructor: entry_points(entry_points_by_type.constructor),
+            external: entry_points(entry_points_by_type.external),
+            l1_handler: entry_points(entry_points_by_type.l1_handler),
+        }),
+        abi: class.abi,
+    }
+}
+
+/// Converts a legacy class. Its compressed program and its ABI are encoded as in the JSON-RPC
+/// API, the program in base64 and the ABI in JSON.
+pub fn legacy_class(
+    class: CompressedLegacyContractClass,
+) -> Result<proto::DeprecatedContractClass, Status> {
+    let entry_points = |entry_points: &[LegacyContractEntryPoint]| {
+        entry_points
+            .iter()
+            .map(|e| proto::DeprecatedCairoEntryPoint {
+                offset: format!("{:#x}", e.offset),
+                selector: some(e.selector),
+            })
+            .collect()
+    };
+
+    let json = serde_json::to_value(&class).map_err(|e| Status::internal(e.to_string()))?;
+    let program = json["program"].as_str().unwrap_or_default().to_string();
+    let abi = if json["abi"].is_null() { String::new() } else { json["abi"].to_string() };
+
+    let entry_points_by_type = &class.entry_points_by_type;
+    Ok(proto::DeprecatedContractClass {
+        program,
+        entry_points_by_type: Some(proto::DeprecatedEntryPointsByType {
+            constructor: entry_points(&entry_points_by_type.constructor),
+            external: entry_points(&entry_points_by_type.external),
+            l1_handler: entry_points(&entry_points_by_type.l1_handler),
+        }),
+        abi,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::core::types::{InvokeTransactionV1, L1HandlerTransaction};
+    use starknet::macros::felt;
+
+    use super::*;
+
+    #[test]
+    fn felt_roundtrip() {
+        let value = felt!("0x1234567890abcdef");
+        let proto = proto::Felt::from(value);
+        assert_eq!(proto.value.len(), 32);
+        assert_eq!(super::felt(Some(proto), "value").unwrap(), value);
+
+        assert!(super::felt(None, "value").is_err());
+        assert!(felt_from_bytes(&[1; 33], "value").is_err());
+        assert_eq!(felt_from_bytes(&[1, 2], "value").unwrap(), Felt::from(0x0102));
+    }
+
+    #[test]
+    fn parse_block_id() {
+        use proto::block_id::Identifier;
+
+        let id = |identifier| Some(proto::BlockId { identifier: Some(identifier) });
+
+        assert_eq!(block_id(id(Identifier::Number(5))).unwrap(), BlockIdOrTag::Number(5));
+        assert_eq!(
+            block_id(id(Identifier::Tag("pending".to_string()))).unwrap(),
+            BlockIdOrTag::Tag(BlockTag::Pending)
+        );
+        assert_eq!(
+            block_id(id(Identifier::Hash(Felt::ONE.into()))).unwrap(),
+            BlockIdOrTag::Hash(Felt::ONE)
+        );
+        assert!(block_id(id(Identifier::Tag("safe".to_string()))).is_err());
+        assert!(block_id(None).is_err());
+    }
+
+    #[test]
+    fn parse_keys_filter() {
+        assert_eq!(keys_filter(vec![]).unwrap(), None);
+
+        let keys = vec![
+            proto::KeysFilter { keys: vec![Felt::ONE.into(), Felt::TWO.into()] },
+            proto::KeysFilter { keys: vec![] },
+        ];
+        assert_eq!(keys_filter(keys).unwrap(), Some(vec![vec![Felt::ONE, Felt::TWO], vec![]]));
+    }
+
+    #[test]
+    fn transaction_hash_and_version() {
+        let tx = Transaction::Invoke(InvokeTransaction::V1(InvokeTransactionV1 {
+            transaction_hash: felt!("0xabc"),
+            sender_address: felt!("0x1"),
+            calldata: vec![felt!("0x2")],
+            max_fee: felt!("0x3"),
+            signature: vec![],
+            nonce: felt!("0x4"),
+        }));
+
+        let proto = proto::Transaction::from(tx);
+        assert_eq!(proto.transaction_hash, Some(felt!("0xabc").into()));
+
+        let Some(ProtoTx::InvokeV1(invoke)) = proto.transaction else {
+            panic!("expected an invoke v1 transaction");
+        };
+        assert_eq!(invoke.version, "0x1");
+        assert_eq!(invoke.r#type, "INVOKE");
+        assert_eq!(invoke.calldata, vec![felt!("0x2").into()]);
+
+        let tx = Transaction::L1Handler(L1HandlerTransaction {
+            transaction_hash: felt!("0xdef"),
+            version: Felt::ZERO,
+            nonce: 7,
+            contract_address: felt!("0x1"),
+            entry_point_selector: felt!("0x2"),
+            calldata: vec![],
+        });
+
+        let Some(ProtoTx::L1Handler(l1_handler)) = proto::Transaction::from(tx).transaction else {
+            panic!("expected an l1 handler transaction");
+        };
+        assert_eq!(l1_handler.version, "0x0");
+        assert_eq!(l1_handler.nonce, 7);
+    }
+}
@@ -0,0 +1,753 @@
+//! The gRPC server of the Starknet service.
+//!
+//! The methods are served by the same [`StarknetApi`] as the JSON-RPC server, hence they behave
+//! exactly like their JSON-RPC counterparts, only the encoding of the responses differs.
+
+mod convert;
+
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use futures::{Stream, StreamExt};
+use jsonrpsee::core::Error as RpcError;
+use jsonrpsee::types::error::CallError;
+use katana_core::backend::Backend;
+use katana_executor::ExecutorFactory;
+use katana_primitives::block::{BlockIdOrTag, BlockNumber};
+use katana_rpc::starknet::StarknetApi;
+use katana_rpc_api::starknet::StarknetApiServer;
+use katana_rpc_types::block::{
+    MaybePendingBlockWithReceipts, MaybePendingBlockWithTxHashes, MaybePendingBlockWithTxs,
+};
+use katana_rpc_types::event::EventFilterWithPage;
+use katana_rpc_types::state_update::MaybePendingStateUpdate;
+use starknet::core::types::{
+    ContractClass, EventFilter, ResultPageRequest, SyncingStatus, TransactionExecutionStatus,
+    TransactionStatus,
+};
+use tokio::net::TcpListener;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::{ReceiverStream, TcpListenerStream};
+use tonic::codec::CompressionEncoding;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+use tracing::{info, warn};
+
+use crate::proto::starknet::starknet_server::{Starknet, StarknetServer};
+use crate::proto::starknet::*;
+use crate::proto::types;
+
+const LOG_TARGET: &str = "grpc";
+
+/// Number of items buffered by a stream before waiting for the client to consume them.
+const STREAM_BUFFER_SIZE: usize = 128;
+/// Number of events fetched at once when streaming the events of a block.
+const EVENTS_CHUNK_SIZE: u64 = 1024;
+
+type ServiceResult<T> = Result<Response<T>, Status>;
+type SubscribeBlocksResponseStream =
+    Pin<Box<dyn Stream<Item = Result<SubscribeBlocksResponse, Status>> + Send>>;
+type SubscribeEventsResponseStream =
+    Pin<Box<dyn Stream<Item = Result<SubscribeEventsResponse, Status>> + Send>>;
+
+/// The Starknet gRPC service.
+#[allow(missing_debug_implementations)]
+pub struct StarknetService<EF: ExecutorFactory> {
+    api: StarknetApi<EF>,
+    backend: Arc<Backend<EF>>,
+}
+
+impl<EF: ExecutorFactory> Clone for StarknetService<EF> {
+    fn clone(&self) -> Self {
+        Self { api: self.api.clone(), backend: self.backend.clone() }
+    }
+}
+
+impl<EF: ExecutorFactory> StarknetService<EF> {
+    pub fn new(api: StarknetApi<EF>, backend: Arc<Backend<EF>>) -> Self {
+        Self { api, backend }
+    }
+
+    /// Sends the blocks from `next` up to `last` included, returning the next block to send, or
+    /// `None` if the client is gone. Nothing is sent if `last` is below `next`.
+    async fn send_blocks(
+        &self,
+        next: BlockNumber,
+        last: BlockNumber,
+        tx: &mpsc::Sender<Result<SubscribeBlocksResponse, Status>>,
+    ) -> Option<BlockNumber> {
+        for number in next..=last {
+            let block = self.api.get_block_with_receipts(BlockIdOrTag::Number(number)).await;
+            let item = match block {
+                Ok(MaybePendingBlockWithReceipts::Block(block)) => {
+                    let block = convert::block_with_receipts((*block).clone());
+                    Ok(SubscribeBlocksResponse { block: Some(block) })
+                }
+                Ok(MaybePendingBlockWithReceipts::Pending(_)) => continue,
+                Err(error) => Err(status(error)),
+            };
+
+            tx.send(item).await.ok()?;
+        }
+
+        // the blocks mined while catching up are notified after having already been sent
+        Some(next.max(last + 1))
+    }
+
+    /// Sends the events matching the filter of the blocks from `next` up to `last` included,
+    /// returning the next block to send, or `None` if the client is gone. Nothing is sent if
+    /// `last` is below `next`.
+    async fn send_events(
+        &self,
+        next: BlockNumber,
+        last: BlockNumber,
+        filter: &EventFilter,
+        tx: &mpsc::Sender<Result<SubscribeEventsResponse, Status>>,
+    ) -> Option<BlockNumber> {
+        for number in next..=last {
+            let mut continuation_token = None;
+
+            loop {
+                let filter = EventFilterWithPage {
+                    event_filter: EventFilter {
+                        from_block: Some(BlockIdOrTag::Number(number)),
+                        to_block: Some(BlockIdOrTag::Number(number)),
+                        ..filter.clone()
+                    },
+                    result_page_request: ResultPageRequest {
+                        continuation_token,
+                        chunk_size: EVENTS_CHUNK_SIZE,
+                    },
+                };
+
+                let page = match self.api.get_events(filter).await {
+                    Ok(page) => page,
+                    Err(error) => {
+                        tx.send(Err(status(error))).await.ok()?;
+                        break;
+                    }
+                };
+
+                for event in page.events {
+                    let event = convert::emitted_event(event.inner);
+                    tx.send(Ok(SubscribeEventsResponse { event: Some(event) })).await.ok()?;
+                }
+
+                match page.continuation_token {
+                    Some(token) => continuation_token = Some(token),
+                    None => break,
+                }
+            }
+        }
+
+        // the blocks mined while catching up are notified after having already been sent
+        Some(next.max(last + 1))
+    }
+
+    /// Returns the number of the latest block.
+    async fn latest_block(&self) -> Result<BlockNumber, Status> {
+        self.api.block_number().await.map_err(status)
+    }
+}
+
+/// Maps an error of the Starknet API to a gRPC status.
+fn status(error: RpcError) -> Status {
+    let RpcError::Call(error) = error else {
+        return Status::internal(error.to_string());
+    };
+
+    let error = match error {
+        CallError::Custom(error) => error,
+        CallError::InvalidParams(e) => return Status::invalid_argument(e.to_string()),
+        CallError::Failed(e) => return Status::internal(e.to_string()),
+    };
+
+    let message = match error.data() {
+        Some(data) => format!("{}: {}", error.message(), data.get()),
+        None => error.message().to_string(),
+    };
+
+    match error.code() {
+        // contract, block, class and transaction not found, and no blocks
+        20 | 24 | 28 | 29 | 32 => Status::not_found(message),
+        // invalid transaction index, page size too big, invalid continuation token and too many
+        // keys in the filter
+        27 | 31 | 33 | 34 => Status::invalid_argument(message),
+        _ => Status::internal(message),
+    }
+}
+
+/// Checks that the block to start streaming from isn't in the future.
+fn check_from_block(from_block: Option<BlockNumber>, latest: BlockNumber) -> Result<(), Status> {
+    match from_block {
+        Some(from) if from > latest + 1 => Err(Status::invalid_argument(format!(
+            "`from_block` {from} is ahead of the latest block {latest}"
+        ))),
+        _ => Ok(()),
+    }
+}
+
+#[tonic::async_trait]
+impl<EF: ExecutorFactory> Starknet for StarknetService<EF> {
+    type SubscribeBlocksStream = SubscribeBlocksResponseStream;
+    type SubscribeEventsStream = SubscribeEventsResponseStream;
+
+    async fn spec_version(
+        &self,
+        _request: Request<SpecVersionRequest>,
+    ) -> ServiceResult<SpecVersionResponse> {
+        let version = self.api.spec_version().await.map_err(status)?;
+        Ok(Response::new(SpecVersionResponse { version }))
+    }
+
+    async fn get_block_with_tx_hashes(
+        &self,
+        request: Request<GetBlockRequest>,
+    ) -> ServiceResult<GetBlockWithTxHashesResponse> {
+        use get_block_with_tx_hashes_response::Result as BlockResult;
+
+        let block_id = convert::block_id(request.into_inner().block_id)?;
+        let block = self.api.get_block_with_tx_hashes(block_id).await.map_err(status)?;
+
+        let result = match block {
+            MaybePendingBlockWithTxHashes::Block(block) => {
+                BlockResult::Block(types::BlockWithTxHashes {
+                    status: convert::block_status(block.status),
+                    header: convert::header(
+                        Some(block.block_hash),
+                        block.parent_hash,
+                        block.block_number,
+                        Some(block.new_root),
+                        block.timestamp,
+                        block.sequencer_address,
+                        block.l1_gas_price.clone(),
+                        block.l1_data_gas_price.clone(),
+                        block.l1_da_mode,
+                        block.starknet_version.clone(),
+                    ),
+                    transactions: convert::felts(block.transactions.clone()),
+                })
+            }
+            MaybePendingBlockWithTxHashes::Pending(block) => {
+                BlockResult::PendingBlock(types::PendingBlockWithTxHashes {
+                    header: convert::header(
+                        None,
+                        block.parent_hash,
+                        0,
+                        None,
+                        block.timestamp,
+                        block.sequencer_address,
+                        block.l1_gas_price.clone(),
+                        block.l1_data_gas_price.clone(),
+                        block.l1_da_mode,
+                        block.starknet_version.clone(),
+                    ),
+                    transactions: convert::felts(block.transactions.clone()),
+                })
+            }
+        };
+
+        Ok(Response::new(GetBlockWithTxHashesResponse { result: Some(result) }))
+    }
+
+    async fn get_block_with_txs(
+        &self,
+        request: Request<GetBlockRequest>,
+    ) -> ServiceResult<GetBlockWithTxsResponse> {
+        use get_block_with_txs_response::Result as BlockResult;
+
+        let block_id = convert::block_id(request.into_inner().block_id)?;
+        let block = self.api.get_block_with_txs(block_id).await.map_err(status)?;
+
+        let result = match block {
+            MaybePendingBlockWithTxs::Block(block) => BlockResult::Block(types::BlockWithTxs {
+                status: convert::block_status(block.status),
+                header: convert::header(
+                    Some(block.block_hash),
+                    block.parent_hash,
+                    block.block_number,
+                    Some(block.new_root),
+                    block.timestamp,
+                    block.sequencer_address,
+                    block.l1_gas_price.clone(),
+                    block.l1_data_gas_price.clone(),
+                    block.l1_da_mode,
+                    block.starknet_version.clone(),
+                ),
+                transactions: block.transactions.iter().cloned().map(Into::into).collect(),
+            }),
+            MaybePendingBlockWithTxs::Pending(block) => {
+                BlockResult::PendingBlock(types::PendingBlockWithTxs {
+                    header: convert::header(
+                        None,
+                        block.parent_hash,
+                        0,
+                        None,
+                        block.timestamp,
+                        block.sequencer_address,
+                        block.l1_gas_price.clone(),
+                        block.l1_data_gas_price.clone(),
+                        block.l1_da_mode,
+                        block.starknet_version.clone(),
+                    ),
+                    transactions: block.transactions.iter().cloned().map(Into::into).collect(),
+                })
+            }
+        };
+
+        Ok(Response::new(GetBlockWithTxsResponse { result: Some(result) }))
+    }
+
+    async fn get_block_with_receipts(
+        &self,
+        request: Request<GetBlockRequest>,
+    ) -> ServiceResult<GetBlockWithReceiptsResponse> {
+        use get_block_with_receipts_response::Result as BlockResult;
+
+        let block_id = convert::block_id(request.into_inner().block_id)?;
+        let block = self.api.get_block_with_receipts(block_id).await.map_err(status)?;
+
+        let result = match block {
+            MaybePendingBlockWithReceipts::Block(block) => {
+                BlockResult::Block(convert::block_with_receipts((*block).clone()))
+            }
+            MaybePendingBlockWithReceipts::Pending(block) => {
+                BlockResult::PendingBlock(types::PendingBlockWithReceipts {
+                    header: convert::header(
+                        None,
+                        block.parent_hash,
+                        0,
+                        None,
+                        block.timestamp,
+                        block.sequencer_address,
+                        block.l1_gas_price.clone(),
+                        block.l1_data_gas_price.clone(),
+                        block.l1_da_mode,
+                        block.starknet_version.clone(),
+                    ),
+                    transactions: convert::transactions_with_receipts(block.transactions.clone()),
+                })
+            }
+        };
+
+        Ok(Response::new(GetBlockWithReceiptsResponse { result: Some(result) }))
+    }
+
+    async fn get_state_update(
+        &self,
+        request: Request<GetBlockRequest>,
+    ) -> ServiceResult<GetStateUpdateResponse> {
+        use get_state_update_response::Result as UpdateResult;
+
+        let block_id = convert::block_id(request.into_inner().block_id)?;
+        let update = self.api.get_state_update(block_id).await.map_err(status)?;
+
+        let result = match update {
+            MaybePendingStateUpdate::Update(update) => {
+                UpdateResult::StateUpdate(types::StateUpdate {
+                    block_hash: Some(update.block_hash.into()),
+                    old_root: Some(update.old_root.into()),
+                    new_root: Some(update.new_root.into()),
+                    state_diff: convert::state_diff(update.state_diff.clone()),
+                })
+            }
+            MaybePendingStateUpdate::Pending(update) => {
+                UpdateResult::PendingStateUpdate(types::PendingStateUpdate {
+                    old_root: Some(update.old_root.into()),
+                    state_diff: convert::state_diff(update.state_diff.clone()),
+                })
+            }
+        };
+
+        Ok(Response::new(GetStateUpdateResponse { result: Some(result) }))
+    }
+
+    async fn get_storage_at(
+        &self,
+        request: Request<GetStorageAtRequest>,
+    ) -> ServiceResult<GetStorageAtResponse> {
+        let request = request.into_inner();
+        let block_id = convert::block_id(request.block_id)?;
+        let address = convert::felt(request.contract_address, "contract_address")?;
+        let key = convert::felt(request.key, "key")?;
+
+        let value = self.api.get_storage_at(address, key, block_id).await.map_err(status)?;
+        Ok(Response::new(GetStorageAtResponse { value: Some((*value).into()) }))
+    }
+
+    async fn get_transaction_status(
+        &self,
+        request: Request<GetTransactionStatusRequest>,
+    ) -> ServiceResult<GetTransactionStatusResponse> {
+        let hash = convert::felt(request.into_inner().transaction_hash, "transaction_hash")?;
+        let tx_status = self.api.get_transaction_status(hash).await.map_err(status)?;
+
+        let (finality_status, execution_status) = match tx_status {
+            TransactionStatus::Received => ("RECEIVED", None),
+            TransactionStatus::Rejected => ("REJECTED", None),
+            TransactionStatus::AcceptedOnL2(s) => ("ACCEPTED_ON_L2", Some(s)),
+            TransactionStatus::AcceptedOnL1(s) => ("ACCEPTED_ON_L1", Some(s)),
+        };
+
+        let execution_status = match execution_status {
+            Some(TransactionExecutionStatus::Succeeded) => "SUCCEEDED",
+            Some(TransactionExecutionStatus::Reverted) => "REVERTED",
+            None => "",
+        };
+
+        Ok(Response::new(GetTransactionStatusResponse {
+            finality_status: finality_status.to_string(),
+            execution_status: execution_status.to_string(),
+        }))
+    }
+
+    async fn get_transaction_by_hash(
+        &self,
+        request: Request<GetTransactionByHashRequest>,
+    ) -> ServiceResult<GetTransactionByHashResponse> {
+        let hash = convert::felt(request.into_inner().transaction_hash, "transaction_hash")?;
+        let tx = self.api.get_transaction_by_hash(hash).await.map_err(status)?;
+        Ok(Response::new(GetTransactionByHashResponse { transaction: Some(tx.0.into()) }))
+    }
+
+    async fn get_transaction_by_block_id_and_index(
+        &self,
+        request: Request<GetTransactionByBlockIdAndIndexRequest>,
+    ) -> ServiceResult<GetTransactionByBlockIdAndIndexResponse> {
+        let request = request.into_inner();
+        let block_id = convert::block_id(request.block_id)?;
+
+        let tx = self
+            .api
+            .get_transaction_by_block_id_and_index(block_id, request.index)
+            .await
+            .map_err(status)?;
+
+        Ok(Response::new(GetTransactionByBlockIdAndIndexResponse {
+            transaction: Some(tx.0.into()),
+        }))
+    }
+
+    async fn get_transaction_receipt(
+        &self,
+        request: Request<GetTransactionReceiptRequest>,
+    ) -> ServiceResult<GetTransactionReceiptResponse> {
+        let hash = convert::felt(request.into_inner().transaction_hash, "transaction_hash")?;
+        let receipt = self.api.get_transaction_receipt(hash).await.map_err(status)?.0;
+        let receipt = convert::receipt(receipt.receipt, Some(receipt.block));
+        Ok(Response::new(GetTransactionReceiptResponse { receipt: Some(receipt) }))
+    }
+
+    async fn get_class(
+        &self,
+        request: Request<GetClassRequest>,
+    ) -> ServiceResult<GetClassResponse> {
+        use get_class_response::Result as ClassResult;
+
+        let request = request.into_inner();
+        let block_id = convert::block_id(request.block_id)?;
+        let class_hash = convert::felt(request.class_hash, "class_hash")?;
+
+        let result = match self.api.get_class(block_id, class_hash).await.map_err(status)? {
+            ContractClass::Sierra(class) => {
+                ClassResult::ContractClass(convert::sierra_class(class))
+            }
+            ContractClass::Legacy(class) => {
+                ClassResult::DeprecatedContractClass(convert::legacy_class(class)?)
+            }
+        };
+
+        Ok(Response::new(GetClassResponse { result: Some(result) }))
+    }
+
+    async fn get_class_hash_at(
+        &self,
+        request: Request<GetClassHashAtRequest>,
+    ) -> ServiceResult<GetClassHashAtResponse> {
+        let request = request.into_inner();
+        let block_id = convert::block_id(request.block_id)?;
+        let address = convert::felt(request.contract_address, "contract_address")?;
+
+        let class_hash = self.api.get_class_hash_at(block_id, address).await.map_err(status)?;
+        Ok(Response::new(GetClassHashAtResponse { class_hash: Some((*class_hash).into()) }))
+    }
+
+    async fn get_class_at(
+        &self,
+        request: Request<GetClassAtRequest>,
+    ) -> ServiceResult<GetClassAtResponse> {
+        use get_class_at_response::Result as ClassResult;
+
+        let request = request.into_inner();
+        let block_id = convert::block_id(request.block_id)?;
+        let address = convert::felt(request.contract_address, "contract_address")?;
+
+        let result = match self.api.get_class_at(block_id, address).await.map_err(status)? {
+            ContractClass::Sierra(class) => {
+                ClassResult::ContractClass(convert::sierra_class(class))
+            }
+            ContractClass::Legacy(class) => {
+                ClassResult::DeprecatedContractClass(convert::legacy_class(class)?)
+            }
+        };
+
+        Ok(Response::new(GetClassAtResponse { result: Some(result) }))
+    }
+
+    async fn get_block_transaction_count(
+        &self,
+        request: Request<GetBlockRequest>,
+    ) -> ServiceResult<GetBlockTransactionCountResponse> {
+        let block_id = convert::block_id(request.into_inner().block_id)?;
+        let count = self.api.get_block_transaction_count(block_id).await.map_err(status)?;
+        Ok(Response::new(GetBlockTransactionCountResponse { count }))
+    }
+
+    async fn call(&self, _request: Request<CallRequest>) -> ServiceResult<CallResponse> {
+        Err(Status::unimplemented("calls are only served by the JSON-RPC API"))
+    }
+
+    async fn estimate_fee(
+        &self,
+        _request: Request<EstimateFeeRequest>,
+    ) -> ServiceResult<EstimateFeeResponse> {
+        Err(Status::unimplemented("fee estimations are only served by the JSON-RPC API"))
+    }
+
+    async fn estimate_message_fee(
+        &self,
+        _request: Request<EstimateMessageFeeRequest>,
+    ) -> ServiceResult<EstimateFeeResponse> {
+        Err(Status::unimplemented("fee estimations are only served by the JSON-RPC API"))
+    }
+
+    async fn block_number(
+        &self,
+        _request: Request<BlockNumberRequest>,
+    ) -> ServiceResult<BlockNumberResponse> {
+        let block_number = self.latest_block().await?;
+        Ok(Response::new(BlockNumberResponse { block_number }))
+    }
+
+    async fn block_hash_and_number(
+        &self,
+        _request: Request<BlockHashAndNumberRequest>,
+    ) -> ServiceResult<BlockHashAndNumberResponse> {
+        let block = self.api.block_hash_and_number().await.map_err(status)?;
+        Ok(Response::new(BlockHashAndNumberResponse {
+            block_hash: Some(block.block_hash.into()),
+            block_number: block.block_number,
+        }))
+    }
+
+    async fn chain_id(&self, _request: Request<ChainIdRequest>) -> ServiceResult<ChainIdResponse> {
+        let chain_id = self.api.chain_id().await.map_err(status)?;
+        Ok(Response::new(ChainIdResponse { chain_id: format!("{:#x}", *chain_id) }))
+    }
+
+    async fn syncing(&self, _request: Request<SyncingRequest>) -> ServiceResult<SyncingResponse> {
+        use syncing_response::Result as SyncingResult;
+
+        let result = match self.api.syncing().await.map_err(status)? {
+            SyncingStatus::NotSyncing => SyncingResult::NotSyncing(true),
+            SyncingStatus::Syncing(s) => SyncingResult::Status(types::SyncStatus {
+                starting_block_hash: Some(s.starting_block_hash.into()),
+                starting_block_num: s.starting_block_num,
+                current_block_hash: Some(s.current_block_hash.into()),
+                current_block_num: s.current_block_num,
+                highest_block_hash: Some(s.highest_block_hash.into()),
+                highest_block_num: s.highest_block_num,
+            }),
+        };
+
+        Ok(Response::new(SyncingResponse { result: Some(result) }))
+    }
+
+    async fn get_events(
+        &self,
+        request: Request<GetEventsRequest>,
+    ) -> ServiceResult<GetEventsResponse> {
+        let request = request.into_inner();
+        let filter = request.filter.unwrap_or_default();
+
+        let block = |id: Option<types::BlockId>| id.map(|id| convert::block_id(Some(id)));
+        let event_filter = EventFilter {
+            from_block: block(filter.from_block).transpose()?,
+            to_block: block(filter.to_block).transpose()?,
+            address: filter.address.map(|a| convert::felt(Some(a), "address")).transpose()?,
+            keys: convert::keys_filter(filter.keys)?,
+        };
+
+        let continuation_token = Some(request.continuation_token).filter(|t| !t.is_empty());
+        let result_page_request =
+            ResultPageRequest { continuation_token, chunk_size: request.chunk_size.into() };
+
+        let page = self
+            .api
+            .get_events(EventFilterWithPage { event_filter, result_page_request })
+            .await
+            .map_err(status)?;
+
+        Ok(Response::new(GetEventsResponse {
+            events: page.events.into_iter().map(|e| convert::emitted_event(e.inner)).collect(),
+            continuation_token: page.continuation_token.unwrap_or_default(),
+        }))
+    }
+
+    async fn get_nonce(
+        &self,
+        request: Request<GetNonceRequest>,
+    ) -> ServiceResult<GetNonceResponse> {
+        let request = request.into_inner();
+        let block_id = convert::block_id(request.block_id)?;
+        let address = convert::felt(request.contract_address, "contract_address")?;
+
+        let nonce = self.api.get_nonce(block_id, address).await.map_err(status)?;
+        Ok(Response::new(GetNonceResponse { nonce: Some((*nonce).into()) }))
+    }
+
+    async fn subscribe_blocks(
+        &self,
+        request: Request<SubscribeBlocksRequest>,
+    ) -> ServiceResult<Self::SubscribeBlocksStream> {
+        let from_block = request.into_inner().from_block;
+        check_from_block(from_block, self.latest_block().await?)?;
+
+        // The listener is registered before catching up, so that no block is missed in between.
+        let mut blocks = self.backend.add_block_listener();
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER_SIZE);
+        let this = self.clone();
+
+        tokio::spawn(async move {
+            let mut next = from_block;
+
+            if let Some(from) = next {
+                let Ok(latest) = this.latest_block().await else { return };
+                let Some(n) = this.send_blocks(from, latest, &tx).await else { return };
+                next = Some(n);
+            }
+
+            while let Some(number) = blocks.next().await {
+                let from = next.unwrap_or(number);
+                let Some(n) = this.send_blocks(from, number, &tx).await else { return };
+                next = Some(n);
+            }
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+
+    async fn subscribe_events(
+        &self,
+        request: Request<SubscribeEventsRequest>,
+    ) -> ServiceResult<Self::SubscribeEventsStream> {
+        let request = request.into_inner();
+        check_from_block(request.from_block, self.latest_block().await?)?;
+
+        let filter = EventFilter {
+            from_block: None,
+            to_block: None,
+            address: request.address.map(|a| convert::felt(Some(a), "address")).transpose()?,
+            keys: convert::keys_filter(request.keys)?,
+        };
+
+        let mut blocks = self.backend.add_block_listener();
+        let (tx, rx) = mpsc::channel(STREAM_BUFFER_SIZE);
+        let this = self.clone();
+
+        tokio::spawn(async move {
+            let mut next = request.from_block;
+
+            if let Some(from) = next {
+                let Ok(latest) = this.latest_block().await else { return };
+                let Some(n) = this.send_events(from, latest, &filter, &tx).await else { return };
+                next = Some(n);
+            }
+
+            while let Some(number) = blocks.next().await {
+                let from = next.unwrap_or(number);
+                let Some(n) = this.send_events(from, number, &filter, &tx).await else { return };
+                next = Some(n);
+            }
+        });
+
+        Ok(Response::new(Box::pin(ReceiverStream::new(rx))))
+    }
+}
+
+/// Handle to a running gRPC server.
+#[derive(Debug)]
+pub struct GrpcServer {
+    pub addr: SocketAddr,
+}
+
+/// Binds the gRPC server to the given address, returning its handle along with the future
+/// serving the requests.
+pub async fn spawn<EF: ExecutorFactory>(
+    api: StarknetApi<EF>,
+    backend: Arc<Backend<EF>>,
+    addr: SocketAddr,
+) -> std::io::Result<(GrpcServer, impl std::future::Future<Output = ()> + Send + 'static)> {
+    let listener = TcpListener::bind(addr).await?;
+    let addr = listener.local_addr()?;
+
+    let reflection = tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(crate::proto::starknet::FILE_DESCRIPTOR_SET)
+        .build()
+        .expect("qed; the file descriptor set is valid");
+
+    let service = StarknetServer::new(StarknetService::new(api, backend))
+        .accept_compressed(CompressionEncoding::Gzip)
+        .send_compressed(CompressionEncoding::Gzip);
+
+    let server = Server::builder()
+        .add_service(reflection)
+        .add_service(service)
+        .serve_with_incoming(TcpListenerStream::new(listener));
+
+    let server = async move {
+        if let Err(error) = server.await {
+            warn!(target: LOG_TARGET, %error, "gRPC server stopped.");
+        }
+    };
+
+    info!(target: LOG_TARGET, %addr, "gRPC server started.");
+
+    Ok((GrpcServer { addr }, server))
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonrpsee::types::ErrorObject;
+    use katana_rpc_types::error::starknet::StarknetApiError;
+    use tonic::Code;
+
+    use super::*;
+
+    #[test]
+    fn errors_to_status() {
+        let code = |e: StarknetApiError| status(RpcError::from(e)).code();
+
+        assert_eq!(code(StarknetApiError::BlockNotFound), Code::NotFound);
+        assert_eq!(code(StarknetApiError::TxnHashNotFound), Code::NotFound);
+        assert_eq!(code(StarknetApiError::PageSizeTooBig), Code::InvalidArgument);
+        assert_eq!(
+            code(StarknetApiError::UnexpectedError { reason: "boom".to_string() }),
+            Code::Internal
+        );
+
+        let error = ErrorObject::owned(24, "Block not found", None::<()>);
+        let status = status(RpcError::Call(CallError::Custom(error)));
+        assert_eq!(status.message(), "Block not found");
+    }
+
+    #[test]
+    fn from_block_ahead_of_the_chain() {
+        assert!(check_from_block(None, 0).is_ok());
+        assert!(check_from_block(Some(0), 0).is_ok());
+        // the next block to be mined
+        assert!(check_from_block(Some(6), 5).is_ok());
+        assert!(check_from_block(Some(7), 5).is_err());
+    }
+}
@@ -177,6 +177,7 @@ pub struct Katana {
     block_time: Option<u64>,
     db_dir: Option<PathBuf>,
     l1_provider: Option<String>,
+    fork_provider: Option<String>,
     fork_block_number: Option<u64>,
     messaging: Option<PathBuf>,
 
@@ -289,6 +290,12 @@ impl Katana {
         self
     }
 
+    /// Sets the RPC URL of the network to fork when the `katana` instance is launched.
+    pub fn fork_provider<T: Into<String>>(mut self, rpc_url: T) -> Self {
+        self.fork_provider = Some(rpc_url.into());
+        self
+    }
+
     /// Sets the fork block number which will be used when the `katana` instance is launched.
     pub const fn fork_block_number(mut self, fork_block_number: u64) -> Self {
         self.fork_block_number = Some(fork_block_number);
@@ -493,8 +500,12 @@ impl Katana {
             cmd.args(["--log.format", "json"]);
         }
 
+        if let Some(url) = self.fork_provider {
+            cmd.arg("--fork.provider").arg(url);
+        }
+
         if let Some(fork_block_number) = self.fork_block_number {
-            cmd.args(["--fork", "--fork.block"]).arg(fork_block_number.to_string());
+            cmd.arg("--fork.block").arg(fork_block_number.to_string());
         }
 
         if let Some(messaging) = self.messaging {
@@ -5,4 +5,16 @@ use std::path::PathBuf;
 pub struct DbConfig {
     /// The path to the database directory.
     pub dir: Option<PathBuf>,
+    /// Pruning options.
+    pub prune: PruneConfig,
+}
+
+/// Database pruning configurations.
+#[derive(Debug, Clone, Default)]
+pub struct PruneConfig {
+    /// The number of most recent blocks whose state history, receipts and traces are kept.
+    ///
+    /// The history of the older blocks is pruned in the background, while their headers and
+    /// transactions are kept. The full history is kept if `None`.
+    pub history: Option<u64>,
 }
@@ -1,3 +1,4 @@
+use katana_core::backend::gas_oracle::GasPricing;
 use katana_core::constants::{
     DEFAULT_ETH_L1_DATA_GAS_PRICE, DEFAULT_ETH_L1_GAS_PRICE, DEFAULT_STRK_L1_DATA_GAS_PRICE,
     DEFAULT_STRK_L1_GAS_PRICE,
@@ -31,6 +32,9 @@ pub struct DevConfig {
     ///
     /// These are the prices that will be used for calculating the gas fee for transactions.
     pub fixed_gas_prices: Option<FixedL1GasPriceConfig>,
+
+    /// How the L1 gas prices change from block to block, starting from the fixed prices.
+    pub gas_pricing: GasPricing,
 }
 
 /// Fixed gas prices for development.
@@ -54,6 +58,11 @@ impl std::default::Default for FixedL1GasPriceConfig {
 
 impl std::default::Default for DevConfig {
     fn default() -> Self {
-        Self { fee: true, account_validation: true, fixed_gas_prices: None }
+        Self {
+            fee: true,
+            account_validation: true,
+            fixed_gas_prices: None,
+            gas_pricing: GasPricing::Fixed,
+        }
     }
 }
@@ -1,3 +1,6 @@
+use katana_executor::GasAccounting;
+use katana_primitives::env::TxLimits;
+
 pub const MAX_RECURSION_DEPTH: usize = 1000;
 
 pub const DEFAULT_INVOCATION_MAX_STEPS: u32 = 10_000_000;
@@ -8,6 +11,11 @@ pub struct ExecutionConfig {
     pub invocation_max_steps: u32,
     pub validation_max_steps: u32,
     pub max_recursion_depth: usize,
+    /// The limits on the size of the transactions, unlimited by default.
+    pub tx_limits: TxLimits,
+    pub gas_accounting: GasAccounting,
+    /// Number of threads executing the transactions of a block. Sequential if lower than 2.
+    pub workers: usize,
 }
 
 impl std::default::Default for ExecutionConfig {
@@ -16,6 +24,9 @@ impl std::default::Default for ExecutionConfig {
             max_recursion_depth: MAX_RECURSION_DEPTH,
             invocation_max_steps: DEFAULT_INVOCATION_MAX_STEPS,
             validation_max_steps: DEFAULT_VALIDATION_MAX_STEPS,
+            tx_limits: TxLimits::default(),
+            gas_accounting: GasAccounting::default(),
+            workers: 1,
         }
     }
 }
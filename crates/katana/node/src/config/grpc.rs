@@ -0,0 +1,22 @@
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+
+/// gRPC server default address.
+pub const DEFAULT_GRPC_ADDR: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+/// gRPC server default port.
+pub const DEFAULT_GRPC_PORT: u16 = 50051;
+
+/// Node gRPC server configurations.
+#[derive(Debug, Copy, Clone)]
+pub struct GrpcConfig {
+    /// The address to bind the gRPC server to.
+    pub addr: IpAddr,
+    /// The port to bind the gRPC server to.
+    pub port: u16,
+}
+
+impl GrpcConfig {
+    /// Returns the [`SocketAddr`] for the gRPC server.
+    pub fn socket_addr(&self) -> SocketAddr {
+        SocketAddr::new(self.addr, self.port)
+    }
+}
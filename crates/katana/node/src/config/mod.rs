@@ -2,14 +2,20 @@ pub mod db;
 pub mod dev;
 pub mod execution;
 pub mod fork;
+pub mod grpc;
 pub mod metrics;
 pub mod rpc;
 
+use std::path::PathBuf;
+
 use db::DbConfig;
 use dev::DevConfig;
 use execution::ExecutionConfig;
 use fork::ForkingConfig;
+use grpc::GrpcConfig;
 use katana_core::service::messaging::MessagingConfig;
+use katana_core::service::scheduler::SchedulerConfig;
+use katana_pool::PoolConfig;
 use katana_primitives::chain_spec::ChainSpec;
 use metrics::MetricsConfig;
 use rpc::RpcConfig;
@@ -34,6 +40,11 @@ pub struct Config {
     /// Metrics options.
     pub metrics: Option<MetricsConfig>,
 
+    /// gRPC server options.
+    ///
+    /// The server is only started if the node is built with the `grpc` feature.
+    pub grpc: Option<GrpcConfig>,
+
     /// Execution options.
     pub execution: ExecutionConfig,
 
@@ -43,6 +54,9 @@ pub struct Config {
     /// Sequencing options.
     pub sequencing: SequencingConfig,
 
+    /// Transaction pool options.
+    pub pool: PoolConfig,
+
     /// Development options.
     pub dev: DevConfig,
 }
@@ -57,4 +71,17 @@ pub struct SequencingConfig {
     ///
     /// Allowing block to only be produced manually.
     pub no_mining: bool,
+
+    /// Path of an executable checking invariants after every block.
+    ///
+    /// Block production is halted when the executable exits with a non-zero status.
+    pub invariant_script: Option<PathBuf>,
+
+    /// Directory where the state is dumped when an invariant is violated.
+    ///
+    /// Defaults to the current directory.
+    pub invariant_dump_dir: Option<PathBuf>,
+
+    /// Calls submitted in transactions on a schedule, if any.
+    pub scheduler: Option<SchedulerConfig>,
 }
@@ -1,8 +1,13 @@
 use std::collections::HashSet;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::time::Duration;
 
 /// The default maximum number of concurrent RPC connections.
 pub const DEFAULT_RPC_MAX_CONNECTIONS: u32 = 100;
+/// The default timeout of an RPC request, in seconds.
+pub const DEFAULT_RPC_REQUEST_TIMEOUT: u64 = 20;
+/// The default maximum number of execution tasks waiting for a worker thread.
+pub const DEFAULT_RPC_MAX_QUEUED_TASKS: usize = 256;
 pub const DEFAULT_RPC_ADDR: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
 pub const DEFAULT_RPC_PORT: u16 = 5050;
 
@@ -12,6 +17,7 @@ pub const DEFAULT_RPC_PORT: u16 = 5050;
 )]
 pub enum ApiKind {
     Starknet,
+    Katana,
     Torii,
     Dev,
     Saya,
@@ -23,8 +29,32 @@ pub struct RpcConfig {
     pub addr: IpAddr,
     pub port: u16,
     pub max_connections: u32,
+    /// Maximum number of calls in a batch request. Batches are unlimited if `None`.
+    pub max_batch_size: Option<u32>,
+    /// Timeout of a request. A batch is a single request, so the timeout covers all its calls.
+    pub request_timeout: Duration,
     pub apis: HashSet<ApiKind>,
     pub cors_origins: Option<Vec<String>>,
+    /// Methods exposed by the server, out of those of the enabled APIs. All of them are exposed if
+    /// `None`.
+    ///
+    /// A pattern ending with `*` matches all the methods starting with what precedes it, eg
+    /// `dev_*`.
+    pub allowed_methods: Option<Vec<String>>,
+    /// Methods not exposed by the server, even if allowed. Same patterns as `allowed_methods`.
+    pub disabled_methods: Vec<String>,
+    /// Maximum number of requests per second from a single client, counted per connection unless
+    /// `trust_forwarded_for` is set. Unlimited if `None`.
+    pub max_requests_per_second: Option<u32>,
+    /// Whether the clients are identified by the `X-Forwarded-For` header for the rate limit. Only
+    /// to be set when the node is only reachable through a reverse proxy setting the header.
+    pub trust_forwarded_for: bool,
+    /// Number of threads running the calls, fee estimations and simulations. As many as there are
+    /// CPUs if `None`.
+    pub worker_threads: Option<usize>,
+    /// Maximum number of calls, fee estimations and simulations waiting for a worker thread. The
+    /// requests exceeding it are rejected until the queue drains.
+    pub max_queued_tasks: usize,
 }
 
 impl RpcConfig {
@@ -32,6 +62,18 @@ impl RpcConfig {
     pub fn socket_addr(&self) -> SocketAddr {
         SocketAddr::new(self.addr, self.port)
     }
+
+    /// Returns `true` if the method must be exposed by the server.
+    pub fn is_method_enabled(&self, method: &str) -> bool {
+        let matches = |pattern: &String| match pattern.strip_suffix('*') {
+            Some(prefix) => method.starts_with(prefix),
+            None => method == pattern,
+        };
+
+        let allowed =
+            self.allowed_methods.as_ref().map_or(true, |allowed| allowed.iter().any(matches));
+        allowed && !self.disabled_methods.iter().any(matches)
+    }
 }
 
 impl Default for RpcConfig {
@@ -41,7 +83,37 @@ impl Default for RpcConfig {
             addr: DEFAULT_RPC_ADDR,
             port: DEFAULT_RPC_PORT,
             max_connections: DEFAULT_RPC_MAX_CONNECTIONS,
+            max_batch_size: None,
+            request_timeout: Duration::from_secs(DEFAULT_RPC_REQUEST_TIMEOUT),
             apis: HashSet::from([ApiKind::Starknet]),
+            allowed_methods: None,
+            disabled_methods: Vec::new(),
+            max_requests_per_second: None,
+            trust_forwarded_for: false,
+            worker_threads: None,
+            max_queued_tasks: DEFAULT_RPC_MAX_QUEUED_TASKS,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::RpcConfig;
+
+    #[test]
+    fn method_filters() {
+        let config = RpcConfig::default();
+        assert!(config.is_method_enabled("dev_setNextBlockTimestamp"));
+
+        let config = RpcConfig {
+            allowed_methods: Some(vec!["starknet_*".into(), "dev_generateBlock".into()]),
+            disabled_methods: vec!["starknet_add*".into()],
+            ..Default::default()
+        };
+        assert!(config.is_method_enabled("starknet_getNonce"));
+        assert!(config.is_method_enabled("dev_generateBlock"));
+        assert!(!config.is_method_enabled("dev_setNextBlockTimestamp"));
+        assert!(!config.is_method_enabled("starknet_addInvokeTransaction"));
+        assert!(!config.is_method_enabled("katana_getBlocks"));
+    }
+}
@@ -2,19 +2,25 @@
 
 pub mod config;
 pub mod exit;
+pub mod middleware;
+pub mod multi;
 pub mod version;
 
 use std::future::IntoFuture;
 use std::net::SocketAddr;
 use std::sync::Arc;
-use std::time::Duration;
 
-use anyhow::Result;
+use anyhow::{bail, Result};
+use config::db::PruneConfig;
+use config::dev::DevConfig;
+use config::grpc::GrpcConfig;
 use config::metrics::MetricsConfig;
 use config::rpc::{ApiKind, RpcConfig};
 use config::{Config, SequencingConfig};
 use dojo_metrics::exporters::prometheus::PrometheusRecorder;
 use dojo_metrics::{Report, Server as MetricsServer};
+use futures::channel::mpsc::Receiver;
+use futures::StreamExt;
 use hyper::{Method, Uri};
 use jsonrpsee::server::middleware::proxy_get_request::ProxyGetRequestLayer;
 use jsonrpsee::server::{AllowHosts, ServerBuilder, ServerHandle};
@@ -28,29 +34,39 @@ use katana_core::constants::{
 };
 use katana_core::env::BlockContextGenerator;
 use katana_core::service::block_producer::BlockProducer;
+use katana_core::service::hooks::BlockBuildingHook;
+use katana_core::service::invariant::{Invariant, InvariantChecker, InvariantScript};
 use katana_core::service::messaging::MessagingConfig;
+use katana_core::service::scheduler::Scheduler;
 use katana_db::mdbx::DbEnv;
 use katana_executor::implementation::blockifier::BlockifierFactory;
 use katana_executor::{ExecutionFlags, ExecutorFactory};
 use katana_pipeline::{stage, Pipeline};
 use katana_pool::ordering::FiFo;
-use katana_pool::validation::stateful::TxValidator;
-use katana_pool::TxPool;
-use katana_primitives::block::GasPrices;
+use katana_pool::{NonceValidation, TxPool};
+use katana_primitives::block::{BlockNumber, GasPrices};
 use katana_primitives::env::{CfgEnv, FeeTokenAddressses};
+use katana_provider::providers::db::DbProvider;
 use katana_rpc::dev::DevApi;
+use katana_rpc::katana::KatanaApi;
 use katana_rpc::metrics::RpcServerMetrics;
 use katana_rpc::saya::SayaApi;
 use katana_rpc::starknet::forking::ForkedClient;
+use katana_rpc::starknet::worker::WorkerPool;
 use katana_rpc::starknet::StarknetApi;
 use katana_rpc::torii::ToriiApi;
 use katana_rpc_api::dev::DevApiServer;
+use katana_rpc_api::katana::KatanaApiServer;
 use katana_rpc_api::saya::SayaApiServer;
-use katana_rpc_api::starknet::{StarknetApiServer, StarknetTraceApiServer, StarknetWriteApiServer};
+use katana_rpc_api::starknet::{
+    StarknetApiServer, StarknetSubscriptionApiServer, StarknetTraceApiServer,
+    StarknetWriteApiServer,
+};
 use katana_rpc_api::torii::ToriiApiServer;
 use katana_tasks::TaskManager;
+use middleware::{BatchLimitLayer, RateLimitLayer, MAX_REQUEST_BODY_SIZE};
 use tower_http::cors::{AllowOrigin, CorsLayer};
-use tracing::info;
+use tracing::{error, info, warn};
 
 use crate::exit::NodeStoppedFuture;
 
@@ -60,6 +76,8 @@ pub struct LaunchedNode {
     pub node: Node,
     /// Handle to the rpc server.
     pub rpc: RpcServer,
+    /// The address of the gRPC server, if started.
+    pub grpc: Option<SocketAddr>,
 }
 
 impl LaunchedNode {
@@ -92,12 +110,47 @@ pub struct Node {
     pub block_producer: BlockProducer<BlockifierFactory>,
     pub rpc_config: RpcConfig,
     pub metrics_config: Option<MetricsConfig>,
+    pub grpc_config: Option<GrpcConfig>,
     pub sequencing_config: SequencingConfig,
     pub messaging_config: Option<MessagingConfig>,
+    pub prune_config: PruneConfig,
+    /// Invariants checked after every block, in addition to the configured invariant script.
+    pub invariants: Vec<Arc<dyn Invariant>>,
     forked_client: Option<ForkedClient>,
 }
 
 impl Node {
+    /// Adds a hook run around the execution of every transaction included in the blocks built by
+    /// the node.
+    pub fn with_block_building_hook(self, hook: impl BlockBuildingHook + 'static) -> Self {
+        self.backend.add_block_building_hook(Arc::new(hook));
+        self
+    }
+
+    /// Reloads the settings of the node that can be changed while it's running, with the
+    /// configuration returned by `load`, whenever its configuration is reloaded. See
+    /// [`reload_config`].
+    pub fn set_config_source<F>(&self, load: F)
+    where
+        F: Fn() -> Result<Config> + Send + Sync + 'static,
+    {
+        // The hook is owned by the backend, so it must not keep the node components alive.
+        let backend = Arc::downgrade(&self.backend);
+        let producer = Arc::downgrade(&self.block_producer.producer);
+
+        self.backend.config_reloader.set(move || {
+            let (Some(backend), Some(producer)) = (backend.upgrade(), producer.upgrade()) else {
+                bail!("the node has stopped");
+            };
+
+            let config = load()?;
+            reload_config(&backend, &BlockProducer { producer }, &config);
+
+            info!(target: "node", "Configuration reloaded.");
+            Ok(())
+        });
+    }
+
     /// Start the node.
     ///
     /// This method will start all the node process, running them until the node is stopped.
@@ -126,9 +179,45 @@ impl Node {
         let block_producer = self.block_producer.clone();
         let validator = self.block_producer.validator().clone();
 
+        // --- build the starknet api, shared by the rpc and the grpc servers
+
+        // the execution-heavy requests of all the apis share the same threads
+        let workers =
+            WorkerPool::new(self.rpc_config.worker_threads, self.rpc_config.max_queued_tasks);
+
+        let starknet_api =
+            if self.rpc_config.apis.contains(&ApiKind::Starknet) || self.grpc_config.is_some() {
+                let (pool, backend, block_producer, workers) =
+                    (pool.clone(), backend.clone(), block_producer.clone(), workers.clone());
+
+                Some(match self.forked_client.take() {
+                    Some(client) => StarknetApi::new_forked(
+                        backend,
+                        pool,
+                        block_producer,
+                        validator,
+                        workers,
+                        client,
+                    ),
+                    None => StarknetApi::new(backend, pool, block_producer, validator, workers),
+                })
+            } else {
+                None
+            };
+
+        // --- start the rpc server, whose address is given to the invariant script
+
+        let node_components =
+            (pool.clone(), backend.clone(), block_producer.clone(), starknet_api.clone(), workers);
+        let rpc = spawn(node_components, self.rpc_config.clone()).await?;
+
+        // --- start the grpc server
+
+        let grpc = self.spawn_grpc(starknet_api).await?;
+
         // --- build sequencing stage
 
-        let sequencing = stage::Sequencing::new(
+        let mut sequencing = stage::Sequencing::new(
             pool.clone(),
             backend.clone(),
             self.task_manager.task_spawner(),
@@ -136,6 +225,59 @@ impl Node {
             self.messaging_config.clone(),
         );
 
+        let mut invariants = self.invariants.clone();
+        if let Some(path) = &self.sequencing_config.invariant_script {
+            let script = InvariantScript::new(path, format!("http://{}", rpc.addr));
+            invariants.push(Arc::new(script));
+        }
+
+        if !invariants.is_empty() {
+            let dump_dir = self.sequencing_config.invariant_dump_dir.clone().unwrap_or_default();
+            sequencing = sequencing.with_invariants(InvariantChecker::new(
+                backend.clone(),
+                invariants,
+                dump_dir,
+            ));
+        }
+
+        // --- start submitting the scheduled calls
+
+        if let Some(config) = self.sequencing_config.scheduler.clone() {
+            let chain = &backend.chain_spec;
+            let scheduler = Scheduler::new(pool.clone(), chain.id, &chain.genesis, config)?;
+
+            self.task_manager
+                .task_spawner()
+                .build_task()
+                .name("Scheduler")
+                .spawn(scheduler.run(backend.add_block_listener()));
+
+            info!("Transaction scheduler started.");
+        }
+
+        // --- start sampling the L1 gas prices
+
+        if let Some(sampler) = backend.gas_oracle.sampler() {
+            self.task_manager.task_spawner().build_task().name("Gas price sampler").spawn(sampler);
+
+            info!("L1 gas price sampling started.");
+        }
+
+        // --- start pruning the database history
+
+        if let (Some(retained), Some(db)) = (self.prune_config.history, self.db.clone()) {
+            let blocks = backend.add_block_listener();
+            let provider = DbProvider::new(db);
+
+            self.task_manager
+                .task_spawner()
+                .build_task()
+                .name("History pruner")
+                .spawn(prune_history(provider, blocks, retained));
+
+            info!(%retained, "History pruning enabled.");
+        }
+
         // --- build and start the pipeline
 
         let mut pipeline = Pipeline::new();
@@ -148,10 +290,35 @@ impl Node {
             .name("Pipeline")
             .spawn(pipeline.into_future());
 
-        let node_components = (pool, backend, block_producer, validator, self.forked_client.take());
-        let rpc = spawn(node_components, self.rpc_config.clone()).await?;
+        Ok(LaunchedNode { node: self, rpc, grpc })
+    }
+
+    /// Starts the gRPC server if it's configured, returning its address.
+    #[cfg(feature = "grpc")]
+    async fn spawn_grpc(
+        &self,
+        starknet_api: Option<StarknetApi<BlockifierFactory>>,
+    ) -> Result<Option<SocketAddr>> {
+        let (Some(config), Some(api)) = (&self.grpc_config, starknet_api) else {
+            return Ok(None);
+        };
 
-        Ok(LaunchedNode { node: self, rpc })
+        let (server, serve) =
+            katana_grpc::server::spawn(api, self.backend.clone(), config.socket_addr()).await?;
+        self.task_manager.task_spawner().build_task().name("gRPC server").spawn(serve);
+
+        Ok(Some(server.addr))
+    }
+
+    #[cfg(not(feature = "grpc"))]
+    async fn spawn_grpc(
+        &self,
+        _starknet_api: Option<StarknetApi<BlockifierFactory>>,
+    ) -> Result<Option<SocketAddr>> {
+        if self.grpc_config.is_some() {
+            warn!(target: "node", "gRPC server not started, katana was built without the `grpc` feature.");
+        }
+        Ok(None)
     }
 }
 
@@ -173,6 +340,8 @@ pub async fn build(mut config: Config) -> Result<Node> {
         invoke_tx_max_n_steps: config.execution.invocation_max_steps,
         validate_max_n_steps: config.execution.validation_max_steps,
         max_recursion_depth: config.execution.max_recursion_depth,
+        disabled_syscalls: config.chain.disabled_syscalls.clone(),
+        tx_limits: config.execution.tx_limits,
         fee_token_addresses: FeeTokenAddressses {
             eth: config.chain.fee_contracts.eth,
             strk: config.chain.fee_contracts.strk,
@@ -181,9 +350,13 @@ pub async fn build(mut config: Config) -> Result<Node> {
 
     let execution_flags = ExecutionFlags::new()
         .with_account_validation(config.dev.account_validation)
-        .with_fee(config.dev.fee);
+        .with_fee(config.dev.fee)
+        .with_nonce_check(config.pool.nonce_validation != NonceValidation::Disabled)
+        .with_gas_accounting(config.execution.gas_accounting);
 
-    let executor_factory = Arc::new(BlockifierFactory::new(cfg_env, execution_flags));
+    let executor_factory = Arc::new(
+        BlockifierFactory::new(cfg_env, execution_flags).with_workers(config.execution.workers),
+    );
 
     // --- build backend
 
@@ -205,18 +378,8 @@ pub async fn build(mut config: Config) -> Result<Node> {
 
     // --- build l1 gas oracle
 
-    // Check if the user specify a fixed gas price in the dev config.
-    let gas_oracle = if let Some(fixed_prices) = config.dev.fixed_gas_prices {
-        L1GasOracle::fixed(fixed_prices.gas_price, fixed_prices.data_gas_price)
-    }
-    // TODO: for now we just use the default gas prices, but this should be a proper oracle in the
-    // future that can perform actual sampling.
-    else {
-        L1GasOracle::fixed(
-            GasPrices { eth: DEFAULT_ETH_L1_GAS_PRICE, strk: DEFAULT_STRK_L1_GAS_PRICE },
-            GasPrices { eth: DEFAULT_ETH_L1_DATA_GAS_PRICE, strk: DEFAULT_STRK_L1_DATA_GAS_PRICE },
-        )
-    };
+    let (gas_prices, data_gas_prices) = l1_gas_prices(&config.dev);
+    let gas_oracle = L1GasOracle::new(gas_prices, data_gas_prices, config.dev.gas_pricing.clone());
 
     let block_context_generator = BlockContextGenerator::default().into();
     let backend = Arc::new(Backend {
@@ -225,6 +388,11 @@ pub async fn build(mut config: Config) -> Result<Node> {
         executor_factory,
         block_context_generator,
         chain_spec: config.chain,
+        storage_diff_listeners: Default::default(),
+        block_listeners: Default::default(),
+        config_reloader: Default::default(),
+        l1_messages: Default::default(),
+        block_building_hooks: Default::default(),
     });
 
     // --- build block producer
@@ -242,7 +410,7 @@ pub async fn build(mut config: Config) -> Result<Node> {
     // --- build transaction pool
 
     let validator = block_producer.validator();
-    let pool = TxPool::new(validator.clone(), FiFo::new());
+    let pool = TxPool::new_with_config(validator.clone(), FiFo::new(), config.pool.clone());
 
     let node = Node {
         db,
@@ -252,52 +420,97 @@ pub async fn build(mut config: Config) -> Result<Node> {
         block_producer,
         rpc_config: config.rpc,
         metrics_config: config.metrics,
+        grpc_config: config.grpc,
         messaging_config: config.messaging,
         sequencing_config: config.sequencing,
+        prune_config: config.db.prune,
+        invariants: Vec::new(),
         task_manager: TaskManager::current(),
     };
 
     Ok(node)
 }
 
+/// Applies the settings of `config` that can be changed while the node is running: the block
+/// time of interval mining and the L1 gas prices. The other settings are ignored.
+///
+/// The block time can only be changed if the node was started with interval mining, switching the
+/// mining mode requires a restart.
+pub fn reload_config<EF: ExecutorFactory>(
+    backend: &Backend<EF>,
+    block_producer: &BlockProducer<EF>,
+    config: &Config,
+) {
+    let (gas_prices, data_gas_prices) = l1_gas_prices(&config.dev);
+    backend.gas_oracle.set_fixed(gas_prices, data_gas_prices);
+
+    if let Some(block_time) = config.sequencing.block_time {
+        if !block_producer.set_block_time(block_time) {
+            warn!(target: "node", "Block time can't be changed without restarting the node.");
+        }
+    }
+}
+
+/// Prunes the history of the database every time a block is mined, keeping only the state
+/// history, receipts and traces of the last `retained` blocks.
+async fn prune_history(provider: DbProvider, mut blocks: Receiver<BlockNumber>, retained: u64) {
+    while blocks.next().await.is_some() {
+        if let Err(error) = provider.prune_history(retained) {
+            error!(target: "node", %error, "Failed to prune the database history.");
+        }
+    }
+}
+
+/// Returns the L1 gas and data gas prices of the gas oracle.
+fn l1_gas_prices(config: &DevConfig) -> (GasPrices, GasPrices) {
+    // Check if the user specify a fixed gas price in the dev config.
+    if let Some(fixed_prices) = &config.fixed_gas_prices {
+        (fixed_prices.gas_price.clone(), fixed_prices.data_gas_price.clone())
+    }
+    // TODO: for now we just use the default gas prices, but this should be a proper oracle in the
+    // future that can perform actual sampling.
+    else {
+        (
+            GasPrices { eth: DEFAULT_ETH_L1_GAS_PRICE, strk: DEFAULT_STRK_L1_GAS_PRICE },
+            GasPrices { eth: DEFAULT_ETH_L1_DATA_GAS_PRICE, strk: DEFAULT_STRK_L1_DATA_GAS_PRICE },
+        )
+    }
+}
+
 // Moved from `katana_rpc` crate
 pub async fn spawn<EF: ExecutorFactory>(
     node_components: (
         TxPool,
         Arc<Backend<EF>>,
         BlockProducer<EF>,
-        TxValidator,
-        Option<ForkedClient>,
+        Option<StarknetApi<EF>>,
+        WorkerPool,
     ),
     config: RpcConfig,
 ) -> Result<RpcServer> {
-    let (pool, backend, block_producer, validator, forked_client) = node_components;
+    let (pool, backend, block_producer, starknet_api, workers) = node_components;
 
     let mut methods = RpcModule::new(());
     methods.register_method("health", |_, _| Ok(serde_json::json!({ "health": true })))?;
 
-    if config.apis.contains(&ApiKind::Starknet) {
-        let server = if let Some(client) = forked_client {
-            StarknetApi::new_forked(
-                backend.clone(),
-                pool.clone(),
-                block_producer.clone(),
-                validator,
-                client,
-            )
-        } else {
-            StarknetApi::new(backend.clone(), pool.clone(), block_producer.clone(), validator)
-        };
-
+    if let Some(server) = starknet_api.filter(|_| config.apis.contains(&ApiKind::Starknet)) {
         methods.merge(StarknetApiServer::into_rpc(server.clone()))?;
         methods.merge(StarknetWriteApiServer::into_rpc(server.clone()))?;
-        methods.merge(StarknetTraceApiServer::into_rpc(server))?;
+        methods.merge(StarknetTraceApiServer::into_rpc(server.clone()))?;
+        methods.merge(StarknetSubscriptionApiServer::into_rpc(server))?;
     }
 
     if config.apis.contains(&ApiKind::Dev) {
         methods.merge(DevApi::new(backend.clone(), block_producer.clone()).into_rpc())?;
     }
 
+    if config.apis.contains(&ApiKind::Katana) {
+        methods.merge(
+            KatanaApi::new(backend.clone(), pool.clone(), block_producer.clone(), workers)
+                .into_rpc(),
+        )?;
+    }
+
     if config.apis.contains(&ApiKind::Torii) {
         methods.merge(
             ToriiApi::new(backend.clone(), pool.clone(), block_producer.clone()).into_rpc(),
@@ -308,6 +521,15 @@ pub async fn spawn<EF: ExecutorFactory>(
         methods.merge(SayaApi::new(backend.clone(), block_producer.clone()).into_rpc())?;
     }
 
+    // the health check is used by the load balancers and is never filtered out
+    let filtered = methods
+        .method_names()
+        .filter(|name| *name != "health" && !config.is_method_enabled(name))
+        .collect::<Vec<_>>();
+    for name in filtered {
+        methods.remove_method(name);
+    }
+
     let cors = CorsLayer::new()
             // Allow `POST` when accessing the resource
             .allow_methods([Method::POST, Method::GET])
@@ -330,14 +552,21 @@ pub async fn spawn<EF: ExecutorFactory>(
 
     let middleware = tower::ServiceBuilder::new()
         .option_layer(cors)
+        .option_layer(
+            config
+                .max_requests_per_second
+                .map(|max| RateLimitLayer::new(max, config.trust_forwarded_for)),
+        )
         .layer(ProxyGetRequestLayer::new("/", "health")?)
-        .timeout(Duration::from_secs(20));
+        .timeout(config.request_timeout)
+        .option_layer(config.max_batch_size.map(BatchLimitLayer::new));
 
     let server = ServerBuilder::new()
         .set_logger(RpcServerMetrics::new(&methods))
         .set_host_filtering(AllowHosts::Any)
         .set_middleware(middleware)
         .max_connections(config.max_connections)
+        .max_request_body_size(MAX_REQUEST_BODY_SIZE)
         .build(config.socket_addr())
         .await?;
 
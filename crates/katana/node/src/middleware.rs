@@ -0,0 +1,484 @@
+//! Middlewares of the RPC server.
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::net::IpAddr;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use hyper::body::{Bytes, HttpBody};
+use hyper::header::{HeaderValue, CONTENT_LENGTH, CONTENT_TYPE};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use jsonrpsee::types::error::OVERSIZED_REQUEST_CODE;
+use serde::de::IgnoredAny;
+use tower::{Layer, Service};
+
+/// Maximum size of a request body, in bytes. Also the limit of the server, which only applies once
+/// the middlewares have let the request through.
+pub const MAX_REQUEST_BODY_SIZE: u32 = 10 * 1024 * 1024;
+
+/// Error code of a batch request exceeding the maximum batch size.
+pub const TOO_BIG_BATCH_REQUEST_CODE: i32 = -32010;
+
+/// Error code of a request rejected because its client exceeded the rate limit.
+pub const RATE_LIMITED_CODE: i32 = -32011;
+
+/// Layer rejecting the JSON-RPC batch requests with more calls than the maximum batch size.
+///
+/// The batch is rejected as a whole with a single error response, none of its calls is executed.
+/// The body is buffered to count the calls, so the requests larger than
+/// [`MAX_REQUEST_BODY_SIZE`] are rejected without being read entirely.
+#[derive(Debug, Clone, Copy)]
+pub struct BatchLimitLayer {
+    max_batch_size: usize,
+    max_body_size: usize,
+}
+
+impl BatchLimitLayer {
+    pub fn new(max_batch_size: u32) -> Self {
+        Self {
+            max_batch_size: max_batch_size as usize,
+            max_body_size: MAX_REQUEST_BODY_SIZE as usize,
+        }
+    }
+}
+
+impl<S> Layer<S> for BatchLimitLayer {
+    type Service = BatchLimit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        BatchLimit { inner, max_batch_size: self.max_batch_size, max_body_size: self.max_body_size }
+    }
+}
+
+/// Service created by [`BatchLimitLayer`].
+#[derive(Debug, Clone)]
+pub struct BatchLimit<S> {
+    inner: S,
+    max_batch_size: usize,
+    max_body_size: usize,
+}
+
+impl<S> Service<Request<Body>> for BatchLimit<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // The service that has been polled ready must be the one handling the request.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        // Only HTTP calls are sent in the request body, the websocket upgrades are `GET` requests.
+        if request.method() != Method::POST {
+            return Box::pin(inner.call(request));
+        }
+
+        let max_batch_size = self.max_batch_size;
+        let max_body_size = self.max_body_size;
+
+        Box::pin(async move {
+            let (parts, body) = request.into_parts();
+
+            let content_length = parts
+                .headers
+                .get(CONTENT_LENGTH)
+                .and_then(|value| value.to_str().ok()?.parse::<usize>().ok());
+            if content_length.is_some_and(|length| length > max_body_size) {
+                return Ok(too_big_request_response(max_body_size));
+            }
+
+            // Invalid bodies are left to the server to reject.
+            let body = match read_body(body, max_body_size).await {
+                Ok(Some(body)) => body,
+                Ok(None) => return Ok(too_big_request_response(max_body_size)),
+                Err(_) => return inner.call(Request::from_parts(parts, Body::empty())).await,
+            };
+
+            if let Some(size) = batch_size(&body) {
+                if size > max_batch_size {
+                    return Ok(too_big_batch_response(max_batch_size));
+                }
+            }
+
+            inner.call(Request::from_parts(parts, Body::from(body))).await
+        })
+    }
+}
+
+/// Reads the request body, or returns `None` as soon as it exceeds `limit` bytes.
+async fn read_body(mut body: Body, limit: usize) -> Result<Option<Bytes>, hyper::Error> {
+    let mut bytes = Vec::new();
+
+    while let Some(chunk) = body.data().await {
+        let chunk = chunk?;
+        if bytes.len() + chunk.len() > limit {
+            return Ok(None);
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(Some(bytes.into()))
+}
+
+/// Returns the number of calls of the request body if it's a batch request.
+fn batch_size(body: &[u8]) -> Option<usize> {
+    let first = body.iter().find(|b| !b.is_ascii_whitespace())?;
+    if *first != b'[' {
+        return None;
+    }
+
+    serde_json::from_slice::<Vec<IgnoredAny>>(body).ok().map(|calls| calls.len())
+}
+
+fn too_big_batch_response(max_batch_size: usize) -> Response<Body> {
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "error": {
+            "code": TOO_BIG_BATCH_REQUEST_CODE,
+            "message": "The batch request was too large",
+            "data": format!("Exceeded max limit of {max_batch_size}"),
+        },
+        "id": null,
+    });
+
+    Response::builder()
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("valid response")
+}
+
+fn too_big_request_response(max_body_size: usize) -> Response<Body> {
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "error": {
+            "code": OVERSIZED_REQUEST_CODE,
+            "message": "Request is too big",
+            "data": format!("Exceeded max limit of {max_body_size}"),
+        },
+        "id": null,
+    });
+
+    Response::builder()
+        .status(StatusCode::PAYLOAD_TOO_LARGE)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("valid response")
+}
+
+/// Length of the windows the requests of a client are counted in.
+const RATE_LIMIT_WINDOW: Duration = Duration::from_secs(1);
+
+/// Layer limiting the number of requests a client can make per second.
+///
+/// The server doesn't expose the address of the peers to its middlewares, so the requests are
+/// counted per connection. When the node is configured to trust the `X-Forwarded-For` header, the
+/// client is identified by the last address of the header, the one appended by the reverse proxy
+/// in front of the node, and is counted across connections. The header must not be trusted if
+/// the node can be reached without going through the proxy, as clients could set it to anything.
+///
+/// A batch counts as a single request, and so does a websocket connection whatever the number of
+/// calls made on it.
+#[derive(Debug, Clone)]
+pub struct RateLimitLayer {
+    max_requests_per_second: u32,
+    trust_forwarded_for: bool,
+    clients: Arc<Mutex<Clients>>,
+    connection_ids: Arc<AtomicU64>,
+}
+
+impl RateLimitLayer {
+    pub fn new(max_requests_per_second: u32, trust_forwarded_for: bool) -> Self {
+        Self {
+            max_requests_per_second,
+            trust_forwarded_for,
+            clients: Default::default(),
+            connection_ids: Default::default(),
+        }
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimit<S>;
+
+    // The server creates a service per connection.
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimit {
+            inner,
+            connection_id: self.connection_ids.fetch_add(1, Ordering::Relaxed),
+            max_requests_per_second: self.max_requests_per_second,
+            trust_forwarded_for: self.trust_forwarded_for,
+            clients: self.clients.clone(),
+        }
+    }
+}
+
+/// Service created by [`RateLimitLayer`].
+#[derive(Debug, Clone)]
+pub struct RateLimit<S> {
+    inner: S,
+    connection_id: u64,
+    max_requests_per_second: u32,
+    trust_forwarded_for: bool,
+    clients: Arc<Mutex<Clients>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Client {
+    Ip(IpAddr),
+    Connection(u64),
+}
+
+/// The requests made by a client in the current window.
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    start: Instant,
+    requests: u32,
+}
+
+/// The windows of the clients that made requests recently.
+#[derive(Debug)]
+struct Clients {
+    windows: HashMap<Client, Window>,
+    /// When the windows of the past were last removed.
+    last_sweep: Instant,
+}
+
+impl Default for Clients {
+    fn default() -> Self {
+        Self { windows: HashMap::new(), last_sweep: Instant::now() }
+    }
+}
+
+impl<S> RateLimit<S> {
+    fn client(&self, request: &Request<Body>) -> Client {
+        let forwarded = || request.headers().get("x-forwarded-for").and_then(forwarded_ip);
+        match self.trust_forwarded_for.then(forwarded).flatten() {
+            Some(ip) => Client::Ip(ip),
+            None => Client::Connection(self.connection_id),
+        }
+    }
+
+    /// Counts a request of the client, returns `false` if it exceeds the limit.
+    fn try_acquire(&self, client: Client) -> bool {
+        let now = Instant::now();
+        let mut clients = self.clients.lock().expect("poisoned lock");
+
+        // only the clients of the current window are kept, so that the ones which stopped making
+        // requests don't accumulate
+        if now.duration_since(clients.last_sweep) >= RATE_LIMIT_WINDOW {
+            clients
+                .windows
+                .retain(|_, window| now.duration_since(window.start) < RATE_LIMIT_WINDOW);
+            clients.last_sweep = now;
+        }
+
+        let window = clients.windows.entry(client).or_insert(Window { start: now, requests: 0 });
+        if now.duration_since(window.start) >= RATE_LIMIT_WINDOW {
+            *window = Window { start: now, requests: 0 };
+        }
+
+        if window.requests >= self.max_requests_per_second {
+            return false;
+        }
+
+        window.requests += 1;
+        true
+    }
+}
+
+impl<S> Service<Request<Body>> for RateLimit<S>
+where
+    S: Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        if !self.try_acquire(self.client(&request)) {
+            return Box::pin(async { Ok(rate_limited_response()) });
+        }
+
+        Box::pin(self.inner.call(request))
+    }
+}
+
+/// Returns the address of the client from the value of a `X-Forwarded-For` header, ie. the last
+/// one, appended by the proxy. The previous ones are set by the client.
+fn forwarded_ip(value: &HeaderValue) -> Option<IpAddr> {
+    value.to_str().ok()?.rsplit(',').next()?.trim().parse().ok()
+}
+
+fn rate_limited_response() -> Response<Body> {
+    let body = serde_json::json!({
+        "jsonrpc": "2.0",
+        "error": {
+            "code": RATE_LIMITED_CODE,
+            "message": "Too many requests",
+        },
+        "id": null,
+    });
+
+    Response::builder()
+        .status(StatusCode::TOO_MANY_REQUESTS)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string()))
+        .expect("valid response")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+
+    use tower::ServiceExt;
+
+    use super::*;
+
+    fn request(body: &str) -> Request<Body> {
+        Request::post("/").body(Body::from(body.to_string())).unwrap()
+    }
+
+    async fn response_body(response: Response<Body>) -> serde_json::Value {
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[test]
+    fn batch_limit() {
+        // echoes the request body
+        let echo = tower::service_fn(|request: Request<Body>| async move {
+            Ok::<_, Infallible>(Response::new(request.into_body()))
+        });
+        let service = BatchLimitLayer::new(2).layer(echo);
+
+        futures::executor::block_on(async {
+            let single = r#"{"jsonrpc":"2.0","id":1,"method":"starknet_chainId"}"#;
+            let response = service.clone().oneshot(request(single)).await.unwrap();
+            assert_eq!(response_body(response).await["id"], 1);
+
+            let batch = format!("[{single},{single}]");
+            let response = service.clone().oneshot(request(&batch)).await.unwrap();
+            assert_eq!(response_body(response).await.as_array().unwrap().len(), 2);
+
+            let batch = format!(" [{single},{single},{single}]");
+            let response = service.clone().oneshot(request(&batch)).await.unwrap();
+            let body = response_body(response).await;
+            assert_eq!(body["error"]["code"], TOO_BIG_BATCH_REQUEST_CODE);
+            assert_eq!(body["id"], serde_json::Value::Null);
+        });
+    }
+
+    #[test]
+    fn body_size_limit() {
+        let echo = tower::service_fn(|request: Request<Body>| async move {
+            Ok::<_, Infallible>(Response::new(request.into_body()))
+        });
+        let service = BatchLimitLayer { max_batch_size: 2, max_body_size: 64 }.layer(echo);
+
+        futures::executor::block_on(async {
+            let single = r#"{"jsonrpc":"2.0","id":1,"method":"starknet_chainId"}"#;
+            let response = service.clone().oneshot(request(single)).await.unwrap();
+            assert_eq!(response_body(response).await["id"], 1);
+
+            // a streamed body without content length is rejected once the limit is reached
+            let (mut sender, body) = Body::channel();
+            let send = async move {
+                for _ in 0..4 {
+                    if sender.send_data(Bytes::from(single)).await.is_err() {
+                        break;
+                    }
+                }
+            };
+            let call = service.clone().oneshot(Request::post("/").body(body).unwrap());
+            let (_, response) = futures::join!(send, call);
+            let response = response.unwrap();
+            assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+            assert_eq!(response_body(response).await["error"]["code"], OVERSIZED_REQUEST_CODE);
+
+            let request = Request::post("/")
+                .header(CONTENT_LENGTH, 1024)
+                .body(Body::from(single.to_string()))
+                .unwrap();
+            let response = service.clone().oneshot(request).await.unwrap();
+            assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        });
+    }
+
+    #[test]
+    fn rate_limit() {
+        let ok = tower::service_fn(|_: Request<Body>| async move {
+            Ok::<_, Infallible>(Response::new(Body::empty()))
+        });
+        let layer = RateLimitLayer::new(2, true);
+        let mut first_connection = layer.layer(ok);
+        let mut second_connection = layer.layer(ok);
+
+        let forwarded = |ip: &str| {
+            Request::post("/").header("x-forwarded-for", ip).body(Body::empty()).unwrap()
+        };
+
+        futures::executor::block_on(async {
+            // the requests without the header are counted per connection
+            for _ in 0..2 {
+                let response = first_connection.call(request("")).await.unwrap();
+                assert_eq!(response.status(), StatusCode::OK);
+            }
+            let response = first_connection.call(request("")).await.unwrap();
+            assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+            assert_eq!(response_body(response).await["error"]["code"], RATE_LIMITED_CODE);
+
+            let response = second_connection.call(request("")).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+
+            // the forwarded clients are counted across connections, by the address appended by
+            // the proxy
+            let response = first_connection.call(forwarded("10.0.0.1")).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let response = second_connection.call(forwarded("1.1.1.1, 10.0.0.1")).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let response = second_connection.call(forwarded("2.2.2.2, 10.0.0.1")).await.unwrap();
+            assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+
+            let response = second_connection.call(forwarded("10.0.0.2")).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        });
+    }
+
+    #[test]
+    fn rate_limit_ignores_untrusted_forwarded_for() {
+        let ok = tower::service_fn(|_: Request<Body>| async move {
+            Ok::<_, Infallible>(Response::new(Body::empty()))
+        });
+        let layer = RateLimitLayer::new(1, false);
+        let mut connection = layer.layer(ok);
+
+        futures::executor::block_on(async {
+            // a client can't get a fresh window by changing the header
+            for (ip, status) in
+                [("10.0.0.1", StatusCode::OK), ("10.0.0.2", StatusCode::TOO_MANY_REQUESTS)]
+            {
+                let request =
+                    Request::post("/").header("x-forwarded-for", ip).body(Body::empty()).unwrap();
+                let response = connection.call(request).await.unwrap();
+                assert_eq!(response.status(), status);
+            }
+        });
+    }
+}
@@ -0,0 +1,263 @@
+//! Hosting several isolated chains in a single process.
+//!
+//! Every chain is a node of its own, with its own database, pool and block producer, whose RPC
+//! server listens on a local port. A router listening on the RPC address of the process forwards
+//! the HTTP requests made to `/chain/<name>` to the RPC server of the chain named `<name>`.
+//!
+//! Subscriptions over WebSocket are not routed, they must be made to the address of the chain's
+//! own RPC server.
+//!
+//! The router reuses its connections to the chains, so it appends the address of its clients to
+//! the `X-Forwarded-For` header of the requests and the chains identify the clients of their rate
+//! limit by it.
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+
+use anyhow::{bail, Result};
+use futures::future::select_all;
+use hyper::client::HttpConnector;
+use hyper::header::HeaderValue;
+use hyper::server::conn::AddrStream;
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Client, Request, Response, Server, StatusCode, Uri};
+use tracing::{error, info};
+
+use crate::config::Config;
+use crate::LaunchedNode;
+
+/// The path prefix of the RPC endpoint of a chain.
+pub const CHAIN_PATH_PREFIX: &str = "/chain/";
+
+const X_FORWARDED_FOR: &str = "x-forwarded-for";
+
+/// Where the requests made to a chain are forwarded.
+#[derive(Debug, Clone, Copy)]
+struct Route {
+    /// The address of the RPC server of the chain.
+    addr: SocketAddr,
+    /// Whether the `X-Forwarded-For` header of the requests made to the router is kept, ie. the
+    /// router itself is behind a trusted reverse proxy.
+    trust_forwarded_for: bool,
+}
+
+/// The chains launched in the process, and the address of the router forwarding the requests to
+/// them.
+#[allow(missing_debug_implementations)]
+pub struct LaunchedChains {
+    /// The address of the router.
+    pub addr: SocketAddr,
+    /// The chains, by name.
+    pub chains: Vec<(String, LaunchedNode)>,
+}
+
+impl LaunchedChains {
+    /// Stops all the chains, and the router with them.
+    pub async fn stop(&self) -> Result<()> {
+        for (_, chain) in &self.chains {
+            chain.stop().await?;
+        }
+        Ok(())
+    }
+
+    /// Waits until any of the chains has stopped, then stops the other ones.
+    pub async fn stopped(&self) -> Result<()> {
+        let chains = self.chains.iter().map(|(_, chain)| chain.stopped());
+        let (result, ..) = select_all(chains).await;
+        self.stop().await?;
+        result
+    }
+}
+
+/// Builds and launches a node for each of the `chains`, given by name with their configuration,
+/// and starts the router forwarding the requests to them on `addr`.
+///
+/// The RPC server of each chain listens on a free local port, whatever its configured address.
+pub async fn launch_chains(
+    chains: Vec<(String, Config)>,
+    addr: SocketAddr,
+) -> Result<LaunchedChains> {
+    if chains.is_empty() {
+        bail!("at least one chain is required");
+    }
+
+    let mut launched = Vec::with_capacity(chains.len());
+    let mut routes = HashMap::with_capacity(chains.len());
+
+    for (name, mut config) in chains {
+        if routes.contains_key(&name) {
+            bail!("chain '{name}' is defined more than once");
+        }
+
+        config.rpc.addr = Ipv4Addr::LOCALHOST.into();
+        config.rpc.port = 0;
+
+        // the requests of all the clients go through the connections of the router, which
+        // appends the address of the client to the header
+        let trust_forwarded_for = config.rpc.trust_forwarded_for;
+        if config.rpc.max_requests_per_second.is_some() {
+            config.rpc.trust_forwarded_for = true;
+        }
+
+        let node = crate::build(config).await?.launch().await?;
+        info!(target: "node", chain = %name, addr = %node.rpc.addr, "Chain started.");
+
+        routes.insert(name.clone(), Route { addr: node.rpc.addr, trust_forwarded_for });
+        launched.push((name, node));
+    }
+
+    let routes = Arc::new(routes);
+    let client = Client::new();
+    let service = make_service_fn(move |conn: &AddrStream| {
+        let (routes, client) = (routes.clone(), client.clone());
+        let peer = conn.remote_addr().ip();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |req| {
+                route(routes.clone(), client.clone(), peer, req)
+            }))
+        }
+    });
+
+    let server = Server::try_bind(&addr)?.serve(service);
+    let addr = server.local_addr();
+
+    // the router is stopped with the task manager of the first chain
+    let (_, first) = &launched[0];
+    first.node.task_manager.task_spawner().build_task().name("Chain router").spawn(async move {
+        if let Err(error) = server.await {
+            error!(target: "node", %error, "Chain router stopped.");
+        }
+    });
+
+    info!(target: "node", %addr, "Chain router started.");
+
+    Ok(LaunchedChains { addr, chains: launched })
+}
+
+/// Forwards a request made by `peer` to `/chain/<name>/<path>` to `/<path>` on the RPC server of
+/// the chain.
+async fn route(
+    routes: Arc<HashMap<String, Route>>,
+    client: Client<HttpConnector>,
+    peer: IpAddr,
+    mut req: Request<Body>,
+) -> Result<Response<Body>, Infallible> {
+    let Some(path) = req.uri().path().strip_prefix(CHAIN_PATH_PREFIX) else {
+        return Ok(response(StatusCode::NOT_FOUND, "expected a path starting with /chain/<name>"));
+    };
+
+    let (name, path) = path.split_once('/').unwrap_or((path, ""));
+    let Some(target) = routes.get(name) else {
+        return Ok(response(StatusCode::NOT_FOUND, format!("unknown chain '{name}'")));
+    };
+
+    let query = req.uri().query().map(|q| format!("?{q}")).unwrap_or_default();
+    let Ok(uri) = format!("http://{}/{path}{query}", target.addr).parse::<Uri>() else {
+        return Ok(response(StatusCode::BAD_REQUEST, "invalid path"));
+    };
+    *req.uri_mut() = uri;
+
+    // the addresses set by the clients can't be trusted if the router is reached directly, and the
+    // trusted proxy in front of the router already appended the address of its client otherwise
+    if !(target.trust_forwarded_for && req.headers().contains_key(X_FORWARDED_FOR)) {
+        let value = HeaderValue::from_str(&peer.to_string()).expect("valid header value");
+        req.headers_mut().insert(X_FORWARDED_FOR, value);
+    }
+
+    match client.request(req).await {
+        Ok(res) => Ok(res),
+        Err(error) => Ok(response(StatusCode::BAD_GATEWAY, error.to_string())),
+    }
+}
+
+fn response(status: StatusCode, body: impl Into<Body>) -> Response<Body> {
+    let mut res = Response::new(body.into());
+    *res.status_mut() = status;
+    res
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Starts a server answering the path and the `X-Forwarded-For` header of the requests.
+    fn echo_server() -> SocketAddr {
+        let service = make_service_fn(|_| async {
+            Ok::<_, Infallible>(service_fn(|req: Request<Body>| async move {
+                let forwarded_for = req.headers().get(X_FORWARDED_FOR).cloned();
+                let forwarded_for = forwarded_for.map(|v| v.to_str().unwrap().to_string());
+                let body = format!("{} {}", req.uri(), forwarded_for.unwrap_or_default());
+                Ok::<_, Infallible>(Response::new(Body::from(body)))
+            }))
+        });
+
+        let server = Server::bind(&(Ipv4Addr::LOCALHOST, 0).into()).serve(service);
+        let addr = server.local_addr();
+        tokio::spawn(server);
+        addr
+    }
+
+    async fn send(
+        routes: &Arc<HashMap<String, Route>>,
+        req: Request<Body>,
+    ) -> (StatusCode, String) {
+        let peer = IpAddr::from([10, 0, 0, 1]);
+        let res = route(routes.clone(), Client::new(), peer, req).await.unwrap();
+        let status = res.status();
+        let body = hyper::body::to_bytes(res.into_body()).await.unwrap();
+        (status, String::from_utf8(body.to_vec()).unwrap())
+    }
+
+    fn routes(addr: SocketAddr, trust_forwarded_for: bool) -> Arc<HashMap<String, Route>> {
+        Arc::new(HashMap::from([("foo".to_string(), Route { addr, trust_forwarded_for })]))
+    }
+
+    #[tokio::test]
+    async fn forward_to_chain() {
+        let routes = routes(echo_server(), false);
+
+        let req = Request::post("/chain/foo/").body(Body::empty()).unwrap();
+        let (status, body) = send(&routes, req).await;
+        assert_eq!((status, body.as_str()), (StatusCode::OK, "/ 10.0.0.1"));
+
+        let req = Request::post("/chain/foo/rpc/v0_7?a=b").body(Body::empty()).unwrap();
+        let (status, body) = send(&routes, req).await;
+        assert_eq!((status, body.as_str()), (StatusCode::OK, "/rpc/v0_7?a=b 10.0.0.1"));
+
+        // the address set by the client is replaced by the one of the peer
+        let req = Request::post("/chain/foo")
+            .header(X_FORWARDED_FOR, "1.2.3.4")
+            .body(Body::empty())
+            .unwrap();
+        let (status, body) = send(&routes, req).await;
+        assert_eq!((status, body.as_str()), (StatusCode::OK, "/ 10.0.0.1"));
+    }
+
+    #[tokio::test]
+    async fn keep_trusted_forwarded_for() {
+        let routes = routes(echo_server(), true);
+
+        // the peer is the proxy, which already appended the address of its client
+        let req = Request::post("/chain/foo/")
+            .header(X_FORWARDED_FOR, "1.2.3.4, 5.6.7.8")
+            .body(Body::empty())
+            .unwrap();
+        let (status, body) = send(&routes, req).await;
+        assert_eq!((status, body.as_str()), (StatusCode::OK, "/ 1.2.3.4, 5.6.7.8"));
+    }
+
+    #[tokio::test]
+    async fn unknown_chain() {
+        let routes = routes(echo_server(), false);
+
+        let req = Request::post("/chain/bar/").body(Body::empty()).unwrap();
+        let (status, body) = send(&routes, req).await;
+        assert_eq!((status, body.as_str()), (StatusCode::NOT_FOUND, "unknown chain 'bar'"));
+
+        let req = Request::post("/foo/").body(Body::empty()).unwrap();
+        let (status, _) = send(&routes, req).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+    }
+}
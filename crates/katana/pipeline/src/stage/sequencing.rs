@@ -4,6 +4,7 @@ use anyhow::Result;
 use futures::future;
 use katana_core::backend::Backend;
 use katana_core::service::block_producer::{BlockProducer, BlockProductionError};
+use katana_core::service::invariant::InvariantChecker;
 use katana_core::service::messaging::{MessagingConfig, MessagingService, MessagingTask};
 use katana_core::service::{BlockProductionTask, TransactionMiner};
 use katana_executor::ExecutorFactory;
@@ -22,6 +23,7 @@ pub struct Sequencing<EF: ExecutorFactory> {
     task_spawner: TaskSpawner,
     block_producer: BlockProducer<EF>,
     messaging_config: Option<MessagingConfig>,
+    invariants: Option<InvariantChecker<EF>>,
 }
 
 impl<EF: ExecutorFactory> Sequencing<EF> {
@@ -32,7 +34,13 @@ impl<EF: ExecutorFactory> Sequencing<EF> {
         block_producer: BlockProducer<EF>,
         messaging_config: Option<MessagingConfig>,
     ) -> Self {
-        Self { pool, backend, task_spawner, block_producer, messaging_config }
+        Self { pool, backend, task_spawner, block_producer, messaging_config, invariants: None }
+    }
+
+    /// Checks the given invariants after every produced block.
+    pub fn with_invariants(mut self, invariants: InvariantChecker<EF>) -> Self {
+        self.invariants = Some(invariants);
+        self
     }
 
     async fn run_messaging(&self) -> Result<TaskHandle<()>> {
@@ -56,7 +64,10 @@ impl<EF: ExecutorFactory> Sequencing<EF> {
         // Create a new transaction miner with a subscription to the pool's pending transactions.
         let miner = TransactionMiner::new(self.pool.pending_transactions());
         let block_producer = self.block_producer.clone();
-        let service = BlockProductionTask::new(self.pool.clone(), miner, block_producer);
+        let mut service = BlockProductionTask::new(self.pool.clone(), miner, block_producer);
+        if let Some(invariants) = &self.invariants {
+            service = service.with_invariants(invariants.clone());
+        }
         self.task_spawner.build_task().name("Block production").spawn(service)
     }
 }
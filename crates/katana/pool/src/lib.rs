@@ -7,6 +7,8 @@ pub mod subscription;
 pub mod tx;
 pub mod validation;
 
+use std::fmt;
+use std::str::FromStr;
 use std::sync::Arc;
 
 use futures::channel::mpsc::Receiver;
@@ -14,6 +16,7 @@ use katana_primitives::transaction::{ExecutableTxWithHash, TxHash};
 use ordering::{FiFo, PoolOrd};
 use pending::PendingTransactions;
 use pool::Pool;
+use serde::{Deserialize, Serialize};
 use tx::PoolTransaction;
 use validation::error::InvalidTransactionError;
 use validation::stateful::TxValidator;
@@ -28,10 +31,92 @@ pub type PoolResult<T> = Result<T, PoolError>;
 pub enum PoolError {
     #[error("Invalid transaction: {0}")]
     InvalidTransaction(Box<InvalidTransactionError>),
+    #[error("Transaction pool is full")]
+    PoolFull,
     #[error("Internal error: {0}")]
     Internal(Box<dyn std::error::Error>),
 }
 
+/// The default maximum number of transactions the pool can hold.
+pub const DEFAULT_POOL_MAX_SIZE: usize = 10_000;
+
+/// Transaction pool configurations.
+#[derive(Debug, Clone)]
+pub struct PoolConfig {
+    /// The maximum number of transactions, pending and queued, the pool can hold.
+    ///
+    /// When the pool is full, the oldest queued transaction is evicted to make room for an
+    /// incoming one. If there are no queued transactions, the incoming one is rejected.
+    pub max_size: usize,
+    /// How the nonces of the incoming transactions are validated.
+    pub nonce_validation: NonceValidation,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self { max_size: DEFAULT_POOL_MAX_SIZE, nonce_validation: NonceValidation::default() }
+    }
+}
+
+/// How the pool validates the nonce of a transaction against its sender's next nonce.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum NonceValidation {
+    /// Reject the transactions whose nonce is ahead of their sender's next nonce.
+    #[default]
+    Strict,
+    /// Queue the transactions whose nonce is ahead of their sender's next nonce until the
+    /// transactions filling the gap are received.
+    AllowGaps,
+    /// Accept the transactions in any nonce order, as long as their nonce isn't below their
+    /// sender's current nonce. Transactions executed out of order may then fail, so this is only
+    /// meant for load testing.
+    Disabled,
+}
+
+impl fmt::Display for NonceValidation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NonceValidation::Strict => f.write_str("strict"),
+            NonceValidation::AllowGaps => f.write_str("allow-gaps"),
+            NonceValidation::Disabled => f.write_str("disabled"),
+        }
+    }
+}
+
+impl FromStr for NonceValidation {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strict" => Ok(NonceValidation::Strict),
+            "allow-gaps" => Ok(NonceValidation::AllowGaps),
+            "disabled" => Ok(NonceValidation::Disabled),
+            _ => Err(format!(
+                "invalid nonce validation mode `{s}`, expected `strict`, `allow-gaps` or `disabled`"
+            )),
+        }
+    }
+}
+
+/// The transactions of a pool.
+#[derive(Debug)]
+pub struct PoolContent<T> {
+    /// The transactions that can be executed, in the order they will be.
+    pub pending: Vec<Arc<T>>,
+    /// The transactions waiting for the transactions filling their nonce gap.
+    pub queued: Vec<Arc<T>>,
+}
+
+/// The number of transactions in a pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PoolStatus {
+    /// The number of transactions that can be executed.
+    pub pending: usize,
+    /// The number of transactions waiting for the transactions filling their nonce gap.
+    pub queued: usize,
+}
+
 /// Represents a complete transaction pool.
 pub trait TransactionPool {
     /// The pool's transaction type.
@@ -62,9 +147,15 @@ pub trait TransactionPool {
     /// Removes a list of transactions from the pool according to their hashes.
     fn remove_transactions(&self, hashes: &[TxHash]);
 
-    /// Get the total number of transactions in the pool.
+    /// Get the number of pending transactions in the pool.
     fn size(&self) -> usize;
 
+    /// Get all the pending and queued transactions of the pool.
+    fn content(&self) -> PoolContent<Self::Transaction>;
+
+    /// Get the number of pending and queued transactions in the pool.
+    fn status(&self) -> PoolStatus;
+
     /// Get a reference to the pool's validator.
     fn validator(&self) -> &Self::Validator;
 }
@@ -1,6 +1,7 @@
 use core::fmt;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 use std::sync::Arc;
+use std::time::Instant;
 
 use futures::channel::mpsc::{channel, Receiver, Sender};
 use katana_primitives::transaction::TxHash;
@@ -14,7 +15,9 @@ use crate::subscription::Subscription;
 use crate::tx::{PendingTx, PoolTransaction, TxId};
 use crate::validation::error::InvalidTransactionError;
 use crate::validation::{ValidationOutcome, Validator};
-use crate::{PoolError, PoolResult, TransactionPool};
+use crate::{
+    NonceValidation, PoolConfig, PoolContent, PoolError, PoolResult, PoolStatus, TransactionPool,
+};
 
 #[derive(Debug)]
 pub struct Pool<T, V, O>
@@ -31,6 +34,9 @@ struct Inner<T, V, O: PoolOrd> {
     /// List of all valid txs in the pool.
     transactions: RwLock<BTreeSet<PendingTx<T, O>>>,
 
+    /// txs waiting for the txs filling their nonce gap, along with the time they were queued at.
+    queued: RwLock<BTreeMap<TxId, (Arc<T>, Instant)>>,
+
     /// listeners for incoming txs
     listeners: RwLock<Vec<Sender<TxHash>>>,
 
@@ -42,6 +48,8 @@ struct Inner<T, V, O: PoolOrd> {
 
     /// the ordering mechanism used to order the txs in the pool
     ordering: O,
+
+    config: PoolConfig,
 }
 
 impl<T, V, O> Pool<T, V, O>
@@ -52,11 +60,18 @@ where
 {
     /// Creates a new [Pool] with the given [Validator] and [PoolOrd] mechanism.
     pub fn new(validator: V, ordering: O) -> Self {
+        Self::new_with_config(validator, ordering, PoolConfig::default())
+    }
+
+    /// Creates a new [Pool] with the given [Validator], [PoolOrd] mechanism and [PoolConfig].
+    pub fn new_with_config(validator: V, ordering: O, config: PoolConfig) -> Self {
         Self {
             inner: Arc::new(Inner {
+                config,
                 ordering,
                 validator,
                 transactions: Default::default(),
+                queued: Default::default(),
                 subscribers: Default::default(),
                 listeners: Default::default(),
             }),
@@ -115,43 +130,32 @@ where
         self.notify_subscribers(tx);
     }
 
-    fn subscribe(&self) -> Subscription<T, O> {
-        let (tx, rx) = mpsc::unbounded_channel();
-        let subscriber = Subscription::new(rx);
-        self.inner.subscribers.write().push(tx);
-        subscriber
-    }
-}
+    /// Inserts a tx in the pool, to be executed.
+    fn insert_pending(&self, id: TxId, tx: T) {
+        // get the priority of the validated tx
+        let priority = self.inner.ordering.priority(&tx);
+        let tx = PendingTx::new(id, tx, priority);
 
-impl<T, V, O> TransactionPool for Pool<T, V, O>
-where
-    T: PoolTransaction + fmt::Debug,
-    V: Validator<Transaction = T>,
-    O: PoolOrd<Transaction = T>,
-{
-    type Transaction = T;
-    type Validator = V;
-    type Ordering = O;
+        self.inner.transactions.write().insert(tx.clone());
+        self.notify(tx);
+    }
 
-    fn add_transaction(&self, tx: T) -> PoolResult<TxHash> {
+    /// Validates the tx and inserts it in the pool, or in the queue if it's a dependent tx and the
+    /// pool is configured to queue them. With the nonce validation disabled, dependent txs are
+    /// inserted in the pool right away.
+    ///
+    /// Room is only made for the tx once it's been validated, so an invalid tx never evicts a
+    /// queued one.
+    fn validate_and_insert(&self, id: TxId, tx: T) -> PoolResult<()> {
         let hash = tx.hash();
-        let id = TxId::new(tx.sender(), tx.nonce());
-
-        info!(target: "pool", hash = format!("{hash:#x}"), "Transaction received.");
 
         match self.inner.validator.validate(tx) {
             Ok(outcome) => {
                 match outcome {
                     ValidationOutcome::Valid(tx) => {
-                        // get the priority of the validated tx
-                        let priority = self.inner.ordering.priority(&tx);
-                        let tx = PendingTx::new(id, tx, priority);
-
-                        // insert the tx in the pool
-                        self.inner.transactions.write().insert(tx.clone());
-                        self.notify(tx);
-
-                        Ok(hash)
+                        self.ensure_capacity()?;
+                        self.insert_pending(id, tx);
+                        Ok(())
                     }
 
                     // TODO: create a small cache for rejected transactions to respect the rpc spec
@@ -161,10 +165,24 @@ where
                         Err(PoolError::InvalidTransaction(Box::new(error)))
                     }
 
-                    // return as error for now but ideally we should kept the tx in a separate
-                    // queue and revalidate it when the parent tx is added to the pool
                     ValidationOutcome::Dependent { tx, tx_nonce, current_nonce } => {
                         info!(target: "pool", hash = format!("{hash:#x}"), %tx_nonce, %current_nonce, "Dependent transaction.");
+
+                        match self.inner.config.nonce_validation {
+                            NonceValidation::AllowGaps => {
+                                self.ensure_capacity()?;
+                                let entry = (Arc::new(tx), Instant::now());
+                                self.inner.queued.write().insert(id, entry);
+                                return Ok(());
+                            }
+                            NonceValidation::Disabled => {
+                                self.ensure_capacity()?;
+                                self.insert_pending(id, tx);
+                                return Ok(());
+                            }
+                            NonceValidation::Strict => {}
+                        }
+
                         let err = InvalidTransactionError::InvalidNonce {
                             address: tx.sender(),
                             current_nonce,
@@ -182,6 +200,68 @@ where
         }
     }
 
+    /// Makes room for an incoming tx if the pool is full, by evicting the oldest queued tx.
+    fn ensure_capacity(&self) -> PoolResult<()> {
+        let mut queued = self.inner.queued.write();
+        if self.inner.transactions.read().len() + queued.len() < self.inner.config.max_size {
+            return Ok(());
+        }
+
+        let oldest = queued.iter().min_by_key(|(_, (_, queued_at))| *queued_at).map(|(id, _)| id);
+        match oldest.cloned() {
+            Some(id) => {
+                let (tx, _) = queued.remove(&id).expect("qed; tx exists");
+                let hash = tx.hash();
+                info!(target: "pool", hash = format!("{hash:#x}"), "Evicting queued transaction.");
+                Ok(())
+            }
+            None => Err(PoolError::PoolFull),
+        }
+    }
+
+    fn subscribe(&self) -> Subscription<T, O> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let subscriber = Subscription::new(rx);
+        self.inner.subscribers.write().push(tx);
+        subscriber
+    }
+}
+
+impl<T, V, O> TransactionPool for Pool<T, V, O>
+where
+    T: PoolTransaction + fmt::Debug,
+    V: Validator<Transaction = T>,
+    O: PoolOrd<Transaction = T>,
+{
+    type Transaction = T;
+    type Validator = V;
+    type Ordering = O;
+
+    fn add_transaction(&self, tx: T) -> PoolResult<TxHash> {
+        let hash = tx.hash();
+        let id = TxId::new(tx.sender(), tx.nonce());
+
+        info!(target: "pool", hash = format!("{hash:#x}"), "Transaction received.");
+
+        self.validate_and_insert(id.clone(), tx)?;
+
+        // the tx may fill the nonce gap of queued txs from the same sender
+        let mut next = id.descendent();
+        loop {
+            let Some((queued, _)) = self.inner.queued.write().remove(&next) else { break };
+            let queued = Arc::unwrap_or_clone(queued);
+
+            if let Err(error) = self.validate_and_insert(next.clone(), queued) {
+                warn!(target: "pool", %error, "Dropping queued transaction.");
+                break;
+            }
+
+            next = next.descendent();
+        }
+
+        Ok(hash)
+    }
+
     fn pending_transactions(&self) -> PendingTransactions<Self::Transaction, Self::Ordering> {
         // take all the transactions
         PendingTransactions {
@@ -221,6 +301,18 @@ where
         self.inner.transactions.read().len()
     }
 
+    fn content(&self) -> PoolContent<T> {
+        let pending = self.inner.transactions.read().iter().map(|t| Arc::clone(&t.tx)).collect();
+        let queued = self.inner.queued.read().values().map(|(tx, _)| Arc::clone(tx)).collect();
+        PoolContent { pending, queued }
+    }
+
+    fn status(&self) -> PoolStatus {
+        let pending = self.inner.transactions.read().len();
+        let queued = self.inner.queued.read().len();
+        PoolStatus { pending, queued }
+    }
+
     fn validator(&self) -> &Self::Validator {
         &self.inner.validator
     }
@@ -319,6 +411,10 @@ pub(crate) mod test_utils {
 #[cfg(test)]
 mod tests {
 
+    use std::collections::HashMap;
+    use std::sync::Arc;
+    use std::time::Instant;
+
     use futures::StreamExt;
     use katana_primitives::contract::{ContractAddress, Nonce};
     use katana_primitives::transaction::TxHash;
@@ -327,9 +423,9 @@ mod tests {
     use super::test_utils::*;
     use super::Pool;
     use crate::ordering::FiFo;
-    use crate::tx::PoolTransaction;
-    use crate::validation::NoopValidator;
-    use crate::TransactionPool;
+    use crate::tx::{PoolTransaction, TxId};
+    use crate::validation::{NoopValidator, ValidationOutcome, ValidationResult, Validator};
+    use crate::{NonceValidation, PoolConfig, PoolError, PoolStatus, TransactionPool};
 
     /// Tx pool that uses a noop validator and a first-come-first-serve ordering.
     type TestPool = Pool<PoolTx, NoopValidator<PoolTx>, FiFo<PoolTx>>;
@@ -340,6 +436,128 @@ mod tests {
         }
     }
 
+    /// Validator expecting the nonces of the txs of a sender to be consecutive, starting from 0.
+    #[derive(Debug, Default)]
+    struct NonceValidator(parking_lot::Mutex<HashMap<ContractAddress, Nonce>>);
+
+    impl Validator for NonceValidator {
+        type Transaction = PoolTx;
+
+        fn validate(&self, tx: PoolTx) -> ValidationResult<PoolTx> {
+            let mut nonces = self.0.lock();
+            let current_nonce = nonces.get(&tx.sender()).copied().unwrap_or_default();
+
+            if tx.nonce() > current_nonce {
+                let tx_nonce = tx.nonce();
+                return Ok(ValidationOutcome::Dependent { tx, tx_nonce, current_nonce });
+            }
+
+            nonces.insert(tx.sender(), current_nonce + Nonce::ONE);
+            Ok(ValidationOutcome::Valid(tx))
+        }
+    }
+
+    fn nonce_pool(config: PoolConfig) -> Pool<PoolTx, NonceValidator, FiFo<PoolTx>> {
+        Pool::new_with_config(NonceValidator::default(), FiFo::new(), config)
+    }
+
+    fn tx_of(sender: u64, nonce: u64) -> PoolTx {
+        PoolTx::new().with_sender(Felt::from(sender).into()).with_nonce(Nonce::from(nonce))
+    }
+
+    #[test]
+    fn reject_nonce_gap() {
+        let pool = nonce_pool(PoolConfig::default());
+
+        let result = pool.add_transaction(tx_of(1, 1));
+        assert!(matches!(result, Err(PoolError::InvalidTransaction(_))));
+        assert_eq!(pool.status(), PoolStatus { pending: 0, queued: 0 });
+    }
+
+    #[tokio::test]
+    async fn queue_nonce_gap() {
+        let config =
+            PoolConfig { nonce_validation: NonceValidation::AllowGaps, ..Default::default() };
+        let pool = nonce_pool(config);
+
+        let txs = [tx_of(1, 0), tx_of(1, 1), tx_of(1, 2)];
+
+        // the txs with a nonce gap are queued
+        pool.add_transaction(txs[2].clone()).unwrap();
+        pool.add_transaction(txs[1].clone()).unwrap();
+        assert_eq!(pool.status(), PoolStatus { pending: 0, queued: 2 });
+
+        let content = pool.content();
+        assert!(content.pending.is_empty());
+        assert_eq!(content.queued.len(), 2);
+
+        // filling the gap promotes the queued txs
+        pool.add_transaction(txs[0].clone()).unwrap();
+        assert_eq!(pool.status(), PoolStatus { pending: 3, queued: 0 });
+
+        let mut pendings = pool.pending_transactions();
+        for expected in &txs {
+            let actual = pendings.next().await.unwrap();
+            assert_eq!(actual.tx.hash(), expected.hash());
+        }
+    }
+
+    #[tokio::test]
+    async fn disabled_nonce_validation() {
+        let config =
+            PoolConfig { nonce_validation: NonceValidation::Disabled, ..Default::default() };
+        let pool = nonce_pool(config);
+
+        let txs = [tx_of(1, 2), tx_of(1, 0), tx_of(1, 1)];
+        for tx in &txs {
+            pool.add_transaction(tx.clone()).unwrap();
+        }
+
+        // the txs are pending in the order they were received
+        assert_eq!(pool.status(), PoolStatus { pending: 3, queued: 0 });
+        let mut pendings = pool.pending_transactions();
+        for expected in &txs {
+            let actual = pendings.next().await.unwrap();
+            assert_eq!(actual.tx.hash(), expected.hash());
+        }
+    }
+
+    #[test]
+    fn evict_queued_when_full() {
+        let config = PoolConfig { max_size: 2, nonce_validation: NonceValidation::AllowGaps };
+        let pool = nonce_pool(config);
+
+        pool.add_transaction(tx_of(1, 0)).unwrap();
+        pool.add_transaction(tx_of(1, 2)).unwrap();
+        assert_eq!(pool.status(), PoolStatus { pending: 1, queued: 1 });
+
+        // the queued tx is evicted to make room for the incoming one
+        pool.add_transaction(tx_of(2, 0)).unwrap();
+        assert_eq!(pool.status(), PoolStatus { pending: 2, queued: 0 });
+
+        // without queued txs to evict, the incoming tx is rejected
+        let result = pool.add_transaction(tx_of(3, 0));
+        assert!(matches!(result, Err(PoolError::PoolFull)));
+        assert_eq!(pool.status(), PoolStatus { pending: 2, queued: 0 });
+    }
+
+    #[test]
+    fn invalid_tx_does_not_evict_queued() {
+        let config = PoolConfig { max_size: 2, nonce_validation: NonceValidation::Strict };
+        let pool = nonce_pool(config);
+
+        pool.add_transaction(tx_of(1, 0)).unwrap();
+        // a tx queued before the nonce validation was made strict
+        let queued = tx_of(1, 2);
+        let id = TxId::new(queued.sender(), queued.nonce());
+        pool.inner.queued.write().insert(id, (Arc::new(queued), Instant::now()));
+
+        // the tx is rejected for its nonce gap, before any room is made for it
+        let result = pool.add_transaction(tx_of(2, 1));
+        assert!(matches!(result, Err(PoolError::InvalidTransaction(_))));
+        assert_eq!(pool.status(), PoolStatus { pending: 1, queued: 1 });
+    }
+
     #[tokio::test]
     async fn pool_operations() {
         let txs = [
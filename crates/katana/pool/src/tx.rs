@@ -133,19 +133,7 @@ impl PoolTransaction for ExecutableTxWithHash {
     }
 
     fn sender(&self) -> ContractAddress {
-        match &self.transaction {
-            ExecutableTx::Invoke(tx) => match tx {
-                InvokeTx::V1(v1) => v1.sender_address,
-                InvokeTx::V3(v3) => v3.sender_address,
-            },
-            ExecutableTx::L1Handler(tx) => tx.contract_address,
-            ExecutableTx::Declare(tx) => match &tx.transaction {
-                DeclareTx::V1(v1) => v1.sender_address,
-                DeclareTx::V2(v2) => v2.sender_address,
-                DeclareTx::V3(v3) => v3.sender_address,
-            },
-            ExecutableTx::DeployAccount(tx) => tx.contract_address(),
-        }
+        self.transaction.sender_address()
     }
 
     fn max_fee(&self) -> u128 {
@@ -123,8 +123,9 @@ impl Validator for TxValidator {
         };
 
         // Check if the transaction nonce is higher than the current account nonce,
-        // if yes, dont't run its validation logic and tag it as a dependent tx.
-        if tx_nonce > current_nonce {
+        // if yes, dont't run its validation logic and tag it as a dependent tx. Without the nonce
+        // check, it's validated like any other tx.
+        if tx_nonce > current_nonce && this.execution_flags.nonce_check() {
             return Ok(ValidationOutcome::Dependent { current_nonce, tx_nonce, tx });
         }
 
@@ -138,12 +139,15 @@ impl Validator for TxValidator {
             _ => tx.nonce() == Nonce::ONE && current_nonce == Nonce::ZERO,
         };
 
+        let account_validation =
+            this.execution_flags.account_validation_of(&tx, this.block_env.timestamp);
+
         // prepare a stateful validator and run the account validation logic (ie __validate__
         // entrypoint)
         let result = validate(
             this.prepare(),
             tx,
-            !this.execution_flags.account_validation() || skip_validate,
+            !account_validation || skip_validate,
             !this.execution_flags.fee(),
         );
 
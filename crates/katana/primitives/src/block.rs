@@ -2,7 +2,7 @@ use starknet::core::utils::cairo_short_string_to_felt;
 use starknet::macros::short_string;
 
 use crate::contract::ContractAddress;
-use crate::da::L1DataAvailabilityMode;
+use crate::da::{DataAvailabilityMode, L1DataAvailabilityMode};
 use crate::transaction::{ExecutableTxWithHash, TxHash, TxWithHash};
 use crate::version::ProtocolVersion;
 use crate::Felt;
@@ -222,6 +222,27 @@ impl Default for Header {
     }
 }
 
+/// Operator-defined header fields, for appchains that need custom block semantics.
+///
+/// These fields are not part of the Starknet block header and thus are not committed to in the
+/// block hash. They are stored alongside the header and exposed through the `katana` RPC
+/// namespace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "arbitrary", derive(::arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HeaderExtension {
+    /// The data availability mode of the chain's state diffs.
+    pub da_mode: DataAvailabilityMode,
+    /// Arbitrary bytes set by the operator.
+    pub extra_data: Vec<u8>,
+}
+
+impl Default for HeaderExtension {
+    fn default() -> Self {
+        Self { da_mode: DataAvailabilityMode::L1, extra_data: Vec::new() }
+    }
+}
+
 /// Represents a Starknet full block.
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -1,15 +1,16 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
 use alloy_primitives::U256;
 use lazy_static::lazy_static;
 use starknet::core::utils::cairo_short_string_to_felt;
 use starknet_crypto::Felt;
 
-use crate::block::{Block, Header};
+use crate::block::{Block, Header, HeaderExtension};
 use crate::chain::ChainId;
 use crate::class::ClassHash;
 use crate::contract::ContractAddress;
 use crate::da::L1DataAvailabilityMode;
+use crate::env::Syscall;
 use crate::genesis::allocation::{DevAllocationsGenerator, GenesisAllocation};
 use crate::genesis::constant::{
     get_fee_token_balance_base_storage_address, DEFAULT_ACCOUNT_CLASS_PUBKEY_STORAGE_SLOT,
@@ -37,6 +38,10 @@ pub struct ChainSpec {
     pub fee_contracts: FeeContracts,
     /// The protocol version.
     pub version: ProtocolVersion,
+    /// Extra header fields attached to every block produced by the chain, if any.
+    pub header_extension: Option<HeaderExtension>,
+    /// The syscalls that contracts deployed on the chain are not allowed to use.
+    pub disabled_syscalls: BTreeSet<Syscall>,
 }
 
 /// Tokens that can be used for transaction fee payments in the chain. As
@@ -142,7 +147,14 @@ lazy_static! {
         let id = ChainId::parse("KATANA").unwrap();
         let genesis = Genesis::default();
         let fee_contracts = FeeContracts { eth: DEFAULT_ETH_FEE_TOKEN_ADDRESS, strk: DEFAULT_STRK_FEE_TOKEN_ADDRESS };
-        ChainSpec { id, genesis, fee_contracts, version: CURRENT_STARKNET_VERSION }
+        ChainSpec {
+            id,
+            genesis,
+            fee_contracts,
+            version: CURRENT_STARKNET_VERSION,
+            header_extension: None,
+            disabled_syscalls: BTreeSet::new(),
+        }
     };
 }
 
@@ -367,6 +379,8 @@ mod tests {
                 eth: DEFAULT_ETH_FEE_TOKEN_ADDRESS,
                 strk: DEFAULT_STRK_FEE_TOKEN_ADDRESS,
             },
+            header_extension: None,
+            disabled_syscalls: BTreeSet::new(),
         };
 
         // setup expected storage values
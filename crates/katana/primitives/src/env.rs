@@ -1,3 +1,7 @@
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
 use crate::block::{BlockNumber, GasPrices};
 use crate::chain::ChainId;
 use crate::contract::ContractAddress;
@@ -30,6 +34,23 @@ pub struct CfgEnv {
     pub validate_max_n_steps: u32,
     /// The maximum recursion depth allowed.
     pub max_recursion_depth: usize,
+    /// The syscalls that contracts are not allowed to use.
+    pub disabled_syscalls: BTreeSet<Syscall>,
+    /// The limits on the size of the transactions.
+    pub tx_limits: TxLimits,
+}
+
+/// Limits on the size of the transactions, `None` meaning unlimited.
+///
+/// Transactions exceeding any of them fail without being executed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TxLimits {
+    /// The maximum length of the calldata of invoke and deploy account transactions.
+    pub max_calldata_length: Option<usize>,
+    /// The maximum length of the signature of a transaction.
+    pub max_signature_length: Option<usize>,
+    /// The maximum size, in felts, of the compiled bytecode of a declared class.
+    pub max_bytecode_size: Option<usize>,
 }
 
 /// The contract addresses of the tokens used for the fees.
@@ -40,3 +61,123 @@ pub struct FeeTokenAddressses {
     /// The contract address of the `ETH` token.
     pub eth: ContractAddress,
 }
+
+/// Starknet syscalls that can be disabled on a chain.
+///
+/// Families of syscalls that share a common prefix (e.g. `secp256k1_new`, `secp256k1_add`, ...) are
+/// grouped under a single variant.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum Syscall {
+    CallContract,
+    Deploy,
+    EmitEvent,
+    GetBlockHash,
+    GetClassHashAt,
+    GetExecutionInfo,
+    Keccak,
+    LibraryCall,
+    ReplaceClass,
+    Secp256k1,
+    Secp256r1,
+    SendMessageToL1,
+    Sha256ProcessBlock,
+    StorageRead,
+    StorageWrite,
+}
+
+impl Syscall {
+    pub const ALL: [Syscall; 15] = [
+        Syscall::CallContract,
+        Syscall::Deploy,
+        Syscall::EmitEvent,
+        Syscall::GetBlockHash,
+        Syscall::GetClassHashAt,
+        Syscall::GetExecutionInfo,
+        Syscall::Keccak,
+        Syscall::LibraryCall,
+        Syscall::ReplaceClass,
+        Syscall::Secp256k1,
+        Syscall::Secp256r1,
+        Syscall::SendMessageToL1,
+        Syscall::Sha256ProcessBlock,
+        Syscall::StorageRead,
+        Syscall::StorageWrite,
+    ];
+
+    /// Returns the name of the syscall, as accepted by [`Syscall::from_str`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Syscall::CallContract => "call_contract",
+            Syscall::Deploy => "deploy",
+            Syscall::EmitEvent => "emit_event",
+            Syscall::GetBlockHash => "get_block_hash",
+            Syscall::GetClassHashAt => "get_class_hash_at",
+            Syscall::GetExecutionInfo => "get_execution_info",
+            Syscall::Keccak => "keccak",
+            Syscall::LibraryCall => "library_call",
+            Syscall::ReplaceClass => "replace_class",
+            Syscall::Secp256k1 => "secp256k1",
+            Syscall::Secp256r1 => "secp256r1",
+            Syscall::SendMessageToL1 => "send_message_to_l1",
+            Syscall::Sha256ProcessBlock => "sha256_process_block",
+            Syscall::StorageRead => "storage_read",
+            Syscall::StorageWrite => "storage_write",
+        }
+    }
+
+    /// Returns whether the Sierra libfunc with the given generic id invokes this syscall.
+    ///
+    /// Syscall libfuncs are named after the syscall with a `_syscall` suffix, e.g.
+    /// `get_block_hash_syscall` or `get_execution_info_v2_syscall`.
+    pub fn matches_libfunc(&self, libfunc: &str) -> bool {
+        match libfunc.strip_suffix("_syscall") {
+            Some(name) => name == self.name() || name.starts_with(&format!("{}_", self.name())),
+            None => false,
+        }
+    }
+}
+
+impl fmt::Display for Syscall {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+#[derive(Debug, thiserror::Error)]
+#[error("unknown syscall '{0}'")]
+pub struct UnknownSyscall(String);
+
+impl FromStr for Syscall {
+    type Err = UnknownSyscall;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Syscall::ALL
+            .into_iter()
+            .find(|syscall| syscall.name() == s)
+            .ok_or_else(|| UnknownSyscall(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Syscall;
+
+    #[test]
+    fn syscall_matches_libfunc() {
+        assert!(Syscall::GetBlockHash.matches_libfunc("get_block_hash_syscall"));
+        assert!(Syscall::GetExecutionInfo.matches_libfunc("get_execution_info_v2_syscall"));
+        assert!(Syscall::Secp256k1.matches_libfunc("secp256k1_mul_syscall"));
+        assert!(!Syscall::Deploy.matches_libfunc("deploy"));
+        assert!(!Syscall::StorageRead.matches_libfunc("storage_write_syscall"));
+    }
+
+    #[test]
+    fn parse_syscall() {
+        for syscall in Syscall::ALL {
+            assert_eq!(syscall.name().parse::<Syscall>().unwrap(), syscall);
+        }
+        assert!("get_block_number".parse::<Syscall>().is_err());
+    }
+}
@@ -19,7 +19,9 @@ pub struct OrderedEvent {
 /// `starknet_getEvents` API, [EventFilter][starknet::core::types::EventFilter].
 ///
 /// There JSON-RPC specification does not specify the format of the continuation token,
-/// so how the node should handle it is implementation specific.
+/// so how the node should handle it is implementation specific. The token is opaque to the
+/// clients, and is prefixed with its format version so that tokens issued by a node with a
+/// different format are rejected instead of being misinterpreted.
 #[derive(PartialEq, Eq, Debug, Clone, Default)]
 pub struct ContinuationToken {
     /// The block number to continue from.
@@ -28,6 +30,9 @@ pub struct ContinuationToken {
     pub txn_n: u64,
     /// The event number within the transaction to continue from.
     pub event_n: u64,
+    /// The hash of the filter the token was issued for. A token can only be used to continue a
+    /// query with the same filter.
+    pub filter_hash: Felt,
 }
 
 #[derive(PartialEq, Eq, Debug, thiserror::Error)]
@@ -36,28 +41,50 @@ pub enum ContinuationTokenError {
     InvalidToken,
     #[error("Invalid format: {0}")]
     ParseFailed(ParseIntError),
+    #[error("Unsupported token version {0}")]
+    UnsupportedVersion(u64),
 }
 
 impl ContinuationToken {
+    /// The version of the format of the tokens issued by the node.
+    pub const VERSION: u64 = 1;
+
     pub fn parse(token: &str) -> Result<Self, ContinuationTokenError> {
         let arr: Vec<&str> = token.split(',').collect();
-        if arr.len() != 3 {
+        if arr.len() != 5 {
             return Err(ContinuationTokenError::InvalidToken);
         }
-        let block_n =
+
+        let version =
             u64::from_str_radix(arr[0], 16).map_err(ContinuationTokenError::ParseFailed)?;
-        let receipt_n =
+        if version != Self::VERSION {
+            return Err(ContinuationTokenError::UnsupportedVersion(version));
+        }
+
+        let block_n =
             u64::from_str_radix(arr[1], 16).map_err(ContinuationTokenError::ParseFailed)?;
-        let event_n =
+        let receipt_n =
             u64::from_str_radix(arr[2], 16).map_err(ContinuationTokenError::ParseFailed)?;
+        let event_n =
+            u64::from_str_radix(arr[3], 16).map_err(ContinuationTokenError::ParseFailed)?;
+        let filter_hash =
+            Felt::from_hex(arr[4]).map_err(|_| ContinuationTokenError::InvalidToken)?;
 
-        Ok(ContinuationToken { block_n, txn_n: receipt_n, event_n })
+        Ok(ContinuationToken { block_n, txn_n: receipt_n, event_n, filter_hash })
     }
 }
 
 impl fmt::Display for ContinuationToken {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:x},{:x},{:x}", self.block_n, self.txn_n, self.event_n)
+        write!(
+            f,
+            "{:x},{:x},{:x},{:x},{:#x}",
+            Self::VERSION,
+            self.block_n,
+            self.txn_n,
+            self.event_n,
+            self.filter_hash
+        )
     }
 }
 
@@ -118,12 +145,12 @@ mod test {
 
     #[test]
     fn to_string_works() {
-        fn helper(block_n: u64, txn_n: u64, event_n: u64) -> String {
-            ContinuationToken { block_n, txn_n, event_n }.to_string()
+        fn helper(block_n: u64, txn_n: u64, event_n: u64, filter_hash: Felt) -> String {
+            ContinuationToken { block_n, txn_n, event_n, filter_hash }.to_string()
         }
 
-        assert_eq!(helper(0, 0, 0), "0,0,0");
-        assert_eq!(helper(30, 255, 4), "1e,ff,4");
+        assert_eq!(helper(0, 0, 0, Felt::ZERO), "1,0,0,0,0x0");
+        assert_eq!(helper(30, 255, 4, Felt::from(0xabc)), "1,1e,ff,4,0xabc");
     }
 
     #[test]
@@ -131,8 +158,16 @@ mod test {
         fn helper(token: &str) -> ContinuationToken {
             ContinuationToken::parse(token).unwrap()
         }
-        assert_eq!(helper("0,0,0"), ContinuationToken { block_n: 0, txn_n: 0, event_n: 0 });
-        assert_eq!(helper("1e,ff,4"), ContinuationToken { block_n: 30, txn_n: 255, event_n: 4 });
+        assert_eq!(helper("1,0,0,0,0x0"), ContinuationToken::default());
+        assert_eq!(
+            helper("1,1e,ff,4,0xabc"),
+            ContinuationToken {
+                block_n: 30,
+                txn_n: 255,
+                event_n: 4,
+                filter_hash: Felt::from(0xabc)
+            }
+        );
     }
 
     #[test]
@@ -145,24 +180,33 @@ mod test {
             ContinuationToken::parse("0,").unwrap_err(),
             ContinuationTokenError::InvalidToken
         );
+        // tokens of the previous format, without version and filter hash
+        assert_eq!(
+            ContinuationToken::parse("1e,ff,4").unwrap_err(),
+            ContinuationTokenError::InvalidToken
+        );
         assert_eq!(
-            ContinuationToken::parse("0,0").unwrap_err(),
+            ContinuationToken::parse("1,1e,ff,4,0xzz").unwrap_err(),
             ContinuationTokenError::InvalidToken
         );
+        assert_eq!(
+            ContinuationToken::parse("2,1e,ff,4,0xabc").unwrap_err(),
+            ContinuationTokenError::UnsupportedVersion(2)
+        );
     }
 
     #[test]
     fn parse_u64_should_fail() {
         matches!(
-            ContinuationToken::parse("2y,100,4").unwrap_err(),
+            ContinuationToken::parse("1,2y,100,4,0x0").unwrap_err(),
             ContinuationTokenError::ParseFailed(_)
         );
         matches!(
-            ContinuationToken::parse("30,255g,4").unwrap_err(),
+            ContinuationToken::parse("1,30,255g,4,0x0").unwrap_err(),
             ContinuationTokenError::ParseFailed(_)
         );
         matches!(
-            ContinuationToken::parse("244,1,fv").unwrap_err(),
+            ContinuationToken::parse("1,244,1,fv,0x0").unwrap_err(),
             ContinuationTokenError::ParseFailed(_)
         );
     }
@@ -175,12 +219,13 @@ mod test {
             assert_eq!(s, "test_token")
         });
 
-        let regular_token = "1e,ff,4";
+        let regular_token = "1,1e,ff,4,0xabc";
         let parsed = MaybeForkedContinuationToken::parse(regular_token).unwrap();
         assert_matches!(parsed, MaybeForkedContinuationToken::Token(t) => {
             assert_eq!(t.block_n, 30);
             assert_eq!(t.txn_n, 255);
             assert_eq!(t.event_n, 4);
+            assert_eq!(t.filter_hash, Felt::from(0xabc));
         });
     }
 }
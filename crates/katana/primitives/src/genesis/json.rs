@@ -1,5 +1,5 @@
 //! JSON representation of the genesis configuration. Used to deserialize the genesis configuration
-//! from a JSON file.
+//! from a JSON file, or from a TOML file with the same structure.
 
 use std::collections::{btree_map, hash_map, BTreeMap, HashMap};
 use std::fs::File;
@@ -175,6 +175,9 @@ pub enum GenesisJsonError {
     #[error(transparent)]
     ParsingError(#[from] serde_json::Error),
 
+    #[error(transparent)]
+    TomlParsingError(#[from] toml::de::Error),
+
     #[error(transparent)]
     ComputeClassHash(#[from] ComputeClassHashError),
 
@@ -222,6 +225,8 @@ pub enum GenesisJsonError {
 /// (eg, using `serde_json`).
 ///
 /// The path of the class artifact are computed **relative** to the JSON file.
+///
+/// The same configuration can also be written in TOML, in a file with the `.toml` extension.
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct GenesisJson {
@@ -243,16 +248,25 @@ impl GenesisJson {
     /// Load the genesis configuration from a JSON file at the given `path` and resolve all the
     /// class paths to their corresponding class definitions. The paths will be resolved relative
     /// to the JSON file itself.
+    ///
+    /// Files with the `.toml` extension are parsed as TOML.
     pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, GenesisJsonError> {
         let mut path = path.as_ref().to_path_buf();
 
         let file = File::open(&path)
             .map_err(|source| GenesisJsonError::FileNotFound { path: path.clone(), source })?;
 
+        let mut genesis: Self = if path.extension().is_some_and(|ext| ext == "toml") {
+            let content = io::read_to_string(file)
+                .map_err(|source| GenesisJsonError::FileNotFound { path: path.clone(), source })?;
+            toml::from_str(&content)?
+        } else {
+            serde_json::from_reader(BufReader::new(file))?
+        };
+
         // Remove the file name from the path to get the base path.
         path.pop();
 
-        let mut genesis: Self = serde_json::from_reader(BufReader::new(file))?;
         // resolves the class paths, if any
         genesis.resolve_class_artifacts(path)?;
 
@@ -704,6 +718,13 @@ mod tests {
         }
     }
 
+    #[test]
+    fn genesis_load_from_toml() {
+        let json = GenesisJson::load("./src/genesis/test-genesis.json").unwrap();
+        let toml = GenesisJson::load("./src/genesis/test-genesis.toml").unwrap();
+        assert_eq!(toml, json);
+    }
+
     #[test]
     fn genesis_load_from_json() {
         let path = PathBuf::from("./src/genesis/test-genesis.json");
@@ -1,6 +1,7 @@
 pub mod allocation;
 pub mod constant;
 pub mod json;
+pub mod snapshot;
 
 use std::collections::BTreeMap;
 use std::fmt::Debug;
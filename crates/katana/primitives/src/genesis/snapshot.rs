@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::sync::Arc;
+
+use alloy_primitives::U256;
+use serde::{Deserialize, Serialize};
+
+use super::allocation::{
+    GenesisAccount, GenesisAccountAlloc, GenesisAllocation, GenesisContractAlloc,
+};
+use super::constant::{
+    get_fee_token_balance_base_storage_address, DEFAULT_ETH_FEE_TOKEN_ADDRESS,
+    DEFAULT_STRK_FEE_TOKEN_ADDRESS, DEFAULT_UDC_ADDRESS,
+};
+use super::{Genesis, GenesisClass};
+use crate::class::{ClassHash, CompiledClass, CompiledClassHash, FlattenedSierraClass};
+use crate::contract::{ContractAddress, StorageKey, StorageValue};
+use crate::state::StateUpdatesWithDeclaredClasses;
+use crate::Felt;
+
+/// A class of the [GenesisSnapshot], including its definitions unlike [GenesisClass] when
+/// serialized.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SnapshotClass {
+    pub compiled_class_hash: CompiledClassHash,
+    pub casm: CompiledClass,
+    pub sierra: Option<FlattenedSierraClass>,
+}
+
+/// The state of a chain, exported to be allocated in the genesis of a new chain.
+///
+/// It allows to boot a chain directly into a state that would otherwise require executing
+/// transactions, eg a migrated world. The fee tokens and the UDC are not part of the snapshot, as
+/// they are always deployed by the chain spec. The fee token balances are carried by the
+/// allocations instead.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct GenesisSnapshot {
+    /// The declared classes.
+    pub classes: BTreeMap<ClassHash, SnapshotClass>,
+    /// The deployed contracts, including the accounts.
+    pub allocations: BTreeMap<ContractAddress, GenesisContractAlloc>,
+}
+
+impl GenesisSnapshot {
+    /// Creates the snapshot of the state resulting from all the state updates of a chain started
+    /// from `genesis`.
+    ///
+    /// The classes of `genesis` are left out, the new chain is expected to be started from the
+    /// same genesis.
+    pub fn new(genesis: &Genesis, states: StateUpdatesWithDeclaredClasses) -> Self {
+        let StateUpdatesWithDeclaredClasses {
+            state_updates,
+            mut declared_sierra_classes,
+            declared_compiled_classes,
+        } = states;
+
+        let classes = declared_compiled_classes
+            .into_iter()
+            .filter(|(hash, _)| !genesis.classes.contains_key(hash))
+            .map(|(hash, casm)| {
+                // Legacy classes don't have a compiled class hash.
+                let compiled_class_hash =
+                    state_updates.declared_classes.get(&hash).copied().unwrap_or(hash);
+                let sierra = declared_sierra_classes.remove(&hash);
+                (hash, SnapshotClass { compiled_class_hash, casm, sierra })
+            })
+            .collect();
+
+        let fee_token_storage =
+            state_updates.storage_updates.get(&DEFAULT_ETH_FEE_TOKEN_ADDRESS).cloned();
+
+        let mut contracts = state_updates.deployed_contracts;
+        contracts.extend(state_updates.replaced_classes);
+
+        let mut storage_updates = state_updates.storage_updates;
+        let mut allocations = BTreeMap::new();
+
+        for (address, class_hash) in contracts {
+            if [DEFAULT_ETH_FEE_TOKEN_ADDRESS, DEFAULT_STRK_FEE_TOKEN_ADDRESS, DEFAULT_UDC_ADDRESS]
+                .contains(&address)
+            {
+                continue;
+            }
+
+            let contract = GenesisContractAlloc {
+                class_hash: Some(class_hash),
+                balance: fee_token_storage.as_ref().and_then(|s| balance_of(s, address)),
+                nonce: state_updates.nonce_updates.get(&address).copied(),
+                storage: storage_updates.remove(&address),
+            };
+
+            allocations.insert(address, contract);
+        }
+
+        Self { classes, allocations }
+    }
+
+    /// Declares the classes and allocates the contracts of the snapshot in `genesis`.
+    ///
+    /// The accounts already allocated in `genesis` are kept as accounts with the nonce, storage
+    /// and balance of the snapshot, so their keys remain available in the new chain. Any other
+    /// allocation at the same address is replaced.
+    pub fn apply(self, genesis: &mut Genesis) {
+        for (hash, class) in self.classes {
+            genesis.classes.entry(hash).or_insert_with(|| GenesisClass {
+                compiled_class_hash: class.compiled_class_hash,
+                casm: Arc::new(class.casm),
+                sierra: class.sierra.map(Arc::new),
+            });
+        }
+
+        for (address, contract) in self.allocations {
+            if let Some(GenesisAllocation::Account(account)) = genesis.allocations.get_mut(&address)
+            {
+                let account: &mut GenesisAccount = match account {
+                    GenesisAccountAlloc::Account(a) => a,
+                    GenesisAccountAlloc::DevAccount(a) => &mut **a,
+                };
+
+                account.nonce = contract.nonce.or(account.nonce);
+                account.storage = contract.storage.or(account.storage.take());
+                account.balance = contract.balance.or(account.balance);
+                continue;
+            }
+
+            genesis.allocations.insert(address, GenesisAllocation::Contract(contract));
+        }
+    }
+
+    /// Loads a snapshot from a JSON file.
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Self> {
+        let content = std::fs::read(path.as_ref())?;
+        Ok(serde_json::from_slice(&content)?)
+    }
+
+    /// Writes the snapshot to a JSON file.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<()> {
+        std::fs::write(path.as_ref(), serde_json::to_vec(self)?)?;
+        Ok(())
+    }
+}
+
+/// Reads the balance of `address` in the storage of a fee token.
+fn balance_of(
+    storage: &BTreeMap<StorageKey, StorageValue>,
+    address: ContractAddress,
+) -> Option<U256> {
+    let base = get_fee_token_balance_base_storage_address(address);
+    let low = storage.get(&base)?;
+    let high = storage.get(&(base + Felt::ONE)).copied().unwrap_or_default();
+
+    let low = U256::from_be_bytes(low.to_bytes_be());
+    let high = U256::from_be_bytes(high.to_bytes_be());
+    Some((high << 128) | low)
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::macros::felt;
+
+    use super::*;
+    use crate::address;
+    use crate::chain_spec::DEV;
+    use crate::genesis::constant::DEFAULT_ACCOUNT_CLASS_HASH;
+
+    #[test]
+    fn snapshot_roundtrip_into_genesis() {
+        let mut states = DEV.state_updates();
+
+        let (account, _) = DEV.genesis.accounts().next().unwrap();
+        let account = *account;
+        let world = address!("0x1234");
+        let world_class = felt!("0x999");
+
+        states.state_updates.deployed_contracts.insert(world, world_class);
+        states.state_updates.declared_classes.insert(world_class, felt!("0x888"));
+        states.declared_compiled_classes.insert(
+            world_class,
+            states.declared_compiled_classes[&DEFAULT_ACCOUNT_CLASS_HASH].clone(),
+        );
+        states
+            .state_updates
+            .storage_updates
+            .entry(world)
+            .or_default()
+            .insert(felt!("0x1"), felt!("0x2"));
+        states.state_updates.nonce_updates.insert(account, felt!("0x5"));
+
+        let snapshot = GenesisSnapshot::new(&DEV.genesis, states);
+        assert!(!snapshot.classes.contains_key(&DEFAULT_ACCOUNT_CLASS_HASH));
+
+        // The fee tokens and the UDC are deployed by the chain spec.
+        assert!(!snapshot.allocations.contains_key(&DEFAULT_ETH_FEE_TOKEN_ADDRESS));
+        assert!(!snapshot.allocations.contains_key(&DEFAULT_UDC_ADDRESS));
+        assert_eq!(snapshot.classes[&world_class].compiled_class_hash, felt!("0x888"));
+
+        let json = serde_json::to_string(&snapshot).unwrap();
+        let snapshot: GenesisSnapshot = serde_json::from_str(&json).unwrap();
+
+        let mut genesis = DEV.genesis.clone();
+        snapshot.apply(&mut genesis);
+
+        let alloc = &genesis.allocations[&world];
+        assert_eq!(alloc.class_hash(), Some(world_class));
+        assert_eq!(alloc.storage().unwrap().get(&felt!("0x1")), Some(&felt!("0x2")));
+        assert!(genesis.classes.contains_key(&world_class));
+
+        // Accounts keep their private key, and carry their nonce and balance.
+        let alloc = &genesis.allocations[&account];
+        assert_eq!(alloc.nonce(), Some(felt!("0x5")));
+        assert_eq!(alloc.balance(), DEV.genesis.allocations[&account].balance());
+        assert!(matches!(alloc, GenesisAllocation::Account(GenesisAccountAlloc::DevAccount(_))));
+    }
+}
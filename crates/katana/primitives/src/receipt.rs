@@ -243,3 +243,106 @@ impl ReceiptWithTxHash {
         hash::Poseidon::hash_array(&elements)
     }
 }
+
+/// The size in bytes of an [`EventsBloom`].
+pub const EVENTS_BLOOM_SIZE: usize = 256;
+
+/// A bloom filter over the emitting addresses and the keys of the events of a block.
+///
+/// The keys are added along with their position in the event, so that the bloom can tell whether
+/// a block may contain events matching a filter on a specific key. A bloom never misses an
+/// address or key that was added to it, but may report ones that weren't.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EventsBloom(pub [u8; EVENTS_BLOOM_SIZE]);
+
+impl EventsBloom {
+    /// Creates the bloom of the events emitted in the given receipts.
+    pub fn from_receipts<'a>(receipts: impl IntoIterator<Item = &'a Receipt>) -> Self {
+        let mut bloom = Self::default();
+        receipts.into_iter().flat_map(|r| r.events()).for_each(|e| bloom.accrue_event(e));
+        bloom
+    }
+
+    /// Adds the emitting address and the keys of an event.
+    pub fn accrue_event(&mut self, event: &Event) {
+        self.accrue(&address_input(event.from_address));
+        for (position, key) in event.keys.iter().enumerate() {
+            self.accrue(&key_input(position, *key));
+        }
+    }
+
+    /// Returns `false` if no event of the bloom was emitted by `address`.
+    pub fn contains_address(&self, address: ContractAddress) -> bool {
+        self.contains(&address_input(address))
+    }
+
+    /// Returns `false` if no event of the bloom has `key` at the given position.
+    pub fn contains_key(&self, position: usize, key: Felt) -> bool {
+        self.contains(&key_input(position, key))
+    }
+
+    fn accrue(&mut self, input: &[u8]) {
+        for (byte, mask) in bloom_bits(input) {
+            self.0[byte] |= mask;
+        }
+    }
+
+    fn contains(&self, input: &[u8]) -> bool {
+        bloom_bits(input).into_iter().all(|(byte, mask)| self.0[byte] & mask != 0)
+    }
+}
+
+impl Default for EventsBloom {
+    fn default() -> Self {
+        Self([0; EVENTS_BLOOM_SIZE])
+    }
+}
+
+fn address_input(address: ContractAddress) -> Vec<u8> {
+    address.to_bytes_be().to_vec()
+}
+
+fn key_input(position: usize, key: Felt) -> Vec<u8> {
+    let mut input = key.to_bytes_be().to_vec();
+    input.extend_from_slice(&(position as u64).to_be_bytes());
+    input
+}
+
+// Sets 3 of the 2048 bits of the bloom per input, like the logs bloom of Ethereum. The bits are
+// taken from the low bytes of the hash as the high ones of a Starknet keccak are masked.
+fn bloom_bits(input: &[u8]) -> [(usize, u8); 3] {
+    let hash = starknet_keccak(input).to_bytes_be();
+    std::array::from_fn(|i| {
+        let bit = u16::from_be_bytes([hash[26 + 2 * i], hash[27 + 2 * i]]) as usize % 2048;
+        (EVENTS_BLOOM_SIZE - 1 - bit / 8, 1 << (bit % 8))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Event, EventsBloom};
+    use crate::contract::ContractAddress;
+    use crate::{address, felt};
+
+    #[test]
+    fn events_bloom() {
+        let event = Event {
+            from_address: address!("0x1234"),
+            keys: vec![felt!("0xa"), felt!("0xb")],
+            data: vec![felt!("0xc")],
+        };
+        let mut bloom = EventsBloom::default();
+        bloom.accrue_event(&event);
+
+        assert!(bloom.contains_address(address!("0x1234")));
+        assert!(bloom.contains_key(0, felt!("0xa")));
+        assert!(bloom.contains_key(1, felt!("0xb")));
+
+        // the keys are only added at their position, and the data isn't added
+        assert!(!bloom.contains_key(1, felt!("0xa")));
+        assert!(!bloom.contains_key(0, felt!("0xc")));
+        assert!(!bloom.contains_address(address!("0x5678")));
+
+        assert!(!EventsBloom::default().contains_address(address!("0x1234")));
+    }
+}
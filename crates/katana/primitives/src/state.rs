@@ -46,6 +46,44 @@ impl StateUpdates {
 
         len
     }
+
+    /// Merges the updates of `other`, made after the ones of `self`, into `self`.
+    ///
+    /// A contract deployed then replaced within the merged updates is reported as deployed with
+    /// its latest class.
+    pub fn merge(&mut self, other: StateUpdates) {
+        self.nonce_updates.extend(other.nonce_updates);
+        self.deployed_contracts.extend(other.deployed_contracts);
+        self.declared_classes.extend(other.declared_classes);
+        self.deprecated_declared_classes.extend(other.deprecated_declared_classes);
+
+        for (address, entries) in other.storage_updates {
+            self.storage_updates.entry(address).or_default().extend(entries);
+        }
+
+        for (address, class_hash) in other.replaced_classes {
+            match self.deployed_contracts.get_mut(&address) {
+                Some(hash) => *hash = class_hash,
+                None => {
+                    self.replaced_classes.insert(address, class_hash);
+                }
+            }
+        }
+    }
+}
+
+/// A change made to a single storage slot of a contract.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StorageDiff {
+    /// The contract whose storage was changed.
+    pub contract_address: ContractAddress,
+    /// The storage slot that was changed.
+    pub key: StorageKey,
+    /// The value of the slot before the change.
+    pub old_value: StorageValue,
+    /// The value of the slot after the change.
+    pub new_value: StorageValue,
 }
 
 /// State update with declared classes definition.
@@ -105,3 +143,35 @@ pub fn compute_state_diff_hash(states: StateUpdates) -> Felt {
 
     hash::Poseidon::hash_array(&elements)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::address;
+
+    #[test]
+    fn merge_state_updates() {
+        let (a, b) = (address!("0x1"), address!("0x2"));
+
+        let mut updates = StateUpdates::default();
+        updates.nonce_updates.insert(a, Felt::ONE);
+        updates.deployed_contracts.insert(b, Felt::ONE);
+        let storage = BTreeMap::from([(Felt::ONE, Felt::ONE), (Felt::TWO, Felt::ONE)]);
+        updates.storage_updates.insert(a, storage);
+
+        let mut next = StateUpdates::default();
+        next.nonce_updates.insert(a, Felt::TWO);
+        next.storage_updates.insert(a, BTreeMap::from([(Felt::TWO, Felt::TWO)]));
+        next.replaced_classes.insert(a, Felt::THREE);
+        next.replaced_classes.insert(b, Felt::TWO);
+
+        updates.merge(next);
+
+        assert_eq!(updates.nonce_updates, BTreeMap::from([(a, Felt::TWO)]));
+        let storage = BTreeMap::from([(Felt::ONE, Felt::ONE), (Felt::TWO, Felt::TWO)]);
+        assert_eq!(updates.storage_updates, BTreeMap::from([(a, storage)]));
+        // the contract deployed in the range is reported with the class it was replaced with
+        assert_eq!(updates.deployed_contracts, BTreeMap::from([(b, Felt::TWO)]));
+        assert_eq!(updates.replaced_classes, BTreeMap::from([(a, Felt::THREE)]));
+    }
+}
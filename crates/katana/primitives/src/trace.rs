@@ -39,6 +39,18 @@ pub struct TxExecInfo {
     pub r#type: TxType,
 }
 
+impl TxExecInfo {
+    /// Returns the Sierra gas consumed by the validation, execution and fee transfer calls of the
+    /// transaction. The calls to legacy classes don't consume any.
+    pub fn sierra_gas_consumed(&self) -> u128 {
+        [&self.validate_call_info, &self.execute_call_info, &self.fee_transfer_call_info]
+            .into_iter()
+            .flatten()
+            .map(|call| call.gas_consumed)
+            .sum()
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -47,6 +59,20 @@ pub struct TxResources {
     pub vm_resources: ExecutionResources,
     pub data_availability: L1Gas,
     pub total_gas_consumed: L1Gas,
+    /// The state changes the transaction is charged for.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub state_diff_size: StateDiffSize,
+}
+
+/// The number of state changes of a transaction, which make up the state diff it publishes on L1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StateDiffSize {
+    pub n_storage_updates: usize,
+    pub n_class_hash_updates: usize,
+    pub n_compiled_class_hash_updates: usize,
+    pub n_modified_contracts: usize,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
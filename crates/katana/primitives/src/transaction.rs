@@ -71,6 +71,23 @@ impl<'a> From<TxRef<'a>> for Tx {
     }
 }
 
+impl Tx {
+    /// Returns the signature of the transaction, which is always empty for L1 handler
+    /// transactions.
+    pub fn signature(&self) -> &[Felt] {
+        match self {
+            Tx::Invoke(InvokeTx::V1(tx)) => &tx.signature,
+            Tx::Invoke(InvokeTx::V3(tx)) => &tx.signature,
+            Tx::Declare(DeclareTx::V1(tx)) => &tx.signature,
+            Tx::Declare(DeclareTx::V2(tx)) => &tx.signature,
+            Tx::Declare(DeclareTx::V3(tx)) => &tx.signature,
+            Tx::DeployAccount(DeployAccountTx::V1(tx)) => &tx.signature,
+            Tx::DeployAccount(DeployAccountTx::V3(tx)) => &tx.signature,
+            Tx::L1Handler(_) => &[],
+        }
+    }
+}
+
 /// Represents a transaction that has all the necessary data to be executed.
 #[derive(Debug, Clone, From)]
 pub enum ExecutableTx {
@@ -98,6 +115,37 @@ impl ExecutableTx {
             ExecutableTx::DeployAccount(_) => TxType::DeployAccount,
         }
     }
+
+    /// Returns the address of the account sending the transaction, or the address of the
+    /// receiving contract for L1 handler transactions.
+    pub fn sender_address(&self) -> ContractAddress {
+        match self {
+            ExecutableTx::Invoke(tx) => match tx {
+                InvokeTx::V1(v1) => v1.sender_address,
+                InvokeTx::V3(v3) => v3.sender_address,
+            },
+            ExecutableTx::L1Handler(tx) => tx.contract_address,
+            ExecutableTx::Declare(tx) => match &tx.transaction {
+                DeclareTx::V1(v1) => v1.sender_address,
+                DeclareTx::V2(v2) => v2.sender_address,
+                DeclareTx::V3(v3) => v3.sender_address,
+            },
+            ExecutableTx::DeployAccount(tx) => tx.contract_address(),
+        }
+    }
+
+    /// Returns the resource bounds of the transaction, for V3 transactions.
+    pub fn resource_bounds(&self) -> Option<&ResourceBoundsMapping> {
+        match self {
+            ExecutableTx::Invoke(InvokeTx::V3(tx)) => Some(&tx.resource_bounds),
+            ExecutableTx::Declare(tx) => match &tx.transaction {
+                DeclareTx::V3(tx) => Some(&tx.resource_bounds),
+                _ => None,
+            },
+            ExecutableTx::DeployAccount(DeployAccountTx::V3(tx)) => Some(&tx.resource_bounds),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Debug, Clone, AsRef, Deref)]
@@ -1,7 +1,13 @@
+use std::collections::BTreeMap;
+
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
+use katana_primitives::block::GasPrices;
+use katana_primitives::contract::{StorageKey, StorageValue};
+use katana_primitives::fee::PriceUnit;
 use katana_primitives::Felt;
-use katana_rpc_types::account::Account;
+use katana_rpc_types::account::{Account, AccountSession, SessionPolicy};
+use katana_rpc_types::transaction::BroadcastedTx;
 
 #[cfg_attr(not(feature = "client"), rpc(server, namespace = "dev"))]
 #[cfg_attr(feature = "client", rpc(client, server, namespace = "dev"))]
@@ -18,10 +24,93 @@ pub trait DevApi {
     #[method(name = "increaseNextBlockTimestamp")]
     async fn increase_next_block_timestamp(&self, timestamp: u64) -> RpcResult<()>;
 
+    /// Moves the clock of the node forward by `seconds`, for the next block and all the blocks
+    /// after it.
+    #[method(name = "increaseTime")]
+    async fn increase_time(&self, seconds: u64) -> RpcResult<()>;
+
+    /// Sets the L1 gas and data gas prices of the next blocks.
+    #[method(name = "setBlockGasPrice")]
+    async fn set_block_gas_price(
+        &self,
+        gas_prices: GasPrices,
+        data_gas_prices: GasPrices,
+    ) -> RpcResult<()>;
+
     #[method(name = "setStorageAt")]
     async fn set_storage_at(&self, contract_address: Felt, key: Felt, value: Felt)
     -> RpcResult<()>;
 
+    /// Returns all the storage entries of a contract in the latest state.
+    #[method(name = "dumpStorage")]
+    async fn dump_storage(
+        &self,
+        contract_address: Felt,
+    ) -> RpcResult<BTreeMap<StorageKey, StorageValue>>;
+
+    /// Sets the given storage entries of a contract in the latest state, eg. to load the storage
+    /// returned by `dev_dumpStorage` on another instance.
+    #[method(name = "loadStorage")]
+    async fn load_storage(
+        &self,
+        contract_address: Felt,
+        entries: BTreeMap<StorageKey, StorageValue>,
+    ) -> RpcResult<()>;
+
+    /// Takes a snapshot of the chain, its blocks and its state, that it can be reverted to with
+    /// `dev_revert`. Returns the id of the snapshot.
+    #[method(name = "snapshot")]
+    async fn snapshot(&self) -> RpcResult<u64>;
+
+    /// Reverts the chain to a snapshot taken with `dev_snapshot`, removing the blocks mined since.
+    /// The snapshot, and the ones taken after it, can't be reverted to anymore.
+    #[method(name = "revert")]
+    async fn revert(&self, snapshot_id: u64) -> RpcResult<()>;
+
+    /// Replaces the last `depth` blocks with as many new blocks, to simulate a chain
+    /// reorganization. The first new block includes `new_txs`, the others are empty. Declare
+    /// transactions are not supported.
+    #[method(name = "reorg")]
+    async fn reorg(&self, depth: u64, new_txs: Vec<BroadcastedTx>) -> RpcResult<()>;
+
+    /// Starts impersonating an account: its transactions are executed without running its
+    /// validation logic, so they can be sent without a valid signature.
+    #[method(name = "impersonateAccount")]
+    async fn impersonate_account(&self, address: Felt) -> RpcResult<()>;
+
+    /// Stops impersonating an account previously impersonated with `dev_impersonateAccount`.
+    #[method(name = "stopImpersonating")]
+    async fn stop_impersonating(&self, address: Felt) -> RpcResult<()>;
+
+    /// Mints a session key for a predeployed account. Until `expires_at`, a block timestamp, the
+    /// transactions of the account signed with the session key are accepted without running the
+    /// account validation logic, as long as all their calls are allowed by `policies`.
+    ///
+    /// The sessions are enforced by the node, whatever the account class. The dev accounts
+    /// predeployed as Controller accounts (`--slot.dev-controllers`) also support the sessions
+    /// of the Controller class, authorized with their dev key.
+    #[method(name = "mintSession")]
+    async fn mint_session(
+        &self,
+        address: Felt,
+        policies: Vec<SessionPolicy>,
+        expires_at: u64,
+    ) -> RpcResult<AccountSession>;
+
+    /// Revokes all the sessions minted for an account with `dev_mintSession`.
+    #[method(name = "revokeSessions")]
+    async fn revoke_sessions(&self, address: Felt) -> RpcResult<()>;
+
+    /// Sets the balance of an address in the fee token of the given unit, ie. ETH for `WEI` and
+    /// STRK for `FRI`.
+    #[method(name = "setBalance")]
+    async fn set_balance(&self, address: Felt, amount: Felt, unit: PriceUnit) -> RpcResult<()>;
+
+    /// Reloads the settings of the node that can be changed without restarting it, as on
+    /// `SIGHUP`.
+    #[method(name = "reloadConfig")]
+    async fn reload_config(&self) -> RpcResult<()>;
+
     #[method(name = "predeployedAccounts")]
     async fn predeployed_accounts(&self) -> RpcResult<Vec<Account>>;
 }
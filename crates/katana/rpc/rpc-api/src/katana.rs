@@ -0,0 +1,154 @@
+use jsonrpsee::core::RpcResult;
+use jsonrpsee::proc_macros::rpc;
+use katana_core::backend::dump::StateDump;
+use katana_primitives::block::{BlockIdOrTag, BlockNumber};
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::trace::{CallInfo, TxExecInfo};
+use katana_primitives::transaction::TxHash;
+use katana_rpc_types::block::{BlockHashVerification, BlockHeaderExtension, BlocksPage};
+use katana_rpc_types::pool::{TxPoolContent, TxPoolStatus};
+use katana_rpc_types::proof::TransactionInclusionProof;
+use katana_rpc_types::receipt::{TxExecutionResources, TxResourceUsage};
+use katana_rpc_types::state_update::{StateDiffFormat, StateDiffRange, StorageDiffItem};
+use katana_rpc_types::world::{EntityStorage, WorldStateUpdate};
+use katana_rpc_types::FunctionCall;
+
+/// Katana-specific extensions to the Starknet JSON-RPC API.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "katana"))]
+#[cfg_attr(feature = "client", rpc(client, server, namespace = "katana"))]
+pub trait KatanaApi {
+    /// Returns the operator-defined header fields of a block, if the chain is configured with
+    /// any.
+    #[method(name = "getBlockHeaderExtension")]
+    async fn block_header_extension(
+        &self,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<Option<BlockHeaderExtension>>;
+
+    /// Returns the Merkle proofs that a mined transaction and its receipt are included in the
+    /// transaction and receipt commitments of their block.
+    #[method(name = "getTransactionInclusionProof")]
+    async fn transaction_inclusion_proof(
+        &self,
+        transaction_hash: TxHash,
+    ) -> RpcResult<TransactionInclusionProof>;
+
+    /// Returns the L1 gas, L1 data gas and L2 (Sierra) gas consumed by a mined transaction, along
+    /// with the Cairo steps it executed.
+    #[method(name = "getTransactionResources")]
+    async fn transaction_resources(&self, transaction_hash: TxHash) -> RpcResult<TxResourceUsage>;
+
+    /// Returns the Cairo steps, memory holes and builtin applications used by a mined
+    /// transaction, along with the number of state changes it is charged for.
+    ///
+    /// The state diff size of the transactions mined by a release that didn't record it is zero.
+    #[method(name = "getExecutionResources")]
+    async fn execution_resources(
+        &self,
+        transaction_hash: TxHash,
+    ) -> RpcResult<TxExecutionResources>;
+
+    /// Returns the mined blocks from `from` to `to` (inclusive), with the hashes of their
+    /// transactions and, if `include_receipts` is `true`, their receipts.
+    ///
+    /// At most 100 blocks are returned per call. If the range spans more mined blocks, the
+    /// returned page holds the number of the block to continue from.
+    #[method(name = "getBlocks")]
+    async fn blocks(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+        include_receipts: Option<bool>,
+    ) -> RpcResult<BlocksPage>;
+
+    /// Returns the state diffs of the mined blocks from `from_block` to `to_block` (inclusive),
+    /// aggregated into a single state diff, in the given `format` (JSON by default).
+    ///
+    /// At most 1000 blocks can be requested per call.
+    #[method(name = "getStateDiffRange")]
+    async fn state_diff_range(
+        &self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+        format: Option<StateDiffFormat>,
+    ) -> RpcResult<StateDiffRange>;
+
+    /// Recomputes the hash of a mined block from its transactions, receipts and state updates,
+    /// and compares it to the hash the block was committed with.
+    ///
+    /// The state root of the block is taken from its header. The genesis block can't be verified
+    /// as it isn't mined.
+    #[method(name = "verifyBlockHash")]
+    async fn verify_block_hash(&self, block_id: BlockIdOrTag) -> RpcResult<BlockHashVerification>;
+
+    /// Returns the state update of a mined block, along with the resources registered and the
+    /// model records written in that block by the Dojo world deployed at `world_address`.
+    ///
+    /// The world updates are decoded from the events emitted by the world, in the order they were
+    /// emitted, so that indexers don't have to fetch and decode the events of the block.
+    #[method(name = "getWorldStateUpdate")]
+    async fn world_state_update(
+        &self,
+        block_id: BlockIdOrTag,
+        world_address: ContractAddress,
+    ) -> RpcResult<WorldStateUpdate>;
+
+    /// Executes a call on top of the state of a block and returns its execution trace: the
+    /// calls it made, each with its calldata, return data, events and the Cairo steps and
+    /// builtins it used.
+    ///
+    /// The call is executed on the latest mined state when `block_id` is the pending block.
+    ///
+    /// The trace is per call, the executor doesn't record the trace of the Cairo VM (the pc and
+    /// opcode of each step). The mined transactions are traced per call by
+    /// `katana_debugTraceTransaction`, and by `starknet_simulateTransactions` before.
+    #[method(name = "debugTraceCall")]
+    async fn debug_trace_call(
+        &self,
+        request: FunctionCall,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<CallInfo>;
+
+    /// Re-executes a mined transaction and returns its execution trace: the validation, execution
+    /// and fee transfer calls it made, with the resources it used.
+    ///
+    /// The transaction is executed on the state of the parent of its block, after the
+    /// transactions preceding it in the block, with the environment of its block. Unlike
+    /// `starknet_traceTransaction`, which returns the trace recorded when the block was mined,
+    /// the trace is produced by executing the transaction again.
+    #[method(name = "debugTraceTransaction")]
+    async fn debug_trace_transaction(&self, transaction_hash: TxHash) -> RpcResult<TxExecInfo>;
+
+    /// Returns the blocks mined on top of the genesis, along with their execution output.
+    #[method(name = "dumpState")]
+    async fn dump_state(&self) -> RpcResult<StateDump>;
+
+    /// Loads the blocks of a dump returned by `katana_dumpState`, restoring the state of the
+    /// dumped chain.
+    ///
+    /// The node must be started from the same genesis as the dumped chain and must not have mined
+    /// any block yet. Only available with instant mining.
+    #[method(name = "loadState")]
+    async fn load_state(&self, dump: StateDump) -> RpcResult<()>;
+
+    /// Returns the transactions waiting in the pool: the pending ones, in the order they will be
+    /// executed, and the ones queued until the gap after their sender's nonce is filled.
+    #[method(name = "txPoolContent")]
+    async fn tx_pool_content(&self) -> RpcResult<TxPoolContent>;
+
+    /// Returns the number of pending and queued transactions in the pool.
+    #[method(name = "txPoolStatus")]
+    async fn tx_pool_status(&self) -> RpcResult<TxPoolStatus>;
+
+    /// Subscribes to the storage changes of a contract made by newly mined blocks.
+    ///
+    /// `entities` are entities of the Dojo world at `contract`: a slot is reported if it stores a
+    /// value of one of them, its addresses being computed from the layout of the model. An empty
+    /// list reports every slot of the contract.
+    #[subscription(
+        name = "subscribeStorageDiffs",
+        unsubscribe = "unsubscribeStorageDiffs",
+        item = StorageDiffItem
+    )]
+    fn subscribe_storage_diffs(&self, contract: ContractAddress, entities: Vec<EntityStorage>);
+}
@@ -1,5 +1,6 @@
 //! Starknet JSON-RPC specifications: <https://github.com/starkware-libs/starknet-specs>
 
+use alloy_primitives::B256;
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
 use katana_primitives::block::{BlockIdOrTag, BlockNumber};
@@ -10,15 +11,17 @@ use katana_rpc_types::block::{
     MaybePendingBlockWithTxs,
 };
 use katana_rpc_types::event::{EventFilterWithPage, EventsPage};
-use katana_rpc_types::message::MsgFromL1;
+use katana_rpc_types::message::{MessageStatus, MsgFromL1};
 use katana_rpc_types::receipt::TxReceiptWithBlockInfo;
+use katana_rpc_types::state_override::StateOverride;
 use katana_rpc_types::state_update::MaybePendingStateUpdate;
+use katana_rpc_types::subscription::{SubscriptionItem, SubscriptionKind, SubscriptionParams};
 use katana_rpc_types::transaction::{
     BroadcastedDeclareTx, BroadcastedDeployAccountTx, BroadcastedInvokeTx, BroadcastedTx,
     DeclareTxResult, DeployAccountTxResult, InvokeTxResult, Tx,
 };
 use katana_rpc_types::{
-    ContractClass, FeeEstimate, FeltAsHex, FunctionCall, SimulationFlag,
+    CompiledCasm, ContractClass, FeeEstimate, FeltAsHex, FunctionCall, SimulationFlag,
     SimulationFlagForEstimateFee, SyncingStatus,
 };
 use starknet::core::types::{
@@ -80,6 +83,11 @@ pub trait StarknetApi {
         transaction_hash: TxHash,
     ) -> RpcResult<TransactionStatus>;
 
+    /// Returns the statuses of the L1 handler transactions executing the messages sent by the
+    /// given transaction of the settlement chain.
+    #[method(name = "getMessagesStatus")]
+    async fn get_messages_status(&self, transaction_hash: B256) -> RpcResult<Vec<MessageStatus>>;
+
     /// Get the details and status of a submitted transaction.
     #[method(name = "getTransactionByHash")]
     async fn get_transaction_by_hash(&self, transaction_hash: TxHash) -> RpcResult<Tx>;
@@ -121,33 +129,53 @@ pub trait StarknetApi {
         contract_address: Felt,
     ) -> RpcResult<ContractClass>;
 
+    /// Get the CASM of the Sierra class with the given hash, as compiled by the node.
+    #[method(name = "getCompiledCasm")]
+    async fn get_compiled_casm(&self, class_hash: Felt) -> RpcResult<CompiledCasm>;
+
     /// Get the number of transactions in a block given a block id.
     #[method(name = "getBlockTransactionCount")]
     async fn get_block_transaction_count(&self, block_id: BlockIdOrTag) -> RpcResult<BlockTxCount>;
 
     /// Call a starknet function without creating a StarkNet transaction.
+    ///
+    /// The optional `state_override` is a Katana extension to the specification, to call the
+    /// function against a modified state, eg to read a view as if a storage slot had another
+    /// value.
     #[method(name = "call")]
     async fn call(
         &self,
         request: FunctionCall,
         block_id: BlockIdOrTag,
+        state_override: Option<StateOverride>,
     ) -> RpcResult<Vec<FeltAsHex>>;
 
     /// Estimate the fee for of StarkNet transactions.
+    ///
+    /// The transactions are estimated in order, each one on top of the state changes of the
+    /// previous ones, eg a contract can be declared then deployed in the same request.
+    ///
+    /// The optional `state_override` is a Katana extension to the specification, to estimate the
+    /// fees against a modified state, eg for accounts that are not deployed yet.
     #[method(name = "estimateFee")]
     async fn estimate_fee(
         &self,
         request: Vec<BroadcastedTx>,
         simulation_flags: Vec<SimulationFlagForEstimateFee>,
         block_id: BlockIdOrTag,
+        state_override: Option<StateOverride>,
     ) -> RpcResult<Vec<FeeEstimate>>;
 
     /// Estimate the L2 fee of a message sent on L1.
+    ///
+    /// The optional `state_override` is a Katana extension to the specification, see
+    /// `estimateFee`.
     #[method(name = "estimateMessageFee")]
     async fn estimate_message_fee(
         &self,
         message: MsgFromL1,
         block_id: BlockIdOrTag,
+        state_override: Option<StateOverride>,
     ) -> RpcResult<FeeEstimate>;
 
     /// Get the most recent accepted block number.
@@ -216,12 +244,16 @@ pub trait StarknetTraceApi {
     async fn trace_transaction(&self, transaction_hash: TxHash) -> RpcResult<TransactionTrace>;
 
     /// Simulates a list of transactions on the provided block.
+    ///
+    /// The optional `state_override` is a Katana extension to the specification, see
+    /// `estimateFee`.
     #[method(name = "simulateTransactions")]
     async fn simulate_transactions(
         &self,
         block_id: BlockIdOrTag,
         transactions: Vec<BroadcastedTx>,
         simulation_flags: Vec<SimulationFlag>,
+        state_override: Option<StateOverride>,
     ) -> RpcResult<Vec<SimulatedTransaction>>;
 
     /// Returns the execution traces of all transactions included in the given block.
@@ -231,3 +263,21 @@ pub trait StarknetTraceApi {
         block_id: BlockIdOrTag,
     ) -> RpcResult<Vec<TransactionTraceWithHash>>;
 }
+
+/// Subscription API, available over WebSocket connections.
+#[cfg_attr(not(feature = "client"), rpc(server, namespace = "starknet"))]
+#[cfg_attr(feature = "client", rpc(client, server, namespace = "starknet"))]
+pub trait StarknetSubscriptionApi {
+    /// Subscribes to the headers of the new blocks (`newHeads`), to the events emitted by the new
+    /// blocks (`events`), or to the status changes of a transaction (`transactionStatus`).
+    ///
+    /// The notifications are pushed with the `starknet_subscription` method, and the
+    /// subscription is cancelled with `starknet_unsubscribe`. A `transactionStatus` subscription
+    /// ends by itself once the transaction is accepted or rejected.
+    #[subscription(
+        name = "subscribe" => "subscription",
+        unsubscribe = "unsubscribe",
+        item = SubscriptionItem
+    )]
+    fn subscribe(&self, kind: SubscriptionKind, params: Option<SubscriptionParams>);
+}
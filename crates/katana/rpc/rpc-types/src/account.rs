@@ -1,4 +1,5 @@
 use alloy_primitives::U256;
+pub use katana_executor::SessionPolicy;
 use katana_primitives::class::ClassHash;
 use katana_primitives::contract::ContractAddress;
 use katana_primitives::genesis::allocation::GenesisAccountAlloc;
@@ -33,3 +34,19 @@ impl Account {
         }
     }
 }
+
+/// A session key minted for a predeployed account with `dev_mintSession`.
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSession {
+    pub account_address: ContractAddress,
+    #[serde_as(as = "UfeHex")]
+    pub public_key: Felt,
+    #[serde_as(as = "UfeHex")]
+    pub private_key: Felt,
+    /// The block timestamp from which the session can't be used anymore.
+    pub expires_at: u64,
+    /// The calls the transactions signed with the session key are allowed to make.
+    pub policies: Vec<SessionPolicy>,
+}
@@ -1,6 +1,8 @@
+use derive_more::Deref;
 use katana_primitives::block::{
-    Block, BlockHash, BlockNumber, FinalityStatus, Header, PartialHeader,
+    Block, BlockHash, BlockNumber, FinalityStatus, Header, HeaderExtension, PartialHeader,
 };
+use katana_primitives::da::DataAvailabilityMode;
 use katana_primitives::receipt::Receipt;
 use katana_primitives::transaction::{TxHash, TxWithHash};
 use serde::{Deserialize, Serialize};
@@ -13,7 +15,7 @@ use crate::transaction::Tx;
 
 pub type BlockTxCount = u64;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Deref)]
 #[serde(transparent)]
 pub struct BlockWithTxs(starknet::core::types::BlockWithTxs);
 
@@ -57,7 +59,7 @@ impl BlockWithTxs {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Deref)]
 #[serde(transparent)]
 pub struct PendingBlockWithTxs(starknet::core::types::PendingBlockWithTxs);
 
@@ -108,7 +110,7 @@ impl From<starknet::core::types::MaybePendingBlockWithTxs> for MaybePendingBlock
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Deref)]
 #[serde(transparent)]
 pub struct BlockWithTxHashes(starknet::core::types::BlockWithTxHashes);
 
@@ -153,7 +155,7 @@ impl BlockWithTxHashes {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Deref)]
 #[serde(transparent)]
 pub struct PendingBlockWithTxHashes(starknet::core::types::PendingBlockWithTxHashes);
 
@@ -207,7 +209,7 @@ impl From<starknet::core::types::MaybePendingBlockWithTxHashes> for MaybePending
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Deref)]
 #[serde(transparent)]
 pub struct BlockHashAndNumber(starknet::core::types::BlockHashAndNumber);
 
@@ -223,7 +225,7 @@ impl From<(BlockHash, BlockNumber)> for BlockHashAndNumber {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Deref)]
 #[serde(transparent)]
 pub struct BlockWithReceipts(starknet::core::types::BlockWithReceipts);
 
@@ -272,7 +274,7 @@ impl BlockWithReceipts {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Deref)]
 #[serde(transparent)]
 pub struct PendingBlockWithReceipts(starknet::core::types::PendingBlockWithReceipts);
 
@@ -337,3 +339,62 @@ impl From<starknet::core::types::MaybePendingBlockWithReceipts> for MaybePending
         }
     }
 }
+
+/// The operator-defined header fields of a block, as returned by the `katana` RPC namespace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockHeaderExtension {
+    pub block_hash: BlockHash,
+    pub block_number: BlockNumber,
+    pub da_mode: DataAvailabilityMode,
+    pub extra_data: alloy_primitives::Bytes,
+}
+
+impl BlockHeaderExtension {
+    pub fn new(block_hash: BlockHash, block_number: BlockNumber, ext: HeaderExtension) -> Self {
+        Self { block_hash, block_number, da_mode: ext.da_mode, extra_data: ext.extra_data.into() }
+    }
+}
+
+/// The result of the verification of a block hash against the content of the block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlockHashVerification {
+    pub block_hash: BlockHash,
+    pub block_number: BlockNumber,
+    /// The hash recomputed from the transactions, receipts and state updates of the block.
+    pub computed_hash: BlockHash,
+    pub is_valid: bool,
+    /// The header fields whose committed value doesn't match the content of the block.
+    pub mismatches: Vec<String>,
+}
+
+impl From<katana_core::backend::verify::BlockHashVerification> for BlockHashVerification {
+    fn from(value: katana_core::backend::verify::BlockHashVerification) -> Self {
+        Self {
+            is_valid: value.is_valid(),
+            block_hash: value.block_hash,
+            block_number: value.block_number,
+            computed_hash: value.computed_hash,
+            mismatches: value.mismatches.into_iter().map(String::from).collect(),
+        }
+    }
+}
+
+/// A mined block returned by `katana_getBlocks`, with the receipts of its transactions if they
+/// were requested.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockWithTxHashesAndReceipts {
+    #[serde(flatten)]
+    pub block: BlockWithTxHashes,
+    /// The receipts of the transactions of the block, in the same order.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receipts: Option<Vec<TxReceipt>>,
+}
+
+/// A chunk of consecutive blocks returned by `katana_getBlocks`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlocksPage {
+    pub blocks: Vec<BlockWithTxHashesAndReceipts>,
+    /// The number of the block to request next to continue fetching the requested range. `None`
+    /// if all the mined blocks of the range have been returned.
+    pub next_block: Option<BlockNumber>,
+}
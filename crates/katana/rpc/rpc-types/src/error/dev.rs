@@ -1,16 +1,49 @@
 use jsonrpsee::core::Error;
 use jsonrpsee::types::error::CallError;
 use jsonrpsee::types::ErrorObject;
+use katana_provider::error::ProviderError;
 
-#[derive(thiserror::Error, Clone, Copy, Debug)]
+#[derive(thiserror::Error, Clone, Debug)]
 #[allow(clippy::enum_variant_names)]
 pub enum DevApiError {
     #[error("Wait for pending transactions.")]
     PendingTransactions,
+    #[error("Contract not found.")]
+    ContractNotFound,
+    #[error("Snapshot not found.")]
+    SnapshotNotFound,
+    #[error("Account is not a predeployed account whose private key is known to the node.")]
+    NotDevAccount,
+    #[error("Invalid reorg: {reason}")]
+    InvalidReorg { reason: String },
+    #[error("Failed to reload the configuration: {reason}")]
+    ConfigReload { reason: String },
+    #[error("An unexpected error occured: {reason}")]
+    UnexpectedError { reason: String },
+}
+
+impl DevApiError {
+    fn code(&self) -> i32 {
+        match self {
+            DevApiError::PendingTransactions => 0,
+            DevApiError::ContractNotFound => 20,
+            DevApiError::ConfigReload { .. } => 21,
+            DevApiError::SnapshotNotFound => 22,
+            DevApiError::InvalidReorg { .. } => 23,
+            DevApiError::NotDevAccount => 24,
+            DevApiError::UnexpectedError { .. } => 63,
+        }
+    }
+}
+
+impl From<ProviderError> for DevApiError {
+    fn from(value: ProviderError) -> Self {
+        DevApiError::UnexpectedError { reason: value.to_string() }
+    }
 }
 
 impl From<DevApiError> for Error {
     fn from(err: DevApiError) -> Self {
-        Error::Call(CallError::Custom(ErrorObject::owned(err as i32, err.to_string(), None::<()>)))
+        Error::Call(CallError::Custom(ErrorObject::owned(err.code(), err.to_string(), None::<()>)))
     }
 }
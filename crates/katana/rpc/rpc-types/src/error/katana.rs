@@ -11,6 +11,30 @@ pub enum KatanaApiError {
     FailedToDumpState = 2,
     #[error("Failed to update storage.")]
     FailedToUpdateStorage = 3,
+    #[error("Block not found.")]
+    BlockNotFound = 4,
+    #[error("Internal error.")]
+    Internal = 5,
+    #[error("Transaction hash not found.")]
+    TxnHashNotFound = 6,
+    #[error("Failed to load state.")]
+    FailedToLoadState = 7,
+    #[error("Failed to verify block hash.")]
+    FailedToVerifyBlockHash = 8,
+    #[error("Invalid block range.")]
+    InvalidBlockRange = 9,
+    #[error("Contract not found.")]
+    ContractNotFound = 10,
+    #[error("Contract error.")]
+    ContractError = 11,
+}
+
+impl KatanaApiError {
+    /// Converts the error into an RPC error, with the reason of the failure as its data.
+    pub fn with_reason(self, reason: impl ToString) -> Error {
+        let data = Some(reason.to_string());
+        Error::Call(CallError::Custom(ErrorObject::owned(self as i32, self.to_string(), data)))
+    }
 }
 
 impl From<KatanaApiError> for Error {
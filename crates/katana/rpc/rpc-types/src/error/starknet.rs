@@ -6,8 +6,8 @@ use katana_pool::PoolError;
 use katana_primitives::event::ContinuationTokenError;
 use katana_provider::error::ProviderError;
 use serde::Serialize;
-use serde_json::Value;
-use starknet::core::types::StarknetError as StarknetRsError;
+use serde_json::{json, Value};
+use starknet::core::types::{SequencerTransactionStatus, StarknetError as StarknetRsError};
 use starknet::providers::ProviderError as StarknetRsProviderError;
 
 /// Possible list of errors that can be returned by the Starknet API according to the spec: <https://github.com/starkware-libs/starknet-specs>.
@@ -27,6 +27,11 @@ pub enum StarknetApiError {
     BlockNotFound,
     #[error("Transaction hash not found")]
     TxnHashNotFound,
+    #[error("No trace available for transaction")]
+    NoTraceAvailable {
+        /// The status of the transaction, either received or rejected.
+        status: SequencerTransactionStatus,
+    },
     #[error("Invalid transaction index in a block")]
     InvalidTxnIndex,
     #[error("Class hash not found")]
@@ -38,7 +43,10 @@ pub enum StarknetApiError {
     #[error("The supplied continuation token is invalid or unknown")]
     InvalidContinuationToken,
     #[error("Contract error")]
-    ContractError { revert_error: String },
+    ContractError {
+        /// The revert error of the call, with the execution trace up to the point of failure.
+        revert_error: String,
+    },
     #[error("Transaction execution error")]
     TransactionExecutionError {
         /// The index of the first transaction failing in a sequence of given transactions.
@@ -77,18 +85,23 @@ pub enum StarknetApiError {
     UnsupportedContractClassVersion,
     #[error("An unexpected error occured")]
     UnexpectedError { reason: String },
+    #[error("Failed to compile the contract")]
+    CompilationError { compilation_error: String },
     #[error("Too many storage keys requested")]
     ProofLimitExceeded,
     #[error("Too many keys provided in a filter")]
     TooManyKeysInFilter,
     #[error("Failed to fetch pending transactions")]
     FailedToFetchPendingTransactions,
+    #[error("Server is busy, try again later")]
+    ServerBusy,
 }
 
 impl StarknetApiError {
     pub fn code(&self) -> i32 {
         match self {
             StarknetApiError::FailedToReceiveTxn => 1,
+            StarknetApiError::NoTraceAvailable { .. } => 10,
             StarknetApiError::ContractNotFound => 20,
             StarknetApiError::InvalidMessageSelector => 21,
             StarknetApiError::InvalidCallData => 22,
@@ -117,7 +130,10 @@ impl StarknetApiError {
             StarknetApiError::UnsupportedTransactionVersion => 61,
             StarknetApiError::UnsupportedContractClassVersion => 62,
             StarknetApiError::UnexpectedError { .. } => 63,
+            StarknetApiError::CompilationError { .. } => 100,
             StarknetApiError::ProofLimitExceeded => 10000,
+            // the code used by jsonrpsee when the server can't take more requests
+            StarknetApiError::ServerBusy => -32009,
         }
     }
 
@@ -125,13 +141,29 @@ impl StarknetApiError {
         self.to_string()
     }
 
+    /// Returns the `data` field of the error, shaped as defined by the specification for each
+    /// error.
     pub fn data(&self) -> Option<serde_json::Value> {
         match self {
-            StarknetApiError::ContractError { .. }
-            | StarknetApiError::UnexpectedError { .. }
-            | StarknetApiError::TransactionExecutionError { .. } => Some(serde_json::json!(self)),
+            StarknetApiError::NoTraceAvailable { status } => Some(json!({ "status": status })),
 
-            StarknetApiError::InvalidTransactionNonce { reason }
+            StarknetApiError::ContractError { revert_error } => {
+                Some(json!({ "revert_error": revert_error }))
+            }
+
+            StarknetApiError::TransactionExecutionError { transaction_index, execution_error } => {
+                Some(json!({
+                    "transaction_index": transaction_index,
+                    "execution_error": execution_error,
+                }))
+            }
+
+            StarknetApiError::CompilationError { compilation_error } => {
+                Some(json!({ "compilation_error": compilation_error }))
+            }
+
+            StarknetApiError::UnexpectedError { reason }
+            | StarknetApiError::InvalidTransactionNonce { reason }
             | StarknetApiError::ValidationFailure { reason } => {
                 Some(Value::String(reason.to_string()))
             }
@@ -155,7 +187,10 @@ impl From<ProviderError> for StarknetApiError {
 impl From<ContinuationTokenError> for StarknetApiError {
     fn from(value: ContinuationTokenError) -> Self {
         match value {
-            ContinuationTokenError::InvalidToken => StarknetApiError::InvalidContinuationToken,
+            ContinuationTokenError::InvalidToken
+            | ContinuationTokenError::UnsupportedVersion(_) => {
+                StarknetApiError::InvalidContinuationToken
+            }
             ContinuationTokenError::ParseFailed(e) => {
                 StarknetApiError::UnexpectedError { reason: e.to_string() }
             }
@@ -176,6 +211,7 @@ impl From<PoolError> for StarknetApiError {
             PoolError::Internal(err) => {
                 StarknetApiError::UnexpectedError { reason: err.to_string() }
             }
+            PoolError::PoolFull => StarknetApiError::UnexpectedError { reason: error.to_string() },
         }
     }
 }
@@ -236,8 +272,8 @@ impl From<StarknetRsError> for StarknetApiError {
             StarknetRsError::UnsupportedContractClassVersion => {
                 Self::UnsupportedContractClassVersion
             }
-            StarknetRsError::NoTraceAvailable(_) => {
-                Self::UnexpectedError { reason: "No trace available".to_string() }
+            StarknetRsError::NoTraceAvailable(data) => {
+                Self::NoTraceAvailable { status: data.status }
             }
         }
     }
@@ -263,7 +299,6 @@ impl From<StarknetRsProviderError> for StarknetApiError {
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
-    use serde_json::json;
 
     use super::*;
 
@@ -339,8 +374,26 @@ mod tests {
         },
         63,
         "An unexpected error occured",
+        Value::String("Unexpected error reason".to_string())
+    )]
+    #[case(
+        StarknetApiError::NoTraceAvailable {
+            status: SequencerTransactionStatus::Received,
+        },
+        10,
+        "No trace available for transaction",
+        json!({
+            "status": "RECEIVED"
+        }),
+    )]
+    #[case(
+        StarknetApiError::CompilationError {
+            compilation_error: "Compilation error message".to_string(),
+        },
+        100,
+        "Failed to compile the contract",
         json!({
-            "reason": "Unexpected error reason".to_string()
+            "compilation_error": "Compilation error message".to_string()
         }),
     )]
     #[case(
@@ -1,2 +1,38 @@
+use serde::{Deserialize, Serialize};
+
 pub type EventFilterWithPage = starknet::core::types::EventFilterWithPage;
-pub type EventsPage = starknet::core::types::EventsPage;
+
+/// A page of the events returned by `starknet_getEvents`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventsPage {
+    pub events: Vec<EmittedEvent>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub continuation_token: Option<String>,
+}
+
+impl From<starknet::core::types::EventsPage> for EventsPage {
+    fn from(value: starknet::core::types::EventsPage) -> Self {
+        let events = value.events.into_iter().map(EmittedEvent::from).collect();
+        Self { events, continuation_token: value.continuation_token }
+    }
+}
+
+/// An event emitted by a transaction, along with its position in its block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EmittedEvent {
+    #[serde(flatten)]
+    pub inner: starknet::core::types::EmittedEvent,
+    /// The index of the event among all the events emitted in its block, following the order of
+    /// the transactions in the block and the order in which each transaction emitted them.
+    ///
+    /// `(block_number, event_index)` strictly orders the events of a chain. Not available for
+    /// the events fetched from the forked network.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_index: Option<u64>,
+}
+
+impl From<starknet::core::types::EmittedEvent> for EmittedEvent {
+    fn from(inner: starknet::core::types::EmittedEvent) -> Self {
+        Self { inner, event_index: None }
+    }
+}
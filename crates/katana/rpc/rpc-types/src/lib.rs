@@ -8,11 +8,16 @@ pub mod block;
 pub mod error;
 pub mod event;
 pub mod message;
+pub mod pool;
+pub mod proof;
 pub mod receipt;
+pub mod state_override;
 pub mod state_update;
+pub mod subscription;
 pub mod trace;
 pub mod transaction;
 mod utils;
+pub mod world;
 
 use std::ops::Deref;
 
@@ -51,6 +56,10 @@ pub type FeeEstimate = starknet::core::types::FeeEstimate;
 
 pub type ContractClass = starknet::core::types::ContractClass;
 
+/// The CASM of a Sierra class, as compiled by the node when the class was declared.
+pub type CompiledCasm =
+    katana_cairo::lang::starknet_classes::casm_contract_class::CasmContractClass;
+
 pub type SimulationFlagForEstimateFee = starknet::core::types::SimulationFlagForEstimateFee;
 
 pub type SimulationFlag = starknet::core::types::SimulationFlag;
@@ -1,8 +1,9 @@
 use katana_primitives::chain::ChainId;
-use katana_primitives::transaction::L1HandlerTx;
+use katana_primitives::transaction::{L1HandlerTx, TxHash};
 use katana_primitives::utils::transaction::compute_l2_to_l1_message_hash;
 use katana_primitives::Felt;
 use serde::{Deserialize, Serialize};
+use starknet::core::types::{SequencerTransactionStatus, TransactionExecutionStatus};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MsgFromL1(starknet::core::types::MsgFromL1);
@@ -44,3 +45,14 @@ impl MsgFromL1 {
         }
     }
 }
+
+/// The status of the L1 handler transaction executing a message sent from the settlement chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MessageStatus {
+    /// The hash of the L1 handler transaction.
+    pub transaction_hash: TxHash,
+    pub finality_status: SequencerTransactionStatus,
+    /// The execution status of the transaction, once it has been executed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution_status: Option<TransactionExecutionStatus>,
+}
@@ -0,0 +1,37 @@
+use katana_pool::{PoolContent, PoolStatus};
+use katana_primitives::transaction::{ExecutableTxWithHash, TxWithHash};
+use serde::{Deserialize, Serialize};
+
+use crate::transaction::Tx;
+
+/// The transactions waiting in the pool of the node.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TxPoolContent {
+    /// The transactions that can be executed, in the order they will be included in blocks.
+    pub pending: Vec<Tx>,
+    /// The transactions waiting for the transactions filling the gap after their sender's nonce.
+    pub queued: Vec<Tx>,
+}
+
+impl From<PoolContent<ExecutableTxWithHash>> for TxPoolContent {
+    fn from(value: PoolContent<ExecutableTxWithHash>) -> Self {
+        let into_rpc = |tx: &ExecutableTxWithHash| Tx::from(TxWithHash::from(tx));
+        Self {
+            pending: value.pending.iter().map(|tx| into_rpc(tx)).collect(),
+            queued: value.queued.iter().map(|tx| into_rpc(tx)).collect(),
+        }
+    }
+}
+
+/// The number of transactions waiting in the pool of the node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxPoolStatus {
+    pub pending: u64,
+    pub queued: u64,
+}
+
+impl From<PoolStatus> for TxPoolStatus {
+    fn from(value: PoolStatus) -> Self {
+        Self { pending: value.pending as u64, queued: value.queued as u64 }
+    }
+}
@@ -0,0 +1,66 @@
+use katana_primitives::block::{BlockHash, BlockNumber};
+use katana_primitives::transaction::TxHash;
+use katana_primitives::Felt;
+use katana_trie::{path_from_felt, ProofNode};
+use serde::{Deserialize, Serialize};
+
+/// A node of a Merkle-Patricia proof, shaped after the `MERKLE_NODE` type of the Starknet
+/// JSON-RPC specification.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MerkleNode {
+    Binary { left: Felt, right: Felt },
+    Edge { child: Felt, path: Felt, length: usize },
+}
+
+impl From<ProofNode> for MerkleNode {
+    fn from(value: ProofNode) -> Self {
+        match value {
+            ProofNode::Binary { left, right } => Self::Binary { left, right },
+            ProofNode::Edge { child, path } => {
+                let length = path.0.len();
+                let path = path.0.iter().fold(Felt::ZERO, |acc, bit| {
+                    if *bit {
+                        acc * Felt::TWO + Felt::ONE
+                    } else {
+                        acc * Felt::TWO
+                    }
+                });
+                Self::Edge { child, path, length }
+            }
+        }
+    }
+}
+
+impl From<MerkleNode> for ProofNode {
+    fn from(value: MerkleNode) -> Self {
+        match value {
+            MerkleNode::Binary { left, right } => Self::Binary { left, right },
+            MerkleNode::Edge { child, path, length } => {
+                Self::Edge { child, path: path_from_felt(path, length) }
+            }
+        }
+    }
+}
+
+/// Proves that a transaction, and its receipt, are part of a block by providing the Merkle paths
+/// to the block's transaction and receipt commitments.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionInclusionProof {
+    pub transaction_hash: TxHash,
+    pub block_hash: BlockHash,
+    pub block_number: BlockNumber,
+    /// The index of the transaction in the block, which is also the leaf key in both trees.
+    pub transaction_index: u64,
+    /// The leaf of the transaction, as committed in the transactions tree. It is the hash of the
+    /// transaction hash and its signature.
+    pub transaction_leaf: Felt,
+    pub transactions_commitment: Felt,
+    /// The nodes from the root of the transactions tree down to the transaction leaf.
+    pub transaction_proof: Vec<MerkleNode>,
+    /// The hash of the receipt, as committed in the receipts tree.
+    pub receipt_hash: Felt,
+    pub receipts_commitment: Felt,
+    /// The nodes from the root of the receipts tree down to the receipt hash.
+    pub receipt_proof: Vec<MerkleNode>,
+}
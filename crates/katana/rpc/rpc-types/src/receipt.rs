@@ -1,7 +1,10 @@
-use katana_cairo::cairo_vm::types::builtin_name::BuiltinName;
 use katana_primitives::block::FinalityStatus;
 use katana_primitives::fee::{PriceUnit, TxFeeInfo};
 use katana_primitives::receipt::{MessageToL1, Receipt};
+use std::collections::BTreeMap;
+
+use katana_cairo::cairo_vm::types::builtin_name::BuiltinName;
+use katana_primitives::trace::{StateDiffSize, TxExecInfo, TxResources};
 use katana_primitives::transaction::TxHash;
 use serde::{Deserialize, Serialize};
 pub use starknet::core::types::ReceiptBlock;
@@ -139,6 +142,67 @@ impl TxReceiptWithBlockInfo {
     }
 }
 
+/// The resources consumed by a mined transaction, by kind.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxResourceUsage {
+    pub transaction_hash: TxHash,
+    /// The L1 gas consumed, including the gas paid for the data availability of the state diff.
+    pub l1_gas: u128,
+    /// The L1 data gas consumed for the data availability of the state diff.
+    pub l1_data_gas: u128,
+    /// The Sierra gas consumed by the calls of the transaction.
+    pub l2_gas: u128,
+    /// The Cairo steps executed, including the reverted ones.
+    pub steps: u64,
+}
+
+impl TxResourceUsage {
+    pub fn new(transaction_hash: TxHash, receipt: &Receipt, trace: &TxExecInfo) -> Self {
+        let resources = receipt.resources_used();
+        Self {
+            transaction_hash,
+            l1_gas: resources.total_gas_consumed.l1_gas,
+            l1_data_gas: resources.total_gas_consumed.l1_data_gas,
+            l2_gas: trace.sierra_gas_consumed(),
+            steps: (resources.vm_resources.n_steps + resources.n_reverted_steps) as u64,
+        }
+    }
+}
+
+/// The Cairo VM resources used by a mined transaction and the size of the state diff it produced.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxExecutionResources {
+    pub transaction_hash: TxHash,
+    /// The Cairo steps executed, excluding the reverted ones.
+    pub steps: u64,
+    /// The Cairo steps executed by the reverted part of the transaction.
+    pub reverted_steps: u64,
+    pub memory_holes: u64,
+    /// The number of applications of each builtin, by builtin name.
+    pub builtins: BTreeMap<String, u64>,
+    pub state_diff_size: StateDiffSize,
+}
+
+impl TxExecutionResources {
+    pub fn new(transaction_hash: TxHash, resources: &TxResources) -> Self {
+        let vm = &resources.vm_resources;
+        let builtins = vm
+            .builtin_instance_counter
+            .iter()
+            .map(|(name, count)| (name.to_str().to_string(), *count as u64))
+            .collect();
+
+        Self {
+            transaction_hash,
+            steps: vm.n_steps as u64,
+            reverted_steps: resources.n_reverted_steps as u64,
+            memory_holes: vm.n_memory_holes as u64,
+            builtins,
+            state_diff_size: resources.state_diff_size,
+        }
+    }
+}
+
 struct MsgToL1(starknet::core::types::MsgToL1);
 
 impl From<MessageToL1> for MsgToL1 {
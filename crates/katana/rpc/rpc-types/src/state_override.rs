@@ -0,0 +1,29 @@
+//! State overrides applied when calling contracts, estimating fees and simulating transactions.
+
+use std::collections::BTreeMap;
+
+use katana_primitives::class::ClassHash;
+use katana_primitives::contract::{ContractAddress, Nonce, StorageKey, StorageValue};
+use katana_primitives::Felt;
+use serde::{Deserialize, Serialize};
+
+/// The state of the contracts to override, keyed by their address.
+pub type StateOverride = BTreeMap<ContractAddress, ContractOverride>;
+
+/// The state of a contract to use instead of its actual state.
+///
+/// Overriding the class hash of an address that has no contract deployed makes it behave as if
+/// the contract was deployed, eg to estimate the fees of an account before it's deployed.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ContractOverride {
+    /// The balance of the contract in both the ETH and STRK fee tokens.
+    #[serde(default)]
+    pub balance: Option<Felt>,
+    #[serde(default)]
+    pub nonce: Option<Nonce>,
+    #[serde(default)]
+    pub class_hash: Option<ClassHash>,
+    /// The storage slots to override, the other slots of the contract keep their actual value.
+    #[serde(default)]
+    pub storage: BTreeMap<StorageKey, StorageValue>,
+}
@@ -1,3 +1,8 @@
+use derive_more::Deref;
+use katana_primitives::block::BlockNumber;
+use katana_primitives::contract::{StorageKey, StorageValue};
+use katana_primitives::da::encoding::encode_state_updates;
+use katana_primitives::state::StateUpdates;
 use serde::{Deserialize, Serialize};
 use starknet::core::types::{
     ContractStorageDiffItem, DeclaredClassItem, DeployedContractItem, NonceUpdate, StorageEntry,
@@ -23,15 +28,15 @@ impl From<starknet::core::types::MaybePendingStateUpdate> for MaybePendingStateU
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Deref)]
 #[serde(transparent)]
 pub struct StateUpdate(starknet::core::types::StateUpdate);
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Deref)]
 #[serde(transparent)]
 pub struct PendingStateUpdate(starknet::core::types::PendingStateUpdate);
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(transparent)]
 pub struct StateDiff(pub starknet::core::types::StateDiff);
 
@@ -92,3 +97,64 @@ impl From<starknet::core::types::PendingStateUpdate> for PendingStateUpdate {
         Self(value)
     }
 }
+
+/// A storage slot change pushed by the `katana_subscribeStorageDiffs` subscription.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StorageDiffItem {
+    pub key: StorageKey,
+    pub old_value: StorageValue,
+    pub new_value: StorageValue,
+    pub block_number: BlockNumber,
+}
+
+impl StorageDiffItem {
+    pub fn new(block_number: BlockNumber, diff: katana_primitives::state::StorageDiff) -> Self {
+        Self { key: diff.key, old_value: diff.old_value, new_value: diff.new_value, block_number }
+    }
+}
+
+/// The format of the state diff returned by `katana_getStateDiffRange`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StateDiffFormat {
+    /// The state diff as in the `starknet_getStateUpdate` response.
+    #[default]
+    Json,
+    /// The state diff encoded the way Starknet publishes it for data availability, as a hex
+    /// string of 32-byte big-endian words.
+    Binary,
+}
+
+/// The state diffs of a range of blocks, aggregated into a single state diff.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StateDiffRange {
+    pub from_block: BlockNumber,
+    pub to_block: BlockNumber,
+    #[serde(flatten)]
+    pub state_diff: EncodedStateDiff,
+}
+
+/// A state diff in one of the [`StateDiffFormat`]s.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "format", content = "state_diff", rename_all = "lowercase")]
+pub enum EncodedStateDiff {
+    Json(StateDiff),
+    Binary(String),
+}
+
+impl EncodedStateDiff {
+    pub fn new(state_updates: StateUpdates, format: StateDiffFormat) -> Self {
+        match format {
+            StateDiffFormat::Json => Self::Json(state_updates.into()),
+            StateDiffFormat::Binary => {
+                let mut encoded = String::from("0x");
+                for word in encode_state_updates(state_updates) {
+                    let bytes = word.to_bytes_be();
+                    let padded = [vec![0u8; 32 - bytes.len()], bytes].concat();
+                    padded.iter().for_each(|b| encoded.push_str(&format!("{b:02x}")));
+                }
+                Self::Binary(encoded)
+            }
+        }
+    }
+}
@@ -0,0 +1,86 @@
+//! Types of the `starknet_subscribe` WebSocket subscriptions.
+
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::transaction::TxHash;
+use katana_primitives::Felt;
+use serde::{Deserialize, Serialize};
+use starknet::core::types::TransactionStatus;
+
+use crate::block::BlockWithTxHashes;
+use crate::event::EmittedEvent;
+
+/// The kind of notifications streamed by a subscription.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SubscriptionKind {
+    /// The header of every new block.
+    NewHeads,
+    /// The events emitted by every new block, matching the subscription filter.
+    Events,
+    /// The status changes of a transaction.
+    TransactionStatus,
+}
+
+/// The parameters of a subscription. Only the ones relevant to its kind are used.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubscriptionParams {
+    /// For `events`, only the events emitted by this contract are streamed.
+    #[serde(default)]
+    pub from_address: Option<ContractAddress>,
+    /// For `events`, only the events matching these keys are streamed, with the same semantics
+    /// as the keys filter of `starknet_getEvents`.
+    #[serde(default)]
+    pub keys: Option<Vec<Vec<Felt>>>,
+    /// For `transactionStatus`, the transaction whose status is streamed. Required.
+    #[serde(default)]
+    pub transaction_hash: Option<TxHash>,
+}
+
+/// A notification pushed by a subscription.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SubscriptionItem {
+    NewHead(BlockWithTxHashes),
+    Event(EmittedEvent),
+    TransactionStatus(TransactionStatusUpdate),
+}
+
+/// A new status of the transaction of a `transactionStatus` subscription.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionStatusUpdate {
+    pub transaction_hash: TxHash,
+    pub status: TransactionStatus,
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use starknet::core::types::TransactionExecutionStatus;
+    use starknet::macros::felt;
+
+    use super::*;
+
+    #[test]
+    fn serde_subscription() {
+        let kind: SubscriptionKind = serde_json::from_value(json!("transactionStatus")).unwrap();
+        assert_eq!(kind, SubscriptionKind::TransactionStatus);
+
+        let params: SubscriptionParams =
+            serde_json::from_value(json!({ "from_address": "0x1", "keys": [["0x2"]] })).unwrap();
+        assert_eq!(params.from_address, Some(felt!("0x1").into()));
+        assert_eq!(params.keys, Some(vec![vec![felt!("0x2")]]));
+        assert_eq!(params.transaction_hash, None);
+
+        let update = SubscriptionItem::TransactionStatus(TransactionStatusUpdate {
+            transaction_hash: felt!("0x3"),
+            status: TransactionStatus::AcceptedOnL2(TransactionExecutionStatus::Succeeded),
+        });
+        assert_eq!(
+            serde_json::to_value(update).unwrap(),
+            json!({
+                "transaction_hash": "0x3",
+                "status": { "finality_status": "ACCEPTED_ON_L2", "execution_status": "SUCCEEDED" }
+            })
+        );
+    }
+}
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use anyhow::Result;
 use derive_more::Deref;
 use katana_primitives::chain::ChainId;
-use katana_primitives::class::ClassHash;
+use katana_primitives::class::{ClassHash, CompiledClass};
 use katana_primitives::contract::ContractAddress;
 use katana_primitives::conversion::rpc::{
     compiled_class_hash_from_flattened_sierra_class, flattened_sierra_to_compiled_class,
@@ -21,7 +21,8 @@ use serde::{Deserialize, Serialize};
 use starknet::core::types::{
     BroadcastedDeclareTransaction, BroadcastedDeployAccountTransaction,
     BroadcastedInvokeTransaction, DeclareTransactionResult, DeployAccountTransactionResult,
-    DeployAccountTransactionV1, DeployAccountTransactionV3, InvokeTransactionResult,
+    DeployAccountTransactionV1, DeployAccountTransactionV3, FlattenedSierraClass,
+    InvokeTransactionResult,
 };
 use starknet::core::utils::get_contract_address;
 
@@ -96,6 +97,22 @@ impl BroadcastedDeclareTx {
 
     /// This function assumes that the compiled class hash is valid.
     pub fn try_into_tx_with_chain_id(self, chain_id: ChainId) -> Result<DeclareTxWithClass> {
+        self.try_into_tx_with_compiled_class(chain_id, None)
+    }
+
+    /// Same as [`Self::try_into_tx_with_chain_id`], but with the class hash and compiled class of
+    /// the Sierra class of the transaction if they were already computed, eg taken from a cache.
+    /// The class is compiled if `compiled` is `None`. Ignored for V1 transactions.
+    pub fn try_into_tx_with_compiled_class(
+        self,
+        chain_id: ChainId,
+        compiled: Option<(ClassHash, CompiledClass)>,
+    ) -> Result<DeclareTxWithClass> {
+        let compile = |class: &FlattenedSierraClass| match compiled {
+            Some(compiled) => Ok(compiled),
+            None => flattened_sierra_to_compiled_class(class).map(|(hash, _, class)| (hash, class)),
+        };
+
         match self.0 {
             BroadcastedDeclareTransaction::V1(tx) => {
                 let (class_hash, compiled_class) =
@@ -116,13 +133,11 @@ impl BroadcastedDeclareTx {
             }
 
             BroadcastedDeclareTransaction::V2(tx) => {
-                // TODO: avoid computing the class hash again
-                let (class_hash, _, compiled_class) =
-                    flattened_sierra_to_compiled_class(&tx.contract_class)?;
+                let (class_hash, compiled_class) = compile(&tx.contract_class)?;
 
                 Ok(DeclareTxWithClass {
                     compiled_class,
-                    sierra_class: Arc::into_inner(tx.contract_class),
+                    sierra_class: Some(Arc::unwrap_or_clone(tx.contract_class)),
                     transaction: DeclareTx::V2(DeclareTxV2 {
                         chain_id,
                         class_hash,
@@ -136,13 +151,11 @@ impl BroadcastedDeclareTx {
             }
 
             BroadcastedDeclareTransaction::V3(tx) => {
-                // TODO: avoid computing the class hash again
-                let (class_hash, _, compiled_class) =
-                    flattened_sierra_to_compiled_class(&tx.contract_class)?;
+                let (class_hash, compiled_class) = compile(&tx.contract_class)?;
 
                 Ok(DeclareTxWithClass {
                     compiled_class,
-                    sierra_class: Arc::into_inner(tx.contract_class),
+                    sierra_class: Some(Arc::unwrap_or_clone(tx.contract_class)),
                     transaction: DeclareTx::V3(DeclareTxV3 {
                         chain_id,
                         class_hash,
@@ -164,6 +177,15 @@ impl BroadcastedDeclareTx {
         }
     }
 
+    /// Returns the Sierra class declared by the transaction, or `None` for V1 transactions.
+    pub fn sierra_class(&self) -> Option<&Arc<FlattenedSierraClass>> {
+        match &self.0 {
+            BroadcastedDeclareTransaction::V1(_) => None,
+            BroadcastedDeclareTransaction::V2(tx) => Some(&tx.contract_class),
+            BroadcastedDeclareTransaction::V3(tx) => Some(&tx.contract_class),
+        }
+    }
+
     pub fn is_query(&self) -> bool {
         match &self.0 {
             BroadcastedDeclareTransaction::V1(tx) => tx.is_query,
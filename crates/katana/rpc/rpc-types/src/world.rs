@@ -0,0 +1,312 @@
+//! The resources registered and the model records written in a block by a Dojo world, decoded from
+//! the events emitted by the world.
+
+use katana_primitives::receipt::Event;
+use katana_primitives::Felt;
+use num_traits::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use starknet::core::utils::starknet_keccak;
+
+use crate::state_update::StateUpdate;
+
+/// The state update of a block, along with the updates made to a Dojo world in that block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldStateUpdate {
+    #[serde(flatten)]
+    pub state_update: StateUpdate,
+    pub world: WorldUpdates,
+}
+
+/// The updates made to a Dojo world, in the order the world emitted them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WorldUpdates {
+    pub registrations: Vec<ResourceRegistration>,
+    pub model_writes: Vec<ModelWrite>,
+}
+
+/// A resource registered to the world.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ResourceRegistration {
+    Namespace { namespace: String, hash: Felt },
+    Model { namespace: String, name: String, class_hash: Felt, address: Felt },
+    Event { namespace: String, name: String, class_hash: Felt, address: Felt },
+    Contract { namespace: String, name: String, class_hash: Felt, address: Felt, salt: Felt },
+}
+
+/// A write to the records of a model. `model` is the selector of the model.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ModelWrite {
+    SetRecord { model: Felt, entity_id: Felt, keys: Vec<Felt>, values: Vec<Felt> },
+    UpdateRecord { model: Felt, entity_id: Felt, values: Vec<Felt> },
+    UpdateMember { model: Felt, entity_id: Felt, member: Felt, values: Vec<Felt> },
+    DeleteRecord { model: Felt, entity_id: Felt },
+}
+
+/// An entity of a model whose storage slots are watched. `model` is the selector of the model, and
+/// `layout` the layout returned by the model contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntityStorage {
+    pub model: Felt,
+    pub entity_id: Felt,
+    pub layout: Layout,
+}
+
+/// The storage layout of a value, mirroring `dojo::meta::Layout`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Layout {
+    /// The bit sizes of the values packed together.
+    Fixed(Vec<u8>),
+    Struct(Vec<FieldLayout>),
+    Tuple(Vec<Layout>),
+    /// The layout of the items of the array.
+    Array(Box<Layout>),
+    ByteArray,
+    /// The layout of the data of each variant.
+    Enum(Vec<FieldLayout>),
+}
+
+/// The layout of a struct member, or of an enum variant. `selector` is the selector of the member
+/// name, or the index of the variant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldLayout {
+    pub selector: Felt,
+    pub layout: Layout,
+}
+
+impl WorldUpdates {
+    /// Decodes the events emitted by the world. Events which aren't registrations or model writes,
+    /// or that can't be decoded, are skipped.
+    pub fn from_events<'a>(events: impl IntoIterator<Item = &'a Event>) -> Self {
+        let selectors = Selectors::new();
+        let mut updates = Self::default();
+
+        for event in events {
+            let Some((selector, keys)) = event.keys.split_first() else { continue };
+            let mut keys = Reader(keys);
+            let mut data = Reader(&event.data);
+
+            if *selector == selectors.namespace_registered {
+                let registration = keys
+                    .byte_array()
+                    .zip(data.felt())
+                    .map(|(namespace, hash)| ResourceRegistration::Namespace { namespace, hash });
+                updates.registrations.extend(registration);
+            } else if *selector == selectors.model_registered
+                || *selector == selectors.event_registered
+            {
+                let Some((name, namespace)) = keys.byte_array().zip(keys.byte_array()) else {
+                    continue;
+                };
+                let Some((class_hash, address)) = data.felt().zip(data.felt()) else { continue };
+
+                updates.registrations.push(if *selector == selectors.model_registered {
+                    ResourceRegistration::Model { namespace, name, class_hash, address }
+                } else {
+                    ResourceRegistration::Event { namespace, name, class_hash, address }
+                });
+            } else if *selector == selectors.contract_registered {
+                let Some((name, namespace)) = keys.byte_array().zip(keys.byte_array()) else {
+                    continue;
+                };
+                let Some(((address, class_hash), salt)) =
+                    data.felt().zip(data.felt()).zip(data.felt())
+                else {
+                    continue;
+                };
+
+                let registration =
+                    ResourceRegistration::Contract { namespace, name, class_hash, address, salt };
+                updates.registrations.push(registration);
+            } else if *selector == selectors.store_set_record {
+                let Some((model, entity_id)) = keys.felt().zip(keys.felt()) else { continue };
+                let Some((record_keys, values)) = data.span().zip(data.span()) else { continue };
+
+                let write = ModelWrite::SetRecord { model, entity_id, keys: record_keys, values };
+                updates.model_writes.push(write);
+            } else if *selector == selectors.store_update_record {
+                let Some((model, entity_id)) = keys.felt().zip(keys.felt()) else { continue };
+                let Some(values) = data.span() else { continue };
+
+                updates.model_writes.push(ModelWrite::UpdateRecord { model, entity_id, values });
+            } else if *selector == selectors.store_update_member {
+                let Some(((model, entity_id), member)) =
+                    keys.felt().zip(keys.felt()).zip(keys.felt())
+                else {
+                    continue;
+                };
+                let Some(values) = data.span() else { continue };
+
+                let write = ModelWrite::UpdateMember { model, entity_id, member, values };
+                updates.model_writes.push(write);
+            } else if *selector == selectors.store_del_record {
+                let Some((model, entity_id)) = keys.felt().zip(keys.felt()) else { continue };
+                updates.model_writes.push(ModelWrite::DeleteRecord { model, entity_id });
+            }
+        }
+
+        updates
+    }
+}
+
+/// The selectors of the world events, ie. the `starknet_keccak` of their names.
+struct Selectors {
+    namespace_registered: Felt,
+    model_registered: Felt,
+    event_registered: Felt,
+    contract_registered: Felt,
+    store_set_record: Felt,
+    store_update_record: Felt,
+    store_update_member: Felt,
+    store_del_record: Felt,
+}
+
+impl Selectors {
+    fn new() -> Self {
+        let selector = |name: &str| starknet_keccak(name.as_bytes());
+        Self {
+            namespace_registered: selector("NamespaceRegistered"),
+            model_registered: selector("ModelRegistered"),
+            event_registered: selector("EventRegistered"),
+            contract_registered: selector("ContractRegistered"),
+            store_set_record: selector("StoreSetRecord"),
+            store_update_record: selector("StoreUpdateRecord"),
+            store_update_member: selector("StoreUpdateMember"),
+            store_del_record: selector("StoreDelRecord"),
+        }
+    }
+}
+
+/// Reads Cairo serialized values from a list of felts.
+struct Reader<'a>(&'a [Felt]);
+
+impl Reader<'_> {
+    fn felt(&mut self) -> Option<Felt> {
+        let (felt, rest) = self.0.split_first()?;
+        self.0 = rest;
+        Some(*felt)
+    }
+
+    fn felts(&mut self, len: usize) -> Option<Vec<Felt>> {
+        if len > self.0.len() {
+            return None;
+        }
+
+        let (felts, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Some(felts.to_vec())
+    }
+
+    /// Reads a `Span<felt252>`, serialized as its length followed by its elements.
+    fn span(&mut self) -> Option<Vec<Felt>> {
+        let len = self.felt()?.to_usize()?;
+        self.felts(len)
+    }
+
+    /// Reads a `ByteArray`, serialized as its full 31 bytes words, followed by the pending word
+    /// and its length in bytes.
+    fn byte_array(&mut self) -> Option<String> {
+        let len = self.felt()?.to_usize()?;
+        let words = self.felts(len)?;
+        let pending_word = self.felt()?;
+        let pending_len = self.felt()?.to_usize().filter(|len| *len < 31)?;
+
+        let mut bytes = Vec::with_capacity(len * 31 + pending_len);
+        for word in words {
+            bytes.extend_from_slice(&word.to_bytes_be()[1..]);
+        }
+        bytes.extend_from_slice(&pending_word.to_bytes_be()[32 - pending_len..]);
+
+        String::from_utf8(bytes).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::contract::ContractAddress;
+    use katana_primitives::receipt::Event;
+    use katana_primitives::{address, felt, Felt};
+    use starknet::core::utils::{cairo_short_string_to_felt, starknet_keccak};
+
+    use super::{ModelWrite, ResourceRegistration, WorldUpdates};
+
+    /// Serializes a short string as a `ByteArray`.
+    fn byte_array(s: &str) -> Vec<Felt> {
+        vec![Felt::ZERO, cairo_short_string_to_felt(s).unwrap(), Felt::from(s.len())]
+    }
+
+    fn event(name: &str, keys: Vec<Felt>, data: Vec<Felt>) -> Event {
+        let mut event_keys = vec![starknet_keccak(name.as_bytes())];
+        event_keys.extend(keys);
+        Event { from_address: address!("0x1234"), keys: event_keys, data }
+    }
+
+    #[test]
+    fn decode_world_events() {
+        let long_name = "a_namespace_name_longer_than_31_bytes";
+        let mut long_name_felts = vec![Felt::ONE];
+        long_name_felts.push(cairo_short_string_to_felt(&long_name[..31]).unwrap());
+        long_name_felts.push(cairo_short_string_to_felt(&long_name[31..]).unwrap());
+        long_name_felts.push(Felt::from(long_name.len() - 31));
+
+        let model_keys = [byte_array("Position"), byte_array("ns")].concat();
+
+        let events = vec![
+            event("NamespaceRegistered", long_name_felts, vec![felt!("0x1")]),
+            event("ModelRegistered", model_keys, vec![felt!("0x2"), felt!("0x3")]),
+            event("WorldSpawned", vec![], vec![felt!("0x4"), felt!("0x5")]),
+            event(
+                "StoreSetRecord",
+                vec![felt!("0x10"), felt!("0x11")],
+                vec![felt!("0x1"), felt!("0x12"), felt!("0x2"), felt!("0x13"), felt!("0x14")],
+            ),
+            event(
+                "StoreUpdateMember",
+                vec![felt!("0x10"), felt!("0x11"), felt!("0x15")],
+                vec![felt!("0x1"), felt!("0x16")],
+            ),
+            event("StoreDelRecord", vec![felt!("0x10"), felt!("0x11")], vec![]),
+            // a malformed event is skipped
+            event("StoreUpdateRecord", vec![felt!("0x10"), felt!("0x11")], vec![felt!("0x2")]),
+        ];
+
+        let updates = WorldUpdates::from_events(&events);
+
+        assert_eq!(
+            updates.registrations,
+            vec![
+                ResourceRegistration::Namespace {
+                    namespace: long_name.to_string(),
+                    hash: felt!("0x1")
+                },
+                ResourceRegistration::Model {
+                    namespace: "ns".to_string(),
+                    name: "Position".to_string(),
+                    class_hash: felt!("0x2"),
+                    address: felt!("0x3"),
+                },
+            ]
+        );
+
+        assert_eq!(
+            updates.model_writes,
+            vec![
+                ModelWrite::SetRecord {
+                    model: felt!("0x10"),
+                    entity_id: felt!("0x11"),
+                    keys: vec![felt!("0x12")],
+                    values: vec![felt!("0x13"), felt!("0x14")],
+                },
+                ModelWrite::UpdateMember {
+                    model: felt!("0x10"),
+                    entity_id: felt!("0x11"),
+                    member: felt!("0x15"),
+                    values: vec![felt!("0x16")],
+                },
+                ModelWrite::DeleteRecord { model: felt!("0x10"), entity_id: felt!("0x11") },
+            ]
+        );
+    }
+}
@@ -1,13 +1,27 @@
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 use jsonrpsee::core::{async_trait, Error};
 use katana_core::backend::Backend;
 use katana_core::service::block_producer::{BlockProducer, BlockProducerMode, PendingExecutor};
-use katana_executor::ExecutorFactory;
+use katana_executor::{ExecutorFactory, Session};
+use katana_primitives::block::GasPrices;
+use katana_primitives::contract::{ContractAddress, StorageKey, StorageValue};
+use katana_primitives::fee::PriceUnit;
+use katana_primitives::genesis::constant::get_fee_token_balance_base_storage_address;
+use katana_primitives::state::StateUpdates;
+use katana_primitives::transaction::{ExecutableTx, ExecutableTxWithHash};
 use katana_primitives::Felt;
+use katana_provider::traits::block::{BlockNumberProvider, BlockUnwinder};
+use katana_provider::traits::checkpoint::{CheckpointId, CheckpointProvider};
+use katana_provider::traits::state::{
+    ContractStorageProvider, StateFactoryProvider, StateProvider, StateWriter,
+};
 use katana_rpc_api::dev::DevApiServer;
-use katana_rpc_types::account::Account;
+use katana_rpc_types::account::{Account, AccountSession, SessionPolicy};
 use katana_rpc_types::error::dev::DevApiError;
+use katana_rpc_types::transaction::BroadcastedTx;
+use starknet::signers::SigningKey;
 
 #[allow(missing_debug_implementations)]
 pub struct DevApi<EF: ExecutorFactory> {
@@ -41,22 +55,226 @@ impl<EF: ExecutorFactory> DevApi<EF> {
             return Err(DevApiError::PendingTransactions);
         }
 
-        let mut block_context_generator = self.backend.block_context_generator.write();
-        block_context_generator.next_block_start_time = timestamp;
-
-        Ok(())
+        self.backend.block_context_generator.write().next_block_start_time = timestamp;
+        self.reopen_pending_block()
     }
 
     pub fn increase_next_block_timestamp(&self, offset: u64) -> Result<(), DevApiError> {
+        self.increase_time(offset)
+    }
+
+    /// Moves the clock of the node forward: the next block, and all the blocks after it, are
+    /// timestamped `seconds` later than they would have been.
+    pub fn increase_time(&self, seconds: u64) -> Result<(), DevApiError> {
+        if self.has_pending_transactions() {
+            return Err(DevApiError::PendingTransactions);
+        }
+
+        self.backend.block_context_generator.write().block_timestamp_offset += seconds as i64;
+        self.reopen_pending_block()
+    }
+
+    /// Sets the L1 gas prices of the next blocks. In _interval_ mode, they also apply to the
+    /// pending block as long as it has no transactions.
+    pub fn set_block_gas_price(&self, gas_prices: GasPrices, data_gas_prices: GasPrices) {
+        self.backend.gas_oracle.set_fixed(gas_prices, data_gas_prices);
+        // the pending block may have received transactions in the meantime, the prices then
+        // apply from the next block.
+        let _ = self.reopen_pending_block();
+    }
+
+    /// Applies the updated block context to the pending block, if it's still empty.
+    fn reopen_pending_block(&self) -> Result<(), DevApiError> {
+        self.block_producer
+            .reopen_pending_block()
+            .map(|_| ())
+            .map_err(|e| DevApiError::UnexpectedError { reason: e.to_string() })
+    }
+
+    pub fn dump_storage(
+        &self,
+        address: ContractAddress,
+    ) -> Result<BTreeMap<StorageKey, StorageValue>, DevApiError> {
+        self.ensure_contract_exists(address)?;
+        Ok(self.backend.blockchain.provider().storage_entries(address)?)
+    }
+
+    /// The storage is written by mining a block without transactions, so that the new values are
+    /// part of the state history and of the state root like any other update. In _interval_ mode,
+    /// the pending block must not have transactions yet.
+    pub fn load_storage(
+        &self,
+        address: ContractAddress,
+        entries: BTreeMap<StorageKey, StorageValue>,
+    ) -> Result<(), DevApiError> {
+        if self.has_pending_transactions() {
+            return Err(DevApiError::PendingTransactions);
+        }
+
+        self.ensure_contract_exists(address)?;
+
+        let state_updates = StateUpdates {
+            storage_updates: BTreeMap::from([(address, entries)]),
+            ..Default::default()
+        };
+
+        self.block_producer
+            .mine_state_updates(state_updates)
+            .map(|_| ())
+            .map_err(|e| DevApiError::UnexpectedError { reason: e.to_string() })
+    }
+
+    /// The snapshot doesn't include the pending block of _interval_ mode.
+    pub fn snapshot(&self) -> Result<CheckpointId, DevApiError> {
+        Ok(self.backend.blockchain.provider().checkpoint()?)
+    }
+
+    /// Reverts the chain to a snapshot, and opens the pending block again on top of its latest
+    /// block. In _interval_ mode, the pending block must not have transactions yet.
+    pub fn revert(&self, id: CheckpointId) -> Result<(), DevApiError> {
         if self.has_pending_transactions() {
             return Err(DevApiError::PendingTransactions);
         }
 
-        let mut block_context_generator = self.backend.block_context_generator.write();
-        block_context_generator.block_timestamp_offset += offset as i64;
+        if !self.backend.blockchain.provider().revert_to_checkpoint(id)? {
+            return Err(DevApiError::SnapshotNotFound);
+        }
+
+        self.block_producer
+            .reset_to_latest_block()
+            .map(|_| ())
+            .map_err(|e| DevApiError::UnexpectedError { reason: e.to_string() })
+    }
+
+    /// Replaces the last `depth` blocks with as many new blocks, the first one including
+    /// `transactions`. In _interval_ mode, the pending block must not have transactions yet.
+    pub fn reorg(&self, depth: u64, transactions: Vec<BroadcastedTx>) -> Result<(), DevApiError> {
+        if self.has_pending_transactions() {
+            return Err(DevApiError::PendingTransactions);
+        }
+
+        let chain_id = self.backend.chain_spec.id;
+        let mut transactions = transactions
+            .into_iter()
+            .map(|tx| {
+                let tx = match tx {
+                    BroadcastedTx::Invoke(tx) => {
+                        ExecutableTx::Invoke(tx.into_tx_with_chain_id(chain_id))
+                    }
+                    BroadcastedTx::DeployAccount(tx) => {
+                        ExecutableTx::DeployAccount(tx.into_tx_with_chain_id(chain_id))
+                    }
+                    BroadcastedTx::Declare(_) => {
+                        let reason = "declare transactions are not supported".to_string();
+                        return Err(DevApiError::InvalidReorg { reason });
+                    }
+                };
+                Ok(ExecutableTxWithHash::new(tx))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // the genesis block can't be unwound
+        let provider = self.backend.blockchain.provider();
+        let latest = provider.latest_number()?;
+        if depth == 0 || depth > latest - self.backend.chain_spec.genesis.number {
+            let reason =
+                format!("depth must be between 1 and the number of mined blocks, got {depth}");
+            return Err(DevApiError::InvalidReorg { reason });
+        }
+
+        provider.unwind_to(latest - depth)?;
+        self.block_producer
+            .reset_to_latest_block()
+            .map_err(|e| DevApiError::UnexpectedError { reason: e.to_string() })?;
+
+        for _ in 0..depth {
+            self.block_producer
+                .mine_transactions(std::mem::take(&mut transactions))
+                .map_err(|e| DevApiError::UnexpectedError { reason: e.to_string() })?;
+        }
+
+        Ok(())
+    }
+
+    pub fn impersonate_account(&self, address: ContractAddress) {
+        self.backend.executor_factory.execution_flags().impersonated_accounts().insert(address);
+    }
+
+    pub fn stop_impersonating(&self, address: ContractAddress) {
+        self.backend.executor_factory.execution_flags().impersonated_accounts().remove(address);
+    }
+
+    /// Sessions can only be minted for the predeployed accounts whose private key is known to the
+    /// node, as it's the owner of their signing key.
+    pub fn mint_session(
+        &self,
+        address: ContractAddress,
+        policies: Vec<SessionPolicy>,
+        expires_at: u64,
+    ) -> Result<AccountSession, DevApiError> {
+        let is_dev_account = self
+            .backend
+            .chain_spec
+            .genesis
+            .accounts()
+            .any(|(account, alloc)| *account == address && alloc.private_key().is_some());
+
+        if !is_dev_account {
+            return Err(DevApiError::NotDevAccount);
+        }
+
+        let key = SigningKey::from_random();
+        let public_key = key.verifying_key().scalar();
+        let session = Session { public_key, expires_at, policies: policies.clone() };
+        self.backend.executor_factory.execution_flags().sessions().insert(address, session);
+
+        Ok(AccountSession {
+            account_address: address,
+            public_key,
+            private_key: key.secret_scalar(),
+            expires_at,
+            policies,
+        })
+    }
+
+    pub fn revoke_sessions(&self, address: ContractAddress) {
+        self.backend.executor_factory.execution_flags().sessions().revoke(address);
+    }
+
+    /// The balance is written directly to the latest state. The total supply of the fee token is
+    /// left unchanged.
+    pub fn set_balance(
+        &self,
+        address: ContractAddress,
+        amount: Felt,
+        unit: PriceUnit,
+    ) -> Result<(), DevApiError> {
+        let fee_contracts = &self.backend.chain_spec.fee_contracts;
+        let token = match unit {
+            PriceUnit::Wei => fee_contracts.eth,
+            PriceUnit::Fri => fee_contracts.strk,
+        };
+
+        // the balance is stored as an u256, split in its low and high u128
+        let bytes = amount.to_bytes_be();
+        let high = Felt::from_bytes_be_slice(&bytes[..16]);
+        let low = Felt::from_bytes_be_slice(&bytes[16..]);
+
+        let key = get_fee_token_balance_base_storage_address(address);
+        let provider = self.backend.blockchain.provider();
+        provider.set_storage(token, key, low)?;
+        provider.set_storage(token, key + Felt::ONE, high)?;
 
         Ok(())
     }
+
+    fn ensure_contract_exists(&self, address: ContractAddress) -> Result<(), DevApiError> {
+        let state = self.backend.blockchain.provider().latest()?;
+        match state.class_hash_of_contract(address)? {
+            Some(_) => Ok(()),
+            None => Err(DevApiError::ContractNotFound),
+        }
+    }
 }
 
 #[async_trait]
@@ -79,6 +297,19 @@ impl<EF: ExecutorFactory> DevApiServer for DevApi<EF> {
         Ok(self.increase_next_block_timestamp(timestamp)?)
     }
 
+    async fn increase_time(&self, seconds: u64) -> Result<(), Error> {
+        Ok(self.increase_time(seconds)?)
+    }
+
+    async fn set_block_gas_price(
+        &self,
+        gas_prices: GasPrices,
+        data_gas_prices: GasPrices,
+    ) -> Result<(), Error> {
+        self.set_block_gas_price(gas_prices, data_gas_prices);
+        Ok(())
+    }
+
     async fn set_storage_at(
         &self,
         _contract_address: Felt,
@@ -92,6 +323,68 @@ impl<EF: ExecutorFactory> DevApiServer for DevApi<EF> {
         Ok(())
     }
 
+    async fn dump_storage(
+        &self,
+        contract_address: Felt,
+    ) -> Result<BTreeMap<StorageKey, StorageValue>, Error> {
+        Ok(self.dump_storage(contract_address.into())?)
+    }
+
+    async fn load_storage(
+        &self,
+        contract_address: Felt,
+        entries: BTreeMap<StorageKey, StorageValue>,
+    ) -> Result<(), Error> {
+        Ok(self.load_storage(contract_address.into(), entries)?)
+    }
+
+    async fn snapshot(&self) -> Result<u64, Error> {
+        Ok(self.snapshot()?)
+    }
+
+    async fn revert(&self, snapshot_id: u64) -> Result<(), Error> {
+        Ok(self.revert(snapshot_id)?)
+    }
+
+    async fn reorg(&self, depth: u64, new_txs: Vec<BroadcastedTx>) -> Result<(), Error> {
+        Ok(self.reorg(depth, new_txs)?)
+    }
+
+    async fn impersonate_account(&self, address: Felt) -> Result<(), Error> {
+        self.impersonate_account(address.into());
+        Ok(())
+    }
+
+    async fn stop_impersonating(&self, address: Felt) -> Result<(), Error> {
+        self.stop_impersonating(address.into());
+        Ok(())
+    }
+
+    async fn mint_session(
+        &self,
+        address: Felt,
+        policies: Vec<SessionPolicy>,
+        expires_at: u64,
+    ) -> Result<AccountSession, Error> {
+        Ok(self.mint_session(address.into(), policies, expires_at)?)
+    }
+
+    async fn revoke_sessions(&self, address: Felt) -> Result<(), Error> {
+        self.revoke_sessions(address.into());
+        Ok(())
+    }
+
+    async fn set_balance(&self, address: Felt, amount: Felt, unit: PriceUnit) -> Result<(), Error> {
+        Ok(self.set_balance(address.into(), amount, unit)?)
+    }
+
+    async fn reload_config(&self) -> Result<(), Error> {
+        self.backend
+            .config_reloader
+            .reload()
+            .map_err(|e| DevApiError::ConfigReload { reason: e.to_string() }.into())
+    }
+
     async fn predeployed_accounts(&self) -> Result<Vec<Account>, Error> {
         Ok(self.backend.chain_spec.genesis.accounts().map(|e| Account::new(*e.0, e.1)).collect())
     }
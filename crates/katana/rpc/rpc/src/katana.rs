@@ -0,0 +1,656 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use anyhow::Context;
+use futures::{stream, StreamExt};
+use jsonrpsee::core::{async_trait, RpcResult};
+use jsonrpsee::types::SubscriptionResult;
+use jsonrpsee::SubscriptionSink;
+use katana_core::backend::dump::StateDump;
+use katana_core::backend::{transaction_commitment_leaf, Backend, BlockStorageDiffs};
+use katana_core::service::block_producer::BlockProducer;
+use katana_executor::{EntryPointCall, ExecutionResult, ExecutorFactory};
+use katana_pool::{TransactionPool, TxPool};
+use katana_primitives::block::{BlockHashOrNumber, BlockIdOrTag, BlockNumber, BlockTag};
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::receipt::ReceiptWithTxHash;
+use katana_primitives::state::StateUpdates;
+use katana_primitives::trace::{CallInfo, TxExecInfo};
+use katana_primitives::transaction::{
+    DeclareTxWithClass, ExecutableTx, ExecutableTxWithHash, Tx, TxHash, TxWithHash,
+};
+use katana_provider::traits::block::{
+    BlockHashProvider, BlockIdReader, BlockNumberProvider, BlockProvider, BlockStatusProvider,
+    HeaderExtensionProvider, HeaderProvider,
+};
+use katana_provider::traits::contract::ContractClassProvider;
+use katana_provider::traits::env::BlockEnvProvider;
+use katana_provider::traits::state::{StateFactoryProvider, StateProvider};
+use katana_provider::traits::state_update::StateUpdateProvider;
+use katana_provider::traits::transaction::{
+    ReceiptProvider, TransactionProvider, TransactionTraceProvider, TransactionsProviderExt,
+};
+use katana_rpc_api::katana::KatanaApiServer;
+use katana_rpc_types::block::{
+    BlockHashVerification, BlockHeaderExtension, BlockWithTxHashes, BlockWithTxHashesAndReceipts,
+    BlocksPage,
+};
+use katana_rpc_types::error::katana::KatanaApiError;
+use katana_rpc_types::error::starknet::StarknetApiError;
+use katana_rpc_types::pool::{TxPoolContent, TxPoolStatus};
+use katana_rpc_types::proof::TransactionInclusionProof;
+use katana_rpc_types::receipt::{TxExecutionResources, TxReceipt, TxResourceUsage};
+use katana_rpc_types::state_update::{
+    EncodedStateDiff, StateDiffFormat, StateDiffRange, StorageDiffItem,
+};
+use katana_rpc_types::world::{EntityStorage, WorldStateUpdate, WorldUpdates};
+use katana_rpc_types::FunctionCall;
+use katana_rpc_types_builder::StateUpdateBuilder;
+use katana_tasks::TokioTaskSpawner;
+use katana_trie::compute_merkle_proof;
+use starknet_types_core::hash;
+use tracing::warn;
+
+use crate::starknet::worker::{WorkerPool, WorkerPoolBusy};
+use crate::utils::storage::entity_storage_addresses;
+
+const LOG_TARGET: &str = "rpc";
+
+/// The maximum number of storage slots watched for a single entity by
+/// `katana_subscribeStorageDiffs`.
+const MAX_ENTITY_STORAGE_SLOTS: usize = 10_000;
+
+/// The maximum number of blocks returned by a single `katana_getBlocks` call.
+const MAX_BLOCKS_PER_PAGE: u64 = 100;
+
+/// The maximum number of blocks whose state diffs are aggregated by a single
+/// `katana_getStateDiffRange` call.
+const MAX_STATE_DIFF_RANGE: u64 = 1000;
+
+#[allow(missing_debug_implementations)]
+pub struct KatanaApi<EF: ExecutorFactory> {
+    backend: Arc<Backend<EF>>,
+    pool: TxPool,
+    block_producer: BlockProducer<EF>,
+    workers: WorkerPool,
+}
+
+impl<EF: ExecutorFactory> Clone for KatanaApi<EF> {
+    fn clone(&self) -> Self {
+        Self {
+            backend: Arc::clone(&self.backend),
+            pool: self.pool.clone(),
+            block_producer: self.block_producer.clone(),
+            workers: self.workers.clone(),
+        }
+    }
+}
+
+impl<EF: ExecutorFactory> KatanaApi<EF> {
+    pub fn new(
+        backend: Arc<Backend<EF>>,
+        pool: TxPool,
+        block_producer: BlockProducer<EF>,
+        workers: WorkerPool,
+    ) -> Self {
+        Self { backend, pool, block_producer, workers }
+    }
+
+    /// Runs an execution-heavy task on the worker pool shared with the Starknet API. Fails with
+    /// [`StarknetApiError::ServerBusy`] if too many tasks are already waiting for a worker.
+    async fn on_cpu_blocking_task<F, T>(&self, func: F) -> RpcResult<T>
+    where
+        F: FnOnce(Self) -> RpcResult<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let this = self.clone();
+        match self.workers.spawn(move || func(this)) {
+            Ok(handle) => handle.await.unwrap(),
+            Err(WorkerPoolBusy) => Err(StarknetApiError::ServerBusy.into()),
+        }
+    }
+
+    async fn on_io_blocking_task<F, T>(&self, func: F) -> T
+    where
+        F: FnOnce(Self) -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let this = self.clone();
+        TokioTaskSpawner::new().unwrap().spawn_blocking(move || func(this)).await.unwrap()
+    }
+
+    /// Returns the changes made by a new block to the storage slots of the given entities, or to
+    /// every slot of the contract if no entity is given.
+    async fn entity_storage_diffs(
+        self,
+        block: BlockStorageDiffs,
+        contract: ContractAddress,
+        entities: Arc<Vec<EntityStorage>>,
+    ) -> Vec<StorageDiffItem> {
+        let number = block.block_number;
+        let diffs = block.diffs.into_iter().filter(move |diff| diff.contract_address == contract);
+
+        if entities.is_empty() {
+            return diffs.map(|diff| StorageDiffItem::new(number, diff)).collect();
+        }
+
+        // the lengths of the arrays of the entities are read from the state right after the block
+        let result = self
+            .on_io_blocking_task(move |this| {
+                let provider = this.backend.blockchain.provider();
+                let state = provider
+                    .historical(BlockHashOrNumber::Num(number))?
+                    .context("Missing state of the block.")?;
+                let storage = |key| -> anyhow::Result<_> {
+                    Ok(state.storage(contract, key)?.unwrap_or_default())
+                };
+
+                let mut addresses = HashSet::new();
+                for entity in entities.iter() {
+                    let entity_addresses =
+                        entity_storage_addresses(entity, storage, MAX_ENTITY_STORAGE_SLOTS)?;
+                    addresses.extend(entity_addresses);
+                }
+
+                Ok::<_, anyhow::Error>(addresses)
+            })
+            .await;
+
+        match result {
+            Ok(addresses) => diffs
+                .filter(|diff| addresses.contains(&diff.key))
+                .map(|diff| StorageDiffItem::new(number, diff))
+                .collect(),
+            Err(error) => {
+                warn!(target: LOG_TARGET, %error, %number, "Failed to read entities storage slots.");
+                Vec::new()
+            }
+        }
+    }
+}
+
+/// Returns the executable form of a mined transaction, reading the classes declared by the
+/// declare transactions from `state`.
+fn executable_tx(
+    tx: TxWithHash,
+    state: &dyn StateProvider,
+) -> anyhow::Result<ExecutableTxWithHash> {
+    let transaction = match tx.transaction {
+        Tx::Invoke(tx) => ExecutableTx::Invoke(tx),
+        Tx::L1Handler(tx) => ExecutableTx::L1Handler(tx),
+        Tx::DeployAccount(tx) => ExecutableTx::DeployAccount(tx),
+        Tx::Declare(tx) => {
+            let class_hash = tx.class_hash();
+            let compiled_class = state
+                .class(class_hash)?
+                .with_context(|| format!("Missing class {class_hash:#x}."))?;
+            let sierra_class = state.sierra_class(class_hash)?;
+            ExecutableTx::Declare(DeclareTxWithClass {
+                sierra_class,
+                compiled_class,
+                transaction: tx,
+            })
+        }
+    };
+
+    Ok(ExecutableTxWithHash { hash: tx.hash, transaction })
+}
+
+#[async_trait]
+impl<EF: ExecutorFactory> KatanaApiServer for KatanaApi<EF> {
+    async fn block_header_extension(
+        &self,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<Option<BlockHeaderExtension>> {
+        self.on_io_blocking_task(move |this| {
+            let provider = this.backend.blockchain.provider();
+
+            let number = provider
+                .convert_block_id(block_id)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::BlockNotFound)?;
+
+            let hash = provider
+                .block_hash_by_num(number)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::BlockNotFound)?;
+
+            let extension = provider
+                .header_extension(BlockHashOrNumber::Num(number))
+                .map_err(|_| KatanaApiError::Internal)?;
+
+            Ok(extension.map(|ext| BlockHeaderExtension::new(hash, number, ext)))
+        })
+        .await
+    }
+
+    async fn transaction_inclusion_proof(
+        &self,
+        transaction_hash: TxHash,
+    ) -> RpcResult<TransactionInclusionProof> {
+        self.on_io_blocking_task(move |this| {
+            let provider = this.backend.blockchain.provider();
+
+            let (block_number, block_hash) = provider
+                .transaction_block_num_and_hash(transaction_hash)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::TxnHashNotFound)?;
+
+            let block_id = BlockHashOrNumber::Num(block_number);
+            let header = provider
+                .header(block_id)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::BlockNotFound)?;
+            let transactions = provider
+                .transactions_by_block(block_id)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::BlockNotFound)?;
+            let receipts = provider
+                .receipts_by_block(block_id)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::BlockNotFound)?;
+
+            let index = transactions
+                .iter()
+                .position(|tx| tx.hash == transaction_hash)
+                .ok_or(KatanaApiError::TxnHashNotFound)?;
+
+            // the leaves must be computed the same way as when the block was committed
+            let transaction_leaves =
+                transactions.iter().map(transaction_commitment_leaf).collect::<Vec<_>>();
+            let receipt_hashes = transactions
+                .iter()
+                .zip(receipts)
+                .map(|(tx, receipt)| ReceiptWithTxHash::new(tx.hash, receipt).compute_hash())
+                .collect::<Vec<_>>();
+
+            let (transactions_commitment, transaction_proof) =
+                compute_merkle_proof::<hash::Poseidon>(&transaction_leaves, index)
+                    .map_err(|_| KatanaApiError::Internal)?;
+            let (receipts_commitment, receipt_proof) =
+                compute_merkle_proof::<hash::Poseidon>(&receipt_hashes, index)
+                    .map_err(|_| KatanaApiError::Internal)?;
+
+            // the proofs are only valid if the trees match the ones committed in the header
+            if transactions_commitment != header.transactions_commitment
+                || receipts_commitment != header.receipts_commitment
+            {
+                let reason = "commitments don't match the block header";
+                return Err(KatanaApiError::Internal.with_reason(reason));
+            }
+
+            Ok(TransactionInclusionProof {
+                transaction_hash,
+                block_hash,
+                block_number,
+                transaction_index: index as u64,
+                transaction_leaf: transaction_leaves[index],
+                transactions_commitment,
+                transaction_proof: transaction_proof.into_iter().map(Into::into).collect(),
+                receipt_hash: receipt_hashes[index],
+                receipts_commitment,
+                receipt_proof: receipt_proof.into_iter().map(Into::into).collect(),
+            })
+        })
+        .await
+    }
+
+    async fn transaction_resources(&self, transaction_hash: TxHash) -> RpcResult<TxResourceUsage> {
+        self.on_io_blocking_task(move |this| {
+            let provider = this.backend.blockchain.provider();
+
+            let receipt = provider
+                .receipt_by_hash(transaction_hash)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::TxnHashNotFound)?;
+            let trace = provider
+                .transaction_execution(transaction_hash)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::TxnHashNotFound)?;
+
+            Ok(TxResourceUsage::new(transaction_hash, &receipt, &trace))
+        })
+        .await
+    }
+
+    async fn execution_resources(
+        &self,
+        transaction_hash: TxHash,
+    ) -> RpcResult<TxExecutionResources> {
+        self.on_io_blocking_task(move |this| {
+            let provider = this.backend.blockchain.provider();
+
+            let receipt = provider
+                .receipt_by_hash(transaction_hash)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::TxnHashNotFound)?;
+
+            Ok(TxExecutionResources::new(transaction_hash, receipt.resources_used()))
+        })
+        .await
+    }
+
+    async fn blocks(
+        &self,
+        from: BlockNumber,
+        to: BlockNumber,
+        include_receipts: Option<bool>,
+    ) -> RpcResult<BlocksPage> {
+        if from > to {
+            let reason = "`from` block must not be greater than `to` block";
+            return Err(KatanaApiError::InvalidBlockRange.with_reason(reason));
+        }
+
+        self.on_io_blocking_task(move |this| {
+            let provider = this.backend.blockchain.provider();
+
+            let latest = provider.latest_number().map_err(|_| KatanaApiError::Internal)?;
+            if from > latest {
+                return Err(KatanaApiError::BlockNotFound.into());
+            }
+
+            // the blocks of the range that aren't mined yet can't be returned
+            let end = to.min(latest);
+            let last = end.min(from.saturating_add(MAX_BLOCKS_PER_PAGE - 1));
+
+            let mut blocks = Vec::with_capacity((last - from + 1) as usize);
+
+            for number in from..=last {
+                let id = BlockHashOrNumber::Num(number);
+
+                let hash = provider
+                    .block_hash_by_num(number)
+                    .map_err(|_| KatanaApiError::Internal)?
+                    .ok_or(KatanaApiError::BlockNotFound)?;
+                let block = provider
+                    .block_with_tx_hashes(id)
+                    .map_err(|_| KatanaApiError::Internal)?
+                    .ok_or(KatanaApiError::BlockNotFound)?;
+                let status = provider
+                    .block_status(id)
+                    .map_err(|_| KatanaApiError::Internal)?
+                    .ok_or(KatanaApiError::BlockNotFound)?;
+
+                let receipts = if include_receipts.unwrap_or_default() {
+                    let receipts = provider
+                        .receipts_by_block(id)
+                        .map_err(|_| KatanaApiError::Internal)?
+                        .ok_or(KatanaApiError::BlockNotFound)?;
+
+                    let receipts = block.body.iter().zip(receipts);
+                    Some(receipts.map(|(hash, r)| TxReceipt::new(*hash, status, r)).collect())
+                } else {
+                    None
+                };
+
+                let block = BlockWithTxHashes::new(hash, block, status);
+                blocks.push(BlockWithTxHashesAndReceipts { block, receipts });
+            }
+
+            let next_block = (last < end).then_some(last + 1);
+            Ok(BlocksPage { blocks, next_block })
+        })
+        .await
+    }
+
+    async fn state_diff_range(
+        &self,
+        from_block: BlockNumber,
+        to_block: BlockNumber,
+        format: Option<StateDiffFormat>,
+    ) -> RpcResult<StateDiffRange> {
+        if from_block > to_block {
+            let reason = "`from` block must not be greater than `to` block";
+            return Err(KatanaApiError::InvalidBlockRange.with_reason(reason));
+        }
+
+        if to_block - from_block >= MAX_STATE_DIFF_RANGE {
+            let reason = format!("at most {MAX_STATE_DIFF_RANGE} blocks can be requested");
+            return Err(KatanaApiError::InvalidBlockRange.with_reason(reason));
+        }
+
+        self.on_io_blocking_task(move |this| {
+            let provider = this.backend.blockchain.provider();
+
+            let latest = provider.latest_number().map_err(|_| KatanaApiError::Internal)?;
+            if to_block > latest {
+                return Err(KatanaApiError::BlockNotFound.into());
+            }
+
+            let mut state_updates = StateUpdates::default();
+            for number in from_block..=to_block {
+                let updates = provider
+                    .state_update(BlockHashOrNumber::Num(number))
+                    .map_err(|_| KatanaApiError::Internal)?
+                    .ok_or(KatanaApiError::BlockNotFound)?;
+                state_updates.merge(updates);
+            }
+
+            let state_diff = EncodedStateDiff::new(state_updates, format.unwrap_or_default());
+            Ok(StateDiffRange { from_block, to_block, state_diff })
+        })
+        .await
+    }
+
+    async fn verify_block_hash(&self, block_id: BlockIdOrTag) -> RpcResult<BlockHashVerification> {
+        self.on_io_blocking_task(move |this| {
+            let provider = this.backend.blockchain.provider();
+
+            let number = provider
+                .convert_block_id(block_id)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::BlockNotFound)?;
+
+            if number == this.backend.chain_spec.genesis.number {
+                let reason = "the genesis block isn't mined";
+                return Err(KatanaApiError::FailedToVerifyBlockHash.with_reason(reason));
+            }
+
+            let verification = this
+                .backend
+                .verify_block_hash(BlockHashOrNumber::Num(number))
+                .map_err(|e| KatanaApiError::FailedToVerifyBlockHash.with_reason(e))?
+                .ok_or(KatanaApiError::BlockNotFound)?;
+
+            Ok(verification.into())
+        })
+        .await
+    }
+
+    async fn world_state_update(
+        &self,
+        block_id: BlockIdOrTag,
+        world_address: ContractAddress,
+    ) -> RpcResult<WorldStateUpdate> {
+        // the pending block has no state update yet
+        if matches!(block_id, BlockIdOrTag::Tag(BlockTag::Pending)) {
+            return Err(KatanaApiError::BlockNotFound.into());
+        }
+
+        self.on_io_blocking_task(move |this| {
+            let provider = this.backend.blockchain.provider();
+
+            let number = provider
+                .convert_block_id(block_id)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::BlockNotFound)?;
+            let id = BlockHashOrNumber::Num(number);
+
+            let state = provider
+                .historical(id)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::BlockNotFound)?;
+            state
+                .class_hash_of_contract(world_address)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::ContractNotFound)?;
+
+            let state_update = StateUpdateBuilder::new(id, provider)
+                .build()
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::BlockNotFound)?;
+            let receipts = provider
+                .receipts_by_block(id)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::BlockNotFound)?;
+
+            let events = receipts
+                .iter()
+                .flat_map(|receipt| receipt.events())
+                .filter(|event| event.from_address == world_address);
+
+            let world = WorldUpdates::from_events(events);
+            Ok(WorldStateUpdate { state_update: state_update.into(), world })
+        })
+        .await
+    }
+
+    async fn debug_trace_call(
+        &self,
+        request: FunctionCall,
+        block_id: BlockIdOrTag,
+    ) -> RpcResult<CallInfo> {
+        self.on_cpu_blocking_task(move |this| {
+            let provider = this.backend.blockchain.provider();
+
+            let number = provider
+                .convert_block_id(block_id)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::BlockNotFound)?;
+            let id = BlockHashOrNumber::Num(number);
+
+            let state = provider
+                .historical(id)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::BlockNotFound)?;
+            let env = provider
+                .block_env_at(id)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::BlockNotFound)?;
+
+            let call = EntryPointCall {
+                calldata: request.calldata,
+                contract_address: request.contract_address.into(),
+                entry_point_selector: request.entry_point_selector,
+            };
+
+            let executor = this.backend.executor_factory.with_state_and_block_env(state, env);
+            let trace = executor
+                .trace_call(call)
+                .map_err(|err| KatanaApiError::ContractError.with_reason(err))?;
+
+            Ok(trace)
+        })
+        .await
+    }
+
+    async fn debug_trace_transaction(&self, transaction_hash: TxHash) -> RpcResult<TxExecInfo> {
+        self.on_cpu_blocking_task(move |this| {
+            let provider = this.backend.blockchain.provider();
+
+            let (block_number, _) = provider
+                .transaction_block_num_and_hash(transaction_hash)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::TxnHashNotFound)?;
+            // the genesis block has no parent state to execute its transactions on
+            let parent_number = block_number.checked_sub(1).ok_or(KatanaApiError::BlockNotFound)?;
+
+            let block_id = BlockHashOrNumber::Num(block_number);
+            let transactions = provider
+                .transactions_by_block(block_id)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::BlockNotFound)?;
+            let index = transactions
+                .iter()
+                .position(|tx| tx.hash == transaction_hash)
+                .ok_or(KatanaApiError::TxnHashNotFound)?;
+
+            // the classes declared in the block are only in the state of the block itself
+            let block_state = provider
+                .historical(block_id)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::BlockNotFound)?;
+            let transactions = transactions
+                .into_iter()
+                .take(index + 1)
+                .map(|tx| executable_tx(tx, &*block_state))
+                .collect::<anyhow::Result<Vec<_>>>()
+                .map_err(|e| KatanaApiError::Internal.with_reason(e))?;
+
+            let state = provider
+                .historical(BlockHashOrNumber::Num(parent_number))
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::BlockNotFound)?;
+            let env = provider
+                .block_env_at(block_id)
+                .map_err(|_| KatanaApiError::Internal)?
+                .ok_or(KatanaApiError::BlockNotFound)?;
+
+            let flags = this.backend.executor_factory.execution_flags().clone();
+            let executor = this.backend.executor_factory.with_state_and_block_env(state, env);
+            let result = executor.simulate(transactions, flags).pop().map(|res| res.result);
+
+            match result {
+                Some(ExecutionResult::Success { trace, .. }) => Ok(trace),
+                Some(ExecutionResult::Failed { error }) => {
+                    Err(KatanaApiError::ContractError.with_reason(error))
+                }
+                None => Err(KatanaApiError::Internal.into()),
+            }
+        })
+        .await
+    }
+
+    async fn dump_state(&self) -> RpcResult<StateDump> {
+        self.on_io_blocking_task(move |this| {
+            this.backend.dump_state().map_err(|e| KatanaApiError::FailedToDumpState.with_reason(e))
+        })
+        .await
+    }
+
+    async fn load_state(&self, dump: StateDump) -> RpcResult<()> {
+        // the pending block of interval mining is built on the state it was opened with
+        if !self.block_producer.is_instant_mining() {
+            let reason = "state can only be loaded with instant mining";
+            return Err(KatanaApiError::FailedToLoadState.with_reason(reason));
+        }
+
+        self.on_io_blocking_task(move |this| {
+            this.backend
+                .load_state(dump)
+                .map_err(|e| KatanaApiError::FailedToLoadState.with_reason(e))
+        })
+        .await
+    }
+
+    async fn tx_pool_content(&self) -> RpcResult<TxPoolContent> {
+        Ok(self.pool.content().into())
+    }
+
+    async fn tx_pool_status(&self) -> RpcResult<TxPoolStatus> {
+        Ok(self.pool.status().into())
+    }
+
+    fn subscribe_storage_diffs(
+        &self,
+        mut sink: SubscriptionSink,
+        contract: ContractAddress,
+        entities: Vec<EntityStorage>,
+    ) -> SubscriptionResult {
+        sink.accept()?;
+
+        let this = self.clone();
+        let entities = Arc::new(entities);
+        let items = self
+            .backend
+            .add_storage_diff_listener()
+            .then(move |block| this.clone().entity_storage_diffs(block, contract, entities.clone()))
+            .flat_map(stream::iter);
+
+        tokio::spawn(async move {
+            let _ = sink.pipe_from_stream(items).await;
+        });
+
+        Ok(())
+    }
+}
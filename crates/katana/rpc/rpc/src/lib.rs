@@ -4,6 +4,7 @@
 #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
 pub mod dev;
+pub mod katana;
 pub mod metrics;
 pub mod saya;
 pub mod starknet;
@@ -0,0 +1,140 @@
+//! Compilation of the Sierra classes of declare transactions.
+//!
+//! Compiling a Sierra class to CASM can take seconds for large classes, so it's done on a
+//! dedicated thread pool instead of the threads handling the requests. The compiled classes are
+//! kept in a bounded cache, so that a class declared again, or whose declaration is estimated
+//! before being sent, isn't compiled twice.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use anyhow::{anyhow, Result};
+use katana_primitives::class::{ClassHash, CompiledClass, FlattenedSierraClass};
+use katana_primitives::conversion::rpc::flattened_sierra_to_compiled_class;
+use katana_tasks::BlockingTaskPool;
+use parking_lot::Mutex;
+
+/// The default number of compiled classes kept in the cache.
+pub const DEFAULT_CACHE_CAPACITY: usize = 128;
+
+/// The class hash and the compiled class of a Sierra class.
+pub type CompiledArtifact = (ClassHash, CompiledClass);
+
+#[derive(Debug, Clone)]
+pub struct ClassCompiler {
+    pool: BlockingTaskPool,
+    cache: Arc<Mutex<ArtifactCache>>,
+}
+
+impl ClassCompiler {
+    /// Creates a compiler keeping up to `capacity` compiled classes in its cache.
+    pub fn new(capacity: usize) -> Self {
+        let pool = BlockingTaskPool::build()
+            .thread_name(|i| format!("class-compiler-{i}"))
+            .build()
+            .map(BlockingTaskPool::new_with_pool)
+            .expect("failed to create class compiler pool");
+
+        Self { pool, cache: Arc::new(Mutex::new(ArtifactCache::new(capacity))) }
+    }
+
+    /// Compiles the class on the compiler thread pool, unless it's already in the cache.
+    pub async fn compile(&self, class: Arc<FlattenedSierraClass>) -> Result<CompiledArtifact> {
+        let class_hash = class.class_hash();
+        if let Some(artifact) = self.cache.lock().get(class_hash) {
+            return Ok(artifact);
+        }
+
+        let artifact = self
+            .pool
+            .spawn(move || flattened_sierra_to_compiled_class(&class))
+            .await
+            .map_err(|_| anyhow!("class compilation panicked"))??;
+
+        let artifact = (class_hash, artifact.2);
+        self.cache.lock().insert(class_hash, artifact.clone());
+        Ok(artifact)
+    }
+
+    /// Compiles the class on the current thread, unless it's already in the cache.
+    pub fn compile_blocking(&self, class: &FlattenedSierraClass) -> Result<CompiledArtifact> {
+        let class_hash = class.class_hash();
+        if let Some(artifact) = self.cache.lock().get(class_hash) {
+            return Ok(artifact);
+        }
+
+        let (_, _, compiled) = flattened_sierra_to_compiled_class(class)?;
+        let artifact = (class_hash, compiled);
+        self.cache.lock().insert(class_hash, artifact.clone());
+        Ok(artifact)
+    }
+}
+
+impl Default for ClassCompiler {
+    fn default() -> Self {
+        Self::new(DEFAULT_CACHE_CAPACITY)
+    }
+}
+
+/// A cache of compiled classes evicting the least recently used one when full.
+#[derive(Debug)]
+struct ArtifactCache {
+    capacity: usize,
+    /// The artifacts, along with the tick they were last used at.
+    entries: HashMap<ClassHash, (CompiledArtifact, u64)>,
+    tick: u64,
+}
+
+impl ArtifactCache {
+    fn new(capacity: usize) -> Self {
+        Self { capacity, entries: HashMap::new(), tick: 0 }
+    }
+
+    fn get(&mut self, class_hash: ClassHash) -> Option<CompiledArtifact> {
+        self.tick += 1;
+        let (artifact, last_used) = self.entries.get_mut(&class_hash)?;
+        *last_used = self.tick;
+        Some(artifact.clone())
+    }
+
+    fn insert(&mut self, class_hash: ClassHash, artifact: CompiledArtifact) {
+        if self.capacity == 0 {
+            return;
+        }
+
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&class_hash) {
+            let lru = self.entries.iter().min_by_key(|(_, (_, last_used))| *last_used);
+            if let Some(lru) = lru.map(|(hash, _)| *hash) {
+                self.entries.remove(&lru);
+            }
+        }
+
+        self.tick += 1;
+        self.entries.insert(class_hash, (artifact, self.tick));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::felt;
+    use katana_primitives::genesis::constant::DEFAULT_LEGACY_UDC_CASM;
+
+    use super::ArtifactCache;
+
+    #[test]
+    fn cache_evicts_least_recently_used() {
+        let class = || DEFAULT_LEGACY_UDC_CASM.clone();
+        let mut cache = ArtifactCache::new(2);
+
+        cache.insert(felt!("0x1"), (felt!("0x1"), class()));
+        cache.insert(felt!("0x2"), (felt!("0x2"), class()));
+
+        // using the first class makes the second one the least recently used
+        assert!(cache.get(felt!("0x1")).is_some());
+        cache.insert(felt!("0x3"), (felt!("0x3"), class()));
+
+        assert!(cache.get(felt!("0x1")).is_some());
+        assert!(cache.get(felt!("0x2")).is_none());
+        assert!(cache.get(felt!("0x3")).is_some());
+    }
+}
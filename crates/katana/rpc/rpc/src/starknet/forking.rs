@@ -283,7 +283,7 @@ impl<P: Provider> ForkedClient<P> {
 
         let events = self.provider.get_events(filter, continuation_token, chunk_size).await?;
 
-        Ok(events)
+        Ok(events.into())
     }
 }
 
@@ -1,12 +1,17 @@
 //! Server implementation for the Starknet JSON-RPC API.
 
+mod compiler;
 pub mod forking;
 mod read;
+mod subscription;
 mod trace;
+pub mod worker;
 mod write;
 
 use std::sync::Arc;
 
+use alloy_primitives::B256;
+use compiler::ClassCompiler;
 use forking::ForkedClient;
 use katana_core::backend::Backend;
 use katana_core::service::block_producer::{BlockProducer, BlockProducerMode, PendingExecutor};
@@ -23,8 +28,13 @@ use katana_primitives::conversion::rpc::legacy_inner_to_rpc_class;
 use katana_primitives::da::L1DataAvailabilityMode;
 use katana_primitives::env::BlockEnv;
 use katana_primitives::event::MaybeForkedContinuationToken;
-use katana_primitives::transaction::{ExecutableTxWithHash, TxHash, TxWithHash};
+use katana_primitives::genesis::constant::get_fee_token_balance_base_storage_address;
+use katana_primitives::state::StateUpdates;
+use katana_primitives::transaction::{
+    DeclareTxWithClass, ExecutableTxWithHash, TxHash, TxWithHash,
+};
 use katana_primitives::Felt;
+use katana_provider::providers::overlay::OverlayStateProvider;
 use katana_provider::traits::block::{BlockHashProvider, BlockIdReader, BlockNumberProvider};
 use katana_provider::traits::contract::ContractClassProvider;
 use katana_provider::traits::env::BlockEnvProvider;
@@ -38,15 +48,19 @@ use katana_rpc_types::block::{
 };
 use katana_rpc_types::error::starknet::StarknetApiError;
 use katana_rpc_types::event::{EventFilterWithPage, EventsPage};
+use katana_rpc_types::message::MessageStatus;
 use katana_rpc_types::receipt::{ReceiptBlock, TxReceiptWithBlockInfo};
+use katana_rpc_types::state_override::StateOverride;
 use katana_rpc_types::state_update::MaybePendingStateUpdate;
-use katana_rpc_types::transaction::Tx;
-use katana_rpc_types::FeeEstimate;
+use katana_rpc_types::transaction::{BroadcastedDeclareTx, Tx};
+use katana_rpc_types::{CompiledCasm, FeeEstimate};
 use katana_rpc_types_builder::ReceiptBuilder;
-use katana_tasks::{BlockingTaskPool, TokioTaskSpawner};
+use katana_tasks::TokioTaskSpawner;
 use starknet::core::types::{
-    ContractClass, PriceUnit, ResultPageRequest, TransactionExecutionStatus, TransactionStatus,
+    ContractClass, PriceUnit, ResultPageRequest, SequencerTransactionStatus,
+    TransactionExecutionStatus, TransactionStatus,
 };
+use worker::{WorkerPool, WorkerPoolBusy};
 
 use crate::utils;
 use crate::utils::events::{Cursor, EventBlockId};
@@ -69,7 +83,8 @@ struct Inner<EF: ExecutorFactory> {
     pool: TxPool,
     backend: Arc<Backend<EF>>,
     block_producer: BlockProducer<EF>,
-    blocking_task_pool: BlockingTaskPool,
+    workers: WorkerPool,
+    compiler: ClassCompiler,
     forked_client: Option<ForkedClient>,
 }
 
@@ -79,8 +94,9 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
         pool: TxPool,
         block_producer: BlockProducer<EF>,
         validator: TxValidator,
+        workers: WorkerPool,
     ) -> Self {
-        Self::new_inner(backend, pool, block_producer, validator, None)
+        Self::new_inner(backend, pool, block_producer, validator, workers, None)
     }
 
     pub fn new_forked(
@@ -88,9 +104,10 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
         pool: TxPool,
         block_producer: BlockProducer<EF>,
         validator: TxValidator,
+        workers: WorkerPool,
         forked_client: ForkedClient,
     ) -> Self {
-        Self::new_inner(backend, pool, block_producer, validator, Some(forked_client))
+        Self::new_inner(backend, pool, block_producer, validator, workers, Some(forked_client))
     }
 
     fn new_inner(
@@ -98,22 +115,34 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
         pool: TxPool,
         block_producer: BlockProducer<EF>,
         validator: TxValidator,
+        workers: WorkerPool,
         forked_client: Option<ForkedClient>,
     ) -> Self {
-        let blocking_task_pool =
-            BlockingTaskPool::new().expect("failed to create blocking task pool");
-        let inner =
-            Inner { pool, backend, block_producer, blocking_task_pool, validator, forked_client };
+        let inner = Inner {
+            pool,
+            backend,
+            block_producer,
+            workers,
+            compiler: ClassCompiler::default(),
+            validator,
+            forked_client,
+        };
         Self { inner: Arc::new(inner) }
     }
 
-    async fn on_cpu_blocking_task<F, T>(&self, func: F) -> T
+    /// Runs an execution-heavy task on the worker pool. Fails with
+    /// [`StarknetApiError::ServerBusy`] if too many tasks are already waiting for a worker.
+    async fn on_cpu_blocking_task<F, T, E>(&self, func: F) -> Result<T, E>
     where
-        F: FnOnce(Self) -> T + Send + 'static,
+        F: FnOnce(Self) -> Result<T, E> + Send + 'static,
         T: Send + 'static,
+        E: From<StarknetApiError> + Send + 'static,
     {
         let this = self.clone();
-        self.inner.blocking_task_pool.spawn(move || func(this)).await.unwrap()
+        match self.inner.workers.spawn(move || func(this)) {
+            Ok(handle) => handle.await.unwrap(),
+            Err(WorkerPoolBusy) => Err(E::from(StarknetApiError::ServerBusy)),
+        }
     }
 
     async fn on_io_blocking_task<F, T>(&self, func: F) -> T
@@ -125,16 +154,42 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
         TokioTaskSpawner::new().unwrap().spawn_blocking(move || func(this)).await.unwrap()
     }
 
+    /// Converts a declare transaction, taking its compiled class from the compiler cache if it
+    /// was already compiled. The class is compiled on the current thread otherwise.
+    fn declare_tx_with_class(
+        &self,
+        tx: BroadcastedDeclareTx,
+    ) -> StarknetApiResult<DeclareTxWithClass> {
+        let chain_id = self.inner.backend.chain_spec.id;
+        let compiled = match tx.sierra_class() {
+            Some(class) => Some(
+                self.inner
+                    .compiler
+                    .compile_blocking(class)
+                    .map_err(|_| StarknetApiError::InvalidContractClass)?,
+            ),
+            None => None,
+        };
+
+        tx.try_into_tx_with_compiled_class(chain_id, compiled)
+            .map_err(|_| StarknetApiError::InvalidContractClass)
+    }
+
     fn estimate_fee_with(
         &self,
         transactions: Vec<ExecutableTxWithHash>,
         block_id: BlockIdOrTag,
         flags: katana_executor::ExecutionFlags,
+        state_override: Option<StateOverride>,
     ) -> StarknetApiResult<Vec<FeeEstimate>> {
         // get the state and block env at the specified block for execution
-        let state = self.state(&block_id)?;
+        let state = self.state_with_override(&block_id, state_override)?;
         let env = self.block_env_at(&block_id)?;
 
+        // the fee must be estimated the same way as it is accounted for by the node
+        let gas_accounting = self.inner.backend.executor_factory.execution_flags().gas_accounting();
+        let flags = flags.with_gas_accounting(gas_accounting);
+
         // create the executor
         let executor = self.inner.backend.executor_factory.with_state_and_block_env(state, env);
         let results = executor.estimate_fee(transactions, flags);
@@ -166,6 +221,53 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
         Ok(estimates)
     }
 
+    /// Returns the state at the specified block, with the overrides applied on top of it.
+    fn state_with_override(
+        &self,
+        block_id: &BlockIdOrTag,
+        state_override: Option<StateOverride>,
+    ) -> StarknetApiResult<OverlayStateProvider<Box<dyn StateProvider>>> {
+        let state = self.state(block_id)?;
+        let overrides = state_override.map(|o| self.state_updates_of(o)).unwrap_or_default();
+        Ok(OverlayStateProvider::new(state, overrides))
+    }
+
+    /// Converts the overrides of the contracts to the state updates to apply on the state. The
+    /// balances are written in the storage of both fee tokens.
+    fn state_updates_of(&self, state_override: StateOverride) -> StateUpdates {
+        let fee_contracts = &self.inner.backend.chain_spec.fee_contracts;
+        let mut updates = StateUpdates::default();
+
+        for (address, contract) in state_override {
+            if let Some(nonce) = contract.nonce {
+                updates.nonce_updates.insert(address, nonce);
+            }
+
+            if let Some(class_hash) = contract.class_hash {
+                updates.deployed_contracts.insert(address, class_hash);
+            }
+
+            if !contract.storage.is_empty() {
+                updates.storage_updates.entry(address).or_default().extend(contract.storage);
+            }
+
+            if let Some(balance) = contract.balance {
+                let bytes = balance.to_bytes_be();
+                let high = Felt::from_bytes_be_slice(&bytes[..16]);
+                let low = Felt::from_bytes_be_slice(&bytes[16..]);
+                let base = get_fee_token_balance_base_storage_address(address);
+
+                for token in [fee_contracts.eth, fee_contracts.strk] {
+                    let storage = updates.storage_updates.entry(token).or_default();
+                    storage.insert(base, low);
+                    storage.insert(base + Felt::ONE, high);
+                }
+            }
+        }
+
+        updates
+    }
+
     /// Returns the pending state if the sequencer is running in _interval_ mode. Otherwise `None`.
     fn pending_executor(&self) -> Option<PendingExecutor> {
         match &*self.inner.block_producer.producer.read() {
@@ -261,6 +363,21 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
         .await
     }
 
+    async fn compiled_casm(&self, class_hash: ClassHash) -> StarknetApiResult<CompiledCasm> {
+        self.on_io_blocking_task(move |this| {
+            let state = this.state(&BlockIdOrTag::Tag(BlockTag::Pending))?;
+
+            match state.class(class_hash)? {
+                Some(CompiledClass::Class(class)) => Ok(class.casm),
+                Some(CompiledClass::Deprecated(_)) => Err(StarknetApiError::CompilationError {
+                    compilation_error: "Cairo 0 classes aren't compiled to CASM".to_string(),
+                }),
+                None => Err(StarknetApiError::ClassHashNotFound),
+            }
+        })
+        .await
+    }
+
     async fn class_hash_at_address(
         &self,
         block_id: BlockIdOrTag,
@@ -576,6 +693,39 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
         }
     }
 
+    async fn messages_status(&self, l1_tx_hash: B256) -> StarknetApiResult<Vec<MessageStatus>> {
+        let hashes = self.inner.backend.l1_messages.read().get(&l1_tx_hash).cloned();
+        let hashes = hashes.ok_or(StarknetApiError::TxnHashNotFound)?;
+
+        let mut statuses = Vec::with_capacity(hashes.len());
+
+        for hash in hashes {
+            let (finality_status, execution_status) = match self.transaction_status(hash).await {
+                Ok(TransactionStatus::Received) => (SequencerTransactionStatus::Received, None),
+                Ok(TransactionStatus::Rejected) => (SequencerTransactionStatus::Rejected, None),
+                Ok(TransactionStatus::AcceptedOnL2(status)) => {
+                    (SequencerTransactionStatus::AcceptedOnL2, Some(status))
+                }
+                Ok(TransactionStatus::AcceptedOnL1(status)) => {
+                    (SequencerTransactionStatus::AcceptedOnL1, Some(status))
+                }
+                // the transaction was dropped from the pool without being included in a block
+                Err(StarknetApiError::TxnHashNotFound) => {
+                    (SequencerTransactionStatus::Rejected, None)
+                }
+                Err(err) => return Err(err),
+            };
+
+            statuses.push(MessageStatus {
+                transaction_hash: hash,
+                finality_status,
+                execution_status,
+            });
+        }
+
+        Ok(statuses)
+    }
+
     async fn block_with_txs(
         &self,
         block_id: BlockIdOrTag,
@@ -871,6 +1021,14 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
         let mut events = Vec::with_capacity(chunk_size as usize);
         let filter = utils::events::Filter { address, keys: keys.clone() };
 
+        // a token can only be used to continue the query it was issued for
+        let filter_hash = filter.hash(&from_block, &to_block);
+        if let Some(MaybeForkedContinuationToken::Token(token)) = &continuation_token {
+            if token.filter_hash != filter_hash {
+                return Err(StarknetApiError::InvalidContinuationToken);
+            }
+        }
+
         match (from, to) {
             (EventBlockId::Num(from), EventBlockId::Num(to)) => {
                 // 1. check if the from and to block is lower than the forked block
@@ -940,7 +1098,7 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
                     &mut events,
                 )?;
 
-                let continuation_token = cursor.map(|c| c.into_rpc_cursor().to_string());
+                let continuation_token = cursor.map(|c| c.into_rpc_cursor(filter_hash).to_string());
                 let events_page = EventsPage { events, continuation_token };
 
                 Ok(events_page)
@@ -1018,7 +1176,7 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
                 // if the internal cursor is Some, meaning the buffer is full and we havent
                 // reached the latest block.
                 if let Some(c) = int_cursor {
-                    let continuation_token = Some(c.into_rpc_cursor().to_string());
+                    let continuation_token = Some(c.into_rpc_cursor(filter_hash).to_string());
                     return Ok(EventsPage { events, continuation_token });
                 }
 
@@ -1031,11 +1189,11 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
                         &mut events,
                     )?;
 
-                    let continuation_token = Some(cursor.into_rpc_cursor().to_string());
+                    let continuation_token = Some(cursor.into_rpc_cursor(filter_hash).to_string());
                     Ok(EventsPage { events, continuation_token })
                 } else {
                     let cursor = Cursor::new_block(latest + 1);
-                    let continuation_token = Some(cursor.into_rpc_cursor().to_string());
+                    let continuation_token = Some(cursor.into_rpc_cursor(filter_hash).to_string());
                     Ok(EventsPage { events, continuation_token })
                 }
             }
@@ -1051,13 +1209,15 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
                         &mut events,
                     )?;
 
-                    let continuation_token = Some(new_cursor.into_rpc_cursor().to_string());
+                    let continuation_token =
+                        Some(new_cursor.into_rpc_cursor(filter_hash).to_string());
                     Ok(EventsPage { events, continuation_token })
                 } else {
                     let latest = provider.latest_number()?;
                     let new_cursor = Cursor::new_block(latest);
 
-                    let continuation_token = Some(new_cursor.into_rpc_cursor().to_string());
+                    let continuation_token =
+                        Some(new_cursor.into_rpc_cursor(filter_hash).to_string());
                     Ok(EventsPage { events, continuation_token })
                 }
             }
@@ -1,3 +1,4 @@
+use alloy_primitives::B256;
 use jsonrpsee::core::{async_trait, Error, RpcResult};
 use katana_executor::{EntryPointCall, ExecutorFactory};
 use katana_primitives::block::BlockIdOrTag;
@@ -10,12 +11,13 @@ use katana_rpc_types::block::{
 };
 use katana_rpc_types::error::starknet::StarknetApiError;
 use katana_rpc_types::event::{EventFilterWithPage, EventsPage};
-use katana_rpc_types::message::MsgFromL1;
+use katana_rpc_types::message::{MessageStatus, MsgFromL1};
 use katana_rpc_types::receipt::TxReceiptWithBlockInfo;
+use katana_rpc_types::state_override::StateOverride;
 use katana_rpc_types::state_update::MaybePendingStateUpdate;
 use katana_rpc_types::transaction::{BroadcastedTx, Tx};
 use katana_rpc_types::{
-    ContractClass, FeeEstimate, FeltAsHex, FunctionCall, SimulationFlagForEstimateFee,
+    CompiledCasm, ContractClass, FeeEstimate, FeltAsHex, FunctionCall, SimulationFlagForEstimateFee,
 };
 use starknet::core::types::TransactionStatus;
 
@@ -119,6 +121,10 @@ impl<EF: ExecutorFactory> StarknetApiServer for StarknetApi<EF> {
         Ok(self.class_at_hash(block_id, class_hash).await?)
     }
 
+    async fn get_compiled_casm(&self, class_hash: Felt) -> RpcResult<CompiledCasm> {
+        Ok(self.compiled_casm(class_hash).await?)
+    }
+
     async fn get_events(&self, filter: EventFilterWithPage) -> RpcResult<EventsPage> {
         Ok(self.events(filter).await?)
     }
@@ -127,8 +133,9 @@ impl<EF: ExecutorFactory> StarknetApiServer for StarknetApi<EF> {
         &self,
         request: FunctionCall,
         block_id: BlockIdOrTag,
+        state_override: Option<StateOverride>,
     ) -> RpcResult<Vec<FeltAsHex>> {
-        self.on_io_blocking_task(move |this| {
+        self.on_cpu_blocking_task(move |this| {
             let request = EntryPointCall {
                 calldata: request.calldata,
                 contract_address: request.contract_address.into(),
@@ -136,7 +143,7 @@ impl<EF: ExecutorFactory> StarknetApiServer for StarknetApi<EF> {
             };
 
             // get the state and block env at the specified block for function call execution
-            let state = this.state(&block_id)?;
+            let state = this.state_with_override(&block_id, state_override)?;
             let env = this.block_env_at(&block_id)?;
             let executor = this.inner.backend.executor_factory.with_state_and_block_env(state, env);
 
@@ -168,6 +175,7 @@ impl<EF: ExecutorFactory> StarknetApiServer for StarknetApi<EF> {
         request: Vec<BroadcastedTx>,
         simulation_flags: Vec<SimulationFlagForEstimateFee>,
         block_id: BlockIdOrTag,
+        state_override: Option<StateOverride>,
     ) -> RpcResult<Vec<FeeEstimate>> {
         self.on_cpu_blocking_task(move |this| {
             let chain_id = this.inner.backend.chain_spec.id;
@@ -193,9 +201,7 @@ impl<EF: ExecutorFactory> StarknetApiServer for StarknetApi<EF> {
 
                         BroadcastedTx::Declare(tx) => {
                             let is_query = tx.is_query();
-                            let tx = tx
-                                .try_into_tx_with_chain_id(chain_id)
-                                .map_err(|_| StarknetApiError::InvalidContractClass)?;
+                            let tx = this.declare_tx_with_class(tx)?;
                             ExecutableTxWithHash::new_query(ExecutableTx::Declare(tx), is_query)
                         }
                     };
@@ -217,11 +223,14 @@ impl<EF: ExecutorFactory> StarknetApiServer for StarknetApi<EF> {
             //
             // This doesn't completely disregard the nonce as nonce < account nonce will
             // return an error. It only 'relaxes' the check for nonce >= account nonce.
+            let node_flags = this.inner.backend.executor_factory.execution_flags();
             let flags = katana_executor::ExecutionFlags::new()
                 .with_account_validation(should_validate)
-                .with_nonce_check(false);
+                .with_nonce_check(false)
+                .with_impersonated_accounts(node_flags.impersonated_accounts().clone())
+                .with_sessions(node_flags.sessions().clone());
 
-            let results = this.estimate_fee_with(transactions, block_id, flags)?;
+            let results = this.estimate_fee_with(transactions, block_id, flags, state_override)?;
             Ok(results)
         })
         .await
@@ -231,6 +240,7 @@ impl<EF: ExecutorFactory> StarknetApiServer for StarknetApi<EF> {
         &self,
         message: MsgFromL1,
         block_id: BlockIdOrTag,
+        state_override: Option<StateOverride>,
     ) -> RpcResult<FeeEstimate> {
         self.on_cpu_blocking_task(move |this| {
             let chain_id = this.inner.backend.chain_spec.id;
@@ -242,6 +252,7 @@ impl<EF: ExecutorFactory> StarknetApiServer for StarknetApi<EF> {
                 vec![ExecutableTxWithHash { hash, transaction: tx.into() }],
                 block_id,
                 Default::default(),
+                state_override,
             );
             match result {
                 Ok(mut res) => {
@@ -266,4 +277,8 @@ impl<EF: ExecutorFactory> StarknetApiServer for StarknetApi<EF> {
     ) -> RpcResult<TransactionStatus> {
         Ok(self.transaction_status(transaction_hash).await?)
     }
+
+    async fn get_messages_status(&self, transaction_hash: B256) -> RpcResult<Vec<MessageStatus>> {
+        Ok(self.messages_status(transaction_hash).await?)
+    }
 }
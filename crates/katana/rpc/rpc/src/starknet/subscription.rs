@@ -0,0 +1,168 @@
+use futures::{future, stream, StreamExt};
+use jsonrpsee::types::error::ErrorCode;
+use jsonrpsee::types::{ErrorObject, SubscriptionResult};
+use jsonrpsee::SubscriptionSink;
+use katana_executor::ExecutorFactory;
+use katana_primitives::block::{BlockHashOrNumber, BlockNumber};
+use katana_primitives::transaction::TxHash;
+use katana_rpc_api::starknet::StarknetSubscriptionApiServer;
+use katana_rpc_types::error::starknet::StarknetApiError;
+use katana_rpc_types::subscription::{
+    SubscriptionItem, SubscriptionKind, SubscriptionParams, TransactionStatusUpdate,
+};
+use katana_rpc_types_builder::BlockBuilder;
+use starknet::core::types::TransactionStatus;
+use tracing::warn;
+
+use super::StarknetApi;
+use crate::utils;
+
+const LOG_TARGET: &str = "rpc";
+
+impl<EF: ExecutorFactory> StarknetApi<EF> {
+    /// Returns the header of a new block, or `None` if it can't be read.
+    async fn new_head(self, number: BlockNumber) -> Option<SubscriptionItem> {
+        let result = self
+            .on_io_blocking_task(move |this| {
+                let provider = this.inner.backend.blockchain.provider();
+                BlockBuilder::new(BlockHashOrNumber::Num(number), provider)
+                    .build_with_tx_hash()?
+                    .ok_or(StarknetApiError::BlockNotFound)
+            })
+            .await;
+
+        match result {
+            Ok(block) => Some(SubscriptionItem::NewHead(block)),
+            Err(error) => {
+                warn!(target: LOG_TARGET, %error, %number, "Failed to read new block header.");
+                None
+            }
+        }
+    }
+
+    /// Returns the events of a new block matching the filter.
+    async fn block_events(
+        self,
+        number: BlockNumber,
+        filter: utils::events::Filter,
+    ) -> Vec<SubscriptionItem> {
+        let result = self
+            .on_io_blocking_task(move |this| {
+                let provider = this.inner.backend.blockchain.provider();
+                let mut events = Vec::new();
+                let range = number..=number;
+                utils::events::fetch_events_at_blocks(
+                    provider,
+                    range,
+                    &filter,
+                    u64::MAX,
+                    None,
+                    &mut events,
+                )?;
+                Ok::<_, StarknetApiError>(events)
+            })
+            .await;
+
+        match result {
+            Ok(events) => events.into_iter().map(SubscriptionItem::Event).collect(),
+            Err(error) => {
+                warn!(target: LOG_TARGET, %error, %number, "Failed to read new block events.");
+                Vec::new()
+            }
+        }
+    }
+
+    /// Streams the status of the transaction every time it changes, starting from its current
+    /// status. The stream ends once the transaction reaches a final status.
+    fn transaction_status_updates(
+        &self,
+        hash: TxHash,
+    ) -> impl stream::Stream<Item = SubscriptionItem> + Send + 'static {
+        let this = self.clone();
+        let blocks = self.inner.backend.add_block_listener();
+
+        stream::unfold((blocks, None, false), move |(mut blocks, last, done)| {
+            let this = this.clone();
+            async move {
+                if done {
+                    return None;
+                }
+
+                // the status is checked right away, then after every new block. the transaction
+                // may not have been submitted yet, in which case its status is not found.
+                loop {
+                    if let Ok(status) = this.transaction_status(hash).await {
+                        if last.as_ref() != Some(&status) {
+                            // a transaction is final once it's included in a block or rejected
+                            let done = !matches!(status, TransactionStatus::Received);
+                            let update = TransactionStatusUpdate {
+                                transaction_hash: hash,
+                                status: status.clone(),
+                            };
+                            let item = SubscriptionItem::TransactionStatus(update);
+                            return Some((item, (blocks, Some(status), done)));
+                        }
+                    }
+
+                    blocks.next().await?;
+                }
+            }
+        })
+    }
+}
+
+impl<EF: ExecutorFactory> StarknetSubscriptionApiServer for StarknetApi<EF> {
+    fn subscribe(
+        &self,
+        mut sink: SubscriptionSink,
+        kind: SubscriptionKind,
+        params: Option<SubscriptionParams>,
+    ) -> SubscriptionResult {
+        let params = params.unwrap_or_default();
+        let this = self.clone();
+
+        let items = match kind {
+            SubscriptionKind::NewHeads => {
+                sink.accept()?;
+
+                self.inner
+                    .backend
+                    .add_block_listener()
+                    .then(move |number| this.clone().new_head(number))
+                    .filter_map(future::ready)
+                    .boxed()
+            }
+
+            SubscriptionKind::Events => {
+                sink.accept()?;
+
+                let filter =
+                    utils::events::Filter { address: params.from_address, keys: params.keys };
+                self.inner
+                    .backend
+                    .add_block_listener()
+                    .then(move |number| this.clone().block_events(number, filter.clone()))
+                    .flat_map(stream::iter)
+                    .boxed()
+            }
+
+            SubscriptionKind::TransactionStatus => {
+                let Some(hash) = params.transaction_hash else {
+                    let message = "`transaction_hash` is required for `transactionStatus`";
+                    let code = ErrorCode::InvalidParams.code();
+                    sink.reject(ErrorObject::owned(code, message, None::<()>))?;
+                    return Ok(());
+                };
+
+                sink.accept()?;
+                self.transaction_status_updates(hash).boxed()
+            }
+        };
+
+        tokio::spawn(async move {
+            let _ = sink.pipe_from_stream(items).await;
+        });
+
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use jsonrpsee::core::{async_trait, RpcResult};
 use katana_executor::{ExecutionResult, ExecutorFactory, ResultAndStates};
+use katana_pool::TransactionPool;
 use katana_primitives::block::{BlockHashOrNumber, BlockIdOrTag};
 use katana_primitives::fee::TxFeeInfo;
 use katana_primitives::trace::{BuiltinCounters, TxExecInfo};
@@ -8,6 +9,7 @@ use katana_provider::traits::block::{BlockNumberProvider, BlockProvider};
 use katana_provider::traits::transaction::{TransactionTraceProvider, TransactionsProviderExt};
 use katana_rpc_api::starknet::StarknetTraceApiServer;
 use katana_rpc_types::error::starknet::StarknetApiError;
+use katana_rpc_types::state_override::StateOverride;
 use katana_rpc_types::trace::FunctionInvocation;
 use katana_rpc_types::transaction::BroadcastedTx;
 use katana_rpc_types::{FeeEstimate, SimulationFlag};
@@ -15,7 +17,7 @@ use starknet::core::types::{
     BlockTag, ComputationResources, DataAvailabilityResources, DataResources,
     DeclareTransactionTrace, DeployAccountTransactionTrace, ExecuteInvocation, ExecutionResources,
     InvokeTransactionTrace, L1HandlerTransactionTrace, PriceUnit, RevertedInvocation,
-    SimulatedTransaction, TransactionTrace, TransactionTraceWithHash,
+    SequencerTransactionStatus, SimulatedTransaction, TransactionTrace, TransactionTraceWithHash,
 };
 
 use super::StarknetApi;
@@ -26,6 +28,7 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
         block_id: BlockIdOrTag,
         transactions: Vec<BroadcastedTx>,
         simulation_flags: Vec<SimulationFlag>,
+        state_override: Option<StateOverride>,
     ) -> Result<Vec<SimulatedTransaction>, StarknetApiError> {
         let chain_id = self.inner.backend.chain_spec.id;
 
@@ -43,10 +46,7 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
                     BroadcastedTx::Declare(tx) => {
                         let is_query = tx.is_query();
                         ExecutableTxWithHash::new_query(
-                            ExecutableTx::Declare(
-                                tx.try_into_tx_with_chain_id(chain_id)
-                                    .map_err(|_| StarknetApiError::InvalidContractClass)?,
-                            ),
+                            ExecutableTx::Declare(self.declare_tx_with_class(tx)?),
                             is_query,
                         )
                     }
@@ -72,12 +72,16 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
         let should_skip_fee = !simulation_flags.contains(&SimulationFlag::SkipFeeCharge)
             && self.inner.backend.executor_factory.execution_flags().fee();
 
+        let node_flags = self.inner.backend.executor_factory.execution_flags();
         let flags = katana_executor::ExecutionFlags::new()
             .with_account_validation(should_validate)
-            .with_fee(!should_skip_fee);
+            .with_fee(!should_skip_fee)
+            .with_gas_accounting(node_flags.gas_accounting())
+            .with_impersonated_accounts(node_flags.impersonated_accounts().clone())
+            .with_sessions(node_flags.sessions().clone());
 
         // get the state and block env at the specified block for execution
-        let state = self.state(&block_id)?;
+        let state = self.state_with_override(&block_id, state_override)?;
         let env = self.block_env_at(&block_id)?;
 
         // create the executor
@@ -159,23 +163,32 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
     }
 
     fn trace(&self, tx_hash: TxHash) -> Result<TransactionTrace, StarknetApiError> {
-        use StarknetApiError::TxnHashNotFound;
+        use StarknetApiError::{NoTraceAvailable, TxnHashNotFound};
 
         // Check in the pending block first
         if let Some(state) = self.pending_executor() {
             let pending_block = state.read();
             let tx = pending_block.transactions().iter().find(|(t, _)| t.hash == tx_hash);
 
-            if let Some(trace) = tx.and_then(|(_, res)| res.trace()) {
-                return Ok(to_rpc_trace(trace.clone()));
+            if let Some((_, res)) = tx {
+                return match res.trace() {
+                    Some(trace) => Ok(to_rpc_trace(trace.clone())),
+                    // the transaction failed and won't be included in the block
+                    None => Err(NoTraceAvailable { status: SequencerTransactionStatus::Rejected }),
+                };
             }
         }
 
         // If not found in pending block, fallback to the provider
         let provider = self.inner.backend.blockchain.provider();
-        let trace = provider.transaction_execution(tx_hash)?.ok_or(TxnHashNotFound)?;
-
-        Ok(to_rpc_trace(trace))
+        match provider.transaction_execution(tx_hash)? {
+            Some(trace) => Ok(to_rpc_trace(trace)),
+            // the transaction is still waiting in the pool to be executed
+            None if self.inner.pool.get(tx_hash).is_some() => {
+                Err(NoTraceAvailable { status: SequencerTransactionStatus::Received })
+            }
+            None => Err(TxnHashNotFound),
+        }
     }
 }
 
@@ -190,9 +203,10 @@ impl<EF: ExecutorFactory> StarknetTraceApiServer for StarknetApi<EF> {
         block_id: BlockIdOrTag,
         transactions: Vec<BroadcastedTx>,
         simulation_flags: Vec<SimulationFlag>,
+        state_override: Option<StateOverride>,
     ) -> RpcResult<Vec<SimulatedTransaction>> {
         self.on_cpu_blocking_task(move |this| {
-            Ok(this.simulate_txs(block_id, transactions, simulation_flags)?)
+            Ok(this.simulate_txs(block_id, transactions, simulation_flags, state_override)?)
         })
         .await
     }
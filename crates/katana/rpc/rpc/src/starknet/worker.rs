@@ -0,0 +1,128 @@
+//! The thread pool running the execution-heavy work of the requests, ie. calls, fee estimations
+//! and simulations.
+//!
+//! The pool has a fixed number of threads, so that a burst of such requests can't take all the
+//! CPUs away from the block production. The tasks waiting for a thread are counted, and new ones
+//! are rejected once there are too many of them, instead of piling up until their requests time
+//! out.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use dojo_metrics::Metrics;
+use katana_tasks::{BlockingTaskHandle, BlockingTaskPool};
+use metrics::{Counter, Gauge};
+
+/// Error returned when a task is spawned while too many tasks are waiting for a thread.
+#[derive(Debug, thiserror::Error)]
+#[error("Too many tasks are waiting for a worker thread")]
+pub struct WorkerPoolBusy;
+
+#[allow(missing_debug_implementations)]
+#[derive(Clone)]
+pub struct WorkerPool {
+    pool: BlockingTaskPool,
+    /// The number of spawned tasks that haven't started yet.
+    queued: Arc<AtomicUsize>,
+    max_queued: usize,
+    metrics: WorkerPoolMetrics,
+}
+
+impl WorkerPool {
+    /// Creates a pool of `threads` threads, or of as many threads as there are CPUs if `None`,
+    /// rejecting the tasks spawned while `max_queued` tasks are waiting for a thread.
+    pub fn new(threads: Option<usize>, max_queued: usize) -> Self {
+        let pool = BlockingTaskPool::build()
+            .thread_name(|i| format!("rpc-worker-{i}"))
+            // rayon uses as many threads as there are CPUs for 0
+            .num_threads(threads.unwrap_or_default())
+            .build()
+            .map(BlockingTaskPool::new_with_pool)
+            .expect("failed to create rpc worker pool");
+
+        Self {
+            pool,
+            queued: Arc::new(AtomicUsize::new(0)),
+            max_queued,
+            metrics: WorkerPoolMetrics::default(),
+        }
+    }
+
+    /// Returns the number of tasks waiting for a thread.
+    pub fn queued(&self) -> usize {
+        self.queued.load(Ordering::Acquire)
+    }
+
+    /// Spawns a task on the pool, unless too many tasks are already waiting for a thread.
+    pub fn spawn<F, R>(&self, func: F) -> Result<BlockingTaskHandle<R>, WorkerPoolBusy>
+    where
+        F: FnOnce() -> R + Send + 'static,
+        R: Send + 'static,
+    {
+        let reserved = self.queued.fetch_update(Ordering::AcqRel, Ordering::Acquire, |queued| {
+            (queued < self.max_queued).then_some(queued + 1)
+        });
+
+        if reserved.is_err() {
+            self.metrics.rejected_tasks.increment(1);
+            return Err(WorkerPoolBusy);
+        }
+
+        self.metrics.queued_tasks.increment(1.0);
+
+        let queued = Arc::clone(&self.queued);
+        let metrics = self.metrics.clone();
+
+        Ok(self.pool.spawn(move || {
+            queued.fetch_sub(1, Ordering::AcqRel);
+            metrics.queued_tasks.decrement(1.0);
+            func()
+        }))
+    }
+}
+
+/// Metrics for the RPC worker pool
+#[derive(Metrics, Clone)]
+#[metrics(scope = "rpc_server.workers")]
+struct WorkerPoolMetrics {
+    /// The number of tasks waiting for a worker thread
+    queued_tasks: Gauge,
+    /// The number of tasks rejected because too many tasks were waiting
+    rejected_tasks: Counter,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::WorkerPool;
+
+    #[tokio::test]
+    async fn rejects_tasks_when_queue_is_full() {
+        let pool = WorkerPool::new(Some(1), 1);
+
+        let (started_tx, started_rx) = mpsc::channel();
+        let (release_tx, release_rx) = mpsc::channel::<()>();
+
+        let running = pool
+            .spawn(move || {
+                started_tx.send(()).unwrap();
+                release_rx.recv().unwrap();
+            })
+            .unwrap();
+        started_rx.recv().unwrap();
+
+        // the only thread is busy, so the next task waits for it
+        let queued = pool.spawn(|| 1).unwrap();
+        assert_eq!(pool.queued(), 1);
+        assert!(pool.spawn(|| 2).is_err());
+
+        release_tx.send(()).unwrap();
+        running.await.unwrap();
+        assert_eq!(queued.await.unwrap(), 1);
+        assert_eq!(pool.queued(), 0);
+
+        // the queue has room again
+        assert_eq!(pool.spawn(|| 3).unwrap().await.unwrap(), 3);
+    }
+}
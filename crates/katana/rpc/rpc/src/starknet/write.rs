@@ -34,13 +34,25 @@ impl<EF: ExecutorFactory> StarknetApi<EF> {
         &self,
         tx: BroadcastedDeclareTx,
     ) -> Result<DeclareTxResult, StarknetApiError> {
-        self.on_io_blocking_task(move |this| {
-            if tx.is_query() {
-                return Err(StarknetApiError::UnsupportedTransactionVersion);
-            }
+        if tx.is_query() {
+            return Err(StarknetApiError::UnsupportedTransactionVersion);
+        }
+
+        // the class is compiled on the compiler pool, without holding a request handling thread
+        let compiled = match tx.sierra_class() {
+            Some(class) => Some(
+                self.inner
+                    .compiler
+                    .compile(class.clone())
+                    .await
+                    .map_err(|_| StarknetApiError::InvalidContractClass)?,
+            ),
+            None => None,
+        };
 
+        self.on_io_blocking_task(move |this| {
             let tx = tx
-                .try_into_tx_with_chain_id(this.inner.backend.chain_spec.id)
+                .try_into_tx_with_compiled_class(this.inner.backend.chain_spec.id, compiled)
                 .map_err(|_| StarknetApiError::InvalidContractClass)?;
 
             let class_hash = tx.class_hash();
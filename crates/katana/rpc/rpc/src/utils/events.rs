@@ -3,17 +3,18 @@ use std::ops::RangeInclusive;
 
 use anyhow::Context;
 use katana_core::service::block_producer::PendingExecutor;
-use katana_primitives::block::{BlockHash, BlockNumber};
+use katana_primitives::block::{BlockHash, BlockIdOrTag, BlockNumber, BlockTag};
 use katana_primitives::contract::ContractAddress;
 use katana_primitives::event::ContinuationToken;
-use katana_primitives::receipt::Event;
+use katana_primitives::receipt::{Event, EventsBloom};
 use katana_primitives::transaction::TxHash;
 use katana_primitives::Felt;
 use katana_provider::error::ProviderError;
 use katana_provider::traits::block::BlockProvider;
-use katana_provider::traits::transaction::ReceiptProvider;
+use katana_provider::traits::transaction::{EventsBloomProvider, ReceiptProvider};
 use katana_rpc_types::error::starknet::StarknetApiError;
-use starknet::core::types::EmittedEvent;
+use katana_rpc_types::event::EmittedEvent;
+use starknet_types_core::hash::{self, StarkHash};
 
 pub type EventQueryResult<T> = Result<T, Error>;
 
@@ -45,6 +46,90 @@ pub struct Filter {
     pub keys: Option<Vec<Vec<Felt>>>,
 }
 
+impl Filter {
+    /// Computes the hash of the filter over the given block range.
+    ///
+    /// The continuation tokens commit to the hash of the filter they're issued for, so that a
+    /// token can't be used to continue a different query.
+    pub fn hash(&self, from: &BlockIdOrTag, to: &BlockIdOrTag) -> Felt {
+        fn block_id(id: &BlockIdOrTag) -> [Felt; 2] {
+            match id {
+                BlockIdOrTag::Hash(hash) => [Felt::ZERO, *hash],
+                BlockIdOrTag::Number(num) => [Felt::ONE, (*num).into()],
+                BlockIdOrTag::Tag(BlockTag::Latest) => [Felt::TWO, Felt::ZERO],
+                BlockIdOrTag::Tag(BlockTag::Pending) => [Felt::TWO, Felt::ONE],
+            }
+        }
+
+        let mut values = Vec::new();
+        values.extend(block_id(from));
+        values.extend(block_id(to));
+
+        match self.address {
+            Some(address) => values.extend([Felt::ONE, address.into()]),
+            None => values.push(Felt::ZERO),
+        }
+
+        match &self.keys {
+            Some(keys) => {
+                values.extend([Felt::ONE, keys.len().into()]);
+                for keys in keys {
+                    values.push(keys.len().into());
+                    values.extend(keys);
+                }
+            }
+            None => values.push(Felt::ZERO),
+        }
+
+        hash::Poseidon::hash_array(&values)
+    }
+
+    /// Returns `false` if none of the events of a block with the given bloom matches the filter.
+    fn may_match(&self, bloom: &EventsBloom) -> bool {
+        if let Some(address) = self.address {
+            if !bloom.contains_address(address) {
+                return false;
+            }
+        }
+
+        // an empty set of keys at a position matches any key
+        self.keys.iter().flatten().enumerate().all(|(position, keys)| {
+            keys.is_empty() || keys.iter().any(|key| bloom.contains_key(position, *key))
+        })
+    }
+
+    /// Returns `true` if the event matches the filter.
+    fn matches(&self, event: &Event) -> bool {
+        // Check if the event matches the address filter
+        if !self.address.map_or(true, |addr| addr == event.from_address) {
+            return false;
+        }
+
+        // Check if the event matches the keys filter
+        match &self.keys {
+            None => true,
+            // From starknet-api spec:
+            // Per key (by position), designate the possible values to be matched for events to be
+            // returned. Empty array designates 'any' value"
+            Some(filters) => filters.iter().enumerate().all(|(i, keys)| {
+                // Lets say we want to filter events which are either named `Event1` or `Event2`
+                // and custom key `0x1` or `0x2` Filter:
+                // [[sn_keccak("Event1"), sn_keccak("Event2")], ["0x1", "0x2"]]
+
+                // This checks: number of keys in event >= number of keys in filter (we check > i
+                // and not >= i because i is zero indexed) because otherwise this event doesn't
+                // contain all the keys we requested
+                event.keys.len() > i &&
+                     // This checks: Empty array desginates 'any' value
+                     (keys.is_empty()
+                     ||
+                     // This checks: If this events i'th value is one of the requested value in filter_keys[i]
+                     keys.contains(&event.keys[i]))
+            }),
+        }
+    }
+}
+
 /// Internal cursor
 #[derive(Debug, Clone, PartialEq)]
 pub struct Cursor {
@@ -61,11 +146,14 @@ impl Cursor {
         Self { block, txn: PartialCursor::default() }
     }
 
-    pub fn into_rpc_cursor(self) -> ContinuationToken {
+    /// Converts the cursor into a continuation token of a query whose filter hash is
+    /// `filter_hash`.
+    pub fn into_rpc_cursor(self, filter_hash: Felt) -> ContinuationToken {
         ContinuationToken {
             block_n: self.block,
             txn_n: self.txn.idx as u64,
             event_n: self.txn.event as u64,
+            filter_hash,
         }
     }
 }
@@ -96,21 +184,39 @@ pub fn fetch_pending_events(
 
     let block_env = pending_block.block_env();
     let txs = pending_block.transactions();
-    let cursor = cursor.unwrap_or(Cursor::new_block(block_env.number));
 
-    // process individual transactions in the block.
-    // the iterator will start with txn index == cursor.txn.idx
+    // a cursor pointing to a previous block means the events of those blocks have all been
+    // fetched, so we start from the beginning of the pending block.
+    let cursor = match cursor {
+        Some(cursor) if cursor.block > block_env.number => return Err(Error::InvalidCursor),
+        Some(cursor) if cursor.block == block_env.number => cursor,
+        _ => Cursor::new_block(block_env.number),
+    };
+
+    // the pending block only grows, so the cursor can't point past its transactions
+    let tx_count = txs.iter().filter(|(_, res)| res.receipt().is_some()).count();
+    if cursor.txn.idx > tx_count {
+        return Err(Error::InvalidCursor);
+    }
+
+    // the index in the block of the first event of the next transaction
+    let mut first_event_idx = 0;
+
+    // process individual transactions in the block, starting from txn index == cursor.txn.idx
     for (tx_idx, (tx_hash, events)) in txs
         .iter()
         .filter_map(|(tx, res)| res.receipt().map(|receipt| (tx.hash, receipt.events())))
         .enumerate()
-        .skip(cursor.txn.idx)
     {
-        if tx_idx == cursor.txn.idx {
-            match events.len().cmp(&cursor.txn.event) {
-                Ordering::Equal | Ordering::Greater => {}
-                Ordering::Less => continue,
-            }
+        let tx_first_event_idx = first_event_idx;
+        first_event_idx += events.len() as u64;
+
+        if tx_idx < cursor.txn.idx {
+            continue;
+        }
+
+        if tx_idx == cursor.txn.idx && cursor.txn.event > events.len() {
+            return Err(Error::InvalidCursor);
         }
 
         // we should only skip for the last txn pointed by the cursor.
@@ -120,6 +226,7 @@ pub fn fetch_pending_events(
             None,
             None,
             tx_idx,
+            tx_first_event_idx,
             tx_hash,
             events,
             filter,
@@ -140,43 +247,71 @@ pub fn fetch_pending_events(
 
 /// Returns `true` if reach the end of the block range.
 pub fn fetch_events_at_blocks(
-    provider: impl BlockProvider + ReceiptProvider,
+    provider: impl BlockProvider + ReceiptProvider + EventsBloomProvider,
     block_range: RangeInclusive<BlockNumber>,
     filter: &Filter,
     chunk_size: u64,
     cursor: Option<Cursor>,
     buffer: &mut Vec<EmittedEvent>,
 ) -> EventQueryResult<Option<Cursor>> {
-    let cursor = cursor.unwrap_or(Cursor::new_block(*block_range.start()));
+    // the receipts of the pruned blocks are no longer available, so their events can't be
+    // returned and the range is clamped to the blocks whose history is kept.
+    let start = (*block_range.start()).max(provider.receipts_pruned_below()?);
+    if start > *block_range.end() {
+        return Ok(None);
+    }
+
+    // the range of a filter using the `latest` tag moves up as blocks are mined, so the cursor of a
+    // token issued for an earlier range starts from the beginning of the current one.
+    let cursor = match cursor {
+        Some(cursor) if cursor.block >= start => cursor,
+        _ => Cursor::new_block(start),
+    };
 
     // update the block range to start from the block pointed by the cursor.
     let block_range = cursor.block..=*block_range.end();
 
     for block_num in block_range {
+        // skip the blocks whose bloom tells they have no matching events, except the one pointed by
+        // the cursor which must be validated.
+        if block_num != cursor.block {
+            if let Some(bloom) = provider.events_bloom(block_num.into())? {
+                if !filter.may_match(&bloom) {
+                    continue;
+                }
+            }
+        }
+
         // collect all receipts at `block_num` block.
         let block_hash = provider.block_hash_by_num(block_num)?.context("Missing block hash")?;
         let receipts = provider.receipts_by_block(block_num.into())?.context("Missing receipts")?;
-        let body_index =
-            provider.block_body_indices(block_num.into())?.context("Missing block body index")?;
-        let tx_hashes = provider.transaction_hashes_in_range(body_index.into())?;
-
-        if block_num == cursor.block {
-            match receipts.len().cmp(&cursor.txn.idx) {
-                Ordering::Equal | Ordering::Greater => {}
-                Ordering::Less => continue,
-            }
+        let tx_hashes = provider
+            .transaction_hashes_by_block(block_num.into())?
+            .context("Missing block transaction hashes")?;
+
+        // the cursor pointing to transactions that don't exist in its block means it wasn't
+        // issued by this chain, skipping the block would silently drop its events.
+        if block_num == cursor.block && cursor.txn.idx > receipts.len() {
+            return Err(Error::InvalidCursor);
         }
 
         // we should only skip for the last block pointed by the cursor.
         let total_tx_to_skip = if block_num == cursor.block { cursor.txn.idx } else { 0 };
 
-        // skip number of transactions as specified in the continuation token
-        for (tx_idx, (tx_hash, events)) in tx_hashes
-            .into_iter()
-            .zip(receipts.iter().map(|r| r.events()))
-            .enumerate()
-            .skip(total_tx_to_skip)
+        // the index in the block of the first event of the next transaction
+        let mut first_event_idx = 0;
+
+        for (tx_idx, (tx_hash, events)) in
+            tx_hashes.into_iter().zip(receipts.iter().map(|r| r.events())).enumerate()
         {
+            let tx_first_event_idx = first_event_idx;
+            first_event_idx += events.len() as u64;
+
+            // skip number of transactions as specified in the continuation token
+            if tx_idx < total_tx_to_skip {
+                continue;
+            }
+
             // Determine the next event index to start processing.
             let next_event =
             // Check if the block AND tx we're currently processing is exactly the one pointed by the cursor.
@@ -185,11 +320,13 @@ pub fn fetch_events_at_blocks(
             // OR (2) exceed the total number of events in the current transaction.
             if block_num == cursor.block && tx_idx == cursor.txn.idx {
                 // If its (1), then that means there are still some events left to process in
-                // the current transaction. Else if its (2), meaning the cursor is pointing to either the
-                // last event or out of bound, which we can just skip to the next transaction.
+                // the current transaction. Else if its (2), meaning the cursor is pointing to the
+                // end of the transaction, which we can just skip to the next transaction. Pointing
+                // out of bound means the cursor wasn't issued by this chain.
                 match cursor.txn.event.cmp(&events.len()) {
                     Ordering::Less => cursor.txn.event,
-                    Ordering::Greater | Ordering::Equal => continue,
+                    Ordering::Equal => continue,
+                    Ordering::Greater => return Err(Error::InvalidCursor),
                 }
             }
             // If we're not processing the block and tx pointed by the cursor, then we start from 0
@@ -202,6 +339,7 @@ pub fn fetch_events_at_blocks(
                 Some(block_num),
                 Some(block_hash),
                 tx_idx,
+                tx_first_event_idx,
                 tx_hash,
                 events,
                 filter,
@@ -220,62 +358,6 @@ pub fn fetch_events_at_blocks(
     Ok(None)
 }
 
-/// An iterator that yields events that match the given filters.
-#[derive(Debug)]
-struct FilteredEvents<'a, I: Iterator<Item = &'a Event>> {
-    iter: I,
-    filter: &'a Filter,
-}
-
-impl<'a, I: Iterator<Item = &'a Event>> FilteredEvents<'a, I> {
-    fn new(iter: I, filter: &'a Filter) -> Self {
-        Self { iter, filter }
-    }
-}
-
-impl<'a, I: Iterator<Item = &'a Event>> Iterator for FilteredEvents<'a, I> {
-    type Item = &'a Event;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        for event in self.iter.by_ref() {
-            // Check if the event matches the address filter
-            if !self.filter.address.map_or(true, |addr| addr == event.from_address) {
-                continue;
-            }
-
-            // Check if the event matches the keys filter
-            let is_matched = match &self.filter.keys {
-                None => true,
-                // From starknet-api spec:
-                // Per key (by position), designate the possible values to be matched for events to
-                // be returned. Empty array designates 'any' value"
-                Some(filters) => filters.iter().enumerate().all(|(i, keys)| {
-                    // Lets say we want to filter events which are either named `Event1` or `Event2`
-                    // and custom key `0x1` or `0x2` Filter:
-                    // [[sn_keccak("Event1"), sn_keccak("Event2")], ["0x1", "0x2"]]
-
-                    // This checks: number of keys in event >= number of keys in filter (we check >
-                    // i and not >= i because i is zero indexed) because
-                    // otherwise this event doesn't contain all the keys we
-                    // requested
-                    event.keys.len() > i &&
-                         // This checks: Empty array desginates 'any' value
-                         (keys.is_empty()
-                         ||
-                         // This checks: If this events i'th value is one of the requested value in filter_keys[i]
-                         keys.contains(&event.keys[i]))
-                }),
-            };
-
-            if is_matched {
-                return Some(event);
-            }
-        }
-
-        None
-    }
-}
-
 /// Fetches events from a transaction, applying filters and respecting chunk size limits.
 ///
 /// Returns a cursor if it couldn't include all the events of the current transaction because
@@ -288,6 +370,7 @@ impl<'a, I: Iterator<Item = &'a Event>> Iterator for FilteredEvents<'a, I> {
 /// * `block_number` - Block number of the current transaction
 /// * `block_hash` - Block hash of the current transaction
 /// * `tx_idx` - Index of the current transaction in the block
+/// * `first_event_idx` - Index in the block of the first event of the current transaction
 /// * `tx_hash` - Hash of the current transaction
 /// * `events` - All events in the current transaction
 /// * `filter` - The filter to apply on the events
@@ -299,6 +382,7 @@ fn fetch_tx_events(
     block_number: Option<BlockNumber>,
     block_hash: Option<BlockHash>,
     tx_idx: usize,
+    first_event_idx: u64,
     tx_hash: TxHash,
     events: &[Event],
     filter: &Filter,
@@ -310,14 +394,20 @@ fn fetch_tx_events(
     let total_can_take = chunk_size.saturating_sub(buffer.len());
 
     // skip events according to the continuation token.
-    let filtered = FilteredEvents::new(events.iter(), filter)
-        .map(|e| EmittedEvent {
-            block_hash,
-            block_number,
-            keys: e.keys.clone(),
-            data: e.data.clone(),
-            transaction_hash: tx_hash,
-            from_address: e.from_address.into(),
+    let filtered = events
+        .iter()
+        .enumerate()
+        .filter(|(_, e)| filter.matches(e))
+        .map(|(position, e)| EmittedEvent {
+            inner: starknet::core::types::EmittedEvent {
+                block_hash,
+                block_number,
+                keys: e.keys.clone(),
+                data: e.data.clone(),
+                transaction_hash: tx_hash,
+                from_address: e.from_address.into(),
+            },
+            event_index: Some(first_event_idx + position as u64),
         })
         // enumerate so that we can keep track of the event's index in the transaction
         .enumerate()
@@ -369,3 +459,100 @@ impl From<ContinuationToken> for Cursor {
         Cursor::new(token.block_n, token.txn_n as usize, token.event_n as usize)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::block::{Block, FinalityStatus, Header, SealedBlockWithStatus};
+    use katana_primitives::contract::ContractAddress;
+    use katana_primitives::fee::{PriceUnit, TxFeeInfo};
+    use katana_primitives::receipt::{Event, EventsBloom, InvokeTxReceipt, Receipt};
+    use katana_primitives::trace::TxExecInfo;
+    use katana_primitives::transaction::{InvokeTx, Tx, TxWithHash};
+    use katana_primitives::{address, felt};
+    use katana_provider::providers::db::DbProvider;
+    use katana_provider::traits::block::BlockWriter;
+
+    use super::{fetch_events_at_blocks, Filter};
+
+    #[test]
+    fn filter_may_match_bloom() {
+        let mut bloom = EventsBloom::default();
+        bloom.accrue_event(&Event {
+            from_address: address!("0x1"),
+            keys: vec![felt!("0xa"), felt!("0xb")],
+            data: vec![],
+        });
+
+        let filter = |address, keys| Filter { address, keys };
+
+        assert!(filter(None, None).may_match(&bloom));
+        assert!(filter(Some(address!("0x1")), None).may_match(&bloom));
+        assert!(!filter(Some(address!("0x2")), None).may_match(&bloom));
+
+        let keys = vec![vec![], vec![felt!("0xc"), felt!("0xb")]];
+        assert!(filter(Some(address!("0x1")), Some(keys)).may_match(&bloom));
+        let keys = vec![vec![felt!("0xb")]];
+        assert!(!filter(None, Some(keys)).may_match(&bloom));
+    }
+
+    #[test]
+    fn events_of_pruned_blocks_are_skipped() {
+        let provider = DbProvider::new_ephemeral();
+
+        // every block has a transaction emitting an event keyed with the block number
+        for number in 0..3u64 {
+            let header = Header { number, ..Default::default() };
+            let body = vec![TxWithHash {
+                hash: (number + 1).into(),
+                transaction: Tx::Invoke(InvokeTx::V1(Default::default())),
+            }];
+            let block = Block { header, body }.seal();
+            let block = SealedBlockWithStatus { block, status: FinalityStatus::AcceptedOnL2 };
+
+            let event =
+                Event { from_address: address!("0x1"), keys: vec![number.into()], data: vec![] };
+            let receipt = Receipt::Invoke(InvokeTxReceipt {
+                revert_error: None,
+                events: vec![event],
+                messages_sent: Vec::new(),
+                execution_resources: Default::default(),
+                fee: TxFeeInfo {
+                    gas_consumed: 0,
+                    gas_price: 0,
+                    overall_fee: 0,
+                    unit: PriceUnit::Wei,
+                },
+            });
+
+            provider
+                .insert_block_with_states_and_receipts(
+                    block,
+                    Default::default(),
+                    vec![receipt],
+                    vec![TxExecInfo::default()],
+                )
+                .unwrap();
+        }
+
+        // only block 0 is older than the last 2 blocks
+        assert_eq!(provider.prune_history(2).unwrap(), 1);
+
+        let mut events = Vec::new();
+        let cursor =
+            fetch_events_at_blocks(&provider, 0..=2, &Filter::default(), 10, None, &mut events)
+                .unwrap();
+
+        assert!(cursor.is_none());
+        let keys = events.iter().map(|e| e.inner.keys.clone()).collect::<Vec<_>>();
+        assert_eq!(keys, vec![vec![felt!("1")], vec![felt!("2")]]);
+
+        // a range entirely pruned has no events
+        let mut events = Vec::new();
+        let cursor =
+            fetch_events_at_blocks(&provider, 0..=0, &Filter::default(), 10, None, &mut events)
+                .unwrap();
+
+        assert!(cursor.is_none());
+        assert!(events.is_empty());
+    }
+}
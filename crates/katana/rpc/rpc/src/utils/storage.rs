@@ -0,0 +1,173 @@
+//! Storage addresses of the entities of a Dojo world.
+//!
+//! The world stores the values of an entity at addresses derived from the model selector, the
+//! entity id and the path of each value in the model, following `dojo::storage::layout`.
+
+use std::collections::HashSet;
+
+use anyhow::{bail, Context, Result};
+use dojo_world::storage::{base_address, combine_key, packed_size, slot_addresses};
+use katana_primitives::contract::{StorageKey, StorageValue};
+use katana_primitives::Felt;
+use katana_rpc_types::world::{EntityStorage, Layout};
+
+/// Number of slots of a byte array besides its data: its length, pending word and pending length.
+const MIN_BYTE_ARRAY_SIZE: u64 = 3;
+
+/// Returns the addresses of all the storage slots of an entity.
+///
+/// The slots of arrays and byte arrays depend on their length, which is read from the world
+/// storage with `storage`. An error is returned if the entity spans more than `max_slots` slots.
+pub fn entity_storage_addresses<F>(
+    entity: &EntityStorage,
+    storage: F,
+    max_slots: usize,
+) -> Result<HashSet<StorageKey>>
+where
+    F: Fn(StorageKey) -> Result<StorageValue>,
+{
+    let mut addresses =
+        EntityAddresses { model: entity.model, storage, max_slots, addresses: HashSet::new() };
+    addresses.layout(entity.entity_id, &entity.layout)?;
+    Ok(addresses.addresses)
+}
+
+struct EntityAddresses<F> {
+    model: Felt,
+    storage: F,
+    max_slots: usize,
+    addresses: HashSet<StorageKey>,
+}
+
+impl<F> EntityAddresses<F>
+where
+    F: Fn(StorageKey) -> Result<StorageValue>,
+{
+    /// Adds the addresses of the value stored at `key`.
+    fn layout(&mut self, key: Felt, layout: &Layout) -> Result<()> {
+        match layout {
+            Layout::Fixed(bits) => self.slots(key, packed_size(bits)),
+            Layout::Struct(fields) => fields
+                .iter()
+                .try_for_each(|field| self.layout(combine_key(key, field.selector), &field.layout)),
+            Layout::Tuple(items) => items
+                .iter()
+                .enumerate()
+                .try_for_each(|(i, item)| self.layout(combine_key(key, Felt::from(i)), item)),
+            Layout::Array(item) => {
+                self.slots(key, 1)?;
+                let len = self.length(key)?;
+                (0..len).try_for_each(|i| self.layout(combine_key(key, Felt::from(i)), item))
+            }
+            Layout::ByteArray => {
+                let len = self.length(key)?;
+                self.slots(key, len + MIN_BYTE_ARRAY_SIZE)
+            }
+            Layout::Enum(variants) => {
+                self.slots(key, 1)?;
+                // the data of every variant is watched, as the variant of the entity can change
+                variants.iter().try_for_each(|variant| {
+                    self.layout(combine_key(key, variant.selector), &variant.layout)
+                })
+            }
+        }
+    }
+
+    /// Reads the length of the array stored at `key`, which is stored in its first slot.
+    fn length(&self, key: Felt) -> Result<u64> {
+        let value = (self.storage)(base_address(self.model, key))?;
+        let len = u64::try_from(value).ok().context("Invalid array length.")?;
+        if len > self.max_slots as u64 {
+            bail!("Entity spans more than {} storage slots.", self.max_slots);
+        }
+        Ok(len)
+    }
+
+    /// Adds the addresses of the `count` slots of the value stored at `key`.
+    fn slots(&mut self, key: Felt, count: u64) -> Result<()> {
+        if self.addresses.len() as u64 + count > self.max_slots as u64 {
+            bail!("Entity spans more than {} storage slots.", self.max_slots);
+        }
+
+        self.addresses.extend(slot_addresses(self.model, key, count));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use dojo_world::storage::chunk_base_address;
+    use katana_primitives::felt;
+    use katana_rpc_types::world::FieldLayout;
+
+    use super::*;
+
+    fn entity(layout: Layout) -> EntityStorage {
+        EntityStorage { model: felt!("0x1234"), entity_id: felt!("0xe"), layout }
+    }
+
+    fn no_storage(_: StorageKey) -> Result<StorageValue> {
+        Ok(Felt::ZERO)
+    }
+
+    #[test]
+    fn struct_members_are_stored_at_their_own_base_address() {
+        let (model, id) = (felt!("0x1234"), felt!("0xe"));
+        let layout = Layout::Struct(vec![
+            FieldLayout { selector: felt!("0xa"), layout: Layout::Fixed(vec![32, 32]) },
+            FieldLayout { selector: felt!("0xb"), layout: Layout::Fixed(vec![251, 8]) },
+        ]);
+
+        let addresses = entity_storage_addresses(&entity(layout), no_storage, 100).unwrap();
+
+        let a = base_address(model, combine_key(id, felt!("0xa")));
+        let b = base_address(model, combine_key(id, felt!("0xb")));
+        let expected = HashSet::from([a, b, b + Felt::ONE]);
+        assert_eq!(addresses, expected);
+        assert!(!addresses.contains(&base_address(model, id)));
+    }
+
+    #[test]
+    fn values_past_the_first_chunk_are_stored_in_other_chunks() {
+        let (model, id) = (felt!("0x1234"), felt!("0xe"));
+        let layout = Layout::Fixed(vec![251; 300]);
+
+        let addresses = entity_storage_addresses(&entity(layout), no_storage, 1000).unwrap();
+
+        let base = base_address(model, id);
+        let second_chunk = chunk_base_address(base, 1);
+        assert_eq!(addresses.len(), 300);
+        assert!(addresses.contains(&(base + Felt::from(255))));
+        assert!(!addresses.contains(&(base + Felt::from(256))));
+        assert!(addresses.contains(&second_chunk));
+        assert!(addresses.contains(&(second_chunk + Felt::from(43))));
+    }
+
+    #[test]
+    fn array_items_are_read_from_the_storage() {
+        let (model, id) = (felt!("0x1234"), felt!("0xe"));
+        let layout = Layout::Array(Box::new(Layout::Fixed(vec![128])));
+        let storage = HashMap::from([(base_address(model, id), Felt::TWO)]);
+        let storage = |key| Ok(storage.get(&key).copied().unwrap_or_default());
+
+        let addresses = entity_storage_addresses(&entity(layout), storage, 100).unwrap();
+
+        let items = (0..2u64).map(|i| base_address(model, combine_key(id, Felt::from(i))));
+        let expected = HashSet::from_iter(items.chain([base_address(model, id)]));
+        assert_eq!(addresses, expected);
+    }
+
+    #[test]
+    fn entity_larger_than_max_slots() {
+        let storage = |_| Ok(Felt::from(u32::MAX));
+
+        let array = Layout::Array(Box::new(Layout::Struct(vec![])));
+        assert!(entity_storage_addresses(&entity(array), storage, 100).is_err());
+        assert!(entity_storage_addresses(&entity(Layout::ByteArray), storage, 100).is_err());
+
+        let fixed = Layout::Fixed(vec![251; 101]);
+        assert!(entity_storage_addresses(&entity(fixed), no_storage, 100).is_err());
+    }
+}
@@ -0,0 +1,118 @@
+//! Conformance of the responses of the Starknet JSON-RPC API to its OpenRPC specification.
+//!
+//! The specification is pinned to the version supported by katana, and vendored in
+//! `tests/test_data/starknet-specs` by `scripts/fetch_rpc_spec.sh`.
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use cainome::rs::abigen_legacy;
+use dojo_test_utils::sequencer::{get_default_test_config, TestSequencer};
+use dojo_utils::TransactionWaiter;
+use jsonrpsee::core::client::ClientT;
+use jsonrpsee::core::params::ArrayParams;
+use jsonrpsee::http_client::HttpClientBuilder;
+use jsonrpsee::rpc_params;
+use katana_node::config::SequencingConfig;
+use katana_primitives::genesis::constant::DEFAULT_ETH_FEE_TOKEN_ADDRESS;
+use katana_rpc_api::starknet::RPC_SPEC_VERSION;
+use serde_json::{json, Value};
+use starknet::accounts::Account;
+use starknet::core::types::Felt;
+use starknet::macros::{felt, selector};
+use starknet::providers::Provider;
+
+use crate::common::prepare_contract_declaration_params;
+
+mod common;
+#[path = "conformance/schema.rs"]
+mod schema;
+
+/// The directory of the vendored specification files, one directory per version.
+const SPEC_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/test_data/starknet-specs");
+
+abigen_legacy!(Erc20Contract, "crates/katana/rpc/rpc/tests/test_data/erc20.json", derives(Clone));
+
+#[tokio::test(flavor = "multi_thread")]
+async fn responses_conform_to_spec() -> Result<()> {
+    let spec = schema::Spec::load(&Path::new(SPEC_DIR).join(RPC_SPEC_VERSION))?;
+
+    let config = get_default_test_config(SequencingConfig::default());
+    let sequencer = TestSequencer::start(config).await;
+    let provider = sequencer.provider();
+    let account = sequencer.account();
+    let client = HttpClientBuilder::default().build(sequencer.url())?;
+
+    // generate a chain with a declared class, and a transaction emitting events
+
+    let path = PathBuf::from("tests/test_data/cairo1_contract.json");
+    let (contract, compiled_class_hash) = prepare_contract_declaration_params(&path)?;
+    let class_hash = contract.class_hash();
+    let res = account.declare_v2(Arc::new(contract), compiled_class_hash).send().await?;
+    TransactionWaiter::new(res.transaction_hash, &provider).await?;
+    let declare_tx = res.transaction_hash;
+
+    let token = Erc20Contract::new(DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(), &account);
+    let amount = Uint256 { low: felt!("0x1"), high: Felt::ZERO };
+    let res = token.transfer(&felt!("0x1"), &amount).send().await?;
+    TransactionWaiter::new(res.transaction_hash, &provider).await?;
+    let invoke_tx = res.transaction_hash;
+
+    let latest = provider.block_hash_and_number().await?;
+    let address = account.address();
+    let eth = Felt::from(DEFAULT_ETH_FEE_TOKEN_ADDRESS);
+    let call = json!({
+        "contract_address": eth,
+        "entry_point_selector": selector!("balanceOf"),
+        "calldata": [address],
+    });
+    let filter = json!({ "from_block": { "block_number": 0 }, "chunk_size": 100 });
+
+    let requests: Vec<(&str, ArrayParams)> = vec![
+        ("starknet_specVersion", rpc_params![]),
+        ("starknet_chainId", rpc_params![]),
+        ("starknet_syncing", rpc_params![]),
+        ("starknet_blockNumber", rpc_params![]),
+        ("starknet_blockHashAndNumber", rpc_params![]),
+        ("starknet_getBlockWithTxHashes", rpc_params!["latest"]),
+        ("starknet_getBlockWithTxHashes", rpc_params!["pending"]),
+        ("starknet_getBlockWithTxs", rpc_params![json!({ "block_hash": latest.block_hash })]),
+        ("starknet_getBlockWithTxs", rpc_params!["pending"]),
+        ("starknet_getBlockWithReceipts", rpc_params!["latest"]),
+        ("starknet_getBlockWithReceipts", rpc_params!["pending"]),
+        ("starknet_getBlockTransactionCount", rpc_params!["latest"]),
+        ("starknet_getStateUpdate", rpc_params!["latest"]),
+        ("starknet_getTransactionByHash", rpc_params![declare_tx]),
+        ("starknet_getTransactionByHash", rpc_params![invoke_tx]),
+        ("starknet_getTransactionByBlockIdAndIndex", rpc_params!["latest", 0]),
+        ("starknet_getTransactionReceipt", rpc_params![declare_tx]),
+        ("starknet_getTransactionReceipt", rpc_params![invoke_tx]),
+        ("starknet_getTransactionStatus", rpc_params![invoke_tx]),
+        ("starknet_getClass", rpc_params!["latest", class_hash]),
+        ("starknet_getClassAt", rpc_params!["latest", address]),
+        ("starknet_getClassAt", rpc_params!["latest", eth]),
+        ("starknet_getClassHashAt", rpc_params!["latest", address]),
+        ("starknet_getNonce", rpc_params!["latest", address]),
+        ("starknet_getStorageAt", rpc_params![eth, felt!("0x1"), "latest"]),
+        ("starknet_call", rpc_params![call, "latest"]),
+        ("starknet_getEvents", rpc_params![filter]),
+        ("starknet_traceTransaction", rpc_params![declare_tx]),
+        ("starknet_traceTransaction", rpc_params![invoke_tx]),
+        ("starknet_traceBlockTransactions", rpc_params!["latest"]),
+    ];
+
+    let mut failures = Vec::new();
+
+    for (method, params) in requests {
+        let result: Value =
+            client.request(method, params).await.with_context(|| format!("{method} failed"))?;
+
+        for error in spec.validate_result(method, &result) {
+            failures.push(format!("{method}: {error}"));
+        }
+    }
+
+    assert!(failures.is_empty(), "Responses not conforming to the spec:\n{}", failures.join("\n"));
+    Ok(())
+}
@@ -0,0 +1,200 @@
+//! A validator of JSON values against the schemas of the OpenRPC specification of Starknet.
+//!
+//! Only the subset of JSON Schema used by the specification is supported. Unknown fields are
+//! not reported, and `oneOf` is checked like `anyOf` as some of its variants overlap in the
+//! specification.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde_json::Value;
+
+/// The files of the specification, the first one defines the read API and the schemas referred
+/// to by the others.
+const SPEC_FILES: [&str; 3] =
+    ["starknet_api_openrpc.json", "starknet_write_api.json", "starknet_trace_api_openrpc.json"];
+
+#[derive(Debug)]
+pub struct Spec {
+    /// The documents of the specification, keyed by their file name.
+    documents: HashMap<String, Value>,
+}
+
+impl Spec {
+    pub fn load(dir: &Path) -> Result<Self> {
+        let mut documents = HashMap::new();
+
+        for file in SPEC_FILES {
+            let path = dir.join(file);
+            let content = fs::read_to_string(&path).with_context(|| {
+                format!(
+                    "Missing specification file {}, vendor it with `scripts/fetch_rpc_spec.sh`.",
+                    path.display()
+                )
+            })?;
+            documents.insert(file.to_string(), serde_json::from_str(&content)?);
+        }
+
+        Ok(Self { documents })
+    }
+
+    /// Validates the result of a method against the schema of its result in the specification,
+    /// returning the list of mismatches.
+    pub fn validate_result(&self, method: &str, result: &Value) -> Vec<String> {
+        for (file, document) in &self.documents {
+            let methods = document["methods"].as_array().map(Vec::as_slice).unwrap_or_default();
+
+            if let Some(spec) = methods.iter().find(|m| m["name"] == method) {
+                let mut errors = Vec::new();
+                self.validate(file, &spec["result"]["schema"], result, "result", &mut errors);
+                return errors;
+            }
+        }
+
+        vec![format!("method `{method}` is not in the specification")]
+    }
+
+    fn validate(
+        &self,
+        file: &str,
+        schema: &Value,
+        value: &Value,
+        path: &str,
+        errors: &mut Vec<String>,
+    ) {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            match self.resolve(file, reference) {
+                Some((file, schema)) => self.validate(file, schema, value, path, errors),
+                None => errors.push(format!("{path}: unresolved reference `{reference}`")),
+            }
+            return;
+        }
+
+        if let Some(schemas) = schema.get("allOf").and_then(Value::as_array) {
+            for schema in schemas {
+                self.validate(file, schema, value, path, errors);
+            }
+        }
+
+        for keyword in ["oneOf", "anyOf"] {
+            if let Some(schemas) = schema.get(keyword).and_then(Value::as_array) {
+                if !schemas.iter().any(|schema| self.matches(file, schema, value, path)) {
+                    errors.push(format!("{path}: {value} matches none of the `{keyword}` schemas"));
+                }
+            }
+        }
+
+        if let Some(schema) = schema.get("not") {
+            if self.matches(file, schema, value, path) {
+                errors.push(format!("{path}: {value} matches a `not` schema"));
+            }
+        }
+
+        if let Some(values) = schema.get("enum").and_then(Value::as_array) {
+            if !values.contains(value) {
+                errors.push(format!("{path}: {value} is not one of {values:?}"));
+            }
+        }
+
+        if let Some(ty) = schema.get("type").and_then(Value::as_str) {
+            let valid = match ty {
+                "object" => value.is_object(),
+                "array" => value.is_array(),
+                "string" => value.is_string(),
+                "integer" => value.is_i64() || value.is_u64(),
+                "number" => value.is_number(),
+                "boolean" => value.is_boolean(),
+                "null" => value.is_null(),
+                _ => true,
+            };
+
+            if !valid {
+                errors.push(format!("{path}: {value} is not of type `{ty}`"));
+                return;
+            }
+        }
+
+        if let (Some(pattern), Some(string)) =
+            (schema.get("pattern").and_then(Value::as_str), value.as_str())
+        {
+            let regex = Regex::new(pattern).expect("valid pattern");
+            if !regex.is_match(string) {
+                errors.push(format!("{path}: `{string}` doesn't match `{pattern}`"));
+            }
+        }
+
+        if let Some(object) = value.as_object() {
+            let required = schema.get("required").and_then(Value::as_array);
+            for field in required.into_iter().flatten().filter_map(Value::as_str) {
+                if !object.contains_key(field) {
+                    errors.push(format!("{path}: missing required field `{field}`"));
+                }
+            }
+
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for (field, schema) in properties.into_iter().flatten() {
+                if let Some(value) = object.get(field) {
+                    self.validate(file, schema, value, &format!("{path}.{field}"), errors);
+                }
+            }
+        }
+
+        if let (Some(schema), Some(items)) = (schema.get("items"), value.as_array()) {
+            for (i, item) in items.iter().enumerate() {
+                self.validate(file, schema, item, &format!("{path}[{i}]"), errors);
+            }
+        }
+    }
+
+    fn matches(&self, file: &str, schema: &Value, value: &Value, path: &str) -> bool {
+        let mut errors = Vec::new();
+        self.validate(file, schema, value, path, &mut errors);
+        errors.is_empty()
+    }
+
+    /// Resolves a reference made from `file`, returning the file the schema is defined in along
+    /// with the schema.
+    fn resolve(&self, file: &str, reference: &str) -> Option<(&str, &Value)> {
+        let (target, pointer) = reference.split_once('#').unwrap_or((reference, ""));
+        let target = if target.is_empty() { file } else { target.rsplit('/').next()? };
+
+        let (file, document) = self.documents.get_key_value(target)?;
+        Some((file, document.pointer(pointer)?))
+    }
+}
+
+#[test]
+fn validate_against_schema() {
+    let document = serde_json::json!({
+        "methods": [{
+            "name": "starknet_getBlock",
+            "result": { "schema": { "$ref": "#/components/schemas/BLOCK" } }
+        }],
+        "components": { "schemas": {
+            "FELT": { "type": "string", "pattern": "^0x(0|[a-fA-F1-9]{1}[a-fA-F0-9]{0,62})$" },
+            "BLOCK": {
+                "type": "object",
+                "properties": {
+                    "hash": { "$ref": "#/components/schemas/FELT" },
+                    "status": { "type": "string", "enum": ["ACCEPTED_ON_L2", "ACCEPTED_ON_L1"] },
+                    "txs": { "type": "array", "items": { "$ref": "#/components/schemas/FELT" } }
+                },
+                "required": ["hash", "status"]
+            }
+        } }
+    });
+
+    let mut documents = HashMap::new();
+    documents.insert(SPEC_FILES[0].to_string(), document);
+    let spec = Spec { documents };
+
+    let block = serde_json::json!({ "hash": "0x1", "status": "ACCEPTED_ON_L2", "txs": ["0x2"] });
+    assert!(spec.validate_result("starknet_getBlock", &block).is_empty());
+
+    let block = serde_json::json!({ "hash": "0x01", "txs": [2] });
+    let errors = spec.validate_result("starknet_getBlock", &block);
+    assert_eq!(errors.len(), 3, "{errors:?}");
+}
@@ -1,8 +1,27 @@
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
 use dojo_test_utils::sequencer::{get_default_test_config, TestSequencer};
+use katana_node::config::dev::FixedL1GasPriceConfig;
 use katana_node::config::SequencingConfig;
-use katana_provider::traits::block::{BlockNumberProvider, BlockProvider};
+use katana_primitives::block::GasPrices;
+use katana_primitives::fee::PriceUnit;
+use katana_primitives::genesis::constant::{
+    get_fee_token_balance_base_storage_address, DEFAULT_ETH_FEE_TOKEN_ADDRESS,
+    DEFAULT_STRK_FEE_TOKEN_ADDRESS,
+};
+use katana_primitives::Felt;
+use katana_provider::traits::block::{BlockHashProvider, BlockNumberProvider, BlockProvider};
 use katana_provider::traits::env::BlockEnvProvider;
+use katana_provider::traits::state::StateRootProvider;
+use katana_provider::traits::transaction::{TransactionProvider, TransactionsProviderExt};
 use katana_rpc_api::dev::DevApiClient;
+use katana_rpc_types::account::SessionPolicy;
+use starknet::accounts::{Account, ExecutionEncoding, SingleOwnerAccount};
+use starknet::core::types::{BlockId, BlockTag, Call};
+use starknet::macros::{felt, selector};
+use starknet::providers::Provider;
+use starknet::signers::{LocalWallet, SigningKey};
 
 async fn create_test_sequencer() -> TestSequencer {
     TestSequencer::start(get_default_test_config(SequencingConfig::default())).await
@@ -122,3 +141,287 @@ async fn test_increase_next_block_timestamp() {
 //         assert_eq!(val, read_val, "latest storage value incorrect after generate");
 //     }
 // }
+
+#[tokio::test]
+async fn dump_and_load_storage() {
+    let sequencer = create_test_sequencer().await;
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+
+    let (account, _) = sequencer.backend().chain_spec.genesis.accounts().next().unwrap();
+    let account: Felt = (*account).into();
+
+    let entries = BTreeMap::from([(felt!("0x20"), felt!("0xabc")), (felt!("0x21"), felt!("0x1"))]);
+    let block = sequencer.provider().block_number().await.unwrap();
+    client.load_storage(account, entries.clone()).await.unwrap();
+
+    // the storage is loaded in a new block, the previous state is left unchanged
+    assert_eq!(sequencer.provider().block_number().await.unwrap(), block + 1);
+    let value = sequencer
+        .provider()
+        .get_storage_at(account, felt!("0x20"), BlockId::Number(block))
+        .await
+        .unwrap();
+    assert_eq!(value, Felt::ZERO);
+
+    let dumped = client.dump_storage(account).await.unwrap();
+    for (key, value) in &entries {
+        assert_eq!(dumped.get(key), Some(value));
+    }
+
+    // the storage is visible to the starknet api too
+    let value = sequencer
+        .provider()
+        .get_storage_at(account, felt!("0x20"), BlockId::Tag(BlockTag::Latest))
+        .await
+        .unwrap();
+    assert_eq!(value, felt!("0xabc"));
+
+    // contracts must be deployed
+    let result = client.dump_storage(felt!("0x1337")).await;
+    assert!(result.is_err());
+    let result = client.load_storage(felt!("0x1337"), entries).await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn impersonate_account() {
+    let sequencer = create_test_sequencer().await;
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+
+    // an account signing with a key that isn't its own
+    let address = sequencer.raw_account().account_address;
+    let account = SingleOwnerAccount::new(
+        sequencer.provider(),
+        LocalWallet::from_signing_key(SigningKey::from_secret_scalar(felt!("0x1337"))),
+        address,
+        sequencer.provider().chain_id().await.unwrap(),
+        ExecutionEncoding::New,
+    );
+
+    let call = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![felt!("0x100"), felt!("0x1"), Felt::ZERO],
+    };
+
+    let result = account.execute_v1(vec![call.clone()]).send().await;
+    assert!(result.is_err());
+
+    client.impersonate_account(address).await.unwrap();
+    let res = account.execute_v1(vec![call.clone()]).send().await.unwrap();
+    dojo_utils::TransactionWaiter::new(res.transaction_hash, &sequencer.provider()).await.unwrap();
+
+    client.stop_impersonating(address).await.unwrap();
+    let result = account.execute_v1(vec![call]).send().await;
+    assert!(result.is_err());
+}
+
+#[tokio::test]
+async fn mint_session() {
+    let sequencer = create_test_sequencer().await;
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let provider = sequencer.provider();
+
+    let transfer = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![felt!("0x100"), felt!("0x1"), Felt::ZERO],
+    };
+    let approve = Call { selector: selector!("approve"), ..transfer.clone() };
+
+    let address = sequencer.raw_account().account_address;
+    let policies = vec![SessionPolicy {
+        contract_address: DEFAULT_ETH_FEE_TOKEN_ADDRESS,
+        selector: selector!("transfer"),
+    }];
+    let session = client.mint_session(address, policies, u64::MAX).await.unwrap();
+    assert_eq!(Felt::from(session.account_address), address);
+
+    // the account, signing with the session key
+    let account = SingleOwnerAccount::new(
+        provider.clone(),
+        LocalWallet::from_signing_key(SigningKey::from_secret_scalar(session.private_key)),
+        address,
+        provider.chain_id().await.unwrap(),
+        ExecutionEncoding::New,
+    );
+
+    let res = account.execute_v1(vec![transfer.clone()]).send().await.unwrap();
+    dojo_utils::TransactionWaiter::new(res.transaction_hash, &provider).await.unwrap();
+
+    // calls outside of the session policies must be signed by the account key
+    assert!(account.execute_v1(vec![approve]).send().await.is_err());
+
+    client.revoke_sessions(address).await.unwrap();
+    assert!(account.execute_v1(vec![transfer.clone()]).send().await.is_err());
+
+    // an expired session
+    let policies = vec![SessionPolicy {
+        contract_address: DEFAULT_ETH_FEE_TOKEN_ADDRESS,
+        selector: selector!("transfer"),
+    }];
+    let session = client.mint_session(address, policies.clone(), 0).await.unwrap();
+    let account = SingleOwnerAccount::new(
+        provider.clone(),
+        LocalWallet::from_signing_key(SigningKey::from_secret_scalar(session.private_key)),
+        address,
+        provider.chain_id().await.unwrap(),
+        ExecutionEncoding::New,
+    );
+    assert!(account.execute_v1(vec![transfer]).send().await.is_err());
+
+    // the node doesn't own the key of other accounts
+    assert!(client.mint_session(felt!("0x1337"), policies, u64::MAX).await.is_err());
+}
+
+#[tokio::test]
+async fn set_balance() {
+    let sequencer = create_test_sequencer().await;
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let provider = sequencer.provider();
+
+    let address = felt!("0x1337");
+    let amount = felt!("0x100000000000000000000000000000001");
+    let base = get_fee_token_balance_base_storage_address(address.into());
+    let block_id = BlockId::Tag(BlockTag::Latest);
+
+    for (unit, token) in [
+        (PriceUnit::Wei, DEFAULT_ETH_FEE_TOKEN_ADDRESS),
+        (PriceUnit::Fri, DEFAULT_STRK_FEE_TOKEN_ADDRESS),
+    ] {
+        client.set_balance(address, amount, unit).await.unwrap();
+
+        let token = Felt::from(token);
+        let low = provider.get_storage_at(token, base, block_id).await.unwrap();
+        let high = provider.get_storage_at(token, base + Felt::ONE, block_id).await.unwrap();
+        assert_eq!(low, Felt::ONE);
+        assert_eq!(high, Felt::ONE);
+    }
+}
+
+#[tokio::test]
+async fn snapshot_and_revert() {
+    let sequencer = create_test_sequencer().await;
+    let provider = sequencer.backend().blockchain.provider();
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+
+    let address = felt!("0x1337");
+    let token = Felt::from(DEFAULT_ETH_FEE_TOKEN_ADDRESS);
+    let base = get_fee_token_balance_base_storage_address(address.into());
+    let block_id = BlockId::Tag(BlockTag::Latest);
+
+    let latest = provider.latest_number().unwrap();
+    let snapshot = client.snapshot().await.unwrap();
+
+    client.set_balance(address, felt!("0x100"), PriceUnit::Wei).await.unwrap();
+    client.generate_block().await.unwrap();
+    assert_eq!(provider.latest_number().unwrap(), latest + 1);
+
+    client.revert(snapshot).await.unwrap();
+    assert_eq!(provider.latest_number().unwrap(), latest);
+    let balance = sequencer.provider().get_storage_at(token, base, block_id).await.unwrap();
+    assert_eq!(balance, Felt::ZERO);
+
+    // the chain goes on from the reverted block
+    client.generate_block().await.unwrap();
+    assert_eq!(provider.latest_number().unwrap(), latest + 1);
+
+    // a snapshot can only be reverted to once
+    assert!(client.revert(snapshot).await.is_err());
+}
+
+#[tokio::test]
+async fn reorg() {
+    let sequencer = create_test_sequencer().await;
+    let provider = sequencer.backend().blockchain.provider();
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+
+    let call = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![felt!("0x100"), felt!("0x1"), Felt::ZERO],
+    };
+
+    // the same transfer twice, so that both blocks change the same state entries
+    for _ in 0..2 {
+        let res = sequencer.account().execute_v1(vec![call.clone()]).send().await.unwrap();
+        dojo_utils::TransactionWaiter::new(res.transaction_hash, &sequencer.provider())
+            .await
+            .unwrap();
+    }
+
+    let latest = provider.latest_number().unwrap();
+    let replaced = provider.block_hash_by_num(latest).unwrap().unwrap();
+    let replaced_tx = provider.transaction_hashes_by_block(latest.into()).unwrap().unwrap()[0];
+    let parent_root = provider.state_root((latest - 1).into()).unwrap().unwrap();
+    assert_ne!(provider.state_root(latest.into()).unwrap().unwrap(), parent_root);
+
+    client.reorg(1, Vec::new()).await.unwrap();
+
+    // the chain keeps its height, but the replaced block and its transaction are gone
+    assert_eq!(provider.latest_number().unwrap(), latest);
+    assert_eq!(provider.block_number_by_hash(replaced).unwrap(), None);
+    assert_eq!(provider.transaction_by_hash(replaced_tx).unwrap(), None);
+
+    // the new block is empty, so its state root is computed over the state of its parent
+    assert_eq!(provider.state_root(latest.into()).unwrap().unwrap(), parent_root);
+
+    // the genesis block can't be replaced
+    assert!(client.reorg(latest + 1, Vec::new()).await.is_err());
+}
+
+#[tokio::test]
+async fn reload_config() {
+    let sequencer = create_test_sequencer().await;
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+
+    // no hook is registered by the test node
+    assert!(client.reload_config().await.is_err());
+
+    let gas_prices = GasPrices { eth: 1234, strk: 5678 };
+    let mut config = get_default_test_config(SequencingConfig::default());
+    config.dev.fixed_gas_prices = Some(FixedL1GasPriceConfig {
+        gas_price: gas_prices.clone(),
+        data_gas_price: gas_prices.clone(),
+    });
+
+    let references = Arc::strong_count(sequencer.backend());
+    sequencer.node().set_config_source(move || Ok(config.clone()));
+    // the hook doesn't keep the backend it's owned by alive
+    assert_eq!(Arc::strong_count(sequencer.backend()), references);
+
+    client.reload_config().await.unwrap();
+    assert_eq!(sequencer.backend().gas_oracle.current_gas_prices(), gas_prices);
+}
+
+#[tokio::test]
+async fn time_and_gas_price_apply_to_pending_block() {
+    let config =
+        get_default_test_config(SequencingConfig { no_mining: true, ..Default::default() });
+    let sequencer = TestSequencer::start(config).await;
+    let provider = sequencer.backend().blockchain.provider();
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+
+    let timestamp = 2_000_000_000;
+    let gas_prices = GasPrices { eth: 1234, strk: 5678 };
+    let data_gas_prices = GasPrices { eth: 12, strk: 34 };
+
+    // the pending block is still empty, so it's opened again with the new context
+    client.set_block_gas_price(gas_prices.clone(), data_gas_prices.clone()).await.unwrap();
+    client.set_next_block_timestamp(timestamp).await.unwrap();
+    client.generate_block().await.unwrap();
+
+    let block = provider.latest_number().unwrap();
+    let header = provider.block(block.into()).unwrap().unwrap().header;
+    assert_eq!(header.timestamp, timestamp);
+    assert_eq!(header.l1_gas_prices, gas_prices);
+    assert_eq!(header.l1_data_gas_prices, data_gas_prices);
+
+    client.increase_time(1000).await.unwrap();
+    client.generate_block().await.unwrap();
+
+    let block = provider.latest_number().unwrap();
+    let header = provider.block(block.into()).unwrap().unwrap().header;
+    // allow for the time elapsed since the timestamp was set
+    assert!((timestamp + 1000..=timestamp + 1002).contains(&header.timestamp));
+}
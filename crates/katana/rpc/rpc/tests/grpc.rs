@@ -0,0 +1,91 @@
+use dojo_test_utils::sequencer::{get_default_test_config, TestSequencer};
+use jsonrpsee::http_client::HttpClientBuilder;
+use katana_grpc::proto::starknet::starknet_client::StarknetClient;
+use katana_grpc::proto::starknet::{
+    get_class_at_response, GetClassAtRequest, SubscribeBlocksRequest,
+};
+use katana_grpc::proto::types::{block_id, BlockId, Felt};
+use katana_node::config::grpc::{GrpcConfig, DEFAULT_GRPC_ADDR};
+use katana_node::config::SequencingConfig;
+use katana_rpc_api::dev::DevApiClient;
+use starknet::accounts::Account;
+use starknet::core::types::{BlockId as RpcBlockId, BlockTag, ContractClass};
+use starknet::providers::Provider;
+
+async fn create_test_sequencer() -> TestSequencer {
+    let mut config = get_default_test_config(SequencingConfig::default());
+    config.grpc = Some(GrpcConfig { addr: DEFAULT_GRPC_ADDR, port: 0 });
+    TestSequencer::start(config).await
+}
+
+async fn grpc_client(sequencer: &TestSequencer) -> StarknetClient<tonic::transport::Channel> {
+    let addr = sequencer.grpc_addr().expect("gRPC server not started");
+    StarknetClient::connect(format!("http://{addr}")).await.unwrap()
+}
+
+#[tokio::test]
+async fn subscribe_blocks_while_mining() {
+    let sequencer = create_test_sequencer().await;
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let mut grpc = grpc_client(&sequencer).await;
+
+    for _ in 0..10 {
+        client.generate_block().await.unwrap();
+    }
+
+    // the blocks are mined while the stream catches up from the genesis, so some of them are
+    // notified after having already been sent
+    let request = SubscribeBlocksRequest { from_block: Some(0) };
+    let mining = async {
+        for _ in 0..10 {
+            client.generate_block().await.unwrap();
+        }
+    };
+    let (stream, _) = tokio::join!(grpc.subscribe_blocks(request), mining);
+    let mut stream = stream.unwrap().into_inner();
+
+    // one more block once the mining is over, which must directly follow the others
+    client.generate_block().await.unwrap();
+
+    for expected in 0..=21 {
+        let block = stream.message().await.unwrap().unwrap().block.unwrap();
+        assert_eq!(block.header.unwrap().block_number, expected);
+    }
+}
+
+#[tokio::test]
+async fn get_class_at() {
+    let sequencer = create_test_sequencer().await;
+    let mut grpc = grpc_client(&sequencer).await;
+
+    let address = sequencer.account().address();
+    let block_id = BlockId { identifier: Some(block_id::Identifier::Tag("latest".to_string())) };
+    let request = GetClassAtRequest {
+        block_id: Some(block_id),
+        contract_address: Some(Felt { value: address.to_bytes_be().to_vec() }),
+    };
+    let class = grpc.get_class_at(request).await.unwrap().into_inner().result.unwrap();
+
+    // the same class as the one served by the JSON-RPC API
+    let expected = sequencer
+        .provider()
+        .get_class_at(RpcBlockId::Tag(BlockTag::Latest), address)
+        .await
+        .unwrap();
+
+    match (class, expected) {
+        (get_class_at_response::Result::ContractClass(class), ContractClass::Sierra(expected)) => {
+            assert_eq!(class.sierra_program.len(), expected.sierra_program.len());
+            assert_eq!(class.contract_class_version, expected.contract_class_version);
+            assert_eq!(class.abi, expected.abi);
+        }
+        (
+            get_class_at_response::Result::DeprecatedContractClass(class),
+            ContractClass::Legacy(expected),
+        ) => {
+            let json = serde_json::to_value(&expected).unwrap();
+            assert_eq!(class.program, json["program"].as_str().unwrap());
+        }
+        (class, expected) => panic!("class mismatch: {class:?} vs {expected:?}"),
+    }
+}
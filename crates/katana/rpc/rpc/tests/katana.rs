@@ -0,0 +1,488 @@
+use std::fs::File;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use assert_matches::assert_matches;
+use dojo_test_utils::sequencer::{get_default_test_config, TestSequencer};
+use futures::future::{self, BoxFuture};
+use jsonrpsee::http_client::HttpClientBuilder;
+use katana_core::backend::transaction_commitment_leaf;
+use katana_core::service::hooks::{BlockBuildingHook, TxAction};
+use katana_core::service::invariant::Invariant;
+use katana_executor::ExecutionResult;
+use katana_node::config::rpc::ApiKind;
+use katana_node::config::SequencingConfig;
+use katana_primitives::block::{BlockHashOrNumber, BlockNumber};
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::env::BlockEnv;
+use katana_primitives::genesis::constant::DEFAULT_ETH_FEE_TOKEN_ADDRESS;
+use katana_primitives::transaction::ExecutableTxWithHash;
+use katana_provider::traits::block::HeaderProvider;
+use katana_provider::traits::transaction::{TransactionProvider, TransactionTraceProvider};
+use katana_rpc_api::katana::KatanaApiClient;
+use katana_rpc_types::state_update::{EncodedStateDiff, StateDiffFormat};
+use katana_trie::verify_merkle_proof;
+use starknet::accounts::{Account, ConnectedAccount};
+use starknet::core::types::{BlockId, BlockTag, Call, Felt, FunctionCall};
+use starknet::macros::{felt, selector};
+use starknet::providers::Provider;
+use starknet_types_core::hash;
+
+mod common;
+
+async fn start_sequencer() -> TestSequencer {
+    let mut config = get_default_test_config(SequencingConfig::default());
+    config.rpc.apis.insert(ApiKind::Katana);
+    TestSequencer::start(config).await
+}
+
+async fn transfer(sequencer: &TestSequencer) {
+    let call = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![felt!("0x100"), felt!("0x1"), Felt::ZERO],
+    };
+
+    let res = sequencer.account().execute_v1(vec![call]).send().await.unwrap();
+    dojo_utils::TransactionWaiter::new(res.transaction_hash, &sequencer.provider()).await.unwrap();
+}
+
+#[tokio::test]
+async fn dump_and_load_state() {
+    let sequencer = start_sequencer().await;
+    let account = sequencer.account();
+    let provider = sequencer.provider();
+
+    let path = PathBuf::from("tests/test_data/cairo1_contract.json");
+    let (contract, compiled_class_hash) =
+        common::prepare_contract_declaration_params(&path).unwrap();
+    let class_hash = contract.class_hash();
+    let res = account.declare_v2(contract.into(), compiled_class_hash).send().await.unwrap();
+    dojo_utils::TransactionWaiter::new(res.transaction_hash, &provider).await.unwrap();
+
+    transfer(&sequencer).await;
+
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let dump = client.dump_state().await.unwrap();
+
+    // restore the state on a new node started from the same genesis
+    let restored = start_sequencer().await;
+    let restored_provider = restored.provider();
+    let restored_client = HttpClientBuilder::default().build(restored.url()).unwrap();
+
+    // a dump whose last block doesn't match its state updates is rejected without loading any of
+    // the blocks before it
+    let mut tampered = dump.clone();
+    tampered.blocks.last_mut().unwrap().header.state_root = Felt::ONE;
+    assert!(restored_client.load_state(tampered).await.is_err());
+    assert_eq!(restored_provider.block_number().await.unwrap(), 0);
+
+    // nor any of their trie changes, so the state roots of the dump still match once loaded
+    restored_client.load_state(dump.clone()).await.unwrap();
+
+    assert_eq!(
+        provider.block_hash_and_number().await.unwrap(),
+        restored_provider.block_hash_and_number().await.unwrap()
+    );
+
+    let block_id = BlockId::Tag(BlockTag::Latest);
+    let address = account.address();
+    assert_eq!(
+        provider.get_nonce(block_id, address).await.unwrap(),
+        restored_provider.get_nonce(block_id, address).await.unwrap()
+    );
+    assert_eq!(
+        provider.get_class(block_id, class_hash).await.unwrap(),
+        restored_provider.get_class(block_id, class_hash).await.unwrap()
+    );
+
+    // the restored chain keeps mining on top of the loaded blocks
+    transfer(&restored).await;
+    let restored_account = restored.account();
+    assert_eq!(
+        restored_account.get_nonce().await.unwrap(),
+        account.get_nonce().await.unwrap() + Felt::ONE
+    );
+
+    // the state can only be loaded on a chain without blocks
+    assert!(restored_client.load_state(dump).await.is_err());
+}
+
+#[tokio::test]
+async fn get_blocks() {
+    let sequencer = start_sequencer().await;
+    transfer(&sequencer).await;
+    transfer(&sequencer).await;
+
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let latest = sequencer.provider().block_number().await.unwrap();
+
+    // the blocks that aren't mined yet are left out
+    let page = client.blocks(0, latest + 10, None).await.unwrap();
+    assert_eq!(page.blocks.len() as u64, latest + 1);
+    assert_eq!(page.next_block, None);
+    assert!(page.blocks.iter().all(|b| b.receipts.is_none()));
+
+    let page = client.blocks(latest, latest, Some(true)).await.unwrap();
+    assert_eq!(page.blocks.len(), 1);
+    assert_matches!(page.blocks[0].receipts.as_deref(), Some([_]));
+
+    assert!(client.blocks(latest, 0, None).await.is_err());
+    assert!(client.blocks(latest + 1, latest + 1, None).await.is_err());
+}
+
+#[tokio::test]
+async fn get_state_diff_range() {
+    let sequencer = start_sequencer().await;
+    transfer(&sequencer).await;
+    transfer(&sequencer).await;
+
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let latest = sequencer.provider().block_number().await.unwrap();
+    let account = sequencer.account().address();
+
+    // the nonce updates of both transfers are aggregated into the latest one
+    let range = client.state_diff_range(1, latest, None).await.unwrap();
+    assert_eq!((range.from_block, range.to_block), (1, latest));
+    let EncodedStateDiff::Json(diff) = range.state_diff else { panic!("expected a json diff") };
+    let nonces = diff.0.nonces.iter().filter(|n| n.contract_address == account);
+    assert_eq!(nonces.map(|n| n.nonce).collect::<Vec<_>>(), vec![Felt::TWO]);
+
+    let range = client.state_diff_range(1, latest, Some(StateDiffFormat::Binary)).await.unwrap();
+    let EncodedStateDiff::Binary(data) = range.state_diff else { panic!("expected a binary diff") };
+    let words = data.strip_prefix("0x").unwrap();
+    assert!(!words.is_empty() && words.len() % 64 == 0);
+
+    assert!(client.state_diff_range(latest, 0, None).await.is_err());
+    assert!(client.state_diff_range(0, latest + 1, None).await.is_err());
+    assert!(client.state_diff_range(0, 1000, None).await.is_err());
+}
+
+#[tokio::test]
+async fn verify_block_hash() {
+    let sequencer = start_sequencer().await;
+    transfer(&sequencer).await;
+
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let verification = client.verify_block_hash(BlockId::Tag(BlockTag::Latest)).await.unwrap();
+
+    let provider = sequencer.provider();
+    let latest = provider.block_hash_and_number().await.unwrap();
+    assert_eq!(verification.block_number, latest.block_number);
+    assert_eq!(verification.block_hash, latest.block_hash);
+    assert_eq!(verification.computed_hash, latest.block_hash);
+    assert!(verification.is_valid);
+    assert!(verification.mismatches.is_empty());
+
+    // the genesis block isn't mined
+    assert!(client.verify_block_hash(BlockId::Number(0)).await.is_err());
+}
+
+#[tokio::test]
+async fn transaction_inclusion_proof() {
+    let sequencer = start_sequencer().await;
+    let call = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![felt!("0x100"), felt!("0x1"), Felt::ZERO],
+    };
+
+    let res = sequencer.account().execute_v1(vec![call]).send().await.unwrap();
+    let provider = sequencer.provider();
+    dojo_utils::TransactionWaiter::new(res.transaction_hash, &provider).await.unwrap();
+
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let proof = client.transaction_inclusion_proof(res.transaction_hash).await.unwrap();
+
+    let storage = sequencer.backend().blockchain.provider();
+    let header = storage.header(BlockHashOrNumber::Num(proof.block_number)).unwrap().unwrap();
+    assert_eq!(proof.transactions_commitment, header.transactions_commitment);
+    assert_eq!(proof.receipts_commitment, header.receipts_commitment);
+
+    // the transaction is committed with its signature
+    let tx = storage.transaction_by_hash(res.transaction_hash).unwrap().unwrap();
+    assert!(!tx.transaction.signature().is_empty());
+    assert_eq!(proof.transaction_leaf, transaction_commitment_leaf(&tx));
+
+    let index = proof.transaction_index as usize;
+    let transaction_proof = proof.transaction_proof.into_iter().map(Into::into).collect::<Vec<_>>();
+    let receipt_proof = proof.receipt_proof.into_iter().map(Into::into).collect::<Vec<_>>();
+
+    assert!(verify_merkle_proof::<hash::Poseidon>(
+        header.transactions_commitment,
+        index,
+        proof.transaction_leaf,
+        &transaction_proof
+    ));
+    assert!(verify_merkle_proof::<hash::Poseidon>(
+        header.receipts_commitment,
+        index,
+        proof.receipt_hash,
+        &receipt_proof
+    ));
+
+    // the bare transaction hash isn't a leaf of the transactions tree
+    assert!(!verify_merkle_proof::<hash::Poseidon>(
+        header.transactions_commitment,
+        index,
+        res.transaction_hash,
+        &transaction_proof
+    ));
+
+    assert!(client.transaction_inclusion_proof(felt!("0x1337")).await.is_err());
+}
+
+#[tokio::test]
+async fn transaction_resources() {
+    let sequencer = start_sequencer().await;
+    let call = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![felt!("0x100"), felt!("0x1"), Felt::ZERO],
+    };
+
+    let res = sequencer.account().execute_v1(vec![call]).send().await.unwrap();
+    let provider = sequencer.provider();
+    dojo_utils::TransactionWaiter::new(res.transaction_hash, &provider).await.unwrap();
+
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let usage = client.transaction_resources(res.transaction_hash).await.unwrap();
+
+    assert_eq!(usage.transaction_hash, res.transaction_hash);
+    assert!(usage.l1_gas > 0);
+    assert!(usage.steps > 0);
+
+    assert!(client.transaction_resources(felt!("0x1337")).await.is_err());
+}
+
+#[tokio::test]
+async fn execution_resources() {
+    let sequencer = start_sequencer().await;
+    let call = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![felt!("0x100"), felt!("0x1"), Felt::ZERO],
+    };
+
+    let res = sequencer.account().execute_v1(vec![call]).send().await.unwrap();
+    let provider = sequencer.provider();
+    dojo_utils::TransactionWaiter::new(res.transaction_hash, &provider).await.unwrap();
+
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let resources = client.execution_resources(res.transaction_hash).await.unwrap();
+
+    assert_eq!(resources.transaction_hash, res.transaction_hash);
+    assert!(resources.steps > 0);
+    assert!(resources.builtins.contains_key("range_check"));
+    // the transfer updates the balances of the sender and the recipient, and the sender's nonce
+    assert!(resources.state_diff_size.n_storage_updates >= 2);
+    assert!(resources.state_diff_size.n_modified_contracts >= 1);
+
+    assert!(client.execution_resources(felt!("0x1337")).await.is_err());
+}
+
+#[tokio::test]
+async fn debug_trace_call() {
+    let sequencer = start_sequencer().await;
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let block_id = BlockId::Tag(BlockTag::Latest);
+
+    let request = FunctionCall {
+        contract_address: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        entry_point_selector: selector!("balanceOf"),
+        calldata: vec![sequencer.account().address()],
+    };
+
+    let trace = client.debug_trace_call(request.clone(), block_id).await.unwrap();
+    let retdata = sequencer.provider().call(request, block_id).await.unwrap();
+
+    assert_eq!(trace.retdata, retdata);
+    assert_eq!(trace.contract_address, DEFAULT_ETH_FEE_TOKEN_ADDRESS);
+    assert!(trace.execution_resources.n_steps > 0);
+    assert!(!trace.failed);
+
+    // calling an entrypoint that doesn't exist fails
+    let request = FunctionCall {
+        contract_address: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        entry_point_selector: selector!("not_an_entrypoint"),
+        calldata: vec![],
+    };
+    assert!(client.debug_trace_call(request, block_id).await.is_err());
+}
+
+#[tokio::test]
+async fn debug_trace_transaction() {
+    let sequencer = start_sequencer().await;
+    let call = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![felt!("0x100"), felt!("0x1"), Felt::ZERO],
+    };
+
+    let res = sequencer.account().execute_v1(vec![call]).send().await.unwrap();
+    dojo_utils::TransactionWaiter::new(res.transaction_hash, &sequencer.provider()).await.unwrap();
+
+    // the next transfer bumps the nonce of the account, so the first one can only be executed
+    // again on the state it was mined on
+    transfer(&sequencer).await;
+
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let trace = client.debug_trace_transaction(res.transaction_hash).await.unwrap();
+
+    let storage = sequencer.backend().blockchain.provider();
+    let mined = storage.transaction_execution(res.transaction_hash).unwrap().unwrap();
+    assert_eq!(trace, mined);
+
+    let execute = trace.execute_call_info.unwrap();
+    assert_eq!(execute.contract_address, sequencer.account().address().into());
+    assert!(trace.revert_error.is_none());
+
+    assert!(client.debug_trace_transaction(felt!("0x1337")).await.is_err());
+}
+
+#[tokio::test]
+async fn tx_pool_content_and_status() {
+    let mut config =
+        get_default_test_config(SequencingConfig { no_mining: true, ..Default::default() });
+    config.rpc.apis.insert(ApiKind::Katana);
+    let sequencer = TestSequencer::start(config).await;
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+
+    let status = client.tx_pool_status().await.unwrap();
+    assert_eq!((status.pending, status.queued), (0, 0));
+
+    // without mining, the transaction stays in the pool
+    let call = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![felt!("0x100"), felt!("0x1"), Felt::ZERO],
+    };
+    let res = sequencer.account().execute_v1(vec![call]).send().await.unwrap();
+
+    let status = client.tx_pool_status().await.unwrap();
+    assert_eq!((status.pending, status.queued), (1, 0));
+
+    let content = client.tx_pool_content().await.unwrap();
+    assert!(content.queued.is_empty());
+    assert_eq!(content.pending.len(), 1);
+    assert_eq!(*content.pending[0].0.transaction_hash(), res.transaction_hash);
+}
+
+/// Leaves out the transactions of a sender, and counts the executed ones.
+struct SkipSender {
+    sender: ContractAddress,
+    executed: Arc<AtomicUsize>,
+}
+
+impl BlockBuildingHook for SkipSender {
+    fn before_transaction(&self, _: &BlockEnv, tx: &ExecutableTxWithHash) -> TxAction {
+        if tx.sender_address() == self.sender {
+            TxAction::Skip
+        } else {
+            TxAction::Execute
+        }
+    }
+
+    fn after_transaction(
+        &self,
+        _: &BlockEnv,
+        _: &ExecutableTxWithHash,
+        _: &ExecutionResult,
+    ) -> Vec<ExecutableTxWithHash> {
+        self.executed.fetch_add(1, Ordering::SeqCst);
+        Vec::new()
+    }
+}
+
+#[tokio::test]
+async fn block_building_hooks() {
+    let sequencer = start_sequencer().await;
+    let skipped = sequencer.account_at_index(1);
+
+    let executed = Arc::new(AtomicUsize::new(0));
+    let hook = SkipSender { sender: skipped.address().into(), executed: executed.clone() };
+    sequencer.backend().add_block_building_hook(Arc::new(hook));
+
+    let call = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![felt!("0x100"), felt!("0x1"), Felt::ZERO],
+    };
+    let res = skipped.execute_v1(vec![call]).send().await.unwrap();
+
+    // the transaction sent after the skipped one is executed
+    transfer(&sequencer).await;
+    assert_eq!(executed.load(Ordering::SeqCst), 1);
+
+    let provider = sequencer.provider();
+    assert!(provider.get_transaction_receipt(res.transaction_hash).await.is_err());
+    assert_eq!(skipped.get_nonce().await.unwrap(), Felt::ZERO);
+
+    // the skipped transaction is dropped from the pool
+    let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+    let status = client.tx_pool_status().await.unwrap();
+    assert_eq!((status.pending, status.queued), (0, 0));
+}
+
+/// An invariant violated by all the blocks from the given one on.
+struct ViolatedFrom(BlockNumber);
+
+impl Invariant for ViolatedFrom {
+    fn name(&self) -> &str {
+        "violated-from"
+    }
+
+    fn check(&self, block_number: BlockNumber) -> BoxFuture<'static, Result<(), String>> {
+        let result = if block_number >= self.0 {
+            Err(format!("violated at block {block_number}"))
+        } else {
+            Ok(())
+        };
+        Box::pin(future::ready(result))
+    }
+}
+
+#[tokio::test]
+async fn invariant_violation_halts_block_production() {
+    let dump_dir = tempfile::tempdir().unwrap();
+    let sequencing = SequencingConfig {
+        invariant_dump_dir: Some(dump_dir.path().to_path_buf()),
+        ..Default::default()
+    };
+    let config = get_default_test_config(sequencing);
+    let sequencer =
+        TestSequencer::start_with_invariants(config, vec![Arc::new(ViolatedFrom(2))]).await;
+    let provider = sequencer.provider();
+
+    // the invariant holds for the first block and is violated by the second one
+    transfer(&sequencer).await;
+    transfer(&sequencer).await;
+    assert_eq!(provider.block_number().await.unwrap(), 2);
+
+    // the state is dumped once the violation is detected
+    let dump = dump_dir.path().join("invariant-violation-2.json");
+    for _ in 0..50 {
+        if dump.exists() {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+    let dump: serde_json::Value = serde_json::from_reader(File::open(&dump).unwrap()).unwrap();
+    assert_eq!(dump["block_number"], 2);
+    assert_eq!(dump["invariant"], "violated-from");
+    assert_eq!(dump["reason"], "violated at block 2");
+
+    // no further block is mined, the new transactions are left in the pool
+    let call = Call {
+        to: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        selector: selector!("transfer"),
+        calldata: vec![felt!("0x100"), felt!("0x1"), Felt::ZERO],
+    };
+    let res = sequencer.account().execute_v1(vec![call]).send().await.unwrap();
+    tokio::time::sleep(Duration::from_secs(1)).await;
+
+    assert_eq!(provider.block_number().await.unwrap(), 2);
+    assert!(provider.get_transaction_receipt(res.transaction_hash).await.is_err());
+}
@@ -10,19 +10,21 @@ use cainome::cairo_serde::EthAddress;
 use cainome::rs::abigen;
 use dojo_test_utils::sequencer::{get_default_test_config, TestSequencer};
 use dojo_utils::TransactionWaiter;
+use jsonrpsee::http_client::HttpClientBuilder;
 use katana_core::service::messaging::MessagingConfig;
 use katana_node::config::SequencingConfig;
 use katana_primitives::felt;
 use katana_primitives::utils::transaction::{
     compute_l1_handler_tx_hash, compute_l1_to_l2_message_hash, compute_l2_to_l1_message_hash,
 };
+use katana_rpc_api::starknet::StarknetApiClient;
 use katana_rpc_types::receipt::ReceiptBlock;
 use rand::Rng;
 use starknet::accounts::{Account, ConnectedAccount};
 use starknet::contract::ContractFactory;
 use starknet::core::types::{
-    BlockId, BlockTag, ContractClass, Felt, Hash256, MsgFromL1, Transaction,
-    TransactionFinalityStatus, TransactionReceipt,
+    BlockId, BlockTag, ContractClass, Felt, Hash256, MsgFromL1, SequencerTransactionStatus,
+    Transaction, TransactionExecutionStatus, TransactionFinalityStatus, TransactionReceipt,
 };
 use starknet::core::utils::get_contract_address;
 use starknet::macros::selector;
@@ -72,6 +74,7 @@ async fn test_messaging() {
         private_key: "".to_string(),
         interval: 2,
         from_block: 0,
+        state_contract_address: None,
     };
 
     let mut config = get_default_test_config(SequencingConfig::default());
@@ -161,6 +164,7 @@ async fn test_messaging() {
             .expect("error getting transaction receipt");
 
         assert!(receipt.status(), "failed to send L1 -> L2 message");
+        let l1_tx_hash = receipt.transaction_hash;
 
         // Wait for the tx to be mined on L2 (Katana)
         tokio::time::sleep(Duration::from_secs(5)).await;
@@ -231,6 +235,14 @@ async fn test_messaging() {
                 panic!("Error, No Receipt TransactionReceipt")
             }
         }
+
+        // the status of the message is retrievable from the hash of the L1 transaction
+        let client = HttpClientBuilder::default().build(sequencer.url()).unwrap();
+        let statuses = client.get_messages_status(l1_tx_hash).await.unwrap();
+        assert_eq!(statuses.len(), 1);
+        assert_eq!(statuses[0].transaction_hash, tx_hash);
+        assert_eq!(statuses[0].finality_status, SequencerTransactionStatus::AcceptedOnL2);
+        assert_eq!(statuses[0].execution_status, Some(TransactionExecutionStatus::Succeeded));
     }
 
     // Send message from L2 to L1
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs::{self};
 use std::path::PathBuf;
 use std::sync::Arc;
@@ -12,10 +13,14 @@ use jsonrpsee::http_client::HttpClientBuilder;
 use katana_node::config::SequencingConfig;
 use katana_primitives::event::ContinuationToken;
 use katana_primitives::genesis::constant::{
-    DEFAULT_ACCOUNT_CLASS_HASH, DEFAULT_ETH_FEE_TOKEN_ADDRESS, DEFAULT_PREFUNDED_ACCOUNT_BALANCE,
-    DEFAULT_STRK_FEE_TOKEN_ADDRESS, DEFAULT_UDC_ADDRESS,
+    get_fee_token_balance_base_storage_address, DEFAULT_ACCOUNT_CLASS_HASH,
+    DEFAULT_ETH_FEE_TOKEN_ADDRESS, DEFAULT_LEGACY_ERC20_CLASS_HASH,
+    DEFAULT_PREFUNDED_ACCOUNT_BALANCE, DEFAULT_STRK_FEE_TOKEN_ADDRESS, DEFAULT_UDC_ADDRESS,
 };
 use katana_rpc_api::dev::DevApiClient;
+use katana_rpc_api::starknet::StarknetApiClient;
+use katana_rpc_types::state_override::{ContractOverride, StateOverride};
+use katana_rpc_types::{FeltAsHex, FunctionCall};
 use starknet::accounts::{
     Account, AccountError, AccountFactory, ConnectedAccount, ExecutionEncoding,
     OpenZeppelinAccountFactory, SingleOwnerAccount,
@@ -23,8 +28,9 @@ use starknet::accounts::{
 use starknet::core::types::contract::legacy::LegacyContractClass;
 use starknet::core::types::{
     BlockId, BlockTag, Call, DeclareTransactionReceipt, DeployAccountTransactionReceipt,
-    EventFilter, EventsPage, ExecutionResult, Felt, StarknetError, TransactionExecutionStatus,
-    TransactionFinalityStatus, TransactionReceipt, TransactionTrace,
+    EventFilter, EventFilterWithPage, EventsPage, ExecutionResult, Felt, ResultPageRequest,
+    StarknetError, TransactionExecutionStatus, TransactionFinalityStatus, TransactionReceipt,
+    TransactionTrace,
 };
 use starknet::core::utils::get_contract_address;
 use starknet::macros::{felt, selector};
@@ -89,6 +95,38 @@ async fn declare_and_deploy_contract() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn get_compiled_casm() -> Result<()> {
+    let sequencer =
+        TestSequencer::start(get_default_test_config(SequencingConfig::default())).await;
+
+    let account = sequencer.account();
+    let provider = sequencer.provider();
+    let client = HttpClientBuilder::default().build(sequencer.url())?;
+
+    let path: PathBuf = PathBuf::from("tests/test_data/cairo1_contract.json");
+    let (contract, compiled_class_hash) = common::prepare_contract_declaration_params(&path)?;
+
+    let class_hash = contract.class_hash();
+    let res = account.declare_v2(contract.into(), compiled_class_hash).send().await?;
+    dojo_utils::TransactionWaiter::new(res.transaction_hash, &provider).await?;
+
+    // the CASM is the one the compiled class hash of the declaration was computed from
+    let casm = StarknetApiClient::get_compiled_casm(&client, class_hash).await?;
+    assert_eq!(casm.compiled_class_hash(), compiled_class_hash);
+
+    // legacy classes have no CASM
+    let err = StarknetApiClient::get_compiled_casm(&client, DEFAULT_LEGACY_ERC20_CLASS_HASH)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Failed to compile the contract"));
+
+    let err = StarknetApiClient::get_compiled_casm(&client, felt!("0x1337")).await.unwrap_err();
+    assert!(err.to_string().contains("Class hash not found"));
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn declare_and_deploy_legacy_contract() -> Result<()> {
     let sequencer =
@@ -670,12 +708,29 @@ async fn get_events_no_pending() -> Result<()> {
         assert_eq!(token.event_n, 0);
     });
 
+    // the token can't be used to continue a query with a different filter
+    let address = Some(DEFAULT_ETH_FEE_TOKEN_ADDRESS.into());
+    let other_filter = EventFilter { address, ..filter.clone() };
+    let result = provider.get_events(other_filter, continuation_token.clone(), chunk_size).await;
+    assert_matches!(
+        result,
+        Err(ProviderError::StarknetError(StarknetError::InvalidContinuationToken))
+    );
+
     let EventsPage { events, continuation_token } =
         provider.get_events(filter.clone(), continuation_token, chunk_size).await?;
 
     assert_eq!(events.len(), 2, "Remaining should be 2");
     assert_matches!(continuation_token, None);
 
+    // a cursor pointing before the requested range starts from the beginning of the range
+    let filter_from_1 = EventFilter { from_block: Some(BlockId::Number(1)), ..filter.clone() };
+    let first_page = provider.get_events(filter_from_1.clone(), None, chunk_size).await?;
+    let mut token = ContinuationToken::parse(&first_page.continuation_token.unwrap())?;
+    token.block_n = 0;
+    let page = provider.get_events(filter_from_1, Some(token.to_string()), chunk_size).await?;
+    assert_eq!(page.events, first_page.events);
+
     // -----------------------------------------------------------------------
     //  case 3 (max chunk is greater than total events in the requested range)
 
@@ -686,6 +741,20 @@ async fn get_events_no_pending() -> Result<()> {
     assert_eq!(events.len(), TOTAL_EVENT_COUNT);
     assert_matches!(continuation_token, None);
 
+    // -----------------------------------------------------------------------
+    //  case 4 (events are returned with their index in their block)
+
+    let result_page_request = ResultPageRequest { continuation_token: None, chunk_size };
+    let page = StarknetApiClient::get_events(
+        &client,
+        EventFilterWithPage { event_filter: filter, result_page_request },
+    )
+    .await?;
+
+    let indexes = page.events.iter().map(|e| e.event_index).collect::<Vec<_>>();
+    let expected = (0..TOTAL_EVENT_COUNT as u64).map(Some).collect::<Vec<_>>();
+    assert_eq!(indexes, expected);
+
     Ok(())
 }
 
@@ -951,3 +1020,91 @@ async fn v3_transactions() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn historical_state_queries() -> Result<()> {
+    let sequencer =
+        TestSequencer::start(get_default_test_config(SequencingConfig::default())).await;
+
+    let provider = sequencer.provider();
+    let account = sequencer.account();
+    let fee_token: Felt = DEFAULT_ETH_FEE_TOKEN_ADDRESS.into();
+    let contract = Erc20Contract::new(fee_token, &account);
+
+    let recipient = felt!("0x1337");
+    let amount = Uint256 { low: felt!("0x100"), high: Felt::ZERO };
+    let balance_key = get_fee_token_balance_base_storage_address(recipient.into());
+
+    let before = provider.block_hash_and_number().await?;
+    let nonce_before =
+        provider.get_nonce(BlockId::Number(before.block_number), account.address()).await?;
+
+    let res = contract.transfer(&recipient, &amount).send().await?;
+    dojo_utils::TransactionWaiter::new(res.transaction_hash, &provider).await?;
+
+    let after = provider.block_hash_and_number().await?;
+    assert!(after.block_number > before.block_number);
+
+    // the state at the older block must not reflect the transfer, whether the block is referred
+    // to by its number or its hash.
+    for block_id in [BlockId::Number(before.block_number), BlockId::Hash(before.block_hash)] {
+        let nonce = provider.get_nonce(block_id, account.address()).await?;
+        assert_eq!(nonce, nonce_before);
+
+        let balance = provider.get_storage_at(fee_token, balance_key, block_id).await?;
+        assert_eq!(balance, Felt::ZERO);
+
+        let class_hash = provider.get_class_hash_at(block_id, account.address()).await?;
+        assert_eq!(class_hash, DEFAULT_ACCOUNT_CLASS_HASH);
+        assert!(provider.get_class(block_id, class_hash).await.is_ok());
+    }
+
+    let block_id = BlockId::Number(after.block_number);
+    let nonce = provider.get_nonce(block_id, account.address()).await?;
+    assert_eq!(nonce, nonce_before + Felt::ONE);
+
+    let balance = provider.get_storage_at(fee_token, balance_key, block_id).await?;
+    assert_eq!(balance, amount.low);
+
+    // blocks that don't exist yet can't be queried
+    let block_id = BlockId::Number(after.block_number + 1);
+    let err = provider.get_nonce(block_id, account.address()).await.unwrap_err();
+    assert_matches!(err, ProviderError::StarknetError(StarknetError::BlockNotFound));
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn call_with_state_override() -> Result<()> {
+    let sequencer =
+        TestSequencer::start(get_default_test_config(SequencingConfig::default())).await;
+    let client = HttpClientBuilder::default().build(sequencer.url())?;
+    let block_id = BlockId::Tag(BlockTag::Latest);
+
+    let holder = felt!("0x1337");
+    let request = FunctionCall {
+        contract_address: DEFAULT_ETH_FEE_TOKEN_ADDRESS.into(),
+        entry_point_selector: selector!("balanceOf"),
+        calldata: vec![holder],
+    };
+
+    let balance = |retdata: Vec<FeltAsHex>| retdata.into_iter().map(Felt::from).collect::<Vec<_>>();
+
+    let retdata = StarknetApiClient::call(&client, request.clone(), block_id, None).await?;
+    assert_eq!(balance(retdata), vec![Felt::ZERO, Felt::ZERO]);
+
+    // the overridden storage slots are read instead of the actual ones
+    let key = get_fee_token_balance_base_storage_address(holder.into());
+    let contract =
+        ContractOverride { storage: BTreeMap::from([(key, felt!("0x100"))]), ..Default::default() };
+    let state_override = StateOverride::from([(DEFAULT_ETH_FEE_TOKEN_ADDRESS, contract)]);
+    let retdata =
+        StarknetApiClient::call(&client, request.clone(), block_id, Some(state_override)).await?;
+    assert_eq!(balance(retdata), vec![felt!("0x100"), Felt::ZERO]);
+
+    // the chain state is left untouched
+    let retdata = StarknetApiClient::call(&client, request, block_id, None).await?;
+    assert_eq!(balance(retdata), vec![Felt::ZERO, Felt::ZERO]);
+
+    Ok(())
+}
@@ -0,0 +1,230 @@
+//! Backup and restore of the database.
+//!
+//! A backup is a database of its own, holding a copy of every table. It is taken from a single
+//! read transaction, so it is consistent even when the database is written to by a running node
+//! in the meantime.
+//!
+//! A backup can be updated incrementally: only the entries that were added, changed or removed
+//! since it was taken are written to it, in a single write transaction, so the backup is left at
+//! its previous state if the update fails.
+
+use std::path::Path;
+
+use anyhow::{bail, Context};
+
+use crate::abstraction::{Database, DbTx};
+use crate::error::DatabaseError;
+use crate::mdbx::tx::{TxRO, TxRW};
+use crate::mdbx::DbEnvKind;
+use crate::tables::{TableType, Tables};
+use crate::utils::is_database_empty;
+use crate::{init_db, init_db_with_kind, open_db_ro};
+
+/// The number of entries changed by a backup or a restore.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SyncStats {
+    /// The number of entries added or updated.
+    pub written: usize,
+    /// The number of entries removed.
+    pub deleted: usize,
+}
+
+/// Backs up the database at `path` to `backup_path`.
+///
+/// If `incremental` is `true` and there is a backup at `backup_path` already, it is updated with
+/// the changes made to the database since it was taken. Otherwise there must be no database at
+/// `backup_path` and a full backup is made.
+///
+/// The database can be in use by a running node.
+pub fn backup_db<P, B>(path: P, backup_path: B, incremental: bool) -> anyhow::Result<SyncStats>
+where
+    P: AsRef<Path>,
+    B: AsRef<Path>,
+{
+    let backup_path = backup_path.as_ref();
+    if !incremental && !is_database_empty(backup_path) {
+        bail!(
+            "A database already exists at path {}, back up incrementally to update it.",
+            backup_path.display()
+        );
+    }
+
+    let source = open_db_ro(path)?;
+    let target = init_db(backup_path)?;
+    sync(&source.tx()?, target.tx_mut()?)
+}
+
+/// Restores the backup at `backup_path` to the database at `path`, which is created if it doesn't
+/// exist.
+///
+/// The content of the database is replaced by the one of the backup. The database is opened
+/// exclusively, so the restore fails if it is in use by a running node.
+pub fn restore_db<B, P>(backup_path: B, path: P) -> anyhow::Result<SyncStats>
+where
+    B: AsRef<Path>,
+    P: AsRef<Path>,
+{
+    let backup_path = backup_path.as_ref();
+    if is_database_empty(backup_path) {
+        bail!("No backup found at path {}.", backup_path.display());
+    }
+
+    let path = path.as_ref();
+    let source = open_db_ro(backup_path)?;
+    let target = init_db_with_kind(path, DbEnvKind::Exclusive).with_context(|| {
+        format!("Database at path {} may be in use, stop the node to restore it", path.display())
+    })?;
+    sync(&source.tx()?, target.tx_mut()?)
+}
+
+/// Makes the content of every table of `target` the same as in `source`, and commits it.
+fn sync(source: &TxRO, target: TxRW) -> anyhow::Result<SyncStats> {
+    let mut stats = SyncStats::default();
+
+    for table in Tables::ALL {
+        let table_stats = sync_table(source, &target, table)
+            .with_context(|| format!("Copying table {}", table.name()))?;
+        stats.written += table_stats.written;
+        stats.deleted += table_stats.deleted;
+    }
+
+    target.commit()?;
+    Ok(stats)
+}
+
+fn sync_table(source: &TxRO, target: &TxRW, table: Tables) -> Result<SyncStats, DatabaseError> {
+    let is_dupsort = matches!(table.table_type(), TableType::DupSort);
+    let source_dbi = source.inner.open_db(Some(table.name())).map_err(DatabaseError::OpenDb)?.dbi();
+    let target_dbi = target.inner.open_db(Some(table.name())).map_err(DatabaseError::OpenDb)?.dbi();
+
+    let mut stats = SyncStats::default();
+
+    // write the entries of the source that the target doesn't have
+    let mut lookup =
+        target.inner.cursor_with_dbi(target_dbi).map_err(DatabaseError::CreateCursor)?;
+    stats.written = target.copy_entries(source, table, |key, value| {
+        let existing = if is_dupsort {
+            lookup.get_both_range::<Vec<u8>>(key, value)
+        } else {
+            target.inner.get::<Vec<u8>>(target_dbi, key)
+        }
+        .map_err(DatabaseError::Read)?;
+
+        Ok(existing.as_deref() != Some(value))
+    })?;
+
+    // remove the entries of the target that the source doesn't have anymore
+    let mut removed = Vec::new();
+    let mut cursor =
+        target.inner.cursor_with_dbi(target_dbi).map_err(DatabaseError::CreateCursor)?;
+    let mut lookup =
+        source.inner.cursor_with_dbi(source_dbi).map_err(DatabaseError::CreateCursor)?;
+    let mut entry = cursor.first::<Vec<u8>, Vec<u8>>().map_err(DatabaseError::Read)?;
+
+    while let Some((key, value)) = entry {
+        let exists = if is_dupsort {
+            let existing = lookup.get_both_range::<Vec<u8>>(&key, &value);
+            existing.map_err(DatabaseError::Read)?.is_some_and(|v| v == value)
+        } else {
+            source.inner.get::<Vec<u8>>(source_dbi, &key).map_err(DatabaseError::Read)?.is_some()
+        };
+
+        if !exists {
+            removed.push((key, value));
+        }
+
+        entry = cursor.next::<Vec<u8>, Vec<u8>>().map_err(DatabaseError::Read)?;
+    }
+
+    for (key, value) in removed {
+        let value = is_dupsort.then_some(value.as_slice());
+        target.inner.del(target_dbi, &key, value).map_err(DatabaseError::Delete)?;
+        stats.deleted += 1;
+    }
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::contract::ContractAddress;
+    use katana_primitives::{address, felt};
+
+    use super::*;
+    use crate::abstraction::{DbDupSortCursor, DbTxMut};
+    use crate::models::storage::StorageEntry;
+    use crate::tables;
+
+    const CONTRACT: ContractAddress = address!("0x1");
+
+    fn entry(key: &str, value: &str) -> StorageEntry {
+        StorageEntry { key: felt!(key), value: felt!(value) }
+    }
+
+    #[test]
+    fn backup_and_restore() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db");
+        let backup_path = dir.path().join("backup");
+        let restored_path = dir.path().join("restored");
+
+        {
+            let db = init_db(&path).unwrap();
+            let tx = db.tx_mut().unwrap();
+            tx.put::<tables::BlockHashes>(0, felt!("0x1")).unwrap();
+            tx.put::<tables::BlockHashes>(1, felt!("0x2")).unwrap();
+            tx.put::<tables::ContractStorage>(CONTRACT, entry("0x1", "0x1")).unwrap();
+            tx.put::<tables::ContractStorage>(CONTRACT, entry("0x2", "0x2")).unwrap();
+            tx.commit().unwrap();
+        }
+
+        let stats = backup_db(&path, &backup_path, false).unwrap();
+        assert_eq!(stats, SyncStats { written: 4, deleted: 0 });
+        // a full backup doesn't overwrite an existing one
+        assert!(backup_db(&path, &backup_path, false).is_err());
+
+        {
+            let db = init_db(&path).unwrap();
+            let tx = db.tx_mut().unwrap();
+            tx.put::<tables::BlockHashes>(1, felt!("0x3")).unwrap();
+            tx.put::<tables::BlockHashes>(2, felt!("0x4")).unwrap();
+            tx.delete::<tables::ContractStorage>(CONTRACT, Some(entry("0x1", "0x1"))).unwrap();
+            tx.commit().unwrap();
+        }
+
+        // only the changes are written to the backup
+        let stats = backup_db(&path, &backup_path, true).unwrap();
+        assert_eq!(stats, SyncStats { written: 2, deleted: 1 });
+        assert_eq!(backup_db(&path, &backup_path, true).unwrap(), SyncStats::default());
+
+        let stats = restore_db(&backup_path, &restored_path).unwrap();
+        assert_eq!(stats, SyncStats { written: 4, deleted: 0 });
+
+        let db = init_db(&restored_path).unwrap();
+        let tx = db.tx().unwrap();
+        assert_eq!(tx.get::<tables::BlockHashes>(0).unwrap(), Some(felt!("0x1")));
+        assert_eq!(tx.get::<tables::BlockHashes>(1).unwrap(), Some(felt!("0x3")));
+        assert_eq!(tx.get::<tables::BlockHashes>(2).unwrap(), Some(felt!("0x4")));
+
+        let mut cursor = tx.cursor_dup::<tables::ContractStorage>().unwrap();
+        let walker = cursor.walk_dup(Some(CONTRACT), None).unwrap().unwrap();
+        let entries = walker.map(|e| e.unwrap().1).collect::<Vec<_>>();
+        assert_eq!(entries, vec![entry("0x2", "0x2")]);
+    }
+
+    #[test]
+    fn restore_database_in_use() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("db");
+        let backup_path = dir.path().join("backup");
+
+        let db = init_db(&path).unwrap();
+        backup_db(&path, &backup_path, false).unwrap();
+
+        // the database is open by a node
+        assert!(restore_db(&backup_path, &path).is_err());
+
+        drop(db);
+        restore_db(&backup_path, &path).unwrap();
+    }
+}
@@ -4,6 +4,7 @@ pub mod postcard;
 use katana_primitives::block::FinalityStatus;
 use katana_primitives::class::FlattenedSierraClass;
 use katana_primitives::contract::ContractAddress;
+use katana_primitives::receipt::{EventsBloom, EVENTS_BLOOM_SIZE};
 use katana_primitives::Felt;
 
 use crate::error::CodecError;
@@ -101,3 +102,18 @@ impl Decompress for FinalityStatus {
         }
     }
 }
+
+impl Compress for EventsBloom {
+    type Compressed = [u8; EVENTS_BLOOM_SIZE];
+    fn compress(self) -> Self::Compressed {
+        self.0
+    }
+}
+
+impl Decompress for EventsBloom {
+    fn decompress<B: AsRef<[u8]>>(bytes: B) -> Result<Self, CodecError> {
+        let bloom =
+            bytes.as_ref().try_into().map_err(|_| CodecError::Decode("Invalid bloom".into()))?;
+        Ok(EventsBloom(bloom))
+    }
+}
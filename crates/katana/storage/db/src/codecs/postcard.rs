@@ -1,4 +1,4 @@
-use katana_primitives::block::Header;
+use katana_primitives::block::{Header, HeaderExtension};
 use katana_primitives::contract::{ContractAddress, GenericContractInfo};
 use katana_primitives::receipt::Receipt;
 use katana_primitives::trace::TxExecInfo;
@@ -12,6 +12,9 @@ use crate::models::block::StoredBlockBodyIndices;
 use crate::models::contract::ContractInfoChangeList;
 use crate::models::list::BlockList;
 use crate::models::trie::TrieDatabaseValue;
+use crate::models::versioned::{
+    VersionedHeader, VersionedReceipt, VersionedTx, VersionedTxExecInfo,
+};
 
 macro_rules! impl_compress_and_decompress_for_table_values {
     ($($name:ty),*) => {
@@ -34,10 +37,7 @@ macro_rules! impl_compress_and_decompress_for_table_values {
 
 impl_compress_and_decompress_for_table_values!(
     u64,
-    Tx,
-    TxExecInfo,
-    Header,
-    Receipt,
+    HeaderExtension,
     Felt,
     TrieDatabaseValue,
     ContractAddress,
@@ -46,3 +46,34 @@ impl_compress_and_decompress_for_table_values!(
     StoredBlockBodyIndices,
     ContractInfoChangeList
 );
+
+/// Implements the codecs of a table value stored in its versioned layout. The value is always
+/// written in its latest layout, and the values written in an older layout are converted into the
+/// current type when read.
+macro_rules! impl_versioned_compress_and_decompress_for_table_values {
+    ($($name:ty => $versioned:ty),*) => {
+        $(
+            impl Compress for $name {
+                type Compressed = Vec<u8>;
+                fn compress(self) -> Self::Compressed {
+                    postcard::to_stdvec(&<$versioned>::from(self)).unwrap()
+                }
+            }
+
+            impl Decompress for $name {
+                fn decompress<B: AsRef<[u8]>>(bytes: B) -> Result<Self, crate::error::CodecError> {
+                    postcard::from_bytes::<$versioned>(bytes.as_ref())
+                        .map(Self::from)
+                        .map_err(|e| CodecError::Decompress(e.to_string()))
+                }
+            }
+        )*
+    }
+}
+
+impl_versioned_compress_and_decompress_for_table_values!(
+    Header => VersionedHeader,
+    Tx => VersionedTx,
+    Receipt => VersionedReceipt,
+    TxExecInfo => VersionedTxExecInfo
+);
@@ -8,9 +8,12 @@ use std::path::Path;
 use anyhow::{anyhow, Context};
 
 pub mod abstraction;
+pub mod backup;
 pub mod codecs;
 pub mod error;
 pub mod mdbx;
+#[cfg(feature = "postcard")]
+pub mod migration;
 pub mod models;
 pub mod tables;
 pub mod trie;
@@ -26,6 +29,11 @@ use version::{check_db_version, create_db_version_file, DatabaseVersionError, CU
 ///
 /// This will create the default tables, if necessary.
 pub fn init_db<P: AsRef<Path>>(path: P) -> anyhow::Result<DbEnv> {
+    init_db_with_kind(path, DbEnvKind::RW)
+}
+
+/// Same as [`init_db`], opening the database environment as `kind`.
+pub(crate) fn init_db_with_kind<P: AsRef<Path>>(path: P, kind: DbEnvKind) -> anyhow::Result<DbEnv> {
     if is_database_empty(path.as_ref()) {
         fs::create_dir_all(&path).with_context(|| {
             format!("Creating database directory at path {}", path.as_ref().display())
@@ -44,11 +52,22 @@ pub fn init_db<P: AsRef<Path>>(path: P) -> anyhow::Result<DbEnv> {
                     )
                 })?
             }
+            Err(err @ DatabaseVersionError::MismatchVersion { found, .. })
+                if found < CURRENT_DB_VERSION =>
+            {
+                return Err(anyhow!(err).context(format!(
+                    "Database at path {} was created by an older release, run `katana db \
+                     migrate` to migrate it",
+                    path.as_ref().display()
+                )));
+            }
             Err(err) => return Err(anyhow!(err)),
         }
     }
 
-    let env = open_db(path)?;
+    let env = DbEnv::open(path.as_ref(), kind).with_context(|| {
+        format!("Opening database in read-write mode at path {}", path.as_ref().display())
+    })?;
     env.create_tables()?;
     Ok(env)
 }
@@ -77,13 +96,32 @@ pub fn open_db<P: AsRef<Path>>(path: P) -> anyhow::Result<DbEnv> {
     })
 }
 
+/// Open the database at the given `path` in read-only mode.
+///
+/// The database can be opened while a node is writing to it, in which case every read transaction
+/// sees the data committed when it was started. Unlike [`init_db`], the database must already be
+/// of the current version, as it can't be migrated or have its tables created.
+pub fn open_db_ro<P: AsRef<Path>>(path: P) -> anyhow::Result<DbEnv> {
+    check_db_version(&path).with_context(|| {
+        format!("Checking database version at path {}", path.as_ref().display())
+    })?;
+
+    DbEnv::open(path.as_ref(), DbEnvKind::RO).with_context(|| {
+        format!("Opening database in read-only mode at path {}", path.as_ref().display())
+    })
+}
+
 #[cfg(test)]
 mod tests {
 
     use std::fs;
 
+    use katana_primitives::felt;
+
+    use crate::abstraction::{Database, DbTx, DbTxMut};
+    use crate::tables;
     use crate::version::{default_version_file_path, get_db_version, CURRENT_DB_VERSION};
-    use crate::{init_db, init_ephemeral_db};
+    use crate::{init_db, init_ephemeral_db, open_db_ro};
 
     #[test]
     fn initialize_db_in_empty_dir() {
@@ -145,6 +183,24 @@ mod tests {
         assert_eq!(actual_version, CURRENT_DB_VERSION);
     }
 
+    #[test]
+    fn open_db_in_read_only_mode() {
+        let path = tempfile::tempdir().unwrap();
+
+        // only an existing database can be opened in read-only mode
+        let err = open_db_ro(path.path()).unwrap_err();
+        assert!(err.to_string().contains("Checking database version"));
+
+        let db = init_db(path.path()).unwrap();
+        db.update(|tx| tx.put::<tables::BlockHashes>(1, felt!("0x1337"))).unwrap().unwrap();
+        drop(db);
+
+        let db = open_db_ro(path.path()).unwrap();
+        let hash = db.view(|tx| tx.get::<tables::BlockHashes>(1)).unwrap().unwrap();
+        assert_eq!(hash, Some(felt!("0x1337")));
+        assert!(db.tx_mut().is_err());
+    }
+
     #[test]
     fn ephemeral_db_deletion_on_drop() {
         // Create an ephemeral database
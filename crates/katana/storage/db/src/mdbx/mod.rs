@@ -3,6 +3,7 @@
 //! The code is adapted from `reth` mdbx implementation:  <https://github.com/paradigmxyz/reth/blob/227e1b7ad513977f4f48b18041df02686fca5f94/crates/storage/db/src/implementation/mdbx/mod.rs>
 
 pub mod cursor;
+pub mod snapshot;
 pub mod stats;
 pub mod tx;
 
@@ -14,8 +15,10 @@ use dojo_metrics::metrics::gauge;
 pub use libmdbx;
 use libmdbx::{DatabaseFlags, EnvironmentFlags, Geometry, Mode, PageSize, SyncMode, RO, RW};
 use metrics::{describe_gauge, Label};
+use parking_lot::Mutex;
 use tracing::error;
 
+use self::snapshot::Snapshots;
 use self::stats::{Stats, TableStat};
 use self::tx::Tx;
 use crate::abstraction::Database;
@@ -36,6 +39,9 @@ pub enum DbEnvKind {
     RO,
     /// Read-write MDBX environment.
     RW,
+    /// Read-write MDBX environment which can't be opened while the database is in use by another
+    /// environment, nor be used by another one while it is open.
+    Exclusive,
 }
 
 /// Wrapper for `libmdbx-sys` environment.
@@ -53,6 +59,8 @@ struct DbEnvInner {
     /// A flag inidicating whether the database is ephemeral or not. If `true`, the database will
     /// be deleted when the environment is dropped.
     ephemeral: bool,
+    /// The snapshots taken of the database, see [`DbEnv::snapshot`].
+    snapshots: Mutex<Snapshots>,
 }
 
 impl DbEnv {
@@ -62,7 +70,9 @@ impl DbEnv {
     pub fn open(path: impl AsRef<Path>, kind: DbEnvKind) -> Result<DbEnv, DatabaseError> {
         let mode = match kind {
             DbEnvKind::RO => Mode::ReadOnly,
-            DbEnvKind::RW => Mode::ReadWrite { sync_mode: SyncMode::Durable },
+            DbEnvKind::RW | DbEnvKind::Exclusive => {
+                Mode::ReadWrite { sync_mode: SyncMode::Durable }
+            }
         };
 
         let mut builder = libmdbx::Environment::builder();
@@ -79,6 +89,7 @@ impl DbEnv {
             })
             .set_flags(EnvironmentFlags {
                 mode,
+                exclusive: matches!(kind, DbEnvKind::Exclusive),
                 // We disable readahead because it improves performance for linear scans, but
                 // worsens it for random access (which is our access pattern outside of sync)
                 no_rdahead: true,
@@ -89,7 +100,7 @@ impl DbEnv {
 
         let env = builder.open(path.as_ref()).map_err(DatabaseError::OpenEnv)?;
         let dir = path.as_ref().to_path_buf();
-        let inner = DbEnvInner { env, dir, ephemeral: false };
+        let inner = DbEnvInner { env, dir, ephemeral: false, snapshots: Default::default() };
 
         Ok(Self { inner: Arc::new(inner) }.with_metrics())
     }
@@ -121,7 +132,7 @@ impl DbEnv {
 
         let env = builder.open(path).map_err(DatabaseError::OpenEnv)?;
         let dir = path.to_path_buf();
-        let inner = DbEnvInner { env, dir, ephemeral: true };
+        let inner = DbEnvInner { env, dir, ephemeral: true, snapshots: Default::default() };
 
         Ok(Self { inner: Arc::new(inner) }.with_metrics())
     }
@@ -562,4 +573,44 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn db_snapshot_revert() {
+        let env = create_test_db();
+        let key = address!("0x1337");
+        let value1 = StorageEntry { key: felt!("1"), value: felt!("1") };
+        let value2 = StorageEntry { key: felt!("2"), value: felt!("2") };
+
+        env.update(|tx| tx.put::<ContractStorage>(key, value1).expect(ERROR_PUT)).unwrap();
+        let first = env.snapshot().unwrap();
+
+        env.update(|tx| {
+            tx.put::<ContractStorage>(key, value2).expect(ERROR_PUT);
+            tx.put::<Headers>(1u64, Header::default()).expect(ERROR_PUT);
+        })
+        .unwrap();
+        let second = env.snapshot().unwrap();
+
+        env.update(|tx| tx.delete::<ContractStorage>(key, Some(value1)).expect(ERROR_DELETE))
+            .unwrap();
+
+        assert!(env.revert_to_snapshot(second).unwrap());
+        {
+            let tx = env.tx().expect(ERROR_INIT_TX);
+            assert_eq!(tx.entries::<ContractStorage>().unwrap(), 2);
+            assert_eq!(tx.entries::<Headers>().unwrap(), 1);
+        }
+
+        assert!(env.revert_to_snapshot(first).unwrap());
+        {
+            let tx = env.tx().expect(ERROR_INIT_TX);
+            assert_eq!(tx.entries::<ContractStorage>().unwrap(), 1);
+            assert_eq!(tx.entries::<Headers>().unwrap(), 0);
+            assert_eq!(tx.get::<ContractStorage>(key).unwrap(), Some(value1));
+        }
+
+        // the snapshots are released once reverted to
+        assert!(!env.revert_to_snapshot(first).unwrap());
+        assert!(!env.revert_to_snapshot(second).unwrap());
+    }
 }
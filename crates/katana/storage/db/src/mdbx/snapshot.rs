@@ -0,0 +1,68 @@
+//! Snapshots of the database content, to revert it to later on.
+//!
+//! A snapshot is a read transaction that is kept open. MDBX being copy-on-write, the pages seen by
+//! a read transaction are never modified by the write transactions committed after it, so taking a
+//! snapshot doesn't copy anything. Reverting to a snapshot copies the content of every table from
+//! it, in a single write transaction.
+//!
+//! The pages of the database that are only seen by a snapshot can't be reused until it is
+//! released, so the database file keeps growing with the writes made while snapshots are held.
+
+use std::collections::BTreeMap;
+
+use super::tx::{TxRO, TxRW};
+use super::DbEnv;
+use crate::abstraction::{Database, DbTx};
+use crate::error::DatabaseError;
+use crate::tables::Tables;
+
+/// The id of a snapshot taken with [`DbEnv::snapshot`].
+pub type SnapshotId = u64;
+
+/// The snapshots held by a [`DbEnv`].
+#[derive(Debug, Default)]
+pub(super) struct Snapshots {
+    next_id: SnapshotId,
+    taken: BTreeMap<SnapshotId, TxRO>,
+}
+
+impl DbEnv {
+    /// Takes a snapshot of the current content of the database, returning its id.
+    pub fn snapshot(&self) -> Result<SnapshotId, DatabaseError> {
+        let tx = self.tx()?;
+
+        let mut snapshots = self.inner.snapshots.lock();
+        let id = snapshots.next_id;
+        snapshots.next_id += 1;
+        snapshots.taken.insert(id, tx);
+
+        Ok(id)
+    }
+
+    /// Reverts the content of the database to the snapshot `id`.
+    ///
+    /// The snapshot is released, and so are the snapshots taken after it. Returns `false` if there
+    /// is no such snapshot.
+    pub fn revert_to_snapshot(&self, id: SnapshotId) -> Result<bool, DatabaseError> {
+        let mut snapshots = self.inner.snapshots.lock();
+        let Some(snapshot) = snapshots.taken.get(&id) else { return Ok(false) };
+
+        let tx = self.tx_mut()?;
+        restore(&tx, snapshot)?;
+        tx.commit()?;
+
+        snapshots.taken.split_off(&id);
+        Ok(true)
+    }
+}
+
+/// Replaces the content of every table with its content in `snapshot`.
+fn restore(tx: &TxRW, snapshot: &TxRO) -> Result<(), DatabaseError> {
+    for table in Tables::ALL {
+        let dbi = tx.inner.open_db(Some(table.name())).map_err(DatabaseError::OpenDb)?.dbi();
+        tx.inner.clear_db(dbi).map_err(DatabaseError::Clear)?;
+        tx.copy_entries(snapshot, table, |_, _| Ok(true))?;
+    }
+
+    Ok(())
+}
@@ -25,7 +25,7 @@ pub type TxRW = Tx<libmdbx::RW>;
 #[derive(Debug)]
 pub struct Tx<K: TransactionKind> {
     /// Libmdbx-sys transaction.
-    pub(super) inner: libmdbx::Transaction<K>,
+    pub(crate) inner: libmdbx::Transaction<K>,
     /// Database table handle cache.
     db_handles: RwLock<[Option<DBI>; NUM_TABLES]>,
 }
@@ -57,6 +57,79 @@ impl<K: TransactionKind> Tx<K> {
     }
 }
 
+impl Tx<RW> {
+    /// Rewrites every value of the table `T` from its raw stored bytes, using `f` to decode them.
+    ///
+    /// This is meant for migrating a table whose value encoding changed between database versions,
+    /// whose values can't be read anymore with the current [`Decompress`] implementation. Returns
+    /// the number of rewritten entries.
+    ///
+    /// [`Decompress`]: crate::codecs::Decompress
+    pub fn rewrite_values<T: Table>(
+        &self,
+        mut f: impl FnMut(&[u8]) -> Result<T::Value, DatabaseError>,
+    ) -> Result<usize, DatabaseError> {
+        let mut cursor = self
+            .inner
+            .cursor_with_dbi(self.get_dbi::<T>()?)
+            .map_err(DatabaseError::CreateCursor)?;
+
+        let mut count = 0;
+        let mut entry = cursor.first::<Vec<u8>, Vec<u8>>().map_err(DatabaseError::Read)?;
+
+        while let Some((key, value)) = entry {
+            let value = f(&value)?.compress();
+            cursor.put(&key, value.as_ref(), WriteFlags::CURRENT).map_err(|error| {
+                DatabaseError::Write { error, table: T::NAME, key: Box::from(key.as_slice()) }
+            })?;
+
+            count += 1;
+            entry = cursor.next::<Vec<u8>, Vec<u8>>().map_err(DatabaseError::Read)?;
+        }
+
+        Ok(count)
+    }
+
+    /// Writes the raw entries of `table` in `source` for which `filter` returns `true`, the
+    /// duplicates of a key being walked one by one. Returns the number of written entries.
+    pub(crate) fn copy_entries<K: TransactionKind>(
+        &self,
+        source: &Tx<K>,
+        table: Tables,
+        mut filter: impl FnMut(&[u8], &[u8]) -> Result<bool, DatabaseError>,
+    ) -> Result<usize, DatabaseError> {
+        let source_dbi =
+            source.inner.open_db(Some(table.name())).map_err(DatabaseError::OpenDb)?.dbi();
+        let target_dbi =
+            self.inner.open_db(Some(table.name())).map_err(DatabaseError::OpenDb)?.dbi();
+
+        let mut source =
+            source.inner.cursor_with_dbi(source_dbi).map_err(DatabaseError::CreateCursor)?;
+        let mut target =
+            self.inner.cursor_with_dbi(target_dbi).map_err(DatabaseError::CreateCursor)?;
+
+        let mut count = 0;
+        let mut entry = source.first::<Vec<u8>, Vec<u8>>().map_err(DatabaseError::Read)?;
+
+        while let Some((key, value)) = entry {
+            if filter(&key, &value)? {
+                target.put(&key, &value, WriteFlags::UPSERT).map_err(|error| {
+                    DatabaseError::Write {
+                        error,
+                        table: table.name(),
+                        key: Box::from(key.as_slice()),
+                    }
+                })?;
+                count += 1;
+            }
+
+            entry = source.next::<Vec<u8>, Vec<u8>>().map_err(DatabaseError::Read)?;
+        }
+
+        Ok(count)
+    }
+}
+
 impl<K: TransactionKind> DbTx for Tx<K> {
     type Cursor<T: Table> = Cursor<K, T>;
     type DupCursor<T: DupSort> = Self::Cursor<T>;
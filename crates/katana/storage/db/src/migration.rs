@@ -0,0 +1,200 @@
+//! Migration of the databases created by older releases of katana.
+//!
+//! Most changes to the stored values are handled by their versioned layouts (see
+//! [`models::versioned`](crate::models::versioned)) and don't require a migration. A migration is
+//! only needed when the layout of the database itself changes, in which case the database version
+//! is bumped and the database must be migrated with [`migrate_db`] before being opened.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context};
+use serde::de::DeserializeOwned;
+
+use crate::abstraction::{Database, DbTx};
+use crate::error::{CodecError, DatabaseError};
+use crate::mdbx::tx::TxRW;
+use crate::models::versioned::v5;
+use crate::tables::{self, Table};
+use crate::version::{
+    create_db_version_file, default_version_file_path, get_db_version, CURRENT_DB_VERSION,
+};
+
+/// The oldest database version that can be migrated to [`CURRENT_DB_VERSION`].
+pub const MIN_MIGRATABLE_DB_VERSION: u32 = 4;
+
+/// Migrates the database at `path` to [`CURRENT_DB_VERSION`], returning the version the database
+/// was migrated from.
+///
+/// All the migration steps are applied in a single transaction, so the database is left untouched
+/// if any of them fails. The database must not be in use by a running node.
+pub fn migrate_db<P: AsRef<Path>>(path: P) -> anyhow::Result<u32> {
+    let path = path.as_ref();
+    let version = get_db_version(path)
+        .with_context(|| format!("Reading database version at path {}", path.display()))?;
+
+    if version == CURRENT_DB_VERSION {
+        return Ok(version);
+    } else if version > CURRENT_DB_VERSION {
+        bail!(
+            "Database version {version} is newer than the latest version {CURRENT_DB_VERSION} \
+             supported by this release."
+        );
+    } else if version < MIN_MIGRATABLE_DB_VERSION {
+        bail!(
+            "Database version {version} is too old to be migrated, the oldest supported version \
+             is {MIN_MIGRATABLE_DB_VERSION}."
+        );
+    }
+
+    let env = crate::open_db(path)?;
+    // Tables added by newer versions have to exist before the migration steps run.
+    env.create_tables()?;
+    let tx = env.tx_mut()?;
+
+    for from in version..CURRENT_DB_VERSION {
+        match from {
+            4 => migrate_v4_to_v5(&tx)?,
+            5 => migrate_v5_to_v6(&tx)?,
+            // Version 7 only adds the `HeaderExtensions` table, created above.
+            6 => {}
+            // Version 8 only adds the `PruneCheckpoints` table, created above.
+            7 => {}
+            // Version 9 only adds the `EventsBlooms` table, created above. The blocks stored before
+            // have no bloom, and are always scanned for events.
+            8 => {}
+            _ => unreachable!("no migration from database version {from}"),
+        }
+    }
+
+    tx.commit()?;
+
+    // The new version file is written aside and then renamed over the old one, so that the
+    // database is never left without a version file.
+    let version_file = default_version_file_path(path);
+    let tmp_version_file = version_file.with_extension("version.tmp");
+    if tmp_version_file.exists() {
+        // left over by an interrupted migration, and read-only
+        fs::remove_file(&tmp_version_file)?;
+    }
+    create_db_version_file(&tmp_version_file, CURRENT_DB_VERSION)?;
+    fs::rename(&tmp_version_file, &version_file)?;
+
+    Ok(version)
+}
+
+/// Version 5 stores the headers, transactions and receipts in their versioned layouts, which were
+/// previously stored untagged.
+fn migrate_v4_to_v5(tx: &TxRW) -> Result<(), DatabaseError> {
+    rewrite_untagged::<tables::Headers>(tx)?;
+    rewrite_untagged::<tables::Transactions>(tx)?;
+    rewrite_untagged_as::<tables::Receipts, v5::Receipt>(tx)?;
+    Ok(())
+}
+
+/// Version 6 stores the transaction traces in their versioned layout, which were previously stored
+/// untagged in the layout of [`v5::TxExecInfo`].
+fn migrate_v5_to_v6(tx: &TxRW) -> Result<(), DatabaseError> {
+    rewrite_untagged_as::<tables::TxTraces, v5::TxExecInfo>(tx)?;
+    Ok(())
+}
+
+/// Rewrites the values of the table `T` stored without their layout version.
+fn rewrite_untagged<T>(tx: &TxRW) -> Result<usize, DatabaseError>
+where
+    T: Table,
+    T::Value: DeserializeOwned,
+{
+    rewrite_untagged_as::<T, T::Value>(tx)
+}
+
+/// Rewrites the values of the table `T` stored untagged in the legacy layout `L`.
+fn rewrite_untagged_as<T, L>(tx: &TxRW) -> Result<usize, DatabaseError>
+where
+    T: Table,
+    L: DeserializeOwned + Into<T::Value>,
+{
+    tx.rewrite_values::<T>(|bytes| {
+        postcard::from_bytes::<L>(bytes)
+            .map(Into::into)
+            .map_err(|e| CodecError::Decompress(e.to_string()).into())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::block::Header;
+    use katana_primitives::fee::{PriceUnit, TxFeeInfo};
+    use katana_primitives::receipt::{InvokeTxReceipt, Receipt};
+    use katana_primitives::trace::TxExecInfo;
+    use katana_primitives::transaction::{InvokeTx, Tx};
+    use libmdbx::WriteFlags;
+
+    use super::*;
+    use crate::abstraction::DbTxMut;
+    use crate::init_db;
+
+    #[test]
+    fn migrate_untagged_values() {
+        let path = tempfile::tempdir().unwrap();
+
+        let header = Header { number: 1, ..Default::default() };
+        let tx = Tx::Invoke(InvokeTx::V1(Default::default()));
+        let fee = TxFeeInfo { gas_consumed: 1, gas_price: 2, overall_fee: 2, unit: PriceUnit::Fri };
+        let legacy_receipt = v5::Receipt::Invoke(v5::InvokeTxReceipt {
+            revert_error: Some("reverted".to_string()),
+            events: Vec::new(),
+            messages_sent: Vec::new(),
+            execution_resources: Default::default(),
+            fee: fee.clone(),
+        });
+        let legacy_trace = v5::TxExecInfo { actual_fee: 2, ..Default::default() };
+
+        {
+            let env = init_db(path.path()).unwrap();
+            let db_tx = env.tx_mut().unwrap();
+            // Write the values as they were stored by the database version 4.
+            let raw = [
+                (tables::Headers::NAME, postcard::to_stdvec(&header).unwrap()),
+                (tables::Transactions::NAME, postcard::to_stdvec(&tx).unwrap()),
+                (tables::Receipts::NAME, postcard::to_stdvec(&legacy_receipt).unwrap()),
+                (tables::TxTraces::NAME, postcard::to_stdvec(&legacy_trace).unwrap()),
+            ];
+            for (table, value) in raw {
+                let db = db_tx.inner.open_db(Some(table)).unwrap();
+                db_tx.inner.put(db.dbi(), 1u64.to_be_bytes(), value, WriteFlags::UPSERT).unwrap();
+            }
+            db_tx.commit().unwrap();
+        }
+
+        fs::remove_file(default_version_file_path(path.path())).unwrap();
+        create_db_version_file(path.path(), 4).unwrap();
+        assert!(init_db(path.path()).is_err());
+
+        assert_eq!(migrate_db(path.path()).unwrap(), 4);
+        assert_eq!(get_db_version(path.path()).unwrap(), CURRENT_DB_VERSION);
+        assert!(!path.path().join("db.version.tmp").exists());
+
+        let env = init_db(path.path()).unwrap();
+        let db_tx = env.tx().unwrap();
+        assert_eq!(db_tx.get::<tables::Headers>(1).unwrap(), Some(header));
+        assert_eq!(db_tx.get::<tables::Transactions>(1).unwrap(), Some(tx));
+        // The values stored in a legacy layout are read back with an empty state diff size.
+        let receipt = Receipt::Invoke(InvokeTxReceipt {
+            revert_error: Some("reverted".to_string()),
+            events: Vec::new(),
+            messages_sent: Vec::new(),
+            execution_resources: Default::default(),
+            fee,
+        });
+        let trace = TxExecInfo { actual_fee: 2, ..Default::default() };
+        assert_eq!(db_tx.get::<tables::Receipts>(1).unwrap(), Some(receipt));
+        assert_eq!(db_tx.get::<tables::TxTraces>(1).unwrap(), Some(trace));
+        db_tx.commit().unwrap();
+
+        // Values written after the migration are read back the same.
+        let db_tx = env.tx_mut().unwrap();
+        db_tx.put::<tables::Headers>(2, Header::default()).unwrap();
+        assert_eq!(db_tx.get::<tables::Headers>(2).unwrap(), Some(Header::default()));
+    }
+}
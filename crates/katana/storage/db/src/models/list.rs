@@ -24,6 +24,11 @@ impl IntegerSet {
         self.0.insert(num);
     }
 
+    /// Removes a number from the set. Returns `true` if the number was present in the set.
+    pub fn remove(&mut self, num: u64) -> bool {
+        self.0.remove(num)
+    }
+
     /// Checks if the set contains the given number.
     pub fn contains(&self, num: u64) -> bool {
         self.0.contains(num)
@@ -2,5 +2,7 @@ pub mod block;
 pub mod class;
 pub mod contract;
 pub mod list;
+pub mod prune;
 pub mod storage;
 pub mod trie;
+pub mod versioned;
@@ -0,0 +1,26 @@
+use crate::codecs::{Decode, Encode};
+use crate::error::CodecError;
+
+/// The parts of the database that can be pruned.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum PruneSegment {
+    /// The historical state, along with the receipts and traces of the transactions.
+    History = 0,
+}
+
+impl Encode for PruneSegment {
+    type Encoded = [u8; 1];
+    fn encode(self) -> Self::Encoded {
+        [self as u8]
+    }
+}
+
+impl Decode for PruneSegment {
+    fn decode<B: AsRef<[u8]>>(bytes: B) -> Result<Self, CodecError> {
+        match bytes.as_ref().first() {
+            Some(0) => Ok(PruneSegment::History),
+            _ => Err(CodecError::Decode("Invalid prune segment".into())),
+        }
+    }
+}
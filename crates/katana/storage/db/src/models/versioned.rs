@@ -0,0 +1,205 @@
+//! Versioned layouts of the block, transaction, receipt and trace values stored in the database.
+//!
+//! These values are stored tagged with the version of their layout, so that a database written by
+//! an older release remains readable after their type changed. When the layout of one of these
+//! types changes, its previous layout is kept as a new variant of the versioned enum together with
+//! a conversion into the current type, instead of bumping the database version.
+
+use katana_primitives::block::Header;
+use katana_primitives::receipt::Receipt;
+use katana_primitives::trace::TxExecInfo;
+use katana_primitives::transaction::Tx;
+use serde::{Deserialize, Serialize};
+
+/// A block header as stored in the `Headers` table.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum VersionedHeader {
+    V5(Header),
+}
+
+/// A transaction as stored in the `Transactions` table.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum VersionedTx {
+    V5(Tx),
+}
+
+/// A transaction receipt as stored in the `Receipts` table.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum VersionedReceipt {
+    V5(v5::Receipt),
+    V6(Receipt),
+}
+
+/// A transaction execution trace as stored in the `TxTraces` table.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub enum VersionedTxExecInfo {
+    V6(TxExecInfo),
+}
+
+macro_rules! impl_versioned_conversions {
+    ($($versioned:ident => $ty:ty { $latest:ident $(, $older:ident)* }),*) => {
+        $(
+            impl From<$ty> for $versioned {
+                fn from(value: $ty) -> Self {
+                    Self::$latest(value)
+                }
+            }
+
+            impl From<$versioned> for $ty {
+                fn from(value: $versioned) -> Self {
+                    match value {
+                        $versioned::$latest(value) => value,
+                        $($versioned::$older(value) => value.into(),)*
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_versioned_conversions!(
+    VersionedHeader => Header { V5 },
+    VersionedTx => Tx { V5 },
+    VersionedReceipt => Receipt { V6, V5 },
+    VersionedTxExecInfo => TxExecInfo { V6 }
+);
+
+/// The layouts of the values stored by the database version 5, before the state diff size of the
+/// transactions was recorded in their execution resources.
+pub mod v5 {
+    use alloy_primitives::B256;
+    use katana_primitives::contract::ContractAddress;
+    use katana_primitives::fee::TxFeeInfo;
+    use katana_primitives::receipt::{self, Event, MessageToL1};
+    use katana_primitives::trace::{self, CallInfo, ExecutionResources, L1Gas};
+    use katana_primitives::transaction::TxType;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct TxResources {
+        pub n_reverted_steps: usize,
+        pub vm_resources: ExecutionResources,
+        pub data_availability: L1Gas,
+        pub total_gas_consumed: L1Gas,
+    }
+
+    impl From<TxResources> for trace::TxResources {
+        fn from(value: TxResources) -> Self {
+            Self {
+                n_reverted_steps: value.n_reverted_steps,
+                vm_resources: value.vm_resources,
+                data_availability: value.data_availability,
+                total_gas_consumed: value.total_gas_consumed,
+                state_diff_size: Default::default(),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct TxExecInfo {
+        pub validate_call_info: Option<CallInfo>,
+        pub execute_call_info: Option<CallInfo>,
+        pub fee_transfer_call_info: Option<CallInfo>,
+        pub actual_fee: u128,
+        pub actual_resources: TxResources,
+        pub revert_error: Option<String>,
+        pub r#type: TxType,
+    }
+
+    impl From<TxExecInfo> for trace::TxExecInfo {
+        fn from(value: TxExecInfo) -> Self {
+            Self {
+                validate_call_info: value.validate_call_info,
+                execute_call_info: value.execute_call_info,
+                fee_transfer_call_info: value.fee_transfer_call_info,
+                actual_fee: value.actual_fee,
+                actual_resources: value.actual_resources.into(),
+                revert_error: value.revert_error,
+                r#type: value.r#type,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct InvokeTxReceipt {
+        pub fee: TxFeeInfo,
+        pub events: Vec<Event>,
+        pub messages_sent: Vec<MessageToL1>,
+        pub revert_error: Option<String>,
+        pub execution_resources: TxResources,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct DeclareTxReceipt {
+        pub fee: TxFeeInfo,
+        pub events: Vec<Event>,
+        pub messages_sent: Vec<MessageToL1>,
+        pub revert_error: Option<String>,
+        pub execution_resources: TxResources,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct L1HandlerTxReceipt {
+        pub fee: TxFeeInfo,
+        pub events: Vec<Event>,
+        pub message_hash: B256,
+        pub messages_sent: Vec<MessageToL1>,
+        pub revert_error: Option<String>,
+        pub execution_resources: TxResources,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct DeployAccountTxReceipt {
+        pub fee: TxFeeInfo,
+        pub events: Vec<Event>,
+        pub messages_sent: Vec<MessageToL1>,
+        pub revert_error: Option<String>,
+        pub execution_resources: TxResources,
+        pub contract_address: ContractAddress,
+    }
+
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum Receipt {
+        Invoke(InvokeTxReceipt),
+        Declare(DeclareTxReceipt),
+        L1Handler(L1HandlerTxReceipt),
+        DeployAccount(DeployAccountTxReceipt),
+    }
+
+    impl From<Receipt> for receipt::Receipt {
+        fn from(value: Receipt) -> Self {
+            match value {
+                Receipt::Invoke(r) => Self::Invoke(receipt::InvokeTxReceipt {
+                    fee: r.fee,
+                    events: r.events,
+                    messages_sent: r.messages_sent,
+                    revert_error: r.revert_error,
+                    execution_resources: r.execution_resources.into(),
+                }),
+                Receipt::Declare(r) => Self::Declare(receipt::DeclareTxReceipt {
+                    fee: r.fee,
+                    events: r.events,
+                    messages_sent: r.messages_sent,
+                    revert_error: r.revert_error,
+                    execution_resources: r.execution_resources.into(),
+                }),
+                Receipt::L1Handler(r) => Self::L1Handler(receipt::L1HandlerTxReceipt {
+                    fee: r.fee,
+                    events: r.events,
+                    message_hash: r.message_hash,
+                    messages_sent: r.messages_sent,
+                    revert_error: r.revert_error,
+                    execution_resources: r.execution_resources.into(),
+                }),
+                Receipt::DeployAccount(r) => Self::DeployAccount(receipt::DeployAccountTxReceipt {
+                    fee: r.fee,
+                    events: r.events,
+                    messages_sent: r.messages_sent,
+                    revert_error: r.revert_error,
+                    execution_resources: r.execution_resources.into(),
+                    contract_address: r.contract_address,
+                }),
+            }
+        }
+    }
+}
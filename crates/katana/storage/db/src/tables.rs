@@ -1,7 +1,7 @@
-use katana_primitives::block::{BlockHash, BlockNumber, FinalityStatus, Header};
+use katana_primitives::block::{BlockHash, BlockNumber, FinalityStatus, Header, HeaderExtension};
 use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash, FlattenedSierraClass};
 use katana_primitives::contract::{ContractAddress, GenericContractInfo, StorageKey};
-use katana_primitives::receipt::Receipt;
+use katana_primitives::receipt::{EventsBloom, Receipt};
 use katana_primitives::trace::TxExecInfo;
 use katana_primitives::transaction::{Tx, TxHash, TxNumber};
 
@@ -9,6 +9,7 @@ use crate::codecs::{Compress, Decode, Decompress, Encode};
 use crate::models::block::StoredBlockBodyIndices;
 use crate::models::contract::{ContractClassChange, ContractInfoChangeList, ContractNonceChange};
 use crate::models::list::BlockList;
+use crate::models::prune::PruneSegment;
 use crate::models::storage::{ContractStorageEntry, ContractStorageKey, StorageEntry};
 use crate::models::trie::{TrieDatabaseKey, TrieDatabaseValue};
 
@@ -47,7 +48,7 @@ pub enum TableType {
     DupSort,
 }
 
-pub const NUM_TABLES: usize = 26;
+pub const NUM_TABLES: usize = 29;
 
 /// Macro to declare `libmdbx` tables.
 #[macro_export]
@@ -66,7 +67,7 @@ macro_rules! define_tables_enum {
             pub const ALL: [Tables; NUM_TABLES] = [$(Tables::$table,)*];
 
             /// The name of the given table in database
-            pub const fn name(&self) -> &str {
+            pub const fn name(&self) -> &'static str {
                 match self {
                     $(Tables::$table => {
                         $table::NAME
@@ -173,7 +174,10 @@ define_tables_enum! {[
     (StorageChangeSet, TableType::Table),
     (ClassTrie, TableType::Table),
     (ContractTrie, TableType::Table),
-    (ContractStorageTrie, TableType::Table)
+    (ContractStorageTrie, TableType::Table),
+    (HeaderExtensions, TableType::Table),
+    (PruneCheckpoints, TableType::Table),
+    (EventsBlooms, TableType::Table)
 ]}
 
 tables! {
@@ -235,7 +239,16 @@ tables! {
     /// Contract trie
     ContractTrie: (TrieDatabaseKey) => TrieDatabaseValue,
     /// Contract storage trie
-    ContractStorageTrie: (TrieDatabaseKey) => TrieDatabaseValue
+    ContractStorageTrie: (TrieDatabaseKey) => TrieDatabaseValue,
+
+    /// Stores the operator-defined header extension of a block
+    HeaderExtensions: (BlockNumber) => HeaderExtension,
+
+    /// Stores the lowest block whose data is still available, for each pruned segment
+    PruneCheckpoints: (PruneSegment) => BlockNumber,
+
+    /// Stores the bloom filter of the events emitted in a block
+    EventsBlooms: (BlockNumber) => EventsBloom
 }
 
 impl Trie for ClassTrie {}
@@ -275,6 +288,10 @@ mod tests {
         assert_eq!(Tables::ALL[22].name(), StorageChangeSet::NAME);
         assert_eq!(Tables::ALL[23].name(), ClassTrie::NAME);
         assert_eq!(Tables::ALL[24].name(), ContractTrie::NAME);
+        assert_eq!(Tables::ALL[25].name(), ContractStorageTrie::NAME);
+        assert_eq!(Tables::ALL[26].name(), HeaderExtensions::NAME);
+        assert_eq!(Tables::ALL[27].name(), PruneCheckpoints::NAME);
+        assert_eq!(Tables::ALL[28].name(), EventsBlooms::NAME);
 
         assert_eq!(Tables::Headers.table_type(), TableType::Table);
         assert_eq!(Tables::BlockHashes.table_type(), TableType::Table);
@@ -301,14 +318,20 @@ mod tests {
         assert_eq!(Tables::StorageChangeSet.table_type(), TableType::Table);
         assert_eq!(Tables::ClassTrie.table_type(), TableType::Table);
         assert_eq!(Tables::ContractTrie.table_type(), TableType::Table);
+        assert_eq!(Tables::ContractStorageTrie.table_type(), TableType::Table);
+        assert_eq!(Tables::HeaderExtensions.table_type(), TableType::Table);
+        assert_eq!(Tables::PruneCheckpoints.table_type(), TableType::Table);
+        assert_eq!(Tables::EventsBlooms.table_type(), TableType::Table);
     }
 
     use katana_primitives::address;
-    use katana_primitives::block::{BlockHash, BlockNumber, FinalityStatus, Header};
+    use katana_primitives::block::{
+        BlockHash, BlockNumber, FinalityStatus, Header, HeaderExtension,
+    };
     use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash};
     use katana_primitives::contract::{ContractAddress, GenericContractInfo};
     use katana_primitives::fee::{PriceUnit, TxFeeInfo};
-    use katana_primitives::receipt::{InvokeTxReceipt, Receipt};
+    use katana_primitives::receipt::{EventsBloom, InvokeTxReceipt, Receipt};
     use katana_primitives::trace::TxExecInfo;
     use katana_primitives::transaction::{InvokeTx, Tx, TxHash, TxNumber};
     use starknet::macros::felt;
@@ -319,6 +342,7 @@ mod tests {
         ContractClassChange, ContractInfoChangeList, ContractNonceChange,
     };
     use crate::models::list::BlockList;
+    use crate::models::prune::PruneSegment;
     use crate::models::storage::{ContractStorageEntry, ContractStorageKey, StorageEntry};
 
     macro_rules! assert_key_encode_decode {
@@ -358,7 +382,8 @@ mod tests {
             (TxNumber, 100),
             (ClassHash, felt!("0x123456789")),
             (ContractAddress, address!("0x123456789")),
-            (ContractStorageKey, ContractStorageKey { contract_address : address!("0x123456789"), key : felt!("0x123456789")})
+            (ContractStorageKey, ContractStorageKey { contract_address : address!("0x123456789"), key : felt!("0x123456789")}),
+            (PruneSegment, PruneSegment::History)
         }
     }
 
@@ -368,6 +393,8 @@ mod tests {
     fn test_value_compress_decompress() {
         assert_value_compress_decompress! {
             (Header, Header::default()),
+            (HeaderExtension, HeaderExtension::default()),
+            (EventsBloom, EventsBloom([0xab; 256])),
             (BlockHash, BlockHash::default()),
             (BlockNumber, BlockNumber::default()),
             (FinalityStatus, FinalityStatus::AcceptedOnL1),
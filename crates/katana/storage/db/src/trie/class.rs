@@ -17,12 +17,12 @@ use crate::trie::TrieDb;
 const CONTRACT_CLASS_LEAF_V0: Felt = short_string!("CONTRACT_CLASS_LEAF_V0");
 
 #[derive(Debug)]
-pub struct ClassTrie<Tx: DbTxMut> {
-    inner: BonsaiStorage<BasicId, TrieDb<tables::ClassTrie, Tx>, Poseidon>,
+pub struct ClassTrie<'tx, Tx: DbTxMut> {
+    inner: BonsaiStorage<BasicId, TrieDb<'tx, tables::ClassTrie, Tx>, Poseidon>,
 }
 
-impl<Tx: DbTxMut> ClassTrie<Tx> {
-    pub fn new(tx: Tx) -> Self {
+impl<'tx, Tx: DbTxMut> ClassTrie<'tx, Tx> {
+    pub fn new(tx: &'tx Tx) -> Self {
         let config = BonsaiStorageConfig {
             max_saved_trie_logs: Some(0),
             max_saved_snapshots: Some(0),
@@ -41,6 +41,11 @@ impl<Tx: DbTxMut> ClassTrie<Tx> {
         self.inner.insert(self.bonsai_identifier(), &key, &value).unwrap();
     }
 
+    pub fn remove(&mut self, hash: ClassHash) {
+        let key: BitVec<u8, Msb0> = hash.to_bytes_be().as_bits()[5..].to_owned();
+        self.inner.remove(self.bonsai_identifier(), &key).unwrap();
+    }
+
     pub fn commit(&mut self, block_number: BlockNumber) {
         self.inner.commit(BasicId::new(block_number)).unwrap();
     }
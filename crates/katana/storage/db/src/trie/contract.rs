@@ -13,12 +13,12 @@ use crate::tables;
 use crate::trie::TrieDb;
 
 #[derive(Debug)]
-pub struct StorageTrie<Tx: DbTxMut> {
-    inner: BonsaiStorage<BasicId, TrieDb<tables::ContractStorageTrie, Tx>, Poseidon>,
+pub struct StorageTrie<'tx, Tx: DbTxMut> {
+    inner: BonsaiStorage<BasicId, TrieDb<'tx, tables::ContractStorageTrie, Tx>, Poseidon>,
 }
 
-impl<Tx: DbTxMut> StorageTrie<Tx> {
-    pub fn new(tx: Tx) -> Self {
+impl<'tx, Tx: DbTxMut> StorageTrie<'tx, Tx> {
+    pub fn new(tx: &'tx Tx) -> Self {
         let config = BonsaiStorageConfig {
             max_saved_trie_logs: Some(0),
             max_saved_snapshots: Some(0),
@@ -46,12 +46,12 @@ impl<Tx: DbTxMut> StorageTrie<Tx> {
 }
 
 #[derive(Debug)]
-pub struct ContractTrie<Tx: DbTxMut> {
-    inner: BonsaiStorage<BasicId, TrieDb<tables::ContractTrie, Tx>, Poseidon>,
+pub struct ContractTrie<'tx, Tx: DbTxMut> {
+    inner: BonsaiStorage<BasicId, TrieDb<'tx, tables::ContractTrie, Tx>, Poseidon>,
 }
 
-impl<Tx: DbTxMut> ContractTrie<Tx> {
-    pub fn new(tx: Tx) -> Self {
+impl<'tx, Tx: DbTxMut> ContractTrie<'tx, Tx> {
+    pub fn new(tx: &'tx Tx) -> Self {
         let config = BonsaiStorageConfig {
             max_saved_trie_logs: Some(0),
             max_saved_snapshots: Some(0),
@@ -69,6 +69,11 @@ impl<Tx: DbTxMut> ContractTrie<Tx> {
         self.inner.insert(self.bonsai_identifier(), &key, &state_hash).unwrap();
     }
 
+    pub fn remove(&mut self, address: ContractAddress) {
+        let key: BitVec<u8, Msb0> = address.to_bytes_be().as_bits()[5..].to_owned();
+        self.inner.remove(self.bonsai_identifier(), &key).unwrap();
+    }
+
     pub fn commit(&mut self, block_number: BlockNumber) {
         self.inner.commit(BasicId::new(block_number)).unwrap();
     }
@@ -22,23 +22,27 @@ pub struct Error(#[from] crate::error::DatabaseError);
 
 impl katana_trie::bonsai::DBError for Error {}
 
+/// A trie table, read and written through a database transaction.
+///
+/// The transaction is borrowed, so that the changes of the tries are committed along with the
+/// other changes made with it.
 #[derive(Debug)]
-pub struct TrieDb<Tb: tables::Trie, Tx: DbTxMut> {
-    tx: Tx,
+pub struct TrieDb<'tx, Tb: tables::Trie, Tx: DbTxMut> {
+    tx: &'tx Tx,
     _table: PhantomData<Tb>,
 }
 
-impl<Tb, Tx> TrieDb<Tb, Tx>
+impl<'tx, Tb, Tx> TrieDb<'tx, Tb, Tx>
 where
     Tb: tables::Trie,
     Tx: DbTxMut,
 {
-    pub fn new(tx: Tx) -> Self {
+    pub fn new(tx: &'tx Tx) -> Self {
         Self { tx, _table: PhantomData }
     }
 }
 
-impl<Tb, Tx> bonsai::BonsaiDatabase for TrieDb<Tb, Tx>
+impl<Tb, Tx> bonsai::BonsaiDatabase for TrieDb<'_, Tb, Tx>
 where
     Tb: tables::Trie,
     Tx: DbTxMut,
@@ -116,13 +120,13 @@ where
     }
 }
 
-impl<Tb, Tx> bonsai::BonsaiPersistentDatabase<BasicId> for TrieDb<Tb, Tx>
+impl<'tx, Tb, Tx> bonsai::BonsaiPersistentDatabase<BasicId> for TrieDb<'tx, Tb, Tx>
 where
     Tb: tables::Trie,
     Tx: DbTxMut,
 {
     type DatabaseError = Error;
-    type Transaction = TrieDb<Tb, Tx>;
+    type Transaction = TrieDb<'tx, Tb, Tx>;
 
     fn snapshot(&mut self, _: BasicId) {}
 
@@ -5,7 +5,7 @@ use std::mem;
 use std::path::{Path, PathBuf};
 
 /// Current version of the database.
-pub const CURRENT_DB_VERSION: u32 = 4;
+pub const CURRENT_DB_VERSION: u32 = 9;
 
 /// Name of the version file.
 const DB_VERSION_FILE_NAME: &str = "db.version";
@@ -81,6 +81,6 @@ mod tests {
     #[test]
     fn test_current_version() {
         use super::CURRENT_DB_VERSION;
-        assert_eq!(CURRENT_DB_VERSION, 4, "Invalid current database version")
+        assert_eq!(CURRENT_DB_VERSION, 9, "Invalid current database version")
     }
 }
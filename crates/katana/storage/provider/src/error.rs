@@ -96,6 +96,14 @@ pub enum ProviderError {
         storage_key: StorageKey,
     },
 
+    /// Error when the historical state of a block has been pruned from the database.
+    #[error("State of block {0} has been pruned")]
+    PrunedState(BlockNumber),
+
+    /// Error when the receipt and trace of a transaction have been pruned from the database.
+    #[error("Receipt and trace of transaction {0} have been pruned")]
+    PrunedTxOutput(TxNumber),
+
     /// Error returned by the database implementation.
     #[error(transparent)]
     Database(#[from] DatabaseError),
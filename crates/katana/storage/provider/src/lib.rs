@@ -1,24 +1,31 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap};
 use std::ops::{Range, RangeInclusive};
 
 use katana_db::models::block::StoredBlockBodyIndices;
 use katana_primitives::block::{
     Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithTxHashes, FinalityStatus, Header,
-    SealedBlockWithStatus,
+    HeaderExtension, SealedBlockWithStatus,
 };
 use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash, FlattenedSierraClass};
 use katana_primitives::contract::{ContractAddress, StorageKey, StorageValue};
 use katana_primitives::env::BlockEnv;
-use katana_primitives::receipt::Receipt;
+use katana_primitives::receipt::{EventsBloom, Receipt};
 use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
 use katana_primitives::trace::TxExecInfo;
 use katana_primitives::transaction::{TxHash, TxNumber, TxWithHash};
 use katana_primitives::Felt;
-use traits::block::{BlockIdReader, BlockStatusProvider, BlockWriter};
+use parking_lot::RwLock;
+use traits::block::{
+    BlockIdReader, BlockStatusProvider, BlockUnwinder, BlockWriter, HeaderExtensionProvider,
+    HeaderExtensionWriter,
+};
+use traits::checkpoint::{CheckpointId, CheckpointProvider};
 use traits::contract::{ContractClassProvider, ContractClassWriter};
 use traits::env::BlockEnvProvider;
-use traits::state::{StateRootProvider, StateWriter};
-use traits::transaction::{TransactionStatusProvider, TransactionTraceProvider};
+use traits::state::{ContractStorageProvider, StateRootProvider, StateWriter};
+use traits::transaction::{
+    EventsBloomProvider, TransactionStatusProvider, TransactionTraceProvider,
+};
 use traits::trie::{ClassTrieWriter, ContractTrieWriter};
 
 pub mod error;
@@ -40,14 +47,66 @@ pub type ProviderResult<T> = Result<T, error::ProviderError>;
 ///
 /// Serves as the main entrypoint for interacting with the storage storage. Every read/write
 /// operation is done through this provider.
+///
+/// The hashes and numbers of the blocks are indexed in memory, as they are looked up for almost
+/// every request referring to a block.
 #[derive(Debug)]
 pub struct BlockchainProvider<Db> {
     provider: Db,
+    block_ids: RwLock<BlockIdIndex>,
 }
 
 impl<Db> BlockchainProvider<Db> {
     pub fn new(provider: Db) -> Self {
-        Self { provider }
+        Self { provider, block_ids: Default::default() }
+    }
+}
+
+/// The hash of a block by its number and the other way around.
+///
+/// It's filled when blocks are inserted and when they are looked up in the underlying provider, and
+/// cleared when blocks are removed, by unwinding the chain or reverting it to a checkpoint.
+#[derive(Debug, Default)]
+struct BlockIdIndex {
+    hashes: HashMap<BlockNumber, BlockHash>,
+    numbers: HashMap<BlockHash, BlockNumber>,
+}
+
+impl BlockIdIndex {
+    fn insert(&mut self, number: BlockNumber, hash: BlockHash) {
+        self.hashes.insert(number, hash);
+        self.numbers.insert(hash, number);
+    }
+}
+
+impl<Db> CheckpointProvider for BlockchainProvider<Db>
+where
+    Db: CheckpointProvider,
+{
+    fn checkpoint(&self) -> ProviderResult<CheckpointId> {
+        self.provider.checkpoint()
+    }
+
+    fn revert_to_checkpoint(&self, id: CheckpointId) -> ProviderResult<bool> {
+        // hold the index while reverting, so it isn't filled with the blocks being removed
+        let mut block_ids = self.block_ids.write();
+        let reverted = self.provider.revert_to_checkpoint(id)?;
+        if reverted {
+            *block_ids = BlockIdIndex::default();
+        }
+        Ok(reverted)
+    }
+}
+
+impl<Db> BlockUnwinder for BlockchainProvider<Db>
+where
+    Db: BlockUnwinder,
+{
+    fn unwind_to(&self, number: BlockNumber) -> ProviderResult<()> {
+        let mut block_ids = self.block_ids.write();
+        self.provider.unwind_to(number)?;
+        *block_ids = BlockIdIndex::default();
+        Ok(())
     }
 }
 
@@ -96,7 +155,16 @@ where
     }
 
     fn block_number_by_hash(&self, hash: BlockHash) -> ProviderResult<Option<BlockNumber>> {
-        self.provider.block_number_by_hash(hash)
+        if let Some(number) = self.block_ids.read().numbers.get(&hash) {
+            return Ok(Some(*number));
+        }
+
+        let number = self.provider.block_number_by_hash(hash)?;
+        if let Some(number) = number {
+            self.block_ids.write().insert(number, hash);
+        }
+
+        Ok(number)
     }
 }
 
@@ -109,7 +177,16 @@ where
     }
 
     fn block_hash_by_num(&self, num: BlockNumber) -> ProviderResult<Option<BlockHash>> {
-        self.provider.block_hash_by_num(num)
+        if let Some(hash) = self.block_ids.read().hashes.get(&num) {
+            return Ok(Some(*hash));
+        }
+
+        let hash = self.provider.block_hash_by_num(num)?;
+        if let Some(hash) = hash {
+            self.block_ids.write().insert(num, hash);
+        }
+
+        Ok(hash)
     }
 }
 
@@ -124,18 +201,45 @@ where
     }
 }
 
+impl<Db> HeaderExtensionProvider for BlockchainProvider<Db>
+where
+    Db: HeaderExtensionProvider,
+{
+    fn header_extension(&self, id: BlockHashOrNumber) -> ProviderResult<Option<HeaderExtension>> {
+        self.provider.header_extension(id)
+    }
+}
+
+impl<Db> HeaderExtensionWriter for BlockchainProvider<Db>
+where
+    Db: HeaderExtensionWriter,
+{
+    fn insert_header_extension(
+        &self,
+        block_number: BlockNumber,
+        extension: HeaderExtension,
+    ) -> ProviderResult<()> {
+        self.provider.insert_header_extension(block_number, extension)
+    }
+}
+
 impl<Db> BlockWriter for BlockchainProvider<Db>
 where
     Db: BlockWriter,
 {
-    fn insert_block_with_states_and_receipts(
+    fn insert_block_with_header_extension(
         &self,
         block: SealedBlockWithStatus,
         states: StateUpdatesWithDeclaredClasses,
         receipts: Vec<Receipt>,
         executions: Vec<TxExecInfo>,
+        extension: Option<HeaderExtension>,
     ) -> ProviderResult<()> {
-        self.provider.insert_block_with_states_and_receipts(block, states, receipts, executions)
+        let (number, hash) = (block.block.header.number, block.block.hash);
+        self.provider
+            .insert_block_with_header_extension(block, states, receipts, executions, extension)?;
+        self.block_ids.write().insert(number, hash);
+        Ok(())
     }
 }
 
@@ -220,6 +324,13 @@ where
     fn transaction_hashes_in_range(&self, range: Range<TxNumber>) -> ProviderResult<Vec<TxHash>> {
         TransactionsProviderExt::transaction_hashes_in_range(&self.provider, range)
     }
+
+    fn transaction_hashes_by_block(
+        &self,
+        block_id: BlockHashOrNumber,
+    ) -> ProviderResult<Option<Vec<TxHash>>> {
+        TransactionsProviderExt::transaction_hashes_by_block(&self.provider, block_id)
+    }
 }
 
 impl<Db> ReceiptProvider for BlockchainProvider<Db>
@@ -236,6 +347,19 @@ where
     ) -> ProviderResult<Option<Vec<Receipt>>> {
         self.provider.receipts_by_block(block_id)
     }
+
+    fn receipts_pruned_below(&self) -> ProviderResult<BlockNumber> {
+        self.provider.receipts_pruned_below()
+    }
+}
+
+impl<Db> EventsBloomProvider for BlockchainProvider<Db>
+where
+    Db: EventsBloomProvider,
+{
+    fn events_bloom(&self, block_id: BlockHashOrNumber) -> ProviderResult<Option<EventsBloom>> {
+        self.provider.events_bloom(block_id)
+    }
 }
 
 impl<Db> StateProvider for BlockchainProvider<Db>
@@ -374,6 +498,18 @@ where
     }
 }
 
+impl<Db> ContractStorageProvider for BlockchainProvider<Db>
+where
+    Db: ContractStorageProvider,
+{
+    fn storage_entries(
+        &self,
+        address: ContractAddress,
+    ) -> ProviderResult<BTreeMap<StorageKey, StorageValue>> {
+        self.provider.storage_entries(address)
+    }
+}
+
 impl<Db> BlockEnvProvider for BlockchainProvider<Db>
 where
     Db: BlockEnvProvider,
@@ -1,13 +1,15 @@
+mod prune;
 pub mod state;
 pub mod trie;
+mod unwind;
 
 use std::collections::BTreeMap;
 use std::fmt::Debug;
 use std::ops::{Range, RangeInclusive};
+use std::path::Path;
 
 use katana_db::abstraction::{Database, DbCursor, DbCursorMut, DbDupSortCursor, DbTx, DbTxMut};
 use katana_db::error::DatabaseError;
-use katana_db::init_ephemeral_db;
 use katana_db::mdbx::DbEnv;
 use katana_db::models::block::StoredBlockBodyIndices;
 use katana_db::models::contract::{
@@ -17,16 +19,17 @@ use katana_db::models::list::BlockList;
 use katana_db::models::storage::{ContractStorageEntry, ContractStorageKey, StorageEntry};
 use katana_db::tables::{self, DupSort, Table};
 use katana_db::utils::KeyValue;
+use katana_db::{init_ephemeral_db, open_db_ro};
 use katana_primitives::block::{
     Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithTxHashes, FinalityStatus, Header,
-    SealedBlockWithStatus,
+    HeaderExtension, SealedBlockWithStatus,
 };
 use katana_primitives::class::{ClassHash, CompiledClassHash};
 use katana_primitives::contract::{
     ContractAddress, GenericContractInfo, Nonce, StorageKey, StorageValue,
 };
 use katana_primitives::env::BlockEnv;
-use katana_primitives::receipt::Receipt;
+use katana_primitives::receipt::{EventsBloom, Receipt};
 use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
 use katana_primitives::trace::TxExecInfo;
 use katana_primitives::transaction::{TxHash, TxNumber, TxWithHash};
@@ -35,14 +38,15 @@ use katana_primitives::Felt;
 use crate::error::ProviderError;
 use crate::traits::block::{
     BlockHashProvider, BlockNumberProvider, BlockProvider, BlockStatusProvider, BlockWriter,
-    HeaderProvider,
+    HeaderExtensionProvider, HeaderExtensionWriter, HeaderProvider,
 };
+use crate::traits::checkpoint::{CheckpointId, CheckpointProvider};
 use crate::traits::env::BlockEnvProvider;
 use crate::traits::state::{StateFactoryProvider, StateProvider, StateRootProvider};
 use crate::traits::state_update::StateUpdateProvider;
 use crate::traits::transaction::{
-    ReceiptProvider, TransactionProvider, TransactionStatusProvider, TransactionTraceProvider,
-    TransactionsProviderExt,
+    EventsBloomProvider, ReceiptProvider, TransactionProvider, TransactionStatusProvider,
+    TransactionTraceProvider, TransactionsProviderExt,
 };
 use crate::ProviderResult;
 
@@ -64,6 +68,25 @@ impl DbProvider<DbEnv> {
         let db = init_ephemeral_db().expect("Failed to initialize ephemeral database");
         Self(db)
     }
+
+    /// Creates a new [`DbProvider`] over the existing database at `path`, opened in read-only
+    /// mode, eg. to read the chain of a running node without a sequencer.
+    ///
+    /// The reads see the blocks committed by the node until then. The writes fail.
+    pub fn open_read_only(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        Ok(Self(open_db_ro(path)?))
+    }
+}
+
+/// The checkpoints are snapshots of the database, see [`DbEnv::snapshot`].
+impl CheckpointProvider for DbProvider<DbEnv> {
+    fn checkpoint(&self) -> ProviderResult<CheckpointId> {
+        Ok(self.0.snapshot()?)
+    }
+
+    fn revert_to_checkpoint(&self, id: CheckpointId) -> ProviderResult<bool> {
+        Ok(self.0.revert_to_snapshot(id)?)
+    }
 }
 
 impl<Db: Database> StateFactoryProvider for DbProvider<Db> {
@@ -91,6 +114,10 @@ impl<Db: Database> StateFactoryProvider for DbProvider<Db> {
 
         let Some(num) = block_number else { return Ok(None) };
 
+        if num < self.history_pruned_below()? {
+            return Err(ProviderError::PrunedState(num));
+        }
+
         Ok(Some(Box::new(self::state::HistoricalStateProvider::new(self.0.tx()?, num))))
     }
 }
@@ -201,7 +228,7 @@ impl<Db: Database> BlockProvider for DbProvider<Db> {
             let res = db_tx.get::<tables::BlockBodyIndices>(block_num)?;
             let body_indices = res.ok_or(ProviderError::MissingBlockTxs(block_num))?;
 
-            let body = self.transaction_hashes_in_range(Range::from(body_indices))?;
+            let body = tx_hashes_in_range(&db_tx, Range::from(body_indices))?;
             let block = BlockWithTxHashes { header, body };
 
             db_tx.commit()?;
@@ -233,6 +260,38 @@ impl<Db: Database> BlockProvider for DbProvider<Db> {
     }
 }
 
+impl<Db: Database> HeaderExtensionProvider for DbProvider<Db> {
+    fn header_extension(&self, id: BlockHashOrNumber) -> ProviderResult<Option<HeaderExtension>> {
+        let db_tx = self.0.tx()?;
+
+        let num = match id {
+            BlockHashOrNumber::Num(num) => Some(num),
+            BlockHashOrNumber::Hash(hash) => db_tx.get::<tables::BlockNumbers>(hash)?,
+        };
+
+        let extension = match num {
+            Some(num) => db_tx.get::<tables::HeaderExtensions>(num)?,
+            None => None,
+        };
+
+        db_tx.commit()?;
+        Ok(extension)
+    }
+}
+
+impl<Db: Database> HeaderExtensionWriter for DbProvider<Db> {
+    fn insert_header_extension(
+        &self,
+        block_number: BlockNumber,
+        extension: HeaderExtension,
+    ) -> ProviderResult<()> {
+        self.0.update(move |db_tx| -> ProviderResult<()> {
+            db_tx.put::<tables::HeaderExtensions>(block_number, extension)?;
+            Ok(())
+        })?
+    }
+}
+
 impl<Db: Database> BlockStatusProvider for DbProvider<Db> {
     fn block_status(&self, id: BlockHashOrNumber) -> ProviderResult<Option<FinalityStatus>> {
         let db_tx = self.0.tx()?;
@@ -476,21 +535,52 @@ impl<Db: Database> TransactionProvider for DbProvider<Db> {
 impl<Db: Database> TransactionsProviderExt for DbProvider<Db> {
     fn transaction_hashes_in_range(&self, range: Range<TxNumber>) -> ProviderResult<Vec<TxHash>> {
         let db_tx = self.0.tx()?;
+        let hashes = tx_hashes_in_range(&db_tx, range)?;
+        db_tx.commit()?;
+        Ok(hashes)
+    }
 
-        let total = range.end - range.start;
-        let mut hashes = Vec::with_capacity(total as usize);
+    fn transaction_hashes_by_block(
+        &self,
+        block_id: BlockHashOrNumber,
+    ) -> ProviderResult<Option<Vec<TxHash>>> {
+        let db_tx = self.0.tx()?;
 
-        for i in range {
-            if let Some(hash) = db_tx.get::<tables::TxHashes>(i)? {
-                hashes.push(hash);
-            }
-        }
+        let block_num = match block_id {
+            BlockHashOrNumber::Num(num) => Some(num),
+            BlockHashOrNumber::Hash(hash) => db_tx.get::<tables::BlockNumbers>(hash)?,
+        };
+
+        let indices = match block_num {
+            Some(num) => db_tx.get::<tables::BlockBodyIndices>(num)?,
+            None => None,
+        };
+
+        let hashes = match indices {
+            Some(indices) => Some(tx_hashes_in_range(&db_tx, Range::from(indices))?),
+            None => None,
+        };
 
         db_tx.commit()?;
         Ok(hashes)
     }
 }
 
+/// Reads the hashes of the transactions in `range`, which only decodes the hashes and not the
+/// transactions themselves.
+fn tx_hashes_in_range<Tx: DbTx>(db_tx: &Tx, range: Range<TxNumber>) -> ProviderResult<Vec<TxHash>> {
+    let total = range.end - range.start;
+    let mut hashes = Vec::with_capacity(total as usize);
+
+    for i in range {
+        if let Some(hash) = db_tx.get::<tables::TxHashes>(i)? {
+            hashes.push(hash);
+        }
+    }
+
+    Ok(hashes)
+}
+
 impl<Db: Database> TransactionStatusProvider for DbProvider<Db> {
     fn transaction_status(&self, hash: TxHash) -> ProviderResult<Option<FinalityStatus>> {
         let db_tx = self.0.tx()?;
@@ -513,9 +603,9 @@ impl<Db: Database> TransactionTraceProvider for DbProvider<Db> {
     fn transaction_execution(&self, hash: TxHash) -> ProviderResult<Option<TxExecInfo>> {
         let db_tx = self.0.tx()?;
         if let Some(num) = db_tx.get::<tables::TxNumbers>(hash)? {
-            let execution = db_tx
-                .get::<tables::TxTraces>(num)?
-                .ok_or(ProviderError::MissingTxExecution(num))?;
+            let execution = db_tx.get::<tables::TxTraces>(num)?.ok_or_else(|| {
+                prune::missing_tx_output(&db_tx, num, ProviderError::MissingTxExecution)
+            })?;
 
             db_tx.commit()?;
             Ok(Some(execution))
@@ -546,9 +636,10 @@ impl<Db: Database> TransactionTraceProvider for DbProvider<Db> {
         let mut traces = Vec::with_capacity(total as usize);
 
         for i in range {
-            if let Some(trace) = db_tx.get::<tables::TxTraces>(i)? {
-                traces.push(trace);
-            }
+            let trace = db_tx.get::<tables::TxTraces>(i)?.ok_or_else(|| {
+                prune::missing_tx_output(&db_tx, i, ProviderError::MissingTxExecution)
+            })?;
+            traces.push(trace);
         }
 
         db_tx.commit()?;
@@ -560,8 +651,9 @@ impl<Db: Database> ReceiptProvider for DbProvider<Db> {
     fn receipt_by_hash(&self, hash: TxHash) -> ProviderResult<Option<Receipt>> {
         let db_tx = self.0.tx()?;
         if let Some(num) = db_tx.get::<tables::TxNumbers>(hash)? {
-            let receipt =
-                db_tx.get::<tables::Receipts>(num)?.ok_or(ProviderError::MissingTxReceipt(num))?;
+            let receipt = db_tx.get::<tables::Receipts>(num)?.ok_or_else(|| {
+                prune::missing_tx_output(&db_tx, num, ProviderError::MissingTxReceipt)
+            })?;
 
             db_tx.commit()?;
             Ok(Some(receipt))
@@ -580,9 +672,10 @@ impl<Db: Database> ReceiptProvider for DbProvider<Db> {
 
             let range = indices.tx_offset..indices.tx_offset + indices.tx_count;
             for i in range {
-                if let Some(receipt) = db_tx.get::<tables::Receipts>(i)? {
-                    receipts.push(receipt);
-                }
+                let receipt = db_tx.get::<tables::Receipts>(i)?.ok_or_else(|| {
+                    prune::missing_tx_output(&db_tx, i, ProviderError::MissingTxReceipt)
+                })?;
+                receipts.push(receipt);
             }
 
             db_tx.commit()?;
@@ -591,6 +684,29 @@ impl<Db: Database> ReceiptProvider for DbProvider<Db> {
             Ok(None)
         }
     }
+
+    fn receipts_pruned_below(&self) -> ProviderResult<BlockNumber> {
+        self.history_pruned_below()
+    }
+}
+
+impl<Db: Database> EventsBloomProvider for DbProvider<Db> {
+    fn events_bloom(&self, block_id: BlockHashOrNumber) -> ProviderResult<Option<EventsBloom>> {
+        let db_tx = self.0.tx()?;
+
+        let num = match block_id {
+            BlockHashOrNumber::Num(num) => Some(num),
+            BlockHashOrNumber::Hash(hash) => db_tx.get::<tables::BlockNumbers>(hash)?,
+        };
+
+        let bloom = match num {
+            Some(num) => db_tx.get::<tables::EventsBlooms>(num)?,
+            None => None,
+        };
+
+        db_tx.commit()?;
+        Ok(bloom)
+    }
 }
 
 impl<Db: Database> BlockEnvProvider for DbProvider<Db> {
@@ -608,17 +724,22 @@ impl<Db: Database> BlockEnvProvider for DbProvider<Db> {
 }
 
 impl<Db: Database> BlockWriter for DbProvider<Db> {
-    fn insert_block_with_states_and_receipts(
+    fn insert_block_with_header_extension(
         &self,
         block: SealedBlockWithStatus,
         states: StateUpdatesWithDeclaredClasses,
         receipts: Vec<Receipt>,
         executions: Vec<TxExecInfo>,
+        extension: Option<HeaderExtension>,
     ) -> ProviderResult<()> {
         self.0.update(move |db_tx| -> ProviderResult<()> {
             let block_hash = block.block.hash;
             let block_number = block.block.header.number;
 
+            if let Some(extension) = extension {
+                db_tx.put::<tables::HeaderExtensions>(block_number, extension)?;
+            }
+
             let block_header = block.block.header;
             let transactions = block.block.body;
 
@@ -633,6 +754,9 @@ impl<Db: Database> BlockWriter for DbProvider<Db> {
             db_tx.put::<tables::Headers>(block_number, block_header)?;
             db_tx.put::<tables::BlockBodyIndices>(block_number, block_body_indices)?;
 
+            let bloom = EventsBloom::from_receipts(&receipts);
+            db_tx.put::<tables::EventsBlooms>(block_number, bloom)?;
+
             for (i, (transaction, receipt, execution)) in transactions
                 .into_iter()
                 .zip(receipts.into_iter())
@@ -792,11 +916,12 @@ mod tests {
     use starknet::macros::felt;
 
     use super::DbProvider;
+    use crate::error::ProviderError;
     use crate::traits::block::{
         BlockHashProvider, BlockNumberProvider, BlockProvider, BlockStatusProvider, BlockWriter,
     };
     use crate::traits::state::StateFactoryProvider;
-    use crate::traits::transaction::TransactionProvider;
+    use crate::traits::transaction::{ReceiptProvider, TransactionProvider};
 
     fn create_dummy_block() -> SealedBlockWithStatus {
         let header = Header { parent_hash: 199u8.into(), number: 0, ..Default::default() };
@@ -1018,4 +1143,75 @@ mod tests {
         assert_eq!(storage1, felt!("100"));
         assert_eq!(storage2, felt!("200"));
     }
+
+    #[test]
+    fn prune_history() {
+        let provider = create_db_provider();
+
+        let states = [
+            create_dummy_state_updates(),
+            create_dummy_state_updates_2(),
+            StateUpdatesWithDeclaredClasses::default(),
+        ];
+
+        for (number, states) in states.into_iter().enumerate() {
+            let header = Header { number: number as u64, ..Default::default() };
+            let body = vec![TxWithHash {
+                hash: (number as u64 + 1).into(),
+                transaction: Tx::Invoke(InvokeTx::V1(Default::default())),
+            }];
+            let block = Block { header, body }.seal();
+            let block = SealedBlockWithStatus { block, status: FinalityStatus::AcceptedOnL2 };
+
+            let receipt = Receipt::Invoke(InvokeTxReceipt {
+                revert_error: None,
+                events: Vec::new(),
+                messages_sent: Vec::new(),
+                execution_resources: Default::default(),
+                fee: TxFeeInfo {
+                    gas_consumed: 0,
+                    gas_price: 0,
+                    overall_fee: 0,
+                    unit: PriceUnit::Wei,
+                },
+            });
+
+            BlockWriter::insert_block_with_states_and_receipts(
+                &provider,
+                block,
+                states,
+                vec![receipt],
+                vec![TxExecInfo::default()],
+            )
+            .expect("failed to insert block");
+        }
+
+        // only block 0 is older than the last 2 blocks
+        assert_eq!(provider.prune_history(2).unwrap(), 1);
+        assert_eq!(provider.history_pruned_below().unwrap(), 1);
+
+        let result = provider.historical(0.into());
+        assert!(matches!(result, Err(ProviderError::PrunedState(0))));
+
+        let state_prov = provider.historical(1.into()).unwrap().unwrap();
+        assert_eq!(state_prov.nonce(address!("1")).unwrap(), Some(felt!("5")));
+        assert_eq!(state_prov.storage(address!("1"), felt!("1")).unwrap(), Some(felt!("100")));
+        assert_eq!(state_prov.class_hash_of_contract(address!("2")).unwrap(), Some(felt!("66")));
+
+        // the pruned block is still available, but not the receipt of its transaction
+        assert!(provider.block(0.into()).unwrap().is_some());
+        assert!(provider.transaction_by_hash(1u64.into()).unwrap().is_some());
+        let result = provider.receipt_by_hash(1u64.into());
+        assert!(matches!(result, Err(ProviderError::PrunedTxOutput(0))));
+        let result = provider.receipts_by_block(0.into());
+        assert!(matches!(result, Err(ProviderError::PrunedTxOutput(0))));
+        let result = provider.transaction_executions_by_block(0.into());
+        assert!(matches!(result, Err(ProviderError::PrunedTxOutput(0))));
+        assert!(provider.receipt_by_hash(2u64.into()).unwrap().is_some());
+        assert_eq!(provider.receipts_by_block(1.into()).unwrap().map(|r| r.len()), Some(1));
+        assert_eq!(provider.receipts_pruned_below().unwrap(), 1);
+
+        // pruning again with the same number of retained blocks is a no-op
+        assert_eq!(provider.prune_history(2).unwrap(), 1);
+    }
 }
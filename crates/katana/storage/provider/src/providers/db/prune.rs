@@ -0,0 +1,155 @@
+use std::ops::Range;
+
+use katana_db::abstraction::{Database, DbCursor, DbTx, DbTxMut};
+use katana_db::models::list::BlockList;
+use katana_db::models::prune::PruneSegment;
+use katana_db::tables::{self, Table};
+use katana_primitives::block::BlockNumber;
+use katana_primitives::transaction::TxNumber;
+
+use super::DbProvider;
+use crate::error::ProviderError;
+use crate::traits::block::BlockNumberProvider;
+use crate::ProviderResult;
+
+impl<Db: Database> DbProvider<Db> {
+    /// Returns the lowest block whose historical state, receipts and traces are still available.
+    pub fn history_pruned_below(&self) -> ProviderResult<BlockNumber> {
+        let db_tx = self.0.tx()?;
+        let checkpoint = history_checkpoint(&db_tx)?;
+        db_tx.commit()?;
+        Ok(checkpoint)
+    }
+
+    /// Prunes the historical data of all the blocks but the last `retained` ones.
+    ///
+    /// Only the state history, receipts and traces of the pruned blocks are removed. Their headers
+    /// and transactions are kept, and so is the latest state. Returns the lowest block whose
+    /// historical data is still available.
+    pub fn prune_history(&self, retained: u64) -> ProviderResult<BlockNumber> {
+        let latest = self.latest_number()?;
+        let target = (latest + 1).saturating_sub(retained.max(1));
+
+        let db_tx = self.0.tx_mut()?;
+        let checkpoint = history_checkpoint(&db_tx)?;
+
+        if target <= checkpoint {
+            db_tx.abort();
+            return Ok(checkpoint);
+        }
+
+        let range = checkpoint..target;
+
+        // A change can only be removed once a more recent change of the same entry has been made at
+        // or before the target block, otherwise it's still needed to read the state of the
+        // retained blocks.
+
+        for (block, change) in changes_in_range::<_, tables::NonceChangeHistory>(&db_tx, &range)? {
+            let address = change.contract_address;
+            let Some(mut change_set) = db_tx.get::<tables::ContractInfoChangeSet>(address)? else {
+                continue;
+            };
+
+            if is_superseded(&change_set.nonce_change_list, block, target) {
+                change_set.nonce_change_list.remove(block);
+                db_tx.delete::<tables::NonceChangeHistory>(block, Some(change))?;
+                db_tx.put::<tables::ContractInfoChangeSet>(address, change_set)?;
+            }
+        }
+
+        for (block, change) in changes_in_range::<_, tables::ClassChangeHistory>(&db_tx, &range)? {
+            let address = change.contract_address;
+            let Some(mut change_set) = db_tx.get::<tables::ContractInfoChangeSet>(address)? else {
+                continue;
+            };
+
+            if is_superseded(&change_set.class_change_list, block, target) {
+                change_set.class_change_list.remove(block);
+                db_tx.delete::<tables::ClassChangeHistory>(block, Some(change))?;
+                db_tx.put::<tables::ContractInfoChangeSet>(address, change_set)?;
+            }
+        }
+
+        for (block, entry) in changes_in_range::<_, tables::StorageChangeHistory>(&db_tx, &range)? {
+            let key = entry.key.clone();
+            let Some(mut list) = db_tx.get::<tables::StorageChangeSet>(key.clone())? else {
+                continue;
+            };
+
+            if is_superseded(&list, block, target) {
+                list.remove(block);
+                db_tx.delete::<tables::StorageChangeHistory>(block, Some(entry))?;
+                db_tx.put::<tables::StorageChangeSet>(key, list)?;
+            }
+        }
+
+        for block in range {
+            db_tx.delete::<tables::EventsBlooms>(block, None)?;
+
+            let Some(indices) = db_tx.get::<tables::BlockBodyIndices>(block)? else { continue };
+
+            for tx_number in indices.tx_offset..indices.tx_offset + indices.tx_count {
+                db_tx.delete::<tables::Receipts>(tx_number, None)?;
+                db_tx.delete::<tables::TxTraces>(tx_number, None)?;
+            }
+        }
+
+        db_tx.put::<tables::PruneCheckpoints>(PruneSegment::History, target)?;
+        db_tx.commit()?;
+
+        Ok(target)
+    }
+}
+
+/// Returns the error for a transaction whose receipt or trace can't be found: either it has been
+/// pruned, or the database is missing it, in which case `missing` is returned.
+pub(super) fn missing_tx_output<Tx: DbTx>(
+    db_tx: &Tx,
+    tx_number: TxNumber,
+    missing: fn(TxNumber) -> ProviderError,
+) -> ProviderError {
+    let block = match db_tx.get::<tables::TxBlocks>(tx_number) {
+        Ok(Some(block)) => block,
+        Ok(None) => return ProviderError::MissingTxBlock(tx_number),
+        Err(error) => return error.into(),
+    };
+
+    match history_checkpoint(db_tx) {
+        Ok(checkpoint) if block < checkpoint => ProviderError::PrunedTxOutput(tx_number),
+        Ok(_) => missing(tx_number),
+        Err(error) => error,
+    }
+}
+
+fn history_checkpoint<Tx: DbTx>(db_tx: &Tx) -> ProviderResult<BlockNumber> {
+    Ok(db_tx.get::<tables::PruneCheckpoints>(PruneSegment::History)?.unwrap_or_default())
+}
+
+/// Returns the entries of a change history table made in the given range of blocks.
+pub(super) fn changes_in_range<Tx, T>(
+    db_tx: &Tx,
+    range: &Range<BlockNumber>,
+) -> ProviderResult<Vec<(BlockNumber, T::Value)>>
+where
+    Tx: DbTx,
+    T: Table<Key = BlockNumber>,
+{
+    let mut cursor = db_tx.cursor::<T>()?;
+    let mut changes = Vec::new();
+
+    for entry in cursor.walk(Some(range.start))? {
+        let (block, value) = entry?;
+        if !range.contains(&block) {
+            break;
+        }
+        changes.push((block, value));
+    }
+
+    Ok(changes)
+}
+
+/// Returns whether the change made at `block` has been overwritten by another change made at or
+/// before `target`.
+fn is_superseded(list: &BlockList, block: BlockNumber, target: BlockNumber) -> bool {
+    list.rank(target) > list.rank(block)
+}
@@ -1,4 +1,5 @@
 use core::fmt;
+use std::collections::BTreeMap;
 
 use katana_db::abstraction::{Database, DbCursorMut, DbDupSortCursor, DbTx, DbTxMut};
 use katana_db::models::contract::ContractInfoChangeList;
@@ -14,7 +15,7 @@ use katana_primitives::contract::{
 use super::DbProvider;
 use crate::error::ProviderError;
 use crate::traits::contract::{ContractClassProvider, ContractClassWriter};
-use crate::traits::state::{StateProvider, StateWriter};
+use crate::traits::state::{ContractStorageProvider, StateProvider, StateWriter};
 use crate::ProviderResult;
 
 impl<Db: Database> StateWriter for DbProvider<Db> {
@@ -69,6 +70,29 @@ impl<Db: Database> StateWriter for DbProvider<Db> {
     }
 }
 
+impl<Db: Database> ContractStorageProvider for DbProvider<Db> {
+    fn storage_entries(
+        &self,
+        address: ContractAddress,
+    ) -> ProviderResult<BTreeMap<StorageKey, StorageValue>> {
+        let db_tx = self.0.tx()?;
+
+        let entries = db_tx
+            .cursor_dup::<tables::ContractStorage>()?
+            .walk_dup(Some(address), None)?
+            .map(|walker| {
+                walker
+                    .map(|entry| entry.map(|(_, e)| (e.key, e.value)).map_err(ProviderError::from))
+                    .collect::<ProviderResult<BTreeMap<_, _>>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        db_tx.commit()?;
+        Ok(entries)
+    }
+}
+
 impl ContractClassWriter for DbProvider {
     fn set_class(&self, hash: ClassHash, class: CompiledClass) -> ProviderResult<()> {
         self.0.update(move |db_tx| -> ProviderResult<()> {
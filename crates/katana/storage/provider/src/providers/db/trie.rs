@@ -27,14 +27,20 @@ impl<Db: Database> ClassTrieWriter for DbProvider<Db> {
         block_number: BlockNumber,
         updates: &BTreeMap<ClassHash, CompiledClassHash>,
     ) -> crate::ProviderResult<Felt> {
-        let mut trie = trie::ClassTrie::new(self.0.tx_mut()?);
+        let tx = self.0.tx_mut()?;
+        let mut trie = trie::ClassTrie::new(&tx);
 
         for (class_hash, compiled_hash) in updates {
             trie.insert(*class_hash, *compiled_hash);
         }
 
         trie.commit(block_number);
-        Ok(trie.root())
+        let root = trie.root();
+
+        drop(trie);
+        tx.commit()?;
+
+        Ok(root)
     }
 }
 
@@ -44,10 +50,11 @@ impl<Db: Database> ContractTrieWriter for DbProvider<Db> {
         block_number: BlockNumber,
         state_updates: &StateUpdates,
     ) -> crate::ProviderResult<Felt> {
+        let tx = self.0.tx_mut()?;
         let mut contract_leafs: HashMap<ContractAddress, ContractLeaf> = HashMap::new();
 
         let leaf_hashes: Vec<_> = {
-            let mut storage_trie_db = StorageTrie::new(self.0.tx_mut()?);
+            let mut storage_trie_db = StorageTrie::new(&tx);
 
             // First we insert the contract storage changes
             for (address, storage_entries) in &state_updates.storage_updates {
@@ -87,14 +94,19 @@ impl<Db: Database> ContractTrieWriter for DbProvider<Db> {
                 .collect::<Vec<_>>()
         };
 
-        let mut contract_trie_db = ContractTrie::new(self.0.tx_mut()?);
+        let mut contract_trie_db = ContractTrie::new(&tx);
 
         for (k, v) in leaf_hashes {
             contract_trie_db.insert(k, v);
         }
 
         contract_trie_db.commit(block_number);
-        Ok(contract_trie_db.root())
+        let root = contract_trie_db.root();
+
+        drop(contract_trie_db);
+        tx.commit()?;
+
+        Ok(root)
     }
 }
 
@@ -0,0 +1,265 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+
+use katana_db::abstraction::{Database, DbCursor, DbCursorMut, DbDupSortCursor, DbTx, DbTxMut};
+use katana_db::models::list::BlockList;
+use katana_db::models::storage::{ContractStorageKey, StorageEntry};
+use katana_db::tables;
+use katana_db::trie::{ClassTrie, ContractTrie, StorageTrie};
+use katana_primitives::block::BlockNumber;
+use katana_primitives::class::ClassHash;
+use katana_primitives::contract::{ContractAddress, GenericContractInfo, StorageKey};
+use katana_primitives::Felt;
+use katana_trie::compute_contract_state_hash;
+
+use super::prune::changes_in_range;
+use super::state::HistoricalStateProvider;
+use super::DbProvider;
+use crate::error::ProviderError;
+use crate::traits::block::{BlockNumberProvider, BlockUnwinder};
+use crate::traits::state::StateProvider;
+use crate::ProviderResult;
+
+/// The state entries changed by the unwound blocks.
+#[derive(Debug, Default)]
+struct UnwoundChanges {
+    /// The contracts whose nonce or class hash changed.
+    contracts: BTreeSet<ContractAddress>,
+    /// The storage entries that changed, by contract.
+    storage: BTreeMap<ContractAddress, BTreeSet<StorageKey>>,
+    /// The classes that were declared.
+    classes: BTreeSet<ClassHash>,
+}
+
+/// The state is reverted with the change history of the unwound blocks, so it must not have been
+/// pruned. The class, contract and storage tries are reverted along with it, so that the state
+/// roots of the blocks mined afterwards are computed over the state they are built on.
+///
+/// The state changes made outside of blocks, eg. with [`StateWriter`], are only reverted for the
+/// entries that were also changed by the unwound blocks.
+///
+/// [`StateWriter`]: crate::traits::state::StateWriter
+impl<Db: Database> BlockUnwinder for DbProvider<Db> {
+    fn unwind_to(&self, number: BlockNumber) -> ProviderResult<()> {
+        let latest = self.latest_number()?;
+        if number >= latest {
+            return Ok(());
+        }
+
+        if number < self.history_pruned_below()? {
+            return Err(ProviderError::PrunedState(number));
+        }
+
+        // opened before unwinding, so it still reads the history of the unwound blocks
+        let state = HistoricalStateProvider::new(self.0.tx()?, number);
+        let range = number + 1..latest + 1;
+
+        let db_tx = self.0.tx_mut()?;
+        let changes = unwind_state_changes(&db_tx, &range)?;
+        restore_state(&db_tx, &state, &changes)?;
+        restore_tries(&db_tx, number, &state, &changes)?;
+        unwind_blocks(&db_tx, range)?;
+        db_tx.commit()?;
+
+        Ok(())
+    }
+}
+
+/// Removes the changes made in the given range of blocks from the change history, including the
+/// classes declared in them. Returns the entries they changed.
+fn unwind_state_changes<Tx: DbTxMut>(
+    db_tx: &Tx,
+    range: &Range<BlockNumber>,
+) -> ProviderResult<UnwoundChanges> {
+    let mut changes = UnwoundChanges::default();
+
+    for (block, change) in changes_in_range::<_, tables::NonceChangeHistory>(db_tx, range)? {
+        let address = change.contract_address;
+        db_tx.delete::<tables::NonceChangeHistory>(block, Some(change))?;
+
+        if let Some(mut change_set) = db_tx.get::<tables::ContractInfoChangeSet>(address)? {
+            change_set.nonce_change_list.remove(block);
+            db_tx.put::<tables::ContractInfoChangeSet>(address, change_set)?;
+        }
+
+        changes.contracts.insert(address);
+    }
+
+    for (block, change) in changes_in_range::<_, tables::ClassChangeHistory>(db_tx, range)? {
+        let address = change.contract_address;
+        db_tx.delete::<tables::ClassChangeHistory>(block, Some(change))?;
+
+        if let Some(mut change_set) = db_tx.get::<tables::ContractInfoChangeSet>(address)? {
+            change_set.class_change_list.remove(block);
+            db_tx.put::<tables::ContractInfoChangeSet>(address, change_set)?;
+        }
+
+        changes.contracts.insert(address);
+    }
+
+    for (block, entry) in changes_in_range::<_, tables::StorageChangeHistory>(db_tx, range)? {
+        let key = entry.key.clone();
+        db_tx.delete::<tables::StorageChangeHistory>(block, Some(entry))?;
+
+        if let Some(mut list) = db_tx.get::<tables::StorageChangeSet>(key.clone())? {
+            list.remove(block);
+            db_tx.put::<tables::StorageChangeSet>(key.clone(), list)?;
+        }
+
+        changes.storage.entry(key.contract_address).or_default().insert(key.key);
+    }
+
+    for (block, class_hash) in changes_in_range::<_, tables::ClassDeclarations>(db_tx, range)? {
+        db_tx.delete::<tables::ClassDeclarations>(block, Some(class_hash))?;
+        db_tx.delete::<tables::ClassDeclarationBlock>(class_hash, None)?;
+        db_tx.delete::<tables::CompiledClassHashes>(class_hash, None)?;
+        db_tx.delete::<tables::CompiledClasses>(class_hash, None)?;
+        db_tx.delete::<tables::SierraClasses>(class_hash, None)?;
+        changes.classes.insert(class_hash);
+    }
+
+    Ok(changes)
+}
+
+/// Sets the entries changed by the unwound blocks back to their value in `state`.
+fn restore_state<Tx: DbTxMut>(
+    db_tx: &Tx,
+    state: &impl StateProvider,
+    changes: &UnwoundChanges,
+) -> ProviderResult<()> {
+    for &address in &changes.contracts {
+        let nonce = state.nonce(address)?;
+        let class_hash = state.class_hash_of_contract(address)?;
+
+        if nonce.is_none() && class_hash.is_none() {
+            db_tx.delete::<tables::ContractInfo>(address, None)?;
+        } else {
+            let info = GenericContractInfo {
+                nonce: nonce.unwrap_or_default(),
+                class_hash: class_hash.unwrap_or_default(),
+            };
+            db_tx.put::<tables::ContractInfo>(address, info)?;
+        }
+    }
+
+    let mut cursor = db_tx.cursor_dup_mut::<tables::ContractStorage>()?;
+    for (&address, keys) in &changes.storage {
+        for &key in keys {
+            match cursor.seek_by_key_subkey(address, key)? {
+                Some(current) if current.key == key => {
+                    cursor.delete_current()?;
+                }
+                _ => {}
+            }
+
+            if let Some(value) = state.storage(address, key)? {
+                cursor.upsert(address, StorageEntry { key, value })?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Sets the leaves of the tries changed by the unwound blocks back to their value in `state`, the
+/// state at block `number`.
+///
+/// The tries are only updated with the state updates of the blocks mined on top of the genesis
+/// block, so an entry which wasn't changed by any of them before the unwound blocks has no leaf.
+fn restore_tries<Tx: DbTxMut>(
+    db_tx: &Tx,
+    number: BlockNumber,
+    state: &impl StateProvider,
+    changes: &UnwoundChanges,
+) -> ProviderResult<()> {
+    let genesis = match db_tx.cursor::<tables::Headers>()?.first()? {
+        Some((genesis, _)) => genesis,
+        None => return Ok(()),
+    };
+
+    let mut class_trie = ClassTrie::new(db_tx);
+    for &class_hash in &changes.classes {
+        class_trie.remove(class_hash);
+    }
+    class_trie.commit(number);
+
+    let mut storage_trie = StorageTrie::new(db_tx);
+    for (&address, keys) in &changes.storage {
+        for &key in keys {
+            let changes = db_tx.get::<tables::StorageChangeSet>(ContractStorageKey {
+                contract_address: address,
+                key,
+            })?;
+
+            let value = match changes {
+                Some(changes) if changed_after(&changes, genesis) => {
+                    state.storage(address, key)?.unwrap_or_default()
+                }
+                // a zero value removes the leaf
+                _ => Felt::ZERO,
+            };
+
+            storage_trie.insert(address, key, value);
+        }
+    }
+    storage_trie.commit(number);
+
+    let mut contract_trie = ContractTrie::new(db_tx);
+    let contracts = changes.contracts.iter().chain(changes.storage.keys()).collect::<BTreeSet<_>>();
+    for &address in contracts {
+        let storage_root = storage_trie.root(&address);
+        let info_changed = db_tx.get::<tables::ContractInfoChangeSet>(address)?.is_some_and(|c| {
+            changed_after(&c.nonce_change_list, genesis)
+                || changed_after(&c.class_change_list, genesis)
+        });
+
+        if info_changed || storage_root != Felt::ZERO {
+            let nonce = state.nonce(address)?.unwrap_or_default();
+            let class_hash = state.class_hash_of_contract(address)?.unwrap_or_default();
+            let leaf = compute_contract_state_hash(&class_hash, &storage_root, &nonce);
+            contract_trie.insert(address, leaf);
+        } else {
+            contract_trie.remove(address);
+        }
+    }
+    contract_trie.commit(number);
+
+    Ok(())
+}
+
+/// Returns whether a change list has a change made after block `number`.
+fn changed_after(changes: &BlockList, number: BlockNumber) -> bool {
+    changes.select(changes.rank(number)).is_some()
+}
+
+/// Removes the blocks in the given range, along with their transactions.
+fn unwind_blocks<Tx: DbTxMut>(db_tx: &Tx, range: Range<BlockNumber>) -> ProviderResult<()> {
+    for block in range {
+        if let Some(hash) = db_tx.get::<tables::BlockHashes>(block)? {
+            db_tx.delete::<tables::BlockNumbers>(hash, None)?;
+        }
+
+        if let Some(indices) = db_tx.get::<tables::BlockBodyIndices>(block)? {
+            for tx_number in indices.tx_offset..indices.tx_offset + indices.tx_count {
+                if let Some(hash) = db_tx.get::<tables::TxHashes>(tx_number)? {
+                    db_tx.delete::<tables::TxNumbers>(hash, None)?;
+                }
+
+                db_tx.delete::<tables::TxHashes>(tx_number, None)?;
+                db_tx.delete::<tables::TxBlocks>(tx_number, None)?;
+                db_tx.delete::<tables::Transactions>(tx_number, None)?;
+                db_tx.delete::<tables::Receipts>(tx_number, None)?;
+                db_tx.delete::<tables::TxTraces>(tx_number, None)?;
+            }
+        }
+
+        db_tx.delete::<tables::BlockHashes>(block, None)?;
+        db_tx.delete::<tables::BlockStatusses>(block, None)?;
+        db_tx.delete::<tables::Headers>(block, None)?;
+        db_tx.delete::<tables::HeaderExtensions>(block, None)?;
+        db_tx.delete::<tables::EventsBlooms>(block, None)?;
+        db_tx.delete::<tables::BlockBodyIndices>(block, None)?;
+    }
+
+    Ok(())
+}
@@ -8,12 +8,12 @@ use std::sync::Arc;
 use katana_db::models::block::StoredBlockBodyIndices;
 use katana_primitives::block::{
     Block, BlockHash, BlockHashOrNumber, BlockNumber, BlockWithTxHashes, FinalityStatus, Header,
-    SealedBlockWithStatus,
+    HeaderExtension, SealedBlockWithStatus,
 };
 use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash, FlattenedSierraClass};
-use katana_primitives::contract::ContractAddress;
+use katana_primitives::contract::{ContractAddress, StorageKey, StorageValue};
 use katana_primitives::env::BlockEnv;
-use katana_primitives::receipt::Receipt;
+use katana_primitives::receipt::{EventsBloom, Receipt};
 use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
 use katana_primitives::trace::TxExecInfo;
 use katana_primitives::transaction::{Tx, TxHash, TxNumber, TxWithHash};
@@ -26,17 +26,21 @@ use self::backend::{Backend, BackendError, SharedStateProvider};
 use self::state::ForkedStateDb;
 use super::in_memory::cache::{CacheDb, CacheStateDb};
 use super::in_memory::state::HistoricalStates;
+use crate::error::ProviderError;
 use crate::traits::block::{
-    BlockHashProvider, BlockNumberProvider, BlockProvider, BlockStatusProvider, BlockWriter,
-    HeaderProvider,
+    BlockHashProvider, BlockNumberProvider, BlockProvider, BlockStatusProvider, BlockUnwinder,
+    BlockWriter, HeaderExtensionProvider, HeaderExtensionWriter, HeaderProvider,
 };
+use crate::traits::checkpoint::{CheckpointId, CheckpointProvider};
 use crate::traits::contract::ContractClassWriter;
 use crate::traits::env::BlockEnvProvider;
-use crate::traits::state::{StateFactoryProvider, StateProvider, StateRootProvider, StateWriter};
+use crate::traits::state::{
+    ContractStorageProvider, StateFactoryProvider, StateProvider, StateRootProvider, StateWriter,
+};
 use crate::traits::state_update::StateUpdateProvider;
 use crate::traits::transaction::{
-    ReceiptProvider, TransactionProvider, TransactionStatusProvider, TransactionTraceProvider,
-    TransactionsProviderExt,
+    EventsBloomProvider, ReceiptProvider, TransactionProvider, TransactionStatusProvider,
+    TransactionTraceProvider, TransactionsProviderExt,
 };
 use crate::traits::trie::{ClassTrieWriter, ContractTrieWriter};
 use crate::ProviderResult;
@@ -111,6 +115,33 @@ impl HeaderProvider for ForkedProvider {
     }
 }
 
+impl HeaderExtensionProvider for ForkedProvider {
+    fn header_extension(&self, id: BlockHashOrNumber) -> ProviderResult<Option<HeaderExtension>> {
+        let storage = self.storage.read();
+
+        let num = match id {
+            BlockHashOrNumber::Num(num) => num,
+            BlockHashOrNumber::Hash(hash) => match storage.block_numbers.get(&hash).copied() {
+                Some(num) => num,
+                None => return Ok(None),
+            },
+        };
+
+        Ok(storage.header_extensions.get(&num).cloned())
+    }
+}
+
+impl HeaderExtensionWriter for ForkedProvider {
+    fn insert_header_extension(
+        &self,
+        block_number: BlockNumber,
+        extension: HeaderExtension,
+    ) -> ProviderResult<()> {
+        self.storage.write().header_extensions.insert(block_number, extension);
+        Ok(())
+    }
+}
+
 impl BlockStatusProvider for ForkedProvider {
     fn block_status(&self, id: BlockHashOrNumber) -> ProviderResult<Option<FinalityStatus>> {
         let num = match id {
@@ -307,6 +338,24 @@ impl TransactionsProviderExt for ForkedProvider {
         }
         Ok(hashes)
     }
+
+    fn transaction_hashes_by_block(
+        &self,
+        block_id: BlockHashOrNumber,
+    ) -> ProviderResult<Option<Vec<TxHash>>> {
+        let block_num = match block_id {
+            BlockHashOrNumber::Num(num) => Some(num),
+            BlockHashOrNumber::Hash(hash) => self.storage.read().block_numbers.get(&hash).cloned(),
+        };
+
+        let Some(indices) =
+            block_num.and_then(|num| self.storage.read().block_body_indices.get(&num).cloned())
+        else {
+            return Ok(None);
+        };
+
+        Ok(Some(self.transaction_hashes_in_range(Range::from(indices))?))
+    }
 }
 
 impl TransactionStatusProvider for ForkedProvider {
@@ -407,6 +456,26 @@ impl ReceiptProvider for ForkedProvider {
 
         Ok(Some(self.storage.read().receipts[offset..offset + count].to_vec()))
     }
+
+    fn receipts_pruned_below(&self) -> ProviderResult<BlockNumber> {
+        Ok(0)
+    }
+}
+
+impl EventsBloomProvider for ForkedProvider {
+    fn events_bloom(&self, block_id: BlockHashOrNumber) -> ProviderResult<Option<EventsBloom>> {
+        let storage = self.storage.read();
+
+        let num = match block_id {
+            BlockHashOrNumber::Num(num) => num,
+            BlockHashOrNumber::Hash(hash) => match storage.block_numbers.get(&hash).copied() {
+                Some(num) => num,
+                None => return Ok(None),
+            },
+        };
+
+        Ok(storage.events_blooms.get(&num).copied())
+    }
 }
 
 impl StateRootProvider for ForkedProvider {
@@ -463,18 +532,23 @@ impl StateFactoryProvider for ForkedProvider {
 }
 
 impl BlockWriter for ForkedProvider {
-    fn insert_block_with_states_and_receipts(
+    fn insert_block_with_header_extension(
         &self,
         block: SealedBlockWithStatus,
         states: StateUpdatesWithDeclaredClasses,
         receipts: Vec<Receipt>,
         executions: Vec<TxExecInfo>,
+        extension: Option<HeaderExtension>,
     ) -> ProviderResult<()> {
         let mut storage = self.storage.write();
 
         let block_hash = block.block.hash;
         let block_number = block.block.header.number;
 
+        if let Some(extension) = extension {
+            storage.header_extensions.insert(block_number, extension);
+        }
+
         let block_header = block.block.header;
         let txs = block.block.body;
 
@@ -500,6 +574,7 @@ impl BlockWriter for ForkedProvider {
         storage.block_headers.insert(block_number, block_header);
         storage.block_statusses.insert(block_number, block.status);
         storage.block_body_indices.insert(block_number, block_body_indices);
+        storage.events_blooms.insert(block_number, EventsBloom::from_receipts(&receipts));
 
         storage.transactions.extend(txs);
         storage.transaction_hashes.extend(txs_id);
@@ -574,6 +649,19 @@ impl StateWriter for ForkedProvider {
     }
 }
 
+impl ContractStorageProvider for ForkedProvider {
+    /// Only the storage entries known locally are returned, ie the ones that have been updated
+    /// since the fork or already fetched from the forked network.
+    fn storage_entries(
+        &self,
+        address: ContractAddress,
+    ) -> ProviderResult<BTreeMap<StorageKey, StorageValue>> {
+        let storage = self.state.storage.read();
+        let entries = storage.get(&address).map(|s| s.iter().map(|(k, v)| (*k, *v)).collect());
+        Ok(entries.unwrap_or_default())
+    }
+}
+
 impl BlockEnvProvider for ForkedProvider {
     fn block_env_at(&self, block_id: BlockHashOrNumber) -> ProviderResult<Option<BlockEnv>> {
         Ok(self.header(block_id)?.map(|header| BlockEnv {
@@ -586,6 +674,26 @@ impl BlockEnvProvider for ForkedProvider {
     }
 }
 
+// TODO: support unwinding the blocks mined on top of the forked block
+impl BlockUnwinder for ForkedProvider {
+    fn unwind_to(&self, number: BlockNumber) -> ProviderResult<()> {
+        let _ = number;
+        Err(ProviderError::Other("unwinding is not supported on a forked chain".to_string()))
+    }
+}
+
+// TODO: support checkpoints on the forked chain
+impl CheckpointProvider for ForkedProvider {
+    fn checkpoint(&self) -> ProviderResult<CheckpointId> {
+        Err(ProviderError::Other("checkpoints are not supported on a forked chain".to_string()))
+    }
+
+    fn revert_to_checkpoint(&self, id: CheckpointId) -> ProviderResult<bool> {
+        let _ = id;
+        Err(ProviderError::Other("checkpoints are not supported on a forked chain".to_string()))
+    }
+}
+
 impl ClassTrieWriter for ForkedProvider {
     fn insert_updates(
         &self,
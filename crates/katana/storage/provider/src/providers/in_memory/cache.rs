@@ -2,10 +2,10 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use katana_db::models::block::StoredBlockBodyIndices;
-use katana_primitives::block::{BlockHash, BlockNumber, FinalityStatus, Header};
+use katana_primitives::block::{BlockHash, BlockNumber, FinalityStatus, Header, HeaderExtension};
 use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash, FlattenedSierraClass};
 use katana_primitives::contract::{ContractAddress, GenericContractInfo, StorageKey, StorageValue};
-use katana_primitives::receipt::Receipt;
+use katana_primitives::receipt::{EventsBloom, Receipt};
 use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
 use katana_primitives::trace::TxExecInfo;
 use katana_primitives::transaction::{Tx, TxHash, TxNumber};
@@ -78,6 +78,8 @@ pub struct CacheDb<Db> {
     pub(crate) block_hashes: HashMap<BlockNumber, BlockHash>,
     pub(crate) block_numbers: HashMap<BlockHash, BlockNumber>,
     pub(crate) block_statusses: HashMap<BlockNumber, FinalityStatus>,
+    pub(crate) header_extensions: HashMap<BlockNumber, HeaderExtension>,
+    pub(crate) events_blooms: HashMap<BlockNumber, EventsBloom>,
     pub(crate) block_body_indices: HashMap<BlockNumber, StoredBlockBodyIndices>,
     pub(crate) latest_block_hash: BlockHash,
     pub(crate) latest_block_number: BlockNumber,
@@ -113,6 +115,8 @@ impl<Db> CacheDb<Db> {
             block_headers: HashMap::new(),
             block_numbers: HashMap::new(),
             block_statusses: HashMap::new(),
+            header_extensions: HashMap::new(),
+            events_blooms: HashMap::new(),
             transaction_block: HashMap::new(),
             transaction_hashes: HashMap::new(),
             block_body_indices: HashMap::new(),
@@ -3,3 +3,4 @@ pub mod db;
 pub mod fork;
 #[cfg(feature = "in-memory")]
 pub mod in_memory;
+pub mod overlay;
@@ -0,0 +1,104 @@
+use katana_primitives::class::{ClassHash, CompiledClass, CompiledClassHash, FlattenedSierraClass};
+use katana_primitives::contract::{ContractAddress, Nonce, StorageKey, StorageValue};
+use katana_primitives::state::StateUpdates;
+
+use crate::traits::contract::ContractClassProvider;
+use crate::traits::state::StateProvider;
+use crate::ProviderResult;
+
+/// A [StateProvider] returning the nonces, storage values and class hashes of the overrides
+/// instead of the ones of the underlying state.
+///
+/// Only the contracts state is overridden, the classes are always read from the underlying
+/// state.
+#[derive(Debug)]
+pub struct OverlayStateProvider<S> {
+    state: S,
+    overrides: StateUpdates,
+}
+
+impl<S> OverlayStateProvider<S> {
+    /// Creates a provider overriding the state with the nonces, storage entries and deployed
+    /// contracts of `overrides`.
+    pub fn new(state: S, overrides: StateUpdates) -> Self {
+        Self { state, overrides }
+    }
+}
+
+impl<S: StateProvider> StateProvider for OverlayStateProvider<S> {
+    fn nonce(&self, address: ContractAddress) -> ProviderResult<Option<Nonce>> {
+        if let Some(nonce) = self.overrides.nonce_updates.get(&address) {
+            return Ok(Some(*nonce));
+        }
+        self.state.nonce(address)
+    }
+
+    fn storage(
+        &self,
+        address: ContractAddress,
+        storage_key: StorageKey,
+    ) -> ProviderResult<Option<StorageValue>> {
+        let value = self.overrides.storage_updates.get(&address).and_then(|s| s.get(&storage_key));
+        if let Some(value) = value {
+            return Ok(Some(*value));
+        }
+        self.state.storage(address, storage_key)
+    }
+
+    fn class_hash_of_contract(
+        &self,
+        address: ContractAddress,
+    ) -> ProviderResult<Option<ClassHash>> {
+        if let Some(hash) = self.overrides.deployed_contracts.get(&address) {
+            return Ok(Some(*hash));
+        }
+        self.state.class_hash_of_contract(address)
+    }
+}
+
+impl<S: StateProvider> ContractClassProvider for OverlayStateProvider<S> {
+    fn class(&self, hash: ClassHash) -> ProviderResult<Option<CompiledClass>> {
+        self.state.class(hash)
+    }
+
+    fn compiled_class_hash_of_class_hash(
+        &self,
+        hash: ClassHash,
+    ) -> ProviderResult<Option<CompiledClassHash>> {
+        self.state.compiled_class_hash_of_class_hash(hash)
+    }
+
+    fn sierra_class(&self, hash: ClassHash) -> ProviderResult<Option<FlattenedSierraClass>> {
+        self.state.sierra_class(hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use katana_primitives::address;
+    use katana_primitives::Felt;
+
+    use super::*;
+    use crate::providers::db::DbProvider;
+    use crate::traits::state::StateFactoryProvider;
+
+    #[test]
+    fn overrides_take_precedence() {
+        let account = address!("0x1");
+
+        let mut overrides = StateUpdates::default();
+        overrides.nonce_updates.insert(account, Felt::THREE);
+        overrides.deployed_contracts.insert(account, Felt::TWO);
+        overrides.storage_updates.entry(account).or_default().insert(Felt::ONE, Felt::TWO);
+
+        let state = DbProvider::new_ephemeral().latest().unwrap();
+        assert_eq!(state.nonce(account).unwrap(), None);
+
+        let state = OverlayStateProvider::new(state, overrides);
+        assert_eq!(state.nonce(account).unwrap(), Some(Felt::THREE));
+        assert_eq!(state.class_hash_of_contract(account).unwrap(), Some(Felt::TWO));
+        assert_eq!(state.storage(account, Felt::ONE).unwrap(), Some(Felt::TWO));
+        assert_eq!(state.storage(account, Felt::TWO).unwrap(), None);
+        assert_eq!(state.nonce(address!("0x2")).unwrap(), None);
+    }
+}
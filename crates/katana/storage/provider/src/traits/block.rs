@@ -3,7 +3,7 @@ use std::ops::RangeInclusive;
 use katana_db::models::block::StoredBlockBodyIndices;
 use katana_primitives::block::{
     Block, BlockHash, BlockHashOrNumber, BlockIdOrTag, BlockNumber, BlockTag, BlockWithTxHashes,
-    FinalityStatus, Header, SealedBlockWithStatus,
+    FinalityStatus, Header, HeaderExtension, SealedBlockWithStatus,
 };
 use katana_primitives::receipt::Receipt;
 use katana_primitives::state::StateUpdatesWithDeclaredClasses;
@@ -94,6 +94,24 @@ pub trait HeaderProvider: Send + Sync {
     }
 }
 
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait HeaderExtensionProvider: Send + Sync {
+    /// Retrieves the operator-defined header extension of a block.
+    ///
+    /// Returns `None` if the block doesn't exist or if no extension was set for it.
+    fn header_extension(&self, id: BlockHashOrNumber) -> ProviderResult<Option<HeaderExtension>>;
+}
+
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait HeaderExtensionWriter: Send + Sync {
+    /// Stores the header extension of an already inserted block.
+    fn insert_header_extension(
+        &self,
+        block_number: BlockNumber,
+        extension: HeaderExtension,
+    ) -> ProviderResult<()>;
+}
+
 #[auto_impl::auto_impl(&, Box, Arc)]
 pub trait BlockStatusProvider: Send + Sync {
     /// Retrieves the finality status of a block.
@@ -149,5 +167,25 @@ pub trait BlockWriter: Send + Sync {
         states: StateUpdatesWithDeclaredClasses,
         receipts: Vec<Receipt>,
         executions: Vec<TxExecInfo>,
+    ) -> ProviderResult<()> {
+        self.insert_block_with_header_extension(block, states, receipts, executions, None)
+    }
+
+    /// Store an executed block along with its execution output and the extension of its header,
+    /// if any, to the storage. Everything is written atomically.
+    fn insert_block_with_header_extension(
+        &self,
+        block: SealedBlockWithStatus,
+        states: StateUpdatesWithDeclaredClasses,
+        receipts: Vec<Receipt>,
+        executions: Vec<TxExecInfo>,
+        extension: Option<HeaderExtension>,
     ) -> ProviderResult<()>;
 }
+
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait BlockUnwinder: Send + Sync {
+    /// Removes the blocks after block `number`, along with their transactions and receipts, and
+    /// reverts the state to the state at that block.
+    fn unwind_to(&self, number: BlockNumber) -> ProviderResult<()>;
+}
@@ -0,0 +1,17 @@
+use crate::ProviderResult;
+
+/// The id of a checkpoint taken with [`CheckpointProvider::checkpoint`].
+pub type CheckpointId = u64;
+
+/// A provider that can revert the chain to an earlier point.
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait CheckpointProvider: Send + Sync {
+    /// Takes a checkpoint of the chain, its blocks and its state, returning its id.
+    fn checkpoint(&self) -> ProviderResult<CheckpointId>;
+
+    /// Reverts the chain to the checkpoint `id`, removing the blocks added since it was taken.
+    ///
+    /// The checkpoint, and the ones taken after it, can't be reverted to anymore. Returns `false`
+    /// if there is no such checkpoint.
+    fn revert_to_checkpoint(&self, id: CheckpointId) -> ProviderResult<bool>;
+}
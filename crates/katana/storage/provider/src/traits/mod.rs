@@ -1,4 +1,5 @@
 pub mod block;
+pub mod checkpoint;
 pub mod contract;
 pub mod env;
 pub mod state;
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use katana_primitives::block::BlockHashOrNumber;
 use katana_primitives::class::ClassHash;
 use katana_primitives::contract::{ContractAddress, Nonce, StorageKey, StorageValue};
@@ -42,6 +44,17 @@ pub trait StateFactoryProvider: Send + Sync {
     ) -> ProviderResult<Option<Box<dyn StateProvider>>>;
 }
 
+/// A type which can enumerate the storage of a contract in the latest state.
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait ContractStorageProvider: Send + Sync {
+    /// Returns all the storage entries of a contract. Empty if the contract has no storage or
+    /// doesn't exist.
+    fn storage_entries(
+        &self,
+        address: ContractAddress,
+    ) -> ProviderResult<BTreeMap<StorageKey, StorageValue>>;
+}
+
 // TEMP: added mainly for compatibility reason. it might be removed in the future.
 #[auto_impl::auto_impl(&, Box, Arc)]
 pub trait StateWriter: Send + Sync {
@@ -1,7 +1,7 @@
 use std::ops::Range;
 
 use katana_primitives::block::{BlockHash, BlockHashOrNumber, BlockNumber, FinalityStatus};
-use katana_primitives::receipt::Receipt;
+use katana_primitives::receipt::{EventsBloom, Receipt};
 use katana_primitives::trace::TxExecInfo;
 use katana_primitives::transaction::{TxHash, TxNumber, TxWithHash};
 
@@ -45,6 +45,12 @@ pub trait TransactionProvider: Send + Sync {
 pub trait TransactionsProviderExt: TransactionProvider + Send + Sync {
     /// Retrieves the tx hashes for the given range of tx numbers.
     fn transaction_hashes_in_range(&self, range: Range<TxNumber>) -> ProviderResult<Vec<TxHash>>;
+
+    /// Returns the hashes of all the transactions in a block, without reading the transactions.
+    fn transaction_hashes_by_block(
+        &self,
+        block_id: BlockHashOrNumber,
+    ) -> ProviderResult<Option<Vec<TxHash>>>;
 }
 
 #[auto_impl::auto_impl(&, Box, Arc)]
@@ -81,4 +87,17 @@ pub trait ReceiptProvider: Send + Sync {
         &self,
         block_id: BlockHashOrNumber,
     ) -> ProviderResult<Option<Vec<Receipt>>>;
+
+    /// Returns the lowest block whose receipts are still available, those of the older blocks
+    /// having been pruned.
+    fn receipts_pruned_below(&self) -> ProviderResult<BlockNumber>;
+}
+
+#[auto_impl::auto_impl(&, Box, Arc)]
+pub trait EventsBloomProvider: Send + Sync {
+    /// Returns the bloom filter of the events emitted in a block.
+    ///
+    /// Returns `None` if the block doesn't exist or if its bloom wasn't stored, in which case its
+    /// receipts have to be scanned.
+    fn events_bloom(&self, block_id: BlockHashOrNumber) -> ProviderResult<Option<EventsBloom>>;
 }
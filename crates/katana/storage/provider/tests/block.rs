@@ -1,23 +1,38 @@
+use std::collections::BTreeMap;
+
 use anyhow::Result;
+use katana_db::mdbx;
 use katana_primitives::block::{
-    Block, BlockHashOrNumber, BlockNumber, BlockWithTxHashes, FinalityStatus,
+    Block, BlockHashOrNumber, BlockNumber, BlockWithTxHashes, FinalityStatus, Header,
+    HeaderExtension, SealedBlock, SealedBlockWithStatus,
 };
+use katana_primitives::contract::ContractAddress;
+use katana_primitives::da::DataAvailabilityMode;
 use katana_primitives::env::BlockEnv;
-use katana_primitives::state::StateUpdatesWithDeclaredClasses;
+use katana_primitives::receipt::{Event, Receipt};
+use katana_primitives::state::{StateUpdates, StateUpdatesWithDeclaredClasses};
 use katana_primitives::transaction::TxWithHash;
+use katana_primitives::{address, felt};
 use katana_provider::providers::db::DbProvider;
 use katana_provider::providers::fork::ForkedProvider;
 use katana_provider::traits::block::{
-    BlockHashProvider, BlockNumberProvider, BlockProvider, BlockStatusProvider, BlockWriter,
+    BlockHashProvider, BlockNumberProvider, BlockProvider, BlockStatusProvider, BlockUnwinder,
+    BlockWriter, HeaderExtensionProvider, HeaderExtensionWriter,
 };
+use katana_provider::traits::checkpoint::CheckpointProvider;
+use katana_provider::traits::contract::ContractClassProvider;
 use katana_provider::traits::env::BlockEnvProvider;
-use katana_provider::traits::state::StateRootProvider;
+use katana_provider::traits::state::{StateFactoryProvider, StateProvider, StateRootProvider};
 use katana_provider::traits::state_update::StateUpdateProvider;
 use katana_provider::traits::transaction::{
-    ReceiptProvider, TransactionProvider, TransactionStatusProvider, TransactionTraceProvider,
+    EventsBloomProvider, ReceiptProvider, TransactionProvider, TransactionStatusProvider,
+    TransactionTraceProvider, TransactionsProviderExt,
 };
+use katana_provider::traits::trie::{ClassTrieWriter, ContractTrieWriter};
 use katana_provider::BlockchainProvider;
 use rstest_reuse::{self, *};
+use starknet::macros::short_string;
+use starknet_types_core::hash::{Poseidon, StarkHash};
 
 mod fixtures;
 mod utils;
@@ -131,10 +146,12 @@ where
         };
 
         let actual_block_with_tx_hashes = provider.block_with_tx_hashes(block_id)?;
+        let actual_tx_hashes = provider.transaction_hashes_by_block(block_id)?;
         let actual_block_env = provider.block_env_at(block_id)?;
 
         assert_eq!(actual_status, Some(FinalityStatus::AcceptedOnL2));
-        assert_eq!(actual_block_with_tx_hashes, Some(expected_block_with_tx_hashes));
+        assert_eq!(actual_block_with_tx_hashes, Some(expected_block_with_tx_hashes.clone()));
+        assert_eq!(actual_tx_hashes, Some(expected_block_with_tx_hashes.body));
 
         for (idx, tx) in expected_block.body.iter().enumerate() {
             let actual_receipt = provider.receipt_by_hash(tx.hash)?;
@@ -169,6 +186,289 @@ where
     Ok(())
 }
 
+#[test]
+fn block_id_index_with_db_provider() -> Result<()> {
+    let db = mdbx::test_utils::create_test_db();
+    let provider = BlockchainProvider::new(DbProvider::new(db.clone()));
+
+    let blocks = utils::generate_dummy_blocks_and_receipts(3);
+    for (block, receipts, executions) in &blocks {
+        provider.insert_block_with_states_and_receipts(
+            block.clone(),
+            Default::default(),
+            receipts.clone(),
+            executions.clone(),
+        )?;
+    }
+
+    // a provider opened on the same database starts with an empty index, filled on lookups
+    let reopened = BlockchainProvider::new(DbProvider::new(db));
+
+    for (block, _, _) in &blocks {
+        let (number, hash) = (block.block.header.number, block.block.hash);
+        for provider in [&provider, &reopened] {
+            assert_eq!(provider.block_number_by_hash(hash)?, Some(number));
+            assert_eq!(provider.block_hash_by_num(number)?, Some(hash));
+        }
+    }
+
+    assert_eq!(reopened.block_number_by_hash(felt!("0x1337"))?, None);
+    assert_eq!(reopened.block_hash_by_num(100)?, None);
+
+    Ok(())
+}
+
+#[test]
+fn read_only_db_provider() -> Result<()> {
+    let dir = tempfile::tempdir()?;
+    let provider = BlockchainProvider::new(DbProvider::new(katana_db::init_db(dir.path())?));
+
+    let blocks = utils::generate_dummy_blocks_and_receipts(3);
+    for (block, receipts, executions) in &blocks {
+        provider.insert_block_with_states_and_receipts(
+            block.clone(),
+            Default::default(),
+            receipts.clone(),
+            executions.clone(),
+        )?;
+    }
+    drop(provider);
+
+    let provider = BlockchainProvider::new(DbProvider::open_read_only(dir.path())?);
+
+    let (block, receipts, executions) = blocks.last().unwrap().clone();
+    assert_eq!(provider.latest_number()?, block.block.header.number);
+    assert_eq!(provider.latest_hash()?, block.block.hash);
+    let block_id = BlockHashOrNumber::Hash(block.block.hash);
+    assert_eq!(provider.receipts_by_block(block_id)?, Some(receipts.clone()));
+
+    // the database can't be written to
+    let result = provider.insert_block_with_states_and_receipts(
+        block,
+        Default::default(),
+        receipts,
+        executions,
+    );
+    assert!(result.is_err());
+
+    Ok(())
+}
+
+#[test]
+fn revert_to_checkpoint_with_db_provider() -> Result<()> {
+    let provider = BlockchainProvider::new(DbProvider::new_ephemeral());
+
+    let blocks = utils::generate_dummy_blocks_and_receipts(3);
+    let mut checkpoint = None;
+
+    for (i, (block, receipts, executions)) in blocks.iter().enumerate() {
+        if i == 1 {
+            checkpoint = Some(provider.checkpoint()?);
+        }
+
+        provider.insert_block_with_states_and_receipts(
+            block.clone(),
+            Default::default(),
+            receipts.clone(),
+            executions.clone(),
+        )?;
+    }
+
+    let checkpoint = checkpoint.expect("taken before the second block");
+    assert!(provider.revert_to_checkpoint(checkpoint)?);
+
+    let (first, _, _) = &blocks[0];
+    assert_eq!(provider.latest_number()?, first.block.header.number);
+    assert_eq!(provider.latest_hash()?, first.block.hash);
+
+    // the removed blocks can't be found anymore, even though they were indexed on insertion
+    for (block, _, _) in &blocks[1..] {
+        let (number, hash) = (block.block.header.number, block.block.hash);
+        assert_eq!(provider.block_number_by_hash(hash)?, None);
+        assert_eq!(provider.block_hash_by_num(number)?, None);
+        assert_eq!(provider.block(number.into())?, None);
+    }
+
+    // a checkpoint can only be reverted to once
+    assert!(!provider.revert_to_checkpoint(checkpoint)?);
+
+    Ok(())
+}
+
+#[rstest::rstest]
+fn unwind_with_db_provider(
+    #[from(provider_with_states)] provider: BlockchainProvider<DbProvider>,
+    #[from(mock_state_updates)] state_updates: [StateUpdatesWithDeclaredClasses; 3],
+) -> Result<()> {
+    // the tries of the blocks changing the state
+    for (number, updates) in [1, 2, 5].into_iter().zip(&state_updates) {
+        state_root(&provider, number, &updates.state_updates)?;
+    }
+
+    // only block 5 changes the state after block 3
+    provider.unwind_to(3)?;
+
+    assert_eq!(provider.latest_number()?, 3);
+    assert_eq!(provider.block_hash_by_num(5)?, None);
+    assert_eq!(provider.block_number_by_hash(felt!("5"))?, None);
+
+    let state = provider.latest()?;
+    let (address_1, address_2) = (address!("1"), address!("2"));
+
+    assert_eq!(state.nonce(address_1)?, Some(felt!("2")));
+    assert_eq!(state.nonce(address_2)?, Some(felt!("1")));
+    assert_eq!(state.class_hash_of_contract(address_1)?, Some(felt!("11")));
+    assert_eq!(state.class_hash_of_contract(address_2)?, Some(felt!("22")));
+    assert_eq!(state.storage(address_1, felt!("3"))?, None);
+    assert_eq!(state.storage(address_2, felt!("1"))?, Some(felt!("200")));
+    assert_eq!(state.compiled_class_hash_of_class_hash(felt!("33"))?, None);
+
+    // the tries hold the state at block 3, as if they were computed from scratch
+    let state_at_3 = StateUpdates {
+        nonce_updates: BTreeMap::from([(address_1, felt!("2")), (address_2, felt!("1"))]),
+        storage_updates: BTreeMap::from([
+            (address_1, BTreeMap::from([(felt!("1"), felt!("111")), (felt!("2"), felt!("222"))])),
+            (address_2, BTreeMap::from([(felt!("1"), felt!("200")), (felt!("2"), felt!("201"))])),
+        ]),
+        deployed_contracts: BTreeMap::from([(address_1, felt!("11")), (address_2, felt!("22"))]),
+        declared_classes: BTreeMap::from([
+            (felt!("11"), felt!("1000")),
+            (felt!("22"), felt!("2000")),
+        ]),
+        ..Default::default()
+    };
+    let expected_root = state_root(&db_provider(), 3, &state_at_3)?;
+    assert_eq!(state_root(&provider, 4, &StateUpdates::default())?, expected_root);
+
+    // the chain goes on from the block it was unwound to
+    let block = SealedBlockWithStatus {
+        status: FinalityStatus::AcceptedOnL2,
+        block: SealedBlock {
+            hash: felt!("0x44"),
+            header: Header { number: 4, ..Default::default() },
+            body: Default::default(),
+        },
+    };
+    provider.insert_block_with_states_and_receipts(block, Default::default(), vec![], vec![])?;
+    assert_eq!(provider.latest_hash()?, felt!("0x44"));
+
+    Ok(())
+}
+
+/// Applies `updates` to the tries of `provider` and returns the resulting state root.
+fn state_root<Db>(
+    provider: &BlockchainProvider<Db>,
+    number: BlockNumber,
+    updates: &StateUpdates,
+) -> Result<Felt>
+where
+    Db: ClassTrieWriter + ContractTrieWriter,
+{
+    let class_root = ClassTrieWriter::insert_updates(provider, number, &updates.declared_classes)?;
+    let contract_root = ContractTrieWriter::insert_updates(provider, number, updates)?;
+    Ok(Poseidon::hash_array(&[short_string!("STARKNET_STATE_V0"), contract_root, class_root]))
+}
+
+#[rstest::rstest]
+fn header_extension_with_fork_provider(
+    #[from(fork_provider)] provider: BlockchainProvider<ForkedProvider>,
+) -> Result<()> {
+    header_extension_test_impl(provider)
+}
+
+#[rstest::rstest]
+fn header_extension_with_db_provider(
+    #[from(db_provider)] provider: BlockchainProvider<DbProvider>,
+) -> Result<()> {
+    header_extension_test_impl(provider)
+}
+
+fn header_extension_test_impl<Db>(provider: BlockchainProvider<Db>) -> Result<()>
+where
+    Db: BlockWriter + HeaderExtensionProvider + HeaderExtensionWriter,
+{
+    let blocks = utils::generate_dummy_blocks_empty(3);
+    for block in &blocks[..2] {
+        provider.insert_block_with_states_and_receipts(
+            block.clone(),
+            Default::default(),
+            vec![],
+            vec![],
+        )?;
+    }
+
+    let extension =
+        HeaderExtension { da_mode: DataAvailabilityMode::L2, extra_data: vec![0xde, 0xad] };
+    provider.insert_header_extension(blocks[1].block.header.number, extension.clone())?;
+
+    // The extension can also be written along with the block itself.
+    provider.insert_block_with_header_extension(
+        blocks[2].clone(),
+        Default::default(),
+        vec![],
+        vec![],
+        Some(extension.clone()),
+    )?;
+
+    let first = blocks[0].block.header.number;
+    assert_eq!(provider.header_extension(BlockHashOrNumber::Num(first))?, None);
+    assert_eq!(
+        provider.header_extension(BlockHashOrNumber::Hash(blocks[1].block.hash))?,
+        Some(extension.clone())
+    );
+    assert_eq!(
+        provider.header_extension(BlockHashOrNumber::Hash(blocks[2].block.hash))?,
+        Some(extension)
+    );
+
+    Ok(())
+}
+
+#[rstest::rstest]
+fn events_bloom_with_fork_provider(
+    #[from(fork_provider)] provider: BlockchainProvider<ForkedProvider>,
+) -> Result<()> {
+    events_bloom_test_impl(provider)
+}
+
+#[rstest::rstest]
+fn events_bloom_with_db_provider(
+    #[from(db_provider)] provider: BlockchainProvider<DbProvider>,
+) -> Result<()> {
+    events_bloom_test_impl(provider)
+}
+
+fn events_bloom_test_impl<Db>(provider: BlockchainProvider<Db>) -> Result<()>
+where
+    Db: BlockWriter + EventsBloomProvider,
+{
+    let (body, mut receipts, executions) = utils::generate_dummy_txs_and_receipts(1);
+    let Receipt::Invoke(receipt) = &mut receipts[0] else { unreachable!() };
+    receipt.events.push(Event {
+        from_address: address!("0x1"),
+        keys: vec![felt!("0xa")],
+        data: vec![],
+    });
+
+    let block = Block { header: Default::default(), body }.seal_with_hash(felt!("0x1337"));
+    let block = SealedBlockWithStatus { block, status: FinalityStatus::AcceptedOnL2 };
+    provider.insert_block_with_states_and_receipts(
+        block,
+        Default::default(),
+        receipts,
+        executions,
+    )?;
+
+    let bloom = provider.events_bloom(BlockHashOrNumber::Hash(felt!("0x1337")))?.unwrap();
+    assert!(bloom.contains_address(address!("0x1")));
+    assert!(bloom.contains_key(0, felt!("0xa")));
+    assert!(!bloom.contains_key(0, felt!("0xb")));
+
+    assert_eq!(provider.events_bloom(BlockHashOrNumber::Num(1))?, None);
+
+    Ok(())
+}
+
 fn insert_block_empty_test_impl<Db>(provider: BlockchainProvider<Db>, count: u64) -> Result<()>
 where
     Db: BlockProvider
@@ -235,10 +535,12 @@ where
             BlockWithTxHashes { header: expected_block.header.clone(), body: vec![] };
 
         let actual_block_with_tx_hashes = provider.block_with_tx_hashes(block_id)?;
+        let actual_tx_hashes = provider.transaction_hashes_by_block(block_id)?;
         let actual_block_env = provider.block_env_at(block_id)?;
 
         assert_eq!(actual_status, Some(FinalityStatus::AcceptedOnL2));
         assert_eq!(actual_block_with_tx_hashes, Some(expected_block_with_tx_hashes));
+        assert_eq!(actual_tx_hashes, Some(vec![]));
 
         let tx_hash = Felt::ZERO;
 
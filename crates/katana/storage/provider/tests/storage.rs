@@ -22,7 +22,10 @@ fn assert_state_provider_storage(
 }
 
 mod latest {
+    use std::collections::BTreeMap;
+
     use katana_provider::providers::db::DbProvider;
+    use katana_provider::traits::state::ContractStorageProvider;
 
     use super::*;
     use crate::fixtures::db_provider;
@@ -69,6 +72,26 @@ mod latest {
     ) -> Result<()> {
         assert_latest_storage_value(provider, expected_storage_entry)
     }
+
+    #[rstest::rstest]
+    fn read_storage_entries_from_db_provider(
+        #[from(provider_with_states)]
+        #[with(db_provider())]
+        provider: BlockchainProvider<DbProvider>,
+    ) -> Result<()> {
+        let entries = provider.storage_entries(ContractAddress::from(felt!("1")))?;
+        let expected = BTreeMap::from([
+            (felt!("1"), felt!("111")),
+            (felt!("2"), felt!("222")),
+            (felt!("3"), felt!("77")),
+        ]);
+        assert_eq!(entries, expected);
+
+        let entries = provider.storage_entries(ContractAddress::from(felt!("3")))?;
+        assert!(entries.is_empty());
+
+        Ok(())
+    }
 }
 
 mod historical {
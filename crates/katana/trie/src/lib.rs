@@ -1,8 +1,12 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
+use bitvec::order::Msb0;
+use bitvec::slice::BitSlice;
 use bitvec::vec::BitVec;
 pub use bonsai_trie as bonsai;
+use bonsai_trie::databases::HashMapDb;
 use bonsai_trie::id::BasicId;
-use bonsai_trie::{BonsaiDatabase, BonsaiPersistentDatabase};
+use bonsai_trie::{BonsaiDatabase, BonsaiPersistentDatabase, BonsaiStorage, BonsaiStorageConfig};
+pub use bonsai_trie::{Path, ProofNode};
 use katana_primitives::class::ClassHash;
 use katana_primitives::Felt;
 use starknet_types_core::hash::{Pedersen, StarkHash};
@@ -17,25 +21,124 @@ pub fn compute_merkle_root<H>(values: &[Felt]) -> Result<Felt>
 where
     H: StarkHash + Send + Sync,
 {
-    use bonsai_trie::id::BasicId;
-    use bonsai_trie::{databases, BonsaiStorage, BonsaiStorageConfig};
+    let bs = commitment_storage::<H>(values);
+    Ok(bs.root_hash(COMMITMENT_IDENTIFIER).unwrap())
+}
+
+/// Computes the root of the commitment tree of `values` along with the proof that the value at
+/// `index` is included in it.
+///
+/// The proof nodes are ordered from the root down to the leaf.
+pub fn compute_merkle_proof<H>(values: &[Felt], index: usize) -> Result<(Felt, Vec<ProofNode>)>
+where
+    H: StarkHash + Send + Sync,
+{
+    let mut bs = commitment_storage::<H>(values);
+    let root = bs.root_hash(COMMITMENT_IDENTIFIER).unwrap();
+
+    let key = BitVec::<u8, Msb0>::from_iter(index.to_be_bytes());
+    let mut nodes = bs.get_multi_proof(COMMITMENT_IDENTIFIER, [key.as_bitslice()]).unwrap().0;
+
+    // the nodes of the proof are keyed by their hash, they're ordered by following the path of
+    // the key from the root
+    let mut proof = Vec::with_capacity(nodes.len());
+    let mut hash = root;
+    let mut height = 0;
+
+    while height < key.len() {
+        let node = nodes.remove(&hash).with_context(|| format!("Missing proof node {hash:#x}"))?;
+        (hash, height) = match &node {
+            ProofNode::Binary { left, right } => {
+                (if key[height] { *right } else { *left }, height + 1)
+            }
+            ProofNode::Edge { child, path } => (*child, height + path.0.len()),
+        };
+        proof.push(node);
+    }
+
+    Ok((root, proof))
+}
+
+/// Verifies that `proof`, as returned by [`compute_merkle_proof`], proves that `value` is at
+/// `index` in the commitment tree of root `root`.
+pub fn verify_merkle_proof<H>(root: Felt, index: usize, value: Felt, proof: &[ProofNode]) -> bool
+where
+    H: StarkHash,
+{
+    let key = BitVec::<u8, Msb0>::from_iter(index.to_be_bytes());
+    let mut expected = root;
+    let mut height = 0;
+
+    for node in proof {
+        if node_hash::<H>(node) != expected {
+            return false;
+        }
+
+        match node {
+            ProofNode::Binary { left, right } => {
+                let Some(bit) = key.get(height) else { return false };
+                expected = if *bit { *right } else { *left };
+                height += 1;
+            }
+            ProofNode::Edge { child, path } => {
+                let end = height + path.0.len();
+                if key.get(height..end) != Some(path.0.as_bitslice()) {
+                    return false;
+                }
+                expected = *child;
+                height = end;
+            }
+        }
+    }
+
+    height == key.len() && expected == value
+}
 
-    // the value is irrelevant
-    const IDENTIFIER: &[u8] = b"1";
+/// Computes the hash of a node, as defined by the Starknet commitment trees.
+fn node_hash<H: StarkHash>(node: &ProofNode) -> Felt {
+    match node {
+        ProofNode::Binary { left, right } => H::hash(left, right),
+        ProofNode::Edge { child, path } => {
+            H::hash(child, &path_to_felt(&path.0)) + Felt::from(path.0.len())
+        }
+    }
+}
+
+fn path_to_felt(path: &BitSlice<u8, Msb0>) -> Felt {
+    path.iter().fold(
+        Felt::ZERO,
+        |acc, bit| if *bit { acc * Felt::TWO + Felt::ONE } else { acc * Felt::TWO },
+    )
+}
 
+/// Builds the path of an edge node from the felt representation of its `length` bits, the most
+/// significant bit first.
+pub fn path_from_felt(path: Felt, length: usize) -> Path {
+    let bits = path.to_bits_be();
+    Path(bits[bits.len().saturating_sub(length)..].iter().by_vals().collect())
+}
+
+// the value is irrelevant
+const COMMITMENT_IDENTIFIER: &[u8] = b"1";
+
+/// Builds an in-memory trie where each value is keyed by its index in `values`.
+fn commitment_storage<H>(values: &[Felt]) -> BonsaiStorage<BasicId, HashMapDb<BasicId>, H>
+where
+    H: StarkHash + Send + Sync,
+{
     let config = BonsaiStorageConfig::default();
-    let bonsai_db = databases::HashMapDb::<BasicId>::default();
+    let bonsai_db = HashMapDb::<BasicId>::default();
     let mut bs = BonsaiStorage::<_, _, H>::new(bonsai_db, config).unwrap();
 
     for (id, value) in values.iter().enumerate() {
-        let key = BitVec::from_iter(id.to_be_bytes());
-        bs.insert(IDENTIFIER, key.as_bitslice(), value).unwrap();
+        let key = BitVec::<u8, Msb0>::from_iter(id.to_be_bytes());
+        bs.insert(COMMITMENT_IDENTIFIER, key.as_bitslice(), value).unwrap();
     }
 
     let id = bonsai_trie::id::BasicIdBuilder::new().new_id();
     bs.commit(id).unwrap();
 
-    Ok(bs.root_hash(IDENTIFIER).unwrap())
+    bs
 }
 
 // H(H(H(class_hash, storage_root), nonce), 0), where H is the pedersen hash
@@ -73,6 +176,39 @@ mod tests {
         assert_eq!(expected_root_hash, computed_root_hash);
     }
 
+    #[test]
+    fn test_commitment_merkle_proof() {
+        let hashes = vec![Felt::from(1), Felt::from(2), Felt::from(3), Felt::from(4)];
+
+        let expected_root_hash = compute_merkle_root::<hash::Pedersen>(&hashes).unwrap();
+
+        for (index, value) in hashes.iter().enumerate() {
+            let (root_hash, proof) =
+                compute_merkle_proof::<hash::Pedersen>(&hashes, index).unwrap();
+
+            assert_eq!(expected_root_hash, root_hash);
+            assert!(verify_merkle_proof::<hash::Pedersen>(root_hash, index, *value, &proof));
+
+            // the proof doesn't hold for another value, or at another index
+            assert!(!verify_merkle_proof::<hash::Pedersen>(root_hash, index, felt!("0x5"), &proof));
+            let other = (index + 1) % hashes.len();
+            assert!(!verify_merkle_proof::<hash::Pedersen>(root_hash, other, *value, &proof));
+
+            // the nodes must be ordered from the root down to the leaf
+            let mut reversed = proof.clone();
+            reversed.reverse();
+            assert!(!verify_merkle_proof::<hash::Pedersen>(root_hash, index, *value, &reversed));
+
+            // edge paths survive their conversion to a felt and back
+            for node in &proof {
+                if let ProofNode::Edge { path, .. } = node {
+                    let felt = path_to_felt(&path.0);
+                    assert_eq!(path_from_felt(felt, path.0.len()).0, path.0);
+                }
+            }
+        }
+    }
+
     // Taken from Pathfinder: https://github.com/eqlabs/pathfinder/blob/29f93d0d6ad8758fdcf5ae3a8bd2faad2a3bc92b/crates/merkle-tree/src/contract_state.rs#L236C5-L252C6
     #[test]
     fn test_compute_contract_state_hash() {
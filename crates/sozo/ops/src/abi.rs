@@ -0,0 +1,299 @@
+//! Event schemas of a world, for external indexers.
+//!
+//! The world emits every event of the application: the registration and upgrade of the
+//! resources, the records written into the models and the events emitted by the systems. An
+//! indexer needs the selectors and the layout of the world events to decode them, and the
+//! schemas of the models and events to decode the records and the events they carry.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{bail, Result};
+use dojo_world::local::{Member, ResourceLocal, WorldLocal};
+use serde::Serialize;
+use starknet::core::types::contract::{
+    AbiEntry, AbiEvent, AbiEventEnum, AbiEventStruct, EventFieldKind, TypedAbiEvent,
+};
+use starknet::core::types::Felt;
+use starknet::core::utils::get_selector_from_name;
+
+/// Maximum nesting of the event enums, which guards against recursive events.
+const MAX_DEPTH: usize = 16;
+
+/// The events of a world and the schemas of its resources.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WorldEvents {
+    /// The address of the world, if it can be computed from the profile.
+    pub world_address: Option<Felt>,
+    /// The events emitted by the world contract, ordered by name.
+    pub world_events: Vec<WorldEvent>,
+    /// The schemas of the models, ordered by tag.
+    pub models: Vec<ResourceSchema>,
+    /// The schemas of the events, ordered by tag.
+    pub events: Vec<ResourceSchema>,
+}
+
+/// An event emitted by the world contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WorldEvent {
+    pub name: String,
+    /// The selectors emitted as the first keys of the event. There's a single one, unless the
+    /// event is nested into the event of a component.
+    pub selectors: Vec<Felt>,
+    /// The members serialized into the keys of the event, after the selectors.
+    pub keys: Vec<EventMember>,
+    /// The members serialized into the data of the event.
+    pub data: Vec<EventMember>,
+}
+
+/// A member of a world event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EventMember {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+/// The schema of a model or an event of the world.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ResourceSchema {
+    pub tag: String,
+    /// The selector emitted by the world along with the records of the resource.
+    pub selector: Felt,
+    pub class_hash: Felt,
+    pub members: Vec<SchemaMember>,
+}
+
+/// A member of a model or an event.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SchemaMember {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+    pub key: bool,
+}
+
+/// Builds the events of a local world, and the schemas of its models and events.
+pub fn world_events(world: &WorldLocal) -> Result<WorldEvents> {
+    let mut models = vec![];
+    let mut events = vec![];
+
+    for resource in world.resources.values() {
+        match resource {
+            ResourceLocal::Model(m) => models.push(resource_schema(resource, &m.members)),
+            ResourceLocal::Event(e) => events.push(resource_schema(resource, &e.members)),
+            _ => {}
+        }
+    }
+
+    models.sort_by(|a, b| a.tag.cmp(&b.tag));
+    events.sort_by(|a, b| a.tag.cmp(&b.tag));
+
+    Ok(WorldEvents {
+        world_address: world.deterministic_world_address().ok(),
+        world_events: contract_events(&world.class.abi)?,
+        models,
+        events,
+    })
+}
+
+fn resource_schema(resource: &ResourceLocal, members: &[Member]) -> ResourceSchema {
+    ResourceSchema {
+        tag: resource.tag(),
+        selector: resource.dojo_selector(),
+        class_hash: resource.class_hash(),
+        members: members
+            .iter()
+            .map(|m| SchemaMember { name: m.name.clone(), ty: m.ty.clone(), key: m.key })
+            .collect(),
+    }
+}
+
+/// Returns the events emitted by a contract, ordered by name.
+///
+/// The events of a contract are the variants of its root event enum, which is the only event
+/// enum not referenced by another event. A nested variant adds the selector of its name to the
+/// keys, whereas the variants of a flattened enum are emitted as if they were declared in the
+/// enum containing it.
+pub fn contract_events(abi: &[AbiEntry]) -> Result<Vec<WorldEvent>> {
+    let mut structs = HashMap::new();
+    let mut enums = HashMap::new();
+
+    for entry in abi {
+        match entry {
+            AbiEntry::Event(AbiEvent::Typed(TypedAbiEvent::Struct(s))) => {
+                structs.insert(s.name.as_str(), s);
+            }
+            AbiEntry::Event(AbiEvent::Typed(TypedAbiEvent::Enum(e))) => {
+                enums.insert(e.name.as_str(), e);
+            }
+            _ => {}
+        }
+    }
+
+    let referenced = enums
+        .values()
+        .flat_map(|e| e.variants.iter().map(|v| v.r#type.as_str()))
+        .collect::<HashSet<_>>();
+    let roots =
+        enums.values().filter(|e| !referenced.contains(e.name.as_str())).collect::<Vec<_>>();
+
+    let root = match roots.as_slice() {
+        [] => return Ok(vec![]),
+        [root] => root,
+        _ => bail!("The ABI declares {} root event enums, expected one.", roots.len()),
+    };
+
+    let events = EventTypes { structs, enums: enums.clone() };
+    let mut out = vec![];
+    events.enum_events(root, &[], &mut out, 0)?;
+
+    out.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(out)
+}
+
+/// The events declared in an ABI.
+struct EventTypes<'a> {
+    structs: HashMap<&'a str, &'a AbiEventStruct>,
+    enums: HashMap<&'a str, &'a AbiEventEnum>,
+}
+
+impl EventTypes<'_> {
+    /// Appends the events of the variants of an event enum to `out`.
+    fn enum_events(
+        &self,
+        e: &AbiEventEnum,
+        selectors: &[Felt],
+        out: &mut Vec<WorldEvent>,
+        depth: usize,
+    ) -> Result<()> {
+        if depth > MAX_DEPTH {
+            bail!("Event `{}` is nested too deeply.", e.name);
+        }
+
+        for variant in &e.variants {
+            let mut selectors = selectors.to_vec();
+            match variant.kind {
+                EventFieldKind::Nested => selectors.push(get_selector_from_name(&variant.name)?),
+                EventFieldKind::Flat => {}
+                _ => bail!(
+                    "Variant `{}` of event `{}` is neither nested nor flat.",
+                    variant.name,
+                    e.name
+                ),
+            }
+
+            if let Some(s) = self.structs.get(variant.r#type.as_str()) {
+                out.push(struct_event(&variant.name, s, selectors));
+            } else if let Some(inner) = self.enums.get(variant.r#type.as_str()) {
+                self.enum_events(inner, &selectors, out, depth + 1)?;
+            } else {
+                bail!("Event `{}` is not declared in the ABI.", variant.r#type);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn struct_event(name: &str, s: &AbiEventStruct, selectors: Vec<Felt>) -> WorldEvent {
+    let mut keys = vec![];
+    let mut data = vec![];
+
+    for member in &s.members {
+        let m = EventMember { name: member.name.clone(), ty: member.r#type.clone() };
+        match member.kind {
+            EventFieldKind::Key => keys.push(m),
+            _ => data.push(m),
+        }
+    }
+
+    WorldEvent { name: name.to_string(), selectors, keys, data }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::core::types::contract::EventField;
+
+    use super::*;
+
+    fn field(name: &str, ty: &str, kind: EventFieldKind) -> EventField {
+        EventField { name: name.to_string(), r#type: ty.to_string(), kind }
+    }
+
+    fn event_struct(name: &str, members: Vec<EventField>) -> AbiEntry {
+        AbiEntry::Event(AbiEvent::Typed(TypedAbiEvent::Struct(AbiEventStruct {
+            name: name.to_string(),
+            members,
+        })))
+    }
+
+    fn event_enum(name: &str, variants: Vec<EventField>) -> AbiEntry {
+        AbiEntry::Event(AbiEvent::Typed(TypedAbiEvent::Enum(AbiEventEnum {
+            name: name.to_string(),
+            variants,
+        })))
+    }
+
+    fn abi() -> Vec<AbiEntry> {
+        vec![
+            event_struct(
+                "world::StoreSetRecord",
+                vec![
+                    field("selector", "core::felt252", EventFieldKind::Key),
+                    field("entity_id", "core::felt252", EventFieldKind::Key),
+                    field("values", "core::array::Span::<core::felt252>", EventFieldKind::Data),
+                ],
+            ),
+            event_struct(
+                "upgradeable::Upgraded",
+                vec![field("class_hash", "core::starknet::ClassHash", EventFieldKind::Data)],
+            ),
+            event_enum(
+                "upgradeable::Event",
+                vec![field("Upgraded", "upgradeable::Upgraded", EventFieldKind::Nested)],
+            ),
+            event_struct("ownable::OwnerChanged", vec![]),
+            event_enum(
+                "ownable::Event",
+                vec![field("OwnerChanged", "ownable::OwnerChanged", EventFieldKind::Nested)],
+            ),
+            event_enum(
+                "world::Event",
+                vec![
+                    field("StoreSetRecord", "world::StoreSetRecord", EventFieldKind::Nested),
+                    field("UpgradeableEvent", "upgradeable::Event", EventFieldKind::Nested),
+                    field("OwnableEvent", "ownable::Event", EventFieldKind::Flat),
+                ],
+            ),
+        ]
+    }
+
+    #[test]
+    fn contract_events_selectors() {
+        let events = contract_events(&abi()).unwrap();
+        let selector = |name| get_selector_from_name(name).unwrap();
+
+        assert_eq!(
+            events.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+            vec!["OwnerChanged", "StoreSetRecord", "Upgraded"]
+        );
+
+        assert_eq!(events[0].selectors, vec![selector("OwnerChanged")]);
+        assert_eq!(events[1].selectors, vec![selector("StoreSetRecord")]);
+        assert_eq!(events[2].selectors, vec![selector("UpgradeableEvent"), selector("Upgraded")]);
+
+        let names =
+            |members: &[EventMember]| members.iter().map(|m| m.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(&events[1].keys), vec!["selector", "entity_id"]);
+        assert_eq!(names(&events[1].data), vec!["values"]);
+    }
+
+    #[test]
+    fn contract_events_without_root() {
+        assert!(contract_events(&[]).unwrap().is_empty());
+
+        let mut abi = abi();
+        abi.push(event_enum("other::Event", vec![]));
+        assert!(contract_events(&abi).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+//! Size breakdown of the classes exceeding the network limits.
+//!
+//! A class can't be declared if its Sierra file or its Casm bytecode is larger than the limits of
+//! the network. The Sierra program of a class is a list of functions laid out one after the
+//! other, the size of each function being the number of statements up to the next one. The
+//! statements are a good proxy of the Casm bytecode each function compiles to, hence the largest
+//! functions, and the crates they come from, are the ones worth moving into another contract.
+
+use std::collections::HashMap;
+
+use anyhow::{anyhow, Result};
+use cairo_lang_sierra::program::Program;
+use cairo_lang_starknet_classes::felt252_serde::sierra_from_felt252s;
+use cairo_lang_utils::bigint::BigUintAsHex;
+use serde::Serialize;
+use starknet::core::types::contract::SierraClass;
+
+/// Maximum size of the Sierra file of a class on the public networks.
+///
+/// Refer to <https://docs.starknet.io/tools/limits-and-triggers/>.
+pub const MAX_SIERRA_SIZE_BYTES: usize = 4_089_446;
+/// Maximum size of the Casm bytecode of a class on the public networks.
+pub const MAX_CASM_FELTS: usize = 81_290;
+
+/// Number of functions reported, from the largest one.
+const TOP_FUNCTIONS: usize = 20;
+
+/// Whether a class of the given sizes can't be declared on the public networks.
+pub fn exceeds_limits(sierra_file_size: usize, casm_felts: usize) -> bool {
+    sierra_file_size > MAX_SIERRA_SIZE_BYTES || casm_felts > MAX_CASM_FELTS
+}
+
+/// The size breakdown of a class.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ClassSize {
+    pub tag: String,
+    pub sierra_file_size: usize,
+    pub casm_felts: usize,
+    /// The total number of statements of the Sierra program.
+    pub statements: usize,
+    /// The largest functions, from the largest one.
+    pub functions: Vec<FunctionSize>,
+    /// The statements of the functions grouped by crate, from the largest one.
+    pub dependencies: Vec<DependencySize>,
+}
+
+/// The size of a function of a Sierra program.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FunctionSize {
+    pub name: String,
+    pub statements: usize,
+}
+
+/// The size of the functions of a crate in a Sierra program.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DependencySize {
+    pub name: String,
+    pub statements: usize,
+    pub functions: usize,
+}
+
+impl ClassSize {
+    /// Returns the share of the program taken by the given number of statements, in percent.
+    pub fn share(&self, statements: usize) -> f64 {
+        if self.statements == 0 {
+            return 0.0;
+        }
+
+        statements as f64 * 100.0 / self.statements as f64
+    }
+}
+
+/// Computes the size breakdown of a class from its Sierra program.
+///
+/// The function names are only known if the class was compiled with its debug info, the
+/// functions being reported by id otherwise.
+pub fn class_size(
+    tag: &str,
+    class: &SierraClass,
+    sierra_file_size: usize,
+    casm_felts: usize,
+) -> Result<ClassSize> {
+    let felts = class
+        .sierra_program
+        .iter()
+        .map(|f| BigUintAsHex { value: f.to_biguint() })
+        .collect::<Vec<_>>();
+    let (_, _, program) = sierra_from_felt252s(&felts)
+        .map_err(|e| anyhow!("Failed to decode the Sierra program of {tag}: {e}"))?;
+
+    let names = class
+        .sierra_program_debug_info
+        .user_func_names
+        .iter()
+        .map(|(id, name)| (*id, name.clone()))
+        .collect::<HashMap<_, _>>();
+
+    Ok(size_breakdown(tag, function_sizes(&program, &names), sierra_file_size, casm_felts))
+}
+
+/// Returns the name and the number of statements of each function of a program.
+fn function_sizes(program: &Program, names: &HashMap<u64, String>) -> Vec<(String, usize)> {
+    let mut funcs = program.funcs.iter().collect::<Vec<_>>();
+    funcs.sort_by_key(|f| f.entry_point.0);
+
+    let ends = funcs.iter().skip(1).map(|f| f.entry_point.0).chain([program.statements.len()]);
+
+    funcs
+        .iter()
+        .zip(ends)
+        .map(|(f, end)| {
+            let name = names
+                .get(&f.id.id)
+                .cloned()
+                .or_else(|| f.id.debug_name.as_ref().map(|n| n.to_string()))
+                .unwrap_or_else(|| format!("function #{}", f.id.id));
+            (name, end.saturating_sub(f.entry_point.0))
+        })
+        .collect()
+}
+
+/// Builds the size breakdown of a class from the sizes of its functions.
+fn size_breakdown(
+    tag: &str,
+    sizes: Vec<(String, usize)>,
+    sierra_file_size: usize,
+    casm_felts: usize,
+) -> ClassSize {
+    let statements = sizes.iter().map(|(_, s)| s).sum();
+
+    let mut dependencies = HashMap::<String, DependencySize>::new();
+    for (name, size) in &sizes {
+        let krate = name.split("::").next().unwrap_or(name).to_string();
+        let dep = dependencies.entry(krate.clone()).or_insert(DependencySize {
+            name: krate,
+            statements: 0,
+            functions: 0,
+        });
+        dep.statements += size;
+        dep.functions += 1;
+    }
+
+    let mut dependencies = dependencies.into_values().collect::<Vec<_>>();
+    dependencies.sort_by(|a, b| b.statements.cmp(&a.statements).then(a.name.cmp(&b.name)));
+
+    let mut functions = sizes
+        .into_iter()
+        .map(|(name, statements)| FunctionSize { name, statements })
+        .collect::<Vec<_>>();
+    functions.sort_by(|a, b| b.statements.cmp(&a.statements).then(a.name.cmp(&b.name)));
+    functions.truncate(TOP_FUNCTIONS);
+
+    ClassSize {
+        tag: tag.to_string(),
+        sierra_file_size,
+        casm_felts,
+        statements,
+        functions,
+        dependencies,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn limits() {
+        assert!(!exceeds_limits(MAX_SIERRA_SIZE_BYTES, MAX_CASM_FELTS));
+        assert!(exceeds_limits(MAX_SIERRA_SIZE_BYTES + 1, 0));
+        assert!(exceeds_limits(0, MAX_CASM_FELTS + 1));
+    }
+
+    #[test]
+    fn breakdown_by_function_and_crate() {
+        let sizes = vec![
+            ("ns::actions::spawn".to_string(), 300),
+            ("core::array::ArrayImpl::append".to_string(), 50),
+            ("ns::actions::move".to_string(), 500),
+            ("dojo::world::IWorldDispatcherImpl::entity".to_string(), 150),
+        ];
+
+        let size = size_breakdown("ns-actions", sizes, 10, 20);
+
+        assert_eq!(size.statements, 1000);
+        assert_eq!(
+            size.functions.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(),
+            vec![
+                "ns::actions::move",
+                "ns::actions::spawn",
+                "dojo::world::IWorldDispatcherImpl::entity",
+                "core::array::ArrayImpl::append"
+            ]
+        );
+        assert_eq!(
+            size.dependencies,
+            vec![
+                DependencySize { name: "ns".to_string(), statements: 800, functions: 2 },
+                DependencySize { name: "dojo".to_string(), statements: 150, functions: 1 },
+                DependencySize { name: "core".to_string(), statements: 50, functions: 1 },
+            ]
+        );
+        assert_eq!(size.share(250), 25.0);
+    }
+
+    #[test]
+    fn breakdown_keeps_the_largest_functions() {
+        let sizes = (0..TOP_FUNCTIONS + 5).map(|i| (format!("ns::f{i}"), i)).collect();
+        let size = size_breakdown("ns-actions", sizes, 0, 0);
+
+        assert_eq!(size.functions.len(), TOP_FUNCTIONS);
+        assert_eq!(size.functions[0].statements, TOP_FUNCTIONS + 4);
+    }
+}
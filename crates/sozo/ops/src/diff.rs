@@ -0,0 +1,545 @@
+//! Classification of the changes between the local world and the deployed world.
+//!
+//! The world only accepts the upgrade of a model or an event which keeps the members of the
+//! deployed version, in the same order and with the same types, new members being appended. The
+//! same goes for the members of the structs and the variants of the enums they're made of. The
+//! upgrade of a contract is accepted whatever its class, but it breaks the callers of the
+//! entrypoints it removes or whose signature it changes.
+
+use std::collections::HashSet;
+
+use anyhow::{bail, Result};
+use dojo_world::diff::{Manifest, ResourceDiff, WorldDiff};
+use dojo_world::local::{Member, ResourceLocal, WorldLocal};
+use starknet::core::types::contract::{AbiEntry, AbiFunction, AbiNamedMember};
+use starknet::core::types::{BlockId, BlockTag, ContractClass, Felt};
+use starknet::providers::Provider;
+
+use crate::fuzz::{array_inner_type, tuple_inner_types};
+use crate::upgrade_check::{self, UpgradeCheck, WorldCompatibility};
+
+/// How the deployed world is affected by a change, from the least to the most disruptive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Compatibility {
+    /// Nothing changes.
+    Unchanged,
+    /// The change can be migrated without breaking the deployed world.
+    Upgradable,
+    /// The change can't be migrated, or breaks the callers of the deployed world.
+    Breaking,
+}
+
+impl From<WorldCompatibility> for Compatibility {
+    fn from(value: WorldCompatibility) -> Self {
+        match value {
+            WorldCompatibility::Synced => Self::Unchanged,
+            WorldCompatibility::NotDeployed | WorldCompatibility::Upgradable => Self::Upgradable,
+            WorldCompatibility::Breaking => Self::Breaking,
+        }
+    }
+}
+
+/// The change of a resource, with the reasons of its compatibility.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceChange {
+    pub tag: String,
+    pub compatibility: Compatibility,
+    pub reasons: Vec<String>,
+}
+
+/// The changes between the local world and the deployed world.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DiffReport {
+    pub world: UpgradeCheck,
+    /// The changes of the contracts, models and events, ordered by tag.
+    pub resources: Vec<ResourceChange>,
+}
+
+impl DiffReport {
+    /// Returns the compatibility of the most disruptive change.
+    pub fn compatibility(&self) -> Compatibility {
+        self.resources
+            .iter()
+            .map(|r| r.compatibility)
+            .fold(self.world.compatibility().into(), Compatibility::max)
+    }
+}
+
+/// Compares the local world with the world deployed on chain.
+///
+/// The classes of the updated resources are fetched to compare their ABI with the local one.
+pub async fn diff_against_chain<P>(diff: &WorldDiff, provider: &P) -> Result<DiffReport>
+where
+    P: Provider,
+{
+    let world = upgrade_check::check_world_upgrade(diff, provider).await?;
+    let mut resources = vec![];
+
+    for resource in diff.resources.values() {
+        let change = match resource {
+            ResourceDiff::Created(ResourceLocal::Namespace(_))
+            | ResourceDiff::Updated(ResourceLocal::Namespace(_), _)
+            | ResourceDiff::Synced(ResourceLocal::Namespace(_), _) => continue,
+            ResourceDiff::Created(local) => created(local.tag()),
+            ResourceDiff::Synced(local, _) => unchanged(local.tag()),
+            ResourceDiff::Updated(local, remote) => {
+                let abi = class_abi(provider, remote.current_class_hash()).await?;
+                compare_resource(local, &abi)
+            }
+        };
+
+        resources.push(change);
+    }
+
+    resources.sort_by(|a, b| a.tag.cmp(&b.tag));
+    Ok(DiffReport { world, resources })
+}
+
+/// Compares the local world with the world recorded in the manifest of the last migration.
+pub fn diff_against_manifest(local: &WorldLocal, manifest: &Manifest) -> DiffReport {
+    let world = UpgradeCheck::new(
+        local.class_hash,
+        Some(manifest.world.class_hash),
+        &local.entrypoints,
+        &manifest.world.entrypoints,
+    );
+
+    let mut resources = vec![];
+
+    for resource in local.resources.values() {
+        let tag = resource.tag();
+        if local.profile_config.is_ignored(&resource.namespace(), &tag) {
+            continue;
+        }
+
+        let change = match resource {
+            ResourceLocal::Namespace(_) => continue,
+            ResourceLocal::Contract(c) => match manifest.contracts.iter().find(|d| d.tag == tag) {
+                None => created(tag),
+                Some(d) if d.class_hash == c.common.class_hash => unchanged(tag),
+                Some(d) => compare_entrypoints(tag, &c.common.class.abi, &d.abi),
+            },
+            ResourceLocal::Model(m) => {
+                let deployed = manifest.models.iter().find(|d| d.tag == tag);
+                let deployed = deployed.map(|d| (d.class_hash, d.members.as_slice()));
+                compare_manifest_members(tag, m.common.class_hash, &m.members, deployed)
+            }
+            ResourceLocal::Event(e) => {
+                let deployed = manifest.events.iter().find(|d| d.tag == tag);
+                let deployed = deployed.map(|d| (d.class_hash, d.members.as_slice()));
+                compare_manifest_members(tag, e.common.class_hash, &e.members, deployed)
+            }
+        };
+
+        resources.push(change);
+    }
+
+    resources.sort_by(|a, b| a.tag.cmp(&b.tag));
+    DiffReport { world, resources }
+}
+
+/// Compares a local resource with the ABI of the class of its deployed version.
+fn compare_resource(local: &ResourceLocal, deployed_abi: &[AbiEntry]) -> ResourceChange {
+    let tag = local.tag();
+
+    match local {
+        ResourceLocal::Contract(c) => compare_entrypoints(tag, &c.common.class.abi, deployed_abi),
+        ResourceLocal::Model(m) => {
+            let deployed = deployed_members(&m.common.name, &m.members, deployed_abi);
+            let abis = Abis { local: &m.common.class.abi, deployed: deployed_abi };
+            compare_members(tag, &m.members, &deployed, Some(abis))
+        }
+        ResourceLocal::Event(e) => {
+            let deployed = deployed_members(&e.common.name, &e.members, deployed_abi);
+            let abis = Abis { local: &e.common.class.abi, deployed: deployed_abi };
+            compare_members(tag, &e.members, &deployed, Some(abis))
+        }
+        ResourceLocal::Namespace(_) => unchanged(tag),
+    }
+}
+
+/// Returns the members of the deployed version of a model or an event, its keys being the ones of
+/// the local version if the ABI doesn't tell them.
+fn deployed_members(name: &str, local: &[Member], deployed_abi: &[AbiEntry]) -> Vec<Member> {
+    let keys = local.iter().filter(|m| m.key).map(|m| m.name.as_str()).collect::<Vec<_>>();
+    Member::from_abi_with_keys(name, deployed_abi, &keys)
+}
+
+fn compare_manifest_members(
+    tag: String,
+    local_class_hash: Felt,
+    local: &[Member],
+    deployed: Option<(Felt, &[dojo_world::diff::Member])>,
+) -> ResourceChange {
+    match deployed {
+        None => created(tag),
+        Some((class_hash, _)) if class_hash == local_class_hash => unchanged(tag),
+        // manifests written before the members were recorded have none
+        Some((_, members)) if members.is_empty() => ResourceChange {
+            tag,
+            compatibility: Compatibility::Upgradable,
+            reasons: vec![
+                "the members of the deployed version aren't recorded in the manifest, compare \
+                 against the chain to check them"
+                    .to_string(),
+            ],
+        },
+        Some((_, members)) => {
+            let deployed = members
+                .iter()
+                .map(|m| Member { name: m.name.clone(), ty: m.ty.clone(), key: m.key })
+                .collect::<Vec<_>>();
+            compare_members(tag, local, &deployed, None)
+        }
+    }
+}
+
+/// Compares the members of the local version of a model or an event with the deployed ones.
+///
+/// Without the ABIs of the classes, the members are compared by the name of their type only.
+pub fn compare_members(
+    tag: String,
+    local: &[Member],
+    deployed: &[Member],
+    abis: Option<Abis<'_>>,
+) -> ResourceChange {
+    let mut breaking = vec![];
+    let mut compared = HashSet::new();
+
+    for (i, old) in deployed.iter().enumerate() {
+        let reason = match local.iter().position(|m| m.name == old.name) {
+            None => format!("member `{}` is removed", old.name),
+            Some(j) if j != i => format!("member `{}` is moved", old.name),
+            Some(j) if local[j].ty != old.ty => {
+                format!("member `{}` changes from `{}` to `{}`", old.name, old.ty, local[j].ty)
+            }
+            Some(j) if local[j].key != old.key => {
+                format!("member `{}` changes from key to value or the opposite", old.name)
+            }
+            Some(_) => match abis.and_then(|abis| abis.type_change(&old.ty, &mut compared)) {
+                Some(change) => format!("member `{}` changes: {change}", old.name),
+                None => continue,
+            },
+        };
+
+        breaking.push(reason);
+    }
+
+    if !breaking.is_empty() {
+        return ResourceChange { tag, compatibility: Compatibility::Breaking, reasons: breaking };
+    }
+
+    let mut reasons = local[deployed.len()..]
+        .iter()
+        .map(|m| format!("member `{}` is added", m.name))
+        .collect::<Vec<_>>();
+    if reasons.is_empty() {
+        reasons.push("the class changes, the members are the same".to_string());
+    }
+
+    ResourceChange { tag, compatibility: Compatibility::Upgradable, reasons }
+}
+
+/// The ABIs of the local and deployed classes of a model or an event, declaring the structs and
+/// enums its members are made of.
+#[derive(Debug, Clone, Copy)]
+pub struct Abis<'a> {
+    pub local: &'a [AbiEntry],
+    pub deployed: &'a [AbiEntry],
+}
+
+/// The definition of a struct or an enum in an ABI.
+enum TypeDef<'a> {
+    Struct(&'a [AbiNamedMember]),
+    Enum(&'a [AbiNamedMember]),
+}
+
+impl Abis<'_> {
+    /// Returns how the definition of the type `ty`, or of the types it's made of, changes between
+    /// the deployed and local classes in a way the world doesn't accept.
+    ///
+    /// The types already in `compared` are skipped.
+    fn type_change(&self, ty: &str, compared: &mut HashSet<String>) -> Option<String> {
+        if !compared.insert(ty.to_string()) {
+            return None;
+        }
+
+        if let Some(inner) = array_inner_type(ty) {
+            return self.type_change(inner, compared);
+        }
+        if let Some(inners) = tuple_inner_types(ty) {
+            return inners.into_iter().find_map(|inner| self.type_change(inner, compared));
+        }
+
+        match (type_def(self.deployed, ty), type_def(self.local, ty)) {
+            (None, None) => None,
+            (Some(TypeDef::Struct(old)), Some(TypeDef::Struct(new))) => {
+                self.fields_change(ty, "member", old, new, compared)
+            }
+            (Some(TypeDef::Enum(old)), Some(TypeDef::Enum(new))) => {
+                self.fields_change(ty, "variant", old, new, compared)
+            }
+            _ => Some(format!("`{ty}` changes from a struct to an enum or the opposite")),
+        }
+    }
+
+    /// Compares the members of a struct or the variants of an enum, new ones being appended.
+    fn fields_change(
+        &self,
+        ty: &str,
+        kind: &str,
+        old: &[AbiNamedMember],
+        new: &[AbiNamedMember],
+        compared: &mut HashSet<String>,
+    ) -> Option<String> {
+        old.iter().enumerate().find_map(|(i, old)| match new.get(i) {
+            Some(new) if new.name != old.name => {
+                Some(format!("{kind} `{}` of `{ty}` is removed or moved", old.name))
+            }
+            None => Some(format!("{kind} `{}` of `{ty}` is removed", old.name)),
+            Some(new) if new.r#type != old.r#type => Some(format!(
+                "{kind} `{}` of `{ty}` changes from `{}` to `{}`",
+                old.name, old.r#type, new.r#type
+            )),
+            Some(_) => self.type_change(&old.r#type, compared),
+        })
+    }
+}
+
+fn type_def<'a>(abi: &'a [AbiEntry], ty: &str) -> Option<TypeDef<'a>> {
+    abi.iter().find_map(|entry| match entry {
+        AbiEntry::Struct(s) if s.name == ty => Some(TypeDef::Struct(&s.members)),
+        AbiEntry::Enum(e) if e.name == ty => Some(TypeDef::Enum(&e.variants)),
+        _ => None,
+    })
+}
+
+/// Compares the entrypoints of the local class of a contract with the deployed ones.
+pub fn compare_entrypoints(
+    tag: String,
+    local_abi: &[AbiEntry],
+    deployed_abi: &[AbiEntry],
+) -> ResourceChange {
+    let local = functions(local_abi);
+    let deployed = functions(deployed_abi);
+
+    let mut breaking = vec![];
+    for old in &deployed {
+        match local.iter().find(|f| f.name == old.name) {
+            None => breaking.push(format!("entrypoint `{}` is removed", old.name)),
+            Some(new) if signature(new) != signature(old) => breaking.push(format!(
+                "entrypoint `{}` changes from `{}` to `{}`",
+                old.name,
+                signature(old),
+                signature(new)
+            )),
+            Some(_) => {}
+        }
+    }
+
+    if !breaking.is_empty() {
+        return ResourceChange { tag, compatibility: Compatibility::Breaking, reasons: breaking };
+    }
+
+    let mut reasons = local
+        .iter()
+        .filter(|f| !deployed.iter().any(|old| old.name == f.name))
+        .map(|f| format!("entrypoint `{}` is added", f.name))
+        .collect::<Vec<_>>();
+    if reasons.is_empty() {
+        reasons.push("the class changes, the entrypoints are the same".to_string());
+    }
+
+    ResourceChange { tag, compatibility: Compatibility::Upgradable, reasons }
+}
+
+fn created(tag: String) -> ResourceChange {
+    ResourceChange {
+        tag,
+        compatibility: Compatibility::Upgradable,
+        reasons: vec!["the resource is new".to_string()],
+    }
+}
+
+fn unchanged(tag: String) -> ResourceChange {
+    ResourceChange { tag, compatibility: Compatibility::Unchanged, reasons: vec![] }
+}
+
+async fn class_abi<P>(provider: &P, class_hash: Felt) -> Result<Vec<AbiEntry>>
+where
+    P: Provider,
+{
+    match provider.get_class(BlockId::Tag(BlockTag::Pending), class_hash).await? {
+        ContractClass::Sierra(class) => Ok(serde_json::from_str(&class.abi)?),
+        ContractClass::Legacy(_) => bail!("The class {class_hash:#x} is not a Cairo 1 class."),
+    }
+}
+
+/// Returns the functions of an ABI, including the ones of its interfaces.
+fn functions(abi: &[AbiEntry]) -> Vec<&AbiFunction> {
+    abi.iter()
+        .flat_map(|entry| match entry {
+            AbiEntry::Function(f) => vec![f],
+            AbiEntry::Interface(i) => functions(&i.items),
+            _ => vec![],
+        })
+        .collect()
+}
+
+/// Returns the types of the inputs and outputs of a function, which are what its callers rely on.
+fn signature(f: &AbiFunction) -> String {
+    let inputs = f.inputs.iter().map(|i| i.r#type.as_str()).collect::<Vec<_>>();
+    let outputs = f.outputs.iter().map(|o| o.r#type.as_str()).collect::<Vec<_>>();
+    format!("({}) -> ({})", inputs.join(", "), outputs.join(", "))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn member(name: &str, ty: &str, key: bool) -> Member {
+        Member { name: name.to_string(), ty: ty.to_string(), key }
+    }
+
+    fn abi(functions: &[(&str, &[&str])]) -> Vec<AbiEntry> {
+        let functions = functions
+            .iter()
+            .map(|(name, inputs)| {
+                let inputs = inputs
+                    .iter()
+                    .enumerate()
+                    .map(|(i, ty)| serde_json::json!({ "name": format!("arg{i}"), "type": ty }))
+                    .collect::<Vec<_>>();
+                serde_json::json!({
+                    "type": "function",
+                    "name": name,
+                    "inputs": inputs,
+                    "outputs": [],
+                    "state_mutability": "external"
+                })
+            })
+            .collect::<Vec<_>>();
+
+        serde_json::from_value(serde_json::json!([{
+            "type": "interface",
+            "name": "ns::actions::IActions",
+            "items": functions
+        }]))
+        .unwrap()
+    }
+
+    #[test]
+    fn members_compatibility() {
+        let deployed = [member("player", "felt252", true), member("x", "u32", false)];
+
+        let local = [member("player", "felt252", true), member("x", "u32", false)];
+        let change = compare_members("ns-M".to_string(), &local, &deployed, None);
+        assert_eq!(change.compatibility, Compatibility::Upgradable);
+
+        let local = [
+            member("player", "felt252", true),
+            member("x", "u32", false),
+            member("y", "u32", false),
+        ];
+        let change = compare_members("ns-M".to_string(), &local, &deployed, None);
+        assert_eq!(change.compatibility, Compatibility::Upgradable);
+        assert_eq!(change.reasons, vec!["member `y` is added"]);
+
+        let local = [member("player", "felt252", true), member("x", "u64", false)];
+        let change = compare_members("ns-M".to_string(), &local, &deployed, None);
+        assert_eq!(change.compatibility, Compatibility::Breaking);
+        assert_eq!(change.reasons, vec!["member `x` changes from `u32` to `u64`"]);
+
+        let local = [member("player", "felt252", true), member("y", "u32", false)];
+        let change = compare_members("ns-M".to_string(), &local, &deployed, None);
+        assert_eq!(change.compatibility, Compatibility::Breaking);
+        assert_eq!(change.reasons, vec!["member `x` is removed"]);
+    }
+
+    #[test]
+    fn nested_types_compatibility() {
+        let vec2 = |members: &[&str]| {
+            let members = members
+                .iter()
+                .map(|m| serde_json::json!({ "name": m, "type": "core::integer::u32" }))
+                .collect::<Vec<_>>();
+            serde_json::json!({ "type": "struct", "name": "ns::models::Vec2", "members": members })
+        };
+        let direction = |variants: &[&str]| {
+            let variants = variants
+                .iter()
+                .map(|v| serde_json::json!({ "name": v, "type": "ns::models::Vec2" }))
+                .collect::<Vec<_>>();
+            serde_json::json!({
+                "type": "enum",
+                "name": "ns::models::Direction",
+                "variants": variants
+            })
+        };
+        let abi = |vec2, direction| -> Vec<AbiEntry> {
+            serde_json::from_value(serde_json::json!([vec2, direction])).unwrap()
+        };
+
+        let members = [
+            member("player", "core::felt252", true),
+            member("path", "core::array::Array::<ns::models::Direction>", false),
+        ];
+        let compare = |local: &[AbiEntry], deployed: &[AbiEntry]| {
+            let abis = Abis { local, deployed };
+            compare_members("ns-M".to_string(), &members, &members, Some(abis))
+        };
+
+        let deployed = abi(vec2(&["x", "y"]), direction(&["Left", "Right"]));
+
+        let local = abi(vec2(&["x", "y", "z"]), direction(&["Left", "Right", "Up"]));
+        assert_eq!(compare(&local, &deployed).compatibility, Compatibility::Upgradable);
+
+        let local = abi(vec2(&["x"]), direction(&["Left", "Right"]));
+        let change = compare(&local, &deployed);
+        assert_eq!(change.compatibility, Compatibility::Breaking);
+        assert_eq!(
+            change.reasons,
+            vec!["member `path` changes: member `y` of `ns::models::Vec2` is removed"]
+        );
+
+        let local = abi(vec2(&["x", "y"]), direction(&["Right", "Left"]));
+        let change = compare(&local, &deployed);
+        assert_eq!(change.compatibility, Compatibility::Breaking);
+        assert_eq!(
+            change.reasons,
+            vec!["member `path` changes: variant `Left` of `ns::models::Direction` is removed or moved"]
+        );
+    }
+
+    #[test]
+    fn entrypoints_compatibility() {
+        let deployed = abi(&[("spawn", &[]), ("move", &["felt252"])]);
+
+        let local = abi(&[("spawn", &[]), ("move", &["felt252"]), ("attack", &[])]);
+        let change = compare_entrypoints("ns-actions".to_string(), &local, &deployed);
+        assert_eq!(change.compatibility, Compatibility::Upgradable);
+        assert_eq!(change.reasons, vec!["entrypoint `attack` is added"]);
+
+        let local = abi(&[("spawn", &[]), ("move", &["felt252", "u8"])]);
+        let change = compare_entrypoints("ns-actions".to_string(), &local, &deployed);
+        assert_eq!(change.compatibility, Compatibility::Breaking);
+
+        let local = abi(&[("move", &["felt252"])]);
+        let change = compare_entrypoints("ns-actions".to_string(), &local, &deployed);
+        assert_eq!(change.compatibility, Compatibility::Breaking);
+        assert_eq!(change.reasons, vec!["entrypoint `spawn` is removed"]);
+    }
+
+    #[test]
+    fn report_compatibility() {
+        let world = UpgradeCheck::new(Felt::ONE, Some(Felt::ONE), &[], &[]);
+        let mut report = DiffReport { world, resources: vec![unchanged("ns-M".to_string())] };
+        assert_eq!(report.compatibility(), Compatibility::Unchanged);
+
+        report.resources.push(created("ns-N".to_string()));
+        assert_eq!(report.compatibility(), Compatibility::Upgradable);
+
+        report.world = UpgradeCheck::new(Felt::ONE, Some(Felt::TWO), &[], &["uuid".to_string()]);
+        assert_eq!(report.compatibility(), Compatibility::Breaking);
+    }
+}
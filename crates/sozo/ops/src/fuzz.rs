@@ -0,0 +1,404 @@
+//! Schema-aware fuzzing of the systems of a contract.
+//!
+//! The calldata of each system is generated from the types of its inputs, as declared in the
+//! contract ABI. Every case is derived from its own seed, so that any failing case can be replayed
+//! by running a single case with the reported seed against the same state.
+
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+use cainome::cairo_serde::{ByteArray, CairoSerde};
+use dojo_utils::{Invoker, TransactionError, TransactionResult, TxnConfig};
+use dojo_world::contracts::cairo_utils::{array_inner_type, tuple_inner_types};
+use rand::distributions::Alphanumeric;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use starknet::accounts::ConnectedAccount;
+use starknet::core::types::contract::{AbiEntry, AbiEnum, AbiFunction, AbiStruct, StateMutability};
+use starknet::core::types::{Call, ExecutionResult, Felt};
+use starknet::core::utils::get_selector_from_name;
+use tracing::trace;
+
+/// Interfaces implemented by every dojo contract, which are not systems.
+const IGNORED_INTERFACES: [&str; 4] =
+    ["::IWorldProvider", "::IUpgradeable", "::IDeployedResource", "::IContract"];
+
+/// Maximum length of the generated arrays.
+const MAX_ARRAY_LEN: usize = 8;
+/// Maximum length of the generated byte arrays.
+const MAX_BYTE_ARRAY_LEN: usize = 40;
+/// Maximum nesting of the generated types, which guards against recursive types.
+const MAX_DEPTH: usize = 16;
+
+/// The configuration of a fuzzing session.
+#[derive(Debug, Clone)]
+pub struct FuzzConfig {
+    /// The number of cases run for each system.
+    pub runs: u64,
+    /// The seed the seeds of the cases are derived from.
+    pub seed: u64,
+}
+
+/// The outcome of the fuzzing of a single system.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemReport {
+    /// The name of the system.
+    pub system: String,
+    /// The number of cases that have been run.
+    pub runs: u64,
+    /// The failing cases, the first one of each distinct failure reason.
+    pub failures: Vec<Failure>,
+}
+
+/// A case that reverted, or that could not be executed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Failure {
+    /// The seed to replay the case with, as the session seed of a single run.
+    pub seed: u64,
+    /// The generated calldata.
+    pub calldata: Vec<Felt>,
+    /// The revert reason.
+    pub reason: String,
+    /// The number of cases that failed with the same reason.
+    pub count: u64,
+}
+
+/// Returns the systems of a contract, which are the external functions of its interfaces, except
+/// the ones implemented by every dojo contract.
+pub fn systems(abi: &[AbiEntry]) -> Vec<&AbiFunction> {
+    abi.iter()
+        .filter_map(|entry| match entry {
+            AbiEntry::Interface(i) if !IGNORED_INTERFACES.iter().any(|s| i.name.ends_with(s)) => {
+                Some(&i.items)
+            }
+            _ => None,
+        })
+        .flatten()
+        .filter_map(|item| match item {
+            AbiEntry::Function(f) if matches!(f.state_mutability, StateMutability::External) => {
+                Some(f)
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Runs `config.runs` cases for each of the given systems of a contract, executing them with
+/// `account`.
+///
+/// Cases are executed in order, and the state modified by a case is observed by the next ones.
+/// Replaying a failing case from a different state may hence not reproduce the failure.
+pub async fn fuzz_systems<A>(
+    account: &A,
+    contract_address: Felt,
+    abi: &[AbiEntry],
+    systems: &[&AbiFunction],
+    config: &FuzzConfig,
+) -> Result<Vec<SystemReport>>
+where
+    A: ConnectedAccount + Send + Sync,
+{
+    let generator = CalldataGenerator::new(abi);
+    let txn_config = TxnConfig { wait: true, receipt: true, ..Default::default() };
+    let invoker = Invoker::new(account, txn_config);
+
+    let mut reports = vec![];
+
+    for system in systems {
+        let selector = get_selector_from_name(&system.name)?;
+        let mut failures: Vec<Failure> = vec![];
+
+        for run in 0..config.runs {
+            let seed = config.seed.wrapping_add(run);
+            let calldata = generator.function_calldata(system, &mut case_rng(seed, selector))?;
+
+            trace!(system = %system.name, seed, ?calldata, "Running fuzzing case.");
+
+            let call = Call { to: contract_address, selector, calldata: calldata.clone() };
+            let Some(reason) = execute_case(&invoker, call).await? else {
+                continue;
+            };
+
+            match failures.iter_mut().find(|f| f.reason == reason) {
+                Some(failure) => failure.count += 1,
+                None => failures.push(Failure { seed, calldata, reason, count: 1 }),
+            }
+        }
+
+        reports.push(SystemReport { system: system.name.clone(), runs: config.runs, failures });
+    }
+
+    Ok(reports)
+}
+
+/// Executes a case, returning the failure reason if the transaction could not be executed or
+/// reverted.
+async fn execute_case<A>(invoker: &Invoker<&A>, call: Call) -> Result<Option<String>>
+where
+    A: ConnectedAccount + Send + Sync,
+{
+    match invoker.invoke(call).await {
+        Ok(TransactionResult::HashReceipt(_, receipt)) => {
+            match receipt.receipt.execution_result() {
+                ExecutionResult::Succeeded => Ok(None),
+                ExecutionResult::Reverted { reason } => Ok(Some(reason.clone())),
+            }
+        }
+        Ok(_) => Ok(None),
+        // Failing during the fee estimation is the most common way to revert.
+        Err(TransactionError::TransactionExecution(reason)) => Ok(Some(reason)),
+        Err(e) => bail!("Failed to execute fuzzing case: {e}"),
+    }
+}
+
+/// Creates the random generator of a case, distinct for each system with the same seed.
+fn case_rng(seed: u64, selector: Felt) -> StdRng {
+    let bytes = selector.to_bytes_be();
+    let system = u64::from_be_bytes(bytes[24..].try_into().expect("8 bytes"));
+    StdRng::seed_from_u64(seed ^ system)
+}
+
+/// Generates random calldata from the types declared in an ABI.
+#[derive(Debug)]
+pub struct CalldataGenerator<'a> {
+    structs: HashMap<&'a str, &'a AbiStruct>,
+    enums: HashMap<&'a str, &'a AbiEnum>,
+}
+
+impl<'a> CalldataGenerator<'a> {
+    pub fn new(abi: &'a [AbiEntry]) -> Self {
+        let mut structs = HashMap::new();
+        let mut enums = HashMap::new();
+
+        for entry in abi {
+            match entry {
+                AbiEntry::Struct(s) => {
+                    structs.insert(s.name.as_str(), s);
+                }
+                AbiEntry::Enum(e) => {
+                    enums.insert(e.name.as_str(), e);
+                }
+                _ => {}
+            }
+        }
+
+        Self { structs, enums }
+    }
+
+    /// Generates the serialized inputs of a function.
+    pub fn function_calldata<R: Rng>(
+        &self,
+        function: &AbiFunction,
+        rng: &mut R,
+    ) -> Result<Vec<Felt>> {
+        let mut calldata = vec![];
+        for input in &function.inputs {
+            self.generate(&input.r#type, rng, &mut calldata, 0)?;
+        }
+        Ok(calldata)
+    }
+
+    /// Generates a random value of the given type and appends its serialization to `out`.
+    pub fn generate<R: Rng>(
+        &self,
+        ty: &str,
+        rng: &mut R,
+        out: &mut Vec<Felt>,
+        depth: usize,
+    ) -> Result<()> {
+        if depth > MAX_DEPTH {
+            bail!("Type `{ty}` is nested too deeply to be generated.");
+        }
+
+        match ty {
+            "()" => {}
+            "core::felt252" => out.push(random_felt(rng, 252)),
+            "core::bytes_31::bytes31" => out.push(random_felt(rng, 248)),
+            "core::starknet::contract_address::ContractAddress"
+            | "core::starknet::class_hash::ClassHash" => out.push(random_felt(rng, 251)),
+            "core::starknet::eth_address::EthAddress" => out.push(random_felt(rng, 160)),
+            "core::bool" => out.push(Felt::from(rng.gen_bool(0.5) as u8)),
+            "core::integer::u8" => out.push(random_felt(rng, 8)),
+            "core::integer::u16" => out.push(random_felt(rng, 16)),
+            "core::integer::u32" | "core::integer::usize" => out.push(random_felt(rng, 32)),
+            "core::integer::u64" => out.push(random_felt(rng, 64)),
+            "core::integer::u128" => out.push(random_felt(rng, 128)),
+            "core::integer::u256" => {
+                out.push(random_felt(rng, 128));
+                out.push(random_felt(rng, 128));
+            }
+            "core::integer::i8" => out.push(random_signed(rng, 8)),
+            "core::integer::i16" => out.push(random_signed(rng, 16)),
+            "core::integer::i32" => out.push(random_signed(rng, 32)),
+            "core::integer::i64" => out.push(random_signed(rng, 64)),
+            "core::integer::i128" => out.push(random_signed(rng, 128)),
+            "core::byte_array::ByteArray" => {
+                let len = rng.gen_range(0..=MAX_BYTE_ARRAY_LEN);
+                let s = (0..len).map(|_| char::from(rng.sample(Alphanumeric))).collect::<String>();
+                out.extend(ByteArray::cairo_serialize(&ByteArray::from_string(&s)?));
+            }
+            _ => {
+                if let Some(inner) = array_inner_type(ty) {
+                    let len = rng.gen_range(0..=MAX_ARRAY_LEN);
+                    out.push(Felt::from(len));
+                    for _ in 0..len {
+                        self.generate(inner, rng, out, depth + 1)?;
+                    }
+                } else if let Some(inners) = tuple_inner_types(ty) {
+                    for inner in inners {
+                        self.generate(inner, rng, out, depth + 1)?;
+                    }
+                } else if let Some(s) = self.structs.get(ty) {
+                    for member in &s.members {
+                        self.generate(&member.r#type, rng, out, depth + 1)?;
+                    }
+                } else if let Some(e) = self.enums.get(ty) {
+                    if e.variants.is_empty() {
+                        bail!("Enum `{ty}` has no variant.");
+                    }
+                    let index = rng.gen_range(0..e.variants.len());
+                    out.push(Felt::from(index));
+                    self.generate(&e.variants[index].r#type, rng, out, depth + 1)?;
+                } else {
+                    bail!("Type `{ty}` is not supported by the fuzzer.");
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns a random unsigned value of at most `bits` bits, biased towards the boundaries.
+fn random_felt<R: Rng>(rng: &mut R, bits: u32) -> Felt {
+    match rng.gen_range(0..8) {
+        0 => Felt::ZERO,
+        1 => Felt::ONE,
+        2 => max_unsigned(bits),
+        3 => Felt::from(rng.gen_range(0..=16_u8)),
+        _ => {
+            let mut bytes = [0_u8; 32];
+            rng.fill(&mut bytes[..]);
+
+            // Keeps the `bits` lowest bits, reduced modulo the field prime for a felt.
+            let full_bytes = (bits / 8) as usize;
+            let zeroed = 32 - full_bytes - usize::from(bits % 8 != 0);
+            bytes[..zeroed].fill(0);
+            if bits % 8 != 0 {
+                bytes[zeroed] &= (1 << (bits % 8)) - 1;
+            }
+
+            Felt::from_bytes_be(&bytes)
+        }
+    }
+}
+
+/// Returns a random signed value of `bits` bits, biased towards the boundaries.
+fn random_signed<R: Rng>(rng: &mut R, bits: u32) -> Felt {
+    let max = i128::MAX >> (128 - bits);
+    let min = -max - 1;
+
+    let value = match rng.gen_range(0..8) {
+        0 => 0,
+        1 => -1,
+        2 => max,
+        3 => min,
+        _ => rng.gen_range(min..=max),
+    };
+
+    Felt::from(value)
+}
+
+fn max_unsigned(bits: u32) -> Felt {
+    if bits >= 252 {
+        Felt::MAX
+    } else {
+        Felt::TWO.pow(bits as u128) - Felt::ONE
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::core::types::contract::AbiNamedMember;
+
+    use super::*;
+
+    fn member(name: &str, ty: &str) -> AbiNamedMember {
+        AbiNamedMember { name: name.to_string(), r#type: ty.to_string() }
+    }
+
+    fn abi() -> Vec<AbiEntry> {
+        vec![
+            AbiEntry::Struct(AbiStruct {
+                name: "ns::models::Vec2".to_string(),
+                members: vec![member("x", "core::integer::u32"), member("y", "core::integer::u32")],
+            }),
+            AbiEntry::Enum(AbiEnum {
+                name: "ns::models::Direction".to_string(),
+                variants: vec![member("Left", "()"), member("To", "ns::models::Vec2")],
+            }),
+        ]
+    }
+
+    #[test]
+    fn generates_schema_aware_calldata() {
+        let abi = abi();
+        let generator = CalldataGenerator::new(&abi);
+        let mut rng = StdRng::seed_from_u64(42);
+
+        for _ in 0..100 {
+            let mut out = vec![];
+            generator.generate("ns::models::Direction", &mut rng, &mut out, 0).unwrap();
+            match out.as_slice() {
+                [variant] => assert_eq!(*variant, Felt::ZERO),
+                [variant, x, y] => {
+                    assert_eq!(*variant, Felt::ONE);
+                    assert!(*x <= Felt::from(u32::MAX) && *y <= Felt::from(u32::MAX));
+                }
+                _ => panic!("unexpected serialization {out:?}"),
+            }
+
+            let mut out = vec![];
+            generator
+                .generate(
+                    "core::array::Span::<(core::integer::u8, core::bool)>",
+                    &mut rng,
+                    &mut out,
+                    0,
+                )
+                .unwrap();
+            let len: u64 = out[0].try_into().unwrap();
+            assert_eq!(out.len() as u64, 1 + 2 * len);
+        }
+
+        assert!(generator.generate("ns::models::Unknown", &mut rng, &mut vec![], 0).is_err());
+    }
+
+    #[test]
+    fn cases_are_reproducible() {
+        let abi = abi();
+        let generator = CalldataGenerator::new(&abi);
+        let function = AbiFunction {
+            name: "move".to_string(),
+            inputs: vec![member("direction", "ns::models::Direction")],
+            outputs: vec![],
+            state_mutability: StateMutability::External,
+        };
+        let selector = get_selector_from_name("move").unwrap();
+
+        let first = generator.function_calldata(&function, &mut case_rng(7, selector)).unwrap();
+        let second = generator.function_calldata(&function, &mut case_rng(7, selector)).unwrap();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn random_values_fit_their_type() {
+        let mut rng = StdRng::seed_from_u64(0);
+        for _ in 0..1000 {
+            assert!(random_felt(&mut rng, 8) <= Felt::from(u8::MAX));
+            assert!(random_felt(&mut rng, 251) < Felt::TWO.pow(251_u128));
+            let signed = random_signed(&mut rng, 8);
+            assert!(signed <= Felt::from(i8::MAX) || signed >= Felt::from(i8::MIN));
+        }
+    }
+}
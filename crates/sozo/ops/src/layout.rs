@@ -0,0 +1,680 @@
+//! Storage layout of the models of a world.
+//!
+//! The records of a model are stored in the world storage, at addresses derived from the model
+//! selector, the entity id and the path of each value in the model. The layout of a model is
+//! computed onchain by its `Introspect` implementation, it's rebuilt here from the types declared
+//! in the ABI of the model class, following the same rules as the `Introspect` derive.
+//!
+//! A type deriving `IntrospectPacked` can't be told apart from the ABI, hence the packed models and
+//! types must be provided explicitly.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+
+use anyhow::{anyhow, bail, Result};
+use dojo_types::naming;
+use dojo_world::contracts::cairo_utils::{array_inner_type, tuple_inner_types};
+use dojo_world::local::{ResourceLocal, WorldLocal};
+use dojo_world::storage::PACKING_MAX_BITS;
+use serde::Serialize;
+use starknet::core::types::contract::{AbiEntry, AbiEnum, AbiStruct};
+use starknet::core::types::Felt;
+use starknet::core::utils::get_selector_from_name;
+
+/// Maximum nesting of the types, which guards against recursive types.
+const MAX_DEPTH: usize = 16;
+
+/// How the base address of a value is computed from its key.
+pub const ADDRESS_FORMULA: &str =
+    "base_address = poseidon('dojo_storage', model_selector, key) mod (2**251 - 256)";
+/// How the address of the slots of a value are computed from its base address.
+pub const SLOT_FORMULA: &str = "slot n is at base_address + n for n < 256, and at \
+                                poseidon(base_address, n / 256, 'DojoStorageChunk') \
+                                mod (2**251 - 256) + n % 256 otherwise";
+
+/// The storage layout of the models of a world.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct WorldLayout {
+    /// How the base address of a value is computed from its key.
+    pub address_formula: String,
+    /// How the slots of a value are addressed.
+    pub slot_formula: String,
+    /// The layout of each model, ordered by tag.
+    pub models: Vec<ModelLayout>,
+}
+
+/// The storage layout of a model.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ModelLayout {
+    pub tag: String,
+    pub selector: Felt,
+    /// Whether the model derives `IntrospectPacked`, all its values being packed at the entity id.
+    pub packed: bool,
+    /// The keys of the model, serialized in order to compute the entity id.
+    pub keys: Vec<KeyMember>,
+    /// How the entity id is computed from the keys.
+    pub entity_id: String,
+    /// The layout returned by the model, as defined in `dojo::meta::Layout`.
+    pub layout: Layout,
+    /// Every value stored for a record, with its storage key.
+    pub entries: Vec<StorageEntry>,
+}
+
+/// A key of a model.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct KeyMember {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+}
+
+/// The layout of a value, mirroring `dojo::meta::Layout`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Layout {
+    /// The bit sizes of the values packed together.
+    Fixed(Vec<u8>),
+    Struct(Vec<FieldLayout>),
+    Tuple(Vec<Layout>),
+    Array(Box<Layout>),
+    ByteArray,
+    Enum(Vec<FieldLayout>),
+}
+
+/// The layout of a struct member, or of an enum variant.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldLayout {
+    /// The selector of the member name, or the index of the variant.
+    pub selector: Felt,
+    pub name: String,
+    pub layout: Layout,
+}
+
+/// What is stored at a storage key.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntryKind {
+    /// Values packed together.
+    Fixed,
+    /// The length of an array, its items being stored at their own key.
+    ArrayLength,
+    /// The index of the variant of an enum, its data being stored at their own key.
+    EnumVariant,
+    /// A serialized byte array, one felt per slot.
+    ByteArray,
+}
+
+/// A value stored at its own storage key.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StorageEntry {
+    /// The path of the value in the model, where `[i]` is an array index.
+    pub path: String,
+    #[serde(rename = "type")]
+    pub ty: String,
+    /// How the storage key is computed, where `poseidon(a, b)` is the poseidon hash of `[a, b]`.
+    pub key: String,
+    pub kind: EntryKind,
+    /// The number of slots, unknown for byte arrays.
+    pub slots: Option<u32>,
+    /// The packed values, in order.
+    pub values: Vec<PackedValue>,
+}
+
+/// A value packed into a slot.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PackedValue {
+    pub path: String,
+    pub bits: u8,
+    /// The index of the slot the value is packed into.
+    pub slot: u32,
+    /// The offset of the lowest bit of the value in its slot.
+    pub offset: u8,
+}
+
+/// Builds the storage layout of the models of a local world.
+///
+/// `packed` contains the tags of the models, and the names of the types, deriving
+/// `IntrospectPacked`.
+pub fn world_layout(world: &WorldLocal, packed: &HashSet<String>) -> Result<WorldLayout> {
+    let mut models = world
+        .resources
+        .values()
+        .filter_map(|r| match r {
+            ResourceLocal::Model(m) => Some(m),
+            _ => None,
+        })
+        .map(|m| {
+            let tag = naming::get_tag(&m.common.namespace, &m.common.name);
+            let is_packed = packed.contains(&tag);
+            model_layout(&tag, &m.common.name, &m.common.class.abi, is_packed, packed)
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    models.sort_by(|a, b| a.tag.cmp(&b.tag));
+
+    Ok(WorldLayout {
+        address_formula: ADDRESS_FORMULA.to_string(),
+        slot_formula: SLOT_FORMULA.to_string(),
+        models,
+    })
+}
+
+/// Builds the storage layout of a model from the ABI of its class.
+///
+/// The model struct and its value struct, which is the model without its keys, are both exposed
+/// in the ABI of the model class.
+pub fn model_layout(
+    tag: &str,
+    name: &str,
+    abi: &[AbiEntry],
+    is_packed: bool,
+    packed_types: &HashSet<String>,
+) -> Result<ModelLayout> {
+    let types = AbiTypes::new(abi, packed_types);
+
+    let model = types
+        .struct_ending_with(&format!("::{name}"))
+        .ok_or_else(|| anyhow!("Model struct `{name}` not found in the ABI of {tag}."))?;
+    let value = types.struct_ending_with(&format!("::{name}Value")).ok_or_else(|| {
+        anyhow!("Model value struct `{name}Value` not found in the ABI of {tag}.")
+    })?;
+
+    let (values, keys): (Vec<_>, Vec<_>) =
+        model.members.iter().partition(|m| value.members.iter().any(|v| v.name == m.name));
+
+    let keys = keys
+        .into_iter()
+        .map(|k| KeyMember { name: k.name.clone(), ty: k.r#type.clone() })
+        .collect::<Vec<_>>();
+    let entity_id = format!(
+        "poseidon({})",
+        keys.iter().map(|k| k.name.as_str()).collect::<Vec<_>>().join(", ")
+    );
+
+    let mut entries = vec![];
+
+    let layout = if is_packed {
+        let mut bits = vec![];
+        for member in &values {
+            types.packed_bits(&member.r#type, &member.name, &mut bits, 0)?;
+        }
+
+        entries.push(fixed_entry(name, &model.name, "entity_id", EntryKind::Fixed, &bits));
+        Layout::Fixed(bits.into_iter().map(|(_, b)| b).collect())
+    } else {
+        let mut fields = vec![];
+        for member in &values {
+            let selector = get_selector_from_name(&member.name)?;
+            let key = format!("poseidon(entity_id, {selector:#x})");
+
+            types.entries(&member.r#type, &member.name, &key, &mut entries, 0)?;
+            fields.push(FieldLayout {
+                selector,
+                name: member.name.clone(),
+                layout: types.layout(&member.r#type, 0)?,
+            });
+        }
+
+        Layout::Struct(fields)
+    };
+
+    Ok(ModelLayout {
+        tag: tag.to_string(),
+        selector: naming::compute_selector_from_tag(tag),
+        packed: is_packed,
+        keys,
+        entity_id,
+        layout,
+        entries,
+    })
+}
+
+/// The types declared in an ABI.
+struct AbiTypes<'a> {
+    structs: HashMap<&'a str, &'a AbiStruct>,
+    enums: HashMap<&'a str, &'a AbiEnum>,
+    packed: &'a HashSet<String>,
+}
+
+impl<'a> AbiTypes<'a> {
+    fn new(abi: &'a [AbiEntry], packed: &'a HashSet<String>) -> Self {
+        let mut structs = HashMap::new();
+        let mut enums = HashMap::new();
+
+        for entry in abi {
+            match entry {
+                AbiEntry::Struct(s) => {
+                    structs.insert(s.name.as_str(), s);
+                }
+                AbiEntry::Enum(e) => {
+                    enums.insert(e.name.as_str(), e);
+                }
+                _ => {}
+            }
+        }
+
+        Self { structs, enums, packed }
+    }
+
+    fn struct_ending_with(&self, suffix: &str) -> Option<&'a AbiStruct> {
+        self.structs.iter().find(|(name, _)| name.ends_with(suffix)).map(|(_, s)| *s)
+    }
+
+    /// Whether a custom type derives `IntrospectPacked`.
+    fn is_packed(&self, ty: &str) -> bool {
+        self.packed.contains(ty.rsplit("::").next().unwrap_or(ty))
+    }
+
+    /// Returns the layout of a type, as returned by `Introspect::<T>::layout()`.
+    fn layout(&self, ty: &str, depth: usize) -> Result<Layout> {
+        if depth > MAX_DEPTH {
+            bail!("Type `{ty}` is nested too deeply.");
+        }
+
+        if let Some(bits) = primitive_bits(ty) {
+            return Ok(Layout::Fixed(bits.to_vec()));
+        }
+        if ty == "core::byte_array::ByteArray" {
+            return Ok(Layout::ByteArray);
+        }
+        if let Some(inner) = array_inner_type(ty) {
+            return Ok(Layout::Array(Box::new(self.layout(inner, depth + 1)?)));
+        }
+        if let Some(inners) = tuple_inner_types(ty) {
+            let layouts = inners.into_iter().map(|t| self.layout(t, depth + 1));
+            return Ok(Layout::Tuple(layouts.collect::<Result<_>>()?));
+        }
+        if self.is_packed(ty) {
+            let mut bits = vec![];
+            self.packed_bits(ty, "", &mut bits, depth)?;
+            return Ok(Layout::Fixed(bits.into_iter().map(|(_, b)| b).collect()));
+        }
+        if let Some(s) = self.structs.get(ty) {
+            let fields = s.members.iter().map(|m| {
+                Ok(FieldLayout {
+                    selector: get_selector_from_name(&m.name)?,
+                    name: m.name.clone(),
+                    layout: self.layout(&m.r#type, depth + 1)?,
+                })
+            });
+            return Ok(Layout::Struct(fields.collect::<Result<_>>()?));
+        }
+        if let Some(e) = self.enums.get(ty) {
+            let variants = e.variants.iter().enumerate().map(|(i, v)| {
+                let layout = match v.r#type.as_str() {
+                    "()" => Layout::Fixed(vec![]),
+                    ty => self.layout(ty, depth + 1)?,
+                };
+                Ok(FieldLayout { selector: Felt::from(i), name: v.name.clone(), layout })
+            });
+            return Ok(Layout::Enum(variants.collect::<Result<_>>()?));
+        }
+
+        bail!("Type `{ty}` has no known layout.")
+    }
+
+    /// Appends the packed values of a type, with their path and their bit size, to `out`.
+    fn packed_bits(
+        &self,
+        ty: &str,
+        path: &str,
+        out: &mut Vec<(String, u8)>,
+        depth: usize,
+    ) -> Result<()> {
+        if depth > MAX_DEPTH {
+            bail!("Type `{ty}` is nested too deeply.");
+        }
+
+        let child =
+            |name: &str| if path.is_empty() { name.to_string() } else { format!("{path}.{name}") };
+
+        if ty == "core::integer::u256" {
+            out.push((child("low"), 128));
+            out.push((child("high"), 128));
+        } else if let Some(bits) = primitive_bits(ty) {
+            out.extend(bits.iter().map(|b| (path.to_string(), *b)));
+        } else if ty == "core::byte_array::ByteArray" || array_inner_type(ty).is_some() {
+            bail!("Type `{ty}` can't be packed.");
+        } else if let Some(inners) = tuple_inner_types(ty) {
+            for (i, inner) in inners.into_iter().enumerate() {
+                self.packed_bits(inner, &child(&i.to_string()), out, depth + 1)?;
+            }
+        } else if let Some(s) = self.structs.get(ty) {
+            for member in &s.members {
+                self.packed_bits(&member.r#type, &child(&member.name), out, depth + 1)?;
+            }
+        } else if let Some(e) = self.enums.get(ty) {
+            // All the variants have the same size, the first one describes the data.
+            out.push((path.to_string(), 8));
+            if let Some(variant) = e.variants.first().filter(|v| v.r#type != "()") {
+                self.packed_bits(&variant.r#type, &child("data"), out, depth + 1)?;
+            }
+        } else {
+            bail!("Type `{ty}` has no known layout.");
+        }
+
+        Ok(())
+    }
+
+    /// Appends the storage entries of a value of type `ty` stored at `key` to `out`.
+    fn entries(
+        &self,
+        ty: &str,
+        path: &str,
+        key: &str,
+        out: &mut Vec<StorageEntry>,
+        depth: usize,
+    ) -> Result<()> {
+        if depth > MAX_DEPTH {
+            bail!("Type `{ty}` is nested too deeply.");
+        }
+
+        match self.layout(ty, depth)? {
+            Layout::Fixed(_) => {
+                let mut bits = vec![];
+                self.packed_bits(ty, path, &mut bits, depth)?;
+                out.push(fixed_entry(path, ty, key, EntryKind::Fixed, &bits));
+            }
+            Layout::ByteArray => out.push(StorageEntry {
+                path: path.to_string(),
+                ty: ty.to_string(),
+                key: key.to_string(),
+                kind: EntryKind::ByteArray,
+                slots: None,
+                values: vec![],
+            }),
+            Layout::Array(_) => {
+                let bits = [(format!("{path}.len"), PACKING_MAX_BITS)];
+                out.push(fixed_entry(path, ty, key, EntryKind::ArrayLength, &bits));
+
+                let inner = array_inner_type(ty).expect("array type");
+                let item_key = format!("poseidon({key}, i)");
+                self.entries(inner, &format!("{path}[i]"), &item_key, out, depth + 1)?;
+            }
+            Layout::Tuple(_) => {
+                let inners = tuple_inner_types(ty).expect("tuple type");
+                for (i, inner) in inners.into_iter().enumerate() {
+                    let item_key = format!("poseidon({key}, {i})");
+                    self.entries(inner, &format!("{path}.{i}"), &item_key, out, depth + 1)?;
+                }
+            }
+            Layout::Struct(fields) => {
+                let s = self.structs[ty];
+                for (member, field) in s.members.iter().zip(fields) {
+                    let member_key = format!("poseidon({key}, {:#x})", field.selector);
+                    let member_path = format!("{path}.{}", member.name);
+                    self.entries(&member.r#type, &member_path, &member_key, out, depth + 1)?;
+                }
+            }
+            Layout::Enum(_) => {
+                let bits = [(path.to_string(), PACKING_MAX_BITS)];
+                out.push(fixed_entry(path, ty, key, EntryKind::EnumVariant, &bits));
+
+                let e = self.enums[ty];
+                for (i, variant) in e.variants.iter().enumerate().filter(|(_, v)| v.r#type != "()")
+                {
+                    let data_key = format!("poseidon({key}, {i})");
+                    let data_path = format!("{path}::{}", variant.name);
+                    self.entries(&variant.r#type, &data_path, &data_key, out, depth + 1)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the layout of the primitive types, as implemented in `dojo::meta::introspect`.
+fn primitive_bits(ty: &str) -> Option<&'static [u8]> {
+    let bits: &'static [u8] = match ty {
+        "core::bool" => &[1],
+        "core::integer::u8" => &[8],
+        "core::integer::u16" => &[16],
+        "core::integer::u32" | "core::integer::usize" => &[32],
+        "core::integer::u64" => &[64],
+        "core::integer::u128" => &[128],
+        "core::integer::u256" => &[128, 128],
+        "core::bytes_31::bytes31" => &[248],
+        "core::felt252"
+        | "core::integer::i8"
+        | "core::integer::i16"
+        | "core::integer::i32"
+        | "core::integer::i64"
+        | "core::integer::i128"
+        | "core::starknet::contract_address::ContractAddress"
+        | "core::starknet::class_hash::ClassHash" => &[PACKING_MAX_BITS],
+        _ => return None,
+    };
+
+    Some(bits)
+}
+
+/// Builds the entry of values packed together, placing them in slots the way `pack` does.
+fn fixed_entry(
+    path: &str,
+    ty: &str,
+    key: &str,
+    kind: EntryKind,
+    bits: &[(String, u8)],
+) -> StorageEntry {
+    let mut values = vec![];
+    let mut slot = 0;
+    let mut offset = 0;
+
+    for (path, size) in bits {
+        if PACKING_MAX_BITS - offset < *size {
+            slot += 1;
+            offset = 0;
+        }
+
+        values.push(PackedValue { path: path.clone(), bits: *size, slot, offset });
+        offset += size;
+    }
+
+    StorageEntry {
+        path: path.to_string(),
+        ty: ty.to_string(),
+        key: key.to_string(),
+        kind,
+        slots: Some(slot + 1),
+        values,
+    }
+}
+
+impl WorldLayout {
+    /// Renders the layout as a human-readable markdown report.
+    pub fn report(&self) -> String {
+        let mut report = String::new();
+
+        // Writing into a string never fails.
+        let _ = writeln!(report, "# World storage layout\n");
+        let _ = writeln!(report, "- `{}`", self.address_formula);
+        let _ = writeln!(report, "- {}", self.slot_formula);
+        let _ = writeln!(report, "- `poseidon(a, b, ...)` is the poseidon hash of `[a, b, ...]`.");
+
+        for model in &self.models {
+            let _ = writeln!(report, "\n## {}\n", model.tag);
+            let _ = writeln!(report, "- Selector: `{:#x}`", model.selector);
+            let _ = writeln!(report, "- Packed: {}", model.packed);
+            let keys =
+                model.keys.iter().map(|k| format!("`{}: {}`", k.name, k.ty)).collect::<Vec<_>>();
+            let _ = writeln!(report, "- Keys: {}", keys.join(", "));
+            let _ = writeln!(report, "- Entity id: `{}`\n", model.entity_id);
+
+            let _ = writeln!(report, "| Value | Type | Key | Slot | Offset | Bits |");
+            let _ = writeln!(report, "|---|---|---|---|---|---|");
+
+            for entry in &model.entries {
+                if entry.values.is_empty() {
+                    let _ = writeln!(
+                        report,
+                        "| `{}` | `{}` | `{}` | 0.. | | {} |",
+                        entry.path,
+                        entry.ty,
+                        entry.key,
+                        entry_kind_note(entry.kind)
+                    );
+                }
+
+                for value in &entry.values {
+                    let _ = writeln!(
+                        report,
+                        "| `{}` | `{}` | `{}` | {} | {} | {}{} |",
+                        value.path,
+                        entry.ty,
+                        entry.key,
+                        value.slot,
+                        value.offset,
+                        value.bits,
+                        entry_kind_note(entry.kind)
+                    );
+                }
+            }
+        }
+
+        report
+    }
+}
+
+fn entry_kind_note(kind: EntryKind) -> &'static str {
+    match kind {
+        EntryKind::Fixed => "",
+        EntryKind::ArrayLength => " (array length)",
+        EntryKind::EnumVariant => " (variant index)",
+        EntryKind::ByteArray => "byte array, one felt per slot",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use starknet::core::types::contract::AbiNamedMember;
+
+    use super::*;
+
+    fn member(name: &str, ty: &str) -> AbiNamedMember {
+        AbiNamedMember { name: name.to_string(), r#type: ty.to_string() }
+    }
+
+    fn abi() -> Vec<AbiEntry> {
+        vec![
+            AbiEntry::Struct(AbiStruct {
+                name: "ns::models::Vec2".to_string(),
+                members: vec![member("x", "core::integer::u32"), member("y", "core::integer::u32")],
+            }),
+            AbiEntry::Enum(AbiEnum {
+                name: "ns::models::Direction".to_string(),
+                variants: vec![member("None", "()"), member("Left", "core::integer::u8")],
+            }),
+            AbiEntry::Struct(AbiStruct {
+                name: "ns::models::Position".to_string(),
+                members: vec![
+                    member("player", "core::starknet::contract_address::ContractAddress"),
+                    member("vec", "ns::models::Vec2"),
+                    member("last", "ns::models::Direction"),
+                    member("path", "core::array::Array::<core::integer::u256>"),
+                ],
+            }),
+            AbiEntry::Struct(AbiStruct {
+                name: "ns::models::PositionValue".to_string(),
+                members: vec![
+                    member("vec", "ns::models::Vec2"),
+                    member("last", "ns::models::Direction"),
+                    member("path", "core::array::Array::<core::integer::u256>"),
+                ],
+            }),
+        ]
+    }
+
+    #[test]
+    fn struct_model_layout() {
+        let layout =
+            model_layout("ns-Position", "Position", &abi(), false, &HashSet::new()).unwrap();
+
+        assert_eq!(
+            layout.keys,
+            vec![KeyMember {
+                name: "player".to_string(),
+                ty: "core::starknet::contract_address::ContractAddress".to_string()
+            }]
+        );
+        assert_eq!(layout.entity_id, "poseidon(player)");
+
+        let vec_selector = get_selector_from_name("vec").unwrap();
+        let x_selector = get_selector_from_name("x").unwrap();
+
+        assert_eq!(layout.entries[0].path, "vec.x");
+        assert_eq!(
+            layout.entries[0].key,
+            format!("poseidon(poseidon(entity_id, {vec_selector:#x}), {x_selector:#x})")
+        );
+
+        let paths = layout.entries.iter().map(|e| e.path.as_str()).collect::<Vec<_>>();
+        assert_eq!(paths, vec!["vec.x", "vec.y", "last", "last::Left", "path", "path[i]"]);
+
+        let kinds = layout.entries.iter().map(|e| e.kind).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            vec![
+                EntryKind::Fixed,
+                EntryKind::Fixed,
+                EntryKind::EnumVariant,
+                EntryKind::Fixed,
+                EntryKind::ArrayLength,
+                EntryKind::Fixed
+            ]
+        );
+
+        let Layout::Struct(fields) = &layout.layout else { panic!("expected a struct layout") };
+        assert_eq!(fields[2].layout, Layout::Array(Box::new(Layout::Fixed(vec![128, 128]))));
+    }
+
+    #[test]
+    fn packed_model_layout() {
+        let packed = HashSet::from(["ns-Position".to_string(), "Vec2".to_string()]);
+        let mut abi = abi();
+        abi.retain(|e| !matches!(e, AbiEntry::Struct(s) if s.name.contains("Position")));
+        abi.push(AbiEntry::Struct(AbiStruct {
+            name: "ns::models::Position".to_string(),
+            members: vec![
+                member("player", "core::starknet::contract_address::ContractAddress"),
+                member("vec", "ns::models::Vec2"),
+                member("last", "ns::models::Direction"),
+                member("balance", "core::integer::u256"),
+            ],
+        }));
+        abi.push(AbiEntry::Struct(AbiStruct {
+            name: "ns::models::PositionValue".to_string(),
+            members: vec![
+                member("vec", "ns::models::Vec2"),
+                member("last", "ns::models::Direction"),
+                member("balance", "core::integer::u256"),
+            ],
+        }));
+
+        let layout = model_layout("ns-Position", "Position", &abi, true, &packed).unwrap();
+        assert_eq!(layout.layout, Layout::Fixed(vec![32, 32, 8, 128, 128]));
+
+        let entry = &layout.entries[0];
+        assert_eq!(entry.key, "entity_id");
+        assert_eq!(entry.slots, Some(2));
+
+        let placement =
+            entry.values.iter().map(|v| (v.path.as_str(), v.slot, v.offset)).collect::<Vec<_>>();
+        assert_eq!(
+            placement,
+            vec![
+                ("vec.x", 0, 0),
+                ("vec.y", 0, 32),
+                ("last", 0, 64),
+                ("balance.low", 0, 72),
+                ("balance.high", 1, 0)
+            ]
+        );
+    }
+
+    #[test]
+    fn unpackable_types() {
+        let packed = HashSet::from(["ns-Position".to_string()]);
+        assert!(model_layout("ns-Position", "Position", &abi(), true, &packed).is_err());
+    }
+}
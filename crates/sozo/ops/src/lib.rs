@@ -1,9 +1,16 @@
 // #![cfg_attr(not(test), warn(unused_crate_dependencies))]
 
+pub mod abi;
+pub mod class_size;
+pub mod diff;
+pub mod fuzz;
+pub mod layout;
 pub mod migrate;
 pub mod migration_ui;
 pub mod model;
 pub mod resource_descriptor;
+pub mod state;
+pub mod upgrade_check;
 
 #[cfg(test)]
 pub mod tests;
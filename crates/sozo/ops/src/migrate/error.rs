@@ -36,4 +36,9 @@ where
     TransactionError(#[from] TransactionError<S>),
     #[error("Declaration of class failed: {0}")]
     DeclareClassError(String),
+    #[error(
+        "The ownership of the resource `{0}` was not handed off to the final owner, verify that \
+         the migrating account owns it."
+    )]
+    OwnershipHandoff(String),
 }
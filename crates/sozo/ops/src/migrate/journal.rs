@@ -0,0 +1,196 @@
+//! The journal of a migration.
+//!
+//! While a migration runs, its progress is persisted to a file after every step: the step being
+//! executed and the transactions sent by the steps so far. This way, the progress of a long
+//! migration can be followed from another process, and what was done before a crash is known.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use starknet_crypto::Felt;
+use tracing::warn;
+
+/// The file name of the journal in the target directory of the profile.
+pub const MIGRATION_JOURNAL_JSON: &str = "migration_journal.json";
+
+/// The state of a migration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationState {
+    /// The migration is running, or the process running it was killed.
+    Running,
+    Completed,
+    Failed,
+}
+
+/// A step of a migration, and the transactions it sent.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationStep {
+    pub description: String,
+    /// The hashes of the transactions sent by the step, once they have been executed.
+    pub transactions: Vec<Felt>,
+    pub done: bool,
+}
+
+/// The progress of a migration, as persisted in the journal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MigrationProgress {
+    pub world_address: Felt,
+    /// The id of the process running the migration.
+    pub pid: u32,
+    pub state: MigrationState,
+    /// The time the migration started at, in seconds since the UNIX epoch.
+    pub started_at: u64,
+    /// The time the journal was last written at, in seconds since the UNIX epoch.
+    pub updated_at: u64,
+    pub steps: Vec<MigrationStep>,
+}
+
+impl MigrationProgress {
+    /// Reads the progress from the journal at `path`, if there is one.
+    pub fn read(path: impl AsRef<Path>) -> anyhow::Result<Option<Self>> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+
+        Ok(Some(serde_json::from_str(&content)?))
+    }
+
+    /// Returns the step being executed, if any.
+    pub fn current_step(&self) -> Option<&MigrationStep> {
+        self.steps.last().filter(|step| !step.done)
+    }
+}
+
+/// Persists the progress of a migration to a journal file.
+///
+/// Failing to write the journal doesn't fail the migration, it is only logged.
+#[derive(Debug)]
+pub struct MigrationJournal {
+    path: PathBuf,
+    progress: Mutex<MigrationProgress>,
+}
+
+impl MigrationJournal {
+    /// Starts a new journal at `path` for the migration of the world at `world_address`,
+    /// replacing the journal of the previous migration.
+    pub fn new(path: impl Into<PathBuf>, world_address: Felt) -> Self {
+        let now = now();
+        let progress = MigrationProgress {
+            world_address,
+            pid: std::process::id(),
+            state: MigrationState::Running,
+            started_at: now,
+            updated_at: now,
+            steps: Vec::new(),
+        };
+
+        let journal = Self { path: path.into(), progress: Mutex::new(progress) };
+        journal.update(|_| {});
+        journal
+    }
+
+    /// Marks the current step as done and starts a new one.
+    pub fn step(&self, description: impl Into<String>) {
+        let description = description.into();
+        self.update(|progress| {
+            if let Some(step) = progress.steps.last_mut() {
+                step.done = true;
+            }
+
+            progress.steps.push(MigrationStep { description, transactions: vec![], done: false });
+        });
+    }
+
+    /// Records the transactions sent by the current step.
+    pub fn record(&self, transactions: impl IntoIterator<Item = Felt>) {
+        self.update(|progress| {
+            if let Some(step) = progress.steps.last_mut() {
+                step.transactions.extend(transactions);
+            }
+        });
+    }
+
+    /// Marks the migration as completed or failed.
+    pub fn finish(&self, success: bool) {
+        self.update(|progress| {
+            if success {
+                if let Some(step) = progress.steps.last_mut() {
+                    step.done = true;
+                }
+                progress.state = MigrationState::Completed;
+            } else {
+                progress.state = MigrationState::Failed;
+            }
+        });
+    }
+
+    /// Returns the current progress of the migration.
+    pub fn progress(&self) -> MigrationProgress {
+        self.progress.lock().unwrap().clone()
+    }
+
+    fn update(&self, f: impl FnOnce(&mut MigrationProgress)) {
+        let mut progress = self.progress.lock().unwrap();
+        f(&mut progress);
+        progress.updated_at = now();
+
+        if let Err(e) = write(&self.path, &progress) {
+            warn!(path = %self.path.display(), error = %e, "Failed to write the migration journal.");
+        }
+    }
+}
+
+/// Writes the progress to a temporary file first, so a reader never sees a partial journal.
+fn write(path: &Path, progress: &MigrationProgress) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let tmp = path.with_extension("json.tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(progress)?)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn journal_progress() {
+        let dir = assert_fs::TempDir::new().unwrap();
+        let path = dir.path().join(MIGRATION_JOURNAL_JSON);
+        assert_eq!(MigrationProgress::read(&path).unwrap(), None);
+
+        let journal = MigrationJournal::new(&path, Felt::ONE);
+        journal.step("Declaring 2 classes...");
+        journal.record([Felt::from(0x10), Felt::from(0x11)]);
+        journal.step("Registering 2 resources...");
+
+        let progress = MigrationProgress::read(&path).unwrap().unwrap();
+        assert_eq!(progress, journal.progress());
+        assert_eq!(progress.world_address, Felt::ONE);
+        assert_eq!(progress.state, MigrationState::Running);
+        assert!(progress.steps[0].done);
+        assert_eq!(progress.steps[0].transactions, vec![Felt::from(0x10), Felt::from(0x11)]);
+        assert_eq!(progress.current_step().unwrap().description, "Registering 2 resources...");
+
+        journal.record([Felt::from(0x12)]);
+        journal.finish(true);
+
+        let progress = MigrationProgress::read(&path).unwrap().unwrap();
+        assert_eq!(progress.state, MigrationState::Completed);
+        assert_eq!(progress.current_step(), None);
+        assert_eq!(progress.steps[1].transactions, vec![Felt::from(0x12)]);
+    }
+}
@@ -17,8 +17,11 @@
 //!      changes are applied.
 //! 4. All contracts that are not initialized are initialized, since permissions are applied,
 //!    initialization of contracts can mutate resources.
+//! 5. If a final owner is configured, the ownership of the world and its resources is handed off
+//!    from the migrating account to the final owner.
 
 use std::collections::HashMap;
+use std::sync::Mutex;
 
 use cainome::cairo_serde::{ByteArray, ClassHash, ContractAddress};
 use dojo_utils::{Declarer, Deployer, Invoker, LabeledClass, TransactionResult, TxnConfig};
@@ -39,7 +42,12 @@ use tracing::trace;
 use crate::migration_ui::MigrationUi;
 
 pub mod error;
+pub mod journal;
 pub use error::MigrationError;
+pub use journal::MigrationJournal;
+
+/// The selector of the world resource, whose owners are owners of all the resources.
+const WORLD: Felt = Felt::ZERO;
 
 #[derive(Debug)]
 pub struct Migration<A>
@@ -53,12 +61,17 @@ where
     // This is only to retrieve the declarers or make custom calls.
     // Ideally, we want this rpc url to be exposed from the world.account.provider().
     rpc_url: String,
+    /// The hashes of the transactions sent so far.
+    transactions: Mutex<Vec<Felt>>,
+    journal: Option<MigrationJournal>,
 }
 
 #[derive(Debug)]
 pub struct MigrationResult {
     pub has_changes: bool,
     pub manifest: Manifest,
+    /// The hashes of the transactions sent by the migration.
+    pub transactions: Vec<Felt>,
 }
 
 impl<A> Migration<A>
@@ -73,7 +86,21 @@ where
         profile_config: ProfileConfig,
         rpc_url: String,
     ) -> Self {
-        Self { diff, world, txn_config, profile_config, rpc_url }
+        Self {
+            diff,
+            world,
+            txn_config,
+            profile_config,
+            rpc_url,
+            transactions: Default::default(),
+            journal: None,
+        }
+    }
+
+    /// Persists the progress of the migration to the given journal.
+    pub fn with_journal(mut self, journal: MigrationJournal) -> Self {
+        self.journal = Some(journal);
+        self
     }
 
     /// Migrates the world by syncing the namespaces, resources, permissions and initializing the
@@ -84,6 +111,19 @@ where
     pub async fn migrate(
         &self,
         ui: &mut MigrationUi,
+    ) -> Result<MigrationResult, MigrationError<A::SignError>> {
+        let result = self.migrate_steps(ui).await;
+
+        if let Some(journal) = &self.journal {
+            journal.finish(result.is_ok());
+        }
+
+        result
+    }
+
+    async fn migrate_steps(
+        &self,
+        ui: &mut MigrationUi,
     ) -> Result<MigrationResult, MigrationError<A::SignError>> {
         let world_has_changed = self.ensure_world(ui).await?;
 
@@ -94,15 +134,40 @@ where
 
         let contracts_have_changed = self.initialize_contracts(ui).await?;
 
+        let ownership_has_changed = self.handoff_ownership(ui).await?;
+
         Ok(MigrationResult {
             has_changes: world_has_changed
                 || resources_have_changed
                 || permissions_have_changed
-                || contracts_have_changed,
+                || contracts_have_changed
+                || ownership_has_changed,
             manifest: Manifest::new(&self.diff),
+            transactions: std::mem::take(&mut *self.transactions.lock().unwrap()),
         })
     }
 
+    /// Keeps track of the transactions sent, if any.
+    fn record<'r>(&self, results: impl IntoIterator<Item = &'r TransactionResult>) {
+        let hashes =
+            results.into_iter().filter_map(TransactionResult::transaction_hash).collect::<Vec<_>>();
+
+        if let Some(journal) = &self.journal {
+            journal.record(hashes.iter().copied());
+        }
+
+        self.transactions.lock().unwrap().extend(hashes);
+    }
+
+    /// Starts a step sending transactions, shown on the UI and persisted to the journal.
+    fn step(&self, ui: &mut MigrationUi, text: String) {
+        if let Some(journal) = &self.journal {
+            journal.step(text.as_str());
+        }
+
+        ui.update_text_boxed(text);
+    }
+
     /// Returns whether multicall should be used. By default, it is enabled.
     fn do_multicall(&self) -> bool {
         self.profile_config
@@ -202,21 +267,124 @@ where
         if !invoker.calls.is_empty() {
             if self.do_multicall() {
                 let ui_text = format!("Initializing {} contracts...", invoker.calls.len());
-                ui.update_text_boxed(ui_text);
+                self.step(ui, ui_text);
 
-                invoker.multicall().await?;
+                self.record([&invoker.multicall().await?]);
             } else {
                 let ui_text =
                     format!("Initializing {} contracts (sequentially)...", invoker.calls.len());
-                ui.update_text_boxed(ui_text);
+                self.step(ui, ui_text);
 
-                invoker.invoke_all_sequentially().await?;
+                self.record(&invoker.invoke_all_sequentially().await?);
             }
         }
 
         Ok(has_changed)
     }
 
+    /// Grants the ownership of the world and of the resources to the final owner configured in the
+    /// [`ProfileConfig`], and revokes the ownership of the migrating account, in a single
+    /// multicall. The ownership is then read back from the world to verify the handoff.
+    ///
+    /// Returns true if the ownership has changed, false otherwise.
+    async fn handoff_ownership(
+        &self,
+        ui: &mut MigrationUi,
+    ) -> Result<bool, MigrationError<A::SignError>> {
+        let Some(final_owner) = self.profile_config.migration.as_ref().and_then(|m| m.final_owner)
+        else {
+            return Ok(false);
+        };
+
+        let deployer = self.world.account.address();
+        if final_owner == deployer {
+            return Ok(false);
+        }
+
+        ui.update_text("Handing off ownership...");
+
+        let final_owner = ContractAddress(final_owner);
+        let deployer = ContractAddress(deployer);
+
+        // The world resource comes last, as revoking it first would prevent the revocation of
+        // the other resources not owned explicitly.
+        let mut selectors = self
+            .diff
+            .resources
+            .iter()
+            .filter(|(_, resource)| !self.profile_config.is_skipped(&resource.tag()))
+            .map(|(selector, _)| *selector)
+            .collect::<Vec<_>>();
+        selectors.sort();
+        selectors.push(WORLD);
+
+        let mut grants = vec![];
+        let mut revokes = vec![];
+
+        let ownership = self.ownership(&selectors, &final_owner, &deployer).await?;
+        for (selector, (final_owner_is_owner, deployer_is_owner)) in selectors.iter().zip(ownership)
+        {
+            if !final_owner_is_owner {
+                grants.push(self.world.grant_owner_getcall(selector, &final_owner));
+            }
+
+            if deployer_is_owner {
+                revokes.push(self.world.revoke_owner_getcall(selector, &deployer));
+            }
+        }
+
+        if grants.is_empty() && revokes.is_empty() {
+            return Ok(false);
+        }
+
+        trace!(
+            final_owner = format!("{:#066x}", final_owner.0),
+            grants = grants.len(),
+            revokes = revokes.len(),
+            "Handing off ownership."
+        );
+
+        let ui_text = format!("Handing off ownership of {} resources...", selectors.len());
+        self.step(ui, ui_text);
+
+        // The ownership is verified once the transaction is accepted.
+        let txn_config = TxnConfig { wait: true, ..self.txn_config };
+        let mut invoker = Invoker::new(&self.world.account, txn_config);
+        for call in grants.into_iter().chain(revokes) {
+            invoker.add_call(call);
+        }
+        self.record([&invoker.multicall().await?]);
+
+        let ownership = self.ownership(&selectors, &final_owner, &deployer).await?;
+        for (selector, (final_owner_is_owner, deployer_is_owner)) in selectors.iter().zip(ownership)
+        {
+            if !final_owner_is_owner || deployer_is_owner {
+                return Err(MigrationError::OwnershipHandoff(format!("{selector:#066x}")));
+            }
+        }
+
+        Ok(true)
+    }
+
+    /// Returns whether the final owner and the deployer are owners of each of the resources,
+    /// the world being queried concurrently.
+    async fn ownership(
+        &self,
+        selectors: &[Felt],
+        final_owner: &ContractAddress,
+        deployer: &ContractAddress,
+    ) -> Result<Vec<(bool, bool)>, MigrationError<A::SignError>> {
+        let is_owner = |account| {
+            let calls = selectors.iter().map(move |s| self.world.is_owner(s, account).call());
+            futures::future::try_join_all(calls)
+        };
+
+        let (final_owner, deployer) =
+            futures::try_join!(is_owner(final_owner), is_owner(deployer))?;
+
+        Ok(final_owner.into_iter().zip(deployer).collect())
+    }
+
     /// Syncs the permissions.
     ///
     /// This first version is naive, and only applies the local permissions to the resources, if the
@@ -275,14 +443,14 @@ where
 
         if self.do_multicall() {
             let ui_text = format!("Syncing {} permissions...", invoker.calls.len());
-            ui.update_text_boxed(ui_text);
+            self.step(ui, ui_text);
 
-            invoker.multicall().await?;
+            self.record([&invoker.multicall().await?]);
         } else {
             let ui_text = format!("Syncing {} permissions (sequentially)...", invoker.calls.len());
-            ui.update_text_boxed(ui_text);
+            self.step(ui, ui_text);
 
-            invoker.invoke_all_sequentially().await?;
+            self.record(&invoker.invoke_all_sequentially().await?);
         }
 
         Ok(has_changed)
@@ -363,9 +531,9 @@ where
             declarer.extend_classes(classes.into_values().collect());
 
             let ui_text = format!("Declaring {} classes...", n_classes);
-            ui.update_text_boxed(ui_text);
+            self.step(ui, ui_text);
 
-            declarer.declare_all().await?;
+            self.record(&declarer.declare_all().await?);
         } else {
             trace!("Declaring classes with {} accounts.", accounts.len());
             let mut declarers = vec![];
@@ -380,37 +548,40 @@ where
 
             let ui_text =
                 format!("Declaring {} classes with {} accounts...", n_classes, declarers.len());
-            ui.update_text_boxed(ui_text);
+            self.step(ui, ui_text);
 
             let declarers_futures =
                 futures::future::join_all(declarers.into_iter().map(|d| d.declare_all())).await;
 
             for declarer_results in declarers_futures {
-                if let Err(e) = declarer_results {
-                    // The issue is that `e` is bound to concrete type `SingleOwnerAccount`.
-                    // Thus, we can't return `e` directly.
-                    // Might have a better solution by addind a new variant?
-                    if e.to_string().contains("Class already declared") {
-                        // If the class is already declared, it might be because it was already
-                        // declared in a previous run or an other declarer.
-                        continue;
+                match declarer_results {
+                    Ok(results) => self.record(&results),
+                    Err(e) => {
+                        // The issue is that `e` is bound to concrete type `SingleOwnerAccount`.
+                        // Thus, we can't return `e` directly.
+                        // Might have a better solution by addind a new variant?
+                        if e.to_string().contains("Class already declared") {
+                            // If the class is already declared, it might be because it was already
+                            // declared in a previous run or an other declarer.
+                            continue;
+                        }
+
+                        return Err(MigrationError::DeclareClassError(e.to_string()));
                     }
-
-                    return Err(MigrationError::DeclareClassError(e.to_string()));
                 }
             }
         }
 
         if self.do_multicall() {
             let ui_text = format!("Registering {} resources...", n_resources);
-            ui.update_text_boxed(ui_text);
+            self.step(ui, ui_text);
 
-            invoker.multicall().await?;
+            self.record([&invoker.multicall().await?]);
         } else {
             let ui_text = format!("Registering {} resources (sequentially)...", n_resources);
-            ui.update_text_boxed(ui_text);
+            self.step(ui, ui_text);
 
-            invoker.invoke_all_sequentially().await?;
+            self.record(&invoker.invoke_all_sequentially().await?);
         }
 
         Ok(has_changed)
@@ -654,7 +825,7 @@ where
         match &self.diff.world_info.status {
             WorldStatus::Synced => return Ok(false),
             WorldStatus::NotDeployed => {
-                ui.update_text("Deploying the world...");
+                self.step(ui, "Deploying the world...".to_string());
                 trace!("Deploying the first world.");
 
                 let labeled_class = LabeledClass {
@@ -663,7 +834,9 @@ where
                     class: self.diff.world_info.class.clone().flatten()?,
                 };
 
-                Declarer::declare(labeled_class, &self.world.account, &self.txn_config).await?;
+                let result =
+                    Declarer::declare(labeled_class, &self.world.account, &self.txn_config).await?;
+                self.record([&result]);
 
                 // We want to wait for the receipt to be be able to print the
                 // world block number.
@@ -682,6 +855,8 @@ where
                     )
                     .await?;
 
+                self.record([&res]);
+
                 match res {
                     TransactionResult::HashReceipt(hash, receipt) => {
                         let block_msg = if let Some(n) = receipt.block.block_number() {
@@ -712,7 +887,7 @@ where
             }
             WorldStatus::NewVersion => {
                 trace!("Upgrading the world.");
-                ui.update_text("Upgrading the world...");
+                self.step(ui, "Upgrading the world...".to_string());
 
                 let labeled_class = LabeledClass {
                     label: "world".to_string(),
@@ -720,7 +895,9 @@ where
                     class: self.diff.world_info.class.clone().flatten()?,
                 };
 
-                Declarer::declare(labeled_class, &self.world.account, &self.txn_config).await?;
+                let result =
+                    Declarer::declare(labeled_class, &self.world.account, &self.txn_config).await?;
+                self.record([&result]);
 
                 let mut invoker = Invoker::new(&self.world.account, self.txn_config);
 
@@ -728,7 +905,7 @@ where
                     self.world.upgrade_getcall(&ClassHash(self.diff.world_info.class_hash)),
                 );
 
-                invoker.multicall().await?;
+                self.record([&invoker.multicall().await?]);
             }
         };
 
@@ -0,0 +1,443 @@
+//! Export and import of the entities stored in a world.
+//!
+//! The entities of a model can't be enumerated from the world storage directly. Hence, the
+//! entities to dump are discovered from the `StoreSetRecord` and `StoreDelRecord` events emitted
+//! by the world, and their latest values are then read from the world storage using the model
+//! layout.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use dojo_utils::{Invoker, TransactionResult, TxnConfig};
+use dojo_world::contracts::abigen::world::{ModelIndex, StoreDelRecord, StoreSetRecord};
+use dojo_world::contracts::model::ModelReader;
+use dojo_world::contracts::naming::compute_selector_from_tag;
+use dojo_world::contracts::world::{WorldContract, WorldContractReader};
+use serde::{Deserialize, Serialize};
+use starknet::accounts::ConnectedAccount;
+use starknet::core::types::{BlockId, EventFilter, Felt};
+use starknet::providers::Provider;
+use tracing::trace;
+
+/// The default number of events fetched per `starknet_getEvents` request.
+const EVENTS_CHUNK_SIZE: u64 = 1000;
+
+/// The current version of the state dump format.
+pub const STATE_DUMP_VERSION: u32 = 1;
+
+/// A dump of the entities of some models of a world.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateDump {
+    /// The version of the dump format.
+    pub version: u32,
+    /// The address of the world the entities have been read from.
+    pub world_address: Felt,
+    /// The dumped models.
+    pub models: Vec<ModelDump>,
+}
+
+/// The entities of a single model.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ModelDump {
+    /// The tag of the model.
+    pub tag: String,
+    /// The entities of the model, ordered by first appearance on chain.
+    pub entities: Vec<EntityDump>,
+}
+
+/// A single entity, represented by its keys and its serialized values.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntityDump {
+    pub keys: Vec<Felt>,
+    pub values: Vec<Felt>,
+}
+
+impl StateDump {
+    /// Reads a state dump from a JSON file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read state dump `{}`.", path.display()))?;
+        let dump: Self = serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse state dump `{}`.", path.display()))?;
+
+        if dump.version != STATE_DUMP_VERSION {
+            anyhow::bail!(
+                "Unsupported state dump version {} (expected {}).",
+                dump.version,
+                STATE_DUMP_VERSION
+            );
+        }
+
+        Ok(dump)
+    }
+
+    /// Writes the state dump as pretty JSON into a file.
+    pub fn to_file(&self, path: &Path) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write state dump `{}`.", path.display()))
+    }
+}
+
+/// A set of rules translating felts of a state dump before it's loaded into another world.
+///
+/// Useful to anonymize production snapshots (e.g. replacing player addresses) or to avoid
+/// collisions with existing entities (e.g. renumbering game ids).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Remapping {
+    pub rules: Vec<RemapRule>,
+}
+
+/// A single remapping rule. A felt is first looked up in `map`, and if not found, `offset` is
+/// added to it when provided. Rules are applied in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemapRule {
+    /// Tags of the models the rule applies to. Applies to all the models if empty.
+    #[serde(default)]
+    pub models: Vec<String>,
+    /// Index of the key the rule applies to. Applies to all the keys if not set.
+    #[serde(default)]
+    pub key_index: Option<usize>,
+    /// Whether the `map` translations are also applied to the values of the entities.
+    #[serde(default)]
+    pub values: bool,
+    /// Exact translations of felts.
+    #[serde(default)]
+    pub map: Vec<FeltMapping>,
+    /// Offset added to the keys not found in `map`.
+    #[serde(default)]
+    pub offset: Option<Felt>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeltMapping {
+    pub from: Felt,
+    pub to: Felt,
+}
+
+impl Remapping {
+    /// Reads a remapping from a JSON file.
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read remapping `{}`.", path.display()))?;
+        serde_json::from_str(&content)
+            .with_context(|| format!("Failed to parse remapping `{}`.", path.display()))
+    }
+
+    /// Applies the remapping rules to all the entities of a state dump.
+    pub fn apply(&self, dump: &mut StateDump) {
+        for model in dump.models.iter_mut() {
+            let rules = self
+                .rules
+                .iter()
+                .filter(|r| r.models.is_empty() || r.models.contains(&model.tag))
+                .collect::<Vec<_>>();
+
+            if rules.is_empty() {
+                continue;
+            }
+
+            for entity in model.entities.iter_mut() {
+                for rule in &rules {
+                    rule.apply(entity);
+                }
+            }
+        }
+    }
+}
+
+impl RemapRule {
+    fn apply(&self, entity: &mut EntityDump) {
+        for (i, key) in entity.keys.iter_mut().enumerate() {
+            if self.key_index.map_or(true, |k| k == i) {
+                *key = match self.lookup(key) {
+                    Some(to) => to,
+                    None => self.offset.map_or(*key, |o| *key + o),
+                };
+            }
+        }
+
+        if self.values {
+            for value in entity.values.iter_mut() {
+                if let Some(to) = self.lookup(value) {
+                    *value = to;
+                }
+            }
+        }
+    }
+
+    fn lookup(&self, felt: &Felt) -> Option<Felt> {
+        self.map.iter().find(|m| &m.from == felt).map(|m| m.to)
+    }
+}
+
+/// Dumps all the entities of the given models of a world.
+///
+/// The entities are discovered by scanning the world events from `from_block` up to `block_id`,
+/// and their values are read at `block_id`.
+pub async fn dump_state<P>(
+    tags: &[String],
+    world_address: Felt,
+    provider: P,
+    from_block: Option<BlockId>,
+    block_id: BlockId,
+) -> Result<StateDump>
+where
+    P: Provider + Send + Sync,
+{
+    let selectors = tags.iter().map(|t| compute_selector_from_tag(t)).collect::<Vec<_>>();
+    let mut entities_keys =
+        fetch_entities_keys(&selectors, world_address, &provider, from_block, block_id).await?;
+
+    let mut world_reader = WorldContractReader::new(world_address, &provider);
+    world_reader.set_block(block_id);
+
+    let mut models = vec![];
+
+    for (tag, selector) in tags.iter().zip(selectors.iter()) {
+        let model = world_reader.model_reader_with_tag(tag).await?;
+        let keys_list = entities_keys.remove(selector).unwrap_or_default();
+
+        let mut entities = vec![];
+        for keys in keys_list {
+            let values = model.entity_storage(&keys).await?;
+            entities.push(EntityDump { keys, values });
+        }
+
+        trace!(
+            tag,
+            selector = format!("{:#x}", model.selector()),
+            count = entities.len(),
+            "Dumped model."
+        );
+        models.push(ModelDump { tag: tag.clone(), entities });
+    }
+
+    Ok(StateDump { version: STATE_DUMP_VERSION, world_address, models })
+}
+
+/// Writes the entities of a state dump into a world.
+///
+/// The models must already be registered in the target world, and the account must have
+/// the writer permission on them. Entities are written with `set_entities`, one transaction per
+/// chunk of `chunk_size` entities, to keep each transaction within the step and calldata limits.
+pub async fn load_state<A>(
+    dump: &StateDump,
+    world_address: Felt,
+    account: &A,
+    txn_config: TxnConfig,
+    chunk_size: usize,
+) -> Result<Vec<TransactionResult>>
+where
+    A: ConnectedAccount + Sync + Send,
+    <A as ConnectedAccount>::Provider: Send,
+{
+    let world_reader = WorldContractReader::new(world_address, account.provider());
+    let world = WorldContract::new(world_address, account);
+
+    let mut invoker = Invoker::new(account, txn_config);
+
+    for model_dump in &dump.models {
+        if model_dump.entities.is_empty() {
+            continue;
+        }
+
+        let model = world_reader
+            .model_reader_with_tag(&model_dump.tag)
+            .await
+            .with_context(|| format!("Model `{}` not found in target world.", model_dump.tag))?;
+        let layout = model.world_layout().await?;
+
+        for chunk in model_dump.entities.chunks(chunk_size.max(1)) {
+            let indexes = chunk.iter().map(|e| ModelIndex::Keys(e.keys.clone())).collect();
+            let values = chunk.iter().map(|e| e.values.clone()).collect();
+
+            invoker.add_call(world.set_entities_getcall(
+                &model.selector(),
+                &indexes,
+                &values,
+                &layout,
+            ));
+        }
+
+        trace!(tag = model_dump.tag, count = model_dump.entities.len(), "Loading model.");
+    }
+
+    Ok(invoker.invoke_all_sequentially().await?)
+}
+
+/// Fetches the keys of all the entities that are set at `to_block` for the given models, indexed
+/// by model selector.
+async fn fetch_entities_keys<P>(
+    selectors: &[Felt],
+    world_address: Felt,
+    provider: &P,
+    from_block: Option<BlockId>,
+    to_block: BlockId,
+) -> Result<HashMap<Felt, Vec<Vec<Felt>>>>
+where
+    P: Provider + Send + Sync,
+{
+    let set_selector = StoreSetRecord::event_selector();
+    let del_selector = StoreDelRecord::event_selector();
+
+    let filter = EventFilter {
+        from_block,
+        to_block: Some(to_block),
+        address: Some(world_address),
+        keys: Some(vec![vec![set_selector, del_selector], selectors.to_vec()]),
+    };
+
+    // Entities ordered by first appearance, which keeps the dump stable between runs.
+    let mut entities: HashMap<Felt, ModelEntities> = HashMap::new();
+    let mut continuation_token = None;
+
+    loop {
+        let page =
+            provider.get_events(filter.clone(), continuation_token, EVENTS_CHUNK_SIZE).await?;
+
+        for event in page.events {
+            // keys: [event selector, model selector, entity id]
+            let (Some(model_selector), Some(entity_id)) = (event.keys.get(1), event.keys.get(2))
+            else {
+                continue;
+            };
+
+            let model_entities = entities.entry(*model_selector).or_default();
+
+            if event.keys[0] == del_selector {
+                model_entities.remove(entity_id);
+                continue;
+            }
+
+            // data: [keys len, keys..., values len, values...]
+            let keys_len = event.data.first().and_then(|l| usize::try_from(*l).ok()).unwrap_or(0);
+            let Some(keys) = event.data.get(1..1 + keys_len) else {
+                continue;
+            };
+
+            model_entities.insert(*entity_id, keys);
+        }
+
+        continuation_token = page.continuation_token;
+        if continuation_token.is_none() {
+            break;
+        }
+    }
+
+    Ok(entities.into_iter().map(|(selector, entities)| (selector, entities.into_keys())).collect())
+}
+
+/// The keys of the entities of a model, by entity id and ordered by first appearance.
+#[derive(Debug, Default)]
+struct ModelEntities {
+    /// The keys of the entities, `None` once the entity is deleted.
+    keys: Vec<Option<Vec<Felt>>>,
+    /// The position of each entity in `keys`.
+    positions: HashMap<Felt, usize>,
+}
+
+impl ModelEntities {
+    /// Inserts an entity, if it's not already present.
+    fn insert(&mut self, entity_id: Felt, keys: &[Felt]) {
+        if !self.positions.contains_key(&entity_id) {
+            self.positions.insert(entity_id, self.keys.len());
+            self.keys.push(Some(keys.to_vec()));
+        }
+    }
+
+    /// Removes an entity, if it's present.
+    fn remove(&mut self, entity_id: &Felt) {
+        if let Some(position) = self.positions.remove(entity_id) {
+            self.keys[position] = None;
+        }
+    }
+
+    /// Returns the keys of the entities, ordered by first appearance.
+    fn into_keys(self) -> Vec<Vec<Felt>> {
+        self.keys.into_iter().flatten().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_dump_roundtrip() {
+        let dump = StateDump {
+            version: STATE_DUMP_VERSION,
+            world_address: Felt::from(0x1234),
+            models: vec![ModelDump {
+                tag: "ns-Position".to_string(),
+                entities: vec![EntityDump {
+                    keys: vec![Felt::ONE],
+                    values: vec![Felt::TWO, Felt::THREE],
+                }],
+            }],
+        };
+
+        let file = assert_fs::NamedTempFile::new("state.json").unwrap();
+        dump.to_file(file.path()).unwrap();
+
+        assert_eq!(StateDump::from_file(file.path()).unwrap(), dump);
+    }
+
+    #[test]
+    fn model_entities_keep_first_appearance_order() {
+        let mut entities = ModelEntities::default();
+        entities.insert(Felt::ONE, &[Felt::from(10)]);
+        entities.insert(Felt::TWO, &[Felt::from(20)]);
+        entities.insert(Felt::ONE, &[Felt::from(11)]);
+        entities.insert(Felt::THREE, &[Felt::from(30)]);
+
+        entities.remove(&Felt::TWO);
+        // an entity set again after its deletion appears last
+        entities.insert(Felt::TWO, &[Felt::from(21)]);
+
+        assert_eq!(
+            entities.into_keys(),
+            vec![vec![Felt::from(10)], vec![Felt::from(30)], vec![Felt::from(21)]]
+        );
+    }
+
+    #[test]
+    fn remapping_translates_keys_and_values() {
+        let player = Felt::from(0xabc);
+        let anon = Felt::from(0x1);
+
+        let mut dump = StateDump {
+            version: STATE_DUMP_VERSION,
+            world_address: Felt::ZERO,
+            models: vec![
+                ModelDump {
+                    tag: "ns-Game".to_string(),
+                    entities: vec![EntityDump {
+                        keys: vec![Felt::from(7), player],
+                        values: vec![player, Felt::from(42)],
+                    }],
+                },
+                ModelDump {
+                    tag: "ns-Health".to_string(),
+                    entities: vec![EntityDump { keys: vec![player], values: vec![Felt::TWO] }],
+                },
+            ],
+        };
+
+        let remapping: Remapping = serde_json::from_value(serde_json::json!({
+            "rules": [
+                { "values": true, "map": [{ "from": "0xabc", "to": "0x1" }] },
+                { "models": ["ns-Game"], "key_index": 0, "offset": "0x100" }
+            ]
+        }))
+        .unwrap();
+
+        remapping.apply(&mut dump);
+
+        assert_eq!(dump.models[0].entities[0].keys, vec![Felt::from(0x107), anon]);
+        assert_eq!(dump.models[0].entities[0].values, vec![anon, Felt::from(42)]);
+        assert_eq!(dump.models[1].entities[0].keys, vec![anon]);
+        assert_eq!(dump.models[1].entities[0].values, vec![Felt::TWO]);
+    }
+}
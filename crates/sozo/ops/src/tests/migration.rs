@@ -2,14 +2,19 @@
 use std::sync::Arc;
 
 use anyhow::Result;
+use cainome::cairo_serde::ContractAddress;
 use dojo_test_utils::compiler::CompilerTestSetup;
 use dojo_test_utils::migration::copy_spawn_and_move_db;
 use dojo_utils::TxnConfig;
+use dojo_world::config::migration_config::MigrationConfig;
+use dojo_world::config::ProfileConfig;
 use dojo_world::contracts::WorldContract;
 use dojo_world::diff::WorldDiff;
 use katana_runner::RunnerCtx;
 use scarb::compiler::Profile;
 use sozo_scarbext::WorkspaceExt;
+use starknet::accounts::ConnectedAccount;
+use starknet::core::types::Felt;
 use starknet::providers::jsonrpc::HttpTransport;
 use starknet::providers::JsonRpcClient;
 
@@ -38,6 +43,15 @@ async fn setup_migration(
 
 /// Migrates the spawn-and-move project from the local environment.
 async fn migrate_spawn_and_move(sequencer: &RunnerCtx) -> MigrationResult {
+    migrate_spawn_and_move_with(sequencer, |_| {}).await
+}
+
+/// Migrates the spawn-and-move project from the local environment, with its profile config
+/// edited by `edit`.
+async fn migrate_spawn_and_move_with(
+    sequencer: &RunnerCtx,
+    edit: impl FnOnce(&mut ProfileConfig),
+) -> MigrationResult {
     let account = sequencer.account(0);
     let provider = Arc::new(JsonRpcClient::new(HttpTransport::new(sequencer.url())));
 
@@ -46,7 +60,8 @@ async fn migrate_spawn_and_move(sequencer: &RunnerCtx) -> MigrationResult {
         .expect("Failed to setup migration");
 
     let world_address = world_diff.world_info.address;
-    let profile_config = world_diff.profile_config.clone();
+    let mut profile_config = world_diff.profile_config.clone();
+    edit(&mut profile_config);
 
     let migration = Migration::new(
         world_diff,
@@ -64,16 +79,44 @@ async fn migrate_spawn_and_move(sequencer: &RunnerCtx) -> MigrationResult {
 #[tokio::test(flavor = "multi_thread")]
 #[katana_runner::test(accounts = 10)]
 async fn migrate_from_local(sequencer: &RunnerCtx) {
-    let MigrationResult { manifest, has_changes } = migrate_spawn_and_move(sequencer).await;
+    let MigrationResult { manifest, has_changes, transactions } =
+        migrate_spawn_and_move(sequencer).await;
 
     assert!(has_changes);
+    assert!(!transactions.is_empty());
     assert_eq!(manifest.contracts.len(), 4);
 }
 
 #[tokio::test(flavor = "multi_thread")]
 #[katana_runner::test(accounts = 10, db_dir = copy_spawn_and_move_db().as_str())]
 async fn migrate_no_change(sequencer: &RunnerCtx) {
-    let MigrationResult { manifest, has_changes } = migrate_spawn_and_move(sequencer).await;
+    let MigrationResult { manifest, has_changes, transactions } =
+        migrate_spawn_and_move(sequencer).await;
     assert!(!has_changes);
+    assert!(transactions.is_empty());
     assert_eq!(manifest.contracts.len(), 4);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+#[katana_runner::test(accounts = 10)]
+async fn migrate_hands_off_ownership(sequencer: &RunnerCtx) {
+    let deployer = sequencer.account(0).address();
+    let final_owner = sequencer.account(1).address();
+
+    let MigrationResult { manifest, .. } = migrate_spawn_and_move_with(sequencer, |config| {
+        config.migration.get_or_insert_with(MigrationConfig::default).final_owner =
+            Some(final_owner);
+    })
+    .await;
+
+    let account = sequencer.account(0);
+    let world = WorldContract::new(manifest.world.address, &account);
+    let resources = manifest.contracts.iter().map(|c| c.selector);
+    let resources = resources.chain(manifest.models.iter().map(|m| m.selector));
+
+    for selector in resources.chain([Felt::ZERO]) {
+        let is_owner = |account| world.is_owner(&selector, &ContractAddress(account)).call();
+        assert!(is_owner(final_owner).await.unwrap(), "{selector:#x} is owned by the final owner");
+        assert!(!is_owner(deployer).await.unwrap(), "{selector:#x} isn't owned by the deployer");
+    }
+}
@@ -0,0 +1,172 @@
+//! Compatibility of the local world class with the deployed world.
+//!
+//! The world class is built from the dojo-core version of the project, and the contracts and
+//! models are compiled against its interface. When the deployed world has a different class, the
+//! migration upgrades it, which breaks the callers of the entrypoints the local class removes.
+
+use anyhow::{bail, Result};
+use dojo_world::diff::WorldDiff;
+use starknet::core::types::contract::{AbiEntry, StateMutability};
+use starknet::core::types::{BlockId, BlockTag, ContractClass, Felt};
+use starknet::providers::Provider;
+
+/// The compatibility of the local world class with the deployed world.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WorldCompatibility {
+    /// The world is not deployed yet.
+    NotDeployed,
+    /// The deployed world has the same class as the local world.
+    Synced,
+    /// The local world only adds entrypoints, the deployed world can be upgraded safely.
+    Upgradable,
+    /// The local world removes entrypoints of the deployed world.
+    Breaking,
+}
+
+/// The result of the comparison of the local world class with the deployed one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpgradeCheck {
+    pub local_class_hash: Felt,
+    pub remote_class_hash: Option<Felt>,
+    /// Entrypoints of the local world missing from the deployed world.
+    pub added_entrypoints: Vec<String>,
+    /// Entrypoints of the deployed world missing from the local world.
+    pub removed_entrypoints: Vec<String>,
+}
+
+impl UpgradeCheck {
+    /// Compares the entrypoints of the local world with the ones of the deployed world.
+    pub fn new(
+        local_class_hash: Felt,
+        remote_class_hash: Option<Felt>,
+        local_entrypoints: &[String],
+        remote_entrypoints: &[String],
+    ) -> Self {
+        let added_entrypoints =
+            local_entrypoints.iter().filter(|e| !remote_entrypoints.contains(e)).cloned().collect();
+        let removed_entrypoints =
+            remote_entrypoints.iter().filter(|e| !local_entrypoints.contains(e)).cloned().collect();
+
+        Self { local_class_hash, remote_class_hash, added_entrypoints, removed_entrypoints }
+    }
+
+    pub fn compatibility(&self) -> WorldCompatibility {
+        match self.remote_class_hash {
+            None => WorldCompatibility::NotDeployed,
+            Some(remote) if remote == self.local_class_hash => WorldCompatibility::Synced,
+            Some(_) if self.removed_entrypoints.is_empty() => WorldCompatibility::Upgradable,
+            Some(_) => WorldCompatibility::Breaking,
+        }
+    }
+
+    /// Describes what the migration does with the deployed world, and what has to be done before
+    /// migrating when the upgrade is breaking.
+    pub fn migration_path(&self) -> String {
+        match self.compatibility() {
+            WorldCompatibility::NotDeployed => {
+                "The world is not deployed, `sozo migrate` deploys the local world class."
+                    .to_string()
+            }
+            WorldCompatibility::Synced => {
+                "The deployed world has the same class as the local world, no upgrade is required."
+                    .to_string()
+            }
+            WorldCompatibility::Upgradable => format!(
+                "The deployed world has an older class, `sozo migrate` upgrades it to the local \
+                 world class. The upgrade only adds entrypoints ({}).",
+                list_or_none(&self.added_entrypoints)
+            ),
+            WorldCompatibility::Breaking => format!(
+                "The local world class removes entrypoints of the deployed world ({}). Contracts \
+                 and clients calling them will fail once `sozo migrate` upgrades the world. \
+                 Either update the callers before migrating, use a dojo version matching the \
+                 deployed world, or deploy a new world with a different seed.",
+                self.removed_entrypoints.join(", ")
+            ),
+        }
+    }
+}
+
+/// Compares the local world class of a diff with the class of the deployed world.
+pub async fn check_world_upgrade<P>(diff: &WorldDiff, provider: &P) -> Result<UpgradeCheck>
+where
+    P: Provider,
+{
+    let local = &diff.world_info;
+
+    let Some(remote_class_hash) = local.remote_class_hash else {
+        return Ok(UpgradeCheck::new(local.class_hash, None, &local.entrypoints, &[]));
+    };
+
+    if remote_class_hash == local.class_hash {
+        return Ok(UpgradeCheck::new(
+            local.class_hash,
+            Some(remote_class_hash),
+            &local.entrypoints,
+            &local.entrypoints,
+        ));
+    }
+
+    let remote_abi =
+        match provider.get_class(BlockId::Tag(BlockTag::Pending), remote_class_hash).await? {
+            ContractClass::Sierra(class) => serde_json::from_str::<Vec<AbiEntry>>(&class.abi)?,
+            ContractClass::Legacy(_) => {
+                bail!("The deployed world {remote_class_hash:#x} is not a Cairo 1 class.")
+            }
+        };
+
+    Ok(UpgradeCheck::new(
+        local.class_hash,
+        Some(remote_class_hash),
+        &local.entrypoints,
+        &entrypoints(&remote_abi),
+    ))
+}
+
+/// Returns the external functions of an ABI.
+fn entrypoints(abi: &[AbiEntry]) -> Vec<String> {
+    abi.iter()
+        .flat_map(|entry| match entry {
+            AbiEntry::Function(f) if matches!(f.state_mutability, StateMutability::External) => {
+                vec![f.name.clone()]
+            }
+            AbiEntry::Interface(i) => entrypoints(&i.items),
+            _ => vec![],
+        })
+        .collect()
+}
+
+fn list_or_none(entrypoints: &[String]) -> String {
+    if entrypoints.is_empty() { "none".to_string() } else { entrypoints.join(", ") }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|n| n.to_string()).collect()
+    }
+
+    #[test]
+    fn compatibility() {
+        let local = names(&["uuid", "register_model", "upgrade_event"]);
+
+        let check = UpgradeCheck::new(Felt::ONE, None, &local, &[]);
+        assert_eq!(check.compatibility(), WorldCompatibility::NotDeployed);
+
+        let check = UpgradeCheck::new(Felt::ONE, Some(Felt::ONE), &local, &local);
+        assert_eq!(check.compatibility(), WorldCompatibility::Synced);
+
+        let remote = names(&["uuid", "register_model"]);
+        let check = UpgradeCheck::new(Felt::ONE, Some(Felt::TWO), &local, &remote);
+        assert_eq!(check.compatibility(), WorldCompatibility::Upgradable);
+        assert_eq!(check.added_entrypoints, names(&["upgrade_event"]));
+
+        let remote = names(&["uuid", "register_model", "set_entity"]);
+        let check = UpgradeCheck::new(Felt::ONE, Some(Felt::TWO), &local, &remote);
+        assert_eq!(check.compatibility(), WorldCompatibility::Breaking);
+        assert_eq!(check.removed_entrypoints, names(&["set_entity"]));
+        assert!(check.migration_path().contains("set_entity"));
+    }
+}
@@ -1,6 +1,7 @@
 //! Scarb extensions for Sozo.
 
 pub mod filesystem;
+pub mod toolchain;
 pub mod workspace;
 
 pub use filesystem::FilesystemExt;
@@ -0,0 +1,165 @@
+//! Compilation of the packages pinned to a Cairo version different from the one embedded in sozo.
+//!
+//! A package pins its compiler with the `cairo-version` field of its manifest. When the pinned
+//! version doesn't match the embedded compiler, the package must declare the sozo binary built
+//! with the matching Cairo version:
+//!
+//! ```toml
+//! [package]
+//! cairo-version = "=2.7.0"
+//!
+//! [tool.dojo]
+//! toolchain = "~/.dojo/toolchains/v1.0.0-alpha.14/sozo"
+//! ```
+//!
+//! The package is then compiled by this toolchain in its own target directory, and the artifacts
+//! are gathered into the target directory of the workspace, where the local world is loaded from.
+
+use std::fs;
+use std::process::Command;
+use std::str::FromStr;
+
+use anyhow::{bail, Context, Result};
+use camino::{Utf8Path, Utf8PathBuf};
+use scarb::core::{Package, Workspace};
+use semver::{Version, VersionReq};
+
+use crate::workspace::WorkspaceExt;
+
+/// Name of the directory, inside the workspace target directory, where the pinned packages are
+/// compiled.
+const PINNED_TARGET_DIR: &str = "pinned";
+
+/// A sozo binary compiling a package pinned to another Cairo version.
+#[derive(Debug, Clone)]
+pub struct PinnedToolchain {
+    /// The Cairo version required by the package.
+    pub cairo_version: VersionReq,
+    /// Path of the sozo binary to compile the package with.
+    pub binary: Utf8PathBuf,
+}
+
+/// Returns the toolchain to compile the package with, or `None` if the package can be compiled
+/// with the embedded Cairo compiler.
+pub fn pinned_toolchain(package: &Package) -> Result<Option<PinnedToolchain>> {
+    let Some(cairo_version) = package.manifest.metadata.cairo_version.clone() else {
+        return Ok(None);
+    };
+
+    let embedded = Version::from_str(scarb::version::get().cairo.version)?;
+    if cairo_version.matches(&embedded) {
+        return Ok(None);
+    }
+
+    let toolchain = package
+        .tool_metadata("dojo")
+        .and_then(|dojo| dojo.get("toolchain"))
+        .and_then(|toolchain| toolchain.as_str());
+
+    let Some(toolchain) = toolchain else {
+        bail!(
+            "Package `{}` requires Cairo {} but sozo embeds Cairo {}. Set `toolchain` under \
+             [tool.dojo] in {} to the sozo binary built with a matching Cairo version.",
+            package.id.name,
+            cairo_version,
+            embedded,
+            package.manifest_path()
+        );
+    };
+
+    Ok(Some(PinnedToolchain { cairo_version, binary: resolve_binary(package.root(), toolchain) }))
+}
+
+/// Compiles the package with its pinned toolchain, and copies the artifacts into the target
+/// directory of the current profile.
+pub fn compile_with_toolchain(
+    ws: &Workspace<'_>,
+    package: &Package,
+    toolchain: &PinnedToolchain,
+) -> Result<()> {
+    let profile = ws.current_profile()?.to_string();
+    let target_dir =
+        ws.target_dir().path_unchecked().join(PINNED_TARGET_DIR).join(package.id.name.as_str());
+
+    ws.config().ui().print(format!(
+        "Compiling {} with Cairo {} ({})",
+        package.id.name, toolchain.cairo_version, toolchain.binary
+    ));
+
+    let status = Command::new(&toolchain.binary)
+        .arg("--manifest-path")
+        .arg(package.manifest_path())
+        .arg("--profile")
+        .arg(&profile)
+        .arg("build")
+        .env("SCARB_TARGET_DIR", &target_dir)
+        .status()
+        .with_context(|| format!("Failed to run toolchain `{}`.", toolchain.binary))?;
+
+    if !status.success() {
+        bail!("Compilation of package `{}` with `{}` failed.", package.id.name, toolchain.binary);
+    }
+
+    let artifacts_dir = target_dir.join(&profile);
+    let output_dir = ws.target_dir_profile().path_unchecked().to_path_buf();
+    fs::create_dir_all(&output_dir)?;
+
+    for entry in fs::read_dir(&artifacts_dir)
+        .with_context(|| format!("No artifacts found in `{artifacts_dir}`."))?
+    {
+        let entry = entry?;
+        if entry.file_type()?.is_file() {
+            fs::copy(entry.path(), output_dir.join(entry.file_name().to_string_lossy().as_ref()))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Resolves the toolchain relatively to the package root, unless it's a binary name to look up in
+/// the `PATH`.
+fn resolve_binary(package_root: &Utf8Path, toolchain: &str) -> Utf8PathBuf {
+    let toolchain = match toolchain.strip_prefix("~/") {
+        Some(path) => match std::env::var("HOME") {
+            Ok(home) => Utf8PathBuf::from(home).join(path),
+            Err(_) => Utf8PathBuf::from(toolchain),
+        },
+        None => Utf8PathBuf::from(toolchain),
+    };
+
+    if toolchain.is_absolute() || toolchain.components().count() == 1 {
+        toolchain
+    } else {
+        package_root.join(toolchain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toolchain_is_resolved_from_the_package_root() {
+        let root = Utf8Path::new("/project/crates/models");
+
+        assert_eq!(resolve_binary(root, "sozo"), Utf8PathBuf::from("sozo"));
+        assert_eq!(resolve_binary(root, "/opt/dojo/sozo"), Utf8PathBuf::from("/opt/dojo/sozo"));
+        assert_eq!(
+            resolve_binary(root, "../../toolchains/sozo"),
+            Utf8PathBuf::from("/project/crates/models/../../toolchains/sozo")
+        );
+    }
+
+    #[test]
+    fn toolchain_in_home_directory() {
+        let root = Utf8Path::new("/project");
+        let binary = resolve_binary(root, "~/.dojo/toolchains/v1.0.0/sozo");
+
+        match std::env::var("HOME") {
+            Ok(home) => {
+                assert_eq!(binary, Utf8PathBuf::from(home).join(".dojo/toolchains/v1.0.0/sozo"))
+            }
+            Err(_) => assert_eq!(binary, root.join("~/.dojo/toolchains/v1.0.0/sozo")),
+        }
+    }
+}
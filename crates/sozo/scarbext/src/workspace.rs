@@ -113,8 +113,12 @@ impl WorkspaceExt for Workspace<'_> {
         // If the profile file is not found, default to `dev.toml` file that must exist.
         let config_path = if !config_path.exists() { dev_config_path } else { config_path };
 
-        let content = fs::read_to_string(&config_path)?;
-        let config: ProfileConfig = toml::from_str(&content)?;
+        let mut config = ProfileConfig::from_toml(&config_path)?;
+
+        let ignore_path = manifest_dir.join(".dojoignore");
+        if ignore_path.exists() {
+            config.extend_ignore_from_file(&ignore_path)?;
+        }
 
         Ok(config)
     }
@@ -152,6 +156,8 @@ impl WorkspaceExt for Workspace<'_> {
 
         let mut file = manifest_dir.open_ro(manifest_name, "Dojo manifest file", self.config())?;
 
-        Ok(Some(serde_json::from_reader(file.deref_mut())?))
+        // Manifests written by older versions are upgraded, and written back in the current
+        // format on the next migration.
+        Ok(Some(Manifest::from_json(serde_json::from_reader(file.deref_mut())?)?))
     }
 }
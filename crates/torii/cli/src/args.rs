@@ -58,6 +58,9 @@ pub struct ToriiArgs {
     #[command(flatten)]
     pub events: EventsOptions,
 
+    #[command(flatten)]
+    pub webhook: WebhookOptions,
+
     #[cfg(feature = "server")]
     #[command(flatten)]
     pub metrics: MetricsOptions,
@@ -114,6 +117,10 @@ impl ToriiArgs {
             self.events = config.events.unwrap_or_default();
         }
 
+        if self.webhook == WebhookOptions::default() {
+            self.webhook = config.webhook.unwrap_or_default();
+        }
+
         #[cfg(feature = "server")]
         {
             if self.server == ServerOptions::default() {
@@ -142,6 +149,7 @@ pub struct ToriiArgsConfig {
     pub explorer: Option<bool>,
     pub indexing: Option<IndexingOptions>,
     pub events: Option<EventsOptions>,
+    pub webhook: Option<WebhookOptions>,
     #[cfg(feature = "server")]
     pub metrics: Option<MetricsOptions>,
     #[cfg(feature = "server")]
@@ -173,6 +181,8 @@ impl TryFrom<ToriiArgs> for ToriiArgsConfig {
             if args.indexing == IndexingOptions::default() { None } else { Some(args.indexing) };
         config.events =
             if args.events == EventsOptions::default() { None } else { Some(args.events) };
+        config.webhook =
+            if args.webhook == WebhookOptions::default() { None } else { Some(args.webhook) };
 
         #[cfg(feature = "server")]
         {
@@ -193,7 +203,7 @@ mod test {
     use std::net::{IpAddr, Ipv4Addr};
     use std::str::FromStr;
 
-    use torii_core::types::{Contract, ContractType};
+    use torii_core::types::{Aggregation, AggregationFunction, Contract, ContractType, World};
 
     use super::*;
 
@@ -278,6 +288,9 @@ mod test {
             "erc721:0x5678"
         ]
         namespaces = []
+        excluded_models = ["ns-Noisy"]
+        aggregations = ["leaderboard:ns-Score:sum(score):player"]
+        worlds = ["0x4321", "0x8765:http://0.0.0.0:3333"]
         "#;
         let path = std::env::temp_dir().join("torii-config.json");
         std::fs::write(&path, content).unwrap();
@@ -312,8 +325,86 @@ mod test {
                 }
             ]
         );
+        assert_eq!(
+            torii_args.indexing.aggregations,
+            vec![Aggregation {
+                name: "leaderboard".to_string(),
+                model: "ns-Score".to_string(),
+                function: AggregationFunction::Sum,
+                member: "score".to_string(),
+                group_by: vec!["player".to_string()],
+            }]
+        );
+        assert_eq!(torii_args.indexing.excluded_models, vec!["ns-Noisy".to_string()]);
+        assert_eq!(
+            torii_args.indexing.worlds,
+            vec![
+                World { address: Felt::from_str("0x4321").unwrap(), rpc: None },
+                World {
+                    address: Felt::from_str("0x8765").unwrap(),
+                    rpc: Some("http://0.0.0.0:3333".to_string())
+                }
+            ]
+        );
         assert_eq!(torii_args.server.http_addr, IpAddr::V4(Ipv4Addr::LOCALHOST));
         assert_eq!(torii_args.server.http_port, 7777);
         assert_eq!(torii_args.server.http_cors_origins, Some(vec!["*".to_string()]));
     }
+
+    #[test]
+    fn test_webhook_config() {
+        let content = r#"
+        world_address = "0x1234"
+
+        [webhook]
+        url = "http://localhost:3000/torii"
+        secret = "s3cr3t"
+        models = ["ns-Score"]
+        batch_size = 10
+        "#;
+        let path = std::env::temp_dir().join("torii-config-webhook.toml");
+        std::fs::write(&path, content).unwrap();
+
+        let path_str = path.to_string_lossy().to_string();
+
+        let args = vec!["torii", "--config", path_str.as_str()];
+
+        let torii_args = ToriiArgs::parse_from(args).with_config_file().unwrap();
+
+        assert_eq!(
+            torii_args.webhook.url,
+            Some(Url::parse("http://localhost:3000/torii").unwrap())
+        );
+        assert_eq!(torii_args.webhook.secret, Some("s3cr3t".to_string()));
+        assert_eq!(torii_args.webhook.models, vec!["ns-Score".to_string()]);
+        assert_eq!(torii_args.webhook.batch_size, 10);
+        assert_eq!(torii_args.webhook.flush_interval, DEFAULT_WEBHOOK_FLUSH_INTERVAL);
+        assert_eq!(torii_args.webhook.max_retries, DEFAULT_WEBHOOK_MAX_RETRIES);
+    }
+
+    #[test]
+    fn test_relay_config() {
+        let content = r#"
+        world_address = "0x1234"
+
+        [relay]
+        enabled = false
+        "#;
+        let path = std::env::temp_dir().join("torii-config-relay.toml");
+        std::fs::write(&path, content).unwrap();
+
+        let path_str = path.to_string_lossy().to_string();
+
+        let args = vec!["torii", "--config", path_str.as_str()];
+
+        let torii_args = ToriiArgs::parse_from(args).with_config_file().unwrap();
+
+        assert!(!torii_args.relay.enabled);
+        assert_eq!(torii_args.relay.port, DEFAULT_RELAY_PORT);
+        assert_eq!(torii_args.relay.websocket_port, DEFAULT_RELAY_WEBSOCKET_PORT);
+
+        // The relay runs unless explicitly disabled.
+        let torii_args = ToriiArgs::parse_from(vec!["torii"]);
+        assert!(torii_args.relay.enabled);
+    }
 }
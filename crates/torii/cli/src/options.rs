@@ -5,7 +5,8 @@ use anyhow::Context;
 use clap::ArgAction;
 use serde::{Deserialize, Serialize};
 use starknet::core::types::Felt;
-use torii_core::types::{Contract, ContractType};
+use torii_core::types::{Aggregation, Contract, ContractType, World};
+use url::Url;
 
 pub const DEFAULT_HTTP_ADDR: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
 pub const DEFAULT_HTTP_PORT: u16 = 8080;
@@ -15,6 +16,9 @@ pub const DEFAULT_EVENTS_CHUNK_SIZE: u64 = 1024;
 pub const DEFAULT_BLOCKS_CHUNK_SIZE: u64 = 10240;
 pub const DEFAULT_POLLING_INTERVAL: u64 = 500;
 pub const DEFAULT_MAX_CONCURRENT_TASKS: usize = 100;
+pub const DEFAULT_WEBHOOK_BATCH_SIZE: usize = 100;
+pub const DEFAULT_WEBHOOK_FLUSH_INTERVAL: u64 = 1000;
+pub const DEFAULT_WEBHOOK_MAX_RETRIES: u32 = 3;
 
 pub const DEFAULT_RELAY_PORT: u16 = 9090;
 pub const DEFAULT_RELAY_WEBRTC_PORT: u16 = 9091;
@@ -23,6 +27,16 @@ pub const DEFAULT_RELAY_WEBSOCKET_PORT: u16 = 9092;
 #[derive(Debug, clap::Args, Clone, Serialize, Deserialize, PartialEq)]
 #[command(next_help_heading = "Relay options")]
 pub struct RelayOptions {
+    /// Whether or not to run the libp2p relay
+    #[arg(
+        long = "relay.enabled",
+        action = ArgAction::Set,
+        default_value_t = true,
+        help = "Whether or not to run the libp2p relay accepting offchain messages from clients."
+    )]
+    #[serde(default = "default_relay_enabled")]
+    pub enabled: bool,
+
     /// Port to serve Libp2p TCP & UDP Quic transports
     #[arg(
         long = "relay.port",
@@ -78,6 +92,7 @@ pub struct RelayOptions {
 impl Default for RelayOptions {
     fn default() -> Self {
         Self {
+            enabled: true,
             port: DEFAULT_RELAY_PORT,
             webrtc_port: DEFAULT_RELAY_WEBRTC_PORT,
             websocket_port: DEFAULT_RELAY_WEBSOCKET_PORT,
@@ -153,6 +168,42 @@ pub struct IndexingOptions {
     )]
     #[serde(default)]
     pub namespaces: Vec<String>,
+
+    /// Models to exclude from indexing
+    #[arg(
+        long = "indexing.excluded_models",
+        value_delimiter = ',',
+        help = "The tags (namespace-name) of the models that torii should not index, e.g. noisy \
+                models."
+    )]
+    #[serde(default)]
+    pub excluded_models: Vec<String>,
+
+    /// Aggregations to maintain over the models entities
+    #[arg(
+        long = "indexing.aggregations",
+        value_delimiter = ',',
+        help = "Aggregations maintained over the entities of a model and exposed through the \
+                GraphQL API, in the format name:model:function(member)[:group_by+group_by] (ex: \
+                leaderboard:ns-Score:sum(score):player). Supported functions are sum, count, min, \
+                max and avg."
+    )]
+    #[serde(deserialize_with = "deserialize_aggregations")]
+    #[serde(default)]
+    pub aggregations: Vec<Aggregation>,
+
+    /// Additional worlds to index
+    #[arg(
+        long = "indexing.worlds",
+        value_delimiter = ',',
+        help = "Additional worlds to index in the same database, in the format address[:rpc] (ex: \
+                0x1234:http://localhost:5050). If no RPC endpoint is given, the one of torii is \
+                used. The models of a namespace `ns` of an additional world are stored in the \
+                namespace `ns_<world address>`, without the 0x prefix."
+    )]
+    #[serde(deserialize_with = "deserialize_worlds")]
+    #[serde(default)]
+    pub worlds: Vec<World>,
 }
 
 impl Default for IndexingOptions {
@@ -166,6 +217,9 @@ impl Default for IndexingOptions {
             polling_interval: DEFAULT_POLLING_INTERVAL,
             max_concurrent_tasks: DEFAULT_MAX_CONCURRENT_TASKS,
             namespaces: vec![],
+            excluded_models: vec![],
+            aggregations: vec![],
+            worlds: vec![],
         }
     }
 }
@@ -204,6 +258,18 @@ impl IndexingOptions {
             if self.namespaces.is_empty() {
                 self.namespaces = other.namespaces.clone();
             }
+
+            if self.excluded_models.is_empty() {
+                self.excluded_models = other.excluded_models.clone();
+            }
+
+            if self.aggregations.is_empty() {
+                self.aggregations = other.aggregations.clone();
+            }
+
+            if self.worlds.is_empty() {
+                self.worlds = other.worlds.clone();
+            }
         }
     }
 }
@@ -233,6 +299,81 @@ impl Default for EventsOptions {
     }
 }
 
+#[derive(Debug, clap::Args, Clone, Serialize, Deserialize, PartialEq)]
+#[command(next_help_heading = "Webhook options")]
+pub struct WebhookOptions {
+    /// URL to POST the batches of entity updates to
+    #[arg(
+        long = "webhook.url",
+        value_name = "URL",
+        help = "URL to POST the batches of entity updates to. If not set, no webhook is called."
+    )]
+    #[serde(default)]
+    pub url: Option<Url>,
+
+    /// Secret used to sign the webhook requests
+    #[arg(
+        long = "webhook.secret",
+        value_name = "SECRET",
+        help = "Secret used to sign the request bodies with HMAC-SHA256. The signature is sent in \
+                the X-Torii-Signature header."
+    )]
+    #[serde(default)]
+    pub secret: Option<String>,
+
+    /// Models to deliver the updates of
+    /// A list of the model tags (namespace-name)
+    #[arg(
+        long = "webhook.models",
+        value_delimiter = ',',
+        help = "Models (namespace-name) to deliver the entity updates of. If empty, the updates \
+                of all the models are delivered."
+    )]
+    #[serde(default)]
+    pub models: Vec<String>,
+
+    /// Maximum number of updates per request
+    #[arg(
+        long = "webhook.batch_size",
+        default_value_t = DEFAULT_WEBHOOK_BATCH_SIZE,
+        help = "Maximum number of entity updates sent in a single request."
+    )]
+    #[serde(default = "default_webhook_batch_size")]
+    pub batch_size: usize,
+
+    /// Flush interval in ms
+    #[arg(
+        long = "webhook.flush_interval",
+        default_value_t = DEFAULT_WEBHOOK_FLUSH_INTERVAL,
+        help = "Maximum time in ms an entity update is buffered before being delivered."
+    )]
+    #[serde(default = "default_webhook_flush_interval")]
+    pub flush_interval: u64,
+
+    /// Number of retries of a failed delivery
+    #[arg(
+        long = "webhook.max_retries",
+        default_value_t = DEFAULT_WEBHOOK_MAX_RETRIES,
+        help = "Number of times a failed delivery is retried with an exponential backoff before \
+                the batch is dropped."
+    )]
+    #[serde(default = "default_webhook_max_retries")]
+    pub max_retries: u32,
+}
+
+impl Default for WebhookOptions {
+    fn default() -> Self {
+        Self {
+            url: None,
+            secret: None,
+            models: vec![],
+            batch_size: DEFAULT_WEBHOOK_BATCH_SIZE,
+            flush_interval: DEFAULT_WEBHOOK_FLUSH_INTERVAL,
+            max_retries: DEFAULT_WEBHOOK_MAX_RETRIES,
+        }
+    }
+}
+
 #[derive(Debug, clap::Args, Clone, Serialize, Deserialize, PartialEq)]
 #[command(next_help_heading = "HTTP server options")]
 pub struct ServerOptions {
@@ -327,6 +468,22 @@ where
     contracts.iter().map(|s| parse_erc_contract(s).map_err(serde::de::Error::custom)).collect()
 }
 
+fn deserialize_aggregations<'de, D>(deserializer: D) -> Result<Vec<Aggregation>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let aggregations: Vec<String> = Vec::deserialize(deserializer)?;
+    aggregations.iter().map(|s| s.parse().map_err(serde::de::Error::custom)).collect()
+}
+
+fn deserialize_worlds<'de, D>(deserializer: D) -> Result<Vec<World>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let worlds: Vec<String> = Vec::deserialize(deserializer)?;
+    worlds.iter().map(|s| s.parse().map_err(serde::de::Error::custom)).collect()
+}
+
 // ** Default functions to setup serde of the configuration file **
 fn default_http_addr() -> IpAddr {
     DEFAULT_HTTP_ADDR
@@ -360,6 +517,22 @@ fn default_max_concurrent_tasks() -> usize {
     DEFAULT_MAX_CONCURRENT_TASKS
 }
 
+fn default_webhook_batch_size() -> usize {
+    DEFAULT_WEBHOOK_BATCH_SIZE
+}
+
+fn default_webhook_flush_interval() -> u64 {
+    DEFAULT_WEBHOOK_FLUSH_INTERVAL
+}
+
+fn default_webhook_max_retries() -> u32 {
+    DEFAULT_WEBHOOK_MAX_RETRIES
+}
+
+fn default_relay_enabled() -> bool {
+    true
+}
+
 fn default_relay_port() -> u16 {
     DEFAULT_RELAY_PORT
 }
@@ -1,3 +1,7 @@
 pub const TOKEN_BALANCE_TABLE: &str = "token_balances";
 pub const TOKEN_TRANSFER_TABLE: &str = "token_transfers";
 pub const TOKENS_TABLE: &str = "tokens";
+pub const AGGREGATIONS_TABLE: &str = "aggregations";
+pub const ACHIEVEMENTS_TABLE: &str = "achievements";
+pub const ACHIEVEMENT_PROGRESSIONS_TABLE: &str = "achievement_progressions";
+pub const ACHIEVEMENT_PROGRESSION_EVENTS_TABLE: &str = "achievement_progression_events";
@@ -139,7 +139,7 @@ bitflags! {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct EngineConfig {
     pub polling_interval: Duration,
     pub start_block: u64,
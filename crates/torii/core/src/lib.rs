@@ -10,3 +10,4 @@ pub mod simple_broker;
 pub mod sql;
 pub mod types;
 pub mod utils;
+pub mod webhook;
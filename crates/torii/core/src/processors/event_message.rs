@@ -4,8 +4,9 @@ use dojo_world::contracts::abigen::world::Event as WorldEvent;
 use dojo_world::contracts::world::WorldContractReader;
 use starknet::core::types::{Event, Felt};
 use starknet::providers::Provider;
-use tracing::info;
+use tracing::{info, warn};
 
+use super::lazy_model::model_or_register;
 use super::{EventProcessor, EventProcessorConfig};
 use crate::sql::Sql;
 
@@ -29,7 +30,7 @@ where
 
     async fn process(
         &self,
-        _world: &WorldContractReader<P>,
+        world: &WorldContractReader<P>,
         db: &mut Sql,
         _block_number: u64,
         block_timestamp: u64,
@@ -51,10 +52,11 @@ where
             }
         };
 
-        // silently ignore if the model is not found
-        let model = match db.model(event.selector).await {
-            Ok(model) => model,
-            Err(_) => return Ok(()),
+        // silently ignore if the model is not indexed
+        let Some(model) =
+            model_or_register(world, db, event.selector, block_timestamp, config).await?
+        else {
+            return Ok(());
         };
 
         info!(
@@ -72,6 +74,24 @@ where
         let mut entity = model.schema.clone();
         entity.deserialize(&mut keys_and_unpacked)?;
 
+        // Achievement events are materialized on top of being stored as event messages. A model
+        // that only shares the name of an achievement event must not stop the indexing.
+        if let Err(e) = db.store_achievement_event(
+            &entity,
+            &model.namespace,
+            &model.name,
+            event_id,
+            block_timestamp,
+        ) {
+            warn!(
+                target: LOG_TARGET,
+                namespace = %model.namespace,
+                name = %model.name,
+                error = %e,
+                "Storing achievement event."
+            );
+        }
+
         // TODO: this must come from some torii's configuration.
         let historical =
             config.historical_events.contains(&format!("{}-{}", model.namespace, model.name));
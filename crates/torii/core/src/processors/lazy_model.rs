@@ -0,0 +1,131 @@
+use anyhow::Result;
+use dojo_world::contracts::abigen::model::ModelContractReader;
+use dojo_world::contracts::abigen::world::Resource;
+use dojo_world::contracts::model::ModelReader;
+use dojo_world::contracts::naming::compute_selector_from_names;
+use dojo_world::contracts::world::WorldContractReader;
+use starknet::core::types::Felt;
+use starknet::providers::Provider;
+use tracing::{debug, info};
+
+use super::EventProcessorConfig;
+use crate::sql::cache::Model;
+use crate::sql::Sql;
+
+pub(crate) const LOG_TARGET: &str = "torii_core::processors::lazy_model";
+
+/// Returns the model of a record or event message, or `None` if the model is not indexed.
+///
+/// A model unknown to torii, e.g. registered before the indexing start block, is registered from
+/// the world the first time one of its records is seen. Its namespace is resolved among the
+/// configured or already registered namespaces, since the world only exposes its hash.
+pub(crate) async fn model_or_register<P>(
+    world: &WorldContractReader<P>,
+    db: &mut Sql,
+    selector: Felt,
+    block_timestamp: u64,
+    config: &EventProcessorConfig,
+) -> Result<Option<Model>>
+where
+    P: Provider + Send + Sync + std::fmt::Debug,
+{
+    let model = match db.model(selector).await {
+        Ok(model) => model,
+        Err(e) if e.to_string().contains("no rows") => {
+            return register_model(world, db, selector, block_timestamp, config).await;
+        }
+        Err(e) => return Err(e),
+    };
+
+    if !config.should_index(&model.namespace, &model.name) {
+        return Ok(None);
+    }
+
+    Ok(Some(model))
+}
+
+async fn register_model<P>(
+    world: &WorldContractReader<P>,
+    db: &mut Sql,
+    selector: Felt,
+    block_timestamp: u64,
+    config: &EventProcessorConfig,
+) -> Result<Option<Model>>
+where
+    P: Provider + Send + Sync + std::fmt::Debug,
+{
+    // Lazy registrations are serialized, the model may have been registered while waiting.
+    let unindexed_models = db.unindexed_models();
+    let mut unindexed_models = unindexed_models.lock().await;
+    if unindexed_models.contains(&(world.address, selector)) {
+        return Ok(None);
+    }
+    if let Ok(model) = db.model(selector).await {
+        return Ok(Some(model));
+    }
+
+    let (is_event, contract_address) = match world.resource(&selector).call().await? {
+        Resource::Model((address, _)) => (false, address),
+        Resource::Event((address, _)) => (true, address),
+        _ => {
+            debug!(target: LOG_TARGET, selector = %format!("{:#x}", selector), "Unknown model.");
+            unindexed_models.insert((world.address, selector));
+            return Ok(None);
+        }
+    };
+
+    let name = ModelContractReader::new(contract_address.into(), world.provider())
+        .dojo_name()
+        .call()
+        .await?
+        .to_string()?;
+
+    let namespaces = if config.namespaces.is_empty() {
+        db.registered_namespaces().await?
+    } else {
+        config.namespaces.iter().cloned().collect()
+    };
+
+    let Some(namespace) =
+        namespaces.into_iter().find(|ns| compute_selector_from_names(ns, &name) == selector)
+    else {
+        info!(
+            target: LOG_TARGET,
+            name = %name,
+            selector = %format!("{:#x}", selector),
+            "Namespace of unregistered model not found, skipping."
+        );
+        unindexed_models.insert((world.address, selector));
+        return Ok(None);
+    };
+
+    if !config.should_index(&namespace, &name) {
+        unindexed_models.insert((world.address, selector));
+        return Ok(None);
+    }
+
+    let model = world.model_reader(&namespace, &name).await?;
+    let schema = model.schema().await?;
+    let layout = model.layout().await?;
+
+    // Events are never stored onchain, hence no packing or unpacking.
+    let (packed_size, unpacked_size) =
+        if is_event { (0, 0) } else { (model.packed_size().await?, model.unpacked_size().await?) };
+
+    info!(target: LOG_TARGET, namespace = %namespace, name = %name, "Lazily registered model.");
+
+    db.register_model(
+        &namespace,
+        &schema,
+        layout,
+        model.class_hash(),
+        model.contract_address(),
+        packed_size,
+        unpacked_size,
+        block_timestamp,
+        None,
+    )
+    .await?;
+
+    Ok(Some(db.model(selector).await?))
+}
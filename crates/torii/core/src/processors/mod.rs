@@ -2,6 +2,7 @@ use std::collections::HashSet;
 
 use anyhow::{Error, Result};
 use async_trait::async_trait;
+use dojo_world::contracts::naming;
 use dojo_world::contracts::world::WorldContractReader;
 use starknet::core::types::{Event, Felt, Transaction};
 use starknet::providers::Provider;
@@ -13,6 +14,7 @@ pub mod erc20_transfer;
 pub mod erc721_legacy_transfer;
 pub mod erc721_transfer;
 pub mod event_message;
+mod lazy_model;
 pub mod metadata_update;
 pub mod raw_event;
 pub mod register_event;
@@ -32,6 +34,17 @@ const ENTITY_ID_INDEX: usize = 1;
 pub struct EventProcessorConfig {
     pub historical_events: HashSet<String>,
     pub namespaces: HashSet<String>,
+    /// Tags (namespace-name) of the models that are never indexed.
+    pub excluded_models: HashSet<String>,
+}
+
+impl EventProcessorConfig {
+    /// Whether a model is indexed, i.e. its namespace is indexed (all namespaces are if none is
+    /// configured) and the model is not excluded.
+    pub fn should_index(&self, namespace: &str, name: &str) -> bool {
+        (self.namespaces.is_empty() || self.namespaces.contains(namespace))
+            && !self.excluded_models.contains(&naming::get_tag(namespace, name))
+    }
 }
 
 #[async_trait]
@@ -85,3 +98,22 @@ pub trait TransactionProcessor<P: Provider + Sync>: Send + Sync {
         transaction: &Transaction,
     ) -> Result<(), Error>;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_index_models() {
+        let config = EventProcessorConfig {
+            namespaces: HashSet::from(["ns".to_string()]),
+            excluded_models: HashSet::from(["ns-Noisy".to_string()]),
+            ..Default::default()
+        };
+
+        assert!(config.should_index("ns", "Position"));
+        assert!(!config.should_index("ns", "Noisy"));
+        assert!(!config.should_index("other", "Position"));
+        assert!(EventProcessorConfig::default().should_index("other", "Position"));
+    }
+}
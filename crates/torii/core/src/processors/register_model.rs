@@ -58,9 +58,9 @@ where
         let namespace = event.namespace.to_string().unwrap();
         let name = event.name.to_string().unwrap();
 
-        // If the namespace is not in the list of namespaces to index, silently ignore it.
-        // If our config is empty, we index all namespaces.
-        if !config.namespaces.is_empty() && !config.namespaces.contains(&namespace) {
+        // If the namespace is not in the list of namespaces to index, or the model is excluded,
+        // silently ignore it. If our config is empty, we index all namespaces.
+        if !config.should_index(&namespace, &name) {
             return Ok(());
         }
 
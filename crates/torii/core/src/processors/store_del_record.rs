@@ -6,6 +6,7 @@ use starknet::core::types::Event;
 use starknet::providers::Provider;
 use tracing::{debug, info};
 
+use super::lazy_model::model_or_register;
 use super::{EventProcessor, EventProcessorConfig};
 use crate::sql::Sql;
 
@@ -29,13 +30,13 @@ where
 
     async fn process(
         &self,
-        _world: &WorldContractReader<P>,
+        world: &WorldContractReader<P>,
         db: &mut Sql,
         _block_number: u64,
         block_timestamp: u64,
         event_id: &str,
         event: &Event,
-        _config: &EventProcessorConfig,
+        config: &EventProcessorConfig,
     ) -> Result<(), Error> {
         // Torii version is coupled to the world version, so we can expect the event to be well
         // formed.
@@ -51,19 +52,17 @@ where
             }
         };
 
-        // If the model does not exist, silently ignore it.
-        // This can happen if only specific namespaces are indexed.
-        let model = match db.model(event.selector).await {
-            Ok(m) => m,
-            Err(e) if e.to_string().contains("no rows") => {
-                debug!(
-                    target: LOG_TARGET,
-                    selector = %event.selector,
-                    "Model does not exist, skipping."
-                );
-                return Ok(());
-            }
-            Err(e) => return Err(e),
+        // If the model is not indexed, silently ignore it.
+        // This can happen if only specific namespaces are indexed or if the model is excluded.
+        let Some(model) =
+            model_or_register(world, db, event.selector, block_timestamp, config).await?
+        else {
+            debug!(
+                target: LOG_TARGET,
+                selector = %event.selector,
+                "Model is not indexed, skipping."
+            );
+            return Ok(());
         };
 
         info!(
@@ -1,7 +1,6 @@
 use anyhow::{Context, Error, Result};
 use async_trait::async_trait;
 use dojo_types::schema::{Struct, Ty};
-use dojo_world::contracts::naming;
 use dojo_world::contracts::world::WorldContractReader;
 use num_traits::ToPrimitive;
 use starknet::core::types::Event;
@@ -9,6 +8,7 @@ use starknet::core::utils::get_selector_from_name;
 use starknet::providers::Provider;
 use tracing::{info, warn};
 
+use super::lazy_model::model_or_register;
 use super::{EventProcessor, EventProcessorConfig};
 use crate::processors::{ENTITY_ID_INDEX, MODEL_INDEX};
 use crate::sql::Sql;
@@ -44,28 +44,23 @@ where
 
     async fn process(
         &self,
-        _world: &WorldContractReader<P>,
+        world: &WorldContractReader<P>,
         db: &mut Sql,
         _block_number: u64,
         block_timestamp: u64,
         event_id: &str,
         event: &Event,
-        _config: &EventProcessorConfig,
+        config: &EventProcessorConfig,
     ) -> Result<(), Error> {
         let model_id = event.data[MODEL_INDEX];
         let entity_id = event.data[ENTITY_ID_INDEX];
         let member_selector = event.data[MEMBER_INDEX];
 
-        // If the model does not exist, silently ignore it.
-        // This can happen if only specific namespaces are indexed.
-        let model = match db.model(model_id).await {
-            Ok(m) => m,
-            Err(e) => {
-                if e.to_string().contains("no rows") {
-                    return Ok(());
-                }
-                return Err(e);
-            }
+        // If the model is not indexed, silently ignore it.
+        // This can happen if only specific namespaces are indexed or if the model is excluded.
+        let Some(model) = model_or_register(world, db, model_id, block_timestamp, config).await?
+        else {
+            return Ok(());
         };
 
         let schema = model.schema;
@@ -97,7 +92,8 @@ where
         // Skip the length to only get the values as they will be deserialized.
         let mut values = event.data[values_start + 1..=values_end].to_vec();
 
-        let tag = naming::get_tag(&model.namespace, &model.name);
+        // the table of the model, whose namespace differs from the world's for additional worlds
+        let tag = schema.name();
 
         if !db.does_entity_exist(tag.clone(), entity_id).await? {
             warn!(
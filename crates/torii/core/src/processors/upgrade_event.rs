@@ -38,7 +38,7 @@ where
         block_timestamp: u64,
         _event_id: &str,
         event: &Event,
-        _config: &EventProcessorConfig,
+        config: &EventProcessorConfig,
     ) -> Result<(), Error> {
         // Torii version is coupled to the world version, so we can expect the event to be well
         // formed.
@@ -73,6 +73,12 @@ where
         };
         let name = model.name;
         let namespace = model.namespace;
+
+        // Excluded models are not upgraded, their records are ignored anyway.
+        if !config.should_index(&namespace, &name) {
+            return Ok(());
+        }
+
         let prev_schema = model.schema;
 
         let model = world.model_reader(&namespace, &name).await?;
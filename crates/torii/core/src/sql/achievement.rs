@@ -0,0 +1,342 @@
+use anyhow::{anyhow, Result};
+use dojo_types::primitive::Primitive;
+use dojo_types::schema::{Struct, Ty};
+use serde_json::json;
+use starknet::core::types::Felt;
+use starknet::core::utils::parse_cairo_short_string;
+
+use super::{Sql, FELT_DELIMITER};
+use crate::constants::{
+    ACHIEVEMENTS_TABLE, ACHIEVEMENT_PROGRESSIONS_TABLE, ACHIEVEMENT_PROGRESSION_EVENTS_TABLE,
+};
+use crate::executor::{Argument, QueryMessage};
+use crate::utils::utc_dt_string_from_timestamp;
+
+/// Name of the event declaring an achievement.
+pub const TROPHY_CREATION_EVENT: &str = "TrophyCreation";
+/// Name of the event reporting the progress of a player on an achievement task.
+pub const TROPHY_PROGRESSION_EVENT: &str = "TrophyProgression";
+
+impl Sql {
+    /// Materializes the achievements and the players progress from the achievement events of a
+    /// namespace. Events of any other model are ignored.
+    pub(crate) fn store_achievement_event(
+        &mut self,
+        entity: &Ty,
+        namespace: &str,
+        name: &str,
+        event_id: &str,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        let Some(event) = entity.as_struct() else {
+            return Ok(());
+        };
+
+        match name {
+            TROPHY_CREATION_EVENT => self.store_trophy_creation(
+                TrophyCreation::try_from(event)?,
+                namespace,
+                block_timestamp,
+            ),
+            TROPHY_PROGRESSION_EVENT => self.store_trophy_progression(
+                TrophyProgression::try_from(event)?,
+                namespace,
+                event_id,
+                block_timestamp,
+            ),
+            _ => Ok(()),
+        }
+    }
+
+    fn store_trophy_creation(
+        &mut self,
+        trophy: TrophyCreation,
+        namespace: &str,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        let trophy_id = format!("{:#x}", trophy.id);
+        let tasks = trophy
+            .tasks
+            .iter()
+            .map(|t| json!({ "id": t.id, "total": t.total, "description": t.description }))
+            .collect::<Vec<_>>();
+
+        let statement = format!(
+            "INSERT INTO {ACHIEVEMENTS_TABLE} (id, namespace, trophy_id, hidden, idx, points, \
+             start_at, end_at, group_name, icon, title, description, tasks, data, executed_at) \
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?) ON CONFLICT(id) DO UPDATE SET \
+             hidden=excluded.hidden, idx=excluded.idx, points=excluded.points, \
+             start_at=excluded.start_at, end_at=excluded.end_at, group_name=excluded.group_name, \
+             icon=excluded.icon, title=excluded.title, description=excluded.description, \
+             tasks=excluded.tasks, data=excluded.data, executed_at=excluded.executed_at, \
+             updated_at=CURRENT_TIMESTAMP"
+        );
+
+        self.executor.send(QueryMessage::other(
+            statement,
+            vec![
+                Argument::String(format!("{namespace}{FELT_DELIMITER}{trophy_id}")),
+                Argument::String(namespace.to_string()),
+                Argument::String(trophy_id),
+                Argument::Bool(trophy.hidden),
+                Argument::Int(trophy.index as i64),
+                Argument::Int(trophy.points as i64),
+                Argument::Int(trophy.start as i64),
+                Argument::Int(trophy.end as i64),
+                Argument::String(trophy.group),
+                Argument::String(trophy.icon),
+                Argument::String(trophy.title),
+                Argument::String(trophy.description),
+                Argument::String(serde_json::to_string(&tasks)?),
+                Argument::String(trophy.data),
+                Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
+            ],
+        ))?;
+
+        Ok(())
+    }
+
+    // Progression events carry an increment, the counts are summed per player and task. The
+    // events already counted are recorded by id, so that indexing an event again is a no-op.
+    fn store_trophy_progression(
+        &mut self,
+        progression: TrophyProgression,
+        namespace: &str,
+        event_id: &str,
+        block_timestamp: u64,
+    ) -> Result<()> {
+        let player_id = format!("{:#x}", progression.player_id);
+        let progression_id = format!(
+            "{namespace}{FELT_DELIMITER}{player_id}{FELT_DELIMITER}{}",
+            progression.task_id
+        );
+
+        let statement = format!(
+            "INSERT INTO {ACHIEVEMENT_PROGRESSIONS_TABLE} (id, namespace, player_id, task_id, \
+             count, executed_at) SELECT ?1, ?2, ?3, ?4, ?5, ?6 WHERE NOT EXISTS (SELECT 1 FROM \
+             {ACHIEVEMENT_PROGRESSION_EVENTS_TABLE} WHERE id = ?7) ON CONFLICT(id) DO UPDATE SET \
+             count={ACHIEVEMENT_PROGRESSIONS_TABLE}.count + excluded.count, \
+             executed_at=excluded.executed_at, updated_at=CURRENT_TIMESTAMP"
+        );
+
+        self.executor.send(QueryMessage::other(
+            statement,
+            vec![
+                Argument::String(progression_id.clone()),
+                Argument::String(namespace.to_string()),
+                Argument::String(player_id),
+                Argument::String(progression.task_id),
+                Argument::Int(progression.count as i64),
+                Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
+                Argument::String(event_id.to_string()),
+            ],
+        ))?;
+
+        self.executor.send(QueryMessage::other(
+            format!(
+                "INSERT INTO {ACHIEVEMENT_PROGRESSION_EVENTS_TABLE} (id, progression_id) VALUES \
+                 (?, ?) ON CONFLICT(id) DO NOTHING"
+            ),
+            vec![Argument::String(event_id.to_string()), Argument::String(progression_id)],
+        ))?;
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct TrophyTask {
+    id: String,
+    total: u64,
+    description: String,
+}
+
+#[derive(Debug, PartialEq)]
+struct TrophyCreation {
+    id: Felt,
+    hidden: bool,
+    index: u64,
+    points: u64,
+    start: u64,
+    end: u64,
+    group: String,
+    icon: String,
+    title: String,
+    description: String,
+    tasks: Vec<TrophyTask>,
+    data: String,
+}
+
+#[derive(Debug, PartialEq)]
+struct TrophyProgression {
+    player_id: Felt,
+    task_id: String,
+    count: u64,
+}
+
+impl TryFrom<&Struct> for TrophyCreation {
+    type Error = anyhow::Error;
+
+    fn try_from(event: &Struct) -> Result<Self> {
+        let tasks = member(event, "tasks")?
+            .as_array()
+            .ok_or_else(|| anyhow!("Member tasks of {} is not an array", event.name))?
+            .iter()
+            .map(|task| {
+                let task = task
+                    .as_struct()
+                    .ok_or_else(|| anyhow!("Task of {} is not a struct", event.name))?;
+                Ok(TrophyTask {
+                    id: short_string_member(task, "id")?,
+                    total: uint_member(task, "total")?,
+                    description: byte_array_member(task, "description")?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            id: felt_member(event, "id")?,
+            hidden: bool_member(event, "hidden")?,
+            index: uint_member(event, "index")?,
+            points: uint_member(event, "points")?,
+            start: uint_member(event, "start")?,
+            end: uint_member(event, "end")?,
+            group: short_string_member(event, "group")?,
+            icon: short_string_member(event, "icon")?,
+            title: short_string_member(event, "title")?,
+            description: byte_array_member(event, "description")?,
+            tasks,
+            data: byte_array_member(event, "data")?,
+        })
+    }
+}
+
+impl TryFrom<&Struct> for TrophyProgression {
+    type Error = anyhow::Error;
+
+    fn try_from(event: &Struct) -> Result<Self> {
+        Ok(Self {
+            player_id: felt_member(event, "player_id")?,
+            task_id: short_string_member(event, "task_id")?,
+            count: uint_member(event, "count")?,
+        })
+    }
+}
+
+fn member<'a>(s: &'a Struct, name: &str) -> Result<&'a Ty> {
+    s.get(name).ok_or_else(|| anyhow!("Missing member {name} in {}", s.name))
+}
+
+fn primitive_member<'a>(s: &'a Struct, name: &str) -> Result<&'a Primitive> {
+    member(s, name)?
+        .as_primitive()
+        .ok_or_else(|| anyhow!("Member {name} of {} is not a primitive", s.name))
+}
+
+fn felt_member(s: &Struct, name: &str) -> Result<Felt> {
+    let primitive = primitive_member(s, name)?;
+    primitive
+        .as_felt252()
+        .or_else(|| primitive.as_contract_address())
+        .ok_or_else(|| anyhow!("Member {name} of {} is not a felt", s.name))
+}
+
+fn uint_member(s: &Struct, name: &str) -> Result<u64> {
+    let primitive = primitive_member(s, name)?;
+    primitive
+        .as_u8()
+        .map(u64::from)
+        .or_else(|| primitive.as_u16().map(u64::from))
+        .or_else(|| primitive.as_u32().map(u64::from))
+        .or_else(|| primitive.as_u64())
+        .ok_or_else(|| anyhow!("Member {name} of {} is not an unsigned integer", s.name))
+}
+
+fn bool_member(s: &Struct, name: &str) -> Result<bool> {
+    primitive_member(s, name)?
+        .as_bool()
+        .ok_or_else(|| anyhow!("Member {name} of {} is not a bool", s.name))
+}
+
+fn short_string_member(s: &Struct, name: &str) -> Result<String> {
+    Ok(parse_cairo_short_string(&felt_member(s, name)?)?)
+}
+
+fn byte_array_member(s: &Struct, name: &str) -> Result<String> {
+    member(s, name)?
+        .as_byte_array()
+        .cloned()
+        .ok_or_else(|| anyhow!("Member {name} of {} is not a ByteArray", s.name))
+}
+
+#[cfg(test)]
+mod tests {
+    use dojo_types::schema::Member;
+    use starknet::core::utils::cairo_short_string_to_felt;
+
+    use super::*;
+
+    fn member(name: &str, ty: Ty, key: bool) -> Member {
+        Member { name: name.to_string(), ty, key }
+    }
+
+    fn short_string(s: &str) -> Ty {
+        Ty::Primitive(Primitive::Felt252(Some(cairo_short_string_to_felt(s).unwrap())))
+    }
+
+    #[test]
+    fn parse_trophy_creation() {
+        let task = Ty::Struct(Struct {
+            name: "Task".to_string(),
+            children: vec![
+                member("id", short_string("KILL"), false),
+                member("total", Ty::Primitive(Primitive::U32(Some(10))), false),
+                member("description", Ty::ByteArray("Kill 10 monsters".to_string()), false),
+            ],
+        });
+
+        let event = Struct {
+            name: "ns-TrophyCreation".to_string(),
+            children: vec![
+                member("id", short_string("HUNTER"), true),
+                member("hidden", Ty::Primitive(Primitive::Bool(Some(false))), false),
+                member("index", Ty::Primitive(Primitive::U8(Some(0))), false),
+                member("points", Ty::Primitive(Primitive::U16(Some(20))), false),
+                member("start", Ty::Primitive(Primitive::U64(Some(0))), false),
+                member("end", Ty::Primitive(Primitive::U64(Some(0))), false),
+                member("group", short_string("Hunting"), false),
+                member("icon", short_string("fa-skull"), false),
+                member("title", short_string("Hunter"), false),
+                member("description", Ty::ByteArray("Hunt monsters".to_string()), false),
+                member("tasks", Ty::Array(vec![task]), false),
+                member("data", Ty::ByteArray(String::new()), false),
+            ],
+        };
+
+        let trophy = TrophyCreation::try_from(&event).unwrap();
+        assert_eq!(trophy.points, 20);
+        assert_eq!(trophy.title, "Hunter");
+        assert_eq!(
+            trophy.tasks,
+            vec![TrophyTask {
+                id: "KILL".to_string(),
+                total: 10,
+                description: "Kill 10 monsters".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_trophy_progression_with_missing_member() {
+        let event = Struct {
+            name: "ns-TrophyProgression".to_string(),
+            children: vec![
+                member("player_id", Ty::Primitive(Primitive::Felt252(Some(Felt::ONE))), true),
+                member("task_id", short_string("KILL"), true),
+            ],
+        };
+
+        let err = TrophyProgression::try_from(&event).unwrap_err();
+        assert_eq!(err.to_string(), "Missing member count in ns-TrophyProgression");
+    }
+}
@@ -0,0 +1,202 @@
+use anyhow::Result;
+use dojo_types::primitive::{Primitive, SqlType};
+use dojo_types::schema::Ty;
+use starknet::core::types::Felt;
+
+use super::{Sql, FELT_DELIMITER};
+use crate::constants::AGGREGATIONS_TABLE;
+use crate::executor::{Argument, QueryMessage};
+use crate::types::{Aggregation, AggregationFunction};
+
+impl Sql {
+    /// Refreshes the aggregations computed over the model of an entity that has been set or
+    /// deleted. Must be called after the model table has been updated.
+    ///
+    /// When an entity is set, only the row of its group is recomputed, unless one of the group by
+    /// members has been updated, in which case the entity may have left another group and the
+    /// whole aggregation is recomputed. Deletions always recompute the whole aggregation, since
+    /// the group of the deleted entity is no longer known.
+    ///
+    /// The `entity` may only hold the updated members, so the type of the aggregated member is
+    /// resolved from the schema of the model of selector `model_id`.
+    pub(crate) async fn refresh_aggregations(
+        &mut self,
+        entity: &Ty,
+        model_id: Felt,
+        entity_id: &str,
+        deleted: bool,
+    ) -> Result<()> {
+        let model = entity.name();
+        let aggregations = self.aggregations.clone();
+        let mut aggregations = aggregations.iter().filter(|a| a.model == model).peekable();
+        if aggregations.peek().is_none() {
+            return Ok(());
+        }
+
+        let schema = self.model(model_id).await?.schema;
+
+        for aggregation in aggregations {
+            let member = schema
+                .as_struct()
+                .and_then(|s| s.children.iter().find(|m| m.name == aggregation.member))
+                .map(|m| &m.ty);
+            let group_updated = entity.as_struct().is_some_and(|s| {
+                s.children.iter().any(|m| !m.key && aggregation.group_by.contains(&m.name))
+            });
+
+            if deleted || group_updated || aggregation.group_by.is_empty() {
+                self.executor.send(QueryMessage::other(
+                    format!("DELETE FROM {AGGREGATIONS_TABLE} WHERE aggregation_name = ?"),
+                    vec![Argument::String(aggregation.name.clone())],
+                ))?;
+                self.executor.send(QueryMessage::other(
+                    refresh_query(aggregation, member, false),
+                    vec![Argument::String(aggregation.name.clone())],
+                ))?;
+            } else {
+                self.executor.send(QueryMessage::other(
+                    refresh_query(aggregation, member, true),
+                    vec![
+                        Argument::String(aggregation.name.clone()),
+                        Argument::String(entity_id.to_string()),
+                    ],
+                ))?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The expression of the group key of an entity, the group by members joined by
+/// [`FELT_DELIMITER`].
+fn group_key_expression(aggregation: &Aggregation) -> String {
+    if aggregation.group_by.is_empty() {
+        return "''".to_string();
+    }
+
+    aggregation
+        .group_by
+        .iter()
+        .map(|m| format!("[external_{m}]"))
+        .collect::<Vec<String>>()
+        .join(&format!(" || '{FELT_DELIMITER}' || "))
+}
+
+/// The expression of the aggregated member, of type `ty`.
+///
+/// The integers wider than 32 bits are stored as zero padded hex strings, which SQLite can't
+/// convert to numbers, so their hex digits are decoded. The result is a floating point number
+/// past 2^63, hence only approximate for such large values.
+fn member_expression(member: &str, ty: Option<&Ty>) -> String {
+    let column = format!("[external_{member}]");
+
+    let primitive = match ty {
+        Some(Ty::Primitive(primitive)) if primitive.to_sql_type() == SqlType::Text => primitive,
+        _ => return column,
+    };
+
+    let digits = match primitive {
+        Primitive::U64(_) => 16,
+        Primitive::U128(_) | Primitive::I128(_) => 32,
+        _ => 64,
+    };
+
+    let decoded = (0..digits)
+        .map(|i| {
+            let digit = format!("(instr('0123456789abcdef', substr({column}, -{}, 1)) - 1)", i + 1);
+            // the weights of the highest digits don't fit in a 64 bits integer
+            if i < 15 {
+                format!("{digit} * {}", 1u64 << (4 * i))
+            } else {
+                format!("{digit} * {:e}", 16f64.powi(i))
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" + ");
+
+    match primitive {
+        // negative values are stored in two's complement
+        Primitive::I128(_) => format!(
+            "({decoded} - CASE WHEN substr({column}, -32, 1) >= '8' THEN {:e} ELSE 0 END)",
+            2f64.powi(128)
+        ),
+        _ => format!("({decoded})"),
+    }
+}
+
+/// Builds the query upserting the rows of an aggregation, whose member is of type `member`. The
+/// aggregation name is bound first, and if `single_group` is set, the query only recomputes the
+/// group of the entity whose id is bound second.
+fn refresh_query(aggregation: &Aggregation, member: Option<&Ty>, single_group: bool) -> String {
+    let table = &aggregation.model;
+    let group_key = group_key_expression(aggregation);
+    let value = match aggregation.function {
+        AggregationFunction::Count => "COUNT(*)".to_string(),
+        function => format!("{function}({})", member_expression(&aggregation.member, member)),
+    };
+
+    let filter = if single_group {
+        format!("WHERE {group_key} = (SELECT {group_key} FROM [{table}] WHERE entity_id = ?2)")
+    } else {
+        String::new()
+    };
+
+    // The `WHERE true` is required by SQLite to disambiguate the upsert clause of an
+    // `INSERT ... SELECT`.
+    format!(
+        "INSERT INTO {AGGREGATIONS_TABLE} (id, aggregation_name, group_key, value, updated_at) \
+         SELECT ?1 || '{FELT_DELIMITER}' || group_key, ?1, group_key, value, CURRENT_TIMESTAMP \
+         FROM (SELECT {group_key} AS group_key, {value} AS value FROM [{table}] {filter} GROUP BY \
+         group_key) WHERE true ON CONFLICT(id) DO UPDATE SET value=excluded.value, \
+         updated_at=excluded.updated_at"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refresh_query_of_a_group() {
+        let aggregation: Aggregation =
+            "leaderboard:ns-Score:sum(score):player+season".parse().unwrap();
+
+        assert_eq!(
+            refresh_query(&aggregation, Some(&Ty::Primitive(Primitive::U32(None))), true),
+            "INSERT INTO aggregations (id, aggregation_name, group_key, value, updated_at) SELECT \
+             ?1 || '/' || group_key, ?1, group_key, value, CURRENT_TIMESTAMP FROM (SELECT \
+             [external_player] || '/' || [external_season] AS group_key, SUM([external_score]) AS \
+             value FROM [ns-Score] WHERE [external_player] || '/' || [external_season] = (SELECT \
+             [external_player] || '/' || [external_season] FROM [ns-Score] WHERE entity_id = ?2) \
+             GROUP BY group_key) WHERE true ON CONFLICT(id) DO UPDATE SET value=excluded.value, \
+             updated_at=excluded.updated_at"
+        );
+    }
+
+    #[test]
+    fn refresh_query_without_group() {
+        let aggregation: Aggregation = "players:ns-Score:count(score)".parse().unwrap();
+
+        assert!(refresh_query(&aggregation, None, false)
+            .contains("FROM (SELECT '' AS group_key, COUNT(*) AS value FROM [ns-Score]  GROUP BY"));
+    }
+
+    #[test]
+    fn hex_members_are_decoded() {
+        let u64_score = member_expression("score", Some(&Ty::Primitive(Primitive::U64(None))));
+        assert!(u64_score.starts_with(
+            "((instr('0123456789abcdef', substr([external_score], -1, 1)) - 1) * 1 + \
+             (instr('0123456789abcdef', substr([external_score], -2, 1)) - 1) * 16 + "
+        ));
+        assert!(u64_score.ends_with("[external_score], -16, 1)) - 1) * 1.152921504606847e18)"));
+
+        let i128_score = member_expression("score", Some(&Ty::Primitive(Primitive::I128(None))));
+        assert!(i128_score.contains("substr([external_score], -32, 1)) - 1) * "));
+        assert!(!i128_score.contains("-33"));
+        assert!(i128_score.ends_with(" >= '8' THEN 3.402823669209385e38 ELSE 0 END)"));
+
+        let u32_score = member_expression("score", Some(&Ty::Primitive(Primitive::U32(None))));
+        assert_eq!(u32_score, "[external_score]");
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryInto;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -14,13 +14,14 @@ use sqlx::{Pool, Sqlite};
 use starknet::core::types::{Event, Felt, InvokeTransaction, Transaction};
 use starknet_crypto::poseidon_hash_many;
 use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::{Mutex, RwLock};
 use utils::felts_to_sql_string;
 
 use crate::executor::{
     Argument, DeleteEntityQuery, EventMessageQuery, QueryMessage, QueryType, ResetCursorsQuery,
     SetHeadQuery, UpdateCursorsQuery,
 };
-use crate::types::Contract;
+use crate::types::{Aggregation, Contract};
 use crate::utils::utc_dt_string_from_timestamp;
 
 type IsEventMessage = bool;
@@ -29,8 +30,11 @@ type IsStoreUpdate = bool;
 pub const WORLD_CONTRACT_TYPE: &str = "WORLD";
 pub const FELT_DELIMITER: &str = "/";
 
+pub mod achievement;
+pub mod aggregation;
 pub mod cache;
 pub mod erc;
+pub mod namespace;
 pub mod query_queue;
 #[cfg(test)]
 #[path = "test.rs"]
@@ -46,6 +50,14 @@ pub struct Sql {
     model_cache: Arc<ModelCache>,
     // when SQL struct is cloned a empty local_cache is created
     local_cache: LocalCache,
+    // aggregations maintained over the models entities
+    aggregations: Arc<Vec<Aggregation>>,
+    // when set, the models and entities are isolated from the ones of the other worlds
+    world_address: Option<Felt>,
+    // stored model of the selectors of the models of `world_address`
+    world_selectors: Arc<RwLock<HashMap<Felt, Felt>>>,
+    // (world, selector) of the models seen in records but not indexed
+    unindexed_models: Arc<Mutex<HashSet<(Felt, Felt)>>>,
 }
 
 #[derive(Debug, Clone)]
@@ -76,13 +88,36 @@ impl Sql {
         }
 
         let local_cache = LocalCache::new(pool.clone()).await;
-        let db = Self { pool: pool.clone(), executor, model_cache, local_cache };
+        let db = Self {
+            pool: pool.clone(),
+            executor,
+            model_cache,
+            local_cache,
+            aggregations: Arc::new(Vec::new()),
+            world_address: None,
+            world_selectors: Arc::new(RwLock::new(HashMap::new())),
+            unindexed_models: Arc::new(Mutex::new(HashSet::new())),
+        };
 
         db.execute().await?;
 
         Ok(db)
     }
 
+    /// Sets the aggregations maintained when the entities of their model are set or deleted.
+    pub fn with_aggregations(mut self, aggregations: Vec<Aggregation>) -> Self {
+        self.aggregations = Arc::new(aggregations);
+        self
+    }
+
+    /// Sends the queries of this instance to another executor, which commits them in its own
+    /// transaction. Instances indexing different worlds must not share an executor, as one would
+    /// commit the queries of a block the other is still processing.
+    pub fn with_executor(mut self, executor: UnboundedSender<QueryMessage>) -> Self {
+        self.executor = executor;
+        self
+    }
+
     pub async fn head(&self, contract: Felt) -> Result<(u64, Option<Felt>, Option<Felt>)> {
         let indexer_query =
             sqlx::query_as::<_, (Option<i64>, Option<String>, Option<String>, String)>(
@@ -259,13 +294,14 @@ impl Sql {
         block_timestamp: u64,
         upgrade_diff: Option<&Ty>,
     ) -> Result<()> {
+        let namespace = &self.stored_namespace(namespace);
         let selector = compute_selector_from_names(namespace, &model.name());
         let namespaced_name = format!("{}-{}", namespace, model.name());
 
         let insert_models =
             "INSERT INTO models (id, namespace, name, class_hash, contract_address, layout, \
-             packed_size, unpacked_size, executed_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?) ON \
-             CONFLICT(id) DO UPDATE SET contract_address=EXCLUDED.contract_address, \
+             packed_size, unpacked_size, executed_at, world_address) VALUES (?, ?, ?, ?, ?, ?, ?, \
+             ?, ?, ?) ON CONFLICT(id) DO UPDATE SET contract_address=EXCLUDED.contract_address, \
              class_hash=EXCLUDED.class_hash, layout=EXCLUDED.layout, \
              packed_size=EXCLUDED.packed_size, unpacked_size=EXCLUDED.unpacked_size, \
              executed_at=EXCLUDED.executed_at RETURNING *";
@@ -279,6 +315,7 @@ impl Sql {
             Argument::Int(packed_size as i64),
             Argument::Int(unpacked_size as i64),
             Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
+            self.world_argument(),
         ];
         self.executor.send(QueryMessage::new(
             insert_models.to_string(),
@@ -320,6 +357,7 @@ impl Sql {
                 },
             )
             .await;
+        self.set_stored_selector(namespace, &model.name()).await;
 
         Ok(())
     }
@@ -335,24 +373,26 @@ impl Sql {
     ) -> Result<()> {
         let namespaced_name = entity.name();
 
-        let entity_id = format!("{:#x}", entity_id);
-        let model_id = format!("{:#x}", model_id);
+        let entity_id = format!("{:#x}", self.stored_entity_id(entity_id));
+        let model_selector = model_id;
+        let model_id = format!("{:#x}", self.stored_selector(model_id).await?);
 
         let insert_entities = if keys_str.is_some() {
-            "INSERT INTO entities (id, event_id, executed_at, keys) VALUES (?, ?, ?, ?) ON \
-             CONFLICT(id) DO UPDATE SET updated_at=CURRENT_TIMESTAMP, \
+            "INSERT INTO entities (id, event_id, executed_at, world_address, keys) VALUES (?, ?, \
+             ?, ?, ?) ON CONFLICT(id) DO UPDATE SET updated_at=CURRENT_TIMESTAMP, \
              executed_at=EXCLUDED.executed_at, event_id=EXCLUDED.event_id, keys=EXCLUDED.keys \
              RETURNING *"
         } else {
-            "INSERT INTO entities (id, event_id, executed_at) VALUES (?, ?, ?) ON CONFLICT(id) DO \
-             UPDATE SET updated_at=CURRENT_TIMESTAMP, executed_at=EXCLUDED.executed_at, \
-             event_id=EXCLUDED.event_id RETURNING *"
+            "INSERT INTO entities (id, event_id, executed_at, world_address) VALUES (?, ?, ?, ?) \
+             ON CONFLICT(id) DO UPDATE SET updated_at=CURRENT_TIMESTAMP, \
+             executed_at=EXCLUDED.executed_at, event_id=EXCLUDED.event_id RETURNING *"
         };
 
         let mut arguments = vec![
             Argument::String(entity_id.clone()),
             Argument::String(event_id.to_string()),
             Argument::String(utc_dt_string_from_timestamp(block_timestamp)),
+            self.world_argument(),
         ];
 
         if let Some(keys) = keys_str {
@@ -382,6 +422,8 @@ impl Sql {
             &vec![],
         )?;
 
+        self.refresh_aggregations(&entity, model_selector, &entity_id, false).await?;
+
         Ok(())
     }
 
@@ -405,7 +447,7 @@ impl Sql {
         let namespaced_name = entity.name();
         let (model_namespace, model_name) = namespaced_name.split_once('-').unwrap();
 
-        let entity_id = format!("{:#x}", poseidon_hash_many(&keys));
+        let entity_id = format!("{:#x}", self.stored_entity_id(poseidon_hash_many(&keys)));
         let model_id = format!("{:#x}", compute_selector_from_names(model_namespace, model_name));
 
         let keys_str = felts_to_sql_string(&keys);
@@ -455,7 +497,9 @@ impl Sql {
         event_id: &str,
         block_timestamp: u64,
     ) -> Result<()> {
-        let entity_id = format!("{:#x}", entity_id);
+        let entity_id = format!("{:#x}", self.stored_entity_id(entity_id));
+        let model_selector = model_id;
+        let model_id = self.stored_selector(model_id).await?;
         let path = vec![entity.name()];
         // delete entity models data
         self.build_delete_entity_queries_recursive(path, &entity_id, &entity)?;
@@ -471,6 +515,8 @@ impl Sql {
             }),
         ))?;
 
+        self.refresh_aggregations(&entity, model_selector, &entity_id, true).await?;
+
         Ok(())
     }
 
@@ -522,12 +568,31 @@ impl Sql {
     }
 
     pub async fn model(&self, selector: Felt) -> Result<Model> {
-        self.model_cache.model(&selector).await.map_err(|e| e.into())
+        let mut model = self.model_cache.model(&self.stored_selector(selector).await?).await?;
+        model.namespace = self.local_namespace(&model.namespace).to_string();
+        model.selector = selector;
+        Ok(model)
+    }
+
+    /// Returns the namespaces of the registered models of the world.
+    pub async fn registered_namespaces(&self) -> Result<Vec<String>> {
+        let namespaces: Vec<String> =
+            sqlx::query_scalar("SELECT DISTINCT namespace FROM models WHERE world_address IS ?")
+                .bind(self.world_address.map(|address| format!("{address:#x}")))
+                .fetch_all(&self.pool)
+                .await?;
+
+        Ok(namespaces.iter().map(|ns| self.local_namespace(ns).to_string()).collect())
+    }
+
+    pub(crate) fn unindexed_models(&self) -> Arc<Mutex<HashSet<(Felt, Felt)>>> {
+        self.unindexed_models.clone()
     }
 
     pub async fn does_entity_exist(&self, model: String, key: Felt) -> Result<bool> {
         let sql = format!("SELECT COUNT(*) FROM [{model}] WHERE id = ?");
 
+        let key = self.stored_entity_id(key);
         let count: i64 =
             sqlx::query_scalar(&sql).bind(format!("{:#x}", key)).fetch_one(&self.pool).await?;
 
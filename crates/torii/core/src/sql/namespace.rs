@@ -0,0 +1,110 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use dojo_world::contracts::naming::compute_selector_from_names;
+use starknet::core::types::Felt;
+use starknet_crypto::poseidon_hash_many;
+use tokio::sync::RwLock;
+
+use super::Sql;
+use crate::executor::Argument;
+
+impl Sql {
+    /// Isolates the models and entities of an additional world indexed through this instance from
+    /// the ones of the other worlds of the database.
+    ///
+    /// The models of the world are stored in [`world_namespace`]s, so that the worlds can use the
+    /// same namespaces, and the ids of its entities are derived from the world address. Both are
+    /// tagged with the world address in the `models` and `entities` tables. The models and
+    /// entities are still read and written with the selectors and ids of the world.
+    pub fn with_world(mut self, world_address: Felt) -> Self {
+        self.world_address = Some(world_address);
+        self.world_selectors = Arc::new(RwLock::new(Default::default()));
+        self
+    }
+
+    /// The world address the models and entities written by this instance are tagged with.
+    pub(crate) fn world_argument(&self) -> Argument {
+        self.world_address.map_or(Argument::Null, Argument::FieldElement)
+    }
+
+    /// Returns the namespace the models of `namespace` of the world are stored in.
+    pub(crate) fn stored_namespace(&self, namespace: &str) -> String {
+        match self.world_address {
+            Some(world_address) => world_namespace(namespace, world_address),
+            None => namespace.to_string(),
+        }
+    }
+
+    /// Returns the namespace of the world of a model stored in `namespace`.
+    pub(crate) fn local_namespace<'a>(&self, namespace: &'a str) -> &'a str {
+        match self.world_address {
+            Some(world_address) => {
+                namespace.strip_suffix(&format!("_{world_address:x}")).unwrap_or(namespace)
+            }
+            None => namespace,
+        }
+    }
+
+    /// Returns the id of the stored model of the model `selector` of the world.
+    pub(crate) async fn stored_selector(&self, selector: Felt) -> Result<Felt> {
+        if self.world_address.is_none() {
+            return Ok(selector);
+        }
+
+        if let Some(stored) = self.world_selectors.read().await.get(&selector) {
+            return Ok(*stored);
+        }
+
+        // the models registered before a restart are only known from the database
+        let models: Vec<(String, String)> =
+            sqlx::query_as("SELECT namespace, name FROM models WHERE world_address = ?")
+                .bind(format!("{:#x}", self.world_address.unwrap()))
+                .fetch_all(&self.pool)
+                .await?;
+
+        let mut selectors = self.world_selectors.write().await;
+        for (namespace, name) in models {
+            let local = compute_selector_from_names(self.local_namespace(&namespace), &name);
+            selectors.insert(local, compute_selector_from_names(&namespace, &name));
+        }
+
+        selectors.get(&selector).copied().ok_or_else(|| sqlx::Error::RowNotFound.into())
+    }
+
+    /// Remembers the stored model of a model of the world being registered.
+    ///
+    /// The stored model only depends on the namespace and name of the model, so an entry added
+    /// for a block which is rolled back stays valid once the block is processed again.
+    pub(crate) async fn set_stored_selector(&self, namespace: &str, name: &str) {
+        if self.world_address.is_some() {
+            let local = compute_selector_from_names(self.local_namespace(namespace), name);
+            let stored = compute_selector_from_names(namespace, name);
+            self.world_selectors.write().await.insert(local, stored);
+        }
+    }
+
+    /// Returns the id under which the entity `entity_id` of the world is stored.
+    pub(crate) fn stored_entity_id(&self, entity_id: Felt) -> Felt {
+        match self.world_address {
+            Some(world_address) => poseidon_hash_many(&[world_address, entity_id]),
+            None => entity_id,
+        }
+    }
+}
+
+/// Returns the namespace the models of `namespace` of an additional world are stored in, e.g.
+/// `ns_1234` for the namespace `ns` of the world `0x1234`.
+pub fn world_namespace(namespace: &str, world_address: Felt) -> String {
+    format!("{namespace}_{world_address:x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn world_namespaces() {
+        assert_eq!(world_namespace("ns", Felt::from(0x1234)), "ns_1234");
+    }
+}
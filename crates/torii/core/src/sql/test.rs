@@ -5,7 +5,10 @@ use std::sync::Arc;
 use cainome::cairo_serde::ContractAddress;
 use dojo_test_utils::compiler::CompilerTestSetup;
 use dojo_test_utils::migration::copy_spawn_and_move_db;
+use dojo_types::primitive::Primitive;
+use dojo_types::schema::{Member, Struct, Ty};
 use dojo_utils::{TransactionExt, TransactionWaiter, TxnConfig};
+use dojo_world::contracts::abigen::model::Layout;
 use dojo_world::contracts::naming::{compute_bytearray_hash, compute_selector_from_names};
 use dojo_world::contracts::world::{WorldContract, WorldContractReader};
 use katana_runner::RunnerCtx;
@@ -16,7 +19,7 @@ use starknet::accounts::Account;
 use starknet::core::types::{Call, Felt};
 use starknet::core::utils::get_selector_from_name;
 use starknet::providers::jsonrpc::HttpTransport;
-use starknet::providers::{JsonRpcClient, Provider};
+use starknet::providers::{JsonRpcClient, Provider, Url};
 use starknet_crypto::poseidon_hash_many;
 use tempfile::NamedTempFile;
 use tokio::sync::broadcast;
@@ -390,6 +393,251 @@ async fn test_update_with_set_record(sequencer: &RunnerCtx) {
     let _ = bootstrap_engine(world_reader, db.clone(), Arc::clone(&provider)).await.unwrap();
 }
 
+/// Creates an empty database in `file`, along with the executor of its queries. The executor's
+/// provider is never reached, the tests using it only write models and entities.
+async fn sqlite_db(file: &NamedTempFile) -> (sqlx::Pool<sqlx::Sqlite>, Sql) {
+    let path = file.path().to_string_lossy();
+    let options = SqliteConnectOptions::from_str(&path).unwrap().create_if_missing(true);
+    let pool = SqlitePoolOptions::new().connect_with(options).await.unwrap();
+    sqlx::migrate!("../migrations").run(&pool).await.unwrap();
+
+    let provider = Arc::new(JsonRpcClient::new(HttpTransport::new(
+        Url::parse("http://localhost:5050").unwrap(),
+    )));
+
+    let (shutdown_tx, _) = broadcast::channel(1);
+    let (mut executor, sender) =
+        Executor::new(pool.clone(), shutdown_tx, provider, 100).await.unwrap();
+    tokio::spawn(async move {
+        executor.run().await.unwrap();
+    });
+
+    let model_cache = Arc::new(ModelCache::new(pool.clone()));
+    let db = Sql::new(pool.clone(), sender, &[], model_cache).await.unwrap();
+
+    (pool, db)
+}
+
+/// A `ns-Position` model, keyed by a player.
+fn position_model() -> Ty {
+    Ty::Struct(Struct {
+        name: "Position".to_string(),
+        children: vec![
+            Member {
+                name: "player".to_string(),
+                ty: Ty::Primitive(Primitive::ContractAddress(None)),
+                key: true,
+            },
+            Member { name: "x".to_string(), ty: Ty::Primitive(Primitive::U32(None)), key: false },
+        ],
+    })
+}
+
+/// Registers the `ns-Position` model in `db` and sets the position of the player `0x1` to `x`.
+async fn set_position(db: &mut Sql, x: u32) {
+    let model = position_model();
+    db.register_model("ns", &model, Layout::Fixed(vec![]), Felt::ZERO, Felt::ZERO, 1, 1, 0, None)
+        .await
+        .unwrap();
+
+    let selector = compute_selector_from_names("ns", "Position");
+    let mut entity = db.model(selector).await.unwrap().schema;
+    entity.deserialize(&mut vec![Felt::ONE, Felt::from(x)]).unwrap();
+
+    let entity_id = poseidon_hash_many(&[Felt::ONE]);
+    db.set_entity(entity, "0x0:0x0:0x0", 0, entity_id, selector, Some("0x1/")).await.unwrap();
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_worlds_isolation() {
+    let tempfile = NamedTempFile::new().unwrap();
+    let (pool, db) = sqlite_db(&tempfile).await;
+    let world = Felt::from(0x1234);
+
+    // both worlds register the same model and set the same entity
+    set_position(&mut db.clone(), 1).await;
+    set_position(&mut db.clone().with_world(world), 2).await;
+    db.execute().await.unwrap();
+
+    let models: Vec<(String, String, Option<String>)> =
+        sqlx::query_as("SELECT id, namespace, world_address FROM models ORDER BY namespace")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        models,
+        vec![
+            (format!("{:#x}", compute_selector_from_names("ns", "Position")), "ns".into(), None),
+            (
+                format!("{:#x}", compute_selector_from_names("ns_1234", "Position")),
+                "ns_1234".into(),
+                Some("0x1234".into())
+            ),
+        ]
+    );
+
+    let entities: Vec<(String, Option<String>)> =
+        sqlx::query_as("SELECT id, world_address FROM entities ORDER BY world_address")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+    let entity_id = poseidon_hash_many(&[Felt::ONE]);
+    assert_eq!(
+        entities,
+        vec![
+            (format!("{:#x}", entity_id), None),
+            (format!("{:#x}", poseidon_hash_many(&[world, entity_id])), Some("0x1234".into())),
+        ]
+    );
+
+    let x: i64 = sqlx::query_scalar("SELECT x FROM [ns-Position]").fetch_one(&pool).await.unwrap();
+    assert_eq!(x, 1);
+    let x: i64 =
+        sqlx::query_scalar("SELECT x FROM [ns_1234-Position]").fetch_one(&pool).await.unwrap();
+    assert_eq!(x, 2);
+
+    // each world reads its own models, with its own namespaces and selectors
+    let selector = compute_selector_from_names("ns", "Position");
+    let model = db.clone().with_world(world).model(selector).await.unwrap();
+    assert_eq!((model.namespace.as_str(), model.selector), ("ns", selector));
+    assert_eq!(model.schema.name(), "ns_1234-Position");
+    assert_eq!(db.model(selector).await.unwrap().schema.name(), "ns-Position");
+
+    assert_eq!(db.registered_namespaces().await.unwrap(), vec!["ns".to_string()]);
+    let other_world = db.clone().with_world(Felt::from(0x5678));
+    assert_eq!(other_world.registered_namespaces().await.unwrap(), Vec::<String>::new());
+    assert!(other_world.model(selector).await.unwrap_err().to_string().contains("no rows"));
+}
+
+/// Sets the score of `player` in the `ns-Score` model of `db`.
+async fn set_score(db: &mut Sql, player: u8, team: u8, score: u128) {
+    let selector = compute_selector_from_names("ns", "Score");
+    let mut entity = db.model(selector).await.unwrap().schema;
+    entity.deserialize(&mut vec![player.into(), team.into(), score.into(), Felt::ONE]).unwrap();
+
+    let entity_id = poseidon_hash_many(&[player.into()]);
+    let keys = format!("{:#x}/", Felt::from(player));
+    db.set_entity(entity, "0x0:0x0:0x0", 0, entity_id, selector, Some(&keys)).await.unwrap();
+}
+
+/// Updates the `member` of `player` in the `ns-Score` model of `db`, as the `StoreUpdateMember`
+/// processor does, with an entity only holding the updated member.
+async fn update_score_member(db: &mut Sql, player: u8, member: &str, value: Felt) {
+    let selector = compute_selector_from_names("ns", "Score");
+    let schema = db.model(selector).await.unwrap().schema;
+    let mut member =
+        schema.as_struct().unwrap().children.iter().find(|m| m.name == member).unwrap().clone();
+    member.ty.deserialize(&mut vec![value]).unwrap();
+
+    let update = Ty::Struct(Struct { name: schema.name(), children: vec![member] });
+    let entity_id = poseidon_hash_many(&[player.into()]);
+    db.set_entity(update, "0x0:0x0:0x0", 0, entity_id, selector, None).await.unwrap();
+}
+
+/// Returns the `(group_key, value)` rows of an aggregation.
+async fn aggregation_rows(name: &str, pool: &sqlx::Pool<sqlx::Sqlite>) -> Vec<(String, f64)> {
+    sqlx::query_as(
+        "SELECT group_key, CAST(value AS REAL) FROM aggregations WHERE aggregation_name = ? \
+         ORDER BY group_key",
+    )
+    .bind(name)
+    .fetch_all(pool)
+    .await
+    .unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_aggregations() {
+    let tempfile = NamedTempFile::new().unwrap();
+    let (pool, db) = sqlite_db(&tempfile).await;
+    let aggregation = "leaderboard:ns-Score:sum(score):team".parse().unwrap();
+    let mut db = db.with_aggregations(vec![aggregation]);
+
+    let model = Ty::Struct(Struct {
+        name: "Score".to_string(),
+        children: vec![
+            Member {
+                name: "player".to_string(),
+                ty: Ty::Primitive(Primitive::U8(None)),
+                key: true,
+            },
+            Member { name: "team".to_string(), ty: Ty::Primitive(Primitive::U8(None)), key: false },
+            Member {
+                name: "score".to_string(),
+                ty: Ty::Primitive(Primitive::U128(None)),
+                key: false,
+            },
+            Member {
+                name: "level".to_string(),
+                ty: Ty::Primitive(Primitive::U8(None)),
+                key: false,
+            },
+        ],
+    });
+    db.register_model("ns", &model, Layout::Fixed(vec![]), Felt::ZERO, Felt::ZERO, 4, 4, 0, None)
+        .await
+        .unwrap();
+
+    // the u128 scores are stored as hex strings, decoded past 2^64
+    let high_score = (1u128 << 64) + 1;
+    set_score(&mut db, 1, 1, high_score).await;
+    set_score(&mut db, 2, 1, 5).await;
+    set_score(&mut db, 3, 2, 7).await;
+    db.execute().await.unwrap();
+
+    assert_eq!(
+        aggregation_rows("leaderboard", &pool).await,
+        vec![("1".to_string(), 2f64.powi(64) + 6.0), ("2".to_string(), 7.0)]
+    );
+
+    // the player 2 leaves the team 1 for the team 2
+    set_score(&mut db, 2, 2, 5).await;
+    db.execute().await.unwrap();
+
+    assert_eq!(
+        aggregation_rows("leaderboard", &pool).await,
+        vec![("1".to_string(), high_score as f64), ("2".to_string(), 12.0)]
+    );
+
+    // updating the score alone only recomputes the group of the player
+    update_score_member(&mut db, 3, "score", Felt::from(8)).await;
+    db.execute().await.unwrap();
+
+    assert_eq!(
+        aggregation_rows("leaderboard", &pool).await,
+        vec![("1".to_string(), high_score as f64), ("2".to_string(), 13.0)]
+    );
+
+    // updating a member which isn't aggregated still decodes the hex stored scores
+    update_score_member(&mut db, 1, "level", Felt::TWO).await;
+    db.execute().await.unwrap();
+
+    assert_eq!(
+        aggregation_rows("leaderboard", &pool).await,
+        vec![("1".to_string(), high_score as f64), ("2".to_string(), 13.0)]
+    );
+
+    // as well as when the whole aggregation is recomputed on a change of group
+    update_score_member(&mut db, 2, "team", Felt::ONE).await;
+    db.execute().await.unwrap();
+
+    assert_eq!(
+        aggregation_rows("leaderboard", &pool).await,
+        vec![("1".to_string(), high_score as f64 + 5.0), ("2".to_string(), 8.0)]
+    );
+
+    // the group of a deleted entity is recomputed, and removed once empty
+    let selector = compute_selector_from_names("ns", "Score");
+    for player in [1u8, 3] {
+        let entity = db.model(selector).await.unwrap().schema;
+        let entity_id = poseidon_hash_many(&[player.into()]);
+        db.delete_entity(entity_id, selector, entity, "0x0:0x0:0x0", 0).await.unwrap();
+    }
+    db.execute().await.unwrap();
+
+    assert_eq!(aggregation_rows("leaderboard", &pool).await, vec![("1".to_string(), 5.0)]);
+}
+
 /// Count the number of rows in a table.
 ///
 /// # Arguments
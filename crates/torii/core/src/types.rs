@@ -127,6 +127,37 @@ pub struct Contract {
     pub r#type: ContractType,
 }
 
+/// An additional world indexed by the same torii instance, optionally through its own RPC
+/// endpoint. Worlds share the database, the models and entities of each additional world being
+/// isolated from the ones of the other worlds.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct World {
+    pub address: Felt,
+    /// The RPC endpoint of the world's chain. Defaults to the RPC endpoint of torii.
+    pub rpc: Option<String>,
+}
+
+impl FromStr for World {
+    type Err = anyhow::Error;
+
+    /// Parses a world in the format `address[:rpc]`, e.g. `0x1234:http://localhost:5050`.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let (address, rpc) = match input.split_once(':') {
+            Some((address, rpc)) => (address, Some(rpc)),
+            None => (input, None),
+        };
+
+        let address = Felt::from_str(address)
+            .map_err(|_| anyhow::anyhow!("Expected world address, found {}", address))?;
+
+        if rpc.is_some_and(|rpc| rpc.is_empty()) {
+            return Err(anyhow::anyhow!("Invalid world format: {}", input));
+        }
+
+        Ok(World { address, rpc: rpc.map(|rpc| rpc.to_string()) })
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ContractType {
     WORLD,
@@ -157,6 +188,104 @@ impl std::fmt::Display for ContractType {
     }
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregationFunction {
+    Sum,
+    Count,
+    Min,
+    Max,
+    Avg,
+}
+
+impl FromStr for AggregationFunction {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input.to_lowercase().as_str() {
+            "sum" => Ok(AggregationFunction::Sum),
+            "count" => Ok(AggregationFunction::Count),
+            "min" => Ok(AggregationFunction::Min),
+            "max" => Ok(AggregationFunction::Max),
+            "avg" => Ok(AggregationFunction::Avg),
+            _ => Err(anyhow::anyhow!("Invalid aggregation function: {}", input)),
+        }
+    }
+}
+
+impl std::fmt::Display for AggregationFunction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AggregationFunction::Sum => write!(f, "SUM"),
+            AggregationFunction::Count => write!(f, "COUNT"),
+            AggregationFunction::Min => write!(f, "MIN"),
+            AggregationFunction::Max => write!(f, "MAX"),
+            AggregationFunction::Avg => write!(f, "AVG"),
+        }
+    }
+}
+
+/// An aggregation over the entities of a model, maintained by the indexer as a table of one row
+/// per group (e.g. `SUM(score) GROUP BY player` over `ns-Score`).
+///
+/// Only top level integer members can be aggregated, and grouped by any top level primitive
+/// member.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Aggregation {
+    /// The name used to query the aggregation.
+    pub name: String,
+    /// The tag of the model (namespace-name) the aggregation is computed over.
+    pub model: String,
+    pub function: AggregationFunction,
+    /// The member aggregated. Ignored for [`AggregationFunction::Count`].
+    pub member: String,
+    /// The members the entities are grouped by. If empty, a single row is maintained.
+    pub group_by: Vec<String>,
+}
+
+impl FromStr for Aggregation {
+    type Err = anyhow::Error;
+
+    /// Parses an aggregation in the format `name:model:function(member)[:group_by+group_by]`,
+    /// e.g. `leaderboard:ns-Score:sum(score):player`.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let parts = input.split(':').collect::<Vec<&str>>();
+        let (name, model, function, group_by) = match parts.as_slice() {
+            [name, model, function] => (name, model, function, None),
+            [name, model, function, group_by] => (name, model, function, Some(group_by)),
+            _ => return Err(anyhow::anyhow!("Invalid aggregation format: {}", input)),
+        };
+
+        let (function, member) = function
+            .strip_suffix(')')
+            .and_then(|f| f.split_once('('))
+            .ok_or_else(|| anyhow::anyhow!("Expected function(member), found {}", function))?;
+
+        if name.is_empty() || model.split_once('-').is_none() {
+            return Err(anyhow::anyhow!("Invalid aggregation name or model tag: {}", input));
+        }
+
+        Ok(Aggregation {
+            name: name.to_string(),
+            model: model.to_string(),
+            function: function.parse()?,
+            member: member.to_string(),
+            group_by: group_by
+                .map(|g| g.split('+').map(|m| m.to_string()).collect())
+                .unwrap_or_default(),
+        })
+    }
+}
+
+#[derive(FromRow, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AggregationEntry {
+    pub id: String,
+    pub aggregation_name: String,
+    pub group_key: String,
+    pub value: String,
+    pub updated_at: DateTime<Utc>,
+}
+
 #[derive(FromRow, Deserialize, Debug, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct ContractCursor {
@@ -0,0 +1,281 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use anyhow::{anyhow, Result};
+use futures_util::StreamExt;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::Sha256;
+use tokio::sync::broadcast::Sender;
+use tokio::time::{interval, sleep, MissedTickBehavior};
+use tracing::{debug, error, warn};
+
+use crate::simple_broker::SimpleBroker;
+use crate::sql::FELT_DELIMITER;
+use crate::types::Entity;
+
+pub(crate) const LOG_TARGET: &str = "torii_core::webhook";
+
+/// Header containing the hex encoded HMAC-SHA256 of the request body, prefixed by `sha256=`.
+pub const SIGNATURE_HEADER: &str = "X-Torii-Signature";
+
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    /// The URL the batches are POSTed to.
+    pub url: String,
+    /// The secret used to sign the request bodies. If not set, requests are not signed.
+    pub secret: Option<String>,
+    /// The model tags (namespace-name) to deliver updates for. If empty, all models are delivered.
+    pub models: HashSet<String>,
+    /// The maximum number of updates sent in a single request.
+    pub batch_size: usize,
+    /// The maximum time an update is buffered before being delivered.
+    pub flush_interval: Duration,
+    /// The number of times a failed delivery is retried, with an exponential backoff.
+    pub max_retries: u32,
+}
+
+/// An entity update as delivered to the webhook.
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EntityUpdatePayload {
+    pub model: String,
+    pub entity_id: String,
+    pub keys: Vec<String>,
+    /// The members written by the update, mapped to their values.
+    pub members: Value,
+    pub deleted: bool,
+    pub block_number: Option<u64>,
+    pub transaction_hash: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct WebhookBatch<'a> {
+    updates: &'a [EntityUpdatePayload],
+}
+
+/// Delivers the committed entity updates matching the configured filters to a webhook, in
+/// batches.
+#[derive(Debug)]
+pub struct WebhookSink {
+    config: WebhookConfig,
+    client: reqwest::Client,
+    shutdown_tx: Sender<()>,
+}
+
+impl WebhookSink {
+    pub fn new(config: WebhookConfig, shutdown_tx: Sender<()>) -> Self {
+        Self { config, client: reqwest::Client::new(), shutdown_tx }
+    }
+
+    pub async fn run(self) -> Result<()> {
+        let mut shutdown_rx = self.shutdown_tx.subscribe();
+        let mut updates = SimpleBroker::<Entity>::subscribe();
+
+        let mut flush = interval(self.config.flush_interval);
+        flush.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        let mut buffer = Vec::with_capacity(self.config.batch_size);
+
+        loop {
+            tokio::select! {
+                _ = shutdown_rx.recv() => break,
+                entity = updates.next() => {
+                    let Some(entity) = entity else { break };
+
+                    if let Some(payload) = entity_payload(&entity, &self.config.models) {
+                        buffer.push(payload);
+                    }
+
+                    if buffer.len() >= self.config.batch_size {
+                        self.deliver(&buffer).await;
+                        buffer.clear();
+                    }
+                }
+                _ = flush.tick() => {
+                    if !buffer.is_empty() {
+                        self.deliver(&buffer).await;
+                        buffer.clear();
+                    }
+                }
+            }
+        }
+
+        if !buffer.is_empty() {
+            self.deliver(&buffer).await;
+        }
+
+        Ok(())
+    }
+
+    /// Sends a batch, retrying on failure. A batch that still fails after all the retries is
+    /// dropped so that a faulty endpoint doesn't hold back the next updates.
+    async fn deliver(&self, updates: &[EntityUpdatePayload]) {
+        let body = match serde_json::to_vec(&WebhookBatch { updates }) {
+            Ok(body) => body,
+            Err(e) => {
+                error!(target: LOG_TARGET, error = %e, "Serializing webhook batch.");
+                return;
+            }
+        };
+
+        let mut backoff = Duration::from_millis(500);
+
+        for attempt in 0..=self.config.max_retries {
+            match self.send(&body).await {
+                Ok(()) => {
+                    debug!(target: LOG_TARGET, count = updates.len(), "Delivered webhook batch.");
+                    return;
+                }
+                Err(e) if attempt < self.config.max_retries => {
+                    warn!(
+                        target: LOG_TARGET,
+                        error = %e,
+                        attempt,
+                        "Delivering webhook batch, retrying."
+                    );
+                    sleep(backoff).await;
+                    backoff *= 2;
+                }
+                Err(e) => {
+                    error!(
+                        target: LOG_TARGET,
+                        error = %e,
+                        count = updates.len(),
+                        "Delivering webhook batch, dropping it."
+                    );
+                }
+            }
+        }
+    }
+
+    async fn send(&self, body: &[u8]) -> Result<()> {
+        let mut request = self
+            .client
+            .post(&self.config.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_vec());
+
+        if let Some(secret) = &self.config.secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, body));
+        }
+
+        let response = request.send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Webhook responded with status {}", response.status()));
+        }
+
+        Ok(())
+    }
+}
+
+/// Computes the signature of a request body, sent in the [`SIGNATURE_HEADER`] header.
+pub fn sign(secret: &str, body: &[u8]) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(body);
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Builds the payload of an entity update, if its model matches the filter.
+fn entity_payload(entity: &Entity, models: &HashSet<String>) -> Option<EntityUpdatePayload> {
+    let model = entity.updated_model.as_ref()?;
+    let name = model.name();
+
+    if !models.is_empty() && !models.contains(&name) {
+        return None;
+    }
+
+    let members = match model.as_struct() {
+        Some(s) if !entity.deleted => Value::Object(
+            s.children
+                .iter()
+                .filter(|m| !m.key)
+                .map(|m| (m.name.clone(), serde_json::to_value(&m.ty).unwrap_or_default()))
+                .collect(),
+        ),
+        _ => Value::Null,
+    };
+
+    // event_id format: block_number:transaction_hash:event_idx, the block number being hex encoded
+    let mut event_id = entity.event_id.split(':');
+    let block_number =
+        event_id.next().and_then(|b| u64::from_str_radix(b.trim_start_matches("0x"), 16).ok());
+    let transaction_hash = event_id.next().map(|t| t.to_string());
+
+    Some(EntityUpdatePayload {
+        model: name,
+        entity_id: entity.id.clone(),
+        keys: entity
+            .keys
+            .split(FELT_DELIMITER)
+            .filter(|k| !k.is_empty())
+            .map(|k| k.to_string())
+            .collect(),
+        members,
+        deleted: entity.deleted,
+        block_number,
+        transaction_hash,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use dojo_types::primitive::Primitive;
+    use dojo_types::schema::{Member, Struct, Ty};
+
+    use super::*;
+
+    fn entity(model: &str) -> Entity {
+        Entity {
+            id: "0x1".to_string(),
+            keys: "0xa/0xb/".to_string(),
+            event_id: format!("{:#064x}:{:#x}:{:#04x}", 42, 0x123, 0),
+            executed_at: Utc::now(),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            updated_model: Some(Ty::Struct(Struct {
+                name: model.to_string(),
+                children: vec![
+                    Member {
+                        name: "player".to_string(),
+                        ty: Ty::Primitive(Primitive::ContractAddress(None)),
+                        key: true,
+                    },
+                    Member {
+                        name: "score".to_string(),
+                        ty: Ty::Primitive(Primitive::U32(Some(7))),
+                        key: false,
+                    },
+                ],
+            })),
+            deleted: false,
+        }
+    }
+
+    #[test]
+    fn payload_is_filtered_by_model() {
+        let models = HashSet::from(["ns-Score".to_string()]);
+
+        assert!(entity_payload(&entity("ns-Position"), &models).is_none());
+        assert!(entity_payload(&entity("ns-Position"), &HashSet::new()).is_some());
+
+        let payload = entity_payload(&entity("ns-Score"), &models).unwrap();
+        assert_eq!(payload.model, "ns-Score");
+        assert_eq!(payload.keys, vec!["0xa".to_string(), "0xb".to_string()]);
+        assert_eq!(payload.block_number, Some(42));
+        assert_eq!(payload.transaction_hash.as_deref(), Some("0x123"));
+        assert_eq!(payload.members.as_object().unwrap().keys().collect::<Vec<_>>(), vec!["score"]);
+    }
+
+    #[test]
+    fn signature_is_hmac_sha256() {
+        // RFC 4231 test case 2.
+        assert_eq!(
+            sign("Jefe", b"what do ya want for nothing?"),
+            "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+    }
+}
@@ -9,6 +9,9 @@ pub const EVENT_MESSAGE_TABLE: &str = "event_messages";
 pub const MODEL_TABLE: &str = "models";
 pub const TRANSACTION_TABLE: &str = "transactions";
 pub const METADATA_TABLE: &str = "metadata";
+pub const AGGREGATION_TABLE: &str = "aggregations";
+pub const ACHIEVEMENT_TABLE: &str = "achievements";
+pub const ACHIEVEMENT_PROGRESSION_TABLE: &str = "achievement_progressions";
 
 pub const ID_COLUMN: &str = "id";
 pub const EVENT_ID_COLUMN: &str = "event_id";
@@ -29,6 +32,9 @@ pub const CONTENT_TYPE_NAME: &str = "World__Content";
 pub const METADATA_TYPE_NAME: &str = "World__Metadata";
 pub const PAGE_INFO_TYPE_NAME: &str = "World__PageInfo";
 pub const TRANSACTION_TYPE_NAME: &str = "World__Transaction";
+pub const AGGREGATION_TYPE_NAME: &str = "World__Aggregation";
+pub const ACHIEVEMENT_TYPE_NAME: &str = "World__Achievement";
+pub const ACHIEVEMENT_PROGRESSION_TYPE_NAME: &str = "World__AchievementProgression";
 pub const QUERY_TYPE_NAME: &str = "World__Query";
 pub const SUBSCRIPTION_TYPE_NAME: &str = "World__Subscription";
 pub const MODEL_ORDER_TYPE_NAME: &str = "World__ModelOrder";
@@ -50,6 +56,10 @@ pub const SOCIAL_NAMES: (&str, &str) = ("social", "socials");
 pub const CONTENT_NAMES: (&str, &str) = ("content", "contents");
 pub const METADATA_NAMES: (&str, &str) = ("metadata", "metadatas");
 pub const TRANSACTION_NAMES: (&str, &str) = ("transaction", "transactions");
+pub const AGGREGATION_NAMES: (&str, &str) = ("aggregation", "aggregations");
+pub const ACHIEVEMENT_NAMES: (&str, &str) = ("achievement", "achievements");
+pub const ACHIEVEMENT_PROGRESSION_NAMES: (&str, &str) =
+    ("achievementProgression", "achievementProgressions");
 pub const PAGE_INFO_NAMES: (&str, &str) = ("pageInfo", "");
 
 pub const ERC20_TOKEN_NAME: (&str, &str) = ("erc20Token", "");
@@ -99,6 +99,65 @@ lazy_static! {
             TypeData::Simple(TypeRef::named(GraphqlType::DateTime.to_string())),
         ),
     ]);
+    pub static ref AGGREGATION_TYPE_MAPPING: TypeMapping = IndexMap::from([
+        (Name::new("id"), TypeData::Simple(TypeRef::named(TypeRef::ID))),
+        (Name::new("aggregationName"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (Name::new("groupKey"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (Name::new("value"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (
+            Name::new("updatedAt"),
+            TypeData::Simple(TypeRef::named(GraphqlType::DateTime.to_string())),
+        ),
+    ]);
+    pub static ref ACHIEVEMENT_TYPE_MAPPING: TypeMapping = IndexMap::from([
+        (Name::new("id"), TypeData::Simple(TypeRef::named(TypeRef::ID))),
+        (Name::new("namespace"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (
+            Name::new("trophyId"),
+            TypeData::Simple(TypeRef::named(Primitive::Felt252(None).to_string())),
+        ),
+        (Name::new("hidden"), TypeData::Simple(TypeRef::named(Primitive::Bool(None).to_string()))),
+        (Name::new("idx"), TypeData::Simple(TypeRef::named(Primitive::U32(None).to_string()))),
+        (Name::new("points"), TypeData::Simple(TypeRef::named(Primitive::U32(None).to_string()))),
+        (Name::new("startAt"), TypeData::Simple(TypeRef::named(Primitive::U32(None).to_string()))),
+        (Name::new("endAt"), TypeData::Simple(TypeRef::named(Primitive::U32(None).to_string()))),
+        (Name::new("groupName"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (Name::new("icon"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (Name::new("title"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (Name::new("description"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (Name::new("tasks"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (Name::new("data"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (
+            Name::new("executedAt"),
+            TypeData::Simple(TypeRef::named(GraphqlType::DateTime.to_string())),
+        ),
+        (
+            Name::new("createdAt"),
+            TypeData::Simple(TypeRef::named(GraphqlType::DateTime.to_string())),
+        ),
+        (
+            Name::new("updatedAt"),
+            TypeData::Simple(TypeRef::named(GraphqlType::DateTime.to_string())),
+        ),
+    ]);
+    pub static ref ACHIEVEMENT_PROGRESSION_TYPE_MAPPING: TypeMapping = IndexMap::from([
+        (Name::new("id"), TypeData::Simple(TypeRef::named(TypeRef::ID))),
+        (Name::new("namespace"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (
+            Name::new("playerId"),
+            TypeData::Simple(TypeRef::named(Primitive::Felt252(None).to_string())),
+        ),
+        (Name::new("taskId"), TypeData::Simple(TypeRef::named(TypeRef::STRING))),
+        (Name::new("count"), TypeData::Simple(TypeRef::named(Primitive::U32(None).to_string()))),
+        (
+            Name::new("executedAt"),
+            TypeData::Simple(TypeRef::named(GraphqlType::DateTime.to_string())),
+        ),
+        (
+            Name::new("updatedAt"),
+            TypeData::Simple(TypeRef::named(GraphqlType::DateTime.to_string())),
+        ),
+    ]);
     pub static ref PAGE_INFO_TYPE_MAPPING: TypeMapping = TypeMapping::from([
         (Name::new("hasPreviousPage"), TypeData::Simple(TypeRef::named(TypeRef::BOOLEAN))),
         (Name::new("hasNextPage"), TypeData::Simple(TypeRef::named(TypeRef::BOOLEAN))),
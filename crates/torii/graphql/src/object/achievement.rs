@@ -0,0 +1,132 @@
+use async_graphql::dynamic::{Field, FieldFuture, InputValue, Object, TypeRef};
+use async_graphql::Value;
+use sqlx::{Pool, Sqlite};
+use starknet_crypto::Felt;
+
+use super::{BasicObject, ResolvableObject, TypeMapping};
+use crate::constants::{
+    ACHIEVEMENT_NAMES, ACHIEVEMENT_PROGRESSION_NAMES, ACHIEVEMENT_PROGRESSION_TABLE,
+    ACHIEVEMENT_PROGRESSION_TYPE_NAME, ACHIEVEMENT_TABLE, ACHIEVEMENT_TYPE_NAME,
+};
+use crate::mapping::{ACHIEVEMENT_PROGRESSION_TYPE_MAPPING, ACHIEVEMENT_TYPE_MAPPING};
+use crate::query::value_mapping_from_row;
+use crate::utils::extract;
+
+#[derive(Debug)]
+pub struct AchievementObject;
+
+impl BasicObject for AchievementObject {
+    fn name(&self) -> (&str, &str) {
+        ACHIEVEMENT_NAMES
+    }
+
+    fn type_name(&self) -> &str {
+        ACHIEVEMENT_TYPE_NAME
+    }
+
+    fn type_mapping(&self) -> &TypeMapping {
+        &ACHIEVEMENT_TYPE_MAPPING
+    }
+}
+
+impl ResolvableObject for AchievementObject {
+    // Returns the achievements of all namespaces, or of a single one, in their declared order.
+    fn resolvers(&self) -> Vec<Field> {
+        let type_mapping = self.type_mapping().clone();
+
+        let field =
+            Field::new(self.name().1, TypeRef::named_nn_list_nn(self.type_name()), move |ctx| {
+                let type_mapping = type_mapping.clone();
+
+                FieldFuture::new(async move {
+                    let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
+                    let namespace = extract::<String>(ctx.args.as_index_map(), "namespace").ok();
+
+                    let query = format!(
+                        "SELECT * FROM {ACHIEVEMENT_TABLE} WHERE ?1 IS NULL OR namespace = ?1 \
+                         ORDER BY namespace, idx"
+                    );
+                    let rows = sqlx::query(&query).bind(namespace).fetch_all(&mut *conn).await?;
+
+                    let results = rows
+                        .iter()
+                        .map(|row| {
+                            value_mapping_from_row(row, &type_mapping, false).map(Value::Object)
+                        })
+                        .collect::<sqlx::Result<Vec<Value>>>()?;
+
+                    Ok(Some(Value::List(results)))
+                })
+            })
+            .argument(InputValue::new("namespace", TypeRef::named(TypeRef::STRING)));
+
+        vec![field]
+    }
+
+    fn connection_objects(&self) -> Option<Vec<Object>> {
+        None
+    }
+}
+
+#[derive(Debug)]
+pub struct AchievementProgressionObject;
+
+impl BasicObject for AchievementProgressionObject {
+    fn name(&self) -> (&str, &str) {
+        ACHIEVEMENT_PROGRESSION_NAMES
+    }
+
+    fn type_name(&self) -> &str {
+        ACHIEVEMENT_PROGRESSION_TYPE_NAME
+    }
+
+    fn type_mapping(&self) -> &TypeMapping {
+        &ACHIEVEMENT_PROGRESSION_TYPE_MAPPING
+    }
+}
+
+impl ResolvableObject for AchievementProgressionObject {
+    // Returns the progress of a player on the achievements tasks. Completion is derived by the
+    // client from the tasks totals of the achievements.
+    fn resolvers(&self) -> Vec<Field> {
+        let type_mapping = self.type_mapping().clone();
+
+        let field =
+            Field::new(self.name().1, TypeRef::named_nn_list_nn(self.type_name()), move |ctx| {
+                let type_mapping = type_mapping.clone();
+
+                FieldFuture::new(async move {
+                    let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
+                    let player_id = extract::<Felt>(ctx.args.as_index_map(), "playerId")?;
+                    let namespace = extract::<String>(ctx.args.as_index_map(), "namespace").ok();
+
+                    let query = format!(
+                        "SELECT * FROM {ACHIEVEMENT_PROGRESSION_TABLE} WHERE player_id = ?1 AND \
+                         (?2 IS NULL OR namespace = ?2) ORDER BY namespace, task_id"
+                    );
+                    let rows = sqlx::query(&query)
+                        .bind(format!("{:#x}", player_id))
+                        .bind(namespace)
+                        .fetch_all(&mut *conn)
+                        .await?;
+
+                    let results = rows
+                        .iter()
+                        .map(|row| {
+                            value_mapping_from_row(row, &type_mapping, false).map(Value::Object)
+                        })
+                        .collect::<sqlx::Result<Vec<Value>>>()?;
+
+                    Ok(Some(Value::List(results)))
+                })
+            })
+            .argument(InputValue::new("playerId", TypeRef::named_nn(TypeRef::STRING)))
+            .argument(InputValue::new("namespace", TypeRef::named(TypeRef::STRING)));
+
+        vec![field]
+    }
+
+    fn connection_objects(&self) -> Option<Vec<Object>> {
+        None
+    }
+}
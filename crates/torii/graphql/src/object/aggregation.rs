@@ -0,0 +1,87 @@
+use async_graphql::dynamic::{Field, FieldFuture, InputValue, Object, TypeRef};
+use async_graphql::Value;
+use sqlx::{Pool, Sqlite};
+
+use super::{BasicObject, ResolvableObject, TypeMapping};
+use crate::constants::{
+    AGGREGATION_NAMES, AGGREGATION_TABLE, AGGREGATION_TYPE_NAME, DEFAULT_LIMIT, ORDER_ASC,
+    ORDER_DESC, ORDER_DIR_TYPE_NAME,
+};
+use crate::mapping::AGGREGATION_TYPE_MAPPING;
+use crate::query::value_mapping_from_row;
+use crate::utils::extract;
+
+#[derive(Debug)]
+pub struct AggregationObject;
+
+impl BasicObject for AggregationObject {
+    fn name(&self) -> (&str, &str) {
+        AGGREGATION_NAMES
+    }
+
+    fn type_name(&self) -> &str {
+        AGGREGATION_TYPE_NAME
+    }
+
+    fn type_mapping(&self) -> &TypeMapping {
+        &AGGREGATION_TYPE_MAPPING
+    }
+}
+
+impl ResolvableObject for AggregationObject {
+    // Returns the rows of an aggregation ordered by value, which is what leaderboards need. The
+    // value is returned as a string since sums can overflow a graphql Int.
+    fn resolvers(&self) -> Vec<Field> {
+        let type_mapping = self.type_mapping().clone();
+
+        let field =
+            Field::new(self.name().1, TypeRef::named_nn_list_nn(self.type_name()), move |ctx| {
+                let type_mapping = type_mapping.clone();
+
+                FieldFuture::new(async move {
+                    let mut conn = ctx.data::<Pool<Sqlite>>()?.acquire().await?;
+                    let name = extract::<String>(ctx.args.as_index_map(), "name")?;
+                    let limit =
+                        extract::<u64>(ctx.args.as_index_map(), "limit").unwrap_or(DEFAULT_LIMIT);
+                    let offset = extract::<u64>(ctx.args.as_index_map(), "offset").unwrap_or(0);
+                    let direction = match ctx.args.get("direction") {
+                        Some(direction) if direction.enum_name()? == ORDER_ASC => ORDER_ASC,
+                        _ => ORDER_DESC,
+                    };
+
+                    // The value is ordered on the numeric column and not on its text cast.
+                    let query = format!(
+                        "SELECT id, aggregation_name, group_key, CAST(value AS TEXT) AS value, \
+                         updated_at FROM {AGGREGATION_TABLE} WHERE aggregation_name = ? ORDER BY \
+                         {AGGREGATION_TABLE}.value {direction} LIMIT ? OFFSET ?"
+                    );
+
+                    let rows = sqlx::query(&query)
+                        .bind(name)
+                        .bind(limit as i64)
+                        .bind(offset as i64)
+                        .fetch_all(&mut *conn)
+                        .await?;
+
+                    let results = rows
+                        .iter()
+                        .map(|row| {
+                            value_mapping_from_row(row, &type_mapping, false).map(Value::Object)
+                        })
+                        .collect::<sqlx::Result<Vec<Value>>>()?;
+
+                    Ok(Some(Value::List(results)))
+                })
+            })
+            .argument(InputValue::new("name", TypeRef::named_nn(TypeRef::STRING)))
+            .argument(InputValue::new("limit", TypeRef::named(TypeRef::INT)))
+            .argument(InputValue::new("offset", TypeRef::named(TypeRef::INT)))
+            .argument(InputValue::new("direction", TypeRef::named(ORDER_DIR_TYPE_NAME)));
+
+        vec![field]
+    }
+
+    fn connection_objects(&self) -> Option<Vec<Object>> {
+        None
+    }
+}
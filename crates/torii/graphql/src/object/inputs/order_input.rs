@@ -1,4 +1,6 @@
-use async_graphql::dynamic::{Enum, Field, InputObject, InputValue, ResolverContext, TypeRef};
+use async_graphql::dynamic::{
+    Enum, Field, InputObject, InputValue, ResolverContext, TypeRef, ValueAccessor,
+};
 
 use super::InputObjectTrait;
 use crate::constants::{ORDER_ASC, ORDER_DESC, ORDER_DIR_TYPE_NAME};
@@ -52,16 +54,35 @@ impl InputObjectTrait for OrderInputObject {
 }
 
 pub fn order_argument(field: Field, type_name: &str) -> Field {
-    field.argument(InputValue::new("order", TypeRef::named(format!("{}Order", type_name))))
+    field
+        .argument(InputValue::new("order", TypeRef::named(format!("{}Order", type_name))))
+        .argument(InputValue::new("orderBy", TypeRef::named_nn_list(format!("{}Order", type_name))))
 }
 
+// `orderBy` takes precedence over `order`, its first element being the primary order and the
+// following ones breaking the ties
 pub fn parse_order_argument(ctx: &ResolverContext<'_>) -> Option<Order> {
-    let order_input = ctx.args.get("order")?;
+    if let Some(order_by) = ctx.args.get("orderBy") {
+        let mut orders =
+            order_by.list().ok()?.iter().map(parse_order).collect::<Option<Vec<_>>>()?;
+        if orders.is_empty() {
+            return None;
+        }
+
+        let mut order = orders.remove(0);
+        order.then = orders;
+        return Some(order);
+    }
+
+    parse_order(ctx.args.get("order")?)
+}
+
+fn parse_order(order_input: ValueAccessor<'_>) -> Option<Order> {
     let input_object = order_input.object().ok()?;
     let dir_value = input_object.get("direction")?;
     let field_value = input_object.get("field")?;
 
     let direction = Direction::try_from(dir_value.enum_name().ok()?).ok()?;
     let field = field_value.enum_name().ok()?.to_lowercase();
-    Some(Order { direction, field })
+    Some(Order { direction, field, then: vec![] })
 }
@@ -20,8 +20,8 @@ pub struct WhereInputObject {
 
 impl WhereInputObject {
     // Iterate through an object's type mapping and create a new mapping for whereInput. For each of
-    // the object type (model member), we add 6 additional types for comparators (great than,
-    // not equal, etc)
+    // the object type (model member), we add additional types for comparators (great than,
+    // not equal, between, etc). Byte arrays get the text comparators only, including contains.
     pub fn new(type_name: &str, object_types: &TypeMapping) -> Self {
         let where_mapping = object_types
             .iter()
@@ -34,16 +34,31 @@ impl WhereInputObject {
                     return vec![(Name::new(type_name), type_data.clone())];
                 }
 
+                let is_byte_array = type_data.type_ref() == TypeRef::named("ByteArray");
+
                 Comparator::iter().fold(
                     vec![(Name::new(type_name), type_data.clone())],
                     |mut acc, comparator| {
                         let name = format!("{}{}", type_name, comparator.as_ref());
 
                         match comparator {
-                            Comparator::In | Comparator::NotIn => acc.push((
-                                Name::new(name),
-                                TypeData::List(Box::new(type_data.clone())),
-                            )),
+                            Comparator::In | Comparator::NotIn | Comparator::Between => {
+                                // range comparisons on text are lexicographic, which is
+                                // meaningless for byte arrays
+                                if !(is_byte_array && comparator == Comparator::Between) {
+                                    acc.push((
+                                        Name::new(name),
+                                        TypeData::List(Box::new(type_data.clone())),
+                                    ))
+                                }
+                            }
+                            Comparator::Contains => {
+                                if is_byte_array {
+                                    acc.push((Name::new(name), type_data.clone()));
+                                }
+                            }
+                            Comparator::Gt | Comparator::Gte | Comparator::Lt | Comparator::Lte
+                                if is_byte_array => {}
                             _ => {
                                 acc.push((Name::new(name), type_data.clone()));
                             }
@@ -98,6 +113,17 @@ pub fn parse_where_argument(
                             )));
                         }
 
+                        // byte arrays are stored as plain text
+                        if type_data.type_ref() == TypeRef::named("ByteArray") {
+                            let value = input.string().map_err(|_| {
+                                GqlError::new(format!("Expected string on field {}", type_name))
+                            })?;
+                            return Ok(Some(parse_filter(
+                                type_name,
+                                FilterValue::String(value.to_string()),
+                            )));
+                        }
+
                         let primitive = Primitive::from_str(&type_data.type_ref().to_string())?;
                         let filter_value = match primitive.to_sql_type() {
                             SqlType::Integer => parse_integer(input, type_name, primitive)?,
@@ -111,6 +137,12 @@ pub fn parse_where_argument(
                         let values = list
                             .iter()
                             .map(|value| {
+                                if inner.type_ref() == TypeRef::named("ByteArray") {
+                                    return value
+                                        .string()
+                                        .map(|s| FilterValue::String(s.to_string()));
+                                }
+
                                 let primitive = Primitive::from_str(&inner.type_ref().to_string())?;
                                 match primitive.to_sql_type() {
                                     SqlType::Integer => parse_integer(value, type_name, primitive),
@@ -119,7 +151,19 @@ pub fn parse_where_argument(
                             })
                             .collect::<Result<Vec<_>>>()?;
 
-                        Ok(Some(parse_filter(type_name, FilterValue::List(values))))
+                        let filter = parse_filter(type_name, FilterValue::List(values));
+                        if filter.comparator == Comparator::Between {
+                            if let FilterValue::List(bounds) = &filter.value {
+                                if bounds.len() != 2 {
+                                    return Err(GqlError::new(format!(
+                                        "Expected a lower and an upper bound on field {}",
+                                        type_name
+                                    )));
+                                }
+                            }
+                        }
+
+                        Ok(Some(filter))
                     }
                     _ => Err(GqlError::new("Nested types are not supported")),
                 })
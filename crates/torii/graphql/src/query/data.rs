@@ -3,7 +3,7 @@ use sqlx::sqlite::SqliteRow;
 use sqlx::{Result, Row, SqliteConnection};
 use torii_core::sql::WORLD_CONTRACT_TYPE;
 
-use super::filter::{Filter, FilterValue};
+use super::filter::{Comparator, Filter, FilterValue};
 use super::order::{CursorDirection, Direction, Order};
 use crate::constants::{DEFAULT_LIMIT, MODEL_TABLE};
 use crate::object::connection::{cursor, ConnectionArguments};
@@ -87,13 +87,29 @@ pub async fn fetch_multiple_rows(
     // `first` or `last` param. Explicit ordering take precedence
     match order {
         Some(order) => {
-            let mut column_name = order.field.clone();
-            if table_name != MODEL_TABLE {
-                column_name = format!("external_{}", column_name);
+            // cursors only encode the primary order field, they can't resume a composite ordering
+            if is_cursor_based && !order.then.is_empty() {
+                return Err(sqlx::Error::Protocol(
+                    "Composite ordering is only supported with `offset`/`limit` pagination".into(),
+                ));
             }
+
+            let column_name = |field: &str| {
+                if table_name != MODEL_TABLE {
+                    format!("external_{}", field)
+                } else {
+                    field.to_string()
+                }
+            };
+
+            let order_by = std::iter::once(order)
+                .chain(order.then.iter())
+                .map(|o| format!("{} {}", column_name(&o.field), o.direction.as_ref()))
+                .collect::<Vec<_>>()
+                .join(", ");
+
             query.push_str(&format!(
-                " ORDER BY {column_name} {}, {id_column} {} LIMIT {limit}",
-                order.direction.as_ref(),
+                " ORDER BY {order_by}, {id_column} {} LIMIT {limit}",
                 order.direction.as_ref()
             ));
         }
@@ -226,27 +242,45 @@ fn build_conditions(keys: &Option<Vec<String>>, filters: &Option<Vec<Filter>>) -
     }
 
     if let Some(filters) = filters {
-        conditions.extend(filters.iter().map(|filter| match &filter.value {
-            FilterValue::Int(i) => format!("{} {} {}", filter.field, filter.comparator, i),
-            FilterValue::String(s) => format!("{} {} '{}'", filter.field, filter.comparator, s),
-            FilterValue::List(list) => {
-                let values = list
-                    .iter()
-                    .map(|value| match value {
-                        FilterValue::Int(i) => i.to_string(),
-                        FilterValue::String(s) => format!("'{}'", s),
-                        FilterValue::List(_) => unreachable!(),
-                    })
-                    .collect::<Vec<_>>()
-                    .join(", ");
+        conditions.extend(filters.iter().map(|filter| match (&filter.comparator, &filter.value) {
+            (Comparator::Contains, FilterValue::String(s)) => {
+                // a plain substring scan, with the LIKE wildcards escaped so the value is matched
+                // literally
+                let pattern = s.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+                format!("{} LIKE '%{}%' ESCAPE '\\'", filter.field, escape_string(&pattern))
+            }
+            (Comparator::Between, FilterValue::List(bounds)) => format!(
+                "{} BETWEEN {} AND {}",
+                filter.field,
+                filter_value_to_sql(&bounds[0]),
+                filter_value_to_sql(&bounds[1])
+            ),
+            (_, FilterValue::List(list)) => {
+                let values = list.iter().map(filter_value_to_sql).collect::<Vec<_>>().join(", ");
                 format!("{} {} ({})", filter.field, filter.comparator, values)
             }
+            (_, value) => {
+                format!("{} {} {}", filter.field, filter.comparator, filter_value_to_sql(value))
+            }
         }));
     }
 
     conditions
 }
 
+fn filter_value_to_sql(value: &FilterValue) -> String {
+    match value {
+        FilterValue::Int(i) => i.to_string(),
+        FilterValue::String(s) => format!("'{}'", escape_string(s)),
+        FilterValue::List(_) => unreachable!(),
+    }
+}
+
+// Escapes single quotes of a string literal, since filter values are inlined in the query
+fn escape_string(s: &str) -> String {
+    s.replace('\'', "''")
+}
+
 fn keys_to_pattern(keys: &[String], use_regex: bool) -> String {
     let pattern = keys
         .iter()
@@ -20,6 +20,11 @@ pub enum Comparator {
     In,
     NotLike,
     Like,
+    // Expects a list of two values, the bounds being inclusive
+    Between,
+    // Case-insensitive substring match, only available on ByteArray members. This isn't a
+    // tokenized full-text search: the needle is matched literally, wildcards included.
+    Contains,
 }
 
 impl fmt::Display for Comparator {
@@ -35,6 +40,8 @@ impl fmt::Display for Comparator {
             Comparator::NotIn => write!(f, "NOT IN"),
             Comparator::Like => write!(f, "LIKE"),
             Comparator::NotLike => write!(f, "NOT LIKE"),
+            Comparator::Between => write!(f, "BETWEEN"),
+            Comparator::Contains => write!(f, "LIKE"),
         }
     }
 }
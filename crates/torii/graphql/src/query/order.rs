@@ -11,6 +11,8 @@ pub enum Direction {
 pub struct Order {
     pub field: String,
     pub direction: Direction,
+    // Orders used to break the ties of this one, in priority order
+    pub then: Vec<Order>,
 }
 
 #[derive(AsRefStr, Debug, EnumString)]
@@ -12,6 +12,8 @@ use super::utils;
 use crate::constants::{
     ERC20_TYPE_NAME, ERC721_TYPE_NAME, QUERY_TYPE_NAME, SUBSCRIPTION_TYPE_NAME, TOKEN_TYPE_NAME,
 };
+use crate::object::achievement::{AchievementObject, AchievementProgressionObject};
+use crate::object::aggregation::AggregationObject;
 use crate::object::erc::erc_token::{Erc20TokenObject, Erc721TokenObject};
 use crate::object::erc::token_balance::ErcBalanceObject;
 use crate::object::erc::token_transfer::ErcTransferObject;
@@ -118,6 +120,9 @@ async fn build_objects(pool: &SqlitePool) -> Result<(Vec<ObjectVariant>, Vec<Uni
         ObjectVariant::Resolvable(Box::new(MetadataObject)),
         ObjectVariant::Resolvable(Box::new(ModelObject)),
         ObjectVariant::Resolvable(Box::new(TransactionObject)),
+        ObjectVariant::Resolvable(Box::new(AggregationObject)),
+        ObjectVariant::Resolvable(Box::new(AchievementObject)),
+        ObjectVariant::Resolvable(Box::new(AchievementProgressionObject)),
         ObjectVariant::Resolvable(Box::new(ErcBalanceObject)),
         ObjectVariant::Resolvable(Box::new(ErcTransferObject)),
         ObjectVariant::Basic(Box::new(SocialObject)),
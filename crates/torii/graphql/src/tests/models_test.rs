@@ -1,17 +1,34 @@
 #[cfg(test)]
 mod tests {
     use std::str::FromStr;
+    use std::sync::Arc;
 
     use anyhow::Result;
     use async_graphql::dynamic::Schema;
+    use dojo_types::primitive::Primitive;
+    use dojo_types::schema::{Member, Struct, Ty};
+    use dojo_world::contracts::abigen::model::Layout;
+    use dojo_world::contracts::naming::compute_selector_from_names;
     use serde_json::Value;
+    use sqlx::SqlitePool;
     use starknet::core::types::Felt;
+    use starknet::providers::jsonrpc::HttpTransport;
+    use starknet::providers::JsonRpcClient;
+    use starknet_crypto::poseidon_hash_many;
     use tempfile::NamedTempFile;
+    use tokio::sync::broadcast;
+    use torii_core::executor::Executor;
+    use torii_core::sql::cache::ModelCache;
+    use torii_core::sql::utils::felts_to_sql_string;
+    use torii_core::sql::Sql;
+    use torii_core::types::{Contract, ContractType};
+    use url::Url;
 
     use crate::schema::build_schema;
     use crate::tests::{
         run_graphql_query, spinup_types_test, Connection, Record, RecordSibling, Subrecord,
     };
+    use crate::utils;
 
     async fn record_sibling_query(schema: &Schema, arg: &str) -> Value {
         let query = format!(
@@ -345,6 +362,17 @@ mod tests {
         let connection: Connection<Record> = serde_json::from_value(records).unwrap();
         assert_eq!(connection.total_count, 7);
 
+        // where filter on Between, bounds are inclusive
+        let records = records_model_query(
+            &schema,
+            "(where: { type_u16BETWEEN: [3, 5] }, order: { direction: ASC, field: TYPE_U16 })",
+        )
+        .await;
+        let connection: Connection<Record> = serde_json::from_value(records).unwrap();
+        assert_eq!(connection.total_count, 3);
+        assert_eq!(connection.edges.first().unwrap().node.type_u16, 3);
+        assert_eq!(connection.edges.last().unwrap().node.type_u16, 5);
+
         // *** ORDER TESTING ***
 
         // order on random u8 DESC (number)
@@ -385,6 +413,23 @@ mod tests {
         assert_eq!(connection.total_count, 5);
         assert!(first_record.node.type_felt > last_record.node.type_felt);
 
+        // composite order on bool DESC then u8 ASC
+        let records = records_model_query(
+            &schema,
+            "(orderBy: [{ field: TYPE_BOOL, direction: DESC }, { field: TYPE_U8, direction: ASC \
+             }], limit: 10)",
+        )
+        .await;
+        let connection: Connection<Record> = serde_json::from_value(records).unwrap();
+        assert_eq!(connection.edges.len(), 10);
+        for pair in connection.edges.windows(2) {
+            let (a, b) = (&pair[0].node, &pair[1].node);
+            assert!(a.type_bool >= b.type_bool);
+            if a.type_bool == b.type_bool {
+                assert!(a.type_u8 <= b.type_u8);
+            }
+        }
+
         // *** WHERE FILTER + PAGINATION TESTING ***
 
         let records = records_model_query(
@@ -526,4 +571,124 @@ mod tests {
 
         Ok(())
     }
+
+    fn named(named_id: u32, name: &str) -> Ty {
+        Ty::Struct(Struct {
+            name: utils::struct_name_from_names("types_test", "Named"),
+            children: vec![
+                Member {
+                    name: "named_id".to_string(),
+                    key: true,
+                    ty: Ty::Primitive(Primitive::U32(Some(named_id))),
+                },
+                Member {
+                    name: "name".to_string(),
+                    key: false,
+                    ty: Ty::ByteArray(name.to_string()),
+                },
+            ],
+        })
+    }
+
+    async fn named_names(schema: &Schema, needle: &str) -> Vec<String> {
+        let query = format!(
+            r#"
+            {{
+                typesTestNamedModels(
+                    where: {{ nameCONTAINS: {} }},
+                    order: {{ direction: ASC, field: NAMED_ID }}
+                ) {{
+                    edges {{
+                        node {{
+                            name
+                        }}
+                    }}
+                }}
+            }}
+            "#,
+            serde_json::to_string(needle).unwrap()
+        );
+
+        let result = run_graphql_query(schema, &query).await;
+        result["typesTestNamedModels"]["edges"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|edge| edge["node"]["name"].as_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[sqlx::test(migrations = "../migrations")]
+    async fn test_byte_array_contains(pool: SqlitePool) {
+        let (shutdown_tx, _) = broadcast::channel(1);
+        // dummy provider since its required to query data for erc721 tokens
+        let url: Url = "https://www.example.com".parse().unwrap();
+        let provider = Arc::new(JsonRpcClient::new(HttpTransport::new(url)));
+        let (mut executor, sender) =
+            Executor::new(pool.clone(), shutdown_tx.clone(), provider, 100).await.unwrap();
+        tokio::spawn(async move {
+            executor.run().await.unwrap();
+        });
+
+        let model_cache = Arc::new(ModelCache::new(pool.clone()));
+        let mut db = Sql::new(
+            pool.clone(),
+            sender,
+            &[Contract { address: Felt::ZERO, r#type: ContractType::WORLD }],
+            model_cache,
+        )
+        .await
+        .unwrap();
+
+        db.register_model(
+            "types_test",
+            &Ty::Struct(Struct {
+                name: "Named".to_string(),
+                children: vec![
+                    Member {
+                        name: "named_id".to_string(),
+                        key: true,
+                        ty: Ty::Primitive(Primitive::U32(None)),
+                    },
+                    Member { name: "name".to_string(), key: false, ty: Ty::ByteArray("".into()) },
+                ],
+            }),
+            Layout::Fixed(vec![]),
+            Felt::ONE,
+            Felt::TWO,
+            0,
+            0,
+            0,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let names = ["100% done", "100 done", "snake_case", "snakeXcase", "it's here", "its here"];
+        for (id, name) in names.iter().enumerate() {
+            let keys = vec![Felt::from(id)];
+            db.set_entity(
+                named(id as u32, name),
+                &format!("0x{:064x}:0x{:04x}:0x{:04x}", 0, 0, id),
+                0,
+                poseidon_hash_many(&keys),
+                compute_selector_from_names("types_test", "Named"),
+                Some(&felts_to_sql_string(&keys)),
+            )
+            .await
+            .unwrap();
+        }
+        db.execute().await.unwrap();
+
+        let schema = build_schema(&pool).await.unwrap();
+
+        // the LIKE wildcards of the needle are matched literally
+        assert_eq!(named_names(&schema, "0%").await, vec!["100% done"]);
+        assert_eq!(named_names(&schema, "e_c").await, vec!["snake_case"]);
+        // quotes don't break out of the SQL string
+        assert_eq!(named_names(&schema, "it's").await, vec!["it's here"]);
+        // the match is a case-insensitive substring match
+        assert_eq!(named_names(&schema, "DONE").await, vec!["100% done", "100 done"]);
+        assert!(named_names(&schema, "%'_").await.is_empty());
+    }
 }
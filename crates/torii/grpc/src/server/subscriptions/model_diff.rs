@@ -3,15 +3,13 @@ use std::future::Future;
 use std::sync::Arc;
 use std::task::Poll;
 
+use dojo_world::storage::slot_addresses;
 use futures_util::future::BoxFuture;
 use futures_util::FutureExt;
-use num_traits::FromPrimitive;
 use rand::Rng;
-use rayon::prelude::{IntoParallelIterator, ParallelIterator};
 use starknet::core::types::{
     BlockId, ContractStorageDiffItem, Felt, MaybePendingStateUpdate, StateUpdate, StorageEntry,
 };
-use starknet::macros::short_string;
 use starknet::providers::Provider;
 use starknet_crypto::poseidon_hash_many;
 use tokio::sync::mpsc::{channel, Receiver, Sender};
@@ -68,16 +66,10 @@ impl StateDiffManager {
             .map(|req| {
                 let keys: ModelKeysClause = req.keys.into();
 
-                let base = poseidon_hash_many(&[
-                    short_string!("dojo_storage"),
-                    req.model.selector,
-                    poseidon_hash_many(&keys.keys),
-                ]);
-
-                let res = (0..req.model.packed_size)
-                    .into_par_iter()
-                    .map(|i| base + Felt::from_usize(i).expect("failed to convert usize to Felt"))
-                    .collect::<Vec<Felt>>();
+                let entity_id = poseidon_hash_many(&keys.keys);
+                let res =
+                    slot_addresses(req.model.selector, entity_id, req.model.packed_size as u64)
+                        .collect::<Vec<Felt>>();
 
                 Ok(res)
             })
@@ -49,4 +49,14 @@ pub enum Error {
 
     #[error(transparent)]
     ProviderError(#[from] ProviderError),
+
+    #[error("Invalid message signature")]
+    InvalidSignatureError,
+
+    #[cfg(not(target_arch = "wasm32"))]
+    #[error(transparent)]
+    SqlxError(#[from] sqlx::Error),
+
+    #[error("Failed to store message: {0}")]
+    StoreMessageError(anyhow::Error),
 }
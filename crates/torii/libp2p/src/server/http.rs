@@ -0,0 +1,134 @@
+use std::future::Future;
+use std::net::SocketAddr;
+
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use starknet::providers::Provider;
+use tokio::sync::broadcast::Receiver;
+use tokio::sync::mpsc::UnboundedSender;
+use torii_core::sql::Sql;
+use tracing::info;
+use warp::http::StatusCode;
+use warp::hyper::body::Bytes;
+use warp::Filter;
+
+use super::{process_message, LOG_TARGET};
+use crate::errors::Error;
+use crate::types::Message;
+
+/// Maximum size of a message body accepted by the HTTP endpoint.
+const MAX_MESSAGE_SIZE: u64 = 64 * 1024;
+
+/// Creates an HTTP server accepting signed offchain messages on `POST /messages`.
+///
+/// Messages go through the same validation, signature verification and storage as the ones
+/// received by the relay, so that clients without a libp2p stack can still publish them. Stored
+/// messages are forwarded to the relay peers through `publisher`, if any.
+pub fn new<P>(
+    mut shutdown_rx: Receiver<()>,
+    db: Sql,
+    provider: P,
+    publisher: Option<UnboundedSender<Message>>,
+) -> (SocketAddr, impl Future<Output = ()> + 'static)
+where
+    P: Provider + Clone + Send + Sync + 'static,
+{
+    let routes = warp::post()
+        .and(warp::path("messages"))
+        .and(warp::path::end())
+        .and(warp::body::content_length_limit(MAX_MESSAGE_SIZE))
+        .and(warp::body::bytes())
+        .and(warp::any().map(move || db.clone()))
+        .and(warp::any().map(move || provider.clone()))
+        .and(warp::any().map(move || publisher.clone()))
+        .then(handle_message);
+
+    warp::serve(routes).bind_with_graceful_shutdown(([127, 0, 0, 1], 0), async move {
+        shutdown_rx.recv().await.ok();
+    })
+}
+
+async fn handle_message<P: Provider + Sync>(
+    body: Bytes,
+    mut db: Sql,
+    provider: P,
+    publisher: Option<UnboundedSender<Message>>,
+) -> warp::reply::WithStatus<warp::reply::Json> {
+    let data: Message = match serde_json::from_slice(&body) {
+        Ok(data) => data,
+        Err(e) => {
+            return reply_error(&Error::InvalidMessageError(e.to_string()));
+        }
+    };
+
+    // Content-address the message, no two identical messages get a different id. The hash must
+    // be stable across builds, as the id is stored along the message.
+    let message_id = format!("{:x}", Sha256::digest(&body));
+
+    match process_message(&mut db, &provider, &message_id, &data).await {
+        Ok(entity_id) => {
+            info!(
+                target: LOG_TARGET,
+                message_id = %message_id,
+                entity_id = %format!("{:#x}", entity_id),
+                "Stored message from HTTP."
+            );
+
+            if let Some(publisher) = publisher {
+                // The relay only stops on shutdown, the message is stored anyway.
+                let _ = publisher.send(data);
+            }
+
+            warp::reply::with_status(
+                warp::reply::json(&json!({
+                    "message_id": message_id,
+                    "entity_id": format!("{:#x}", entity_id),
+                })),
+                StatusCode::OK,
+            )
+        }
+        Err(e) => {
+            info!(
+                target: LOG_TARGET,
+                error = %e,
+                message_id = %message_id,
+                "Processing message from HTTP."
+            );
+            reply_error(&e)
+        }
+    }
+}
+
+fn reply_error(error: &Error) -> warp::reply::WithStatus<warp::reply::Json> {
+    warp::reply::with_status(
+        warp::reply::json(&json!({ "error": error.to_string() })),
+        error_status(error),
+    )
+}
+
+fn error_status(error: &Error) -> StatusCode {
+    match error {
+        Error::InvalidMessageError(_) | Error::InvalidTypeError(_) => StatusCode::BAD_REQUEST,
+        Error::InvalidSignatureError => StatusCode::UNAUTHORIZED,
+        Error::ProviderError(_) => StatusCode::BAD_GATEWAY,
+        _ => StatusCode::INTERNAL_SERVER_ERROR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_status() {
+        assert_eq!(
+            error_status(&Error::InvalidMessageError("missing field".to_string())),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(error_status(&Error::InvalidSignatureError), StatusCode::UNAUTHORIZED);
+        assert_eq!(
+            error_status(&Error::StoreMessageError(anyhow::anyhow!("closed"))),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+}
@@ -25,16 +25,18 @@ use starknet::core::types::{BlockId, BlockTag, Felt, FunctionCall};
 use starknet::core::utils::get_selector_from_name;
 use starknet::providers::Provider;
 use starknet_crypto::poseidon_hash_many;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver, UnboundedSender};
 use torii_core::executor::QueryMessage;
 use torii_core::sql::utils::felts_to_sql_string;
 use torii_core::sql::Sql;
-use tracing::{info, warn};
+use tracing::info;
 use webrtc::tokio::Certificate;
 
 use crate::constants;
 use crate::errors::Error;
 
 mod events;
+pub mod http;
 
 use crate::server::events::ServerEvent;
 use crate::typed_data::{encode_type, parse_value_to_ty, PrimitiveType, TypedData};
@@ -57,6 +59,9 @@ pub struct Relay<P: Provider + Sync> {
     swarm: Swarm<Behaviour>,
     db: Sql,
     provider: Box<P>,
+    // messages received outside of the relay, e.g. over HTTP, to publish to the peers
+    publish_tx: UnboundedSender<Message>,
+    publish_rx: UnboundedReceiver<Message>,
 }
 
 impl<P: Provider + Sync> Relay<P> {
@@ -176,12 +181,53 @@ impl<P: Provider + Sync> Relay<P> {
             .subscribe(&IdentTopic::new(constants::MESSAGING_TOPIC))
             .unwrap();
 
-        Ok(Self { swarm, db: pool, provider: Box::new(provider) })
+        let (publish_tx, publish_rx) = unbounded_channel();
+
+        Ok(Self { swarm, db: pool, provider: Box::new(provider), publish_tx, publish_rx })
+    }
+
+    /// Returns a sender of verified messages to publish to the peers subscribed to the messaging
+    /// topic.
+    pub fn publisher(&self) -> UnboundedSender<Message> {
+        self.publish_tx.clone()
+    }
+
+    fn publish(&mut self, message: &Message) {
+        let data = match serde_json::to_vec(message) {
+            Ok(data) => data,
+            Err(e) => {
+                info!(target: LOG_TARGET, error = %e, "Serializing message.");
+                return;
+            }
+        };
+
+        // Publishing fails when no peer is subscribed to the topic, which is expected.
+        match self
+            .swarm
+            .behaviour_mut()
+            .gossipsub
+            .publish(IdentTopic::new(constants::MESSAGING_TOPIC), data)
+        {
+            Ok(message_id) => {
+                info!(target: LOG_TARGET, message_id = %message_id, "Published message.");
+            }
+            Err(e) => {
+                info!(target: LOG_TARGET, error = %e, "Publishing message.");
+            }
+        }
     }
 
     pub async fn run(&mut self) {
         loop {
-            match self.swarm.next().await.expect("Infinite Stream.") {
+            let event = tokio::select! {
+                event = self.swarm.select_next_some() => event,
+                Some(message) = self.publish_rx.recv() => {
+                    self.publish(&message);
+                    continue;
+                }
+            };
+
+            match event {
                 SwarmEvent::Behaviour(event) => {
                     match &event {
                         ServerEvent::Gossipsub(gossipsub::Event::Message {
@@ -203,18 +249,6 @@ impl<P: Provider + Sync> Relay<P> {
                                 }
                             };
 
-                            let ty = match validate_message(&self.db, &data.message).await {
-                                Ok(parsed_message) => parsed_message,
-                                Err(e) => {
-                                    info!(
-                                        target: LOG_TARGET,
-                                        error = %e,
-                                        "Validating message."
-                                    );
-                                    continue;
-                                }
-                            };
-
                             info!(
                                 target: LOG_TARGET,
                                 message_id = %message_id,
@@ -223,127 +257,20 @@ impl<P: Provider + Sync> Relay<P> {
                                 "Received message."
                             );
 
-                            // retrieve entity identity from db
-                            let mut pool = match self.db.pool.acquire().await {
-                                Ok(pool) => pool,
-                                Err(e) => {
-                                    warn!(
-                                        target: LOG_TARGET,
-                                        error = %e,
-                                        "Acquiring pool."
-                                    );
-                                    continue;
-                                }
-                            };
-
-                            let keys = match ty_keys(&ty) {
-                                Ok(keys) => keys,
-                                Err(e) => {
-                                    warn!(
-                                        target: LOG_TARGET,
-                                        error = %e,
-                                        "Retrieving message model keys."
-                                    );
-                                    continue;
-                                }
-                            };
-                            let keys_str = felts_to_sql_string(&keys);
-                            let entity_id = poseidon_hash_many(&keys);
-                            let model_id = ty_model_id(&ty).unwrap();
-
-                            // select only identity field, if doesn't exist, empty string
-                            let query = format!(
-                                "SELECT external_identity FROM [{}] WHERE id = ?",
-                                ty.name()
-                            );
-                            let entity_identity: Option<String> = match sqlx::query_scalar(&query)
-                                .bind(format!("{:#x}", entity_id))
-                                .fetch_optional(&mut *pool)
-                                .await
-                            {
-                                Ok(entity_identity) => entity_identity,
-                                Err(e) => {
-                                    warn!(
-                                        target: LOG_TARGET,
-                                        error = %e,
-                                        "Fetching entity."
-                                    );
-                                    continue;
-                                }
-                            };
-
-                            let entity_identity = match entity_identity {
-                                Some(identity) => match Felt::from_str(&identity) {
-                                    Ok(identity) => identity,
-                                    Err(e) => {
-                                        warn!(
-                                            target: LOG_TARGET,
-                                            error = %e,
-                                            "Parsing identity."
-                                        );
-                                        continue;
-                                    }
-                                },
-                                None => match get_identity_from_ty(&ty) {
-                                    Ok(identity) => identity,
-                                    Err(e) => {
-                                        warn!(
-                                            target: LOG_TARGET,
-                                            error = %e,
-                                            "Getting identity from message."
-                                        );
-                                        continue;
-                                    }
-                                },
-                            };
-
-                            // TODO: have a nonce in model to check
-                            // against entity nonce and message nonce
-                            // to prevent replay attacks.
-
-                            // Verify the signature
-                            if !match validate_signature(
-                                &self.provider,
-                                entity_identity,
-                                &data.message,
-                                &data.signature,
-                            )
-                            .await
-                            {
-                                Ok(res) => res,
-                                Err(e) => {
-                                    warn!(
-                                        target: LOG_TARGET,
-                                        error = %e,
-                                        "Verifying signature."
-                                    );
-                                    continue;
-                                }
-                            } {
-                                info!(
-                                    target: LOG_TARGET,
-                                    message_id = %message_id,
-                                    peer_id = %peer_id,
-                                    "Invalid signature."
-                                );
-                                continue;
-                            }
-
-                            if let Err(e) = set_entity(
+                            if let Err(e) = process_message(
                                 &mut self.db,
-                                ty,
+                                &self.provider,
                                 &message_id.to_string(),
-                                Utc::now().timestamp() as u64,
-                                entity_id,
-                                model_id,
-                                &keys_str,
+                                &data,
                             )
                             .await
                             {
                                 info!(
                                     target: LOG_TARGET,
                                     error = %e,
-                                    "Setting message."
+                                    message_id = %message_id,
+                                    peer_id = %peer_id,
+                                    "Processing message."
                                 );
                                 continue;
                             }
@@ -410,6 +337,50 @@ impl<P: Provider + Sync> Relay<P> {
     }
 }
 
+/// Validates an offchain message against its model schema, verifies its signature against the
+/// account of the entity identity and stores it as an entity. Returns the id of the entity.
+pub async fn process_message<P: Provider + Sync>(
+    db: &mut Sql,
+    provider: &P,
+    message_id: &str,
+    data: &Message,
+) -> Result<Felt, Error> {
+    let ty = validate_message(db, &data.message).await?;
+
+    let keys = ty_keys(&ty)?;
+    let keys_str = felts_to_sql_string(&keys);
+    let entity_id = poseidon_hash_many(&keys);
+    let model_id = ty_model_id(&ty)?;
+
+    // select only identity field, if doesn't exist, empty string
+    let query = format!("SELECT external_identity FROM [{}] WHERE id = ?", ty.name());
+    let entity_identity: Option<String> = sqlx::query_scalar(&query)
+        .bind(format!("{:#x}", entity_id))
+        .fetch_optional(&db.pool)
+        .await?;
+
+    let entity_identity = match entity_identity {
+        Some(identity) => Felt::from_str(&identity)
+            .map_err(|e| Error::InvalidMessageError(format!("Invalid identity: {e}")))?,
+        None => get_identity_from_ty(&ty)?,
+    };
+
+    // TODO: have a nonce in model to check
+    // against entity nonce and message nonce
+    // to prevent replay attacks.
+
+    // Verify the signature
+    if !validate_signature(provider, entity_identity, &data.message, &data.signature).await? {
+        return Err(Error::InvalidSignatureError);
+    }
+
+    set_entity(db, ty, message_id, Utc::now().timestamp() as u64, entity_id, model_id, &keys_str)
+        .await
+        .map_err(Error::StoreMessageError)?;
+
+    Ok(entity_id)
+}
+
 async fn validate_signature<P: Provider + Sync>(
     provider: &P,
     entity_identity: Felt,
@@ -0,0 +1,12 @@
+-- Materialized aggregations over the models entities, one row per group.
+CREATE TABLE aggregations (
+    -- <aggregation_name>/<group_key>
+    id TEXT PRIMARY KEY NOT NULL,
+    aggregation_name TEXT NOT NULL,
+    -- The values of the group by members, joined by '/'.
+    group_key TEXT NOT NULL,
+    value NUMERIC,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_aggregations_name_value ON aggregations (aggregation_name, value);
@@ -0,0 +1,38 @@
+-- Achievements (trophies) declared by the worlds through `TrophyCreation` events.
+CREATE TABLE achievements (
+    -- <namespace>/<trophy_id>
+    id TEXT PRIMARY KEY NOT NULL,
+    namespace TEXT NOT NULL,
+    trophy_id TEXT NOT NULL,
+    hidden BOOLEAN NOT NULL,
+    idx INTEGER NOT NULL,
+    points INTEGER NOT NULL,
+    start_at INTEGER NOT NULL,
+    end_at INTEGER NOT NULL,
+    group_name TEXT NOT NULL,
+    icon TEXT NOT NULL,
+    title TEXT NOT NULL,
+    description TEXT NOT NULL,
+    -- JSON array of the tasks to complete, `[{ "id", "total", "description" }]`.
+    tasks TEXT NOT NULL,
+    data TEXT NOT NULL,
+    executed_at DATETIME NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_achievements_namespace ON achievements (namespace);
+
+-- Progress of the players on the achievements tasks, accumulated from `TrophyProgression` events.
+CREATE TABLE achievement_progressions (
+    -- <namespace>/<player_id>/<task_id>
+    id TEXT PRIMARY KEY NOT NULL,
+    namespace TEXT NOT NULL,
+    player_id TEXT NOT NULL,
+    task_id TEXT NOT NULL,
+    count INTEGER NOT NULL,
+    executed_at DATETIME NOT NULL,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_achievement_progressions_player_id ON achievement_progressions (player_id);
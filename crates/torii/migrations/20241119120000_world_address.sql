@@ -0,0 +1,7 @@
+-- The world of the models and entities of the additional worlds indexed in the same database,
+-- NULL for the ones of the main world.
+ALTER TABLE models ADD COLUMN world_address TEXT;
+ALTER TABLE entities ADD COLUMN world_address TEXT;
+
+CREATE INDEX idx_models_world_address ON models (world_address);
+CREATE INDEX idx_entities_world_address ON entities (world_address);
@@ -0,0 +1,8 @@
+-- The `TrophyProgression` events already counted in the achievement progressions, so that an event
+-- indexed again is not counted twice.
+CREATE TABLE achievement_progression_events (
+    -- <block_number>:<transaction_hash>:<event_idx>
+    id TEXT PRIMARY KEY NOT NULL,
+    progression_id TEXT NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
@@ -59,6 +59,7 @@ pub struct Proxy {
     allowed_origins: Option<Vec<String>>,
     grpc_addr: Option<SocketAddr>,
     artifacts_addr: Option<SocketAddr>,
+    messages_addr: Option<SocketAddr>,
     graphql_addr: Arc<RwLock<Option<SocketAddr>>>,
 }
 
@@ -69,6 +70,7 @@ impl Proxy {
         grpc_addr: Option<SocketAddr>,
         graphql_addr: Option<SocketAddr>,
         artifacts_addr: Option<SocketAddr>,
+        messages_addr: Option<SocketAddr>,
     ) -> Self {
         Self {
             addr,
@@ -76,6 +78,7 @@ impl Proxy {
             grpc_addr,
             graphql_addr: Arc::new(RwLock::new(graphql_addr)),
             artifacts_addr,
+            messages_addr,
         }
     }
 
@@ -93,6 +96,7 @@ impl Proxy {
         let grpc_addr = self.grpc_addr;
         let graphql_addr = self.graphql_addr.clone();
         let artifacts_addr = self.artifacts_addr;
+        let messages_addr = self.messages_addr;
 
         let make_svc = make_service_fn(move |conn: &AddrStream| {
             let remote_addr = conn.remote_addr().ip();
@@ -134,7 +138,15 @@ impl Proxy {
                 let graphql_addr = graphql_addr_clone.clone();
                 async move {
                     let graphql_addr = graphql_addr.read().await;
-                    handle(remote_addr, grpc_addr, artifacts_addr, *graphql_addr, req).await
+                    handle(
+                        remote_addr,
+                        grpc_addr,
+                        artifacts_addr,
+                        messages_addr,
+                        *graphql_addr,
+                        req,
+                    )
+                    .await
                 }
             });
 
@@ -155,6 +167,7 @@ async fn handle(
     client_ip: IpAddr,
     grpc_addr: Option<SocketAddr>,
     artifacts_addr: Option<SocketAddr>,
+    messages_addr: Option<SocketAddr>,
     graphql_addr: Option<SocketAddr>,
     req: Request<Body>,
 ) -> Result<Response<Body>, Infallible> {
@@ -180,6 +193,27 @@ async fn handle(
         }
     }
 
+    if req.uri().path().starts_with("/messages") {
+        if let Some(messages_addr) = messages_addr {
+            let messages_addr = format!("http://{}", messages_addr);
+            return match GRAPHQL_PROXY_CLIENT.call(client_ip, &messages_addr, req).await {
+                Ok(response) => Ok(response),
+                Err(_error) => {
+                    error!("{:?}", _error);
+                    Ok(Response::builder()
+                        .status(StatusCode::INTERNAL_SERVER_ERROR)
+                        .body(Body::empty())
+                        .unwrap())
+                }
+            };
+        } else {
+            return Ok(Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .body(Body::empty())
+                .unwrap());
+        }
+    }
+
     if req.uri().path().starts_with("/graphql") {
         if let Some(graphql_addr) = graphql_addr {
             let graphql_addr = format!("http://{}", graphql_addr);
@@ -0,0 +1,17 @@
+#!/bin/bash
+
+# Vendors the Starknet JSON-RPC specification used by the RPC conformance tests of katana.
+# The version must match the `RPC_SPEC_VERSION` supported by katana, and the fetched files must be
+# committed along with the version bump.
+
+set -euo pipefail
+
+VERSION="${1:-0.7.1}"
+SPEC_DIR="$(dirname "$0")/../crates/katana/rpc/rpc/tests/test_data/starknet-specs/$VERSION"
+BASE_URL="https://raw.githubusercontent.com/starkware-libs/starknet-specs/v$VERSION/api"
+
+mkdir -p "$SPEC_DIR"
+
+for file in starknet_api_openrpc.json starknet_write_api.json starknet_trace_api_openrpc.json; do
+    curl --fail --silent --show-error --location "$BASE_URL/$file" --output "$SPEC_DIR/$file"
+done
@@ -5,6 +5,7 @@ use std::str::FromStr;
 
 use anyhow::Result;
 use dojo_test_utils::compiler::CompilerTestSetup;
+use dojo_test_utils::snapshot::genesis_snapshot_from_db;
 use dojo_utils::TxnConfig;
 use dojo_world::contracts::WorldContract;
 use dojo_world::diff::{Manifest, WorldDiff};
@@ -134,6 +135,9 @@ async fn main() -> Result<()> {
     let spawn_and_move_compressed_path = "spawn-and-move-db.tar.gz";
     let types_test_compressed_path = "types-test-db.tar.gz";
 
+    let spawn_and_move_snapshot_path = "spawn-and-move-genesis.json";
+    let types_test_snapshot_path = "types-test-genesis.json";
+
     let _ = fs::remove_dir_all(spawn_and_move_compressed_path);
     let _ = fs::remove_dir_all(types_test_compressed_path);
 
@@ -154,6 +158,11 @@ async fn main() -> Result<()> {
     assert!(spawn_and_move_db_path.exists(), "spawn-and-move-db directory does not exist");
     assert!(types_test_db_path.exists(), "types-test-db directory does not exist");
 
+    // Exports the migrated states as genesis snapshots, to start katana directly from them with
+    // `--genesis.snapshot`. The runners are stopped at this point, so the databases can be opened.
+    genesis_snapshot_from_db(&spawn_and_move_db_path)?.save(spawn_and_move_snapshot_path)?;
+    genesis_snapshot_from_db(&types_test_db_path)?.save(types_test_snapshot_path)?;
+
     compress_db(&spawn_and_move_db_path, spawn_and_move_compressed_path);
     compress_db(&types_test_db_path, types_test_compressed_path);
 